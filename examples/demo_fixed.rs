@@ -22,6 +22,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         model_eviction_policy: "lru".to_string(),
         kv_cache_type_k: None,
         kv_cache_type_v: None,
+        default_timeout_ms: None,
+        draft_model: None,
     };
 
     // Create the LLM engine
@@ -7,6 +7,7 @@
  */
 
 import { getS5Client, getS5Status, getAdvancedClient } from './s5_client.js';
+import { bridgeConfig } from './config.js';
 import { BlobIdentifier } from '@julesl23/s5js/dist/src/identifier/blob.js';
 import { MULTIHASH_BLAKE3 } from '@julesl23/s5js/dist/src/constants.js';
 
@@ -277,6 +278,26 @@ export async function registerRoutes(fastify) {
     }
   });
 
+  // HEAD /s5/blob/{cid} - Check whether a blob is already stored on the portal
+  // Lets clients skip uploading content that is already on the network
+  fastify.head('/s5/blob/:cid', async (request, reply) => {
+    const { cid } = request.params;
+
+    try {
+      const response = await fetch(`${bridgeConfig.portalUrl}/s5/blob/${cid}`, {
+        method: 'HEAD',
+        redirect: 'manual',
+      });
+      // Portals answer blob requests with a redirect to the storage location
+      const found = response.ok || (response.status >= 300 && response.status < 400);
+      fastify.log.debug({ cid, portalStatus: response.status, found }, 'Blob existence check');
+      reply.code(found ? 200 : 404).send();
+    } catch (error) {
+      fastify.log.warn({ cid, error: error.message }, 'Blob existence check failed');
+      reply.code(502).send();
+    }
+  });
+
   // NOTE: Directory listing route (/s5/fs/*/) disabled
   // Wildcard pattern /s5/fs/*/ is invalid in Fastify (wildcard must be last character)
   // TODO: Implement directory listing with query parameter instead (e.g., /s5/fs/*?list=true)
@@ -291,6 +312,7 @@ export async function registerRoutes(fastify) {
         download: 'GET /s5/fs/{path}',
         upload: 'PUT /s5/fs/{path}',
         delete: 'DELETE /s5/fs/{path}',
+        blobExists: 'HEAD /s5/blob/{cid}',
         list: 'GET /s5/fs/{path}/',
       },
     });
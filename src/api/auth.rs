@@ -0,0 +1,227 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! API key authentication for the HTTP API
+//!
+//! Keys are sent as `X-API-Key` or `Authorization: Bearer` and checked
+//! against `ApiConfig::api_keys`, then against an `ApiKeyVerifier` when one
+//! is installed. An accepted key becomes an `ApiIdentity`, stored in the
+//! request's extensions for billing and rate limiting. With
+//! `ApiConfig::require_api_key` set, requests outside the public paths
+//! without an accepted key get 401. This authenticates callers only; message
+//! confidentiality is the job of the WebSocket E2E encryption.
+
+use axum::http::HeaderMap;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use super::rate_limit::{path_has_prefix, request_api_key};
+use super::ApiError;
+
+pub const API_KEYS_ENV: &str = "API_KEYS";
+pub const API_PUBLIC_PATHS_ENV: &str = "API_PUBLIC_PATHS";
+
+/// Paths reachable without a key unless `API_PUBLIC_PATHS` says otherwise
+pub fn default_public_paths() -> Vec<String> {
+    vec!["/health".to_string(), "/metrics".to_string()]
+}
+
+fn list_from_env(name: &str) -> Option<Vec<String>> {
+    std::env::var(name).ok().map(|v| {
+        v.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+/// Comma-separated keys from `API_KEYS`
+pub fn api_keys_from_env() -> Vec<String> {
+    list_from_env(API_KEYS_ENV).unwrap_or_default()
+}
+
+/// Comma-separated path prefixes from `API_PUBLIC_PATHS`
+pub fn public_paths_from_env() -> Vec<String> {
+    list_from_env(API_PUBLIC_PATHS_ENV).unwrap_or_else(default_public_paths)
+}
+
+/// The client an accepted API key belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApiIdentity {
+    /// Stable id safe to log and bill against; never the key itself
+    pub client_id: String,
+}
+
+impl ApiIdentity {
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+        }
+    }
+
+    /// Identity of a key from `ApiConfig::api_keys`, derived from its hash
+    fn for_configured_key(api_key: &str) -> Self {
+        let digest = Sha256::digest(api_key.as_bytes());
+        Self::new(format!("key_{}", hex::encode(&digest[..6])))
+    }
+}
+
+/// Checks keys that are not in `ApiConfig::api_keys`, e.g. against a
+/// database. Any `Fn(&str) -> Option<ApiIdentity>` works.
+pub trait ApiKeyVerifier: Send + Sync {
+    fn verify(&self, api_key: &str) -> Option<ApiIdentity>;
+}
+
+impl<F> ApiKeyVerifier for F
+where
+    F: Fn(&str) -> Option<ApiIdentity> + Send + Sync,
+{
+    fn verify(&self, api_key: &str) -> Option<ApiIdentity> {
+        self(api_key)
+    }
+}
+
+pub struct ApiKeyAuth {
+    required: bool,
+    keys: HashSet<String>,
+    public_paths: Vec<String>,
+    verifier: RwLock<Option<Arc<dyn ApiKeyVerifier>>>,
+}
+
+impl ApiKeyAuth {
+    pub fn new(required: bool, keys: &[String], public_paths: Vec<String>) -> Self {
+        Self {
+            required,
+            keys: keys.iter().cloned().collect(),
+            public_paths,
+            verifier: RwLock::new(None),
+        }
+    }
+
+    pub fn set_verifier(&self, verifier: Arc<dyn ApiKeyVerifier>) {
+        *self.verifier.write().unwrap() = Some(verifier);
+    }
+
+    /// Whether `path` is a public path or lies under one
+    pub fn is_public(&self, path: &str) -> bool {
+        self.public_paths
+            .iter()
+            .any(|prefix| path_has_prefix(path, prefix))
+    }
+
+    /// Identity of `api_key`, if it is configured or the verifier accepts it
+    pub fn verify(&self, api_key: &str) -> Option<ApiIdentity> {
+        if self.keys.contains(api_key) {
+            return Some(ApiIdentity::for_configured_key(api_key));
+        }
+        let verifier = self.verifier.read().unwrap().clone();
+        verifier?.verify(api_key)
+    }
+
+    /// Identity of the caller of `path`. Fails with `Unauthorized` when keys
+    /// are required, the path is not public and no accepted key was sent;
+    /// otherwise a missing or unknown key just yields no identity.
+    pub fn authenticate(
+        &self,
+        path: &str,
+        headers: &HeaderMap,
+    ) -> Result<Option<ApiIdentity>, ApiError> {
+        let api_key = request_api_key(headers);
+        let identity = api_key.and_then(|key| self.verify(key));
+        if identity.is_none() && self.required && !self.is_public(path) {
+            let reason = match api_key {
+                Some(_) => "Invalid API key",
+                None => "Missing API key",
+            };
+            return Err(ApiError::Unauthorized(reason.to_string()));
+        }
+        Ok(identity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(api_key: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(key) = api_key {
+            headers.insert(
+                axum::http::header::AUTHORIZATION,
+                format!("Bearer {}", key).parse().unwrap(),
+            );
+        }
+        headers
+    }
+
+    #[test]
+    fn test_required_keys_reject_missing_and_invalid() {
+        let auth = ApiKeyAuth::new(true, &["secret".to_string()], default_public_paths());
+
+        let identity = auth
+            .authenticate("/v1/inference", &headers(Some("secret")))
+            .unwrap()
+            .unwrap();
+        assert!(identity.client_id.starts_with("key_"));
+        assert!(!identity.client_id.contains("secret"));
+
+        for key in [None, Some("guess")] {
+            assert!(matches!(
+                auth.authenticate("/v1/inference", &headers(key)),
+                Err(ApiError::Unauthorized(_))
+            ));
+        }
+
+        // Public paths stay open
+        assert_eq!(
+            auth.authenticate("/health/ready", &headers(None)).unwrap(),
+            None
+        );
+        assert_eq!(
+            auth.authenticate("/metrics", &headers(Some("guess")))
+                .unwrap(),
+            None
+        );
+        // Only whole path segments match
+        for path in ["/healthX", "/health-anything", "/metricsz"] {
+            assert!(auth.authenticate(path, &headers(None)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verifier_accepts_unconfigured_keys() {
+        let auth = ApiKeyAuth::new(true, &[], default_public_paths());
+        assert!(auth.verify("db-key").is_none());
+
+        auth.set_verifier(Arc::new(|key: &str| {
+            (key == "db-key").then(|| ApiIdentity::new("customer-7"))
+        }));
+        assert_eq!(
+            auth.authenticate("/v1/embed", &headers(Some("db-key")))
+                .unwrap(),
+            Some(ApiIdentity::new("customer-7"))
+        );
+        assert!(auth
+            .authenticate("/v1/embed", &headers(Some("other")))
+            .is_err());
+    }
+
+    #[test]
+    fn test_optional_keys_still_identify_clients() {
+        let auth = ApiKeyAuth::new(false, &["secret".to_string()], vec![]);
+        assert_eq!(
+            auth.authenticate("/v1/inference", &headers(None)).unwrap(),
+            None
+        );
+        assert_eq!(
+            auth.authenticate("/v1/inference", &headers(Some("guess")))
+                .unwrap(),
+            None
+        );
+        assert!(auth
+            .authenticate("/v1/inference", &headers(Some("secret")))
+            .unwrap()
+            .is_some());
+    }
+}
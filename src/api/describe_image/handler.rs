@@ -6,9 +6,11 @@ use axum::{extract::State, http::StatusCode, Json};
 use tracing::{debug, info, warn};
 
 use super::request::DescribeImageRequest;
-use super::response::{DescribeImageResponse, ImageAnalysis};
+use super::response::{DescribeImageResponse, DetectedObject, ImageAnalysis};
 use crate::api::http_server::AppState;
-use crate::vision::decode_base64_image;
+use crate::api::ocr::response::BoundingBox;
+use crate::vision::florence::{DetectedObject as FlorenceObject, FlorenceTask};
+use crate::vision::{decode_base64_image, ImageInfo};
 
 /// POST /v1/describe-image - Generate a description of an image
 ///
@@ -22,11 +24,15 @@ use crate::vision::decode_base64_image;
 /// - `prompt`: Custom prompt for description (optional)
 /// - `maxTokens`: Maximum tokens in response (10-500) - defaults to 150
 /// - `chainId`: Chain ID for pricing context - defaults to 84532 (Base Sepolia)
+/// - `task`: Florence-2 task - "describe" (default), "<CAPTION>",
+///   "<DETAILED_CAPTION>", "<MORE_DETAILED_CAPTION>", "<OD>" or "<OCR>".
+///   Tasks other than "describe" always run on Florence-2, skipping the VLM
 ///
 /// # Response
-/// - `description`: Generated text description
-/// - `objects`: Detected objects (currently empty, reserved for future)
-/// - `analysis`: Image metadata (dimensions, colors)
+/// - `description`: Generated text description (caption, detected labels, or
+///   read text, depending on the task)
+/// - `objects`: Detected objects with bounding boxes (`<OD>` only)
+/// - `analysis`: Image metadata (dimensions, colors, task, and text for `<OCR>`)
 /// - `processingTimeMs`: Processing time in milliseconds
 /// - `model`: Model used ("florence-2")
 /// - `provider`: Service provider ("host")
@@ -41,8 +47,8 @@ pub async fn describe_image_handler(
     Json(request): Json<DescribeImageRequest>,
 ) -> Result<Json<DescribeImageResponse>, (StatusCode, String)> {
     debug!(
-        "Describe-image request received: detail={}, chain_id={}",
-        request.detail, request.chain_id
+        "Describe-image request received: task={:?}, detail={}, chain_id={}",
+        request.task, request.detail, request.chain_id
     );
 
     // 1. Validate request
@@ -61,8 +67,11 @@ pub async fn describe_image_handler(
         )
     })?;
 
-    // 2b. Try VLM first (if available)
-    if let Some(vlm_client) = manager.get_vlm_client() {
+    // 2b. Try VLM first (if available); Florence-specific tasks skip it
+    let vlm_client = manager
+        .get_vlm_client()
+        .filter(|_| request.task == FlorenceTask::Describe);
+    if let Some(vlm_client) = vlm_client {
         let vlm_image = request
             .image
             .as_ref()
@@ -90,6 +99,8 @@ pub async fn describe_image_handler(
                     height: 0,
                     dominant_colors: vec![],
                     scene_type: None,
+                    task: FlorenceTask::Describe,
+                    text: None,
                 };
                 let response = DescribeImageResponse::new(
                     vlm_result.description,
@@ -134,13 +145,19 @@ pub async fn describe_image_handler(
 
     // 5. Run Florence description
     info!(
-        "Running Florence describe: detail={}, prompt={:?}",
+        "Running Florence task {:?}: detail={}, prompt={:?}",
+        request.task,
         request.detail,
         request.prompt.as_deref()
     );
 
     let description_result = florence_model
-        .describe(&image, &request.detail, request.prompt.as_deref())
+        .run_task(
+            &image,
+            request.task,
+            &request.detail,
+            request.prompt.as_deref(),
+        )
         .map_err(|e| {
             // Log full error chain for debugging
             warn!("Florence description failed: {}", e);
@@ -156,23 +173,26 @@ pub async fn describe_image_handler(
         })?;
 
     info!(
-        "Florence complete: {} chars, {}ms",
+        "Florence complete: {} chars, {} objects, {}ms",
         description_result.description.len(),
+        description_result.objects.len(),
         description_result.processing_time_ms
     );
 
     // 6. Build response with chain context
-    // Note: Objects detection is not yet implemented in Florence, returning empty
+    // Report dimensions and boxes in the submitted image, not the downscaled one
     let analysis = ImageAnalysis {
-        width: description_result.analysis.width,
-        height: description_result.analysis.height,
+        width: image_info.original_width,
+        height: image_info.original_height,
         dominant_colors: description_result.analysis.dominant_colors.clone(),
         scene_type: description_result.analysis.scene_type.clone(),
+        task: description_result.analysis.task,
+        text: description_result.analysis.text.clone(),
     };
 
     let response = DescribeImageResponse::new(
         description_result.description,
-        vec![], // Objects detection reserved for future
+        to_response_objects(&description_result.objects, &image_info),
         analysis,
         description_result.processing_time_ms,
         request.chain_id,
@@ -182,6 +202,24 @@ pub async fn describe_image_handler(
     Ok(Json(response))
 }
 
+/// Convert Florence detections to the API response format, in the
+/// submitted image's coordinates
+fn to_response_objects(objects: &[FlorenceObject], image_info: &ImageInfo) -> Vec<DetectedObject> {
+    objects
+        .iter()
+        .map(|o| DetectedObject {
+            label: o.label.clone(),
+            confidence: o.confidence,
+            bounding_box: o.bounding_box.as_ref().map(|b| BoundingBox {
+                x: image_info.to_original(b.x),
+                y: image_info.to_original(b.y),
+                width: image_info.to_original(b.width),
+                height: image_info.to_original(b.height),
+            }),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,6 +240,8 @@ mod tests {
                 height: 0,
                 dominant_colors: vec![],
                 scene_type: None,
+                task: FlorenceTask::Describe,
+                text: None,
             },
             50,
             84532,
@@ -220,6 +260,8 @@ mod tests {
                 height: 480,
                 dominant_colors: vec![],
                 scene_type: None,
+                task: FlorenceTask::Describe,
+                text: None,
             },
             200,
             84532,
@@ -239,6 +281,8 @@ mod tests {
                 height: 100,
                 dominant_colors: vec![],
                 scene_type: None,
+                task: FlorenceTask::Describe,
+                text: None,
             },
             300,
             84532,
@@ -248,6 +292,31 @@ mod tests {
         assert_eq!(response.description, "fallback");
     }
 
+    #[test]
+    fn test_detection_objects_keep_boxes() {
+        use crate::vision::florence::parse_detections;
+
+        let objects = parse_detections("cat<loc_100><loc_200><loc_300><loc_400>", 1000, 1000);
+        // The model saw the image at half its submitted size
+        let image_info = ImageInfo {
+            width: 1000,
+            height: 1000,
+            format: image::ImageFormat::Png,
+            size_bytes: 0,
+            original_width: 2000,
+            original_height: 2000,
+            scale_factor: 0.5,
+        };
+        let converted = to_response_objects(&objects, &image_info);
+        assert_eq!(converted.len(), 1);
+        assert_eq!(converted[0].label, "cat");
+        let bbox = converted[0].bounding_box.as_ref().unwrap();
+        assert_eq!(
+            (bbox.x, bbox.y, bbox.width, bbox.height),
+            (200, 400, 400, 400)
+        );
+    }
+
     #[test]
     fn test_image_analysis_creation() {
         let analysis = ImageAnalysis {
@@ -255,6 +324,8 @@ mod tests {
             height: 1080,
             dominant_colors: vec!["#FF0000".to_string()],
             scene_type: Some("outdoor".to_string()),
+            task: FlorenceTask::Describe,
+            text: None,
         };
         assert_eq!(analysis.width, 1920);
         assert_eq!(analysis.height, 1080);
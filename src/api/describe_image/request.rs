@@ -5,6 +5,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::api::errors::ApiError;
+use crate::vision::florence::FlorenceTask;
 
 /// Supported image formats
 const SUPPORTED_FORMATS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
@@ -64,6 +65,11 @@ pub struct DescribeImageRequest {
     /// Chain ID for pricing/metering
     #[serde(default = "default_chain_id")]
     pub chain_id: u64,
+
+    /// Florence-2 task: "describe" (default), "<CAPTION>", "<DETAILED_CAPTION>",
+    /// "<MORE_DETAILED_CAPTION>", "<OD>" or "<OCR>"
+    #[serde(default)]
+    pub task: FlorenceTask,
 }
 
 impl DescribeImageRequest {
@@ -109,6 +115,14 @@ impl DescribeImageRequest {
             });
         }
 
+        // Custom prompts only steer the describe task
+        if self.prompt.is_some() && self.task != FlorenceTask::Describe {
+            return Err(ApiError::ValidationError {
+                field: "prompt".to_string(),
+                message: "prompt is only supported with the describe task".to_string(),
+            });
+        }
+
         // Validate max_tokens range
         if self.max_tokens < MIN_TOKENS_LIMIT || self.max_tokens > MAX_TOKENS_LIMIT {
             return Err(ApiError::ValidationError {
@@ -147,6 +161,21 @@ mod tests {
         assert_eq!(request.detail, "detailed");
         assert_eq!(request.max_tokens, 150);
         assert_eq!(request.chain_id, 84532);
+        assert_eq!(request.task, FlorenceTask::Describe);
+    }
+
+    #[test]
+    fn test_task_selection() {
+        let request: DescribeImageRequest =
+            serde_json::from_str(r#"{"image": "dGVzdA==", "task": "<OD>"}"#).unwrap();
+        assert_eq!(request.task, FlorenceTask::ObjectDetection);
+        assert!(request.validate().is_ok());
+
+        let with_prompt = DescribeImageRequest {
+            prompt: Some("A photo of".to_string()),
+            ..request
+        };
+        assert!(with_prompt.validate().is_err());
     }
 
     #[test]
@@ -158,6 +187,7 @@ mod tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
         assert!(request.validate().is_err());
     }
@@ -171,6 +201,7 @@ mod tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
         assert!(request.validate().is_err());
     }
@@ -184,6 +215,7 @@ mod tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
         assert!(request.validate().is_err());
     }
@@ -197,6 +229,7 @@ mod tests {
             prompt: None,
             max_tokens: 5,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
         assert!(request.validate().is_err());
     }
@@ -210,6 +243,7 @@ mod tests {
             prompt: None,
             max_tokens: 1000,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
         assert!(request.validate().is_err());
     }
@@ -223,6 +257,7 @@ mod tests {
             prompt: Some("Describe the main subject".to_string()),
             max_tokens: 100,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
         assert!(request.validate().is_ok());
     }
@@ -5,6 +5,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::api::ocr::response::BoundingBox;
+use crate::vision::florence::FlorenceTask;
 
 /// A detected object in the image
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +33,11 @@ pub struct ImageAnalysis {
     /// Scene type (indoor, outdoor, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scene_type: Option<String>,
+    /// Task that produced this analysis
+    pub task: FlorenceTask,
+    /// Text read from the image (`<OCR>` task only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
 }
 
 /// Response from image description
@@ -106,6 +112,8 @@ mod tests {
                 height: 1080,
                 dominant_colors: vec!["#FF0000".to_string()],
                 scene_type: Some("indoor".to_string()),
+                task: FlorenceTask::Describe,
+                text: None,
             },
             4500,
             84532,
@@ -127,6 +135,8 @@ mod tests {
                 height: 100,
                 dominant_colors: vec![],
                 scene_type: None,
+                task: FlorenceTask::Describe,
+                text: None,
             },
             100,
             84532,
@@ -146,6 +156,8 @@ mod tests {
                 height: 100,
                 dominant_colors: vec![],
                 scene_type: None,
+                task: FlorenceTask::Describe,
+                text: None,
             },
             100,
             5611,
@@ -165,6 +177,8 @@ mod tests {
                 height: 100,
                 dominant_colors: vec![],
                 scene_type: None,
+                task: FlorenceTask::Describe,
+                text: None,
             },
             100,
             84532,
@@ -1,16 +1,24 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
-//! Image generation endpoint handler
+//! Image generation endpoint handlers
 
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+use super::jobs::ImageJobResponse;
 use super::request::GenerateImageRequest;
 use super::response::{BillingInfo, GenerateImageResponse, SafetyInfo};
 use crate::api::http_server::AppState;
+use crate::diffusion::billing::ImagePriceTable;
 use crate::diffusion::client::ImageSize;
 use crate::diffusion::prompt_safety::PromptSafetyClassifier;
 use crate::diffusion::safety::SafetyConfig;
+use crate::diffusion::{DiffusionClient, DiffusionResult, ImageGenerationRequest, QueueTicket};
 
 /// POST /v1/images/generate - Generate an image from a text prompt
 ///
@@ -19,9 +27,10 @@ use crate::diffusion::safety::SafetyConfig;
 /// 2. Get DiffusionClient from AppState (503 if absent)
 /// 3. Run prompt safety keyword check (Layer 1 fast path)
 /// 4. If prompt unsafe -> return 400 with reason
-/// 5. Call DiffusionClient::generate()
-/// 6. Calculate billing units
-/// 7. Build and return GenerateImageResponse
+/// 5. Join the generation queue (429 if full) and wait for a slot
+/// 6. Call DiffusionClient::generate()
+/// 7. Calculate itemized billing and, with a job ID, track it for settlement
+/// 8. Build and return GenerateImageResponse
 pub async fn generate_image_handler(
     State(state): State<AppState>,
     Json(request): Json<GenerateImageRequest>,
@@ -32,6 +41,106 @@ pub async fn generate_image_handler(
         request.chain_id
     );
 
+    let client_guard = state.diffusion_client.read().await;
+    let diffusion_client = checked_client(&request, client_guard.as_ref())?;
+    let diffusion_request = to_diffusion_request(&request);
+    let ticket = enqueue(diffusion_client)?;
+
+    let result = diffusion_client
+        .generate_queued(ticket, &diffusion_request, |_| {})
+        .await
+        .map_err(|e| {
+            warn!("Diffusion generation failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Image generation failed: {}", e),
+            )
+        })?;
+
+    let response = build_response(&request, result);
+    track_billing(&state, &request, &response).await;
+
+    Ok(Json(response))
+}
+
+/// POST /v1/images/jobs - Queue an image generation and return immediately
+///
+/// Runs the same checks as `/v1/images/generate`, then responds 202 with a
+/// job id, queue position and ETA. The image is fetched by polling
+/// `GET /v1/images/jobs/:job_id`.
+pub async fn submit_image_job_handler(
+    State(state): State<AppState>,
+    Json(request): Json<GenerateImageRequest>,
+) -> Result<(StatusCode, Json<ImageJobResponse>), (StatusCode, String)> {
+    let client_guard = state.diffusion_client.read().await;
+    let diffusion_client = checked_client(&request, client_guard.as_ref())?.clone();
+    let diffusion_request = to_diffusion_request(&request);
+    let ticket = enqueue(&diffusion_client)?;
+
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let job = ImageJobResponse::queued(job_id.clone(), ticket.position());
+    let jobs = state.api_server.image_gen_jobs();
+    let api_server = state.api_server.clone();
+    jobs.insert(job.clone());
+    info!(
+        "Image generation job {} queued at position {:?}",
+        job_id, job.queue_position
+    );
+
+    tokio::spawn(async move {
+        let permit = ticket
+            .wait_turn_with_updates(|position| jobs.update_position(&job_id, position))
+            .await;
+        jobs.set_running(&job_id);
+
+        match diffusion_client.generate(&diffusion_request).await {
+            Ok(result) => {
+                permit.complete();
+                let response = build_response(&request, result);
+                if let Some(billing_job_id) = request.job_id {
+                    api_server
+                        .track_image_generation(
+                            billing_job_id,
+                            request.session_id.as_deref(),
+                            response.billing.generation_units,
+                        )
+                        .await;
+                }
+                jobs.complete(&job_id, response);
+            }
+            Err(e) => {
+                warn!("Image generation job {} failed: {}", job_id, e);
+                jobs.fail(&job_id, format!("Image generation failed: {}", e));
+            }
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// GET /v1/images/jobs/:job_id - Poll a queued image generation job
+pub async fn image_job_status_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<ImageJobResponse>, (StatusCode, String)> {
+    state
+        .api_server
+        .image_gen_jobs()
+        .get(&job_id)
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Image generation job '{}' not found", job_id),
+            )
+        })
+}
+
+/// Validate the request, require a diffusion client and check prompt safety
+fn checked_client<'a>(
+    request: &GenerateImageRequest,
+    client: Option<&'a Arc<DiffusionClient>>,
+) -> Result<&'a Arc<DiffusionClient>, (StatusCode, String)> {
     // 1. Validate request
     if let Err(e) = request.validate() {
         warn!("Image generation validation failed: {}", e);
@@ -39,8 +148,7 @@ pub async fn generate_image_handler(
     }
 
     // 2. Get diffusion client (503 if None)
-    let client_guard = state.diffusion_client.read().await;
-    let diffusion_client = client_guard.as_ref().ok_or_else(|| {
+    let diffusion_client = client.ok_or_else(|| {
         warn!("Diffusion service not available");
         (
             StatusCode::SERVICE_UNAVAILABLE,
@@ -61,51 +169,64 @@ pub async fn generate_image_handler(
         return Err((StatusCode::BAD_REQUEST, reason));
     }
 
-    // 4. Build the internal ImageGenerationRequest for the diffusion client
-    let size_str = request.size.as_deref().unwrap_or("1024x1024");
-    let steps = request.steps.unwrap_or(4);
-    let guidance_scale = request.guidance_scale.unwrap_or(3.5);
+    Ok(diffusion_client)
+}
 
-    let diffusion_request = crate::diffusion::ImageGenerationRequest {
+/// Build the internal ImageGenerationRequest for the diffusion client
+fn to_diffusion_request(request: &GenerateImageRequest) -> ImageGenerationRequest {
+    ImageGenerationRequest {
         prompt: request.prompt.clone(),
         model: request.model.clone(),
-        size: size_str.to_string(),
-        steps,
+        size: request.size.as_deref().unwrap_or("1024x1024").to_string(),
+        steps: request.steps.unwrap_or(4),
         seed: request.seed,
         negative_prompt: request.negative_prompt.clone(),
-        guidance_scale,
+        guidance_scale: request.guidance_scale.unwrap_or(3.5),
         response_format: "b64_json".to_string(),
         n: 1,
-    };
+    }
+}
 
-    // 5. Generate image
-    let result = diffusion_client
-        .generate(&diffusion_request)
-        .await
-        .map_err(|e| {
-            warn!("Diffusion generation failed: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Image generation failed: {}", e),
+/// Join the generation queue, rejecting with 429 when it is full
+fn enqueue(client: &DiffusionClient) -> Result<QueueTicket, (StatusCode, String)> {
+    client.queue().try_enqueue().map_err(|e| {
+        warn!("Rejecting image generation: {}", e);
+        (StatusCode::TOO_MANY_REQUESTS, e.to_string())
+    })
+}
+
+/// Bill the generation to the request's job, if it has one
+async fn track_billing(
+    state: &AppState,
+    request: &GenerateImageRequest,
+    response: &GenerateImageResponse,
+) {
+    if let Some(job_id) = request.job_id {
+        state
+            .api_server
+            .track_image_generation(
+                job_id,
+                request.session_id.as_deref(),
+                response.billing.generation_units,
             )
-        })?;
+            .await;
+    }
+}
+
+/// Calculate billing and build the response for a finished generation
+fn build_response(
+    request: &GenerateImageRequest,
+    result: DiffusionResult,
+) -> GenerateImageResponse {
+    let size_str = request.size.as_deref().unwrap_or("1024x1024");
+    let steps = result.steps;
 
-    // 6. Calculate billing
     let size = ImageSize::parse(size_str).unwrap_or(ImageSize {
         width: 1024,
         height: 1024,
     });
-    let megapixels = size.megapixels();
-    let step_factor = steps as f64 / 20.0;
-    let model_multiplier = 1.0;
-    let generation_units = megapixels * step_factor * model_multiplier;
-
-    let billing = BillingInfo {
-        generation_units,
-        model_multiplier,
-        megapixels,
-        steps,
-    };
+    let billing = BillingInfo::from(ImagePriceTable::global().charge(&size, steps, &result.model));
+    let generation_units = billing.generation_units;
 
     let safety_info = SafetyInfo {
         prompt_safe: true,
@@ -124,18 +245,15 @@ pub async fn generate_image_handler(
         result.model, size_str, steps, result.processing_time_ms, generation_units
     );
 
-    // 7. Build response
-    let response = GenerateImageResponse::with_chain_context(
+    GenerateImageResponse::with_chain_context(
         result.base64_image,
         result.model,
         size_str.to_string(),
-        result.steps,
+        steps,
         result.seed,
         result.processing_time_ms,
         safety_info,
         billing,
         chain_id,
-    );
-
-    Ok(Json(response))
+    )
 }
@@ -0,0 +1,147 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Queued image generation jobs polled via GET /v1/images/jobs/:job_id
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use super::response::GenerateImageResponse;
+use crate::diffusion::QueuePosition;
+
+/// How long finished jobs stay available for polling
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(600);
+
+/// Lifecycle of a queued image generation job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Job state returned when submitting and polling a job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageJobResponse {
+    /// Job identifier for polling
+    pub job_id: String,
+    /// Current status
+    pub status: ImageJobStatus,
+    /// Position in the queue (1 = next), while queued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+    /// Estimated milliseconds until the image is ready, while queued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_ms: Option<u64>,
+    /// Generated image, once completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GenerateImageResponse>,
+    /// Failure reason, if failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ImageJobResponse {
+    /// A newly queued job
+    pub fn queued(job_id: String, position: Option<QueuePosition>) -> Self {
+        Self {
+            job_id,
+            status: ImageJobStatus::Queued,
+            queue_position: position.map(|p| p.position),
+            eta_ms: position.map(|p| p.eta_ms),
+            result: None,
+            error: None,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            ImageJobStatus::Completed | ImageJobStatus::Failed
+        )
+    }
+}
+
+/// In-memory store of queued image generation jobs
+///
+/// Finished jobs are pruned `FINISHED_JOB_TTL` after completion.
+pub struct ImageGenerationJobs {
+    jobs: RwLock<HashMap<String, (ImageJobResponse, Instant)>>,
+}
+
+impl Default for ImageGenerationJobs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageGenerationJobs {
+    pub fn new() -> Self {
+        Self {
+            jobs: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Add a job, pruning expired finished jobs
+    pub fn insert(&self, job: ImageJobResponse) {
+        let mut jobs = self.jobs.write().unwrap();
+        jobs.retain(|_, (job, updated)| !job.is_finished() || updated.elapsed() < FINISHED_JOB_TTL);
+        jobs.insert(job.job_id.clone(), (job, Instant::now()));
+    }
+
+    /// Get a job's current state
+    pub fn get(&self, job_id: &str) -> Option<ImageJobResponse> {
+        self.jobs
+            .read()
+            .unwrap()
+            .get(job_id)
+            .map(|(job, _)| job.clone())
+    }
+
+    /// Record a queued job's new position
+    pub fn update_position(&self, job_id: &str, position: QueuePosition) {
+        self.update(job_id, |job| {
+            job.queue_position = Some(position.position);
+            job.eta_ms = Some(position.eta_ms);
+        });
+    }
+
+    /// Mark a job as holding a generation slot
+    pub fn set_running(&self, job_id: &str) {
+        self.update(job_id, |job| {
+            job.status = ImageJobStatus::Running;
+            job.queue_position = None;
+        });
+    }
+
+    /// Store a job's generated image
+    pub fn complete(&self, job_id: &str, response: GenerateImageResponse) {
+        self.update(job_id, |job| {
+            job.status = ImageJobStatus::Completed;
+            job.queue_position = None;
+            job.eta_ms = None;
+            job.result = Some(response);
+        });
+    }
+
+    /// Record why a job failed
+    pub fn fail(&self, job_id: &str, error: String) {
+        self.update(job_id, |job| {
+            job.status = ImageJobStatus::Failed;
+            job.queue_position = None;
+            job.eta_ms = None;
+            job.error = Some(error);
+        });
+    }
+
+    fn update(&self, job_id: &str, apply: impl FnOnce(&mut ImageJobResponse)) {
+        if let Some((job, updated)) = self.jobs.write().unwrap().get_mut(job_id) {
+            apply(job);
+            *updated = Instant::now();
+        }
+    }
+}
@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: BUSL-1.1
 //! Image generation API endpoint module
 //!
-//! Provides POST /v1/images/generate for text-to-image generation.
+//! Provides POST /v1/images/generate for text-to-image generation, and
+//! POST /v1/images/jobs with GET /v1/images/jobs/:job_id for queued jobs.
 
 pub mod handler;
+pub mod jobs;
 pub mod request;
 pub mod response;
 
-pub use handler::generate_image_handler;
+pub use handler::{generate_image_handler, image_job_status_handler, submit_image_job_handler};
+pub use jobs::{ImageGenerationJobs, ImageJobResponse, ImageJobStatus};
 pub use request::GenerateImageRequest;
 pub use response::{BillingInfo, GenerateImageResponse, SafetyInfo};
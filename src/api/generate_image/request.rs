@@ -4,7 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::diffusion::client::ALLOWED_SIZES;
+use crate::diffusion::client::{ALLOWED_SIZES, MAX_SEED};
 
 /// Request for image generation via POST /v1/images/generate
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,7 +25,8 @@ pub struct GenerateImageRequest {
     #[serde(default)]
     pub steps: Option<u32>,
 
-    /// Random seed for reproducibility
+    /// Random seed for reproducibility (0 to 4294967295); a random seed is
+    /// used and returned in the response when omitted
     #[serde(default)]
     pub seed: Option<u64>,
 
@@ -80,6 +81,16 @@ impl GenerateImageRequest {
             }
         }
 
+        // Validate seed is within the sidecar's accepted range
+        if let Some(seed) = self.seed {
+            if seed > MAX_SEED {
+                return Err(format!(
+                    "seed must be between 0 and {}, got {}",
+                    MAX_SEED, seed
+                ));
+            }
+        }
+
         Ok(())
     }
 }
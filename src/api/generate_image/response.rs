@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::diffusion::billing::ImageCharge;
+
 /// Response from image generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,7 +18,7 @@ pub struct GenerateImageResponse {
     pub size: String,
     /// Inference steps used
     pub steps: u32,
-    /// Random seed used
+    /// Seed used, including a randomly chosen one when none was requested
     pub seed: u64,
     /// Processing time in milliseconds
     pub processing_time_ms: u64,
@@ -50,7 +52,7 @@ pub struct SafetyInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BillingInfo {
-    /// Generation units consumed (megapixels * step_factor * model_multiplier)
+    /// Generation units consumed (sum of the itemized charges)
     pub generation_units: f64,
     /// Model-specific billing multiplier
     pub model_multiplier: f64,
@@ -58,6 +60,29 @@ pub struct BillingInfo {
     pub megapixels: f64,
     /// Number of inference steps
     pub steps: u32,
+    /// Flat per-generation charge
+    #[serde(default)]
+    pub base_units: f64,
+    /// Charge for the output resolution
+    #[serde(default)]
+    pub megapixel_units: f64,
+    /// Charge for the inference steps
+    #[serde(default)]
+    pub step_units: f64,
+}
+
+impl From<ImageCharge> for BillingInfo {
+    fn from(charge: ImageCharge) -> Self {
+        Self {
+            generation_units: charge.total_units,
+            model_multiplier: charge.model_multiplier,
+            megapixels: charge.megapixels,
+            steps: charge.steps,
+            base_units: charge.base_units,
+            megapixel_units: charge.megapixel_units,
+            step_units: charge.step_units,
+        }
+    }
 }
 
 impl GenerateImageResponse {
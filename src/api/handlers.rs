@@ -1,7 +1,11 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
+use crate::inference::{
+    tools, ChatTemplateOverride, ResponseFormat, TokenLogprobs, Tool, ToolCall, MAX_LOGPROBS,
+};
 use crate::job_processor::Message;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InferenceRequest {
@@ -35,8 +39,46 @@ pub struct InferenceRequest {
     /// Values: "enabled", "disabled", "low", "medium", "high"
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub thinking: Option<String>,
+    /// Custom stop sequences — generation halts before the first match
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        alias = "stop_sequences"
+    )]
+    pub stop: Vec<String>,
+    /// Additive logit bias keyed by token ID (clamped to ±100, -100 bans a token)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub logit_bias: HashMap<u32, f32>,
+    /// Per-request inference timeout in milliseconds (overrides INFERENCE_TIMEOUT_MS)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timeout_ms: Option<u64>,
+    /// Structured output format, e.g. `{"type": "json_schema", "schema": {...}}`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub response_format: Option<ResponseFormat>,
+    /// Return per-token logprobs with this many top alternatives (0-20)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub logprobs: Option<u32>,
+    /// Sampling seed; repeating a request with the same seed reproduces its
+    /// output while `system_fingerprint` is unchanged
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub seed: Option<u64>,
+    /// Chat template used instead of the model default: a built-in name
+    /// (e.g. "chatml") or custom text with `{role}` and `{content}` placeholders
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        alias = "chatTemplate"
+    )]
+    pub chat_template: Option<String>,
+    /// Functions the model may call; a call ends the response with
+    /// `finish_reason: "tool_calls"`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool>,
 }
 
+/// Maximum number of custom stop sequences per request
+pub const MAX_STOP_SEQUENCES: usize = 8;
+
 fn default_max_searches() -> u32 {
     5
 }
@@ -70,6 +112,15 @@ pub struct InferenceResponse {
     /// Context usage information (v8.21.0+)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage: Option<UsageInfo>,
+    /// Per-token logprobs, present when the request set `logprobs`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub logprobs: Option<Vec<TokenLogprobs>>,
+    /// Function calls the model requested, when the request offered tools
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Identifies the model and engine settings behind the response
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub system_fingerprint: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,11 +148,21 @@ pub struct ModelsResponse {
     pub chain_name: Option<String>,
 }
 
+/// Response for `GET /v1/models/{id}`: GGUF header details of a loaded model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDetailsResponse {
+    pub id: String,
+    pub metadata: crate::inference::GgufMetadata,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthResponse {
     pub status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub issues: Option<Vec<String>>,
+    /// Present when an S5 bridge is attached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s5_circuit_breaker: Option<crate::storage::CircuitBreakerStatus>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -205,6 +266,75 @@ impl InferenceRequest {
             });
         }
 
+        if self.stop.len() > MAX_STOP_SEQUENCES {
+            return Err(ApiError::ValidationError {
+                field: "stop".to_string(),
+                message: format!("At most {} stop sequences are allowed", MAX_STOP_SEQUENCES),
+            });
+        }
+
+        if self.stop.iter().any(|s| s.is_empty()) {
+            return Err(ApiError::ValidationError {
+                field: "stop".to_string(),
+                message: "Stop sequences cannot be empty".to_string(),
+            });
+        }
+
+        if let Some((token_id, _)) = self.logit_bias.iter().find(|(_, bias)| !bias.is_finite()) {
+            return Err(ApiError::ValidationError {
+                field: "logit_bias".to_string(),
+                message: format!("Bias for token {} must be a finite number", token_id),
+            });
+        }
+
+        if self.logprobs.is_some_and(|n| n > MAX_LOGPROBS) {
+            return Err(ApiError::ValidationError {
+                field: "logprobs".to_string(),
+                message: format!("logprobs must be between 0 and {}", MAX_LOGPROBS),
+            });
+        }
+
+        if let Some(ref format) = self.response_format {
+            if let Err(e) = format.to_gbnf() {
+                return Err(ApiError::ValidationError {
+                    field: "response_format".to_string(),
+                    message: e.to_string(),
+                });
+            }
+        }
+
+        if self.timeout_ms == Some(0) {
+            return Err(ApiError::ValidationError {
+                field: "timeout_ms".to_string(),
+                message: "timeout_ms must be greater than 0".to_string(),
+            });
+        }
+
+        if let Some(ref template) = self.chat_template {
+            let rendered = ChatTemplateOverride::parse(template).and_then(|template| {
+                crate::utils::context::build_prompt_with_template(
+                    &self.conversation_context,
+                    &self.prompt,
+                    None,
+                    Some(&template),
+                    &self.tools,
+                )
+            });
+            if let Err(e) = rendered {
+                return Err(ApiError::ValidationError {
+                    field: "chat_template".to_string(),
+                    message: e.to_string(),
+                });
+            }
+        }
+
+        if let Err(message) = tools::validate_tools(&self.tools) {
+            return Err(ApiError::ValidationError {
+                field: "tools".to_string(),
+                message,
+            });
+        }
+
         if let Some(ref thinking) = self.thinking {
             let valid = ["enabled", "disabled", "low", "medium", "high"];
             if !valid.contains(&thinking.as_str()) {
@@ -220,6 +350,22 @@ impl InferenceRequest {
 
         Ok(())
     }
+
+    /// The request's chat template override, if it has a valid one
+    pub fn chat_template_override(&self) -> Option<ChatTemplateOverride> {
+        self.chat_template
+            .as_deref()
+            .and_then(|template| ChatTemplateOverride::parse(template).ok())
+    }
+
+    /// Requested stop sequences plus those of an overriding built-in template
+    pub fn stop_sequences(&self) -> Vec<String> {
+        let mut stop = self.stop.clone();
+        if let Some(template) = self.chat_template_override() {
+            stop.extend(template.stop_tokens().into_iter().map(String::from));
+        }
+        stop
+    }
 }
 
 #[cfg(test)]
@@ -271,4 +417,117 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_stop_field_deserializes_and_validates() {
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,"stop":["\n\n","END"]}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.stop, vec!["\n\n".to_string(), "END".to_string()]);
+        assert!(req.validate().is_ok());
+
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(req.stop.is_empty());
+    }
+
+    #[test]
+    fn test_stop_field_validation_rejects_empty_and_too_many() {
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,"stop":[""]}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(format!("{:?}", req.validate().unwrap_err()).contains("stop"));
+
+        let mut req = req;
+        req.stop = (0..=MAX_STOP_SEQUENCES).map(|i| i.to_string()).collect();
+        assert!(format!("{:?}", req.validate().unwrap_err()).contains("stop"));
+    }
+
+    #[test]
+    fn test_logit_bias_field_deserializes() {
+        let json =
+            r#"{"model":"m","prompt":"p","max_tokens":10,"logit_bias":{"50256":-100,"13":2.5}}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.logit_bias.get(&50256), Some(&-100.0));
+        assert_eq!(req.logit_bias.get(&13), Some(&2.5));
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_response_format_field_validates_schema() {
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,
+            "response_format":{"type":"json_schema","schema":{"type":"object","properties":{"ok":{"type":"boolean"}}}}}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            req.response_format,
+            Some(ResponseFormat::JsonSchema { .. })
+        ));
+        assert!(req.validate().is_ok());
+
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,
+            "response_format":{"type":"json_schema","schema":{"type":"date"}}}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(format!("{:?}", req.validate().unwrap_err()).contains("response_format"));
+    }
+
+    #[test]
+    fn test_logprobs_field_bounds() {
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,"logprobs":5}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.logprobs, Some(5));
+        assert!(req.validate().is_ok());
+
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,"logprobs":21}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(format!("{:?}", req.validate().unwrap_err()).contains("logprobs"));
+    }
+
+    #[test]
+    fn test_chat_template_field_validates_rendering() {
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,"chatTemplate":"chatml"}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(req.validate().is_ok());
+        assert!(matches!(
+            req.chat_template_override(),
+            Some(ChatTemplateOverride::Builtin(_))
+        ));
+
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,
+            "chat_template":"<tool_call>{role}: {content}</tool_call>\n"}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(req.validate().is_ok());
+
+        let json =
+            r#"{"model":"m","prompt":"p","max_tokens":10,"chat_template":"{name}: {content}"}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(format!("{:?}", req.validate().unwrap_err()).contains("chat_template"));
+        assert!(req.chat_template_override().is_none());
+
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(req.chat_template_override().is_none());
+    }
+
+    #[test]
+    fn test_tools_field_deserializes_and_validates() {
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,"tools":[
+            {"type":"function","function":{"name":"get_weather","parameters":{"type":"object"}}}]}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.tools[0].function.name, "get_weather");
+        assert!(req.validate().is_ok());
+
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,"tools":[
+            {"type":"function","function":{"name":"get weather"}}]}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert!(format!("{:?}", req.validate().unwrap_err()).contains("tools"));
+    }
+
+    #[test]
+    fn test_seed_field_accepts_full_u64_range() {
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10,"seed":18446744073709551615}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.seed, Some(u64::MAX));
+
+        let json = r#"{"model":"m","prompt":"p","max_tokens":10}"#;
+        let req: InferenceRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.seed, None);
+    }
 }
@@ -0,0 +1,313 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Prometheus metrics exposition
+//!
+//! On every scrape of `/metrics` the server samples the engine, prompt cache,
+//! GPUs and per-chain payment trackers into a shared `MetricsRegistry`, which
+//! is then rendered in Prometheus text format.
+
+use crate::cache::CacheMetrics;
+use crate::inference::EngineMetrics;
+use crate::monitoring::MetricsRegistry;
+use crate::payments::PaymentStats;
+use crate::performance::GpuMetrics;
+use ethers::types::U256;
+
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Engine totals, plus one `fabstir_model_loaded` series per loaded model
+pub async fn record_engine_metrics(
+    registry: &MetricsRegistry,
+    metrics: &EngineMetrics,
+    loaded_models: &[String],
+) {
+    registry
+        .set_counter(
+            "fabstir_inferences_total",
+            "Completed inference requests",
+            &[],
+            metrics.total_inferences as f64,
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_tokens_generated_total",
+            "Tokens generated across all inferences",
+            &[],
+            metrics.total_tokens_generated as f64,
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_inference_seconds_total",
+            "Time spent generating tokens",
+            &[],
+            metrics.total_inference_time.as_secs_f64(),
+        )
+        .await;
+    registry
+        .set_gauge(
+            "fabstir_tokens_per_second",
+            "Average generation throughput",
+            &[],
+            metrics.average_tokens_per_second as f64,
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_speculative_tokens_drafted_total",
+            "Tokens proposed by the draft model",
+            &[],
+            metrics.speculative_tokens_drafted as f64,
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_speculative_tokens_accepted_total",
+            "Drafted tokens accepted by the main model",
+            &[],
+            metrics.speculative_tokens_accepted as f64,
+        )
+        .await;
+
+    registry.clear_family("fabstir_model_loaded").await;
+    for model in loaded_models {
+        registry
+            .set_gauge(
+                "fabstir_model_loaded",
+                "Models currently loaded in the engine",
+                &[("model", model.as_str())],
+                1.0,
+            )
+            .await;
+    }
+}
+
+pub async fn record_cache_metrics(registry: &MetricsRegistry, metrics: &CacheMetrics) {
+    registry
+        .set_counter(
+            "fabstir_prompt_cache_requests_total",
+            "Prompt cache lookups",
+            &[],
+            metrics.total_requests as f64,
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_prompt_cache_hits_total",
+            "Prompt cache hits",
+            &[],
+            metrics.cache_hits as f64,
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_prompt_cache_misses_total",
+            "Prompt cache misses",
+            &[],
+            metrics.cache_misses as f64,
+        )
+        .await;
+    registry
+        .set_gauge(
+            "fabstir_prompt_cache_hit_rate",
+            "Fraction of prompt cache lookups that hit",
+            &[],
+            metrics.hit_rate,
+        )
+        .await;
+    registry
+        .set_gauge(
+            "fabstir_prompt_cache_size_bytes",
+            "Memory used by cached responses",
+            &[],
+            metrics.cache_size_mb * 1024.0 * 1024.0,
+        )
+        .await;
+}
+
+/// Per-device GPU gauges, with memory broken down by the model holding it
+pub async fn record_gpu_metrics(registry: &MetricsRegistry, gpus: &[(i32, GpuMetrics)]) {
+    registry
+        .clear_family("fabstir_gpu_model_memory_bytes")
+        .await;
+    for (device_id, metrics) in gpus {
+        let device = device_id.to_string();
+        let labels = [("device", device.as_str())];
+        registry
+            .set_gauge(
+                "fabstir_gpu_utilization_percent",
+                "GPU utilization",
+                &labels,
+                metrics.utilization_percent as f64,
+            )
+            .await;
+        registry
+            .set_gauge(
+                "fabstir_gpu_memory_used_bytes",
+                "GPU memory in use",
+                &labels,
+                metrics.memory_used as f64,
+            )
+            .await;
+        registry
+            .set_gauge(
+                "fabstir_gpu_memory_total_bytes",
+                "Total GPU memory",
+                &labels,
+                metrics.memory_total as f64,
+            )
+            .await;
+        registry
+            .set_gauge(
+                "fabstir_gpu_temperature_celsius",
+                "GPU temperature",
+                &labels,
+                metrics.temperature_celsius as f64,
+            )
+            .await;
+        registry
+            .set_gauge(
+                "fabstir_gpu_power_watts",
+                "GPU power draw",
+                &labels,
+                metrics.power_draw_watts as f64,
+            )
+            .await;
+        for process in &metrics.processes {
+            registry
+                .set_gauge(
+                    "fabstir_gpu_model_memory_bytes",
+                    "GPU memory held by each model",
+                    &[
+                        ("device", device.as_str()),
+                        ("model", process.name.as_str()),
+                    ],
+                    process.memory_used as f64,
+                )
+                .await;
+        }
+    }
+}
+
+/// Payment totals for one chain, amounts in wei
+pub async fn record_payment_stats(registry: &MetricsRegistry, chain_id: u64, stats: &PaymentStats) {
+    let chain = chain_id.to_string();
+    let labels = [("chain_id", chain.as_str())];
+    registry
+        .set_counter(
+            "fabstir_payments_total",
+            "Confirmed payments received",
+            &labels,
+            stats.payment_count as f64,
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_payments_received_wei_total",
+            "Payment amount received",
+            &labels,
+            wei_to_f64(stats.total_received),
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_payments_failed_wei_total",
+            "Payment amount that failed",
+            &labels,
+            wei_to_f64(stats.total_failed),
+        )
+        .await;
+    registry
+        .set_counter(
+            "fabstir_payments_refunded_wei_total",
+            "Payment amount refunded",
+            &labels,
+            wei_to_f64(stats.total_refunded),
+        )
+        .await;
+    registry
+        .set_gauge(
+            "fabstir_payment_success_rate",
+            "Fraction of payments that succeeded",
+            &labels,
+            stats.success_rate,
+        )
+        .await;
+}
+
+/// Prometheus samples are floats; precision loss on huge amounts is fine
+fn wei_to_f64(amount: U256) -> f64 {
+    amount.to_string().parse().unwrap_or(f64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::PrometheusExporter;
+    use crate::performance::gpu_management::ProcessInfo;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_series_labeled_by_model_and_chain() {
+        let registry = MetricsRegistry::new();
+        let engine = EngineMetrics {
+            total_inferences: 3,
+            total_tokens_generated: 120,
+            average_tokens_per_second: 40.0,
+            total_inference_time: Duration::from_secs(3),
+            speculative_tokens_drafted: 0,
+            speculative_tokens_accepted: 0,
+            speculative_acceptance_rate: 0.0,
+        };
+        record_engine_metrics(&registry, &engine, &["llama-7b".to_string()]).await;
+        record_gpu_metrics(
+            &registry,
+            &[(
+                0,
+                GpuMetrics {
+                    temperature_celsius: 60.0,
+                    utilization_percent: 50.0,
+                    memory_used: 1024,
+                    memory_total: 4096,
+                    power_draw_watts: 250.0,
+                    processes: vec![ProcessInfo {
+                        pid: 1,
+                        name: "llama-7b".to_string(),
+                        memory_used: 1024,
+                    }],
+                },
+            )],
+        )
+        .await;
+        let stats = PaymentStats {
+            total_received: U256::from(5_000u64),
+            total_failed: U256::zero(),
+            total_refunded: U256::zero(),
+            payment_count: 2,
+            success_rate: 1.0,
+            average_payment: U256::from(2_500u64),
+        };
+        record_payment_stats(&registry, 84532, &stats).await;
+        record_payment_stats(&registry, 5611, &stats).await;
+
+        let output = registry.export(&PrometheusExporter::new()).await.unwrap();
+        assert!(output.contains("fabstir_inferences_total 3\n"));
+        assert!(output.contains("fabstir_model_loaded{model=\"llama-7b\"} 1\n"));
+        assert!(output
+            .contains("fabstir_gpu_model_memory_bytes{device=\"0\",model=\"llama-7b\"} 1024\n"));
+        assert!(output.contains("fabstir_payments_total{chain_id=\"84532\"} 2\n"));
+        assert!(output.contains("fabstir_payments_total{chain_id=\"5611\"} 2\n"));
+        assert_eq!(
+            output
+                .matches("# TYPE fabstir_payments_total counter")
+                .count(),
+            1
+        );
+
+        // Unloaded models drop out on the next scrape
+        record_engine_metrics(&registry, &engine, &[]).await;
+        let output = registry.export(&PrometheusExporter::new()).await.unwrap();
+        assert!(!output.contains("fabstir_model_loaded"));
+    }
+}
@@ -1,20 +1,27 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
+pub mod auth;
 pub mod describe_image;
 pub mod embed;
 pub mod errors;
 pub mod generate_image;
 pub mod handlers;
 pub mod http_server;
+pub mod metrics;
 pub mod ocr;
 pub mod pool;
+pub mod rate_limit;
+pub mod request_log;
 pub mod response_formatter;
 pub mod search;
 pub mod server;
 pub mod streaming;
 pub mod token_tracker;
+pub mod tokenize;
+pub mod verify_proof;
 pub mod websocket;
 
+pub use auth::{ApiIdentity, ApiKeyVerifier};
 pub use describe_image::{describe_image_handler, DescribeImageRequest, DescribeImageResponse};
 pub use embed::{embed_handler, EmbedRequest, EmbedResponse, EmbeddingResult};
 pub use errors::{ApiError, ErrorResponse};
@@ -24,8 +31,15 @@ pub use handlers::{
     InferenceRequest, InferenceResponse, ModelInfo, ModelsResponse, SessionInfo,
     SessionInfoResponse, SessionStatus, TotalStatistics, UsageInfo,
 };
-pub use ocr::{ocr_handler, OcrRequest, OcrResponse};
+pub use ocr::{ocr_handler, ocr_stream_handler, OcrRequest, OcrResponse, OcrStreamRequest};
 pub use pool::{ConnectionPool, ConnectionStats, PoolConfig};
+pub use rate_limit::{RateLimit, RateLimitConfig};
+pub use request_log::{FieldPolicy, RequestLogConfig};
 pub use search::{search_handler, SearchApiRequest, SearchApiResponse};
 pub use server::{ApiConfig, ApiServer};
 pub use streaming::StreamingResponse;
+pub use tokenize::{
+    detokenize_handler, tokenize_handler, DetokenizeRequest, DetokenizeResponse, TokenizeRequest,
+    TokenizeResponse,
+};
+pub use verify_proof::{verify_proof_handler, VerifyProofRequest, VerifyProofResponse};
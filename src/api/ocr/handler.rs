@@ -2,13 +2,23 @@
 // SPDX-License-Identifier: BUSL-1.1
 //! OCR endpoint handler
 
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::{extract::State, http::StatusCode, Json};
+use futures::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 use tracing::{debug, info, warn};
 
-use super::request::OcrRequest;
-use super::response::{BoundingBox, OcrResponse, TextRegion};
+use super::request::{OcrRequest, OcrStreamRequest};
+use super::response::{
+    BoundingBox, OcrPageError, OcrPageResponse, OcrResponse, OcrStreamSummary, TextRegion,
+};
 use crate::api::http_server::AppState;
 use crate::vision::decode_base64_image;
+use crate::vision::ocr::{PageOcrResult, TextRegion as OcrTextRegion};
 
 /// POST /v1/ocr - Extract text from an image
 ///
@@ -18,13 +28,16 @@ use crate::vision::decode_base64_image;
 /// # Request
 /// - `image`: Base64-encoded image data (required)
 /// - `format`: Image format hint (png, jpg, webp, gif) - defaults to "png"
-/// - `language`: Language hint (en, zh, ja, ko) - defaults to "en"
+/// - `language`: Language hint (en, zh, ja, ko) selecting the recognition
+///   model - defaults to "auto", which detects the script of each region
 /// - `chainId`: Chain ID for pricing context - defaults to 84532 (Base Sepolia)
+/// - `minConfidence`: Drop regions with lower recognition confidence - defaults
+///   to no filtering
 ///
 /// # Response
 /// - `text`: Full extracted text (all regions combined)
 /// - `confidence`: Average confidence score (0.0-1.0)
-/// - `regions`: Individual text regions with bounding boxes
+/// - `regions`: Individual text regions with bounding boxes and detected language
 /// - `processingTimeMs`: Processing time in milliseconds
 /// - `model`: Model used ("paddleocr")
 /// - `provider`: Service provider ("host")
@@ -114,13 +127,19 @@ pub async fn ocr_handler(
     );
 
     // 5. Run OCR
-    let ocr_result = ocr_model.process(&image).map_err(|e| {
-        warn!("OCR processing failed: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("OCR processing failed: {}", e),
-        )
-    })?;
+    let mut ocr_result = ocr_model
+        .process_with_language(&image, request.language_hint())
+        .map_err(|e| {
+            warn!("OCR processing failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("OCR processing failed: {}", e),
+            )
+        })?;
+    ocr_result.scale_to_original(&image_info);
+    if let Some(min_confidence) = request.min_confidence {
+        ocr_result.retain_min_confidence(min_confidence);
+    }
 
     info!(
         "OCR complete: {} regions, {:.2} confidence, {}ms",
@@ -130,20 +149,7 @@ pub async fn ocr_handler(
     );
 
     // 6. Convert OCR result to response format
-    let regions: Vec<TextRegion> = ocr_result
-        .regions
-        .iter()
-        .map(|r| TextRegion {
-            text: r.text.clone(),
-            confidence: r.confidence,
-            bounding_box: BoundingBox {
-                x: r.bounding_box.x,
-                y: r.bounding_box.y,
-                width: r.bounding_box.width,
-                height: r.bounding_box.height,
-            },
-        })
-        .collect();
+    let regions = to_response_regions(&ocr_result.regions);
 
     // 7. Build response with chain context
     let response = OcrResponse::new(
@@ -158,6 +164,177 @@ pub async fn ocr_handler(
     Ok(Json(response))
 }
 
+/// POST /v1/ocr/stream - Extract text from a multi-page document
+///
+/// Accepts base64-encoded page images and streams one Server-Sent Event per
+/// page as soon as it is recognized, so clients can render early pages of a
+/// long scan while later ones are still processing. Always uses PaddleOCR:
+/// the VLM path returns no bounding boxes.
+///
+/// # Request
+/// - `images`: Base64-encoded page images, in page order (required, max 100)
+/// - `format`, `language`, `chainId`, `minConfidence`: As for `/v1/ocr`
+///
+/// # Events
+/// - `page`: `OcrPageResponse` with `pageIndex`, page `width`/`height` and
+///   regions whose bounding boxes are in that page's coordinates
+/// - `error`: `OcrPageError` for a page that failed; later pages still run
+/// - `done`: `OcrStreamSummary` with page counts, total time and chain context
+///
+/// # Errors
+/// Returned before the stream starts:
+/// - 400 Bad Request: Invalid request or an undecodable page image
+/// - 503 Service Unavailable: OCR model not loaded
+pub async fn ocr_stream_handler(
+    State(state): State<AppState>,
+    Json(request): Json<OcrStreamRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    debug!(
+        "Streaming OCR request received: {} pages, chain_id: {}",
+        request.images.len(),
+        request.chain_id
+    );
+
+    // 1. Validate request
+    if let Err(e) = request.validate() {
+        warn!("Streaming OCR validation failed: {}", e);
+        return Err((StatusCode::BAD_REQUEST, e.to_string()));
+    }
+
+    // 2. Get OCR model
+    let ocr_model = {
+        let manager_guard = state.vision_model_manager.read().await;
+        let manager = manager_guard.as_ref().ok_or_else(|| {
+            warn!("Vision service not available");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Vision service not available".to_string(),
+            )
+        })?;
+        manager.get_ocr_model().ok_or_else(|| {
+            warn!("OCR model not loaded");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "OCR model not loaded".to_string(),
+            )
+        })?
+    };
+
+    // 3. Decode every page up front so a bad image fails the request
+    let pages = request
+        .images
+        .iter()
+        .enumerate()
+        .map(|(page_index, image_data)| {
+            decode_base64_image(image_data).map_err(|e| {
+                warn!("Failed to decode page {}: {}", page_index, e);
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid image for page {}: {}", page_index, e),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 4. Stream page results as they complete, then a summary
+    let page_count = pages.len();
+    let chain_id = request.chain_id;
+    let start = Instant::now();
+    let failed_pages = Arc::new(AtomicUsize::new(0));
+    let failed = failed_pages.clone();
+
+    let min_confidence = request.min_confidence;
+    let page_events = ocr_model
+        .recognize_pages_with_language(pages, request.language_hint())
+        .enumerate()
+        .map(move |(page_index, mut page)| {
+            if let (Ok(page), Some(min_confidence)) = (&mut page, min_confidence) {
+                page.result.retain_min_confidence(min_confidence);
+            }
+            Ok(page_event(page_index, page, &failed))
+        });
+
+    let done_event = stream::once(async move {
+        let summary = OcrStreamSummary::new(
+            page_count,
+            failed_pages.load(Ordering::Relaxed),
+            start.elapsed().as_millis() as u64,
+            chain_id,
+            "paddleocr",
+        );
+        info!(
+            "Streaming OCR complete: {} pages ({} failed), {}ms",
+            summary.pages, summary.failed_pages, summary.processing_time_ms
+        );
+        Ok(json_event("done", &summary))
+    });
+
+    Ok(Sse::new(page_events.chain(done_event)).keep_alive(KeepAlive::default()))
+}
+
+/// Turn one page's OCR outcome into a `page` or `error` event
+fn page_event(
+    page_index: usize,
+    page: anyhow::Result<PageOcrResult>,
+    failed: &AtomicUsize,
+) -> Event {
+    match page {
+        Ok(page) => {
+            info!(
+                "OCR page {} complete: {} regions, {}ms",
+                page.page_index,
+                page.result.regions.len(),
+                page.result.processing_time_ms
+            );
+            let response = OcrPageResponse {
+                page_index: page.page_index,
+                width: page.width,
+                height: page.height,
+                regions: to_response_regions(&page.result.regions),
+                text: page.result.text,
+                confidence: page.result.confidence,
+                processing_time_ms: page.result.processing_time_ms,
+            };
+            json_event("page", &response)
+        }
+        Err(e) => {
+            warn!("OCR page {} failed: {:#}", page_index, e);
+            failed.fetch_add(1, Ordering::Relaxed);
+            let error = OcrPageError {
+                page_index,
+                error: format!("{:#}", e),
+            };
+            json_event("error", &error)
+        }
+    }
+}
+
+/// Build a named SSE event with a JSON payload
+fn json_event<T: Serialize>(name: &str, payload: &T) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(payload)
+        .unwrap_or_else(|e| Event::default().event("error").data(e.to_string()))
+}
+
+/// Convert model text regions to the API response format
+fn to_response_regions(regions: &[OcrTextRegion]) -> Vec<TextRegion> {
+    regions
+        .iter()
+        .map(|r| TextRegion {
+            text: r.text.clone(),
+            confidence: r.confidence,
+            bounding_box: BoundingBox {
+                x: r.bounding_box.x,
+                y: r.bounding_box.y,
+                width: r.bounding_box.width,
+                height: r.bounding_box.height,
+            },
+            language: r.language.to_string(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,8 +386,32 @@ mod tests {
                 width: 100,
                 height: 30,
             },
+            language: "en".to_string(),
         };
         assert_eq!(region.text, "Hello");
         assert_eq!(region.bounding_box.x, 10);
     }
+
+    #[test]
+    fn test_response_regions_keep_page_coordinates() {
+        use crate::vision::ocr::{BoundingBox as OcrBoundingBox, OcrLanguage};
+
+        let regions = vec![OcrTextRegion {
+            text: "Total".to_string(),
+            confidence: 0.9,
+            bounding_box: OcrBoundingBox {
+                x: 512,
+                y: 1400,
+                width: 80,
+                height: 24,
+            },
+            language: OcrLanguage::Ko,
+        }];
+        let converted = to_response_regions(&regions);
+        assert_eq!(converted[0].text, "Total");
+        assert_eq!(converted[0].bounding_box.x, 512);
+        assert_eq!(converted[0].bounding_box.y, 1400);
+        assert_eq!(converted[0].bounding_box.height, 24);
+        assert_eq!(converted[0].language, "ko");
+    }
 }
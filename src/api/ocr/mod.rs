@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: BUSL-1.1
 //! OCR API endpoint module
 //!
-//! Provides POST /v1/ocr for extracting text from images.
+//! Provides POST /v1/ocr for extracting text from images, and
+//! POST /v1/ocr/stream for streaming text from multi-page documents.
 
 pub mod handler;
 pub mod request;
 pub mod response;
 
-pub use handler::ocr_handler;
-pub use request::OcrRequest;
-pub use response::{OcrResponse, TextRegion};
+pub use handler::{ocr_handler, ocr_stream_handler};
+pub use request::{OcrRequest, OcrStreamRequest};
+pub use response::{OcrPageResponse, OcrResponse, OcrStreamSummary, TextRegion};
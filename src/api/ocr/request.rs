@@ -5,22 +5,31 @@
 use serde::{Deserialize, Serialize};
 
 use crate::api::errors::ApiError;
+use crate::vision::ocr::OcrLanguage;
 
 /// Supported image formats
 const SUPPORTED_FORMATS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
 
-/// Supported OCR languages
-const SUPPORTED_LANGUAGES: &[&str] = &["en", "zh", "ja", "ko"];
+/// Supported OCR languages; "auto" detects the script of each region
+const SUPPORTED_LANGUAGES: &[&str] = &["auto", "en", "zh", "ja", "ko"];
 
 /// Maximum image size (10MB base64 encoded)
 const MAX_IMAGE_SIZE: usize = 10 * 1024 * 1024;
 
+/// Maximum pages in one streaming OCR request
+pub const MAX_OCR_PAGES: usize = 100;
+
 fn default_format() -> String {
     "png".to_string()
 }
 
 fn default_language() -> String {
-    "en".to_string()
+    "auto".to_string()
+}
+
+/// Recognition language for a validated `language` field; `None` for "auto"
+fn language_hint(language: &str) -> Option<OcrLanguage> {
+    language.parse().ok()
 }
 
 fn default_chain_id() -> u64 {
@@ -39,16 +48,26 @@ pub struct OcrRequest {
     #[serde(default = "default_format")]
     pub format: String,
 
-    /// Language hint for OCR (en, zh, ja, ko)
+    /// Language hint for OCR (en, zh, ja, ko), or "auto" to detect
     #[serde(default = "default_language")]
     pub language: String,
 
     /// Chain ID for pricing/metering
     #[serde(default = "default_chain_id")]
     pub chain_id: u64,
+
+    /// Drop regions with recognition confidence below this (0.0-1.0);
+    /// unset keeps every region
+    #[serde(default)]
+    pub min_confidence: Option<f32>,
 }
 
 impl OcrRequest {
+    /// Recognition language to use, or `None` to auto-detect
+    pub fn language_hint(&self) -> Option<OcrLanguage> {
+        language_hint(&self.language)
+    }
+
     /// Validate the OCR request
     pub fn validate(&self) -> Result<(), ApiError> {
         // Validate image is provided
@@ -69,41 +88,148 @@ impl OcrRequest {
             }
         }
 
-        // Validate format
-        if !SUPPORTED_FORMATS.contains(&self.format.to_lowercase().as_str()) {
+        validate_options(
+            &self.format,
+            &self.language,
+            self.chain_id,
+            self.min_confidence,
+        )
+    }
+}
+
+/// Request for streaming OCR over the pages of a multi-page document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrStreamRequest {
+    /// Base64-encoded page images, in page order
+    #[serde(default)]
+    pub images: Vec<String>,
+
+    /// Image format hint (png, jpg, webp, gif)
+    #[serde(default = "default_format")]
+    pub format: String,
+
+    /// Language hint for OCR (en, zh, ja, ko), or "auto" to detect
+    #[serde(default = "default_language")]
+    pub language: String,
+
+    /// Chain ID for pricing/metering
+    #[serde(default = "default_chain_id")]
+    pub chain_id: u64,
+
+    /// Drop regions with recognition confidence below this (0.0-1.0);
+    /// unset keeps every region
+    #[serde(default)]
+    pub min_confidence: Option<f32>,
+}
+
+impl OcrStreamRequest {
+    /// Recognition language to use, or `None` to auto-detect
+    pub fn language_hint(&self) -> Option<OcrLanguage> {
+        language_hint(&self.language)
+    }
+
+    /// Validate the streaming OCR request
+    pub fn validate(&self) -> Result<(), ApiError> {
+        if self.images.is_empty() {
             return Err(ApiError::ValidationError {
-                field: "format".to_string(),
-                message: format!(
-                    "unsupported format '{}', supported: {:?}",
-                    self.format, SUPPORTED_FORMATS
-                ),
+                field: "images".to_string(),
+                message: "at least one image is required".to_string(),
             });
         }
 
-        // Validate language
-        if !SUPPORTED_LANGUAGES.contains(&self.language.to_lowercase().as_str()) {
+        if self.images.len() > MAX_OCR_PAGES {
             return Err(ApiError::ValidationError {
-                field: "language".to_string(),
+                field: "images".to_string(),
                 message: format!(
-                    "unsupported language '{}', supported: {:?}",
-                    self.language, SUPPORTED_LANGUAGES
+                    "{} images exceeds maximum of {} pages",
+                    self.images.len(),
+                    MAX_OCR_PAGES
                 ),
             });
         }
 
-        // Validate chain_id
-        if self.chain_id != 84532 && self.chain_id != 5611 {
+        for (page, image) in self.images.iter().enumerate() {
+            if image.is_empty() {
+                return Err(ApiError::ValidationError {
+                    field: "images".to_string(),
+                    message: format!("image for page {} is empty", page),
+                });
+            }
+            if image.len() > MAX_IMAGE_SIZE {
+                return Err(ApiError::ValidationError {
+                    field: "images".to_string(),
+                    message: format!(
+                        "image for page {} exceeds maximum size of {} bytes",
+                        page, MAX_IMAGE_SIZE
+                    ),
+                });
+            }
+        }
+
+        validate_options(
+            &self.format,
+            &self.language,
+            self.chain_id,
+            self.min_confidence,
+        )
+    }
+}
+
+/// Validate the options shared by OCR requests
+fn validate_options(
+    format: &str,
+    language: &str,
+    chain_id: u64,
+    min_confidence: Option<f32>,
+) -> Result<(), ApiError> {
+    // Validate format
+    if !SUPPORTED_FORMATS.contains(&format.to_lowercase().as_str()) {
+        return Err(ApiError::ValidationError {
+            field: "format".to_string(),
+            message: format!(
+                "unsupported format '{}', supported: {:?}",
+                format, SUPPORTED_FORMATS
+            ),
+        });
+    }
+
+    // Validate language
+    if !SUPPORTED_LANGUAGES.contains(&language.to_lowercase().as_str()) {
+        return Err(ApiError::ValidationError {
+            field: "language".to_string(),
+            message: format!(
+                "unsupported language '{}', supported: {:?}",
+                language, SUPPORTED_LANGUAGES
+            ),
+        });
+    }
+
+    // Validate chain_id
+    if chain_id != 84532 && chain_id != 5611 {
+        return Err(ApiError::ValidationError {
+            field: "chain_id".to_string(),
+            message: format!(
+                "chain_id must be 84532 (Base Sepolia) or 5611 (opBNB Testnet), got {}",
+                chain_id
+            ),
+        });
+    }
+
+    // Validate min_confidence
+    if let Some(min_confidence) = min_confidence {
+        if !(0.0..=1.0).contains(&min_confidence) {
             return Err(ApiError::ValidationError {
-                field: "chain_id".to_string(),
+                field: "min_confidence".to_string(),
                 message: format!(
-                    "chain_id must be 84532 (Base Sepolia) or 5611 (opBNB Testnet), got {}",
-                    self.chain_id
+                    "min_confidence must be between 0.0 and 1.0, got {}",
+                    min_confidence
                 ),
             });
         }
-
-        Ok(())
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -114,8 +240,32 @@ mod tests {
     fn test_default_values() {
         let request: OcrRequest = serde_json::from_str(r#"{"image": "dGVzdA=="}"#).unwrap();
         assert_eq!(request.format, "png");
-        assert_eq!(request.language, "en");
+        assert_eq!(request.language, "auto");
         assert_eq!(request.chain_id, 84532);
+        assert_eq!(request.language_hint(), None);
+        assert_eq!(request.min_confidence, None);
+    }
+
+    #[test]
+    fn test_validation_min_confidence() {
+        let request: OcrRequest =
+            serde_json::from_str(r#"{"image": "dGVzdA==", "minConfidence": 0.6}"#).unwrap();
+        assert_eq!(request.min_confidence, Some(0.6));
+        assert!(request.validate().is_ok());
+
+        let out_of_range = OcrRequest {
+            min_confidence: Some(1.5),
+            ..request
+        };
+        assert!(out_of_range.validate().is_err());
+    }
+
+    #[test]
+    fn test_language_hint() {
+        let request: OcrRequest =
+            serde_json::from_str(r#"{"image": "dGVzdA==", "language": "ja"}"#).unwrap();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.language_hint(), Some(OcrLanguage::Ja));
     }
 
     #[test]
@@ -125,6 +275,7 @@ mod tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
         assert!(request.validate().is_err());
     }
@@ -136,6 +287,7 @@ mod tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
         assert!(request.validate().is_err());
     }
@@ -147,6 +299,7 @@ mod tests {
             format: "bmp".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
         assert!(request.validate().is_err());
     }
@@ -158,6 +311,7 @@ mod tests {
             format: "png".to_string(),
             language: "fr".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
         assert!(request.validate().is_err());
     }
@@ -169,6 +323,7 @@ mod tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 1,
+            min_confidence: None,
         };
         assert!(request.validate().is_err());
     }
@@ -180,6 +335,7 @@ mod tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
         assert!(request.validate().is_ok());
     }
@@ -197,4 +353,29 @@ mod tests {
         assert_eq!(request.language, "zh");
         assert_eq!(request.chain_id, 5611);
     }
+
+    #[test]
+    fn test_stream_request_validation() {
+        let request: OcrStreamRequest =
+            serde_json::from_str(r#"{"images": ["dGVzdA==", "dGVzdA=="], "chainId": 5611}"#)
+                .unwrap();
+        assert_eq!(request.images.len(), 2);
+        assert_eq!(request.format, "png");
+        assert!(request.validate().is_ok());
+
+        let empty: OcrStreamRequest = serde_json::from_str(r#"{"images": []}"#).unwrap();
+        assert!(empty.validate().is_err());
+
+        let blank_page = OcrStreamRequest {
+            images: vec!["dGVzdA==".to_string(), String::new()],
+            ..request.clone()
+        };
+        assert!(blank_page.validate().is_err());
+
+        let too_many = OcrStreamRequest {
+            images: vec!["dGVzdA==".to_string(); MAX_OCR_PAGES + 1],
+            ..request
+        };
+        assert!(too_many.validate().is_err());
+    }
 }
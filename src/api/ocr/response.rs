@@ -23,6 +23,8 @@ pub struct TextRegion {
     pub confidence: f32,
     /// Bounding box location
     pub bounding_box: BoundingBox,
+    /// Detected language code (en, zh, ja, ko)
+    pub language: String,
 }
 
 /// Response from OCR processing
@@ -79,6 +81,83 @@ impl OcrResponse {
     }
 }
 
+/// One page of a streaming OCR response (`page` event)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrPageResponse {
+    /// Zero-based page index within the request's `images`
+    pub page_index: usize,
+    /// Page width in pixels
+    pub width: u32,
+    /// Page height in pixels
+    pub height: u32,
+    /// Full extracted text for the page
+    pub text: String,
+    /// Average confidence score (0.0-1.0)
+    pub confidence: f32,
+    /// Text regions with bounding boxes in this page's coordinates
+    pub regions: Vec<TextRegion>,
+    /// Processing time for the page in milliseconds
+    pub processing_time_ms: u64,
+}
+
+/// A page that failed during streaming OCR (`error` event)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrPageError {
+    pub page_index: usize,
+    pub error: String,
+}
+
+/// Final event of a streaming OCR response (`done` event)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrStreamSummary {
+    /// Pages submitted
+    pub pages: usize,
+    /// Pages that failed
+    pub failed_pages: usize,
+    /// Total processing time in milliseconds
+    pub processing_time_ms: u64,
+    /// Model used for OCR
+    pub model: String,
+    /// Provider (always "host")
+    pub provider: String,
+    /// Chain ID
+    pub chain_id: u64,
+    /// Chain name (e.g., "Base Sepolia")
+    pub chain_name: String,
+    /// Native token symbol (e.g., "ETH")
+    pub native_token: String,
+}
+
+impl OcrStreamSummary {
+    /// Create a stream summary with chain context
+    pub fn new(
+        pages: usize,
+        failed_pages: usize,
+        processing_time_ms: u64,
+        chain_id: u64,
+        model: &str,
+    ) -> Self {
+        let (chain_name, native_token) = match chain_id {
+            5611 => ("opBNB Testnet", "BNB"),
+            _ => ("Base Sepolia", "ETH"),
+        };
+
+        Self {
+            pages,
+            failed_pages,
+            processing_time_ms,
+            model: model.to_string(),
+            provider: "host".to_string(),
+            chain_id,
+            chain_name: chain_name.to_string(),
+            native_token: native_token.to_string(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,8 +209,30 @@ mod tests {
                 width: 100,
                 height: 30,
             },
+            language: "en".to_string(),
         };
         let json = serde_json::to_string(&region).unwrap();
         assert!(json.contains("\"boundingBox\""));
+        assert!(json.contains("\"language\":\"en\""));
+    }
+
+    #[test]
+    fn test_page_response_serialization() {
+        let page = OcrPageResponse {
+            page_index: 2,
+            width: 800,
+            height: 1000,
+            text: "Page three".to_string(),
+            confidence: 0.9,
+            regions: vec![],
+            processing_time_ms: 40,
+        };
+        let json = serde_json::to_string(&page).unwrap();
+        assert!(json.contains("\"pageIndex\":2"));
+        assert!(json.contains("\"height\":1000"));
+
+        let summary = OcrStreamSummary::new(3, 1, 120, 5611, "paddleocr");
+        assert_eq!(summary.chain_name, "opBNB Testnet");
+        assert_eq!(summary.failed_pages, 1);
     }
 }
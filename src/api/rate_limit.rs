@@ -0,0 +1,359 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Per-client rate limiting for the HTTP API
+//!
+//! Token buckets (as in `search::rate_limiter`) are kept per client: the API
+//! key when the request carries one the node knows (or the `ApiIdentity` a
+//! verifier gave it), otherwise the client's IP address. Unknown keys are
+//! ignored so a client cannot escape its address's limit by inventing keys.
+//! Endpoints listed in `RateLimitConfig::endpoints` get their own buckets and
+//! limits; all other endpoints share one bucket at
+//! `ApiConfig::rate_limit_per_minute`. Keys in `RateLimitConfig::api_keys`
+//! use their own limit on every endpoint.
+
+use axum::http::HeaderMap;
+use governor::clock::{Clock, DefaultClock};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU32;
+
+use super::ApiError;
+
+pub const API_RATE_LIMIT_ENDPOINTS_ENV: &str = "API_RATE_LIMIT_ENDPOINTS";
+pub const API_RATE_LIMIT_KEYS_ENV: &str = "API_RATE_LIMIT_KEYS";
+pub const API_RATE_LIMIT_EXEMPT_ENV: &str = "API_RATE_LIMIT_EXEMPT";
+
+/// Header carrying the client's API key (`Authorization: Bearer` also works)
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Buckets kept before idle ones are dropped
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Bucket scope of endpoints without their own limit
+const DEFAULT_SCOPE: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests_per_minute: u32,
+    /// Requests allowed back to back before the per-minute rate applies
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn per_minute(requests_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute,
+            burst: requests_per_minute,
+        }
+    }
+
+    /// Parse `rpm` or `rpm:burst`
+    fn parse(value: &str) -> Option<Self> {
+        let (rpm, burst) = match value.split_once(':') {
+            Some((rpm, burst)) => (rpm.trim().parse().ok()?, burst.trim().parse().ok()?),
+            None => {
+                let rpm = value.trim().parse().ok()?;
+                (rpm, rpm)
+            }
+        };
+        (rpm > 0 && burst > 0).then_some(Self {
+            requests_per_minute: rpm,
+            burst,
+        })
+    }
+
+    fn quota(&self) -> Quota {
+        let rpm = NonZeroU32::new(self.requests_per_minute).unwrap_or(NonZeroU32::MIN);
+        let burst = NonZeroU32::new(self.burst).unwrap_or(rpm);
+        Quota::per_minute(rpm).allow_burst(burst)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    /// Limits by path prefix, e.g. `/v1/inference`; the longest match wins
+    pub endpoints: HashMap<String, RateLimit>,
+    /// Limits for trusted API keys or verifier client ids, replacing the
+    /// endpoint and default limits
+    pub api_keys: HashMap<String, RateLimit>,
+    /// Path prefixes that are never limited
+    pub exempt_paths: Vec<String>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: HashMap::new(),
+            api_keys: HashMap::new(),
+            exempt_paths: vec!["/health".to_string()],
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// `API_RATE_LIMIT_ENDPOINTS` and `API_RATE_LIMIT_KEYS` are comma-separated
+    /// `path=limit` and `key=limit` pairs, where a limit is `rpm` or
+    /// `rpm:burst`. `API_RATE_LIMIT_EXEMPT` replaces the exempt path list.
+    pub fn from_env() -> Self {
+        let limits = |name: &str| -> HashMap<String, RateLimit> {
+            std::env::var(name)
+                .map(|v| {
+                    v.split(',')
+                        .filter_map(|pair| {
+                            let (target, limit) = pair.split_once('=')?;
+                            Some((target.trim().to_string(), RateLimit::parse(limit)?))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let exempt_paths = match std::env::var(API_RATE_LIMIT_EXEMPT_ENV) {
+            Ok(v) => v
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Err(_) => Self::default().exempt_paths,
+        };
+
+        Self {
+            endpoints: limits(API_RATE_LIMIT_ENDPOINTS_ENV),
+            api_keys: limits(API_RATE_LIMIT_KEYS_ENV),
+            exempt_paths,
+        }
+    }
+}
+
+/// Who a request is counted against
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitKey {
+    ApiKey(String),
+    Client(String),
+}
+
+/// Whether `path` is `prefix` or lies under it. Matching stops at `/`
+/// boundaries, so `/health` covers `/health/ready` but not `/healthz`.
+pub fn path_has_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.is_empty(),
+        None => false,
+    }
+}
+
+/// API key sent with a request, if any
+pub fn request_api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get(API_KEY_HEADER) {
+        return key.to_str().ok();
+    }
+    headers
+        .get(axum::http::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+pub struct HttpRateLimiter {
+    config: RateLimitConfig,
+    known_keys: HashSet<String>,
+    default: DefaultKeyedRateLimiter<RateLimitKey>,
+    endpoints: HashMap<String, DefaultKeyedRateLimiter<RateLimitKey>>,
+    /// Buckets of trusted keys, keyed by scope
+    api_keys: HashMap<String, DefaultKeyedRateLimiter<String>>,
+}
+
+impl HttpRateLimiter {
+    /// `default_limit` applies to endpoints without their own limit;
+    /// `api_keys` are the keys identifying clients besides those with
+    /// overrides
+    pub fn new(default_limit: RateLimit, config: RateLimitConfig, api_keys: &[String]) -> Self {
+        let known_keys = api_keys
+            .iter()
+            .chain(config.api_keys.keys())
+            .cloned()
+            .collect();
+        Self {
+            default: RateLimiter::keyed(default_limit.quota()),
+            endpoints: config
+                .endpoints
+                .iter()
+                .map(|(path, limit)| (path.clone(), RateLimiter::keyed(limit.quota())))
+                .collect(),
+            api_keys: config
+                .api_keys
+                .iter()
+                .map(|(key, limit)| (key.clone(), RateLimiter::keyed(limit.quota())))
+                .collect(),
+            known_keys,
+            config,
+        }
+    }
+
+    /// The key a request is limited by: its API key if the node knows it,
+    /// otherwise `client` (normally the IP address)
+    pub fn key_for(&self, api_key: Option<&str>, client: impl Into<String>) -> RateLimitKey {
+        match api_key {
+            Some(key) if self.known_keys.contains(key) => RateLimitKey::ApiKey(key.to_string()),
+            _ => RateLimitKey::Client(client.into()),
+        }
+    }
+
+    fn is_exempt(&self, path: &str) -> bool {
+        self.config
+            .exempt_paths
+            .iter()
+            .any(|prefix| path_has_prefix(path, prefix))
+    }
+
+    /// Configured endpoint with the longest prefix of `path`
+    fn endpoint(&self, path: &str) -> Option<&str> {
+        self.config
+            .endpoints
+            .keys()
+            .filter(|prefix| path_has_prefix(path, prefix))
+            .max_by_key(|prefix| prefix.len())
+            .map(String::as_str)
+    }
+
+    /// Count a request to `path`, failing with `RateLimitExceeded` (and the
+    /// seconds until the next request would pass) when over the limit
+    pub fn check(&self, path: &str, key: &RateLimitKey) -> Result<(), ApiError> {
+        if self.is_exempt(path) {
+            return Ok(());
+        }
+
+        let endpoint = self.endpoint(path);
+        let trusted = match key {
+            RateLimitKey::ApiKey(api_key) => self.api_keys.get(api_key),
+            RateLimitKey::Client(_) => None,
+        };
+        let outcome = match (trusted, endpoint) {
+            (Some(limiter), _) => limiter.check_key(&endpoint.unwrap_or(DEFAULT_SCOPE).to_string()),
+            (None, Some(endpoint)) => {
+                let limiter = &self.endpoints[endpoint];
+                prune(limiter);
+                limiter.check_key(key)
+            }
+            (None, None) => {
+                prune(&self.default);
+                self.default.check_key(key)
+            }
+        };
+
+        outcome.map_err(|not_until| {
+            let wait = not_until.wait_time_from(DefaultClock::default().now());
+            ApiError::RateLimitExceeded {
+                retry_after: wait.as_secs_f64().ceil().max(1.0) as u64,
+            }
+        })
+    }
+}
+
+/// Drop buckets that have refilled once too many clients are tracked
+fn prune<K: std::hash::Hash + Eq + Clone>(limiter: &DefaultKeyedRateLimiter<K>) {
+    if limiter.len() > MAX_TRACKED_CLIENTS {
+        limiter.retain_recent();
+        limiter.shrink_to_fit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(ip: &str) -> RateLimitKey {
+        RateLimitKey::Client(ip.to_string())
+    }
+
+    fn limiter(config: RateLimitConfig) -> HttpRateLimiter {
+        HttpRateLimiter::new(RateLimit::per_minute(2), config, &[])
+    }
+
+    fn retry_after(result: Result<(), ApiError>) -> u64 {
+        match result {
+            Err(ApiError::RateLimitExceeded { retry_after }) => retry_after,
+            other => panic!("expected rate limit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_limits_each_client_separately() {
+        let limiter = limiter(RateLimitConfig::default());
+        assert!(limiter.check("/v1/models", &client("10.0.0.1")).is_ok());
+        assert!(limiter.check("/v1/inference", &client("10.0.0.1")).is_ok());
+        let wait = retry_after(limiter.check("/v1/models", &client("10.0.0.1")));
+        assert!((1..=30).contains(&wait));
+
+        assert!(limiter.check("/v1/models", &client("10.0.0.2")).is_ok());
+        // Health checks are never limited
+        for _ in 0..10 {
+            assert!(limiter.check("/health/ready", &client("10.0.0.1")).is_ok());
+        }
+        // ...but paths that merely share the prefix are
+        assert!(limiter.check("/healthz", &client("10.0.0.2")).is_ok());
+        assert!(limiter.check("/healthz", &client("10.0.0.2")).is_err());
+    }
+
+    #[test]
+    fn test_endpoint_limits_use_their_own_buckets() {
+        let limiter = limiter(RateLimitConfig {
+            endpoints: HashMap::from([("/v1/inference".to_string(), RateLimit::per_minute(1))]),
+            ..Default::default()
+        });
+        let ip = client("10.0.0.1");
+        assert!(limiter.check("/v1/inference", &ip).is_ok());
+        assert!(limiter.check("/v1/inference", &ip).is_err());
+        // The default bucket is untouched
+        assert!(limiter.check("/v1/embed", &ip).is_ok());
+    }
+
+    #[test]
+    fn test_trusted_api_keys_get_their_own_limit() {
+        let limiter = HttpRateLimiter::new(
+            RateLimit::per_minute(1),
+            RateLimitConfig {
+                api_keys: HashMap::from([("trusted".to_string(), RateLimit::per_minute(100))]),
+                ..Default::default()
+            },
+            &["regular".to_string()],
+        );
+
+        let trusted = limiter.key_for(Some("trusted"), "10.0.0.1");
+        for _ in 0..50 {
+            assert!(limiter.check("/v1/inference", &trusted).is_ok());
+        }
+
+        let regular = limiter.key_for(Some("regular"), "10.0.0.1");
+        assert_eq!(regular, RateLimitKey::ApiKey("regular".to_string()));
+        assert!(limiter.check("/v1/inference", &regular).is_ok());
+        assert!(limiter.check("/v1/inference", &regular).is_err());
+
+        // Unknown keys are limited by address
+        assert_eq!(
+            limiter.key_for(Some("made-up"), "10.0.0.1"),
+            client("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_parse_limits_and_api_key_headers() {
+        assert_eq!(RateLimit::parse("30"), Some(RateLimit::per_minute(30)));
+        assert_eq!(
+            RateLimit::parse(" 30:5 "),
+            Some(RateLimit {
+                requests_per_minute: 30,
+                burst: 5
+            })
+        );
+        assert_eq!(RateLimit::parse("0"), None);
+        assert_eq!(RateLimit::parse("fast"), None);
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_api_key(&headers), None);
+        headers.insert("authorization", "Bearer abc".parse().unwrap());
+        assert_eq!(request_api_key(&headers), Some("abc"));
+        headers.insert(API_KEY_HEADER, "xyz".parse().unwrap());
+        assert_eq!(request_api_key(&headers), Some("xyz"));
+    }
+}
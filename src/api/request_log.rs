@@ -0,0 +1,314 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Structured request logs
+//!
+//! Each HTTP request is logged when it completes as one JSON object with its
+//! method, path, status, latency, model and token counts. JSON bodies up to
+//! `max_body_bytes` are included field by field, every top-level field
+//! redacted, hashed or logged as is according to its `FieldPolicy`. Fields
+//! are redacted unless configured otherwise, since prompts and completions
+//! may contain personal data. Hashes are unsalted, so they only link equal
+//! values; short values can still be guessed.
+
+use axum::body::{Body, HttpBody};
+use axum::http::{header, HeaderMap};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub const API_REQUEST_LOG_ENV: &str = "API_REQUEST_LOG";
+pub const API_REQUEST_LOG_FIELDS_ENV: &str = "API_REQUEST_LOG_FIELDS";
+
+/// `tracing` target of request log events
+pub const REQUEST_LOG_TARGET: &str = "api::request_log";
+
+const REDACTED: &str = "[redacted]";
+
+/// How a body field appears in the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldPolicy {
+    Redact,
+    /// Replaced by a short SHA-256 of its JSON text
+    Hash,
+    Log,
+}
+
+impl FromStr for FieldPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "redact" => Ok(FieldPolicy::Redact),
+            "hash" => Ok(FieldPolicy::Hash),
+            "log" => Ok(FieldPolicy::Log),
+            other => Err(format!(
+                "Unknown field policy '{}': use redact, hash or log",
+                other
+            )),
+        }
+    }
+}
+
+impl FieldPolicy {
+    fn apply(self, value: &Value) -> Value {
+        match self {
+            FieldPolicy::Redact => Value::String(REDACTED.to_string()),
+            FieldPolicy::Hash => {
+                let digest = Sha256::digest(value.to_string().as_bytes());
+                Value::String(format!("sha256:{}", hex::encode(&digest[..8])))
+            }
+            FieldPolicy::Log => value.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLogConfig {
+    pub enabled: bool,
+    /// Policy of body fields without their own in `fields`
+    pub default_policy: FieldPolicy,
+    /// Policies by top-level body field name, e.g. `max_tokens`
+    pub fields: HashMap<String, FieldPolicy>,
+    /// Larger bodies, and bodies that are not JSON, are left out
+    pub max_body_bytes: usize,
+}
+
+impl Default for RequestLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_policy: FieldPolicy::Redact,
+            fields: HashMap::new(),
+            max_body_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl RequestLogConfig {
+    /// `API_REQUEST_LOG=false` turns logging off. `API_REQUEST_LOG_FIELDS` is
+    /// a comma-separated list of `field=policy` pairs, where `*` sets the
+    /// default policy, e.g. `*=hash,max_tokens=log`.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(v) = std::env::var(API_REQUEST_LOG_ENV) {
+            config.enabled = !(v == "0" || v.eq_ignore_ascii_case("false"));
+        }
+        if let Ok(v) = std::env::var(API_REQUEST_LOG_FIELDS_ENV) {
+            for (field, policy) in v.split(',').filter_map(|pair| {
+                let (field, policy) = pair.split_once('=')?;
+                Some((field.trim(), policy.parse().ok()?))
+            }) {
+                if field == "*" {
+                    config.default_policy = policy;
+                } else {
+                    config.fields.insert(field.to_string(), policy);
+                }
+            }
+        }
+        config
+    }
+
+    pub fn policy(&self, field: &str) -> FieldPolicy {
+        self.fields
+            .get(field)
+            .copied()
+            .unwrap_or(self.default_policy)
+    }
+
+    /// `body` with each top-level field passed through its policy
+    pub fn redact(&self, body: &Value) -> Value {
+        match body {
+            Value::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), self.policy(name).apply(value)))
+                    .collect(),
+            ),
+            other => self.default_policy.apply(other),
+        }
+    }
+
+    /// Buffer `body` if `headers` mark it as JSON and its known size is
+    /// within `max_body_bytes`. Returns the body to pass on, unchanged when
+    /// it was not captured, or the error if reading it failed.
+    pub async fn capture(
+        &self,
+        headers: &HeaderMap,
+        body: Body,
+    ) -> Result<(Body, Option<Value>), axum::Error> {
+        let is_json = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("application/json"));
+        let small = body
+            .size_hint()
+            .exact()
+            .is_some_and(|size| size <= self.max_body_bytes as u64);
+        if !is_json || !small {
+            return Ok((body, None));
+        }
+
+        let bytes = axum::body::to_bytes(body, self.max_body_bytes).await?;
+        let value = serde_json::from_slice(&bytes).ok();
+        Ok((Body::from(bytes), value))
+    }
+}
+
+/// A completed request
+#[derive(Debug, Clone)]
+pub struct RequestLogEntry {
+    pub request_id: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub latency: Duration,
+    pub request_body: Option<Value>,
+    pub response_body: Option<Value>,
+}
+
+impl RequestLogEntry {
+    /// The log record, with bodies redacted by `config`
+    pub fn to_json(&self, config: &RequestLogConfig) -> Value {
+        let mut record = Map::new();
+        if let Some(request_id) = &self.request_id {
+            record.insert("request_id".to_string(), request_id.as_str().into());
+        }
+        record.insert("method".to_string(), self.method.as_str().into());
+        record.insert("path".to_string(), self.path.as_str().into());
+        record.insert("status".to_string(), self.status.into());
+        record.insert(
+            "latency_ms".to_string(),
+            (self.latency.as_millis() as u64).into(),
+        );
+
+        let model = [self.request_body.as_ref(), self.response_body.as_ref()]
+            .into_iter()
+            .flatten()
+            .find_map(|body| body.get("model")?.as_str());
+        if let Some(model) = model {
+            record.insert("model".to_string(), model.into());
+        }
+
+        if let Some(response) = &self.response_body {
+            let usage = response.get("usage");
+            for field in ["prompt_tokens", "completion_tokens", "total_tokens"] {
+                if let Some(count) = usage.and_then(|usage| usage.get(field)) {
+                    record.insert(field.to_string(), count.clone());
+                }
+            }
+            if let Some(tokens_used) = response.get("tokens_used") {
+                record.insert("tokens_used".to_string(), tokens_used.clone());
+            }
+        }
+
+        if let Some(body) = &self.request_body {
+            record.insert("request".to_string(), config.redact(body));
+        }
+        if let Some(body) = &self.response_body {
+            record.insert("response".to_string(), config.redact(body));
+        }
+        Value::Object(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry() -> RequestLogEntry {
+        RequestLogEntry {
+            request_id: Some("req-1".to_string()),
+            method: "POST".to_string(),
+            path: "/v1/inference".to_string(),
+            status: 200,
+            latency: Duration::from_millis(420),
+            request_body: Some(json!({
+                "model": "llama-7b",
+                "prompt": "My card number is 4111 1111 1111 1111",
+                "max_tokens": 50
+            })),
+            response_body: Some(json!({
+                "model": "llama-7b",
+                "content": "I can't store that.",
+                "tokens_used": 6,
+                "usage": {"prompt_tokens": 12, "completion_tokens": 6, "total_tokens": 18}
+            })),
+        }
+    }
+
+    #[test]
+    fn test_bodies_are_redacted_by_default() {
+        let record = entry().to_json(&RequestLogConfig::default());
+
+        assert_eq!(record["model"], "llama-7b");
+        assert_eq!(record["status"], 200);
+        assert_eq!(record["latency_ms"], 420);
+        assert_eq!(record["prompt_tokens"], 12);
+        assert_eq!(record["total_tokens"], 18);
+        assert_eq!(record["request"]["prompt"], REDACTED);
+        assert_eq!(record["response"]["content"], REDACTED);
+        assert!(!record.to_string().contains("4111"));
+    }
+
+    #[test]
+    fn test_field_policies_can_hash_or_log() {
+        let config = RequestLogConfig {
+            default_policy: FieldPolicy::Hash,
+            fields: HashMap::from([("max_tokens".to_string(), FieldPolicy::Log)]),
+            ..Default::default()
+        };
+        let record = entry().to_json(&config);
+
+        assert_eq!(record["request"]["max_tokens"], 50);
+        let hashed = record["request"]["prompt"].as_str().unwrap();
+        assert!(hashed.starts_with("sha256:"));
+        assert_eq!(hashed.len(), "sha256:".len() + 16);
+        // Equal values hash alike, so requests can be correlated
+        assert_eq!(
+            config.redact(&json!({"prompt": "My card number is 4111 1111 1111 1111"}))["prompt"],
+            hashed
+        );
+    }
+
+    #[test]
+    fn test_parse_field_policy() {
+        assert_eq!("Hash".parse(), Ok(FieldPolicy::Hash));
+        assert_eq!(" log ".parse(), Ok(FieldPolicy::Log));
+        assert!("plain".parse::<FieldPolicy>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_capture_skips_large_and_non_json_bodies() {
+        let config = RequestLogConfig {
+            max_body_bytes: 16,
+            ..Default::default()
+        };
+        let mut json_headers = HeaderMap::new();
+        json_headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+
+        let (body, value) = config
+            .capture(&json_headers, Body::from(r#"{"a":1}"#))
+            .await
+            .unwrap();
+        assert_eq!(value, Some(json!({"a": 1})));
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], br#"{"a":1}"#);
+
+        let (_, value) = config
+            .capture(
+                &json_headers,
+                Body::from(r#"{"prompt":"longer than sixteen"}"#),
+            )
+            .await
+            .unwrap();
+        assert_eq!(value, None);
+        let (_, value) = config
+            .capture(&HeaderMap::new(), Body::from("{}"))
+            .await
+            .unwrap();
+        assert_eq!(value, None);
+    }
+}
@@ -176,6 +176,9 @@ mod tests {
             search_queries_count: None,
             search_provider: None,
             usage: None,
+            logprobs: None,
+            tool_calls: None,
+            system_fingerprint: None,
         };
 
         let formatted = formatter.format_inference_response(response);
@@ -195,6 +198,9 @@ mod tests {
             chain_id: None,
             chain_name: None,
             native_token: None,
+            logprobs: None,
+            tool_calls: None,
+            system_fingerprint: None,
         };
 
         let formatted = formatter.format_streaming_response(response);
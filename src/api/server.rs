@@ -1,12 +1,12 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
-use anyhow::Result;
+use anyhow::{bail, Result};
 use axum::{
     extract::{
         ws::{WebSocket, WebSocketUpgrade},
-        DefaultBodyLimit, Json, Path, State,
+        ConnectInfo, DefaultBodyLimit, Json, Path, State,
     },
-    http::StatusCode,
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
     Router,
@@ -16,34 +16,99 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot, Mutex, RwLock};
-use tower_http::cors::CorsLayer;
-use tracing::{debug, error, info, warn};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+use tracing::{debug, error, info, warn, Instrument};
 
-use super::handlers::{HealthResponse, ModelInfo, ModelsResponse};
+use super::auth::{default_public_paths, ApiIdentity, ApiKeyAuth, ApiKeyVerifier};
+use super::handlers::{HealthResponse, ModelDetailsResponse, ModelInfo, ModelsResponse};
 use super::pool::{ConnectionPool, ConnectionStats, PoolConfig};
+use super::rate_limit::{
+    request_api_key, HttpRateLimiter, RateLimit, RateLimitConfig, RateLimitKey, API_KEY_HEADER,
+};
+use super::request_log::{RequestLogConfig, RequestLogEntry, REQUEST_LOG_TARGET};
 use super::{ApiError, InferenceRequest, InferenceResponse, StreamingResponse, UsageInfo};
+use crate::api::metrics::{
+    record_cache_metrics, record_engine_metrics, record_gpu_metrics, record_payment_stats,
+    PROMETHEUS_CONTENT_TYPE,
+};
 use crate::api::token_tracker::TokenTracker;
+use crate::api::websocket::messages::{is_cancel_message, message_index_of};
+use crate::cache::PromptCache;
 use crate::contracts::checkpoint_manager::CheckpointManager;
+use crate::contracts::Web3Client;
 use crate::crypto::SessionKeyStore;
-use crate::inference::LlmEngine;
+use crate::inference::{LateContext, LlmEngine, TokenLogprobs, TOOL_CALLS_FINISH_REASON};
+use crate::monitoring::telemetry::{REQUEST_ID_HEADER, TRACEPARENT_HEADER};
+use crate::monitoring::{
+    ComponentHealth, HealthStatus, LivenessProbe, MetricsRegistry, PrometheusExporter,
+    ReadinessProbe, TraceContext,
+};
 use crate::p2p::Node;
-use crate::utils::context::{build_prompt_with_context, count_context_tokens};
+use crate::payments::PaymentTracker;
+use crate::performance::GpuManager;
+use crate::storage::enhanced_s5_client::EnhancedS5Client;
+use crate::utils::context::{build_prompt_with_template, count_context_tokens, late_context_frame};
 use sha2::{Digest, Sha256};
 
+/// Reject logit_bias token IDs that fall outside the target model's vocabulary
+fn validate_logit_bias_vocab(
+    engine: &LlmEngine,
+    model_id: &str,
+    request: &InferenceRequest,
+) -> Result<(), ApiError> {
+    if request.logit_bias.is_empty() {
+        return Ok(());
+    }
+    if let Some(n_vocab) = engine.vocab_size(model_id) {
+        if let Some(token_id) = request.logit_bias.keys().find(|&&id| id >= n_vocab) {
+            return Err(ApiError::ValidationError {
+                field: "logit_bias".to_string(),
+                message: format!(
+                    "Token id {} is outside the model vocabulary (size {})",
+                    token_id, n_vocab
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
 // TODO: Implement full HTTP server using axum framework
 // See tests/client/ for expected functionality
 
+pub const API_CORS_ORIGINS_ENV: &str = "API_CORS_ORIGINS";
+pub const API_CORS_METHODS_ENV: &str = "API_CORS_METHODS";
+pub const API_CORS_HEADERS_ENV: &str = "API_CORS_HEADERS";
+pub const API_CORS_ALLOW_CREDENTIALS_ENV: &str = "API_CORS_ALLOW_CREDENTIALS";
+
+/// How long browsers may cache a preflight response
+const CORS_MAX_AGE: Duration = Duration::from_secs(600);
+
 #[derive(Debug, Clone)]
 pub struct ApiConfig {
     pub listen_addr: String,
     pub max_connections: usize,
     pub max_connections_per_ip: usize,
     pub request_timeout: Duration,
+    /// Origins (`scheme://host[:port]`) browsers may call the API from; `*`
+    /// allows any origin, but not together with credentials
     pub cors_allowed_origins: Vec<String>,
+    /// `*` allows any method
+    pub cors_allowed_methods: Vec<String>,
+    /// Request headers browsers may send; `*` allows any
+    pub cors_allowed_headers: Vec<String>,
+    /// Let browsers send cookies and `Authorization` cross-origin
+    pub cors_allow_credentials: bool,
     pub enable_websocket: bool,
+    /// Reject requests outside `public_paths` without an accepted API key
     pub require_api_key: bool,
     pub api_keys: Vec<String>,
+    /// Path prefixes open without a key, `/health` and `/metrics` by default
+    pub public_paths: Vec<String>,
+    /// Per-client limit for endpoints without their own in `rate_limits`
     pub rate_limit_per_minute: usize,
+    pub rate_limits: RateLimitConfig,
+    pub request_log: RequestLogConfig,
     pub enable_http2: bool,
     pub enable_auto_retry: bool,
     pub max_retries: usize,
@@ -70,10 +135,16 @@ impl Default for ApiConfig {
             max_connections_per_ip: 10,
             request_timeout: Duration::from_secs(30),
             cors_allowed_origins: vec!["*".to_string()],
+            cors_allowed_methods: vec!["GET".to_string(), "POST".to_string()],
+            cors_allowed_headers: default_cors_headers(),
+            cors_allow_credentials: false,
             enable_websocket: false,
             require_api_key: false,
             api_keys: Vec::new(),
+            public_paths: default_public_paths(),
             rate_limit_per_minute: 60,
+            rate_limits: RateLimitConfig::default(),
+            request_log: RequestLogConfig::default(),
             enable_http2: false,
             enable_auto_retry: false,
             max_retries: 3,
@@ -94,35 +165,135 @@ impl Default for ApiConfig {
     }
 }
 
-struct RateLimiter {
-    requests: Arc<RwLock<HashMap<String, Vec<Instant>>>>,
-    limit: usize,
+fn default_cors_headers() -> Vec<String> {
+    [
+        header::CONTENT_TYPE.as_str(),
+        header::AUTHORIZATION.as_str(),
+        API_KEY_HEADER,
+        REQUEST_ID_HEADER,
+        TRACEPARENT_HEADER,
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
 }
 
-impl RateLimiter {
-    fn new(limit: usize) -> Self {
-        Self {
-            requests: Arc::new(RwLock::new(HashMap::new())),
-            limit,
+/// An allowlisted origin in the form browsers send: `scheme://host[:port]`
+fn cors_origin(origin: &str) -> Result<HeaderValue> {
+    let origin = origin.trim().trim_end_matches('/').to_ascii_lowercase();
+    let valid = origin.split_once("://").is_some_and(|(scheme, host)| {
+        !scheme.is_empty() && !host.is_empty() && !host.contains('/')
+    });
+    if !valid {
+        bail!(
+            "Invalid CORS origin '{}': expected scheme://host[:port]",
+            origin
+        );
+    }
+    Ok(HeaderValue::from_str(&origin)?)
+}
+
+impl ApiConfig {
+    /// Override the CORS settings from `API_CORS_ORIGINS`, `API_CORS_METHODS`
+    /// and `API_CORS_HEADERS` (comma-separated) and
+    /// `API_CORS_ALLOW_CREDENTIALS`
+    pub fn with_cors_from_env(mut self) -> Self {
+        let list = |name: &str| -> Option<Vec<String>> {
+            std::env::var(name).ok().map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+        };
+        if let Some(origins) = list(API_CORS_ORIGINS_ENV) {
+            self.cors_allowed_origins = origins;
+        }
+        if let Some(methods) = list(API_CORS_METHODS_ENV) {
+            self.cors_allowed_methods = methods;
         }
+        if let Some(headers) = list(API_CORS_HEADERS_ENV) {
+            self.cors_allowed_headers = headers;
+        }
+        if let Ok(v) = std::env::var(API_CORS_ALLOW_CREDENTIALS_ENV) {
+            self.cors_allow_credentials = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        self
     }
 
-    async fn check_rate_limit(&self, key: &str) -> Result<(), ApiError> {
-        let now = Instant::now();
-        let one_minute_ago = now - Duration::from_secs(60);
+    /// CORS policy from the `cors_*` settings. Requests from origins not on
+    /// the list get no `Access-Control-Allow-Origin` header, so browsers
+    /// block them; preflight `OPTIONS` requests are answered here.
+    fn cors_layer(&self) -> Result<CorsLayer> {
+        let wildcard = |values: &[String]| values.iter().any(|v| v.trim() == "*");
+        let any_origin = wildcard(&self.cors_allowed_origins);
+        let any_method = wildcard(&self.cors_allowed_methods);
+        let any_header = wildcard(&self.cors_allowed_headers);
+        if self.cors_allow_credentials && (any_origin || any_method || any_header) {
+            bail!(
+                "CORS credentials cannot be combined with `*`; \
+                 list origins, methods and headers explicitly"
+            );
+        }
 
-        let mut requests = self.requests.write().await;
-        let entry = requests.entry(key.to_string()).or_insert_with(Vec::new);
+        let origins = if any_origin {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(
+                self.cors_allowed_origins
+                    .iter()
+                    .map(String::as_str)
+                    .map(cors_origin)
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        };
+        let methods = if any_method {
+            AllowMethods::any()
+        } else {
+            AllowMethods::list(
+                self.cors_allowed_methods
+                    .iter()
+                    .map(|method| Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes()))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        };
+        let headers = if any_header {
+            AllowHeaders::any()
+        } else {
+            AllowHeaders::list(
+                self.cors_allowed_headers
+                    .iter()
+                    .map(|name| HeaderName::from_bytes(name.trim().as_bytes()))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        };
 
-        // Remove old requests
-        entry.retain(|&t| t > one_minute_ago);
+        Ok(CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .allow_credentials(self.cors_allow_credentials)
+            .expose_headers([
+                header::RETRY_AFTER,
+                HeaderName::from_static(REQUEST_ID_HEADER),
+                HeaderName::from_static(TRACEPARENT_HEADER),
+            ])
+            .max_age(CORS_MAX_AGE))
+    }
 
-        if entry.len() >= self.limit {
-            return Err(ApiError::RateLimitExceeded { retry_after: 60 });
-        }
+    fn rate_limiter(&self) -> HttpRateLimiter {
+        let default_limit =
+            RateLimit::per_minute(self.rate_limit_per_minute.min(u32::MAX as usize) as u32);
+        HttpRateLimiter::new(default_limit, self.rate_limits.clone(), &self.api_keys)
+    }
 
-        entry.push(now);
-        Ok(())
+    fn auth(&self) -> ApiKeyAuth {
+        ApiKeyAuth::new(
+            self.require_api_key,
+            &self.api_keys,
+            self.public_paths.clone(),
+        )
     }
 }
 
@@ -179,7 +350,9 @@ pub struct ApiServer {
     node: Arc<RwLock<Option<Node>>>,
     engine: Arc<RwLock<Option<Arc<LlmEngine>>>>,
     default_model_id: Arc<RwLock<String>>,
-    rate_limiter: Arc<RateLimiter>,
+    cors: CorsLayer,
+    auth: Arc<ApiKeyAuth>,
+    rate_limiter: Arc<HttpRateLimiter>,
     circuit_breaker: Arc<CircuitBreaker>,
     connection_pool: Arc<ConnectionPool>,
     active_connections: Arc<RwLock<HashMap<String, usize>>>,
@@ -190,12 +363,28 @@ pub struct ApiServer {
     node_private_key: Option<[u8; 32]>,
     embedding_model_manager: Arc<RwLock<Option<Arc<crate::embeddings::EmbeddingModelManager>>>>,
     vision_model_manager: Arc<RwLock<Option<Arc<crate::vision::VisionModelManager>>>>,
+    /// Cross-encoder for searchVectors reranking; results keep vector order without one
+    reranker: Arc<RwLock<Option<Arc<dyn crate::rag::Reranker>>>>,
     search_service: Arc<RwLock<Option<Arc<crate::search::SearchService>>>>,
     diffusion_client: Arc<RwLock<Option<Arc<crate::diffusion::DiffusionClient>>>>,
     image_gen_tracker: Arc<crate::diffusion::billing::ImageGenerationTracker>,
     image_gen_rate_limiter: Arc<crate::diffusion::ImageGenerationRateLimiter>,
+    /// Queued jobs polled via GET /v1/images/jobs/:job_id
+    image_gen_jobs: Arc<crate::api::generate_image::ImageGenerationJobs>,
     auto_image_routing: bool,
     session_store: Arc<RwLock<crate::api::websocket::session_store::SessionStore>>,
+    /// Caches verification keys for /v1/verify-proof
+    key_manager: Arc<crate::crypto::ezkl::KeyManager>,
+    /// Series rendered by /metrics
+    metrics_registry: Arc<MetricsRegistry>,
+    prompt_cache: Arc<RwLock<Option<Arc<PromptCache>>>>,
+    gpu_manager: Arc<RwLock<Option<Arc<GpuManager>>>>,
+    /// Payment trackers keyed by chain id
+    payment_trackers: Arc<RwLock<HashMap<u64, Arc<PaymentTracker>>>>,
+    /// Dependencies probed by /health/ready when attached
+    web3_client: Arc<RwLock<Option<Arc<Web3Client>>>>,
+    s5_client: Arc<RwLock<Option<Arc<EnhancedS5Client>>>>,
+    started_at: Instant,
     shutdown_tx: Option<oneshot::Sender<()>>,
     listener: Option<tokio::net::TcpListener>,
 }
@@ -217,6 +406,11 @@ impl ApiServer {
     pub fn new_for_test() -> Self {
         let config = ApiConfig::default();
         let addr = "127.0.0.1:0".parse().unwrap();
+        let cors = config
+            .cors_layer()
+            .expect("default CORS settings are valid");
+        let auth = Arc::new(config.auth());
+        let rate_limiter = Arc::new(config.rate_limiter());
 
         let session_store_config =
             crate::api::websocket::session_store::SessionStoreConfig::default();
@@ -230,7 +424,9 @@ impl ApiServer {
             node: Arc::new(RwLock::new(None)),
             engine: Arc::new(RwLock::new(None)),
             default_model_id: Arc::new(RwLock::new("test-model".to_string())),
-            rate_limiter: Arc::new(RateLimiter::new(100)),
+            cors,
+            auth,
+            rate_limiter,
             circuit_breaker: Arc::new(CircuitBreaker::new(5, Duration::from_secs(60))),
             connection_pool: Arc::new(ConnectionPool::new_for_test(PoolConfig::default())),
             active_connections: Arc::new(RwLock::new(HashMap::new())),
@@ -245,12 +441,22 @@ impl ApiServer {
             node_private_key: None,
             embedding_model_manager: Arc::new(RwLock::new(None)),
             vision_model_manager: Arc::new(RwLock::new(None)),
+            reranker: Arc::new(RwLock::new(None)),
             search_service: Arc::new(RwLock::new(None)),
             diffusion_client: Arc::new(RwLock::new(None)),
             image_gen_tracker: Arc::new(crate::diffusion::billing::ImageGenerationTracker::new()),
             image_gen_rate_limiter: Arc::new(crate::diffusion::ImageGenerationRateLimiter::new(10)),
+            image_gen_jobs: Arc::new(crate::api::generate_image::ImageGenerationJobs::new()),
             auto_image_routing: false,
             session_store,
+            key_manager: Arc::new(crate::crypto::ezkl::KeyManager::new()),
+            metrics_registry: Arc::new(MetricsRegistry::new()),
+            prompt_cache: Arc::new(RwLock::new(None)),
+            gpu_manager: Arc::new(RwLock::new(None)),
+            payment_trackers: Arc::new(RwLock::new(HashMap::new())),
+            web3_client: Arc::new(RwLock::new(None)),
+            s5_client: Arc::new(RwLock::new(None)),
+            started_at: Instant::now(),
             shutdown_tx: None,
             listener: None,
         }
@@ -268,6 +474,7 @@ impl ApiServer {
 
         // Parse the address
         let addr: SocketAddr = config.listen_addr.parse()?;
+        let cors = config.cors_layer()?;
 
         // Bind to the address
         let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -306,7 +513,8 @@ impl ApiServer {
             enable_persistence: false,
         };
         let session_store = Arc::new(RwLock::new(
-            crate::api::websocket::session_store::SessionStore::new(session_store_config),
+            crate::api::websocket::session_store::SessionStore::new(session_store_config)
+                .with_rag_memory_limits(crate::rag::RagMemoryLimits::from_env()),
         ));
 
         let mut server = Self {
@@ -314,7 +522,9 @@ impl ApiServer {
             node: Arc::new(RwLock::new(None)),
             engine: Arc::new(RwLock::new(None)),
             default_model_id: Arc::new(RwLock::new("tiny-vicuna".to_string())),
-            rate_limiter: Arc::new(RateLimiter::new(config.rate_limit_per_minute)),
+            cors,
+            auth: Arc::new(config.auth()),
+            rate_limiter: Arc::new(config.rate_limiter()),
             circuit_breaker: Arc::new(CircuitBreaker::new(
                 config.circuit_breaker_threshold,
                 config.circuit_breaker_timeout,
@@ -328,6 +538,7 @@ impl ApiServer {
             node_private_key,
             embedding_model_manager: Arc::new(RwLock::new(None)),
             vision_model_manager: Arc::new(RwLock::new(None)),
+            reranker: Arc::new(RwLock::new(None)),
             search_service: Arc::new(RwLock::new(None)),
             diffusion_client: Arc::new(RwLock::new(None)),
             image_gen_tracker: Arc::new(crate::diffusion::billing::ImageGenerationTracker::new()),
@@ -337,6 +548,7 @@ impl ApiServer {
                     .and_then(|v| v.parse().ok())
                     .unwrap_or(5),
             )),
+            image_gen_jobs: Arc::new(crate::api::generate_image::ImageGenerationJobs::new()),
             auto_image_routing: match std::env::var("AUTO_IMAGE_ROUTING") {
                 Ok(v) => v == "true",
                 Err(_) => std::env::var("DIFFUSION_ENDPOINT")
@@ -344,6 +556,14 @@ impl ApiServer {
                     .unwrap_or(false),
             },
             session_store,
+            key_manager: Arc::new(crate::crypto::ezkl::KeyManager::from_env()),
+            metrics_registry: Arc::new(MetricsRegistry::new()),
+            prompt_cache: Arc::new(RwLock::new(None)),
+            gpu_manager: Arc::new(RwLock::new(None)),
+            payment_trackers: Arc::new(RwLock::new(HashMap::new())),
+            web3_client: Arc::new(RwLock::new(None)),
+            s5_client: Arc::new(RwLock::new(None)),
+            started_at: Instant::now(),
             shutdown_tx: None,
             listener: Some(listener),
             config,
@@ -367,8 +587,9 @@ impl ApiServer {
             let server = self.clone_for_http();
 
             tokio::spawn(async move {
-                let app = Self::create_router(server);
-
+                // Connection addresses key the per-client rate limits
+                let app =
+                    Self::create_router(server).into_make_service_with_connect_info::<SocketAddr>();
                 let serve_future = axum::serve(listener, app).with_graceful_shutdown(async move {
                     let _ = shutdown_rx.await;
                 });
@@ -385,6 +606,8 @@ impl ApiServer {
             node: self.node.clone(),
             engine: self.engine.clone(),
             default_model_id: self.default_model_id.clone(),
+            cors: self.cors.clone(),
+            auth: self.auth.clone(),
             rate_limiter: self.rate_limiter.clone(),
             circuit_breaker: self.circuit_breaker.clone(),
             connection_pool: self.connection_pool.clone(),
@@ -396,12 +619,22 @@ impl ApiServer {
             node_private_key: self.node_private_key,
             embedding_model_manager: self.embedding_model_manager.clone(),
             vision_model_manager: self.vision_model_manager.clone(),
+            reranker: self.reranker.clone(),
             search_service: self.search_service.clone(),
             diffusion_client: self.diffusion_client.clone(),
             image_gen_tracker: self.image_gen_tracker.clone(),
             image_gen_rate_limiter: self.image_gen_rate_limiter.clone(),
+            image_gen_jobs: self.image_gen_jobs.clone(),
             auto_image_routing: self.auto_image_routing,
             session_store: self.session_store.clone(),
+            key_manager: self.key_manager.clone(),
+            metrics_registry: self.metrics_registry.clone(),
+            prompt_cache: self.prompt_cache.clone(),
+            gpu_manager: self.gpu_manager.clone(),
+            payment_trackers: self.payment_trackers.clone(),
+            web3_client: self.web3_client.clone(),
+            s5_client: self.s5_client.clone(),
+            started_at: self.started_at,
             shutdown_tx: None,
             listener: None,
         })
@@ -415,10 +648,46 @@ impl ApiServer {
         *self.engine.write().await = Some(engine);
     }
 
+    pub async fn get_engine(&self) -> Option<Arc<LlmEngine>> {
+        self.engine.read().await.clone()
+    }
+
     pub async fn set_default_model_id(&self, model_id: String) {
+        if let Some(engine) = self.engine.read().await.as_ref() {
+            engine.set_default_model(model_id.clone()).await;
+        }
         *self.default_model_id.write().await = model_id;
     }
 
+    /// Routes a request's `model` field to a model id, lazily loading it if it
+    /// is registered but not yet in memory. Empty or legacy "tiny-vicuna"
+    /// names and unknown models fall back to the default model.
+    async fn resolve_model_id(
+        &self,
+        engine: &LlmEngine,
+        requested: &str,
+    ) -> Result<String, ApiError> {
+        let model_id = if requested.is_empty()
+            || requested == "tiny-vicuna"
+            || !engine.is_model_known(requested).await
+        {
+            self.default_model_id.read().await.clone()
+        } else {
+            requested.to_string()
+        };
+        // The default may have been evicted to make room for another model,
+        // so it is reloaded like any registered one
+        if !engine.is_model_known(&model_id).await {
+            return Ok(model_id);
+        }
+
+        engine.ensure_model_loaded(&model_id).await.map_err(|e| {
+            error!("Failed to load model {}: {}", model_id, e);
+            ApiError::ServiceUnavailable(format!("model {} could not be loaded", model_id))
+        })?;
+        Ok(model_id)
+    }
+
     pub async fn set_checkpoint_manager(&self, checkpoint_manager: Arc<CheckpointManager>) {
         *self.checkpoint_manager.write().await = Some(checkpoint_manager);
     }
@@ -448,6 +717,16 @@ impl ApiServer {
         self.vision_model_manager.read().await.clone()
     }
 
+    /// Accept API keys beyond `ApiConfig::api_keys`, e.g. looked up in a
+    /// customer database
+    pub fn set_api_key_verifier(&self, verifier: Arc<dyn ApiKeyVerifier>) {
+        self.auth.set_verifier(verifier);
+    }
+
+    pub async fn set_reranker(&self, reranker: Arc<dyn crate::rag::Reranker>) {
+        *self.reranker.write().await = Some(reranker);
+    }
+
     /// Set the search service for web search functionality (v8.7.0+)
     pub async fn set_search_service(&self, service: Arc<crate::search::SearchService>) {
         *self.search_service.write().await = Some(service);
@@ -458,6 +737,11 @@ impl ApiServer {
         self.search_service.read().await.clone()
     }
 
+    /// Get the key manager used to load verification keys
+    pub fn key_manager(&self) -> Arc<crate::crypto::ezkl::KeyManager> {
+        self.key_manager.clone()
+    }
+
     /// Set the diffusion client for image generation (v8.16.0+)
     pub async fn set_diffusion_client(&self, client: Arc<crate::diffusion::DiffusionClient>) {
         *self.diffusion_client.write().await = Some(client);
@@ -468,16 +752,131 @@ impl ApiServer {
         self.diffusion_client.read().await.clone()
     }
 
+    /// Attach the prompt cache whose hit rates /metrics reports
+    pub async fn set_prompt_cache(&self, cache: Arc<PromptCache>) {
+        *self.prompt_cache.write().await = Some(cache);
+    }
+
+    /// Attach the GPU manager whose devices /metrics reports
+    pub async fn set_gpu_manager(&self, manager: Arc<GpuManager>) {
+        *self.gpu_manager.write().await = Some(manager);
+    }
+
+    /// Report a chain's payment stats on /metrics, labeled by `chain_id`
+    pub async fn add_payment_tracker(&self, chain_id: u64, tracker: Arc<PaymentTracker>) {
+        self.payment_trackers
+            .write()
+            .await
+            .insert(chain_id, tracker);
+    }
+
+    /// Probe this chain's RPC endpoint for readiness
+    pub async fn set_web3_client(&self, client: Arc<Web3Client>) {
+        *self.web3_client.write().await = Some(client);
+    }
+
+    /// Probe this S5 bridge for readiness
+    pub async fn set_s5_client(&self, client: Arc<EnhancedS5Client>) {
+        *self.s5_client.write().await = Some(client);
+    }
+
+    /// Registry behind /metrics, for subsystems that publish their own series
+    pub fn metrics_registry(&self) -> Arc<MetricsRegistry> {
+        self.metrics_registry.clone()
+    }
+
+    /// Sample every attached subsystem and render Prometheus text format.
+    /// A failing source is logged and skipped so the rest still export.
+    pub async fn render_metrics(&self) -> Result<String> {
+        let registry = &self.metrics_registry;
+
+        if let Some(engine) = self.get_engine().await {
+            let loaded_models = engine.list_loaded_models().await;
+            record_engine_metrics(registry, &engine.get_metrics().await, &loaded_models).await;
+        }
+
+        if let Some(cache) = self.prompt_cache.read().await.clone() {
+            match cache.get_metrics().await {
+                Ok(metrics) => record_cache_metrics(registry, &metrics).await,
+                Err(e) => warn!("Failed to collect prompt cache metrics: {}", e),
+            }
+        }
+
+        if let Some(gpu_manager) = self.gpu_manager.read().await.clone() {
+            match gpu_manager.discover_gpus().await {
+                Ok(devices) => {
+                    let mut gpus = Vec::with_capacity(devices.len());
+                    for device in devices {
+                        match gpu_manager.get_gpu_metrics(device.device_id).await {
+                            Ok(metrics) => gpus.push((device.device_id, metrics)),
+                            Err(e) => warn!(
+                                "Failed to collect metrics for GPU {}: {}",
+                                device.device_id, e
+                            ),
+                        }
+                    }
+                    record_gpu_metrics(registry, &gpus).await;
+                }
+                Err(e) => warn!("Failed to discover GPUs for metrics: {}", e),
+            }
+        }
+
+        let trackers: Vec<(u64, Arc<PaymentTracker>)> = self
+            .payment_trackers
+            .read()
+            .await
+            .iter()
+            .map(|(chain_id, tracker)| (*chain_id, tracker.clone()))
+            .collect();
+        for (chain_id, tracker) in trackers {
+            match tracker.get_payment_stats().await {
+                Ok(stats) => record_payment_stats(registry, chain_id, &stats).await,
+                Err(e) => warn!(
+                    "Failed to collect payment stats for chain {}: {}",
+                    chain_id, e
+                ),
+            }
+        }
+
+        registry.export(&PrometheusExporter::new()).await
+    }
+
     /// Get the image generation rate limiter (v8.16.0+)
     pub fn image_gen_rate_limiter(&self) -> &crate::diffusion::ImageGenerationRateLimiter {
         &self.image_gen_rate_limiter
     }
 
+    /// Get the store of queued image generation jobs
+    pub fn image_gen_jobs(&self) -> Arc<crate::api::generate_image::ImageGenerationJobs> {
+        self.image_gen_jobs.clone()
+    }
+
     /// Get the image generation billing tracker (v8.16.0+)
     pub fn image_gen_tracker(&self) -> &crate::diffusion::billing::ImageGenerationTracker {
         &self.image_gen_tracker
     }
 
+    /// Bill an image generation to a job
+    ///
+    /// Records the units with the image generation tracker, and registers
+    /// their token-equivalents with the checkpoint manager so image jobs go
+    /// through the same proof submission and settlement as text jobs.
+    pub async fn track_image_generation(&self, job_id: u64, session_id: Option<&str>, units: f64) {
+        self.image_gen_tracker
+            .track(job_id, session_id, units)
+            .await;
+
+        if let Some(cm) = self.get_checkpoint_manager().await {
+            let image_tokens = crate::diffusion::billing::units_to_tokens(units);
+            if let Err(e) = cm
+                .track_tokens(job_id, image_tokens, session_id.map(|s| s.to_string()))
+                .await
+            {
+                warn!("Image gen token tracking failed for job {}: {}", job_id, e);
+            }
+        }
+    }
+
     /// Get the session key store for encryption/decryption operations
     pub fn get_session_key_store(&self) -> Arc<SessionKeyStore> {
         self.session_key_store.clone()
@@ -513,18 +912,10 @@ impl ApiServer {
     pub async fn handle_inference_request(
         &self,
         request: InferenceRequest,
-        client_ip: String,
     ) -> Result<InferenceResponse, ApiError> {
-        // Validate request
+        // Validate request (HTTP requests are rate limited before they get here)
         request.validate()?;
 
-        // Check rate limit
-        if self.config.require_api_key {
-            // Rate limit by API key if available
-        } else {
-            self.rate_limiter.check_rate_limit(&client_ip).await?;
-        }
-
         // Check circuit breaker
         if self.config.enable_circuit_breaker && self.circuit_breaker.is_open().await {
             return Err(ApiError::CircuitBreakerOpen);
@@ -536,19 +927,9 @@ impl ApiServer {
             ApiError::ServiceUnavailable("inference engine not initialized".to_string())
         })?;
 
-        // Use default model ID if model field is "tiny-vicuna" or similar
-        let model_id = if request.model == "tiny-vicuna" || request.model.is_empty() {
-            self.default_model_id.read().await.clone()
-        } else {
-            // Check if this specific model ID is loaded
-            let loaded_models = engine.list_loaded_models().await;
-            if loaded_models.contains(&request.model) {
-                request.model.clone()
-            } else {
-                // Fall back to default
-                self.default_model_id.read().await.clone()
-            }
-        };
+        let model_id = self.resolve_model_id(engine, &request.model).await?;
+
+        validate_logit_bias_vocab(engine, &model_id, &request)?;
 
         // Web search integration (v8.7.0+)
         let mut search_metadata: Option<(bool, u32, String)> = None;
@@ -633,11 +1014,17 @@ impl ApiServer {
         } else {
             request.prompt.clone()
         };
-        let full_prompt = build_prompt_with_context(
+        let full_prompt = build_prompt_with_template(
             &request.conversation_context,
             &prompt_with_search,
             request.thinking.as_deref(),
-        );
+            request.chat_template_override().as_ref(),
+            &request.tools,
+        )
+        .map_err(|e| ApiError::ValidationError {
+            field: "chat_template".to_string(),
+            message: e.to_string(),
+        })?;
 
         if !request.conversation_context.is_empty() {
             info!(
@@ -693,23 +1080,60 @@ impl ApiServer {
             frequency_penalty: freq_pen,
             presence_penalty: pres_pen,
             min_p: 0.0,
-            seed: None,
-            stop_sequences: vec![],
+            seed: request.seed,
+            stop_sequences: request.stop_sequences(),
+            logit_bias: request.logit_bias.clone(),
+            response_format: request.response_format.clone(),
+            chat_template: request.chat_template_override(),
+            logprobs: request.logprobs,
+            timeout_ms: request.timeout_ms,
             stream: false,
             cancel_flag: None,
             token_sender: None,
             result_sender: None,
+            late_context: None,
         };
 
         // Run inference with real model
-        let result = engine.run_inference(engine_request).await.map_err(|e| {
-            let msg = format!("{}", e);
-            if msg.contains("exceeds context window") {
-                ApiError::InvalidRequest(msg)
-            } else {
-                ApiError::InternalError(format!("Inference failed: {}", e))
+        let result = match engine.run_inference(engine_request).await {
+            Ok(result) => result,
+            Err(e) => {
+                // Timed out: bill the partial generation before reporting the timeout
+                if let Some(crate::inference::InferenceError::Timeout { partial, .. }) =
+                    e.downcast_ref::<crate::inference::InferenceError>()
+                {
+                    warn!("{}", e);
+                    if let Some(jid) = job_id {
+                        let tokens = partial.tokens_generated;
+                        if let Some(cm) = self.checkpoint_manager.read().await.as_ref() {
+                            if let Err(e) = cm
+                                .track_tokens(jid, tokens as u64, request.session_id.clone())
+                                .await
+                            {
+                                warn!("Token tracking failed for job {}: {}", jid, e);
+                            }
+                        } else {
+                            self.token_tracker
+                                .track_tokens(Some(jid), tokens, request.session_id.clone())
+                                .await;
+                        }
+                    }
+                    return Err(ApiError::Timeout);
+                }
+
+                let msg = format!("{}", e);
+                return Err(
+                    if msg.contains("exceeds context window")
+                        || msg.contains("logit_bias")
+                        || msg.contains("response_format")
+                    {
+                        ApiError::InvalidRequest(msg)
+                    } else {
+                        ApiError::InternalError(format!("Inference failed: {}", e))
+                    },
+                );
             }
-        })?;
+        };
 
         // Convert to API response (include search metadata if search was performed)
         let (web_search_performed, search_queries_count, search_provider) =
@@ -719,11 +1143,23 @@ impl ApiServer {
                 (None, None, None)
             };
 
+        // A response calling one of the offered tools returns the calls
+        // instead of their JSON as text
+        let (content, finish_reason, tool_calls) =
+            match crate::inference::tools::parse_tool_calls(&result.text, &request.tools) {
+                Some(parsed) => (
+                    parsed.content,
+                    TOOL_CALLS_FINISH_REASON.to_string(),
+                    Some(parsed.tool_calls),
+                ),
+                None => (result.text.clone(), result.finish_reason, None),
+            };
+
         let response = InferenceResponse {
             model: request.model.clone(),
-            content: result.text.clone(),
+            content,
             tokens_used: result.tokens_generated as u32,
-            finish_reason: result.finish_reason,
+            finish_reason,
             request_id: request
                 .request_id
                 .clone()
@@ -740,6 +1176,9 @@ impl ApiServer {
                 total_tokens: cu.total_tokens as u32,
                 context_window_size: cu.context_window_size as u32,
             }),
+            logprobs: result.logprobs,
+            tool_calls,
+            system_fingerprint: result.system_fingerprint,
         };
 
         // Phase 4: Store response hash for proof binding (non-streaming path - v8.10.0+)
@@ -802,7 +1241,8 @@ impl ApiServer {
     > {
         // Validate and check limits (same as non-streaming)
         request.validate()?;
-        self.rate_limiter.check_rate_limit(&client_ip).await?;
+        self.rate_limiter
+            .check(WS_INFERENCE_SCOPE, &RateLimitKey::Client(client_ip))?;
 
         if self.config.enable_circuit_breaker && self.circuit_breaker.is_open().await {
             return Err(ApiError::CircuitBreakerOpen);
@@ -814,23 +1254,16 @@ impl ApiServer {
             ApiError::ServiceUnavailable("inference engine not initialized".to_string())
         })?;
 
-        // Use default model ID if model field is "tiny-vicuna" or similar
-        let model_id = if request.model == "tiny-vicuna" || request.model.is_empty() {
-            self.default_model_id.read().await.clone()
-        } else {
-            // Check if this specific model ID is loaded
-            let loaded_models = engine.list_loaded_models().await;
-            if loaded_models.contains(&request.model) {
-                request.model.clone()
-            } else {
-                // Fall back to default
-                self.default_model_id.read().await.clone()
-            }
-        };
+        let model_id = self.resolve_model_id(engine, &request.model).await?;
+
+        validate_logit_bias_vocab(engine, &model_id, &request)?;
 
         // Web search integration for streaming (v8.7.5+)
         // Auto-detect search intent from prompt if not explicitly requested (v8.7.8+)
         let mut search_context = String::new();
+        // Content for lower-ranked results that is still being fetched when
+        // generation starts; the engine appends it only while the answer is short
+        let mut late_context = None;
         let should_search =
             request.web_search || crate::search::query_extractor::needs_web_search(&request.prompt);
 
@@ -865,22 +1298,38 @@ impl ApiServer {
                     // Log the actual search query for debugging
                     debug!("🔍 Search queries (cleaned): {:?}", queries_to_search);
 
-                    // Perform searches with content fetching (Phase 9)
+                    // Perform searches, waiting only for each top result's
+                    // content so tokens start streaming sooner
                     let mut all_results = Vec::new();
                     let mut content_fetched_count = 0usize;
+                    let (late_tx, late_rx) = mpsc::unbounded_channel();
 
                     for query in &queries_to_search {
-                        // Use search_with_content to fetch actual page content
-                        match search_service.search_with_content(query, Some(5)).await {
-                            Ok(result) => {
-                                content_fetched_count += result.content_fetched_count;
-                                all_results.extend(result.results);
+                        match search_service
+                            .search_with_progressive_content(query, Some(5))
+                            .await
+                        {
+                            Ok(search) => {
+                                content_fetched_count += search.response.content_fetched_count;
+                                // Late results keep the numbering of the initial context
+                                let offset = all_results.len();
+                                all_results.extend(search.response.results);
+
+                                super::streaming::forward_late_results(
+                                    search.remaining,
+                                    offset,
+                                    late_tx.clone(),
+                                );
                             }
                             Err(e) => {
                                 warn!("Search failed for streaming query '{}': {}", query, e);
                             }
                         }
                     }
+                    late_context = Some(LateContext::new(
+                        late_rx,
+                        late_context_frame(request.chat_template_override().as_ref()),
+                    ));
 
                     if !all_results.is_empty() {
                         // Format search results with content (Phase 9)
@@ -911,11 +1360,17 @@ impl ApiServer {
         } else {
             request.prompt.clone()
         };
-        let full_prompt = build_prompt_with_context(
+        let full_prompt = build_prompt_with_template(
             &request.conversation_context,
             &prompt_with_search,
             request.thinking.as_deref(),
-        );
+            request.chat_template_override().as_ref(),
+            &request.tools,
+        )
+        .map_err(|e| ApiError::ValidationError {
+            field: "chat_template".to_string(),
+            message: e.to_string(),
+        })?;
 
         if !request.conversation_context.is_empty() {
             info!(
@@ -980,12 +1435,18 @@ impl ApiServer {
             frequency_penalty: freq_pen,
             presence_penalty: pres_pen,
             min_p: 0.0,
-            seed: None,
-            stop_sequences: vec![],
+            seed: request.seed,
+            stop_sequences: request.stop_sequences(),
+            logit_bias: request.logit_bias.clone(),
+            response_format: request.response_format.clone(),
+            chat_template: request.chat_template_override(),
+            logprobs: request.logprobs,
+            timeout_ms: request.timeout_ms,
             stream: true, // Enable streaming!
             cancel_flag,
             token_sender: None,
             result_sender: None,
+            late_context,
         };
 
         // Run streaming inference with real model
@@ -997,6 +1458,7 @@ impl ApiServer {
                     error!("Failed to start streaming inference: {}", e);
                     ApiError::InternalError(format!("Streaming inference failed: {}", e))
                 })?;
+        let system_fingerprint = engine.system_fingerprint(&model_id).await;
 
         let (tx, rx) = mpsc::channel(100);
 
@@ -1007,121 +1469,156 @@ impl ApiServer {
 
         let session_id = request.session_id.clone();
         let token_tracker = self.token_tracker.clone();
+        // Tokens billed so far, reported back if the session cancels
+        let streamed_tokens = match session_id {
+            Some(ref sid) => {
+                self.session_store
+                    .read()
+                    .await
+                    .inference_token_counter(sid)
+                    .await
+            }
+            None => None,
+        };
 
         // Spawn task to convert token stream to streaming responses
-        tokio::spawn(async move {
-            use futures::StreamExt;
-            futures::pin_mut!(token_stream);
-
-            let mut accumulated_text = String::new();
-            let mut total_tokens = 0;
-            let mut got_any_tokens = false;
-
-            while let Some(token_result) = token_stream.next().await {
-                match token_result {
-                    Ok(token_info) => {
-                        got_any_tokens = true;
-                        accumulated_text.push_str(&token_info.text);
-                        total_tokens += 1;
-
-                        // Skip empty tokens except for the first one
-                        if token_info.text.is_empty() && total_tokens > 1 {
-                            continue;
-                        }
+        tokio::spawn(
+            async move {
+                use futures::StreamExt;
+                futures::pin_mut!(token_stream);
+
+                let mut accumulated_text = String::new();
+                let mut total_tokens = 0;
+                let mut got_any_tokens = false;
+
+                while let Some(token_result) = token_stream.next().await {
+                    match token_result {
+                        Ok(token_info) => {
+                            got_any_tokens = true;
+                            accumulated_text.push_str(&token_info.text);
+                            total_tokens += 1;
+
+                            // Skip empty tokens except for the first one
+                            if token_info.text.is_empty() && total_tokens > 1 {
+                                continue;
+                            }
 
-                        // Track tokens for checkpoint submission (silent - logs only on checkpoint trigger)
-                        // Phase 4: Also append token to response buffer for hash computation (v8.10.0+)
-                        if let Some(jid) = job_id {
-                            if let Some(cm) = checkpoint_manager.as_ref() {
-                                // Append token to response buffer for proof binding
-                                cm.append_response(jid, &token_info.text).await;
-                                let _ = cm.track_tokens(jid, 1, session_id.clone()).await;
-                            } else {
-                                token_tracker
-                                    .track_tokens(Some(jid), 1, session_id.clone())
-                                    .await;
+                            // Track tokens for checkpoint submission (silent - logs only on checkpoint trigger)
+                            // Phase 4: Also append token to response buffer for hash computation (v8.10.0+)
+                            if let Some(jid) = job_id {
+                                if let Some(cm) = checkpoint_manager.as_ref() {
+                                    // Append token to response buffer for proof binding
+                                    cm.append_response(jid, &token_info.text).await;
+                                    let _ = cm.track_tokens(jid, 1, session_id.clone()).await;
+                                } else {
+                                    token_tracker
+                                        .track_tokens(Some(jid), 1, session_id.clone())
+                                        .await;
+                                }
+                            }
+                            if let Some(ref counter) = streamed_tokens {
+                                counter.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
                             }
-                        }
 
-                        let response = StreamingResponse {
-                            content: token_info.text.clone(),
-                            tokens: 1,
-                            finish_reason: None,
-                            chain_id: request.chain_id,
-                            chain_name: None,
-                            native_token: None,
-                        };
+                            let response = StreamingResponse {
+                                content: token_info.text.clone(),
+                                tokens: 1,
+                                finish_reason: None,
+                                chain_id: request.chain_id,
+                                chain_name: None,
+                                native_token: None,
+                                logprobs: TokenLogprobs::from_token_info(&token_info),
+                                tool_calls: None,
+                                system_fingerprint: None,
+                            };
 
-                        if tx.send(response).await.is_err() {
+                            if tx.send(response).await.is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            error!("Token stream error: {}", e);
+                            // Send error message to client
+                            let error_response = StreamingResponse {
+                                content: format!("Error: {}", e),
+                                tokens: 0,
+                                finish_reason: Some("error".to_string()),
+                                chain_id: request.chain_id,
+                                chain_name: None,
+                                native_token: None,
+                                logprobs: None,
+                                tool_calls: None,
+                                system_fingerprint: None,
+                            };
+                            let _ = tx.send(error_response).await;
                             break;
                         }
                     }
-                    Err(e) => {
-                        error!("Token stream error: {}", e);
-                        // Send error message to client
-                        let error_response = StreamingResponse {
-                            content: format!("Error: {}", e),
-                            tokens: 0,
-                            finish_reason: Some("error".to_string()),
-                            chain_id: request.chain_id,
-                            chain_name: None,
-                            native_token: None,
-                        };
-                        let _ = tx.send(error_response).await;
-                        break;
-                    }
                 }
-            }
 
-            // Log completion
-            if !got_any_tokens {
-                error!("Stream completed with no tokens generated");
-            } else if let Some(jid) = job_id {
-                eprintln!(
-                    "✅ Streaming job {} completed: {} tokens",
-                    jid, total_tokens
-                );
-            }
+                // Log completion
+                if !got_any_tokens {
+                    error!("Stream completed with no tokens generated");
+                } else if let Some(jid) = job_id {
+                    eprintln!(
+                        "✅ Streaming job {} completed: {} tokens",
+                        jid, total_tokens
+                    );
+                }
 
-            // Try to submit checkpoint if we have enough tokens
-            // BUT DON'T CLEANUP - the session might continue!
-            if let Some(jid) = job_id {
-                if let Some(cm) = checkpoint_manager.as_ref() {
-                    // Phase 4: Finalize response hash before checkpoint (v8.10.0+)
-                    let _ = cm.finalize_response_hash(jid).await;
-                    let _ = cm.force_checkpoint(jid).await;
-                    // DON'T cleanup here - session continues across multiple prompts!
-                    // Cleanup should only happen when websocket disconnects
-                } else {
-                    token_tracker.force_checkpoint(jid).await;
-                    // DON'T cleanup here either
+                // Try to submit checkpoint if we have enough tokens
+                // BUT DON'T CLEANUP - the session might continue!
+                if let Some(jid) = job_id {
+                    if let Some(cm) = checkpoint_manager.as_ref() {
+                        // Phase 4: Finalize response hash before checkpoint (v8.10.0+)
+                        let _ = cm.finalize_response_hash(jid).await;
+                        let _ = cm.force_checkpoint(jid).await;
+                        // DON'T cleanup here - session continues across multiple prompts!
+                        // Cleanup should only happen when websocket disconnects
+                    } else {
+                        token_tracker.force_checkpoint(jid).await;
+                        // DON'T cleanup here either
+                    }
                 }
-            }
 
-            // Phase 4.2: Track assistant response for checkpoint publishing (v8.11.0)
-            if let Some(ref session_id) = session_id {
-                if let Some(cm) = checkpoint_manager.as_ref() {
-                    cm.track_conversation_message(
-                        session_id,
-                        "assistant",
-                        &accumulated_text,
-                        false,
-                    )
-                    .await;
+                // Phase 4.2: Track assistant response for checkpoint publishing (v8.11.0)
+                if let Some(ref session_id) = session_id {
+                    if let Some(cm) = checkpoint_manager.as_ref() {
+                        cm.track_conversation_message(
+                            session_id,
+                            "assistant",
+                            &accumulated_text,
+                            false,
+                        )
+                        .await;
+                    }
                 }
-            }
 
-            // Send final message with finish reason
-            let final_response = StreamingResponse {
-                content: String::new(),
-                tokens: 0,
-                finish_reason: Some("stop".to_string()),
-                chain_id: request.chain_id,
-                chain_name: None,
-                native_token: None,
-            };
-            let _ = tx.send(final_response).await;
-        });
+                // Send final message with finish reason. The call's JSON has
+                // already streamed as text; the final chunk carries it parsed.
+                let tool_calls =
+                    crate::inference::tools::parse_tool_calls(&accumulated_text, &request.tools)
+                        .map(|parsed| parsed.tool_calls);
+                let finish_reason = if tool_calls.is_some() {
+                    TOOL_CALLS_FINISH_REASON
+                } else {
+                    "stop"
+                };
+                let final_response = StreamingResponse {
+                    content: String::new(),
+                    tokens: 0,
+                    finish_reason: Some(finish_reason.to_string()),
+                    chain_id: request.chain_id,
+                    chain_name: None,
+                    native_token: None,
+                    logprobs: None,
+                    tool_calls,
+                    system_fingerprint,
+                };
+                let _ = tx.send(final_response).await;
+            }
+            .in_current_span(),
+        );
 
         // Record success
         if self.config.enable_circuit_breaker {
@@ -1154,6 +1651,33 @@ impl ApiServer {
         })
     }
 
+    /// GGUF metadata for a loaded model; `"default"` resolves to the default model id
+    pub async fn get_model_details(
+        &self,
+        model_id: &str,
+    ) -> Result<ModelDetailsResponse, ApiError> {
+        let engine = self.get_engine().await.ok_or_else(|| {
+            ApiError::ServiceUnavailable("inference engine not available".to_string())
+        })?;
+
+        let model_id = if model_id == "default" {
+            self.default_model_id.read().await.clone()
+        } else {
+            model_id.to_string()
+        };
+
+        match engine.model_metadata(&model_id).await {
+            Some(metadata) => Ok(ModelDetailsResponse {
+                id: model_id,
+                metadata,
+            }),
+            None => Err(ApiError::ModelNotFound {
+                model: model_id,
+                available_models: engine.list_loaded_models().await,
+            }),
+        }
+    }
+
     pub async fn health_check(&self) -> HealthResponse {
         let mut issues = Vec::new();
 
@@ -1168,6 +1692,14 @@ impl ApiServer {
             issues.push("Circuit breaker is open".to_string());
         }
 
+        let s5_client = self.s5_client.read().await.clone();
+        let s5_circuit_breaker = s5_client
+            .as_ref()
+            .map(|client| client.circuit_breaker_status());
+        if s5_client.is_some_and(|client| !client.is_available()) {
+            issues.push("S5 circuit breaker is open".to_string());
+        }
+
         let status = if issues.is_empty() {
             "healthy"
         } else if issues.len() == 1 {
@@ -1183,6 +1715,74 @@ impl ApiServer {
             } else {
                 Some(issues)
             },
+            s5_circuit_breaker,
+        }
+    }
+
+    /// Whether the process is up and serving requests, regardless of
+    /// whether it can do useful work yet
+    pub fn liveness_check(&self) -> LivenessProbe {
+        LivenessProbe {
+            is_alive: true,
+            uptime_seconds: self.started_at.elapsed().as_secs(),
+            status: HealthStatus::Healthy,
+        }
+    }
+
+    /// Whether the node should receive traffic: a model must be loaded, and
+    /// the chain RPC and S5 bridge must respond when they are attached
+    pub async fn readiness_check(&self) -> ReadinessProbe {
+        let engine = self.engine.read().await.clone();
+        let web3_client = self.web3_client.read().await.clone();
+        let s5_client = self.s5_client.read().await.clone();
+
+        let model = probe_dependency("model", async {
+            let engine = engine.ok_or_else(|| anyhow::anyhow!("Inference engine not started"))?;
+            let models = engine.list_loaded_models().await;
+            if models.is_empty() {
+                return Err(anyhow::anyhow!("No model loaded yet"));
+            }
+            Ok(format!("Loaded: {}", models.join(", ")))
+        });
+        let chain_rpc = async {
+            let client = web3_client?;
+            Some(
+                probe_dependency("chain_rpc", async move {
+                    let block = client.get_block_number().await?;
+                    Ok(format!("Latest block {}", block))
+                })
+                .await,
+            )
+        };
+        let s5 = async {
+            let client = s5_client?;
+            Some(
+                probe_dependency("s5", async move {
+                    let health = client.bridge_health_check().await?;
+                    if !health.connected {
+                        return Err(anyhow::anyhow!("Bridge not connected to the S5 network"));
+                    }
+                    Ok(format!("Bridge connected to {} peers", health.peer_count))
+                })
+                .await,
+            )
+        };
+        let (model, chain_rpc, s5) = tokio::join!(model, chain_rpc, s5);
+
+        let dependencies: HashMap<String, ComponentHealth> = std::iter::once(model)
+            .chain(chain_rpc)
+            .chain(s5)
+            .map(|health| (health.name.clone(), health))
+            .collect();
+        let components_ready: HashMap<String, bool> = dependencies
+            .iter()
+            .map(|(name, health)| (name.clone(), health.status == HealthStatus::Healthy))
+            .collect();
+
+        ReadinessProbe {
+            is_ready: components_ready.values().all(|&ready| ready),
+            components_ready,
+            dependencies,
         }
     }
 
@@ -1193,32 +1793,250 @@ impl ApiServer {
         // Vision routes need higher body limit for large images
         let vision_routes = Router::new()
             .route("/ocr", post(ocr_handler_wrapper))
+            .route("/ocr/stream", post(ocr_stream_handler_wrapper))
             .route("/describe-image", post(describe_image_handler_wrapper))
             .layer(DefaultBodyLimit::max(Self::VISION_BODY_LIMIT))
             .with_state(server.clone());
 
         Router::new()
             .route("/health", get(health_handler))
+            .route("/health/live", get(liveness_handler))
+            .route("/health/ready", get(readiness_handler))
             .route("/v1/version", get(version_handler))
             .route("/v1/models", get(models_handler))
+            .route("/v1/models/:model_id", get(model_details_handler))
             .route("/v1/checkpoints/:session_id", get(checkpoints_handler))
             .route("/v1/inference", post(simple_inference_handler))
             .route("/v1/embed", post(embed_handler_wrapper))
             .route("/v1/search", post(search_handler_wrapper))
+            .route("/v1/tokenize", post(tokenize_handler_wrapper))
+            .route("/v1/detokenize", post(detokenize_handler_wrapper))
+            .route("/v1/verify-proof", post(verify_proof_handler_wrapper))
             .route("/v1/images/generate", post(generate_image_handler_wrapper))
+            .route("/v1/images/jobs", post(submit_image_job_handler_wrapper))
+            .route(
+                "/v1/images/jobs/:job_id",
+                get(image_job_status_handler_wrapper),
+            )
             .nest("/v1", vision_routes)
             .route("/v1/ws", get(websocket_handler))
             .route("/metrics", get(metrics_handler))
-            .layer(CorsLayer::permissive())
+            .layer(axum::middleware::from_fn_with_state(
+                server.clone(),
+                rate_limit_request,
+            ))
+            .layer(axum::middleware::from_fn_with_state(
+                server.clone(),
+                authenticate_request,
+            ))
+            .layer(axum::middleware::from_fn_with_state(
+                server.clone(),
+                log_request,
+            ))
+            .layer(axum::middleware::from_fn(trace_request))
+            .layer(server.cors.clone())
             .with_state(server)
     }
 }
 
+/// Run each request inside a span seeded from its trace headers and echo
+/// the ids back so callers can find the trace
+async fn trace_request(
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let trace = TraceContext::from_headers(request.headers());
+    let span = trace.span("http_request");
+    info!(
+        parent: &span,
+        method = %request.method(),
+        path = %request.uri().path(),
+        "Request received"
+    );
+    request.extensions_mut().insert(trace.clone());
+
+    let mut response = next.run(request).instrument(span).await;
+    let headers = response.headers_mut();
+    if let Ok(value) = trace.request_id.parse() {
+        headers.insert(REQUEST_ID_HEADER, value);
+    }
+    if let Ok(value) = trace.traceparent().parse() {
+        headers.insert(TRACEPARENT_HEADER, value);
+    }
+    response
+}
+
+/// Log the completed request as one JSON record, with bodies redacted as
+/// `ApiConfig::request_log` says
+async fn log_request(
+    State(server): State<Arc<ApiServer>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let config = &server.config.request_log;
+    if !config.enabled {
+        return next.run(request).await;
+    }
+
+    let started = Instant::now();
+    let request_id = request
+        .extensions()
+        .get::<TraceContext>()
+        .map(|trace| trace.request_id.clone());
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+
+    let (parts, body) = request.into_parts();
+    let (body, request_body) = match config.capture(&parts.headers, body).await {
+        Ok(captured) => captured,
+        Err(e) => {
+            return ApiServer::error_response(ApiError::InvalidRequest(format!(
+                "Failed to read request body: {}",
+                e
+            )))
+        }
+    };
+    let response = next
+        .run(axum::extract::Request::from_parts(parts, body))
+        .await;
+    let latency = started.elapsed();
+
+    let (parts, body) = response.into_parts();
+    let (body, response_body) = match config.capture(&parts.headers, body).await {
+        Ok(captured) => captured,
+        Err(e) => {
+            error!("Failed to read response body of {} {}: {}", method, path, e);
+            return ApiServer::error_response(ApiError::InternalError(
+                "Failed to read response body".to_string(),
+            ));
+        }
+    };
+    let entry = RequestLogEntry {
+        request_id,
+        method,
+        path,
+        status: parts.status.as_u16(),
+        latency,
+        request_body,
+        response_body,
+    };
+    info!(target: REQUEST_LOG_TARGET, "{}", entry.to_json(config));
+    Response::from_parts(parts, body)
+}
+
+/// Check the request's API key, answering 401 when one is required and
+/// missing or invalid, and attach the caller's `ApiIdentity`. Rejected
+/// requests still count against the client IP's limits, so guessing keys
+/// ends in 429s like any other flood.
+async fn authenticate_request(
+    State(server): State<Arc<ApiServer>>,
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    match server
+        .auth
+        .authenticate(request.uri().path(), request.headers())
+    {
+        Ok(Some(identity)) => {
+            request.extensions_mut().insert(identity);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            let key = RateLimitKey::Client(client_ip(&request));
+            if let Err(limited) = server.rate_limiter.check(request.uri().path(), &key) {
+                debug!("Rate limited {:?} on {}", key, request.uri().path());
+                return ApiServer::error_response(limited);
+            }
+            debug!(
+                "Rejected unauthenticated request to {}",
+                request.uri().path()
+            );
+            return ApiServer::error_response(e);
+        }
+    }
+    next.run(request).await
+}
+
+/// Address of the client that sent `request`, or "unknown"
+fn client_ip(request: &axum::extract::Request) -> String {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Rate limit scope of inference requests sent over a WebSocket session
+const WS_INFERENCE_SCOPE: &str = "/v1/ws/inference";
+
+/// Count the request against its client's limits, answering 429 with a
+/// `Retry-After` header when they are used up
+async fn rate_limit_request(
+    State(server): State<Arc<ApiServer>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let client = client_ip(&request);
+    // Keys accepted only by the verifier are limited by their identity
+    let key = match (
+        server
+            .rate_limiter
+            .key_for(request_api_key(request.headers()), client),
+        request.extensions().get::<ApiIdentity>(),
+    ) {
+        (RateLimitKey::Client(_), Some(identity)) => {
+            RateLimitKey::ApiKey(identity.client_id.clone())
+        }
+        (key, _) => key,
+    };
+    if let Err(e) = server.rate_limiter.check(request.uri().path(), &key) {
+        debug!("Rate limited {:?} on {}", key, request.uri().path());
+        return ApiServer::error_response(e);
+    }
+    next.run(request).await
+}
+
 // Handler functions as free functions
 async fn health_handler(State(server): State<Arc<ApiServer>>) -> impl IntoResponse {
     axum::response::Json(server.health_check().await)
 }
 
+async fn liveness_handler(State(server): State<Arc<ApiServer>>) -> impl IntoResponse {
+    axum::response::Json(server.liveness_check())
+}
+
+async fn readiness_handler(State(server): State<Arc<ApiServer>>) -> impl IntoResponse {
+    let probe = server.readiness_check().await;
+    let status = if probe.is_ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, axum::response::Json(probe))
+}
+
+/// Time allowed for each readiness dependency to answer
+const READINESS_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+async fn probe_dependency(
+    name: &str,
+    check: impl std::future::Future<Output = Result<String>>,
+) -> ComponentHealth {
+    let started = Instant::now();
+    let (status, message) = match tokio::time::timeout(READINESS_PROBE_TIMEOUT, check).await {
+        Ok(Ok(message)) => (HealthStatus::Healthy, message),
+        Ok(Err(e)) => (HealthStatus::Unhealthy, e.to_string()),
+        Err(_) => (HealthStatus::Unhealthy, "Check timed out".to_string()),
+    };
+    ComponentHealth {
+        name: name.to_string(),
+        status,
+        message: Some(message),
+        last_check: chrono::Utc::now().timestamp() as u64,
+        response_time_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
 async fn models_handler(State(server): State<Arc<ApiServer>>) -> impl IntoResponse {
     match server.get_available_models().await {
         Ok(models) => (StatusCode::OK, axum::response::Json(models)).into_response(),
@@ -1226,6 +2044,16 @@ async fn models_handler(State(server): State<Arc<ApiServer>>) -> impl IntoRespon
     }
 }
 
+async fn model_details_handler(
+    State(server): State<Arc<ApiServer>>,
+    Path(model_id): Path<String>,
+) -> impl IntoResponse {
+    match server.get_model_details(&model_id).await {
+        Ok(details) => (StatusCode::OK, axum::response::Json(details)).into_response(),
+        Err(e) => ApiServer::error_response(e),
+    }
+}
+
 async fn version_handler() -> impl IntoResponse {
     axum::response::Json(crate::version::get_version_info())
 }
@@ -1322,32 +2150,38 @@ async fn checkpoints_handler(
 // Inference handler that properly uses axum extractors
 async fn simple_inference_handler(
     State(server): State<Arc<ApiServer>>,
+    identity: Option<axum::Extension<ApiIdentity>>,
     Json(request): Json<InferenceRequest>,
 ) -> impl IntoResponse {
-    let client_ip = "127.0.0.1".to_string();
-
-    match server.handle_inference_request(request, client_ip).await {
-        Ok(response) => (StatusCode::OK, axum::response::Json(response)).into_response(),
+    match server.handle_inference_request(request).await {
+        Ok(response) => {
+            if let Some(axum::Extension(identity)) = identity {
+                info!(
+                    client_id = %identity.client_id,
+                    request_id = %response.request_id,
+                    tokens_used = response.tokens_used,
+                    "Inference completed for API client"
+                );
+            }
+            (StatusCode::OK, axum::response::Json(response)).into_response()
+        }
         Err(e) => ApiServer::error_response(e),
     }
 }
 
-async fn metrics_handler() -> impl IntoResponse {
-    let metrics = "# HELP http_requests_total Total HTTP requests\n\
-                  # TYPE http_requests_total counter\n\
-                  http_requests_total 0\n\
-                  # HELP http_request_duration_seconds Request duration\n\
-                  # TYPE http_request_duration_seconds histogram\n\
-                  http_request_duration_seconds_bucket{le=\"0.1\"} 0\n";
-
-    (
-        StatusCode::OK,
-        [(
-            axum::http::header::CONTENT_TYPE,
-            "text/plain; version=0.0.4",
-        )],
-        metrics,
-    )
+async fn metrics_handler(State(server): State<Arc<ApiServer>>) -> impl IntoResponse {
+    match server.render_metrics().await {
+        Ok(metrics) => (
+            StatusCode::OK,
+            [(axum::http::header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+            metrics,
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to render metrics: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
 }
 
 // Embedding handler wrapper that converts ApiServer state to AppState
@@ -1416,6 +2250,130 @@ async fn ocr_handler_wrapper(
     }
 }
 
+// Streaming OCR handler wrapper that converts ApiServer state to AppState
+async fn ocr_stream_handler_wrapper(
+    State(server): State<Arc<ApiServer>>,
+    Json(request): Json<crate::api::ocr::OcrStreamRequest>,
+) -> impl IntoResponse {
+    use crate::api::http_server::AppState;
+    use crate::blockchain::ChainRegistry;
+
+    let app_state = AppState {
+        api_server: server.clone(),
+        chain_registry: Arc::new(ChainRegistry::new()),
+        sessions: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        chain_stats: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        embedding_model_manager: server.embedding_model_manager.clone(),
+        vision_model_manager: server.vision_model_manager.clone(),
+        search_service: server.search_service.clone(),
+        diffusion_client: server.diffusion_client.clone(),
+    };
+
+    match crate::api::ocr_stream_handler(axum::extract::State(app_state), Json(request)).await {
+        Ok(sse) => sse.into_response(),
+        Err((status, message)) => (
+            status,
+            axum::response::Json(serde_json::json!({
+                "error": message
+            })),
+        )
+            .into_response(),
+    }
+}
+
+// Tokenize handler wrapper that converts ApiServer state to AppState
+async fn tokenize_handler_wrapper(
+    State(server): State<Arc<ApiServer>>,
+    Json(request): Json<crate::api::TokenizeRequest>,
+) -> impl IntoResponse {
+    use crate::api::http_server::AppState;
+    use crate::blockchain::ChainRegistry;
+
+    let app_state = AppState {
+        api_server: server.clone(),
+        chain_registry: Arc::new(ChainRegistry::new()),
+        sessions: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        chain_stats: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        embedding_model_manager: server.embedding_model_manager.clone(),
+        vision_model_manager: server.vision_model_manager.clone(),
+        search_service: server.search_service.clone(),
+        diffusion_client: server.diffusion_client.clone(),
+    };
+
+    match crate::api::tokenize_handler(axum::extract::State(app_state), Json(request)).await {
+        Ok(response) => (StatusCode::OK, axum::response::Json(response.0)).into_response(),
+        Err((status, message)) => (
+            status,
+            axum::response::Json(serde_json::json!({
+                "error": message
+            })),
+        )
+            .into_response(),
+    }
+}
+
+// Detokenize handler wrapper that converts ApiServer state to AppState
+async fn detokenize_handler_wrapper(
+    State(server): State<Arc<ApiServer>>,
+    Json(request): Json<crate::api::DetokenizeRequest>,
+) -> impl IntoResponse {
+    use crate::api::http_server::AppState;
+    use crate::blockchain::ChainRegistry;
+
+    let app_state = AppState {
+        api_server: server.clone(),
+        chain_registry: Arc::new(ChainRegistry::new()),
+        sessions: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        chain_stats: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        embedding_model_manager: server.embedding_model_manager.clone(),
+        vision_model_manager: server.vision_model_manager.clone(),
+        search_service: server.search_service.clone(),
+        diffusion_client: server.diffusion_client.clone(),
+    };
+
+    match crate::api::detokenize_handler(axum::extract::State(app_state), Json(request)).await {
+        Ok(response) => (StatusCode::OK, axum::response::Json(response.0)).into_response(),
+        Err((status, message)) => (
+            status,
+            axum::response::Json(serde_json::json!({
+                "error": message
+            })),
+        )
+            .into_response(),
+    }
+}
+
+// Verify proof handler wrapper that converts ApiServer state to AppState
+async fn verify_proof_handler_wrapper(
+    State(server): State<Arc<ApiServer>>,
+    Json(request): Json<crate::api::VerifyProofRequest>,
+) -> impl IntoResponse {
+    use crate::api::http_server::AppState;
+    use crate::blockchain::ChainRegistry;
+
+    let app_state = AppState {
+        api_server: server.clone(),
+        chain_registry: Arc::new(ChainRegistry::new()),
+        sessions: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        chain_stats: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        embedding_model_manager: server.embedding_model_manager.clone(),
+        vision_model_manager: server.vision_model_manager.clone(),
+        search_service: server.search_service.clone(),
+        diffusion_client: server.diffusion_client.clone(),
+    };
+
+    match crate::api::verify_proof_handler(axum::extract::State(app_state), Json(request)).await {
+        Ok(response) => (StatusCode::OK, axum::response::Json(response.0)).into_response(),
+        Err((status, message)) => (
+            status,
+            axum::response::Json(serde_json::json!({
+                "error": message
+            })),
+        )
+            .into_response(),
+    }
+}
+
 // Describe image handler wrapper that converts ApiServer state to AppState
 async fn describe_image_handler_wrapper(
     State(server): State<Arc<ApiServer>>,
@@ -1520,6 +2478,80 @@ async fn generate_image_handler_wrapper(
     }
 }
 
+// Submit image job handler wrapper that converts ApiServer state to AppState
+async fn submit_image_job_handler_wrapper(
+    State(server): State<Arc<ApiServer>>,
+    Json(request): Json<crate::api::generate_image::GenerateImageRequest>,
+) -> impl IntoResponse {
+    use crate::api::http_server::AppState;
+    use crate::blockchain::ChainRegistry;
+
+    // Create AppState from ApiServer
+    let app_state = AppState {
+        api_server: server.clone(),
+        chain_registry: Arc::new(ChainRegistry::new()),
+        sessions: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        chain_stats: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        embedding_model_manager: server.embedding_model_manager.clone(),
+        vision_model_manager: server.vision_model_manager.clone(),
+        search_service: server.search_service.clone(),
+        diffusion_client: server.diffusion_client.clone(),
+    };
+
+    match crate::api::generate_image::submit_image_job_handler(
+        axum::extract::State(app_state),
+        Json(request),
+    )
+    .await
+    {
+        Ok((status, response)) => (status, axum::response::Json(response.0)).into_response(),
+        Err((status, message)) => (
+            status,
+            axum::response::Json(serde_json::json!({
+                "error": message
+            })),
+        )
+            .into_response(),
+    }
+}
+
+// Image job status handler wrapper that converts ApiServer state to AppState
+async fn image_job_status_handler_wrapper(
+    State(server): State<Arc<ApiServer>>,
+    Path(job_id): Path<String>,
+) -> impl IntoResponse {
+    use crate::api::http_server::AppState;
+    use crate::blockchain::ChainRegistry;
+
+    // Create AppState from ApiServer
+    let app_state = AppState {
+        api_server: server.clone(),
+        chain_registry: Arc::new(ChainRegistry::new()),
+        sessions: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        chain_stats: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        embedding_model_manager: server.embedding_model_manager.clone(),
+        vision_model_manager: server.vision_model_manager.clone(),
+        search_service: server.search_service.clone(),
+        diffusion_client: server.diffusion_client.clone(),
+    };
+
+    match crate::api::generate_image::image_job_status_handler(
+        axum::extract::State(app_state),
+        Path(job_id),
+    )
+    .await
+    {
+        Ok(response) => (StatusCode::OK, axum::response::Json(response.0)).into_response(),
+        Err((status, message)) => (
+            status,
+            axum::response::Json(serde_json::json!({
+                "error": message
+            })),
+        )
+            .into_response(),
+    }
+}
+
 /// Process images via VLM sidecar, return augmented prompt (v8.15.3+)
 ///
 /// Strip SDK UI markers from prompt before sending to LLM (v8.15.4+)
@@ -1632,9 +2664,13 @@ async fn process_vision_images(
 
 async fn websocket_handler(
     ws: WebSocketUpgrade,
+    headers: axum::http::HeaderMap,
+    axum::extract::Query(query): axum::extract::Query<HashMap<String, String>>,
     State(server): State<Arc<ApiServer>>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_websocket(socket, server))
+    // The whole session, settlement included, shares the upgrade's trace
+    let span = TraceContext::from_headers_or_query(&headers, &query).span("websocket_session");
+    ws.on_upgrade(|socket| handle_websocket(socket, server).instrument(span))
 }
 
 async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
@@ -1667,8 +2703,8 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
             Ok(axum::extract::ws::Message::Text(text)) => {
                 // Parse WebSocket message
                 if let Ok(json_msg) = serde_json::from_str::<serde_json::Value>(&text) {
-                    // Handle stream_cancel (always plaintext, processed before all other types)
-                    if json_msg["type"] == "stream_cancel" {
+                    // Handle cancel / stream_cancel (always plaintext, processed before all other types)
+                    if is_cancel_message(&json_msg) {
                         let cancel_sid = json_msg["session_id"]
                             .as_str()
                             .or_else(|| json_msg["sessionId"].as_str())
@@ -1676,11 +2712,12 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                             .or(session_id.clone());
                         if let Some(sid) = &cancel_sid {
                             let store = server.session_store.read().await;
-                            if let Some(session) = store.get_session(sid).await {
-                                session
-                                    .inference_cancel_flag
-                                    .store(true, std::sync::atomic::Ordering::Release);
-                                info!("🛑 stream_cancel received for session {}", sid);
+                            if store
+                                .cancel_inference(sid, message_index_of(&json_msg))
+                                .await
+                                .is_some()
+                            {
+                                info!("🛑 {} received for session {}", json_msg["type"], sid);
                             } else {
                                 debug!("stream_cancel for unknown session {}, ignoring", sid);
                             }
@@ -2260,13 +3297,14 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                                     == Some("image_generation")
                                                                 {
                                                                     info!("Routing encrypted message to image generation handler");
-                                                                    let response_msg = crate::api::websocket::handlers::image_generation::handle_encrypted_image_generation(
+                                                                    let response_msg = crate::api::websocket::handlers::image_generation::handle_encrypted_image_generation_with_updates(
                                                                         &server,
                                                                         &decrypted_json,
                                                                         &session_key,
                                                                         current_session_id.as_deref().unwrap_or("unknown"),
                                                                         job_id,
                                                                         json_msg.get("id"),
+                                                                        &mut ws_sender,
                                                                     ).await;
                                                                     let _ = ws_sender.send(axum::extract::ws::Message::Text(response_msg.to_string())).await;
                                                                     continue;
@@ -2326,13 +3364,14 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                                         "action": "image_generation",
                                                                         "prompt": last_user_msg,
                                                                     });
-                                                                    let response_msg = crate::api::websocket::handlers::image_generation::handle_encrypted_image_generation(
+                                                                    let response_msg = crate::api::websocket::handlers::image_generation::handle_encrypted_image_generation_with_updates(
                                                                         &server,
                                                                         &auto_json,
                                                                         &session_key,
                                                                         current_session_id.as_deref().unwrap_or("unknown"),
                                                                         job_id,
                                                                         json_msg.get("id"),
+                                                                        &mut ws_sender,
                                                                     ).await;
                                                                     let _ = ws_sender.send(axum::extract::ws::Message::Text(response_msg.to_string())).await;
                                                                     continue;
@@ -2467,6 +3506,44 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                                     "thinking": thinking
                                                                 });
 
+                                                                // Custom stop sequences
+                                                                if let Some(stop) = decrypted_json
+                                                                    .get("stop")
+                                                                    .or_else(|| {
+                                                                        json_msg.get("stop")
+                                                                    })
+                                                                    .filter(|v| v.is_array())
+                                                                {
+                                                                    request_value["stop"] =
+                                                                        stop.clone();
+                                                                }
+
+                                                                // Logit bias (token ID → additive bias)
+                                                                if let Some(bias) = decrypted_json
+                                                                    .get("logit_bias")
+                                                                    .or_else(|| {
+                                                                        json_msg.get("logit_bias")
+                                                                    })
+                                                                    .filter(|v| v.is_object())
+                                                                {
+                                                                    request_value["logit_bias"] =
+                                                                        bias.clone();
+                                                                }
+
+                                                                // Structured output constraint
+                                                                if let Some(format) = decrypted_json
+                                                                    .get("response_format")
+                                                                    .or_else(|| {
+                                                                        json_msg
+                                                                            .get("response_format")
+                                                                    })
+                                                                    .filter(|v| v.is_object())
+                                                                {
+                                                                    request_value
+                                                                        ["response_format"] =
+                                                                        format.clone();
+                                                                }
+
                                                                 // Add search_queries if present
                                                                 if let Some(queries) =
                                                                     search_queries
@@ -2488,27 +3565,31 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                                     )
                                                                 {
                                                                     // Reset and clone cancel flag for this inference
-                                                                    let cancel_flag = if let Some(
-                                                                        ref sid,
-                                                                    ) =
-                                                                        current_session_id
-                                                                    {
-                                                                        let store = server
-                                                                            .session_store
-                                                                            .read()
-                                                                            .await;
-                                                                        if let Some(session) = store
-                                                                            .get_session(sid)
-                                                                            .await
+                                                                    let prompt_index =
+                                                                        message_index_of(
+                                                                            &decrypted_json,
+                                                                        )
+                                                                        .or_else(|| {
+                                                                            message_index_of(
+                                                                                &json_msg,
+                                                                            )
+                                                                        });
+                                                                    let cancel_flag =
+                                                                        if let Some(ref sid) =
+                                                                            current_session_id
                                                                         {
-                                                                            session.inference_cancel_flag.store(false, std::sync::atomic::Ordering::Release);
-                                                                            Some(session.inference_cancel_flag.clone())
+                                                                            server
+                                                                                .session_store
+                                                                                .read()
+                                                                                .await
+                                                                                .begin_inference(
+                                                                                    sid,
+                                                                                    prompt_index,
+                                                                                )
+                                                                                .await
                                                                         } else {
                                                                             None
-                                                                        }
-                                                                    } else {
-                                                                        None
-                                                                    };
+                                                                        };
 
                                                                     // Handle streaming inference
                                                                     match server
@@ -2541,9 +3622,14 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                                                         match ws_msg {
                                                                                             Some(Ok(axum::extract::ws::Message::Text(text))) => {
                                                                                                 if let Ok(cj) = serde_json::from_str::<serde_json::Value>(&text) {
-                                                                                                    if cj["type"] == "stream_cancel" {
-                                                                                                        if let Some(ref flag) = cancel_flag {
-                                                                                                            flag.store(true, std::sync::atomic::Ordering::Release);
+                                                                                                    if is_cancel_message(&cj) {
+                                                                                                        let matched = match current_session_id {
+                                                                                                            Some(ref sid) => server.session_store.read().await.cancel_inference(sid, message_index_of(&cj)).await.is_some(),
+                                                                                                            None => true,
+                                                                                                        };
+                                                                                                        if !matched {
+                                                                                                            debug!("cancel for an earlier message during encrypted streaming, ignoring");
+                                                                                                            continue;
                                                                                                         }
                                                                                                         info!("🛑 stream_cancel during encrypted streaming");
                                                                                                         let mut end_msg = json!({"type": "stream_end", "reason": "cancelled", "tokens_used": total_tokens, "finish_reason": "cancelled"});
@@ -2707,10 +3793,14 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                                                                             // Add usage and finish_reason from inference result (v8.21.0)
                                                                                                             if let Ok(meta) = result_rx.try_recv() {
                                                                                                                 stream_end_msg["finish_reason"] = json!(&meta.finish_reason);
+                                                                                                                if let Some(ref fingerprint) = meta.system_fingerprint {
+                                                                                                                    stream_end_msg["system_fingerprint"] = json!(fingerprint);
+                                                                                                                }
                                                                                                                 if let Some(ref cu) = meta.context_usage {
                                                                                                                     stream_end_msg["usage"] = json!({
                                                                                                                         "prompt_tokens": cu.prompt_tokens,
                                                                                                                         "completion_tokens": cu.completion_tokens,
+                                                                                                                        "late_context_tokens": cu.late_context_tokens,
                                                                                                                         "total_tokens": cu.total_tokens,
                                                                                                                         "context_window_size": cu.context_window_size
                                                                                                                     });
@@ -3023,7 +4113,11 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                     "max_tokens": json_msg["max_tokens"].as_u64().unwrap_or(4000),
                                     "temperature": json_msg["temperature"].as_f64().unwrap_or(0.7),
                                     "stream": json_msg["stream"].as_bool().unwrap_or(true),
-                                    "thinking": json_msg["thinking"].as_str()
+                                    "thinking": json_msg["thinking"].as_str(),
+                                    "stop": json_msg.get("stop").filter(|v| v.is_array()).cloned().unwrap_or_else(|| json!([])),
+                                    "logit_bias": json_msg.get("logit_bias").filter(|v| v.is_object()).cloned().unwrap_or_else(|| json!({})),
+                                    "response_format": json_msg.get("response_format").filter(|v| v.is_object()).cloned(),
+                                    "logprobs": json_msg.get("logprobs").and_then(|v| v.as_u64())
                                 })
                             }
                         } else {
@@ -3098,15 +4192,12 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
 
                             // Reset and clone cancel flag for this inference
                             let cancel_flag = if let Some(ref sid) = session_id {
-                                let store = server.session_store.read().await;
-                                if let Some(session) = store.get_session(sid).await {
-                                    session
-                                        .inference_cancel_flag
-                                        .store(false, std::sync::atomic::Ordering::Release);
-                                    Some(session.inference_cancel_flag.clone())
-                                } else {
-                                    None
-                                }
+                                server
+                                    .session_store
+                                    .read()
+                                    .await
+                                    .begin_inference(sid, message_index_of(&json_msg))
+                                    .await
                             } else {
                                 None
                             };
@@ -3135,9 +4226,14 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                 match ws_msg {
                                                     Some(Ok(axum::extract::ws::Message::Text(text))) => {
                                                         if let Ok(cj) = serde_json::from_str::<serde_json::Value>(&text) {
-                                                            if cj["type"] == "stream_cancel" {
-                                                                if let Some(ref flag) = cancel_flag {
-                                                                    flag.store(true, std::sync::atomic::Ordering::Release);
+                                                            if is_cancel_message(&cj) {
+                                                                let matched = match session_id {
+                                                                    Some(ref sid) => server.session_store.read().await.cancel_inference(sid, message_index_of(&cj)).await.is_some(),
+                                                                    None => true,
+                                                                };
+                                                                if !matched {
+                                                                    debug!("cancel for an earlier message during plaintext streaming, ignoring");
+                                                                    continue;
                                                                 }
                                                                 info!("🛑 stream_cancel during plaintext streaming");
                                                                 let mut end_msg = json!({"type": "stream_end", "reason": "cancelled", "tokens_used": total_tokens, "finish_reason": "cancelled"});
@@ -3165,6 +4261,10 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                 "tokens": response.tokens,
                                             });
 
+                                            if let Some(ref logprobs) = response.logprobs {
+                                                ws_msg["logprobs"] = json!(logprobs);
+                                            }
+
                                             // Include message ID if present for correlation
                                             if let Some(ref msg_id) = message_id {
                                                 ws_msg["id"] = msg_id.clone();
@@ -3194,10 +4294,17 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                                 if let Ok(meta) = result_rx.try_recv() {
                                                     end_msg["finish_reason"] =
                                                         json!(&meta.finish_reason);
+                                                    if let Some(ref fingerprint) =
+                                                        meta.system_fingerprint
+                                                    {
+                                                        end_msg["system_fingerprint"] =
+                                                            json!(fingerprint);
+                                                    }
                                                     if let Some(ref cu) = meta.context_usage {
                                                         end_msg["usage"] = json!({
                                                             "prompt_tokens": cu.prompt_tokens,
                                                             "completion_tokens": cu.completion_tokens,
+                                                            "late_context_tokens": cu.late_context_tokens,
                                                             "total_tokens": cu.total_tokens,
                                                             "context_window_size": cu.context_window_size
                                                         });
@@ -3465,11 +4572,13 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
                                 };
 
                                 let rag_session_arc = Arc::new(std::sync::Mutex::new(rag_session));
+                                let reranker = server.reranker.read().await.clone();
 
                                 // Call the RAG handler
-                                match crate::api::websocket::handlers::rag::handle_search_vectors(
+                                match crate::api::websocket::handlers::rag::handle_search_vectors_with_reranker(
                                     &rag_session_arc,
                                     request,
+                                    reranker.as_deref(),
                                 ) {
                                     Ok(response) => {
                                         match serde_json::to_string(&response) {
@@ -3593,24 +4702,27 @@ async fn handle_websocket(socket: WebSocket, server: Arc<ApiServer>) {
             drop(cm); // Release lock before spawning
 
             // ASYNC: Spawn session completion in background to avoid blocking
-            tokio::spawn(async move {
-                info!(
-                    "[WS-BG] 🚀 Starting background session completion for job_id: {}",
-                    jid
-                );
+            tokio::spawn(
+                async move {
+                    info!(
+                        "[WS-BG] 🚀 Starting background session completion for job_id: {}",
+                        jid
+                    );
 
-                match checkpoint_manager.complete_session_job(jid).await {
-                    Ok(()) => {
-                        info!(
-                            "[WS-BG] 💰 Settlement completed successfully for job_id: {}",
-                            jid
-                        );
-                    }
-                    Err(e) => {
-                        error!("[WS-BG] ❌ Failed to complete session job {}: {}", jid, e);
+                    match checkpoint_manager.complete_session_job(jid).await {
+                        Ok(()) => {
+                            info!(
+                                "[WS-BG] 💰 Settlement completed successfully for job_id: {}",
+                                jid
+                            );
+                        }
+                        Err(e) => {
+                            error!("[WS-BG] ❌ Failed to complete session job {}: {}", jid, e);
+                        }
                     }
                 }
-            });
+                .in_current_span(),
+            );
         } else {
             drop(cm);
             warn!("⚠️ No checkpoint manager available for settlement");
@@ -3630,7 +4742,13 @@ impl ApiServer {
             StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
         let body = error.to_response(None);
 
-        (status, axum::response::Json(body)).into_response()
+        let mut response = (status, axum::response::Json(body)).into_response();
+        if let ApiError::RateLimitExceeded { retry_after } = error {
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, retry_after.into());
+        }
+        response
     }
 }
 
@@ -3654,10 +4772,16 @@ pub async fn create_test_server() -> Result<TestServer> {
         max_connections_per_ip: 10,
         request_timeout: Duration::from_secs(30),
         cors_allowed_origins: vec!["*".to_string()],
+        cors_allowed_methods: vec!["GET".to_string(), "POST".to_string()],
+        cors_allowed_headers: default_cors_headers(),
+        cors_allow_credentials: false,
         enable_websocket: true,
         require_api_key: false,
         api_keys: vec![],
+        public_paths: default_public_paths(),
         rate_limit_per_minute: 100,
+        rate_limits: RateLimitConfig::default(),
+        request_log: RequestLogConfig::default(),
         enable_http2: false,
         enable_auto_retry: false,
         max_retries: 0,
@@ -1,5 +1,8 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
+use crate::inference::{TokenLogprobs, ToolCall};
+use crate::search::query_extractor::format_late_result_for_prompt;
+use crate::search::SearchResultWithContent;
 use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
@@ -17,6 +20,15 @@ pub struct StreamingResponse {
     pub chain_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub native_token: Option<String>,
+    /// Logprobs for the token in this chunk, when the request asked for them
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub logprobs: Option<TokenLogprobs>,
+    /// Function calls the model requested, on the final chunk
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Identifies the model and engine settings, on the final chunk
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub system_fingerprint: Option<String>,
 }
 
 pub struct StreamingHandler {
@@ -41,6 +53,29 @@ impl Stream for StreamingHandler {
     }
 }
 
+/// Longest page text forwarded for one late search result
+const LATE_RESULT_MAX_CHARS: usize = 2000;
+
+/// Forward search results whose content finishes fetching after streaming
+/// started to the engine as late context. `offset` is the number of results
+/// already in the prompt, so late results continue its numbering. Stops once
+/// generation no longer accepts late context.
+pub fn forward_late_results(
+    mut remaining: mpsc::UnboundedReceiver<(usize, SearchResultWithContent)>,
+    offset: usize,
+    late_tx: mpsc::UnboundedSender<String>,
+) {
+    tokio::spawn(async move {
+        while let Some((index, result)) = remaining.recv().await {
+            let text =
+                format_late_result_for_prompt(offset + index + 1, &result, LATE_RESULT_MAX_CHARS);
+            if late_tx.send(text).is_err() {
+                break;
+            }
+        }
+    });
+}
+
 pub fn format_sse(response: &StreamingResponse) -> String {
     if response.finish_reason.as_deref() == Some("stop") {
         "data: [DONE]\n\n".to_string()
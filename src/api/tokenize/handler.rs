@@ -0,0 +1,148 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Tokenization API endpoint handlers
+
+use axum::{extract::State, http::StatusCode, Json};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::request::{DetokenizeRequest, TokenizeRequest};
+use super::response::{DetokenizeResponse, TokenizeResponse};
+use crate::api::http_server::AppState;
+use crate::inference::LlmEngine;
+
+/// Resolve the engine and make sure `model` is loaded in memory
+async fn engine_for_model(
+    state: &AppState,
+    model: &str,
+) -> Result<Arc<LlmEngine>, (StatusCode, String)> {
+    let engine = state.api_server.get_engine().await.ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Inference engine not initialized".to_string(),
+        )
+    })?;
+
+    if engine.vocab_size(model).is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Model '{}' is not loaded", model),
+        ));
+    }
+
+    Ok(engine)
+}
+
+/// POST /v1/tokenize - Tokenize text with a loaded model's tokenizer
+///
+/// # Request
+/// - `model`: ID of a loaded model
+/// - `text`: Text to tokenize
+///
+/// # Response
+/// - `tokens`: Token IDs (no BOS token is added)
+/// - `count`: Number of tokens
+///
+/// # Errors
+/// - 400 Bad Request: Invalid request
+/// - 404 Not Found: Model not loaded
+/// - 503 Service Unavailable: Engine not initialized
+/// - 500 Internal Server Error: Tokenization failed
+pub async fn tokenize_handler(
+    State(state): State<AppState>,
+    Json(request): Json<TokenizeRequest>,
+) -> Result<Json<TokenizeResponse>, (StatusCode, String)> {
+    if let Err(e) = request.validate() {
+        warn!("Tokenize validation failed: {}", e);
+        return Err((StatusCode::BAD_REQUEST, e));
+    }
+
+    let engine = engine_for_model(&state, &request.model).await?;
+
+    let tokens = engine
+        .tokenize(&request.model, &request.text)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    debug!(
+        "Tokenized {} bytes into {} tokens (model={})",
+        request.text.len(),
+        tokens.len(),
+        request.model
+    );
+
+    Ok(Json(TokenizeResponse::new(tokens)))
+}
+
+/// POST /v1/detokenize - Convert token IDs back to text
+///
+/// Tokens are decoded at the byte level and joined before UTF-8 conversion,
+/// so multi-byte characters split across tokens decode correctly. Any byte
+/// sequence that is still invalid UTF-8 is replaced with U+FFFD, which means
+/// tokenize → detokenize may not round-trip exactly for every tokenizer.
+///
+/// # Request
+/// - `model`: ID of a loaded model
+/// - `tokens`: Token IDs
+///
+/// # Response
+/// - `text`: Decoded text
+///
+/// # Errors
+/// - 400 Bad Request: Invalid request or token ID outside the model vocabulary
+/// - 404 Not Found: Model not loaded
+/// - 503 Service Unavailable: Engine not initialized
+pub async fn detokenize_handler(
+    State(state): State<AppState>,
+    Json(request): Json<DetokenizeRequest>,
+) -> Result<Json<DetokenizeResponse>, (StatusCode, String)> {
+    if let Err(e) = request.validate() {
+        warn!("Detokenize validation failed: {}", e);
+        return Err((StatusCode::BAD_REQUEST, e));
+    }
+
+    let engine = engine_for_model(&state, &request.model).await?;
+
+    let text = engine
+        .detokenize(&request.model, &request.tokens)
+        .map_err(|e| {
+            let msg = e.to_string();
+            if msg.contains("Invalid token id") {
+                (StatusCode::BAD_REQUEST, msg)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, msg)
+            }
+        })?;
+
+    Ok(Json(DetokenizeResponse { text }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tokenize_without_engine_is_unavailable() {
+        let state = AppState::new_for_test();
+        let request = TokenizeRequest {
+            model: "missing".to_string(),
+            text: "Hello".to_string(),
+        };
+        let err = tokenize_handler(State(state), Json(request))
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_detokenize_rejects_empty_model() {
+        let state = AppState::new_for_test();
+        let request = DetokenizeRequest {
+            model: String::new(),
+            tokens: vec![1, 2],
+        };
+        let err = detokenize_handler(State(state), Json(request))
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
+}
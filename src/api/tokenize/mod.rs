@@ -0,0 +1,14 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Tokenization API endpoints
+//!
+//! Provides `/v1/tokenize` and `/v1/detokenize` so clients can count and
+//! inspect tokens with the loaded model's tokenizer without running inference.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::{detokenize_handler, tokenize_handler};
+pub use request::{DetokenizeRequest, TokenizeRequest};
+pub use response::{DetokenizeResponse, TokenizeResponse};
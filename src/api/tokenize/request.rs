@@ -0,0 +1,109 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Tokenization API request types
+
+use serde::{Deserialize, Serialize};
+
+/// Maximum text length accepted by /v1/tokenize (bytes)
+pub const MAX_TOKENIZE_TEXT_LEN: usize = 1_000_000;
+
+/// Maximum number of tokens accepted by /v1/detokenize
+pub const MAX_DETOKENIZE_TOKENS: usize = 262_144;
+
+/// Request body for POST /v1/tokenize
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenizeRequest {
+    /// ID of a model currently loaded by the engine
+    pub model: String,
+
+    /// Text to tokenize
+    pub text: String,
+}
+
+impl TokenizeRequest {
+    /// Validate the request
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.trim().is_empty() {
+            return Err("Model cannot be empty".to_string());
+        }
+        if self.text.len() > MAX_TOKENIZE_TEXT_LEN {
+            return Err(format!(
+                "Text too long (max {} bytes)",
+                MAX_TOKENIZE_TEXT_LEN
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Request body for POST /v1/detokenize
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetokenizeRequest {
+    /// ID of a model currently loaded by the engine
+    pub model: String,
+
+    /// Token IDs to convert back to text
+    pub tokens: Vec<u32>,
+}
+
+impl DetokenizeRequest {
+    /// Validate the request
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.trim().is_empty() {
+            return Err("Model cannot be empty".to_string());
+        }
+        if self.tokens.len() > MAX_DETOKENIZE_TOKENS {
+            return Err(format!("Too many tokens (max {})", MAX_DETOKENIZE_TOKENS));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_request_deserialization() {
+        let json = r#"{"model": "llama-3", "text": "Hello world"}"#;
+        let request: TokenizeRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.model, "llama-3");
+        assert_eq!(request.text, "Hello world");
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_tokenize_request_allows_empty_text() {
+        let request = TokenizeRequest {
+            model: "llama-3".to_string(),
+            text: String::new(),
+        };
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_tokenize_request_rejects_empty_model() {
+        let request = TokenizeRequest {
+            model: " ".to_string(),
+            text: "Hello".to_string(),
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_detokenize_request_deserialization() {
+        let json = r#"{"model": "llama-3", "tokens": [15339, 1917]}"#;
+        let request: DetokenizeRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.tokens, vec![15339, 1917]);
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_detokenize_request_rejects_too_many_tokens() {
+        let request = DetokenizeRequest {
+            model: "llama-3".to_string(),
+            tokens: vec![0; MAX_DETOKENIZE_TOKENS + 1],
+        };
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Tokenization API response types
+
+use serde::{Deserialize, Serialize};
+
+/// Response body for POST /v1/tokenize
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenizeResponse {
+    /// Token IDs produced by the model's tokenizer (no BOS token)
+    pub tokens: Vec<u32>,
+
+    /// Number of tokens
+    pub count: usize,
+}
+
+impl TokenizeResponse {
+    pub fn new(tokens: Vec<u32>) -> Self {
+        let count = tokens.len();
+        Self { tokens, count }
+    }
+}
+
+/// Response body for POST /v1/detokenize
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetokenizeResponse {
+    /// Decoded text (invalid UTF-8 byte sequences replaced with U+FFFD)
+    pub text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_response_count_matches_tokens() {
+        let response = TokenizeResponse::new(vec![1, 2, 3]);
+        assert_eq!(response.count, 3);
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["tokens"], serde_json::json!([1, 2, 3]));
+        assert_eq!(json["count"], 3);
+    }
+
+    #[test]
+    fn test_detokenize_response_serialization() {
+        let response = DetokenizeResponse {
+            text: "Hello world".to_string(),
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["text"], "Hello world");
+    }
+}
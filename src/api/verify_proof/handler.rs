@@ -0,0 +1,162 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Proof verification API endpoint handler
+
+use axum::{extract::State, http::StatusCode, Json};
+use tracing::{debug, warn};
+
+use super::request::VerifyProofRequest;
+use super::response::VerifyProofResponse;
+use crate::api::http_server::AppState;
+use crate::crypto::ezkl::{EzklError, EzklVerifier, KeyManager};
+
+/// POST /v1/verify-proof - Verify an EZKL proof against a known key
+///
+/// Verification keys are loaded through the server's `KeyManager`, so each key
+/// is read from disk once and served from its cache afterwards.
+///
+/// # Request
+/// - `proof`: Hex-encoded proof bytes
+/// - `public_inputs`: Hex-encoded 32-byte public inputs
+/// - `verifying_key_id`: ID of a verification key known to this node
+///
+/// # Response
+/// - `valid`: Whether the proof verified
+/// - `trust_level`: `strict` (cryptographic check) or `relaxed` (mock verifier)
+///
+/// # Errors
+/// - 400 Bad Request: Invalid request or malformed proof / public inputs
+/// - 404 Not Found: Unknown verification key ID
+/// - 500 Internal Server Error: Key could not be loaded
+pub async fn verify_proof_handler(
+    State(state): State<AppState>,
+    Json(request): Json<VerifyProofRequest>,
+) -> Result<Json<VerifyProofResponse>, (StatusCode, String)> {
+    let key_manager = state.api_server.key_manager();
+    verify_with_key_manager(&key_manager, &request).map(Json)
+}
+
+fn verify_with_key_manager(
+    key_manager: &KeyManager,
+    request: &VerifyProofRequest,
+) -> Result<VerifyProofResponse, (StatusCode, String)> {
+    if let Err(e) = request.validate() {
+        warn!("Verify proof validation failed: {}", e);
+        return Err((StatusCode::BAD_REQUEST, e));
+    }
+
+    let proof = request
+        .decode_proof()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let public_inputs = request
+        .decode_public_inputs()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let key_path = key_manager
+        .verifying_key_path_for_id(&request.verifying_key_id)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Unknown verifying key '{}'", request.verifying_key_id),
+            )
+        })?;
+    let key = key_manager
+        .load_verifying_key(&key_path)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut verifier = EzklVerifier::with_key(key)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let input_refs: Vec<&[u8; 32]> = public_inputs.iter().collect();
+
+    let valid = verifier
+        .verify_proof_bytes(&proof, &input_refs)
+        .map_err(|e| match e {
+            // Raised for proofs or inputs the verifier can't even parse
+            EzklError::ProofVerificationFailed { .. } => (StatusCode::BAD_REQUEST, e.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+
+    debug!(
+        "Verified {}-byte proof with key '{}': valid={}",
+        proof.len(),
+        request.verifying_key_id,
+        valid
+    );
+
+    Ok(VerifyProofResponse::new(valid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "real-ezkl"))]
+    use crate::crypto::ezkl::setup::{compile_circuit, generate_keys, save_verifying_key};
+    #[cfg(not(feature = "real-ezkl"))]
+    use crate::crypto::ezkl::CommitmentCircuit;
+
+    fn request(proof: String, verifying_key_id: &str) -> VerifyProofRequest {
+        VerifyProofRequest {
+            proof,
+            public_inputs: (0u8..4).map(|i| hex::encode([i; 32])).collect(),
+            verifying_key_id: verifying_key_id.to_string(),
+        }
+    }
+
+    #[cfg(not(feature = "real-ezkl"))]
+    fn key_dir() -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        let circuit = CommitmentCircuit::new([0u8; 32], [1u8; 32], [2u8; 32], [3u8; 32]);
+        let (_, verifying_key) = generate_keys(&compile_circuit(&circuit).unwrap()).unwrap();
+        save_verifying_key(&verifying_key, &dir.path().join("commitment.bin")).unwrap();
+        dir
+    }
+
+    #[test]
+    #[cfg(not(feature = "real-ezkl"))]
+    fn test_verify_mock_proof() {
+        let dir = key_dir();
+        let manager = KeyManager::new().with_verifying_key_dir(dir.path());
+
+        let mut proof = vec![0u8; 256];
+        proof[0] = 0xEF;
+        let response =
+            verify_with_key_manager(&manager, &request(hex::encode(&proof), "commitment")).unwrap();
+        assert!(response.valid);
+
+        // Well-formed but wrong marker: verifies as invalid rather than erroring
+        proof[0] = 0x00;
+        let response =
+            verify_with_key_manager(&manager, &request(hex::encode(&proof), "commitment")).unwrap();
+        assert!(!response.valid);
+    }
+
+    #[test]
+    #[cfg(not(feature = "real-ezkl"))]
+    fn test_unknown_key_is_not_found() {
+        let dir = key_dir();
+        let manager = KeyManager::new().with_verifying_key_dir(dir.path());
+
+        let err =
+            verify_with_key_manager(&manager, &request("ef".repeat(256), "missing")).unwrap_err();
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_malformed_proof_is_bad_request() {
+        let manager = KeyManager::new();
+
+        let err = verify_with_key_manager(&manager, &request("not-hex".to_string(), "commitment"))
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_handler_without_key_dir_is_not_found() {
+        let state = AppState::new_for_test();
+        let err = verify_proof_handler(State(state), Json(request("ef".repeat(256), "commitment")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+    }
+}
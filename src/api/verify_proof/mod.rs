@@ -0,0 +1,14 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Proof verification API endpoint
+//!
+//! Provides `/v1/verify-proof` so clients can check a result's EZKL proof
+//! against a published verification key without running a full node.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::verify_proof_handler;
+pub use request::VerifyProofRequest;
+pub use response::VerifyProofResponse;
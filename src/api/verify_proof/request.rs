@@ -0,0 +1,118 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Proof verification API request types
+
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::ezkl::is_valid_key_id;
+
+/// Maximum decoded proof size accepted by /v1/verify-proof (bytes); its hex
+/// form has to fit under the router's default 2MB body limit
+pub const MAX_PROOF_BYTES: usize = 512 * 1024;
+
+/// Maximum number of public inputs accepted by /v1/verify-proof
+pub const MAX_PUBLIC_INPUTS: usize = 16;
+
+/// Request body for POST /v1/verify-proof
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyProofRequest {
+    /// Proof bytes, hex-encoded (optional `0x` prefix)
+    pub proof: String,
+
+    /// Public inputs, each a hex-encoded 32-byte hash
+    pub public_inputs: Vec<String>,
+
+    /// ID of a verification key known to this node
+    pub verifying_key_id: String,
+}
+
+impl VerifyProofRequest {
+    /// Validate the request
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_valid_key_id(&self.verifying_key_id) {
+            return Err("verifying_key_id must be 1-64 letters, digits, '-' or '_'".to_string());
+        }
+        if self.proof.trim().is_empty() {
+            return Err("Proof cannot be empty".to_string());
+        }
+        if self.public_inputs.len() > MAX_PUBLIC_INPUTS {
+            return Err(format!(
+                "Too many public inputs (max {})",
+                MAX_PUBLIC_INPUTS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Decode the hex-encoded proof
+    pub fn decode_proof(&self) -> Result<Vec<u8>, String> {
+        let hex_str = strip_hex_prefix(self.proof.trim());
+        if hex_str.len() / 2 > MAX_PROOF_BYTES {
+            return Err(format!("Proof too large (max {} bytes)", MAX_PROOF_BYTES));
+        }
+        hex::decode(hex_str).map_err(|e| format!("Proof is not valid hex: {}", e))
+    }
+
+    /// Decode the public inputs into 32-byte values
+    pub fn decode_public_inputs(&self) -> Result<Vec<[u8; 32]>, String> {
+        self.public_inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                let bytes = hex::decode(strip_hex_prefix(input.trim()))
+                    .map_err(|e| format!("Public input {} is not valid hex: {}", i, e))?;
+                bytes.try_into().map_err(|bytes: Vec<u8>| {
+                    format!("Public input {} must be 32 bytes, got {}", i, bytes.len())
+                })
+            })
+            .collect()
+    }
+}
+
+fn strip_hex_prefix(s: &str) -> &str {
+    s.strip_prefix("0x").unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(proof: &str, public_inputs: Vec<String>) -> VerifyProofRequest {
+        VerifyProofRequest {
+            proof: proof.to_string(),
+            public_inputs,
+            verifying_key_id: "commitment-v1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_verify_proof_request_deserialization() {
+        let json =
+            r#"{"proof": "0xef00", "public_inputs": [], "verifying_key_id": "commitment-v1"}"#;
+        let request: VerifyProofRequest = serde_json::from_str(json).unwrap();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.decode_proof().unwrap(), vec![0xEF, 0x00]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_key_id() {
+        let mut req = request("ef", vec![]);
+        req.verifying_key_id = "../secrets".to_string();
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_malformed_proof_hex() {
+        assert!(request("0xzz", vec![]).decode_proof().is_err());
+        assert!(request("abc", vec![]).decode_proof().is_err()); // odd length
+    }
+
+    #[test]
+    fn test_public_inputs_must_be_32_bytes() {
+        let ok = request("ef", vec![format!("0x{}", "11".repeat(32))]);
+        assert_eq!(ok.decode_public_inputs().unwrap(), vec![[0x11; 32]]);
+
+        let short = request("ef", vec!["1122".to_string()]);
+        assert!(short.decode_public_inputs().is_err());
+    }
+}
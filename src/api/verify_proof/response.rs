@@ -0,0 +1,42 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Proof verification API response types
+
+use serde::{Deserialize, Serialize};
+
+use crate::ezkl::TrustLevel;
+
+/// Response body for POST /v1/verify-proof
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyProofResponse {
+    /// Whether the proof verified against the key and public inputs
+    pub valid: bool,
+
+    /// How much the verification itself can be trusted: `strict` when the
+    /// proof was checked cryptographically, `relaxed` when this node runs the
+    /// mock verifier, which only checks the proof's structure
+    pub trust_level: TrustLevel,
+}
+
+impl VerifyProofResponse {
+    pub fn new(valid: bool) -> Self {
+        let trust_level = if cfg!(feature = "real-ezkl") {
+            TrustLevel::Strict
+        } else {
+            TrustLevel::Relaxed
+        };
+        Self { valid, trust_level }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_proof_response_serialization() {
+        let json = serde_json::to_value(VerifyProofResponse::new(true)).unwrap();
+        assert_eq!(json["valid"], true);
+        assert!(json["trust_level"] == "strict" || json["trust_level"] == "relaxed");
+    }
+}
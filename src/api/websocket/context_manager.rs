@@ -153,6 +153,8 @@ impl ContextManager {
                             role: "system".to_string(),
                             content: system_prompt.clone(),
                             timestamp: None,
+                            tool_calls: None,
+                            tool_call_id: None,
                         },
                     );
                 }
@@ -483,6 +485,8 @@ impl ContextManager {
                 to_summarize.len()
             ),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         };
 
         let mut result = vec![summary];
@@ -231,6 +231,8 @@ impl ContextStrategy for SummarizeStrategy {
             role: "system".to_string(),
             content: format!("[Summary] {}", summary_text),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         };
 
         // Combine summary with recent messages
@@ -214,6 +214,8 @@ impl WebSocketHandler {
                 role: "user".to_string(),
                 content: inference.prompt.clone(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         )
         .await?;
@@ -234,6 +236,8 @@ impl WebSocketHandler {
                 role: "assistant".to_string(),
                 content: response_content.clone(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         )
         .await?;
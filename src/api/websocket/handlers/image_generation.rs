@@ -10,11 +10,16 @@ use crate::api::generate_image::{
     BillingInfo, GenerateImageRequest, GenerateImageResponse, SafetyInfo,
 };
 use crate::api::server::ApiServer;
-use crate::diffusion::billing::calculate_generation_units;
+use crate::diffusion::billing::ImagePriceTable;
+use crate::diffusion::client::ImageSize;
 use crate::diffusion::prompt_safety::PromptSafetyClassifier;
 use crate::diffusion::safety::SafetyConfig;
+use crate::diffusion::QueuePosition;
+use axum::extract::ws::Message;
+use futures::{Sink, SinkExt};
 use rand::RngCore;
 use serde_json::{json, Value};
+use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 
 /// Build an encrypted response envelope wrapping `inner_json`.
@@ -95,7 +100,7 @@ fn build_encrypted_error(
 /// 3. Validate (empty prompt, invalid size, steps range)
 /// 4. Prompt safety (keyword blocklist)
 /// 5. Get diffusion client
-/// 6. Generate image via sidecar
+/// 6. Join the generation queue and generate image via sidecar
 /// 7. Calculate billing, record rate limit, track billing
 /// 8. Build encrypted response
 pub async fn handle_encrypted_image_generation(
@@ -105,6 +110,71 @@ pub async fn handle_encrypted_image_generation(
     session_id: &str,
     job_id: Option<u64>,
     message_id: Option<&Value>,
+) -> Value {
+    generate_encrypted(
+        server,
+        decrypted_json,
+        session_key,
+        session_id,
+        job_id,
+        message_id,
+        None,
+    )
+    .await
+}
+
+/// Handle an encrypted image generation request, sending encrypted
+/// `image_generation_queued` messages to `ws_sender` while the request
+/// waits for a generation slot.
+///
+/// Returns the final encrypted response, which the caller sends.
+pub async fn handle_encrypted_image_generation_with_updates<S>(
+    server: &ApiServer,
+    decrypted_json: &Value,
+    session_key: &[u8; 32],
+    session_id: &str,
+    job_id: Option<u64>,
+    message_id: Option<&Value>,
+    ws_sender: &mut S,
+) -> Value
+where
+    S: Sink<Message> + Unpin,
+{
+    let (updates_tx, mut updates_rx) = mpsc::unbounded_channel();
+    let generation = generate_encrypted(
+        server,
+        decrypted_json,
+        session_key,
+        session_id,
+        job_id,
+        message_id,
+        Some(updates_tx),
+    );
+    tokio::pin!(generation);
+
+    loop {
+        tokio::select! {
+            response = &mut generation => {
+                while let Ok(update) = updates_rx.try_recv() {
+                    let _ = ws_sender.send(Message::Text(update.to_string())).await;
+                }
+                return response;
+            }
+            Some(update) = updates_rx.recv() => {
+                let _ = ws_sender.send(Message::Text(update.to_string())).await;
+            }
+        }
+    }
+}
+
+async fn generate_encrypted(
+    server: &ApiServer,
+    decrypted_json: &Value,
+    session_key: &[u8; 32],
+    session_id: &str,
+    job_id: Option<u64>,
+    message_id: Option<&Value>,
+    updates: Option<mpsc::UnboundedSender<Value>>,
 ) -> Value {
     // Step 1: Rate limit check
     if !server.image_gen_rate_limiter().check_rate_limit(session_id) {
@@ -192,7 +262,39 @@ pub async fn handle_encrypted_image_generation(
         n: 1,
     };
 
-    let gen_result = match client.generate(&sidecar_request).await {
+    let ticket = match client.queue().try_enqueue() {
+        Ok(ticket) => ticket,
+        Err(e) => {
+            warn!("Rejecting image generation: {}", e);
+            return build_encrypted_error(
+                "QUEUE_FULL",
+                &e.to_string(),
+                session_key,
+                session_id,
+                message_id,
+            );
+        }
+    };
+    let on_update = |position: QueuePosition| {
+        if let Some(ref updates) = updates {
+            let inner = json!({
+                "type": "image_generation_queued",
+                "queuePosition": position.position,
+                "etaMs": position.eta_ms,
+            });
+            let _ = updates.send(build_encrypted_response(
+                &inner,
+                session_key,
+                session_id,
+                message_id,
+            ));
+        }
+    };
+
+    let gen_result = match client
+        .generate_queued(ticket, &sidecar_request, on_update)
+        .await
+    {
         Ok(r) => r,
         Err(e) => {
             error!("Diffusion sidecar generation failed: {}", e);
@@ -209,28 +311,16 @@ pub async fn handle_encrypted_image_generation(
     // Step 7: Calculate billing, record rate limit, track billing
     let (width, height) = (gen_result.width, gen_result.height);
     let processing_time_ms = gen_result.processing_time_ms;
-    let units = calculate_generation_units(width, height, steps, 1.0);
+    let charge =
+        ImagePriceTable::global().charge(&ImageSize { width, height }, steps, &gen_result.model);
+    let units = charge.total_units;
 
     server.image_gen_rate_limiter().record_request(session_id);
 
     if let Some(jid) = job_id {
         server
-            .image_gen_tracker()
-            .track(jid, Some(session_id), units)
+            .track_image_generation(jid, Some(session_id), units)
             .await;
-
-        // Register with checkpoint manager for proof submission & settlement
-        // Converts billing units to token-equivalents (×1000) so the existing
-        // proof interval system works: 0.20 units → 200 tokens
-        if let Some(cm) = server.get_checkpoint_manager().await {
-            let image_tokens = (units * 1000.0).ceil() as u64;
-            if let Err(e) = cm
-                .track_tokens(jid, image_tokens, Some(session_id.to_string()))
-                .await
-            {
-                warn!("Image gen token tracking failed for job {}: {}", jid, e);
-            }
-        }
     }
 
     info!(
@@ -258,12 +348,7 @@ pub async fn handle_encrypted_image_generation(
             output_safe: true,
             safety_level,
         },
-        BillingInfo {
-            generation_units: units,
-            model_multiplier: 1.0,
-            megapixels: (width as f64 * height as f64) / 1_048_576.0,
-            steps,
-        },
+        BillingInfo::from(charge),
         chain_id,
     );
 
@@ -280,12 +365,7 @@ pub async fn handle_encrypted_image_generation(
             "outputSafe": response.safety.output_safe,
             "safetyLevel": response.safety.safety_level,
         },
-        "billing": {
-            "generationUnits": response.billing.generation_units,
-            "modelMultiplier": response.billing.model_multiplier,
-            "megapixels": response.billing.megapixels,
-            "steps": response.billing.steps,
-        },
+        "billing": response.billing,
         "provider": response.provider,
         "chainId": response.chain_id,
         "chainName": response.chain_name,
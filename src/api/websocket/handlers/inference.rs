@@ -10,6 +10,7 @@ use crate::inference::{
 };
 use anyhow::{anyhow, Result};
 use futures::stream::{Stream, StreamExt};
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -99,6 +100,8 @@ impl InferenceHandler {
             .map(|m| ChatMessage {
                 role: m.role.clone(),
                 content: m.content.clone(),
+                tool_calls: None,
+                tool_call_id: None,
             })
             .collect();
 
@@ -110,6 +113,8 @@ impl InferenceHandler {
             chat_messages.push(ChatMessage {
                 role: "user".to_string(),
                 content: prompt.to_string(),
+                tool_calls: None,
+                tool_call_id: None,
             });
         }
 
@@ -132,10 +137,15 @@ impl InferenceHandler {
             min_p: 0.0,
             seed: None,
             stop_sequences: vec![],
+            logit_bias: HashMap::new(),
+            response_format: None,
+            chat_template: None,
+            timeout_ms: None,
             stream: false,
             cancel_flag: None,
             token_sender: None,
             result_sender: None,
+            late_context: None,
         };
 
         // Run inference or use mock
@@ -154,6 +164,8 @@ impl InferenceHandler {
                         token_info: vec![],
                         was_cancelled: false,
                         context_usage: None,
+                        logprobs: None,
+                        system_fingerprint: None,
                     }
                 }
             }
@@ -169,6 +181,8 @@ impl InferenceHandler {
                 token_info: vec![],
                 was_cancelled: false,
                 context_usage: None,
+                logprobs: None,
+                system_fingerprint: None,
             }
         };
 
@@ -225,6 +239,8 @@ impl InferenceHandler {
             .map(|m| ChatMessage {
                 role: m.role.clone(),
                 content: m.content.clone(),
+                tool_calls: None,
+                tool_call_id: None,
             })
             .collect();
 
@@ -232,6 +248,8 @@ impl InferenceHandler {
         chat_messages.push(ChatMessage {
             role: "user".to_string(),
             content: prompt.to_string(),
+            tool_calls: None,
+            tool_call_id: None,
         });
 
         // Create inference request
@@ -249,10 +267,15 @@ impl InferenceHandler {
             min_p: 0.0,
             seed: None,
             stop_sequences: vec![],
+            logit_bias: HashMap::new(),
+            response_format: None,
+            chat_template: None,
+            timeout_ms: None,
             stream: false,
             cancel_flag: None,
             token_sender: None,
             result_sender: None,
+            late_context: None,
         };
 
         // Mock response for now
@@ -410,6 +433,8 @@ impl InferenceHandler {
             .map(|m| ChatMessage {
                 role: m.role.clone(),
                 content: m.content.clone(),
+                tool_calls: None,
+                tool_call_id: None,
             })
             .collect())
     }
@@ -443,7 +468,11 @@ impl InferenceHandler {
                         m.role, m.content.len(), cleaned.len()
                     );
                 }
-                (m.role.clone(), cleaned)
+                let content = crate::inference::tools::message_content(
+                    &cleaned,
+                    m.tool_calls.as_deref(),
+                );
+                (m.role.clone(), content)
             })
             .collect();
 
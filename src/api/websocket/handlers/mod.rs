@@ -10,6 +10,7 @@ pub mod session_init;
 pub mod session_resume;
 
 use super::messages::{ErrorCode, WebSocketMessage};
+use super::session_store::{SessionStore, SessionStoreConfig};
 use anyhow::Result;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -20,6 +21,7 @@ pub struct MessageRouter {
     session_resume_handler: Arc<session_resume::SessionResumeHandler>,
     prompt_handler: Arc<prompt::PromptHandler>,
     response_handler: Arc<response::ResponseHandler>,
+    session_store: Arc<RwLock<SessionStore>>,
 }
 
 impl MessageRouter {
@@ -38,9 +40,18 @@ impl MessageRouter {
             session_resume_handler,
             prompt_handler,
             response_handler,
+            session_store: Arc::new(RwLock::new(
+                SessionStore::new(SessionStoreConfig::default()),
+            )),
         }
     }
 
+    /// Use the server's session store, so cancels reach its generations
+    pub fn with_session_store(mut self, session_store: Arc<RwLock<SessionStore>>) -> Self {
+        self.session_store = session_store;
+        self
+    }
+
     /// Route a message to the appropriate handler
     pub async fn route_message(&self, message: WebSocketMessage) -> Result<WebSocketMessage> {
         match message {
@@ -68,6 +79,7 @@ impl MessageRouter {
                         ),
                         tokens_used: response.total_tokens,
                         message_index: 0,
+                        finish_reason: None,
                     }),
                     Err(e) => Ok(WebSocketMessage::Error {
                         session_id,
@@ -101,6 +113,7 @@ impl MessageRouter {
                         ),
                         tokens_used: response.total_tokens,
                         message_index: response.last_message_index,
+                        finish_reason: None,
                     }),
                     Err(e) => Ok(WebSocketMessage::Error {
                         session_id,
@@ -121,12 +134,18 @@ impl MessageRouter {
                     .await
                 {
                     Ok(_) => {
+                        self.session_store
+                            .read()
+                            .await
+                            .begin_inference(&session_id, Some(message_index))
+                            .await;
                         // Start streaming response
                         Ok(WebSocketMessage::Response {
                             session_id,
                             content: "Processing...".to_string(),
                             tokens_used: 0,
                             message_index: message_index + 1,
+                            finish_reason: None,
                         })
                     }
                     Err(e) => Ok(WebSocketMessage::Error {
@@ -137,6 +156,33 @@ impl MessageRouter {
                 }
             }
 
+            WebSocketMessage::Cancel {
+                session_id,
+                message_index,
+            } => {
+                // A cancel for an earlier message must not stop the current one
+                match self
+                    .session_store
+                    .read()
+                    .await
+                    .cancel_inference(&session_id, Some(message_index))
+                    .await
+                {
+                    Some(tokens_used) => Ok(WebSocketMessage::Response {
+                        session_id,
+                        content: String::new(),
+                        tokens_used,
+                        message_index,
+                        finish_reason: Some("cancelled".to_string()),
+                    }),
+                    None => Ok(WebSocketMessage::Error {
+                        session_id,
+                        error: format!("No inference in flight for message {}", message_index),
+                        code: ErrorCode::InvalidMessageIndex,
+                    }),
+                }
+            }
+
             WebSocketMessage::SessionEnd { session_id } => {
                 // Clean up session
                 self.session_init_handler.cleanup_session(&session_id).await;
@@ -157,3 +203,77 @@ impl Default for MessageRouter {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::websocket::session::SessionConfig;
+    use std::sync::atomic::Ordering;
+
+    #[tokio::test]
+    async fn test_cancel_stops_matching_inference_and_reports_tokens() {
+        let store = Arc::new(RwLock::new(
+            SessionStore::new(SessionStoreConfig::default()),
+        ));
+        store
+            .write()
+            .await
+            .create_session_with_id("s1".to_string(), SessionConfig::default())
+            .await
+            .unwrap();
+        let router = MessageRouter::new().with_session_store(store.clone());
+
+        let flag = store
+            .read()
+            .await
+            .begin_inference("s1", Some(3))
+            .await
+            .unwrap();
+        let counter = store
+            .read()
+            .await
+            .inference_token_counter("s1")
+            .await
+            .unwrap();
+        counter.fetch_add(7, Ordering::AcqRel);
+
+        // A late cancel for the previous message leaves the generation running
+        let late = router
+            .route_message(WebSocketMessage::Cancel {
+                session_id: "s1".to_string(),
+                message_index: 2,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            late,
+            WebSocketMessage::Error {
+                code: ErrorCode::InvalidMessageIndex,
+                ..
+            }
+        ));
+        assert!(!flag.load(Ordering::Acquire));
+
+        let response = router
+            .route_message(WebSocketMessage::Cancel {
+                session_id: "s1".to_string(),
+                message_index: 3,
+            })
+            .await
+            .unwrap();
+        match response {
+            WebSocketMessage::Response {
+                tokens_used,
+                message_index,
+                finish_reason,
+                ..
+            } => {
+                assert_eq!(tokens_used, 7);
+                assert_eq!(message_index, 3);
+                assert_eq!(finish_reason.as_deref(), Some("cancelled"));
+            }
+            other => panic!("expected cancelled response, got {:?}", other),
+        }
+        assert!(flag.load(Ordering::Acquire));
+    }
+}
@@ -7,6 +7,9 @@ use crate::api::websocket::message_types::{
     VectorSearchResult,
 };
 use crate::api::websocket::session::{VectorLoadingStatus, WebSocketSession};
+use crate::rag::reranker::{rerank, Reranker};
+use crate::rag::session_vector_store::estimate_entry_bytes;
+use crate::rag::SearchResult;
 use anyhow::{anyhow, Result};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
@@ -40,6 +43,14 @@ pub fn handle_upload_vectors(
     let arc_ptr = Arc::as_ptr(&vector_store);
     info!("📦 handle_upload_vectors: Arc ptr={:?}", arc_ptr);
 
+    // Reject a batch no amount of eviction could make room for
+    let batch_bytes: usize = request
+        .vectors
+        .iter()
+        .map(|v| estimate_entry_bytes(&v.id, &v.vector, &v.metadata))
+        .sum();
+    vector_store.lock().unwrap().check_load(batch_bytes)?;
+
     // If replace=true, clear existing vectors first
     if request.replace {
         let mut store = vector_store.lock().unwrap();
@@ -104,6 +115,27 @@ pub fn handle_search_vectors(
     session: &Arc<Mutex<WebSocketSession>>,
     request: SearchVectorsRequest,
 ) -> Result<SearchVectorsResponse> {
+    handle_search_vectors_with_reranker(session, request, None)
+}
+
+/// Handles vector search requests, reranking the results when a reranker is
+/// configured and the request carries `queryText`
+///
+/// The top `rerankTopK` candidates (at least `k`) are retrieved by vector
+/// similarity, then reordered by the reranker and cut to `k`. Without a
+/// reranker the results are the plain top-k in vector order.
+pub fn handle_search_vectors_with_reranker(
+    session: &Arc<Mutex<WebSocketSession>>,
+    request: SearchVectorsRequest,
+    reranker: Option<&dyn Reranker>,
+) -> Result<SearchVectorsResponse> {
+    let reranking = reranker.is_some() && request.query_text.is_some();
+    let candidates = if reranking {
+        request.rerank_top_k.unwrap_or(request.k).max(request.k)
+    } else {
+        request.k
+    };
+
     // Start timer for performance tracking
     let start = Instant::now();
 
@@ -162,7 +194,24 @@ pub fn handle_search_vectors(
 
         // Perform HNSW search
         let threshold = request.threshold.unwrap_or(0.0);
-        let search_results = index.search(&request.query_vector, request.k, threshold)?;
+        let search_results: Vec<SearchResult> = index
+            .search(&request.query_vector, candidates, threshold)?
+            .into_iter()
+            .map(|r| SearchResult {
+                id: r.id,
+                score: r.score,
+                metadata: r.metadata,
+                vector_score: Some(r.score),
+                lexical_score: None,
+                rerank_score: None,
+            })
+            .collect();
+        let search_results = rerank(
+            reranker,
+            request.query_text.as_deref(),
+            search_results,
+            request.k,
+        )?;
 
         // Calculate search time
         let search_time_ms = start.elapsed().as_secs_f64() * 1000.0;
@@ -171,14 +220,7 @@ pub fn handle_search_vectors(
         let total_vectors = index.vector_count();
 
         // Convert to response format
-        let results: Vec<VectorSearchResult> = search_results
-            .into_iter()
-            .map(|r| VectorSearchResult {
-                id: r.id,
-                score: r.score,
-                metadata: r.metadata,
-            })
-            .collect();
+        let results = to_response_results(search_results);
 
         return Ok(SearchVectorsResponse {
             msg_type: "searchVectorsResponse".to_string(),
@@ -218,11 +260,17 @@ pub fn handle_search_vectors(
 
         // Use search_with_filter if metadata filter provided
         if let Some(ref filter) = request.metadata_filter {
-            store.search_with_filter(request.query_vector, request.k, filter.clone())?
+            store.search_with_filter(request.query_vector, candidates, filter.clone())?
         } else {
-            store.search(request.query_vector, request.k, request.threshold)?
+            store.search(request.query_vector, candidates, request.threshold)?
         }
     };
+    let search_results = rerank(
+        reranker,
+        request.query_text.as_deref(),
+        search_results,
+        request.k,
+    )?;
 
     // Calculate search time
     let search_time_ms = start.elapsed().as_secs_f64() * 1000.0;
@@ -234,14 +282,7 @@ pub fn handle_search_vectors(
     };
 
     // Convert to response format
-    let results: Vec<VectorSearchResult> = search_results
-        .into_iter()
-        .map(|r| VectorSearchResult {
-            id: r.id,
-            score: r.score,
-            metadata: r.metadata,
-        })
-        .collect();
+    let results = to_response_results(search_results);
 
     Ok(SearchVectorsResponse {
         msg_type: "searchVectorsResponse".to_string(),
@@ -252,6 +293,19 @@ pub fn handle_search_vectors(
     })
 }
 
+/// Wire format keeps the vector similarity as `score`
+fn to_response_results(results: Vec<SearchResult>) -> Vec<VectorSearchResult> {
+    results
+        .into_iter()
+        .map(|r| VectorSearchResult {
+            id: r.id,
+            score: r.vector_score.unwrap_or(r.score),
+            metadata: r.metadata,
+            rerank_score: r.rerank_score,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,6 +360,8 @@ mod tests {
             k: 1,
             threshold: None,
             metadata_filter: None,
+            query_text: None,
+            rerank_top_k: None,
         };
 
         let response = handle_search_vectors(&session, search_req).unwrap();
@@ -314,6 +370,74 @@ mod tests {
         assert!(response.search_time_ms >= 0.0);
     }
 
+    /// Prefers passages that contain the query verbatim
+    struct ContainsQuery;
+
+    impl Reranker for ContainsQuery {
+        fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f32>> {
+            Ok(passages
+                .iter()
+                .map(|p| if p.contains(query) { 1.0 } else { 0.0 })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_handle_search_vectors_reranks_candidates() {
+        let mut session =
+            WebSocketSession::with_config("test".to_string(), SessionConfig::default());
+        session.enable_rag(100);
+        let session = Arc::new(Mutex::new(session));
+
+        let mut near = vec![0.0; 384];
+        near[0] = 1.0;
+        let mut far = vec![0.0; 384];
+        far[0] = 1.0;
+        far[1] = 1.0;
+        let upload_req = UploadVectorsRequest {
+            request_id: None,
+            vectors: vec![
+                crate::api::websocket::message_types::VectorUpload {
+                    id: "near".to_string(),
+                    vector: near.clone(),
+                    metadata: json!({"text": "Dolphins are mammals."}),
+                },
+                crate::api::websocket::message_types::VectorUpload {
+                    id: "far".to_string(),
+                    vector: far,
+                    metadata: json!({"text": "The blue whale is the largest animal."}),
+                },
+            ],
+            replace: false,
+        };
+        handle_upload_vectors(&session, upload_req).unwrap();
+
+        let search_req = SearchVectorsRequest {
+            request_id: None,
+            query_vector: near,
+            k: 1,
+            threshold: None,
+            metadata_filter: None,
+            query_text: Some("whale".to_string()),
+            rerank_top_k: Some(2),
+        };
+
+        let plain = handle_search_vectors(&session, search_req.clone()).unwrap();
+        assert_eq!(plain.results[0].id, "near");
+        assert!(plain.results[0].rerank_score.is_none());
+
+        let reranked =
+            handle_search_vectors_with_reranker(&session, search_req, Some(&ContainsQuery))
+                .unwrap();
+        assert_eq!(reranked.results.len(), 1);
+        assert_eq!(reranked.results[0].id, "far");
+        assert_eq!(reranked.results[0].rerank_score, Some(1.0));
+        assert!(
+            reranked.results[0].score < 1.0,
+            "score stays the cosine similarity"
+        );
+    }
+
     #[test]
     fn test_handle_upload_rag_not_enabled() {
         let session = WebSocketSession::with_config("test".to_string(), SessionConfig::default());
@@ -345,6 +469,8 @@ mod tests {
             k: 1,
             threshold: None,
             metadata_filter: None,
+            query_text: None,
+            rerank_top_k: None,
         };
 
         let result = handle_search_vectors(&session, request);
@@ -394,6 +520,8 @@ mod tests {
             k: 5,
             threshold: None,
             metadata_filter: None,
+            query_text: None,
+            rerank_top_k: None,
         };
         let search_response = handle_search_vectors(&arc2, search_req).unwrap();
 
@@ -13,6 +13,7 @@ use crate::inference::engine::{
     EngineConfig, InferenceRequest as BaseRequest, InferenceResult as BaseResult,
     LlmEngine as BaseEngine,
 };
+use crate::inference::speculative::DraftModelConfig;
 
 /// Configuration for inference engine
 #[derive(Debug, Clone)]
@@ -117,6 +118,8 @@ impl InferenceEngine {
             model_eviction_policy: "lru".to_string(),
             kv_cache_type_k: std::env::var("KV_CACHE_TYPE").ok(),
             kv_cache_type_v: std::env::var("KV_CACHE_TYPE").ok(),
+            default_timeout_ms: EngineConfig::timeout_from_env(),
+            draft_model: DraftModelConfig::from_env(),
         };
 
         // Create base engine
@@ -188,10 +191,15 @@ impl InferenceEngine {
             min_p: 0.0,
             seed: None,
             stop_sequences: vec![],
+            logit_bias: HashMap::new(),
+            response_format: None,
+            chat_template: None,
+            timeout_ms: None,
             stream: false,
             cancel_flag: None,
             token_sender: None,
             result_sender: None,
+            late_context: None,
         };
 
         // Generate with engine
@@ -247,10 +255,15 @@ impl InferenceEngine {
             min_p: 0.0,
             seed: None,
             stop_sequences: vec![],
+            logit_bias: HashMap::new(),
+            response_format: None,
+            chat_template: None,
+            timeout_ms: None,
             stream: false,
             cancel_flag: None,
             token_sender: None,
             result_sender: None,
+            late_context: None,
         };
 
         // For streaming, we need to use the engine's stream method
@@ -158,6 +158,7 @@ impl JobVerifier {
                     private_key: None,
                     max_reconnection_attempts: 3,
                     reconnection_delay: Duration::from_secs(1),
+                    ..Default::default()
                 };
 
                 match Web3Client::new(web3_config).await {
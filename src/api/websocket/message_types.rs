@@ -655,6 +655,15 @@ pub struct SearchVectorsRequest {
     /// Optional metadata filter (JSON query object)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata_filter: Option<Value>,
+
+    /// Raw query text; required for reranking
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_text: Option<String>,
+
+    /// Number of vector candidates to rerank down to `k` (max: MAX_SEARCH_K).
+    /// Ignored when the node has no reranker model configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_top_k: Option<usize>,
 }
 
 /// Response containing search results
@@ -691,6 +700,10 @@ pub struct VectorSearchResult {
 
     /// Vector metadata
     pub metadata: Value,
+
+    /// Cross-encoder relevance score, present when results were reranked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
 }
 
 impl SearchVectorsRequest {
@@ -705,6 +718,16 @@ impl SearchVectorsRequest {
             ));
         }
 
+        if let Some(rerank_top_k) = self.rerank_top_k {
+            if rerank_top_k > MAX_SEARCH_K {
+                return Err(anyhow!(
+                    "Rerank top-k too large: {} (max: {})",
+                    rerank_top_k,
+                    MAX_SEARCH_K
+                ));
+            }
+        }
+
         // Validate query vector dimensions
         if self.query_vector.len() != 384 {
             return Err(anyhow!(
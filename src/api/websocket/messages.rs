@@ -170,6 +170,15 @@ pub enum WebSocketMessage {
         content: String,
         tokens_used: u32,
         message_index: u32,
+        /// Why generation ended ("stop", "length", "cancelled"), if known
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        finish_reason: Option<String>,
+    },
+
+    /// Cancel the in-flight generation for a prompt (e.g. the user closed the tab)
+    Cancel {
+        session_id: String,
+        message_index: u32,
     },
 
     /// Error message
@@ -181,6 +190,27 @@ pub enum WebSocketMessage {
 
     /// End the session
     SessionEnd { session_id: String },
+
+    /// Vectors loaded so far while a vector database streams in from S5
+    VectorLoadProgress {
+        session_id: String,
+        loaded: usize,
+        total: usize,
+    },
+}
+
+/// Whether a raw client message asks to stop the session's generation:
+/// `cancel` (`WebSocketMessage::Cancel`) or the older `stream_cancel`
+pub fn is_cancel_message(message: &serde_json::Value) -> bool {
+    message["type"] == "cancel" || message["type"] == "stream_cancel"
+}
+
+/// Client message index carried by a raw prompt or cancel message, if any
+pub fn message_index_of(message: &serde_json::Value) -> Option<u32> {
+    message["message_index"]
+        .as_u64()
+        .or_else(|| message["messageIndex"].as_u64())
+        .and_then(|index| u32::try_from(index).ok())
 }
 
 impl WebSocketMessage {
@@ -191,8 +221,10 @@ impl WebSocketMessage {
             WebSocketMessage::SessionResume { session_id, .. } => session_id,
             WebSocketMessage::Prompt { session_id, .. } => session_id,
             WebSocketMessage::Response { session_id, .. } => session_id,
+            WebSocketMessage::Cancel { session_id, .. } => session_id,
             WebSocketMessage::Error { session_id, .. } => session_id,
             WebSocketMessage::SessionEnd { session_id } => session_id,
+            WebSocketMessage::VectorLoadProgress { session_id, .. } => session_id,
         }
     }
 
@@ -203,8 +235,10 @@ impl WebSocketMessage {
             WebSocketMessage::SessionResume { .. } => "session_resume",
             WebSocketMessage::Prompt { .. } => "prompt",
             WebSocketMessage::Response { .. } => "response",
+            WebSocketMessage::Cancel { .. } => "cancel",
             WebSocketMessage::Error { .. } => "error",
             WebSocketMessage::SessionEnd { .. } => "session_end",
+            WebSocketMessage::VectorLoadProgress { .. } => "vector_load_progress",
         }
     }
 }
@@ -341,6 +375,48 @@ mod tests {
         assert_eq!(msg.message_type(), "prompt");
     }
 
+    #[test]
+    fn test_cancel_message_deserialization() {
+        let json = json!({
+            "type": "cancel",
+            "session_id": "test",
+            "message_index": 3
+        });
+
+        let msg: WebSocketMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(msg.message_type(), "cancel");
+        assert_eq!(msg.session_id(), "test");
+    }
+
+    #[test]
+    fn test_response_finish_reason_is_optional() {
+        let json = json!({
+            "type": "response",
+            "session_id": "test",
+            "content": "Hi",
+            "tokens_used": 1,
+            "message_index": 1
+        });
+        let msg: WebSocketMessage = serde_json::from_value(json).unwrap();
+        assert!(matches!(
+            msg,
+            WebSocketMessage::Response {
+                finish_reason: None,
+                ..
+            }
+        ));
+
+        let msg = WebSocketMessage::Response {
+            session_id: "test".to_string(),
+            content: "partial".to_string(),
+            tokens_used: 4,
+            message_index: 2,
+            finish_reason: Some("cancelled".to_string()),
+        };
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["finish_reason"], "cancelled");
+    }
+
     #[test]
     fn test_error_code_serialization() {
         let code = ErrorCode::SessionNotFound;
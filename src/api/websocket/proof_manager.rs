@@ -90,6 +90,7 @@ impl ProofManager {
     }
 
     /// Generate a proof for the given inference result
+    #[tracing::instrument(name = "proof", skip_all, fields(model = %model))]
     pub async fn generate_proof(
         &self,
         model: &str,
@@ -248,6 +248,8 @@ impl SessionSerializer {
                     role: msg_data["role"].as_str().unwrap_or("user").to_string(),
                     content: msg_data["content"].as_str().unwrap_or("").to_string(),
                     timestamp: msg_data["timestamp"].as_i64(),
+                    tool_calls: None,
+                    tool_call_id: None,
                 };
                 session.add_message(message)?;
             }
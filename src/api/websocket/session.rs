@@ -3,12 +3,13 @@
 use crate::api::websocket::message_types::VectorDatabaseInfo;
 use crate::config::chains::ChainRegistry;
 use crate::job_processor::Message;
+use crate::rag::memory::{RagMemoryBudget, RagMemoryLimits};
 use crate::rag::session_vector_store::SessionVectorStore;
 use crate::vector::hnsw::HnswIndex;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicU32};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::sync::mpsc::UnboundedSender;
@@ -135,6 +136,10 @@ pub struct WebSocketSession {
     pub encryption_key: Option<Vec<u8>>,
     /// Cancellation flag for active inference — shared with generation loop
     pub inference_cancel_flag: Arc<AtomicBool>,
+    /// Client message index of the active inference, if the prompt carried one
+    pub inference_message_index: Arc<Mutex<Option<u32>>>,
+    /// Tokens streamed so far by the active inference
+    pub inference_tokens: Arc<AtomicU32>,
     /// Cancellation token for background vector loading task
     /// Used to gracefully cancel loading when session disconnects
     pub cancel_token: CancellationToken,
@@ -170,6 +175,8 @@ impl WebSocketSession {
             vector_index: None,
             encryption_key: None,
             inference_cancel_flag: Arc::new(AtomicBool::new(false)),
+            inference_message_index: Arc::new(Mutex::new(None)),
+            inference_tokens: Arc::new(AtomicU32::new(0)),
             cancel_token: CancellationToken::new(),
             tx: None,
         }
@@ -300,6 +307,19 @@ impl WebSocketSession {
         self.vector_store = Some(Arc::new(Mutex::new(store)));
     }
 
+    /// Enable RAG with per-session memory caps and a node-wide budget;
+    /// least recently used vectors are evicted once a cap is reached
+    pub fn enable_rag_with_limits(
+        &mut self,
+        max_vectors: usize,
+        limits: RagMemoryLimits,
+        budget: Arc<RagMemoryBudget>,
+    ) {
+        let store = SessionVectorStore::new(self.id.clone(), max_vectors)
+            .with_memory_limits(limits, budget);
+        self.vector_store = Some(Arc::new(Mutex::new(store)));
+    }
+
     /// Get the vector store for this session (if RAG enabled)
     ///
     /// # Returns
@@ -499,6 +519,8 @@ impl WebSocketSession {
                     .duration_since(std::time::UNIX_EPOCH)?
                     .as_secs() as i64,
             ),
+            tool_calls: None,
+            tool_call_id: None,
         };
         self.conversation_history.push(message.clone());
         self.messages.write().await.push(message);
@@ -532,6 +554,8 @@ mod tests {
             role: "user".to_string(),
             content: "Hello".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         };
 
         session.add_message(message).unwrap();
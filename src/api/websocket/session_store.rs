@@ -3,9 +3,11 @@
 use super::persistence::{PersistenceConfig, SessionPersistence};
 use super::session::{SessionConfig, SessionMetrics, WebSocketSession};
 use crate::job_processor::Message;
+use crate::rag::memory::{RagMemoryBudget, RagMemoryLimits};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -40,6 +42,8 @@ pub struct SessionStore {
     config: SessionStoreConfig,
     sessions: Arc<RwLock<HashMap<String, WebSocketSession>>>,
     persistence: Option<Arc<SessionPersistence>>,
+    /// Memory caps applied to RAG sessions, with the budget they share
+    rag_memory: Option<(RagMemoryLimits, Arc<RagMemoryBudget>)>,
 }
 
 impl SessionStore {
@@ -48,9 +52,23 @@ impl SessionStore {
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
             persistence: None,
+            rag_memory: None,
         }
     }
 
+    /// Cap the memory of RAG sessions created by `get_or_create_rag_session`,
+    /// each on its own and all together
+    pub fn with_rag_memory_limits(mut self, limits: RagMemoryLimits) -> Self {
+        let budget = Arc::new(RagMemoryBudget::new(limits.max_global_bytes));
+        self.rag_memory = Some((limits, budget));
+        self
+    }
+
+    /// Node-wide RAG budget, when memory limits are configured
+    pub fn rag_memory_budget(&self) -> Option<Arc<RagMemoryBudget>> {
+        self.rag_memory.as_ref().map(|(_, budget)| budget.clone())
+    }
+
     pub fn with_persistence(
         config: SessionStoreConfig,
         persistence_config: PersistenceConfig,
@@ -60,6 +78,7 @@ impl SessionStore {
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
             persistence: Some(Arc::new(persistence)),
+            rag_memory: None,
         }
     }
 
@@ -194,6 +213,60 @@ impl SessionStore {
         sessions.get(session_id).cloned()
     }
 
+    /// Cancel flag for a new generation in `session_id`, cleared so that a
+    /// cancel meant for an earlier generation doesn't stop it. The decode
+    /// loop checks it between tokens. `message_index` is the client index of
+    /// the prompt being answered, used to match later cancels against it.
+    pub async fn begin_inference(
+        &self,
+        session_id: &str,
+        message_index: Option<u32>,
+    ) -> Option<Arc<AtomicBool>> {
+        let sessions = self.sessions.read().await;
+        let session = sessions.get(session_id)?;
+        if let Ok(mut index) = session.inference_message_index.lock() {
+            *index = message_index;
+        }
+        session.inference_tokens.store(0, Ordering::Release);
+        let flag = session.inference_cancel_flag.clone();
+        flag.store(false, Ordering::Release);
+        Some(flag)
+    }
+
+    /// Counter the streaming loop bumps for every token it sends, so a
+    /// cancel can report how many tokens the client was billed for.
+    pub async fn inference_token_counter(&self, session_id: &str) -> Option<Arc<AtomicU32>> {
+        let sessions = self.sessions.read().await;
+        Some(sessions.get(session_id)?.inference_tokens.clone())
+    }
+
+    /// Stops the session's in-flight generation at its next token and
+    /// returns the number of tokens streamed so far. A `message_index` that
+    /// doesn't match the in-flight prompt is a late cancel for an earlier
+    /// message and is ignored; `None` (legacy `stream_cancel`) always
+    /// matches. Returns `None` if the session is unknown or the index
+    /// doesn't match.
+    pub async fn cancel_inference(
+        &self,
+        session_id: &str,
+        message_index: Option<u32>,
+    ) -> Option<u32> {
+        let sessions = self.sessions.read().await;
+        let session = sessions.get(session_id)?;
+        let in_flight = session
+            .inference_message_index
+            .lock()
+            .ok()
+            .and_then(|index| *index);
+        if let (Some(requested), Some(current)) = (message_index, in_flight) {
+            if requested != current {
+                return None;
+            }
+        }
+        session.inference_cancel_flag.store(true, Ordering::Release);
+        Some(session.inference_tokens.load(Ordering::Acquire))
+    }
+
     pub async fn get_session_mut(&self, session_id: &str) -> Option<WebSocketSession> {
         let sessions = self.sessions.read().await;
         sessions.get(session_id).cloned()
@@ -306,6 +379,9 @@ impl SessionStore {
 
     /// Get or create a session and enable RAG with specified max vectors
     ///
+    /// With memory limits configured, the session's store also evicts
+    /// against them and draws from the shared budget.
+    ///
     /// This is a convenience method for RAG functionality that:
     /// 1. Creates session if it doesn't exist
     /// 2. Enables RAG on the session if not already enabled
@@ -332,7 +408,12 @@ impl SessionStore {
             .ok_or_else(|| anyhow!("Session not found"))?;
 
         if session.get_vector_store().is_none() {
-            session.enable_rag(max_vectors);
+            match &self.rag_memory {
+                Some((limits, budget)) => {
+                    session.enable_rag_with_limits(max_vectors, *limits, budget.clone())
+                }
+                None => session.enable_rag(max_vectors),
+            }
         }
 
         // Return a clone (Arc is shallow-copied, so vector store is shared)
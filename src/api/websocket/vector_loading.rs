@@ -10,7 +10,8 @@
 //! - **Non-Blocking**: session_init returns immediately, loading happens in background
 //! - **5-Minute Timeout**: Automatic cancellation after 300 seconds
 //! - **Graceful Cancellation**: Responds to CancellationToken on session disconnect
-//! - **Progress Updates**: Sends real-time status via WebSocket
+//! - **Progress Updates**: Sends real-time status via WebSocket, including a
+//!   `vector_load_progress` message with the vectors loaded after each chunk
 //! - **HNSW Indexing**: Builds searchable index after loading completes
 //! - **Error Handling**: Updates session status on failures
 //!
@@ -35,10 +36,12 @@
 use crate::api::websocket::message_types::{
     LoadingErrorCode, LoadingProgressMessage, MessageType, VectorDatabaseInfo, WebSocketMessage,
 };
+use crate::api::websocket::messages::WebSocketMessage as SessionMessage;
 use crate::api::websocket::session::{VectorLoadingStatus, WebSocketSession};
 use crate::api::websocket::session_store::SessionStore;
 use crate::api::websocket::vector_loading_errors::VectorLoadingError;
 use crate::job_processor::Message;
+use crate::rag::errors::VectorLoadError;
 use crate::rag::vector_loader::{LoadProgress, VectorLoader};
 use crate::storage::enhanced_s5_client::{EnhancedS5Client, S5Config};
 use crate::storage::s5_client::EnhancedS5Backend;
@@ -265,7 +268,8 @@ async fn load_vectors_with_cancellation(
         Box::new(s5_backend),
         5, // max parallel chunks
         VECTOR_LOADING_TIMEOUT,
-    );
+    )
+    .with_cancellation(cancel_token.clone());
 
     // Create progress channel for VectorLoader
     let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel(10);
@@ -284,6 +288,23 @@ async fn load_vectors_with_cancellation(
 
             // Convert LoadProgress to LoadingProgressMessage
             let progress_msg = match progress {
+                LoadProgress::VectorsLoaded { loaded, total } => {
+                    let msg = SessionMessage::VectorLoadProgress {
+                        session_id: session_id_clone.clone(),
+                        loaded,
+                        total,
+                    };
+                    if let Err(e) =
+                        send_to_session(&session_id_clone, &session_store_clone, &msg).await
+                    {
+                        warn!(
+                            session_id = %session_id_clone,
+                            error = %e,
+                            "Failed to send vector load progress message"
+                        );
+                    }
+                    continue;
+                }
                 LoadProgress::ManifestDownloaded => LoadingProgressMessage::ManifestDownloaded,
                 LoadProgress::ChunkDownloaded { chunk_id, total } => {
                     LoadingProgressMessage::ChunkDownloaded { chunk_id, total }
@@ -311,31 +332,38 @@ async fn load_vectors_with_cancellation(
         }
     });
 
-    // Load vectors with cancellation check
-    let vectors = tokio::select! {
-        result = loader.load_vectors_from_s5(
+    // Load vectors; the loader frees loaded chunks itself if cancelled
+    let vectors = match loader
+        .load_vectors_from_s5(
             &vdb_info.manifest_path,
             &vdb_info.user_address,
             &session_key,
             Some(progress_tx.clone()),
-        ) => {
-            // Convert VectorLoadError to VectorLoadingError using From trait
-            result.map_err(|e| {
-                let loading_error: VectorLoadingError = e.into();
-                anyhow::anyhow!("{}", loading_error)
-            })?
-        }
-        _ = cancel_token.cancelled() => {
-            warn!(session_id = %session_id, "⚠️  Vector loading cancelled by disconnect");
+        )
+        .await
+    {
+        Ok(vectors) => vectors,
+        Err(VectorLoadError::Cancelled { loaded_vectors }) => {
+            warn!(
+                session_id = %session_id,
+                loaded_vectors,
+                "⚠️  Vector loading cancelled by disconnect"
+            );
             update_session_status(
                 &session_id,
                 &session_store,
                 VectorLoadingStatus::Error {
                     error: "Loading cancelled by client disconnect".to_string(),
                 },
-            ).await?;
+            )
+            .await?;
             return Ok(());
         }
+        // Convert VectorLoadError to VectorLoadingError using From trait
+        Err(e) => {
+            let loading_error: VectorLoadingError = e.into();
+            return Err(anyhow::anyhow!("{}", loading_error));
+        }
     };
 
     let vector_count = vectors.len();
@@ -447,25 +475,36 @@ async fn send_loading_progress(
         "Sending loading progress message"
     );
 
+    // Serialize LoadingProgressMessage to JSON
+    let progress_payload = serde_json::to_value(&progress)?;
+
+    // Create WebSocket message
+    let ws_message = WebSocketMessage {
+        msg_type: MessageType::VectorLoadingProgress,
+        session_id: Some(session_id.to_string()),
+        payload: progress_payload,
+    };
+
+    send_to_session(session_id, session_store, &ws_message).await
+}
+
+/// Send a JSON message to the client over the session's tx channel
+async fn send_to_session<T: serde::Serialize>(
+    session_id: &str,
+    session_store: &Arc<RwLock<SessionStore>>,
+    message: &T,
+) -> Result<()> {
     // Get session's tx channel
     let store = session_store.read().await;
     if let Some(session) = store.get_session(session_id).await {
         if let Some(ref tx) = session.tx {
-            // Serialize LoadingProgressMessage to JSON
-            let progress_payload = serde_json::to_value(&progress)?;
-
-            // Create WebSocket message
-            let ws_message = WebSocketMessage {
-                msg_type: MessageType::VectorLoadingProgress,
-                session_id: Some(session_id.to_string()),
-                payload: progress_payload,
-            };
-
             // Convert to job_processor Message (role: "system", content: JSON string)
             let msg = Message {
                 role: "system".to_string(),
-                content: serde_json::to_string(&ws_message)?,
+                content: serde_json::to_string(message)?,
                 timestamp: Some(chrono::Utc::now().timestamp_millis()),
+                tool_calls: None,
+                tool_call_id: None,
             };
 
             // Send via channel
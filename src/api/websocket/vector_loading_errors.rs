@@ -301,6 +301,9 @@ impl From<VectorLoadError> for VectorLoadingError {
             VectorLoadError::ChunkValidationFailed { reason, .. } => {
                 Self::InternalError(anyhow::anyhow!("Chunk validation failed: {reason}"))
             }
+            VectorLoadError::Cancelled { .. } => {
+                Self::InternalError(anyhow::anyhow!("Loading cancelled"))
+            }
             VectorLoadError::IoError(e) => Self::InternalError(anyhow::anyhow!("I/O error: {e}")),
             VectorLoadError::Other(msg) => Self::InternalError(anyhow::anyhow!("{msg}")),
         }
@@ -7,8 +7,12 @@ pub mod registration_metrics;
 pub mod registration_monitor;
 
 pub use chain_config::{ChainConfig, ChainRegistry, ContractAddresses, TokenInfo};
-pub use multi_chain_registrar::{MultiChainRegistrar, NodeMetadata, RegistrationStatus};
-pub use registration_health::{BalanceHealth, ConnectivityHealth, RegistrationHealthChecker};
+pub use multi_chain_registrar::{
+    inconsistent_chains, MultiChainRegistrar, NodeMetadata, RegistrationStatus,
+};
+pub use registration_health::{
+    BalanceHealth, ConnectivityHealth, GasBalanceSignal, RegistrationHealthChecker,
+};
 pub use registration_metrics::{AggregatedMetrics, RegistrationMetrics};
 pub use registration_monitor::{
     HealthIssue, MonitorConfig, RegistrationHealth, RegistrationMonitor,
@@ -44,6 +44,32 @@ pub struct MultiChainRegistrar {
     providers: HashMap<u64, Arc<Provider<Http>>>,
     signers: HashMap<u64, Arc<SignerMiddleware<Provider<Http>, LocalWallet>>>,
     registration_status: Arc<RwLock<HashMap<u64, RegistrationStatus>>>,
+    all_or_nothing: bool,
+}
+
+/// Chains the node is registered on while registration failed elsewhere
+///
+/// Empty unless at least one chain is Pending/Confirmed and another Failed.
+pub fn inconsistent_chains(statuses: &HashMap<u64, RegistrationStatus>) -> Vec<u64> {
+    let has_failure = statuses
+        .values()
+        .any(|s| matches!(s, RegistrationStatus::Failed { .. }));
+    if !has_failure {
+        return Vec::new();
+    }
+
+    let mut chains: Vec<u64> = statuses
+        .iter()
+        .filter(|(_, s)| {
+            matches!(
+                s,
+                RegistrationStatus::Pending { .. } | RegistrationStatus::Confirmed { .. }
+            )
+        })
+        .map(|(chain_id, _)| *chain_id)
+        .collect();
+    chains.sort_unstable();
+    chains
 }
 
 impl MultiChainRegistrar {
@@ -104,9 +130,16 @@ impl MultiChainRegistrar {
             providers,
             signers,
             registration_status,
+            all_or_nothing: false,
         })
     }
 
+    /// Roll back every successful chain if registration fails on any chain
+    pub fn with_all_or_nothing(mut self, enabled: bool) -> Self {
+        self.all_or_nothing = enabled;
+        self
+    }
+
     /// Check FAB token balance for registration
     async fn check_fab_balance(&self, chain_id: u64) -> Result<U256> {
         let provider = self
@@ -420,13 +453,19 @@ impl MultiChainRegistrar {
                             receipt.block_number.unwrap_or_default()
                         );
 
+                        // A rollback may have replaced the Pending status meanwhile
                         let mut status = registration_status.write().await;
-                        status.insert(
-                            chain_id_copy,
-                            RegistrationStatus::Confirmed {
-                                block_number: receipt.block_number.unwrap_or_default().as_u64(),
-                            },
-                        );
+                        if matches!(
+                            status.get(&chain_id_copy),
+                            Some(RegistrationStatus::Pending { tx_hash: pending }) if *pending == tx_hash
+                        ) {
+                            status.insert(
+                                chain_id_copy,
+                                RegistrationStatus::Confirmed {
+                                    block_number: receipt.block_number.unwrap_or_default().as_u64(),
+                                },
+                            );
+                        }
                     }
                     Ok(None) => {
                         debug!(
@@ -592,6 +631,143 @@ impl MultiChainRegistrar {
         Ok(results)
     }
 
+    /// Register on the given chains, returning the resulting status per chain
+    ///
+    /// In all-or-nothing mode the first failure stops further attempts and
+    /// rolls back the chains that already succeeded.
+    pub async fn register(&self, chains: &[u64]) -> Result<HashMap<u64, RegistrationStatus>> {
+        let mut succeeded = Vec::new();
+        let mut failed = false;
+
+        for &chain_id in chains {
+            if failed && self.all_or_nothing {
+                debug!(
+                    "Skipping chain {} after an earlier registration failure",
+                    chain_id
+                );
+                continue;
+            }
+
+            info!("Attempting registration on chain {}", chain_id);
+            match self.register_on_chain(chain_id).await {
+                Ok(_) => succeeded.push(chain_id),
+                Err(e) => {
+                    error!("Registration failed on chain {}: {}", chain_id, e);
+                    self.registration_status.write().await.insert(
+                        chain_id,
+                        RegistrationStatus::Failed {
+                            error: e.to_string(),
+                        },
+                    );
+                    failed = true;
+                }
+            }
+        }
+
+        if failed && self.all_or_nothing && !succeeded.is_empty() {
+            warn!(
+                "All-or-nothing registration failed, rolling back chains {:?}",
+                succeeded
+            );
+            for (chain_id, result) in self.rollback(&succeeded).await {
+                if let Err(e) = result {
+                    error!("Rollback failed on chain {}: {}", chain_id, e);
+                }
+            }
+        }
+
+        let status = self.registration_status.read().await;
+        Ok(chains
+            .iter()
+            .map(|chain_id| {
+                let chain_status = status
+                    .get(chain_id)
+                    .cloned()
+                    .unwrap_or(RegistrationStatus::NotRegistered);
+                (*chain_id, chain_status)
+            })
+            .collect())
+    }
+
+    /// Deregister from the given chains, e.g. after a partial registration
+    ///
+    /// Successful chains go back to NotRegistered; chains that could not be
+    /// rolled back are marked Failed so the health monitor flags them.
+    pub async fn rollback(&self, chains: &[u64]) -> Vec<(u64, Result<H256>)> {
+        let mut results = Vec::new();
+
+        for &chain_id in chains {
+            let result = self.unregister_on_chain(chain_id).await;
+            let new_status = match &result {
+                Ok(tx_hash) => {
+                    info!(
+                        "Rolled back registration on chain {}: {:?}",
+                        chain_id, tx_hash
+                    );
+                    RegistrationStatus::NotRegistered
+                }
+                Err(e) => RegistrationStatus::Failed {
+                    error: format!("Rollback failed: {}", e),
+                },
+            };
+            self.registration_status
+                .write()
+                .await
+                .insert(chain_id, new_status);
+            results.push((chain_id, result));
+        }
+
+        results
+    }
+
+    /// Call unregisterNode on a specific chain and wait for the receipt
+    async fn unregister_on_chain(&self, chain_id: u64) -> Result<H256> {
+        let chain_config = self
+            .chain_registry
+            .get_chain(chain_id)
+            .ok_or_else(|| anyhow!("Chain {} not supported", chain_id))?;
+
+        let signer = self
+            .signers
+            .get(&chain_id)
+            .ok_or_else(|| anyhow!("No signer available for chain {}", chain_id))?;
+
+        use ethers::abi::Function;
+        use ethers::types::Bytes;
+
+        let function = Function {
+            name: "unregisterNode".to_string(),
+            inputs: vec![],
+            outputs: vec![],
+            constant: None,
+            state_mutability: ethers::abi::StateMutability::NonPayable,
+        };
+        let encoded = function
+            .encode_input(&[])
+            .map_err(|e| anyhow!("Failed to encode unregisterNode: {}", e))?;
+
+        let tx_request = ethers::types::TransactionRequest::new()
+            .to(chain_config.contracts.node_registry)
+            .data(Bytes::from(encoded));
+
+        let pending_tx = signer
+            .send_transaction(tx_request, None)
+            .await
+            .map_err(|e| anyhow!("unregisterNode tx failed on chain {}: {}", chain_id, e))?;
+        let tx_hash = pending_tx.tx_hash();
+
+        match pending_tx.await? {
+            Some(receipt) if receipt.status == Some(1u64.into()) => Ok(tx_hash),
+            Some(_) => Err(anyhow!("unregisterNode reverted on chain {}", chain_id)),
+            None => Err(anyhow!("unregisterNode dropped on chain {}", chain_id)),
+        }
+    }
+
+    /// Chains left registered while registration failed on another chain
+    pub async fn inconsistent_chains(&self) -> Vec<u64> {
+        inconsistent_chains(&*self.registration_status.read().await)
+    }
+
     /// Get all chain IDs this registrar can work with
     pub async fn get_all_chain_ids(&self) -> Result<Vec<u64>> {
         Ok(self.chain_registry.get_all_chain_ids())
@@ -653,6 +829,40 @@ mod tests {
         assert_eq!(&encoded[..4], expected_selector);
     }
 
+    #[test]
+    fn test_inconsistent_chains_requires_failure() {
+        let mut statuses = HashMap::new();
+        statuses.insert(84532, RegistrationStatus::Confirmed { block_number: 10 });
+        statuses.insert(5611, RegistrationStatus::NotRegistered);
+        assert!(inconsistent_chains(&statuses).is_empty());
+
+        statuses.insert(
+            5611,
+            RegistrationStatus::Failed {
+                error: "insufficient FAB".to_string(),
+            },
+        );
+        statuses.insert(
+            8453,
+            RegistrationStatus::Pending {
+                tx_hash: H256::zero(),
+            },
+        );
+        assert_eq!(inconsistent_chains(&statuses), vec![8453, 84532]);
+    }
+
+    #[test]
+    fn test_inconsistent_chains_all_failed() {
+        let mut statuses = HashMap::new();
+        statuses.insert(
+            84532,
+            RegistrationStatus::Failed {
+                error: "rpc down".to_string(),
+            },
+        );
+        assert!(inconsistent_chains(&statuses).is_empty());
+    }
+
     #[test]
     fn test_set_token_pricing_validates_range() {
         // Out-of-range price should be rejected by stable::validate_price
@@ -3,10 +3,14 @@
 use anyhow::{anyhow, Result};
 use ethers::types::{Address, U256};
 use reqwest::Client;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tracing::{debug, warn};
 
 use crate::blockchain::multi_chain_registrar::RegistrationStatus;
+use crate::contracts::Web3Client;
 
 /// Performs detailed health checks for node registrations
 pub struct RegistrationHealthChecker {
@@ -179,6 +183,62 @@ pub struct BalanceHealth {
     pub warning_level: WarningLevel,
 }
 
+/// Latest known native (gas) balance of the host wallet
+///
+/// Cloned into every component that needs to react to the balance, e.g.
+/// `JobClaimer` pauses claiming while it cannot afford proof submissions.
+#[derive(Debug, Clone)]
+pub struct GasBalanceSignal {
+    sender: Arc<watch::Sender<Option<U256>>>,
+}
+
+impl GasBalanceSignal {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(None);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// Publish a freshly observed balance
+    pub fn update(&self, balance: U256) {
+        self.sender.send_replace(Some(balance));
+    }
+
+    /// Last published balance, `None` until the first update
+    pub fn current(&self) -> Option<U256> {
+        *self.sender.borrow()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Option<U256>> {
+        self.sender.subscribe()
+    }
+
+    /// Poll the wallet balance every `interval` and publish it
+    pub fn spawn_poller(&self, web3_client: Arc<Web3Client>, interval: Duration) -> JoinHandle<()> {
+        let signal = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match web3_client.get_balance().await {
+                    Ok(balance) => {
+                        debug!("Host gas balance: {} wei", balance);
+                        signal.update(balance);
+                    }
+                    Err(e) => warn!("Failed to refresh host gas balance: {}", e),
+                }
+            }
+        })
+    }
+}
+
+impl Default for GasBalanceSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Connectivity health information
 #[derive(Debug, Clone)]
 pub struct ConnectivityHealth {
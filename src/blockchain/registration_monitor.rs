@@ -15,6 +15,9 @@ use crate::blockchain::multi_chain_registrar::{MultiChainRegistrar, Registration
 use crate::monitoring::alerting::{Alert, AlertLevel, AlertManager};
 use crate::monitoring::metrics::{Counter, Gauge, Histogram, MetricsCollector};
 
+/// How far a mock renewal pushes out a mocked expiry
+const MOCK_RENEWAL_PERIOD: Duration = Duration::from_secs(30 * 86400);
+
 /// Configuration for the registration monitor
 #[derive(Debug, Clone)]
 pub struct MonitorConfig {
@@ -53,6 +56,8 @@ pub struct RegistrationHealth {
     pub fab_balance: U256,
     pub registration_block: Option<u64>,
     pub time_until_expiry: Option<Duration>,
+    /// When auto-renewal will resubmit the registration, if enabled
+    pub next_renewal: Option<Instant>,
 }
 
 /// Health issues that can be detected
@@ -74,6 +79,8 @@ pub enum IssueType {
     RpcFailure,
     ApiUnreachable,
     ModelNotApproved,
+    PartialRegistration,
+    RenewalFailed,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -98,6 +105,8 @@ pub struct RegistrationMonitor {
     config: Arc<RwLock<MonitorConfig>>,
     health_states: Arc<RwLock<HashMap<u64, RegistrationHealth>>>,
     monitor_handles: Arc<Mutex<HashMap<u64, JoinHandle<()>>>>,
+    renewal_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    renewal_failures: Arc<RwLock<HashMap<u64, String>>>,
     metrics_collector: Arc<MetricsCollector>,
     alert_manager: Option<Arc<AlertManager>>,
     warning_callbacks:
@@ -136,6 +145,8 @@ impl RegistrationMonitor {
             config: Arc::new(RwLock::new(config)),
             health_states: Arc::new(RwLock::new(HashMap::new())),
             monitor_handles: Arc::new(Mutex::new(HashMap::new())),
+            renewal_handle: Arc::new(Mutex::new(None)),
+            renewal_failures: Arc::new(RwLock::new(HashMap::new())),
             metrics_collector,
             alert_manager: None,
             warning_callbacks: Arc::new(RwLock::new(Vec::new())),
@@ -156,9 +167,164 @@ impl RegistrationMonitor {
             self.start_chain_monitor(chain_id).await?;
         }
 
+        self.start_auto_renewal().await;
+
         Ok(())
     }
 
+    /// Start the task that renews registrations nearing expiry
+    ///
+    /// Runs `renew_due_registrations` every `check_interval`.
+    async fn start_auto_renewal(&self) {
+        let mut renewal_handle = self.renewal_handle.lock().await;
+        if renewal_handle.is_some() {
+            return;
+        }
+
+        let monitor = self.clone_for_renewal();
+        let handle = tokio::spawn(async move {
+            let mut check_interval = {
+                let cfg = monitor.config.read().await;
+                interval(cfg.check_interval)
+            };
+
+            loop {
+                check_interval.tick().await;
+                monitor.renew_due_registrations().await;
+            }
+        });
+
+        *renewal_handle = Some(handle);
+    }
+
+    /// Resubmit the registration for each chain whose remaining validity is
+    /// below `renewal_buffer`, then re-read its health so the renewed expiry
+    /// (or the failure) is visible before the chain monitor's next check.
+    /// Does nothing while `auto_renewal` is disabled.
+    async fn renew_due_registrations(&self) {
+        let cfg = self.config.read().await.clone();
+        if !cfg.auto_renewal {
+            return;
+        }
+
+        let due: Vec<u64> = {
+            let states = self.health_states.read().await;
+            states
+                .values()
+                .filter(|h| h.time_until_expiry.is_some_and(|t| t < cfg.renewal_buffer))
+                .map(|h| h.chain_id)
+                .collect()
+        };
+
+        for chain_id in due {
+            info!("Triggering auto-renewal for chain {}", chain_id);
+            let is_mock = *self.mock_mode.read().await;
+            self.metrics_collector
+                .increment_counter("renewal_attempts", 1.0);
+
+            match Self::renew_registration(chain_id, &self.registrar, &cfg, is_mock).await {
+                Ok(tx_hash) => {
+                    if let Some(tx_hash) = tx_hash {
+                        info!("Renewal transaction sent: {:?}", tx_hash);
+                    } else {
+                        // Extend the mock registration as a real renewal would
+                        self.mock_expiries
+                            .write()
+                            .await
+                            .insert(chain_id, Instant::now() + MOCK_RENEWAL_PERIOD);
+                        info!("Mock renewal recorded for chain {}", chain_id);
+                    }
+                    self.renewal_failures.write().await.remove(&chain_id);
+                    self.renewal_history
+                        .write()
+                        .await
+                        .entry(chain_id)
+                        .or_insert_with(Vec::new)
+                        .push(Instant::now());
+                }
+                Err(e) => {
+                    error!("Failed to renew registration on chain {}: {}", chain_id, e);
+                    self.renewal_failures
+                        .write()
+                        .await
+                        .insert(chain_id, e.to_string());
+                }
+            }
+
+            match Self::check_health_internal(
+                chain_id,
+                &self.registrar,
+                &self.config,
+                &self.simulated_failures,
+                &self.mock_expiries,
+                &self.renewal_failures,
+            )
+            .await
+            {
+                Ok(health) => {
+                    self.health_states.write().await.insert(chain_id, health);
+                }
+                Err(e) => warn!(
+                    "Health check after renewal failed for chain {}: {}",
+                    chain_id, e
+                ),
+            }
+        }
+    }
+
+    /// Handle sharing this monitor's state, for the auto-renewal task
+    fn clone_for_renewal(&self) -> Self {
+        Self {
+            registrar: self.registrar.clone(),
+            config: self.config.clone(),
+            health_states: self.health_states.clone(),
+            monitor_handles: self.monitor_handles.clone(),
+            renewal_handle: self.renewal_handle.clone(),
+            renewal_failures: self.renewal_failures.clone(),
+            metrics_collector: self.metrics_collector.clone(),
+            alert_manager: self.alert_manager.clone(),
+            warning_callbacks: self.warning_callbacks.clone(),
+            simulated_failures: self.simulated_failures.clone(),
+            mock_expiries: self.mock_expiries.clone(),
+            renewal_history: self.renewal_history.clone(),
+            mock_mode: self.mock_mode.clone(),
+        }
+    }
+
+    /// Resubmit the registration, retrying up to `max_retry_attempts` times
+    ///
+    /// Returns `None` in mock mode, where no transaction is sent.
+    async fn renew_registration(
+        chain_id: u64,
+        registrar: &Arc<MultiChainRegistrar>,
+        config: &MonitorConfig,
+        is_mock: bool,
+    ) -> Result<Option<H256>> {
+        if is_mock {
+            return Ok(None);
+        }
+
+        let attempts = config.max_retry_attempts.max(1);
+        let mut last_error = None;
+        for attempt in 1..=attempts {
+            match registrar.register_on_chain(chain_id).await {
+                Ok(tx_hash) => return Ok(Some(tx_hash)),
+                Err(e) => {
+                    warn!(
+                        "Renewal attempt {}/{} failed on chain {}: {}",
+                        attempt, attempts, chain_id, e
+                    );
+                    last_error = Some(e);
+                    if attempt < attempts {
+                        sleep(config.retry_delay).await;
+                    }
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("Renewal failed on chain {}", chain_id)))
+    }
+
     /// Start monitoring a specific chain
     async fn start_chain_monitor(&self, chain_id: u64) -> Result<()> {
         let mut handles = self.monitor_handles.lock().await;
@@ -175,8 +341,7 @@ impl RegistrationMonitor {
         let callbacks = self.warning_callbacks.clone();
         let failures = self.simulated_failures.clone();
         let mock_expiries = self.mock_expiries.clone();
-        let renewal_history = self.renewal_history.clone();
-        let mock_mode = self.mock_mode.clone();
+        let renewal_failures = self.renewal_failures.clone();
 
         let handle = tokio::spawn(async move {
             info!("Starting monitor for chain {}", chain_id);
@@ -197,6 +362,7 @@ impl RegistrationMonitor {
                     &config,
                     &failures,
                     &mock_expiries,
+                    &renewal_failures,
                 )
                 .await
                 {
@@ -295,41 +461,6 @@ impl RegistrationMonitor {
                         }
 
                         metrics.increment_counter("expiry_warnings", 1.0);
-
-                        // Check if auto-renewal should trigger
-                        if cfg.auto_renewal && time_until_expiry < cfg.renewal_buffer {
-                            info!("Triggering auto-renewal for chain {}", chain_id);
-
-                            // Check if we're in mock mode
-                            let is_mock = *mock_mode.read().await;
-
-                            if is_mock {
-                                // For testing, just record the renewal attempt
-                                let mut history = renewal_history.write().await;
-                                history
-                                    .entry(chain_id)
-                                    .or_insert_with(Vec::new)
-                                    .push(Instant::now());
-                                metrics.increment_counter("renewal_attempts", 1.0);
-                                info!("Mock renewal recorded for chain {}", chain_id);
-                            } else {
-                                match registrar.register_on_chain(chain_id).await {
-                                    Ok(tx_hash) => {
-                                        info!("Renewal transaction sent: {:?}", tx_hash);
-                                        metrics.increment_counter("renewal_attempts", 1.0);
-
-                                        let mut history = renewal_history.write().await;
-                                        history
-                                            .entry(chain_id)
-                                            .or_insert_with(Vec::new)
-                                            .push(Instant::now());
-                                    }
-                                    Err(e) => {
-                                        error!("Failed to renew registration: {}", e);
-                                    }
-                                }
-                            }
-                        }
                     }
                 }
 
@@ -347,9 +478,10 @@ impl RegistrationMonitor {
     async fn check_health_internal(
         chain_id: u64,
         registrar: &Arc<MultiChainRegistrar>,
-        _config: &Arc<RwLock<MonitorConfig>>,
+        config: &Arc<RwLock<MonitorConfig>>,
         failures: &Arc<RwLock<HashMap<u64, bool>>>,
         mock_expiries: &Arc<RwLock<HashMap<u64, Instant>>>,
+        renewal_failures: &Arc<RwLock<HashMap<u64, String>>>,
     ) -> Result<RegistrationHealth> {
         let mut issues = Vec::new();
 
@@ -401,6 +533,20 @@ impl RegistrationMonitor {
             RegistrationStatus::Pending { .. } => true, // Pending is considered healthy
         };
 
+        // Flag chains left registered while registration failed elsewhere
+        let inconsistent = registrar.inconsistent_chains().await;
+        if inconsistent.contains(&chain_id) {
+            issues.push(HealthIssue {
+                issue_type: IssueType::PartialRegistration,
+                severity: IssueSeverity::Warning,
+                message: "Registered on this chain but registration failed on another chain; \
+                          consider rolling back"
+                    .to_string(),
+                detected_at: Instant::now(),
+                resolved: false,
+            });
+        }
+
         // Check mock expiry
         let time_until_expiry = {
             let expiries = mock_expiries.read().await;
@@ -414,6 +560,25 @@ impl RegistrationMonitor {
             })
         };
 
+        let next_renewal = {
+            let cfg = config.read().await;
+            if cfg.auto_renewal {
+                time_until_expiry.map(|t| Instant::now() + t.saturating_sub(cfg.renewal_buffer))
+            } else {
+                None
+            }
+        };
+
+        if let Some(error) = renewal_failures.read().await.get(&chain_id) {
+            issues.push(HealthIssue {
+                issue_type: IssueType::RenewalFailed,
+                severity: IssueSeverity::Critical,
+                message: format!("Registration renewal failed: {}", error),
+                detected_at: Instant::now(),
+                resolved: false,
+            });
+        }
+
         // Check FAB balance (mock for now)
         let fab_balance = U256::from(1000u64) * U256::exp10(18); // Mock 1000 FAB
         let stake_balance = U256::from(1000u64) * U256::exp10(18); // Mock staked amount
@@ -428,6 +593,7 @@ impl RegistrationMonitor {
             fab_balance,
             registration_block: None,
             time_until_expiry,
+            next_renewal,
         })
     }
 
@@ -442,6 +608,11 @@ impl RegistrationMonitor {
             handle.abort();
         }
 
+        if let Some(handle) = self.renewal_handle.lock().await.take() {
+            debug!("Stopping auto-renewal task");
+            handle.abort();
+        }
+
         Ok(())
     }
 
@@ -525,3 +696,122 @@ impl RegistrationMonitor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::multi_chain_registrar::NodeMetadata;
+    use crate::config::chains::ChainRegistry;
+
+    async fn test_monitor(auto_renewal: bool) -> RegistrationMonitor {
+        let metadata = NodeMetadata {
+            name: "Test Monitor Node".to_string(),
+            version: "1.0.0".to_string(),
+            api_url: "http://localhost:8080".to_string(),
+            capabilities: vec!["inference".to_string()],
+            performance_tier: "standard".to_string(),
+        };
+        let registrar = MultiChainRegistrar::new(
+            Arc::new(ChainRegistry::new()),
+            "0xe7855c0ea54ccca55126d40f97d90868b2a73bad0363e92ccdec0c4fbd6c0ce2",
+            metadata,
+        )
+        .await
+        .unwrap();
+        let config = MonitorConfig {
+            auto_renewal,
+            renewal_buffer: Duration::from_secs(1800),
+            max_retry_attempts: 1,
+            retry_delay: Duration::from_millis(1),
+            ..Default::default()
+        };
+        RegistrationMonitor::new(Arc::new(registrar), config)
+            .await
+            .unwrap()
+    }
+
+    /// Run one health check for `chain_id`, as the chain monitor would
+    async fn check(monitor: &RegistrationMonitor, chain_id: u64) -> RegistrationHealth {
+        let health = RegistrationMonitor::check_health_internal(
+            chain_id,
+            &monitor.registrar,
+            &monitor.config,
+            &monitor.simulated_failures,
+            &monitor.mock_expiries,
+            &monitor.renewal_failures,
+        )
+        .await
+        .unwrap();
+        monitor
+            .health_states
+            .write()
+            .await
+            .insert(chain_id, health.clone());
+        health
+    }
+
+    fn has_renewal_failure(health: &RegistrationHealth) -> bool {
+        health
+            .issues
+            .iter()
+            .any(|issue| issue.issue_type == IssueType::RenewalFailed)
+    }
+
+    #[tokio::test]
+    async fn test_renewal_moves_expiry_forward() {
+        let monitor = test_monitor(true).await;
+        monitor
+            .mock_expiring_registration(84532, Duration::from_secs(600))
+            .await
+            .unwrap();
+
+        // Inside the renewal buffer, so renewal is due now
+        let health = check(&monitor, 84532).await;
+        assert!(health.next_renewal.unwrap() <= Instant::now());
+
+        monitor.renew_due_registrations().await;
+        assert!(monitor.was_renewed(84532).await.unwrap());
+        let health = monitor.get_health(84532).await.unwrap();
+        assert!(health.time_until_expiry.unwrap() > Duration::from_secs(1800));
+        assert!(health.next_renewal.unwrap() > Instant::now());
+        assert!(!has_renewal_failure(&health));
+
+        // The renewed registration is no longer due
+        monitor.renew_due_registrations().await;
+        assert_eq!(monitor.renewal_history.read().await[&84532].len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_renewal_is_reported() {
+        let monitor = test_monitor(true).await;
+        monitor
+            .mock_expiring_registration(999_999, Duration::from_secs(600))
+            .await
+            .unwrap();
+        // Send a real registration, which fails for an unsupported chain
+        *monitor.mock_mode.write().await = false;
+        check(&monitor, 999_999).await;
+
+        monitor.renew_due_registrations().await;
+        assert!(!monitor.was_renewed(999_999).await.unwrap());
+        let health = monitor.get_health(999_999).await.unwrap();
+        assert!(has_renewal_failure(&health));
+        // Still due, so the next pass tries again
+        assert!(health.time_until_expiry.unwrap() < Duration::from_secs(1800));
+    }
+
+    #[tokio::test]
+    async fn test_no_renewal_when_disabled() {
+        let monitor = test_monitor(false).await;
+        monitor
+            .mock_expiring_registration(84532, Duration::from_secs(600))
+            .await
+            .unwrap();
+
+        let health = check(&monitor, 84532).await;
+        assert!(health.next_renewal.is_none());
+
+        monitor.renew_due_registrations().await;
+        assert!(!monitor.was_renewed(84532).await.unwrap());
+    }
+}
@@ -7,21 +7,24 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use crate::embeddings::{EmbeddingConfig, EmbeddingGenerator};
 use crate::storage::{EnhancedS5Client, S5Config};
-use crate::vector::{VectorDbClient, VectorDbConfig};
+use crate::vector::{DistanceMetric, VectorDbClient, VectorDbConfig};
 
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
     pub s5_url: String,
     pub vector_db_url: String,
+    /// Minimum score for cosine/dot-product, maximum distance for Euclidean
     pub similarity_threshold: f32,
     pub ttl_seconds: u64,
     pub max_cache_size_mb: usize,
+    /// Metric used to compare prompt embeddings
+    pub metric: DistanceMetric,
 }
 
 #[derive(Debug, Clone)]
@@ -44,12 +47,66 @@ pub struct CacheEntry {
     pub parameters: JsonValue,
     pub generated_at: String,
     pub generation_time_ms: u64,
+    /// Embedding of the base prompt, stored once at `put` time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embedding: Vec<f32>,
+    /// Expiry as seconds since the Unix epoch (0 = unknown, see `restore_expiry`)
+    #[serde(default)]
+    pub expires_at: u64,
     #[serde(skip, default = "SystemTime::now")]
     pub created_at: SystemTime,
     #[serde(skip, default)]
     pub size_bytes: usize,
 }
 
+impl CacheEntry {
+    /// Returns true once the entry's TTL has elapsed
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(unix_now())
+    }
+
+    fn is_expired_at(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Fills in `expires_at` for entries stored before it existed
+    ///
+    /// Derives the expiry from `generated_at` when it parses; otherwise the
+    /// entry is treated as freshly restored rather than infinitely old.
+    fn restore_expiry(&mut self, ttl_seconds: u64) {
+        if self.expires_at != 0 {
+            return;
+        }
+        let generated_at = chrono::DateTime::parse_from_rfc3339(&self.generated_at)
+            .map(|t| t.timestamp().max(0) as u64)
+            .unwrap_or_else(|_| unix_now());
+        self.expires_at = generated_at.saturating_add(ttl_seconds);
+    }
+}
+
+/// S5 directory holding serialized entries, sharded by the first two hash characters
+const PROMPTS_DIR: &str = "/cache/prompts/";
+
+/// Most remote points fetched per invalidation query
+const INVALIDATE_QUERY_LIMIT: usize = 10_000;
+
+/// S5 path of the serialized entry for a prompt hash
+fn entry_path(prompt_hash: &str) -> String {
+    format!("{}{}/{}.json", PROMPTS_DIR, &prompt_hash[0..2], prompt_hash)
+}
+
+/// Approximate in-memory footprint used for cache size accounting
+fn entry_size(prompt_key: &str, response: &str) -> usize {
+    response.len() + prompt_key.len() + 200
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::from_secs(0))
+        .as_secs()
+}
+
 struct CacheMetricsInternal {
     total_requests: usize,
     cache_hits: usize,
@@ -59,6 +116,41 @@ struct CacheMetricsInternal {
     cache_size_bytes: usize,
 }
 
+/// Prompt key of the form `<prompt>;model=<id>;temp=<f>;max_tokens=<n>`
+struct PromptKey<'a> {
+    base_prompt: &'a str,
+    model: String,
+    parameters: JsonValue,
+}
+
+fn parse_prompt_key(prompt: &str) -> PromptKey<'_> {
+    let mut parts = prompt.split(';');
+    let base_prompt = parts.next().unwrap_or(prompt);
+    let mut model = "llama-3.2-1b-instruct".to_string();
+    let mut parameters = json!({});
+
+    for part in parts {
+        if let Some((key, value)) = part.split_once('=') {
+            match key {
+                "model" => model = value.to_string(),
+                "temp" => {
+                    parameters["temperature"] = json!(value.parse::<f64>().unwrap_or(0.7));
+                }
+                "max_tokens" => {
+                    parameters["max_tokens"] = json!(value.parse::<u64>().unwrap_or(100));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    PromptKey {
+        base_prompt,
+        model,
+        parameters,
+    }
+}
+
 pub struct PromptCache {
     config: CacheConfig,
     s5_client: EnhancedS5Client,
@@ -70,11 +162,12 @@ pub struct PromptCache {
 
 impl PromptCache {
     pub async fn new(config: CacheConfig) -> Result<Self> {
-        // Initialize S5 client
+        // Initialize S5 client; it retries failed requests and fast-fails
+        // while the bridge's circuit is open, so each attempt is kept short
         let s5_config = S5Config {
             api_url: config.s5_url.clone(),
             api_key: Some("cache-api-key".to_string()),
-            timeout_secs: 30,
+            timeout_secs: 5,
         };
         let s5_client = EnhancedS5Client::new(s5_config)?;
 
@@ -114,12 +207,27 @@ impl PromptCache {
         })
     }
 
+    /// Applies the similarity threshold in the direction of the configured metric
+    fn is_similar(&self, score: f32, distance: f32) -> bool {
+        // Use a slightly looser threshold for better semantic matching
+        let threshold = match self.config.metric {
+            DistanceMetric::Euclidean => self.config.similarity_threshold / 0.95,
+            DistanceMetric::Cosine | DistanceMetric::DotProduct => {
+                self.config.similarity_threshold * 0.95
+            }
+        };
+        self.config
+            .metric
+            .within_threshold(score, distance, threshold)
+    }
+
     fn hash_prompt(&self, prompt: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(prompt.as_bytes());
         format!("{:x}", hasher.finalize())
     }
 
+    #[tracing::instrument(name = "cache_lookup", skip_all)]
     pub async fn get(&self, prompt: &str) -> Result<Option<String>> {
         let start = Instant::now();
         let prompt_hash = self.hash_prompt(prompt);
@@ -134,12 +242,7 @@ impl PromptCache {
         {
             let entries = self.cache_entries.lock().unwrap();
             if let Some(entry) = entries.get(&prompt_hash) {
-                // Check TTL
-                let age = SystemTime::now()
-                    .duration_since(entry.created_at)
-                    .unwrap_or(Duration::from_secs(0));
-
-                if age.as_secs() <= self.config.ttl_seconds {
+                if !entry.is_expired() {
                     // Cache hit
                     let elapsed = start.elapsed().as_millis() as f64;
                     let mut metrics = self.metrics.lock().unwrap();
@@ -151,89 +254,68 @@ impl PromptCache {
         }
 
         // Try to retrieve from S5
-        let path = format!("/cache/prompts/{}/{}.json", &prompt_hash[0..2], prompt_hash);
+        let path = entry_path(&prompt_hash);
         if let Ok((data, _metadata)) = self.s5_client.get(&path).await {
             if let Ok(json_str) = String::from_utf8(data) {
-                if let Ok(entry) = serde_json::from_str::<CacheEntry>(&json_str) {
-                    // Check TTL based on generated_at
-                    if let Ok(generated_at) =
-                        chrono::DateTime::parse_from_rfc3339(&entry.generated_at)
-                    {
-                        let age = SystemTime::now()
-                            .duration_since(
-                                SystemTime::UNIX_EPOCH
-                                    + Duration::from_secs(generated_at.timestamp() as u64),
-                            )
-                            .unwrap_or(Duration::from_secs(u64::MAX));
-
-                        if age.as_secs() <= self.config.ttl_seconds {
-                            // Cache hit from S5
-                            let elapsed = start.elapsed().as_millis() as f64;
-                            let mut metrics = self.metrics.lock().unwrap();
-                            metrics.cache_hits += 1;
-                            metrics.hit_times_ms.push(elapsed);
-
-                            // Update in-memory cache
-                            let mut entries = self.cache_entries.lock().unwrap();
-                            let mut cache_entry = entry.clone();
-                            cache_entry.created_at = SystemTime::now() - age;
-                            entries.insert(prompt_hash.clone(), cache_entry);
-
-                            return Ok(Some(entry.response));
+                if let Ok(mut entry) = serde_json::from_str::<CacheEntry>(&json_str) {
+                    entry.restore_expiry(self.config.ttl_seconds);
+                    if !entry.is_expired() {
+                        // Cache hit from S5; update in-memory cache (entries before metrics)
+                        let elapsed = start.elapsed().as_millis() as f64;
+                        let response = entry.response.clone();
+                        entry.size_bytes = entry_size(&entry.prompt_key, &entry.response);
+                        let added = entry.size_bytes;
+
+                        let mut entries = self.cache_entries.lock().unwrap();
+                        let mut metrics = self.metrics.lock().unwrap();
+                        metrics.cache_hits += 1;
+                        metrics.hit_times_ms.push(elapsed);
+                        metrics.cache_size_bytes += added;
+                        if let Some(previous) = entries.insert(prompt_hash.clone(), entry) {
+                            metrics.cache_size_bytes =
+                                metrics.cache_size_bytes.saturating_sub(previous.size_bytes);
                         }
+
+                        return Ok(Some(response));
                     }
                 }
             }
         }
 
-        // If no exact match, try semantic search
-        // Extract base prompt without parameters for semantic search
-        let base_prompt = prompt.split(';').next().unwrap_or(prompt);
-        let embedding = self.embedding_generator.generate(base_prompt).await?;
+        // If no exact match, try semantic search against entries for the same model
+        let key = parse_prompt_key(prompt);
+        let embedding = self.embedding_generator.generate(key.base_prompt).await?;
         let filter = Some(json!({
-            "type": "cache_entry"
+            "type": "cache_entry",
+            "model": key.model,
         }));
 
-        let results = self.vector_client.search(embedding, 1, filter).await?;
-
-        if !results.is_empty() {
-            if let Some(first) = results.first() {
-                if let Some(score) = first.get("score").and_then(|s| s.as_f64()) {
-                    // Use a slightly lower threshold for better semantic matching
-                    let threshold = self.config.similarity_threshold * 0.95;
-                    if score as f32 >= threshold {
-                        // Found similar cached prompt
-                        if let Some(metadata) = first.get("metadata") {
-                            if let Some(cached_response) =
-                                metadata.get("response").and_then(|r| r.as_str())
-                            {
-                                // Check TTL
-                                if let Some(generated_at_str) =
-                                    metadata.get("generated_at").and_then(|g| g.as_str())
-                                {
-                                    if let Ok(generated_at) =
-                                        chrono::DateTime::parse_from_rfc3339(generated_at_str)
-                                    {
-                                        let age = SystemTime::now()
-                                            .duration_since(
-                                                SystemTime::UNIX_EPOCH
-                                                    + Duration::from_secs(
-                                                        generated_at.timestamp() as u64
-                                                    ),
-                                            )
-                                            .unwrap_or(Duration::from_secs(u64::MAX));
-
-                                        if age.as_secs() <= self.config.ttl_seconds {
-                                            // Semantic cache hit
-                                            let elapsed = start.elapsed().as_millis() as f64;
-                                            let mut metrics = self.metrics.lock().unwrap();
-                                            metrics.cache_hits += 1;
-                                            metrics.hit_times_ms.push(elapsed);
-                                            return Ok(Some(cached_response.to_string()));
-                                        }
-                                    }
-                                }
-                            }
+        let results = self
+            .vector_client
+            .search_with_metric(embedding, 1, filter, self.config.metric)
+            .await?;
+
+        if let Some(first) = results.first() {
+            if let Some(score) = first.get("score").and_then(|s| s.as_f64()) {
+                let distance = first
+                    .get("distance")
+                    .and_then(|d| d.as_f64())
+                    .unwrap_or(f64::INFINITY);
+                if self.is_similar(score as f32, distance as f32) {
+                    // Found similar cached prompt
+                    let entry = first
+                        .get("metadata")
+                        .cloned()
+                        .and_then(|m| serde_json::from_value::<CacheEntry>(m).ok());
+                    if let Some(mut entry) = entry {
+                        entry.restore_expiry(self.config.ttl_seconds);
+                        if !entry.is_expired() {
+                            // Semantic cache hit
+                            let elapsed = start.elapsed().as_millis() as f64;
+                            let mut metrics = self.metrics.lock().unwrap();
+                            metrics.cache_hits += 1;
+                            metrics.hit_times_ms.push(elapsed);
+                            return Ok(Some(entry.response));
                         }
                     }
                 }
@@ -256,37 +338,21 @@ impl PromptCache {
             .format("%Y-%m-%dT%H:%M:%S%.3fZ")
             .to_string();
 
-        // Parse prompt key for model and parameters
-        let parts: Vec<&str> = prompt.split(';').collect();
-        let base_prompt = if !parts.is_empty() { parts[0] } else { prompt };
-        let mut model = "llama-3.2-1b-instruct".to_string();
-        let mut parameters = json!({});
-
-        for part in &parts[1..] {
-            if let Some((key, value)) = part.split_once('=') {
-                match key {
-                    "model" => model = value.to_string(),
-                    "temp" => {
-                        parameters["temperature"] = json!(value.parse::<f64>().unwrap_or(0.7));
-                    }
-                    "max_tokens" => {
-                        parameters["max_tokens"] = json!(value.parse::<u64>().unwrap_or(100));
-                    }
-                    _ => {}
-                }
-            }
-        }
+        let key = parse_prompt_key(prompt);
+        let embedding = self.embedding_generator.generate(key.base_prompt).await?;
 
         let entry = CacheEntry {
-            prompt: base_prompt.to_string(),
+            prompt: key.base_prompt.to_string(),
             prompt_key: prompt.to_string(),
             response: response.to_string(),
-            model,
-            parameters,
+            model: key.model,
+            parameters: key.parameters,
             generated_at: generated_at.clone(),
             generation_time_ms: 1250, // Mock value
+            embedding,
+            expires_at: unix_now().saturating_add(self.config.ttl_seconds),
             created_at: now,
-            size_bytes: response.len() + prompt.len() + 200, // Approximate
+            size_bytes: entry_size(prompt, response),
         };
 
         // Check cache size and evict if necessary
@@ -322,46 +388,44 @@ impl PromptCache {
         }
 
         // Store in S5
-        let path = format!("/cache/prompts/{}/{}.json", &prompt_hash[0..2], prompt_hash);
+        let path = entry_path(&prompt_hash);
         let json_data = serde_json::to_string(&entry)?;
         let metadata = json!({
             "type": "cache_entry",
             "prompt_hash": prompt_hash,
             "model": entry.model,
             "generated_at": generated_at,
+            "expires_at": entry.expires_at,
         });
 
-        // Store in S5 with error handling to prevent hanging
-        if let Err(e) = tokio::time::timeout(
-            Duration::from_secs(5),
-            self.s5_client
-                .put(&path, json_data.into_bytes(), Some(metadata)),
-        )
-        .await
+        // The entry embeds its prompt_key, so identical bytes already live at
+        // this path and the upload can be skipped.
+        // Log S5 failures but continue (don't fail the whole put operation)
+        if let Err(e) = self
+            .s5_client
+            .put_dedup(&path, json_data.into_bytes(), Some(metadata))
+            .await
         {
-            // Log error but continue (don't fail the whole put operation)
-            eprintln!("Warning: S5 storage timed out or failed: {:?}", e);
+            eprintln!("Warning: S5 storage failed for {}: {}", path, e);
         }
 
-        // Generate embedding and store in vector DB (use base prompt for embedding)
-        let embedding = self.embedding_generator.generate(base_prompt).await?;
-        let vector_metadata = json!({
-            "type": "cache_entry",
-            "prompt": entry.prompt,
-            "prompt_key": entry.prompt_key,
-            "response": response,
-            "model": entry.model,
-            "parameters": entry.parameters,
-            "generated_at": generated_at,
-            "prompt_hash": prompt_hash,
-            "s5_path": path,
-        });
+        // Store in vector DB; metadata carries the entry minus its embedding
+        let mut vector_metadata = serde_json::to_value(CacheEntry {
+            embedding: Vec::new(),
+            ..entry.clone()
+        })?;
+        vector_metadata["type"] = json!("cache_entry");
+        vector_metadata["prompt_hash"] = json!(prompt_hash);
+        vector_metadata["s5_path"] = json!(path);
 
         // Store in vector DB with error handling
         if let Err(e) = tokio::time::timeout(
             Duration::from_secs(5),
-            self.vector_client
-                .insert_vector(&prompt_hash, embedding, vector_metadata),
+            self.vector_client.insert_vector(
+                &prompt_hash,
+                entry.embedding.clone(),
+                vector_metadata,
+            ),
         )
         .await
         {
@@ -403,6 +467,181 @@ impl PromptCache {
         })
     }
 
+    /// Rebuilds the in-memory index from entries persisted in S5
+    ///
+    /// Loads at most `max_entries` unexpired entries (and never more than
+    /// `max_cache_size_mb`), skipping prompts already in memory. Returns the
+    /// number of entries loaded.
+    pub async fn warm_from_s5(&self, max_entries: usize) -> Result<usize> {
+        let mut loaded = Vec::new();
+        let shards = self.s5_client.list_directory(PROMPTS_DIR).await?;
+
+        'shards: for shard in shards.iter().filter(|f| f.file_type != "file") {
+            let dir = format!("{}{}/", PROMPTS_DIR, shard.name);
+            let files = match self.s5_client.list_directory(&dir).await {
+                Ok(files) => files,
+                Err(e) => {
+                    eprintln!("Warning: failed to list {}: {}", dir, e);
+                    continue;
+                }
+            };
+
+            for file in files
+                .iter()
+                .filter(|f| f.file_type == "file" && f.name.ends_with(".json"))
+            {
+                if loaded.len() >= max_entries {
+                    break 'shards;
+                }
+
+                let path = format!("{}{}", dir, file.name);
+                let Ok((data, _metadata)) = self.s5_client.get(&path).await else {
+                    continue;
+                };
+                let Ok(mut entry) = serde_json::from_slice::<CacheEntry>(&data) else {
+                    continue;
+                };
+                entry.restore_expiry(self.config.ttl_seconds);
+                if entry.is_expired() {
+                    continue;
+                }
+                entry.size_bytes = entry_size(&entry.prompt_key, &entry.response);
+
+                let prompt_hash = file.name.trim_end_matches(".json").to_string();
+                loaded.push((prompt_hash, entry));
+            }
+        }
+
+        let mut entries = self.cache_entries.lock().unwrap();
+        let mut metrics = self.metrics.lock().unwrap();
+        let max_size_bytes = self.config.max_cache_size_mb * 1024 * 1024;
+        let mut count = 0;
+
+        for (prompt_hash, entry) in loaded {
+            if entries.contains_key(&prompt_hash) {
+                continue;
+            }
+            if metrics.cache_size_bytes + entry.size_bytes > max_size_bytes {
+                break;
+            }
+            metrics.cache_size_bytes += entry.size_bytes;
+            entries.insert(prompt_hash, entry);
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Removes every entry generated by `model`; returns the number invalidated
+    pub async fn invalidate_model(&self, model: &str) -> Result<usize> {
+        let filter = json!({
+            "type": "cache_entry",
+            "model": model,
+        });
+        let invalidated = self
+            .invalidate_where(filter.clone(), |entry_model, _| entry_model == model)
+            .await?;
+
+        // Catches points beyond the remote query limit; their S5 objects are
+        // left to the entry TTL
+        let remote_deleted = match self.vector_client.delete_by_metadata(&filter).await {
+            Ok(count) => count as usize,
+            Err(e) => {
+                eprintln!(
+                    "Warning: vector DB delete for model {} failed: {}",
+                    model, e
+                );
+                0
+            }
+        };
+
+        Ok(invalidated + remote_deleted)
+    }
+
+    /// Removes every entry whose prompt starts with `prefix`; returns the number invalidated
+    pub async fn invalidate_prefix(&self, prefix: &str) -> Result<usize> {
+        let filter = json!({ "type": "cache_entry" });
+        self.invalidate_where(filter, |_, prompt_key| prompt_key.starts_with(prefix))
+            .await
+    }
+
+    /// Drops matching entries from memory, the vector DB and S5
+    ///
+    /// `matches` receives the entry's model and full prompt key.
+    async fn invalidate_where<F>(&self, filter: JsonValue, matches: F) -> Result<usize>
+    where
+        F: Fn(&str, &str) -> bool,
+    {
+        let mut prompt_hashes: HashSet<String> = {
+            let mut entries = self.cache_entries.lock().unwrap();
+            let mut metrics = self.metrics.lock().unwrap();
+
+            let keys: Vec<String> = entries
+                .iter()
+                .filter(|(_, entry)| matches(&entry.model, &entry.prompt_key))
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in &keys {
+                if let Some(removed) = entries.remove(key) {
+                    metrics.cache_size_bytes =
+                        metrics.cache_size_bytes.saturating_sub(removed.size_bytes);
+                }
+            }
+            keys.into_iter().collect()
+        };
+
+        // Entries that were evicted from memory may still live in the vector DB
+        let removed_points = self
+            .vector_client
+            .delete_where(Some(filter.clone()), |metadata| {
+                let model = metadata.get("model").and_then(|m| m.as_str());
+                let prompt_key = metadata.get("prompt_key").and_then(|k| k.as_str());
+                matches(model.unwrap_or_default(), prompt_key.unwrap_or_default())
+            })
+            .await?;
+        prompt_hashes.extend(removed_points);
+
+        // Points written before a restart are only known to the remote vector DB
+        match self
+            .vector_client
+            .query_by_metadata(&filter, INVALIDATE_QUERY_LIMIT)
+            .await
+        {
+            Ok(points) => {
+                for point in points {
+                    let Some(id) = point
+                        .get("id")
+                        .and_then(|id| id.as_str())
+                        .filter(|id| id.len() >= 2 && id.is_ascii())
+                    else {
+                        continue;
+                    };
+                    let metadata = point.get("metadata").cloned().unwrap_or_default();
+                    let model = metadata.get("model").and_then(|m| m.as_str());
+                    let prompt_key = metadata.get("prompt_key").and_then(|k| k.as_str());
+                    if !matches(model.unwrap_or_default(), prompt_key.unwrap_or_default()) {
+                        continue;
+                    }
+                    if let Err(e) = self.vector_client.delete_vector(id).await {
+                        eprintln!("Warning: vector DB delete failed for {}: {}", id, e);
+                    }
+                    prompt_hashes.insert(id.to_string());
+                }
+            }
+            Err(e) => eprintln!("Warning: vector DB query for invalidation failed: {}", e),
+        }
+
+        // Remote-only entries are also in S5, where `get` would otherwise find them by hash
+        for prompt_hash in &prompt_hashes {
+            let path = entry_path(prompt_hash);
+            if let Err(e) = self.s5_client.delete(&path).await {
+                eprintln!("Warning: S5 delete failed for {}: {}", path, e);
+            }
+        }
+
+        Ok(prompt_hashes.len())
+    }
+
     pub async fn clear(&self) -> Result<()> {
         let mut entries = self.cache_entries.lock().unwrap();
         entries.clear();
@@ -413,3 +652,268 @@ impl PromptCache {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(generated_at: &str, expires_at: u64) -> CacheEntry {
+        CacheEntry {
+            prompt: "What is Rust?".to_string(),
+            prompt_key: "What is Rust?;model=llama".to_string(),
+            response: "A language".to_string(),
+            model: "llama".to_string(),
+            parameters: json!({}),
+            generated_at: generated_at.to_string(),
+            generation_time_ms: 10,
+            embedding: vec![0.1, 0.2],
+            expires_at,
+            created_at: SystemTime::now(),
+            size_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_is_expired_uses_epoch() {
+        let e = entry("2025-01-01T00:00:00.000Z", 1_000);
+        assert!(!e.is_expired_at(999));
+        assert!(e.is_expired_at(1_000));
+        assert!(!entry("", unix_now() + 60).is_expired());
+    }
+
+    #[test]
+    fn test_restore_expiry_from_generated_at() {
+        let mut e = entry("2025-01-01T00:00:00.000Z", 0);
+        e.restore_expiry(3600);
+        assert_eq!(e.expires_at, 1_735_689_600 + 3600);
+    }
+
+    #[test]
+    fn test_restore_expiry_unparseable_generated_at_is_servable() {
+        let mut e = entry("not-a-timestamp", 0);
+        e.restore_expiry(3600);
+        assert!(!e.is_expired());
+    }
+
+    #[test]
+    fn test_restore_expiry_keeps_existing_value() {
+        let mut e = entry("not-a-timestamp", 42);
+        e.restore_expiry(3600);
+        assert_eq!(e.expires_at, 42);
+    }
+
+    #[test]
+    fn test_entry_round_trip_keeps_embedding_and_expiry() {
+        let e = entry("2025-01-01T00:00:00.000Z", 1_000);
+        let restored: CacheEntry =
+            serde_json::from_str(&serde_json::to_string(&e).unwrap()).unwrap();
+        assert_eq!(restored.embedding, vec![0.1, 0.2]);
+        assert_eq!(restored.expires_at, 1_000);
+    }
+
+    async fn offline_cache() -> PromptCache {
+        PromptCache::new(CacheConfig {
+            s5_url: "http://127.0.0.1:1".to_string(),
+            vector_db_url: "http://127.0.0.1:1".to_string(),
+            similarity_threshold: 0.99,
+            ttl_seconds: 3600,
+            max_cache_size_mb: 10,
+            metric: DistanceMetric::Cosine,
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_model() {
+        let cache = offline_cache().await;
+        cache.put("What is Rust?;model=old", "a").await.unwrap();
+        cache.put("What is Go?;model=old", "b").await.unwrap();
+        cache.put("What is Rust?;model=new", "c").await.unwrap();
+
+        assert_eq!(cache.invalidate_model("old").await.unwrap(), 2);
+        assert_eq!(cache.invalidate_model("old").await.unwrap(), 0);
+        assert_eq!(
+            cache.get("What is Rust?;model=new").await.unwrap(),
+            Some("c".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_prefix() {
+        let cache = offline_cache().await;
+        cache.put("Translate: hello;model=m", "hola").await.unwrap();
+        cache.put("Translate: bye;model=m", "adios").await.unwrap();
+        cache.put("Summarize: text;model=m", "short").await.unwrap();
+
+        assert_eq!(cache.invalidate_prefix("Translate:").await.unwrap(), 2);
+        let metrics = cache.get_metrics().await.unwrap();
+        assert!(metrics.cache_size_mb > 0.0);
+        assert_eq!(
+            cache.get("Summarize: text;model=m").await.unwrap(),
+            Some("short".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_remote_only_entries() {
+        use axum::extract::Path;
+        use axum::routing::{delete, post};
+
+        let stale = "ab".repeat(32);
+        let points = json!({ "results": [
+            {
+                "id": stale,
+                "metadata": { "type": "cache_entry", "model": "m", "prompt_key": "Translate: old;model=m" }
+            },
+            {
+                "id": "cd".repeat(32),
+                "metadata": { "type": "cache_entry", "model": "m", "prompt_key": "Summarize: old;model=m" }
+            }
+        ]});
+        let s5_deletes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = s5_deletes.clone();
+        let app = axum::Router::new()
+            .route(
+                "/api/v1/vectors/query",
+                post(move || async move { axum::Json(points) }),
+            )
+            .route(
+                "/api/v1/vectors/delete",
+                post(|| async { axum::Json(json!({ "deletedCount": 1 })) }),
+            )
+            .route(
+                "/api/v1/vectors/:id",
+                delete(|| async { axum::Json(json!({ "status": "deleted" })) }),
+            )
+            .route(
+                "/s5/fs/*path",
+                delete(move |Path(path): Path<String>| async move {
+                    recorded.lock().unwrap().push(path);
+                    axum::Json(json!({}))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let cache = PromptCache::new(CacheConfig {
+            s5_url: url.clone(),
+            vector_db_url: url,
+            similarity_threshold: 0.99,
+            ttl_seconds: 3600,
+            max_cache_size_mb: 10,
+            metric: DistanceMetric::Cosine,
+        })
+        .await
+        .unwrap();
+
+        // Only the remote point with a matching prompt key is invalidated
+        assert_eq!(cache.invalidate_prefix("Translate:").await.unwrap(), 1);
+        assert_eq!(
+            *s5_deletes.lock().unwrap(),
+            vec![format!("cache/prompts/ab/{}.json", stale)]
+        );
+
+        // Both queried points plus the one reported by the delete-by-metadata sweep
+        assert_eq!(cache.invalidate_model("m").await.unwrap(), 3);
+        assert_eq!(s5_deletes.lock().unwrap().len(), 3);
+    }
+
+    /// In-memory S5 bridge serving PUT, GET and directory listings
+    async fn s5_bridge() -> String {
+        use axum::extract::Path;
+        use axum::http::StatusCode;
+        use axum::response::IntoResponse;
+        use axum::routing::get;
+
+        let files: Arc<Mutex<HashMap<String, Vec<u8>>>> = Arc::default();
+        let stored = files.clone();
+        let app = axum::Router::new().route(
+            "/s5/fs/*path",
+            get(move |Path(path): Path<String>| async move {
+                let files = files.lock().unwrap();
+                if !path.ends_with('/') {
+                    return match files.get(&path) {
+                        Some(data) => data.clone().into_response(),
+                        None => StatusCode::NOT_FOUND.into_response(),
+                    };
+                }
+                let mut listing: HashMap<String, JsonValue> = HashMap::new();
+                for (key, data) in files.iter() {
+                    let Some(rest) = key.strip_prefix(&path) else {
+                        continue;
+                    };
+                    let entry = match rest.split_once('/') {
+                        Some((dir, _)) => json!({ "name": dir, "size": 0, "type": "directory" }),
+                        None => json!({ "name": rest, "size": data.len(), "type": "file" }),
+                    };
+                    listing.insert(entry["name"].as_str().unwrap().to_string(), entry);
+                }
+                if listing.is_empty() {
+                    return StatusCode::NOT_FOUND.into_response();
+                }
+                axum::Json(listing.into_values().collect::<Vec<_>>()).into_response()
+            })
+            .put(
+                move |Path(path): Path<String>, body: axum::body::Bytes| async move {
+                    stored.lock().unwrap().insert(path, body.to_vec());
+                    axum::Json(json!({ "cid": "bcache" }))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_warm_from_s5_restores_entries() {
+        let s5_url = s5_bridge().await;
+        let config = CacheConfig {
+            s5_url,
+            vector_db_url: "http://127.0.0.1:1".to_string(),
+            similarity_threshold: 0.99,
+            ttl_seconds: 3600,
+            max_cache_size_mb: 10,
+            metric: DistanceMetric::Cosine,
+        };
+
+        let cache = PromptCache::new(config.clone()).await.unwrap();
+        cache.put("What is Rust?;model=m", "a").await.unwrap();
+        cache.put("What is Go?;model=m", "b").await.unwrap();
+        cache.put("What is Zig?;model=m", "c").await.unwrap();
+
+        // A fresh cache (e.g. after a restart) starts empty
+        let restarted = PromptCache::new(config).await.unwrap();
+        assert_eq!(restarted.get_metrics().await.unwrap().cache_size_mb, 0.0);
+
+        assert_eq!(restarted.warm_from_s5(2).await.unwrap(), 2);
+        assert_eq!(restarted.cache_entries.lock().unwrap().len(), 2);
+        assert!(restarted.get_metrics().await.unwrap().cache_size_mb > 0.0);
+
+        // Already loaded entries are skipped; the rest fill up to the limit
+        assert_eq!(restarted.warm_from_s5(10).await.unwrap(), 1);
+        for (prompt, response) in [
+            ("What is Rust?;model=m", "a"),
+            ("What is Go?;model=m", "b"),
+            ("What is Zig?;model=m", "c"),
+        ] {
+            let hash = restarted.hash_prompt(prompt);
+            assert_eq!(
+                restarted.cache_entries.lock().unwrap()[&hash].response,
+                response
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_prompt_key() {
+        let key = parse_prompt_key("Hello;model=tiny;temp=0.2;max_tokens=50");
+        assert_eq!(key.base_prompt, "Hello");
+        assert_eq!(key.model, "tiny");
+        assert_eq!(key.parameters["temperature"], json!(0.2));
+        assert_eq!(key.parameters["max_tokens"], json!(50));
+    }
+}
@@ -47,6 +47,10 @@ pub struct RegisterNodeArgs {
     /// Dry run mode - don't actually submit transactions
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Roll back successful chains if registration fails on any chain
+    #[arg(long)]
+    pub all_or_nothing: bool,
 }
 
 /// Arguments for registration-status command
@@ -153,6 +157,43 @@ pub async fn register_node(args: RegisterNodeArgs) -> Result<()> {
     let chain_registry = Arc::new(ChainRegistry::new());
     let registrar = MultiChainRegistrar::new(chain_registry, &private_key, metadata).await?;
 
+    if args.all_or_nothing {
+        let registrar = registrar.with_all_or_nothing(true);
+        println!(
+            "\n🚀 Registering on chains {:?} (all-or-nothing)...",
+            chain_ids
+        );
+
+        let statuses = registrar.register(&chain_ids).await?;
+        let mut all_succeeded = true;
+        for chain_id in &chain_ids {
+            match statuses.get(chain_id) {
+                Some(RegistrationStatus::Pending { tx_hash }) => {
+                    println!("  Chain {}: ✅ Submitted (tx: {:?})", chain_id, tx_hash);
+                }
+                Some(RegistrationStatus::Confirmed { block_number }) => {
+                    println!(
+                        "  Chain {}: ✅ Registered (block: {})",
+                        chain_id, block_number
+                    );
+                }
+                Some(RegistrationStatus::Failed { error }) => {
+                    all_succeeded = false;
+                    println!("  Chain {}: ❌ Failed: {}", chain_id, error);
+                }
+                Some(RegistrationStatus::NotRegistered) | None => {
+                    all_succeeded = false;
+                    println!("  Chain {}: ↩️  Not registered", chain_id);
+                }
+            }
+        }
+
+        if !all_succeeded {
+            return Err(anyhow!("Registration did not succeed on every chain"));
+        }
+        return Ok(());
+    }
+
     // Register on each chain
     for chain_id in chain_ids {
         println!("\n🚀 Registering on chain {}...", chain_id);
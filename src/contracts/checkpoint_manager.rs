@@ -11,7 +11,7 @@ use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
-use super::client::Web3Client;
+use super::client::{ContractCall, Web3Client};
 
 // S5 decentralized storage for off-chain proof storage (Phase 2.1)
 use crate::storage::s5_client::{S5Client, S5Storage};
@@ -20,7 +20,7 @@ use crate::storage::s5_client::{S5Client, S5Storage};
 use crate::checkpoint::{CheckpointMessage, CheckpointPublisher};
 
 #[cfg(feature = "real-ezkl")]
-use crate::crypto::ezkl::{EzklProver, WitnessBuilder};
+use crate::crypto::ezkl::{EzklProver, ProofCache, WitnessBuilder};
 
 const CHECKPOINT_THRESHOLD: u64 = 1000; // Submit checkpoint every 1000 tokens (production value to minimize streaming pauses)
                                         // Minimum tokens required for checkpoint submission (contract requirement)
@@ -31,6 +31,37 @@ const MIN_PROVEN_TOKENS: u64 = 100;
 /// from when the tx is confirmed (~1-2s later on Base Sepolia).
 const DISPUTE_WINDOW_BUFFER_SECS: u64 = 5;
 
+/// JobMarketplace `sessionJobs` getter; proofInterval is at index 10 of the result
+const SESSION_JOBS_ABI: &str = "function sessionJobs(uint256 jobId) external view returns (uint256, address, address, address, uint256, uint256, uint256, uint256, uint256, uint256, uint256, uint8, uint256, uint256, string, bytes32, string)";
+
+/// JobMarketplace `sessionModel` getter (AUDIT-F4)
+const SESSION_MODEL_ABI: &str =
+    "function sessionModel(uint256 sessionId) external view returns (bytes32)";
+
+/// Per-session values read from the JobMarketplace contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionChainState {
+    /// Session's proofInterval (minimum billable tokens)
+    pub proof_interval: u64,
+    /// Model ID the session was created for (bytes32(0) for legacy sessions)
+    pub model_id: [u8; 32],
+}
+
+/// Decode `sessionJobs` and `sessionModel` outputs into a `SessionChainState`
+fn decode_session_state(
+    session_job: &[ethers::abi::Token],
+    session_model: &[ethers::abi::Token],
+) -> Option<SessionChainState> {
+    let proof_interval = session_job.get(10)?.clone().into_uint()?;
+    let model_bytes = session_model.first()?.clone().into_fixed_bytes()?;
+    let model_id: [u8; 32] = model_bytes.try_into().ok()?;
+
+    Some(SessionChainState {
+        proof_interval: proof_interval.low_u64(),
+        model_id,
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct JobTokenTracker {
     pub job_id: u64,
@@ -271,9 +302,7 @@ impl CheckpointManager {
         //           maxDuration, startTime, lastProofTime, proofInterval, status, ...)
         let contract = ethers::contract::Contract::new(
             self.proof_system_address,
-            ethers::abi::parse_abi(&[
-                "function sessionJobs(uint256 jobId) external view returns (uint256, address, address, address, uint256, uint256, uint256, uint256, uint256, uint256, uint256, uint8, uint256, uint256, string, bytes32, string)"
-            ]).unwrap_or_default(),
+            ethers::abi::parse_abi(&[SESSION_JOBS_ABI]).unwrap_or_default(),
             self.web3_client.provider.clone(),
         );
 
@@ -328,6 +357,93 @@ impl CheckpointManager {
         }
     }
 
+    /// Query proofInterval and modelId for many sessions at once
+    ///
+    /// All reads go through `Web3Client::batch_read`, so the whole set costs a
+    /// single Multicall3 `eth_call` instead of two calls per job. Jobs whose
+    /// reads fail are left out of the result.
+    pub async fn query_session_states(&self, job_ids: &[u64]) -> HashMap<u64, SessionChainState> {
+        let abi = match ethers::abi::parse_abi(&[SESSION_JOBS_ABI, SESSION_MODEL_ABI]) {
+            Ok(abi) => abi,
+            Err(e) => {
+                warn!("⚠️ Failed to parse session ABI: {}", e);
+                return HashMap::new();
+            }
+        };
+        let (Ok(session_jobs), Ok(session_model)) =
+            (abi.function("sessionJobs"), abi.function("sessionModel"))
+        else {
+            return HashMap::new();
+        };
+
+        let calls: Vec<ContractCall> = job_ids
+            .iter()
+            .flat_map(|job_id| {
+                let arg = vec![ethers::abi::Token::Uint(U256::from(*job_id))];
+                [
+                    ContractCall::new(self.proof_system_address, session_jobs.clone(), arg.clone()),
+                    ContractCall::new(self.proof_system_address, session_model.clone(), arg),
+                ]
+            })
+            .collect();
+
+        let results = self.web3_client.batch_read(&calls).await;
+
+        let mut states = HashMap::new();
+        for (job_id, pair) in job_ids.iter().zip(results.chunks(2)) {
+            match pair {
+                [Ok(job), Ok(model)] => match decode_session_state(job, model) {
+                    Some(state) => {
+                        states.insert(*job_id, state);
+                    }
+                    None => warn!("⚠️ Unexpected session state encoding for job {}", job_id),
+                },
+                [job, model] => {
+                    let error = job.as_ref().err().or(model.as_ref().err());
+                    warn!(
+                        "⚠️ Failed to query session state for job {}: {:?}",
+                        job_id, error
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        states
+    }
+
+    /// Refresh every tracked job's proofInterval from the contract in one batch
+    ///
+    /// Trackers created while the contract was unreachable fall back to
+    /// `CHECKPOINT_THRESHOLD`; this brings them back in line with the chain.
+    /// Returns the number of trackers whose proofInterval changed.
+    pub async fn reconcile_job_trackers(&self) -> usize {
+        let job_ids: Vec<u64> = self.job_trackers.read().await.keys().copied().collect();
+        if job_ids.is_empty() {
+            return 0;
+        }
+
+        let states = self.query_session_states(&job_ids).await;
+
+        let mut trackers = self.job_trackers.write().await;
+        let mut updated = 0;
+        for (job_id, state) in states {
+            if let Some(tracker) = trackers.get_mut(&job_id) {
+                let proof_interval = std::cmp::max(state.proof_interval, MIN_PROVEN_TOKENS);
+                if tracker.proof_interval != proof_interval {
+                    info!(
+                        "📋 Reconciled proofInterval for job {}: {} -> {} tokens",
+                        job_id, tracker.proof_interval, proof_interval
+                    );
+                    tracker.proof_interval = proof_interval;
+                    updated += 1;
+                }
+            }
+        }
+
+        updated
+    }
+
     /// Track tokens generated for a specific job
     pub async fn track_tokens(
         &self,
@@ -608,10 +724,11 @@ impl CheckpointManager {
         // Use a path that works with both Mock and EnhancedS5 backends
         let proof_path = format!("home/proofs/job_{}_proof.bin", job_id);
 
-        // Upload to S5 - this will return a CID
+        // Upload to S5 - this will return a CID. Proofs are fetched by CID,
+        // so an identical proof that is already stored is not re-uploaded.
         let cid = self
             .s5_storage
-            .put(&proof_path, proof_bytes.to_vec())
+            .put_dedup(&proof_path, proof_bytes.to_vec())
             .await
             .map_err(|e| anyhow!("S5 upload failed: {}", e))?;
 
@@ -672,7 +789,7 @@ impl CheckpointManager {
                 .map_err(|e| anyhow!("Failed to build witness: {}", e))?;
 
             // Generate proof
-            let mut prover = EzklProver::new();
+            let mut prover = EzklProver::new().with_cache(ProofCache::shared());
             let proof_data = prover
                 .generate_proof(&witness)
                 .map_err(|e| anyhow!("Failed to generate proof: {}", e))?;
@@ -1216,7 +1333,7 @@ impl CheckpointManager {
                 .build()
                 .map_err(|e| anyhow!("Failed to build witness: {}", e))?;
 
-            let mut prover = EzklProver::new();
+            let mut prover = EzklProver::new().with_cache(ProofCache::shared());
             let proof_data = prover
                 .generate_proof(&witness)
                 .map_err(|e| anyhow!("Failed to generate proof: {}", e))?;
@@ -1261,7 +1378,7 @@ impl CheckpointManager {
         let proof_path = format!("home/proofs/job_{}_proof.bin", job_id);
 
         let cid = s5_storage
-            .put(&proof_path, proof_bytes.to_vec())
+            .put_dedup(&proof_path, proof_bytes.to_vec())
             .await
             .map_err(|e| anyhow!("S5 upload failed: {}", e))?;
 
@@ -1543,6 +1660,7 @@ impl CheckpointManager {
     }
 
     /// Complete a session job and trigger payment settlement
+    #[tracing::instrument(name = "settlement", skip(self))]
     pub async fn complete_session_job(
         &self,
         job_id: u64,
@@ -3328,6 +3446,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_session_state() {
+        use ethers::abi::Token;
+
+        let mut session_job = vec![Token::Uint(U256::zero()); 17];
+        session_job[10] = Token::Uint(U256::from(250));
+        let session_model = vec![Token::FixedBytes(vec![7u8; 32])];
+
+        assert_eq!(
+            decode_session_state(&session_job, &session_model),
+            Some(SessionChainState {
+                proof_interval: 250,
+                model_id: [7u8; 32],
+            })
+        );
+
+        // Truncated or mistyped outputs are rejected
+        assert!(decode_session_state(&session_job[..5], &session_model).is_none());
+        assert!(decode_session_state(&session_job, &[Token::Bool(true)]).is_none());
+    }
+
+    #[test]
+    fn test_session_abis_parse() {
+        let abi = ethers::abi::parse_abi(&[SESSION_JOBS_ABI, SESSION_MODEL_ABI]).unwrap();
+        assert_eq!(abi.function("sessionJobs").unwrap().outputs.len(), 17);
+        assert_eq!(
+            abi.function("sessionModel").unwrap().short_signature(),
+            SessionModelQuery::new(0).encode()[..4]
+        );
+    }
+
     // ========================================================================
     // Dispute Window Fix Tests (v8.17.5)
     // ========================================================================
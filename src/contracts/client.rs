@@ -3,11 +3,12 @@
 use anyhow::{anyhow, Result};
 use ethers::prelude::*;
 use ethers::providers::{Http, Provider};
+use ethers::types::transaction::eip2718::TypedTransaction;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
-use tracing::info;
+use tracing::{info, warn};
 
 use super::types::*;
 
@@ -20,6 +21,132 @@ pub struct Web3Config {
     pub private_key: Option<String>,
     pub max_reconnection_attempts: usize,
     pub reconnection_delay: Duration,
+    /// Transaction fee model (EIP-1559 or legacy gas price)
+    pub fee_mode: FeeMode,
+    /// Fixed `maxFeePerGas` instead of the fee-history estimate
+    pub max_fee_per_gas: Option<U256>,
+    /// Fixed `maxPriorityFeePerGas` instead of the fee-history estimate
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// Number of recent blocks sampled by `eth_feeHistory`
+    pub fee_history_blocks: u64,
+    /// Reward percentile used for the priority fee estimate
+    pub fee_history_percentile: f64,
+    /// How long a transaction may stay pending before its fees are bumped
+    pub tx_pending_timeout: Duration,
+    /// Percentage fees are raised by on each bump (nodes require at least 10)
+    pub fee_bump_percent: u64,
+    /// Maximum number of fee bumps before giving up on a transaction
+    pub max_fee_bumps: usize,
+    /// Defer submissions while the gas price is above this (gwei); `None` disables
+    pub max_gas_price_gwei: Option<f64>,
+    /// How long deferred submissions wait before gas is checked again
+    pub gas_ceiling_retry_after: Duration,
+}
+
+/// The current gas price is above the configured ceiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasCeilingExceeded {
+    pub gas_price: U256,
+    pub max_gas_price: U256,
+}
+
+impl std::fmt::Display for GasCeilingExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "gas price {} wei exceeds ceiling {} wei",
+            self.gas_price, self.max_gas_price
+        )
+    }
+}
+
+/// Convert a gwei amount to wei
+pub fn gwei_to_wei(gwei: f64) -> U256 {
+    U256::from((gwei.max(0.0) * 1e9).round() as u128)
+}
+
+/// Read a gas price ceiling in gwei from an environment variable
+fn max_gas_price_from_env(var: &str) -> Option<f64> {
+    std::env::var(var).ok().and_then(|v| v.parse().ok())
+}
+
+/// How transaction fees are priced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeMode {
+    /// EIP-1559 dynamic fees, falling back to legacy if the chain has no base fee
+    Eip1559,
+    /// Legacy `gasPrice` transactions
+    Legacy,
+}
+
+/// Fees attached to a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxFees {
+    Eip1559 {
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    },
+    Legacy {
+        gas_price: U256,
+    },
+}
+
+impl TxFees {
+    /// Raise every fee by `percent`, rounding up so a bump always changes the fee
+    pub fn bumped(&self, percent: u64) -> Self {
+        let bump = |fee: U256| {
+            let increase = (fee * U256::from(percent) + U256::from(99)) / U256::from(100);
+            fee + increase.max(U256::one())
+        };
+        match *self {
+            TxFees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => TxFees::Eip1559 {
+                max_fee_per_gas: bump(max_fee_per_gas),
+                max_priority_fee_per_gas: bump(max_priority_fee_per_gas),
+            },
+            TxFees::Legacy { gas_price } => TxFees::Legacy {
+                gas_price: bump(gas_price),
+            },
+        }
+    }
+}
+
+/// Derive EIP-1559 fees from `eth_feeHistory`
+///
+/// The max fee allows the base fee to double before the transaction is
+/// priced out. Returns `None` if the chain reports no base fee.
+fn eip1559_fees_from_history(history: &FeeHistory, config: &Web3Config) -> Option<TxFees> {
+    // The last entry is the base fee of the next block
+    let base_fee = *history.base_fee_per_gas.last()?;
+    if base_fee.is_zero() {
+        return None;
+    }
+
+    let max_priority_fee_per_gas = config.max_priority_fee_per_gas.unwrap_or_else(|| {
+        let rewards: Vec<U256> = history
+            .reward
+            .iter()
+            .filter_map(|block| block.first().copied())
+            .collect();
+        if rewards.is_empty() {
+            U256::one()
+        } else {
+            let total = rewards.iter().fold(U256::zero(), |acc, r| acc + r);
+            (total / U256::from(rewards.len())).max(U256::one())
+        }
+    });
+
+    let max_fee_per_gas = config
+        .max_fee_per_gas
+        .unwrap_or(base_fee * 2 + max_priority_fee_per_gas)
+        .max(max_priority_fee_per_gas);
+
+    Some(TxFees::Eip1559 {
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+    })
 }
 
 impl Default for Web3Config {
@@ -32,6 +159,16 @@ impl Default for Web3Config {
             private_key: None,
             max_reconnection_attempts: 3,
             reconnection_delay: Duration::from_millis(100),
+            fee_mode: FeeMode::Eip1559,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            fee_history_blocks: 10,
+            fee_history_percentile: 50.0,
+            tx_pending_timeout: Duration::from_secs(60),
+            fee_bump_percent: 15,
+            max_fee_bumps: 3,
+            max_gas_price_gwei: max_gas_price_from_env("MAX_GAS_PRICE_GWEI"),
+            gas_ceiling_retry_after: Duration::from_secs(60),
         }
     }
 }
@@ -41,6 +178,8 @@ pub struct ChainConfig {
     pub name: String,
     pub chain_id: u64,
     pub rpc_url: String,
+    /// Gas price ceiling for submissions on this chain (gwei)
+    pub max_gas_price_gwei: Option<f64>,
 }
 
 impl ChainConfig {
@@ -49,6 +188,7 @@ impl ChainConfig {
             name: "Base Mainnet".to_string(),
             chain_id: 8453,
             rpc_url: "https://mainnet.base.org".to_string(),
+            max_gas_price_gwei: max_gas_price_from_env("BASE_MAINNET_MAX_GAS_PRICE_GWEI"),
         }
     }
 
@@ -57,8 +197,97 @@ impl ChainConfig {
             name: "Base Sepolia".to_string(),
             chain_id: 84532,
             rpc_url: "https://sepolia.base.org".to_string(),
+            max_gas_price_gwei: max_gas_price_from_env("BASE_SEPOLIA_MAX_GAS_PRICE_GWEI"),
+        }
+    }
+}
+
+/// Maximum number of calls packed into one Multicall3 `aggregate3`
+const MULTICALL_BATCH_SIZE: usize = 100;
+
+/// A read-only contract call for `Web3Client::batch_read`
+#[derive(Debug, Clone)]
+pub struct ContractCall {
+    pub target: Address,
+    pub function: ethers::abi::Function,
+    pub args: Vec<ethers::abi::Token>,
+}
+
+impl ContractCall {
+    pub fn new(
+        target: Address,
+        function: ethers::abi::Function,
+        args: Vec<ethers::abi::Token>,
+    ) -> Self {
+        Self {
+            target,
+            function,
+            args,
         }
     }
+
+    fn encode(&self) -> Result<Bytes> {
+        let data = self
+            .function
+            .encode_input(&self.args)
+            .map_err(|e| anyhow!("Failed to encode {} call: {}", self.function.name, e))?;
+        Ok(Bytes::from(data))
+    }
+
+    fn decode(&self, output: &[u8]) -> Result<Vec<ethers::abi::Token>> {
+        self.function
+            .decode_output(output)
+            .map_err(|e| anyhow!("Failed to decode {} result: {}", self.function.name, e))
+    }
+}
+
+/// Serializes nonce allocation for the host wallet
+///
+/// Every sender sharing an `Arc<Web3Client>` draws nonces from one in-memory
+/// counter, seeded from `eth_getTransactionCount(pending)`. After a failed
+/// send the counter is dropped and reseeded from the chain on next use, so a
+/// nonce that never reached the mempool is handed out again.
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    next: tokio::sync::Mutex<Option<U256>>,
+}
+
+impl NonceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate the next nonce, seeding the counter with `fetch_pending` if needed
+    pub async fn allocate<F, Fut>(&self, fetch_pending: F) -> Result<U256>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<U256>>,
+    {
+        let mut next = self.next.lock().await;
+        let nonce = match *next {
+            Some(nonce) => nonce,
+            None => fetch_pending().await?,
+        };
+        *next = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Replace the counter with the chain's pending nonce
+    pub async fn resync<F, Fut>(&self, fetch_pending: F) -> Result<U256>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<U256>>,
+    {
+        let mut next = self.next.lock().await;
+        let nonce = fetch_pending().await?;
+        *next = Some(nonce);
+        Ok(nonce)
+    }
+
+    /// Forget the counter so the next allocation reseeds from the chain
+    pub async fn invalidate(&self) {
+        *self.next.lock().await = None;
+    }
 }
 
 pub struct Web3Client {
@@ -67,6 +296,10 @@ pub struct Web3Client {
     config: Web3Config,
     contract_addresses: Arc<RwLock<HashMap<String, Address>>>,
     multicall: Arc<RwLock<Option<Multicall3<Provider<Http>>>>>,
+    /// Whether Multicall3 is deployed on this chain (`None` until checked)
+    multicall_available: Arc<RwLock<Option<bool>>>,
+    /// Nonce allocation shared by all senders using this client
+    nonce_manager: Arc<NonceManager>,
     block_stream_sender: Arc<RwLock<Option<mpsc::Sender<Block<H256>>>>>,
 }
 
@@ -109,6 +342,8 @@ impl Web3Client {
             config,
             contract_addresses: Arc::new(RwLock::new(HashMap::new())),
             multicall: Arc::new(RwLock::new(None)),
+            multicall_available: Arc::new(RwLock::new(None)),
+            nonce_manager: Arc::new(NonceManager::new()),
             block_stream_sender: Arc::new(RwLock::new(None)),
         })
     }
@@ -251,6 +486,7 @@ impl Web3Client {
         // This is a blocking operation, should be refactored in production
         futures::executor::block_on(async {
             *self.wallet.write().await = Some(signer);
+            self.nonce_manager.invalidate().await;
         });
 
         Ok(())
@@ -283,21 +519,133 @@ impl Web3Client {
         to: Address,
         value: U256,
         data: Option<Bytes>,
+    ) -> Result<H256> {
+        let fees = self.estimate_fees().await?;
+        self.send_transaction_with_fees(to, value, data, fees, None)
+            .await
+    }
+
+    /// Send a transaction and raise its fees if it stays pending
+    ///
+    /// When the transaction is not mined within `tx_pending_timeout`, it is
+    /// replaced (same nonce) with fees bumped by `fee_bump_percent`, up to
+    /// `max_fee_bumps` times. Returns the hash of the transaction that was mined.
+    pub async fn send_transaction_with_fee_bump(
+        &self,
+        to: Address,
+        value: U256,
+        data: Option<Bytes>,
+    ) -> Result<H256> {
+        let nonce = self.next_nonce().await?;
+        let mut fees = self.estimate_fees().await?;
+        let mut sent: Vec<H256> = Vec::new();
+
+        for bump in 0..=self.config.max_fee_bumps {
+            if bump > 0 {
+                fees = fees.bumped(self.config.fee_bump_percent);
+                warn!(
+                    "Transaction with nonce {} pending for over {:?}, resubmitting with fees {:?} (bump {}/{})",
+                    nonce, self.config.tx_pending_timeout, fees, bump, self.config.max_fee_bumps
+                );
+            }
+
+            match self
+                .send_transaction_with_fees(to, value, data.clone(), fees, Some(nonce))
+                .await
+            {
+                Ok(tx_hash) => sent.push(tx_hash),
+                // An earlier submission may have been mined in the meantime
+                Err(e) if !sent.is_empty() => {
+                    warn!("Replacement transaction rejected: {}", e);
+                }
+                Err(e) => {
+                    // The allocated nonce was never used
+                    self.nonce_manager.invalidate().await;
+                    return Err(e);
+                }
+            }
+
+            if let Some(mined) = self
+                .wait_for_any_mined(&sent, self.config.tx_pending_timeout)
+                .await?
+            {
+                return Ok(mined);
+            }
+        }
+
+        Err(anyhow!(
+            "Transaction with nonce {} still pending after {} fee bumps: {:?}",
+            nonce,
+            self.config.max_fee_bumps,
+            sent
+        ))
+    }
+
+    /// Sign and send a transaction with explicit fees and optional nonce
+    async fn send_transaction_with_fees(
+        &self,
+        to: Address,
+        value: U256,
+        data: Option<Bytes>,
+        fees: TxFees,
+        nonce: Option<U256>,
     ) -> Result<H256> {
         let wallet_guard = self.wallet.read().await;
         let wallet = wallet_guard
             .as_ref()
             .ok_or_else(|| anyhow!("No wallet configured"))?;
 
-        let mut tx = TransactionRequest::new().to(to).value(value);
+        let mut tx: TypedTransaction = match fees {
+            TxFees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => Eip1559TransactionRequest::new()
+                .to(to)
+                .value(value)
+                .max_fee_per_gas(max_fee_per_gas)
+                .max_priority_fee_per_gas(max_priority_fee_per_gas)
+                .chain_id(self.config.chain_id)
+                .into(),
+            TxFees::Legacy { gas_price } => TransactionRequest::new()
+                .to(to)
+                .value(value)
+                .gas_price(gas_price)
+                .chain_id(self.config.chain_id)
+                .into(),
+        };
 
         if let Some(data) = data {
-            tx = tx.data(data);
+            tx.set_data(data);
         }
 
+        // Take a nonce from the shared counter unless the caller is replacing
+        // a transaction with a known nonce
+        let allocated = nonce.is_none();
+        let nonce = match nonce {
+            Some(nonce) => nonce,
+            None => {
+                // The wallet guard is held, so read its address directly
+                let address = wallet.address();
+                self.nonce_manager
+                    .allocate(|| self.get_pending_nonce(address))
+                    .await?
+            }
+        };
+        tx.set_nonce(nonce);
+
         // CRITICAL: Use send_transaction which signs locally with SignerMiddleware
         // This should use eth_sendRawTransaction, not eth_sendTransaction
-        let pending_tx = wallet.send_transaction(tx, None).await
+        let send_result = wallet.send_transaction(tx, None).await;
+        if let Err(e) = &send_result {
+            if allocated {
+                warn!(
+                    "Send with nonce {} failed, resyncing nonce from chain: {}",
+                    nonce, e
+                );
+                self.nonce_manager.invalidate().await;
+            }
+        }
+        let pending_tx = send_result
             .map_err(|e| {
                 // Check if it's the eth_sendTransaction error
                 if e.to_string().contains("eth_sendTransaction") ||
@@ -390,18 +738,153 @@ impl Web3Client {
         Ok(multicall)
     }
 
+    /// Execute read-only calls and decode their results
+    ///
+    /// Calls are batched into Multicall3 `aggregate3` requests so N reads cost
+    /// one `eth_call` per `MULTICALL_BATCH_SIZE` calls. On chains without
+    /// Multicall3 deployed, or if the aggregate call fails, each call is made
+    /// individually. Results are returned in the same order as `calls`; a
+    /// failed or undecodable call yields an `Err` in its slot.
+    pub async fn batch_read(&self, calls: &[ContractCall]) -> Vec<Result<Vec<ethers::abi::Token>>> {
+        if calls.is_empty() {
+            return Vec::new();
+        }
+
+        if let Some(multicall) = self.available_multicall().await {
+            let mut results = Vec::with_capacity(calls.len());
+            let mut batched = true;
+
+            for chunk in calls.chunks(MULTICALL_BATCH_SIZE) {
+                match self.aggregate_chunk(&multicall, chunk).await {
+                    Ok(chunk_results) => results.extend(chunk_results),
+                    Err(e) => {
+                        warn!(
+                            "Multicall3 batch failed, falling back to sequential calls: {}",
+                            e
+                        );
+                        batched = false;
+                        break;
+                    }
+                }
+            }
+
+            if batched {
+                return results;
+            }
+        }
+
+        let mut results = Vec::with_capacity(calls.len());
+        for call in calls {
+            results.push(self.single_read(call).await);
+        }
+        results
+    }
+
+    async fn aggregate_chunk(
+        &self,
+        multicall: &Multicall3<Provider<Http>>,
+        calls: &[ContractCall],
+    ) -> Result<Vec<Result<Vec<ethers::abi::Token>>>> {
+        // Encoding errors are reported per call; only encodable calls are sent
+        let encoded: Vec<Result<Bytes>> = calls.iter().map(ContractCall::encode).collect();
+        let call3s: Vec<Call3> = calls
+            .iter()
+            .zip(&encoded)
+            .filter_map(|(call, data)| {
+                data.as_ref().ok().map(|data| Call3 {
+                    target: call.target,
+                    allow_failure: true,
+                    call_data: data.clone(),
+                })
+            })
+            .collect();
+
+        let mut returned = if call3s.is_empty() {
+            Vec::new()
+        } else {
+            multicall.aggregate_3(call3s).call().await?
+        }
+        .into_iter();
+
+        let mut results = Vec::with_capacity(calls.len());
+        for (call, data) in calls.iter().zip(encoded) {
+            let result = match data {
+                Err(e) => Err(e),
+                Ok(_) => {
+                    let ret = returned
+                        .next()
+                        .ok_or_else(|| anyhow!("Multicall3 returned too few results"))?;
+                    if ret.success {
+                        call.decode(&ret.return_data)
+                    } else {
+                        Err(anyhow!("{} call reverted", call.function.name))
+                    }
+                }
+            };
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    async fn single_read(&self, call: &ContractCall) -> Result<Vec<ethers::abi::Token>> {
+        let tx = TransactionRequest::new()
+            .to(call.target)
+            .data(call.encode()?);
+        let output = self.provider.call(&tx.into(), None).await?;
+        call.decode(&output)
+    }
+
+    /// Multicall3 contract if it is deployed on the current chain
+    async fn available_multicall(&self) -> Option<Multicall3<Provider<Http>>> {
+        if *self.multicall_available.read().await == Some(false) {
+            return None;
+        }
+
+        let multicall = match self.multicall.read().await.clone() {
+            Some(multicall) => multicall,
+            None => self.create_multicall().await.ok()?,
+        };
+
+        if self.multicall_available.read().await.is_none() {
+            let deployed = match self.provider.get_code(multicall.address(), None).await {
+                Ok(code) => !code.is_empty(),
+                Err(e) => {
+                    warn!("Failed to check for Multicall3 deployment: {}", e);
+                    return None;
+                }
+            };
+            if !deployed {
+                info!(
+                    "Multicall3 not deployed at {:?}, using sequential contract reads",
+                    multicall.address()
+                );
+            }
+            *self.multicall_available.write().await = Some(deployed);
+            if !deployed {
+                return None;
+            }
+        }
+
+        Some(multicall)
+    }
+
     pub async fn switch_network(&mut self, chain_config: ChainConfig) -> Result<()> {
         self.config.rpc_url = chain_config.rpc_url;
         self.config.chain_id = chain_config.chain_id;
+        self.config.max_gas_price_gwei = chain_config.max_gas_price_gwei;
 
         // Recreate provider
         let provider = Provider::<Http>::try_from(&self.config.rpc_url)?
             .interval(self.config.polling_interval);
 
         self.provider = Arc::new(provider);
+        *self.multicall.write().await = None;
+        *self.multicall_available.write().await = None;
 
         // Clear wallet to avoid issues
         *self.wallet.write().await = None;
+        self.nonce_manager.invalidate().await;
 
         Ok(())
     }
@@ -458,9 +941,141 @@ impl Web3Client {
         Ok(gas_price)
     }
 
+    /// Compare the current gas price with the chain's `max_gas_price_gwei`
+    ///
+    /// Returns `Some` when submitting now would pay more than the ceiling.
+    pub async fn check_gas_ceiling(&self) -> Result<Option<GasCeilingExceeded>> {
+        let Some(max_gwei) = self.config.max_gas_price_gwei else {
+            return Ok(None);
+        };
+
+        let gas_price = self.get_gas_price().await?;
+        let max_gas_price = gwei_to_wei(max_gwei);
+        if gas_price > max_gas_price {
+            Ok(Some(GasCeilingExceeded {
+                gas_price,
+                max_gas_price,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Delay before deferred submissions are retried
+    pub fn gas_ceiling_retry_after(&self) -> Duration {
+        self.config.gas_ceiling_retry_after
+    }
+
+    /// Whether submissions can be deferred for gas at all
+    pub fn has_gas_ceiling(&self) -> bool {
+        self.config.max_gas_price_gwei.is_some()
+    }
+
+    /// Returns `(max_fee_per_gas, max_priority_fee_per_gas)` from fee history,
+    /// with any overrides from `Web3Config` applied
     pub async fn get_eip1559_gas_price(&self) -> Result<(U256, U256)> {
-        let (max_fee, priority_fee) = self.provider.estimate_eip1559_fees(None).await?;
-        Ok((max_fee, priority_fee))
+        match self.estimate_eip1559_fees().await? {
+            Some(TxFees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            }) => Ok((max_fee_per_gas, max_priority_fee_per_gas)),
+            _ => Err(anyhow!("Chain does not support EIP-1559 fees")),
+        }
+    }
+
+    /// Fees for the next transaction according to the configured `FeeMode`
+    ///
+    /// In EIP-1559 mode, chains without a base fee fall back to legacy pricing.
+    pub async fn estimate_fees(&self) -> Result<TxFees> {
+        if self.config.fee_mode == FeeMode::Eip1559 {
+            match self.estimate_eip1559_fees().await {
+                Ok(Some(fees)) => return Ok(fees),
+                Ok(None) => {
+                    warn!("Chain reports no base fee, using legacy gas pricing");
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to fetch fee history, using legacy gas pricing: {}",
+                        e
+                    );
+                }
+            }
+        }
+
+        let gas_price = self.get_gas_price().await?;
+        Ok(TxFees::Legacy { gas_price })
+    }
+
+    /// EIP-1559 fees from fee history, or `None` if the chain has no base fee
+    async fn estimate_eip1559_fees(&self) -> Result<Option<TxFees>> {
+        let history = self
+            .provider
+            .fee_history(
+                self.config.fee_history_blocks.max(1),
+                BlockNumber::Latest,
+                &[self.config.fee_history_percentile],
+            )
+            .await?;
+
+        Ok(eip1559_fees_from_history(&history, &self.config))
+    }
+
+    fn wallet_address(&self) -> Result<Address> {
+        let address = self.address();
+        if address.is_zero() {
+            return Err(anyhow!("No wallet configured"));
+        }
+        Ok(address)
+    }
+
+    /// Allocate the nonce for the next transaction from the shared counter
+    pub async fn next_nonce(&self) -> Result<U256> {
+        let address = self.wallet_address()?;
+        self.nonce_manager
+            .allocate(|| self.get_pending_nonce(address))
+            .await
+    }
+
+    /// Reseed the nonce counter from the chain's pending transaction count
+    pub async fn resync_nonce(&self) -> Result<U256> {
+        let address = self.wallet_address()?;
+        let nonce = self
+            .nonce_manager
+            .resync(|| self.get_pending_nonce(address))
+            .await?;
+        info!("Nonce resynced from chain: {}", nonce);
+        Ok(nonce)
+    }
+
+    /// Nonce for the next transaction, counting ones still in the mempool
+    async fn get_pending_nonce(&self, address: Address) -> Result<U256> {
+        let nonce = self
+            .provider
+            .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+            .await?;
+        Ok(nonce)
+    }
+
+    /// Poll until one of `tx_hashes` is mined, or `None` after `timeout`
+    async fn wait_for_any_mined(
+        &self,
+        tx_hashes: &[H256],
+        timeout: Duration,
+    ) -> Result<Option<H256>> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            for tx_hash in tx_hashes {
+                if let Ok(Some(receipt)) = self.provider.get_transaction_receipt(*tx_hash).await {
+                    return Ok(Some(receipt.transaction_hash));
+                }
+            }
+
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(self.config.polling_interval.max(Duration::from_millis(500))).await;
+        }
     }
 
     pub async fn subscribe_blocks(&self) -> Result<mpsc::Receiver<Block<H256>>> {
@@ -495,3 +1110,179 @@ impl Web3Client {
         Ok(rx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(base_fees: &[u64], rewards: &[u64]) -> FeeHistory {
+        FeeHistory {
+            base_fee_per_gas: base_fees.iter().map(|f| U256::from(*f)).collect(),
+            gas_used_ratio: vec![0.5; rewards.len()],
+            oldest_block: U256::zero(),
+            reward: rewards.iter().map(|r| vec![U256::from(*r)]).collect(),
+        }
+    }
+
+    #[test]
+    fn test_fees_from_history() {
+        let config = Web3Config::default();
+        let fees = eip1559_fees_from_history(&history(&[90, 100], &[2, 4]), &config);
+
+        assert_eq!(
+            fees,
+            Some(TxFees::Eip1559 {
+                max_fee_per_gas: U256::from(203),
+                max_priority_fee_per_gas: U256::from(3),
+            })
+        );
+    }
+
+    #[test]
+    fn test_fee_overrides() {
+        let config = Web3Config {
+            max_fee_per_gas: Some(U256::from(500)),
+            max_priority_fee_per_gas: Some(U256::from(7)),
+            ..Default::default()
+        };
+        let fees = eip1559_fees_from_history(&history(&[100], &[2]), &config);
+
+        assert_eq!(
+            fees,
+            Some(TxFees::Eip1559 {
+                max_fee_per_gas: U256::from(500),
+                max_priority_fee_per_gas: U256::from(7),
+            })
+        );
+    }
+
+    #[test]
+    fn test_no_base_fee_means_legacy() {
+        let config = Web3Config::default();
+        assert!(eip1559_fees_from_history(&history(&[], &[]), &config).is_none());
+        assert!(eip1559_fees_from_history(&history(&[0, 0], &[1]), &config).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_nonce_manager_allocates_sequentially() {
+        let manager = NonceManager::new();
+
+        let first = manager
+            .allocate(|| async { Ok(U256::from(5)) })
+            .await
+            .unwrap();
+        // The seed is only fetched once
+        let second = manager
+            .allocate(|| async { Ok(U256::from(99)) })
+            .await
+            .unwrap();
+
+        assert_eq!(first, U256::from(5));
+        assert_eq!(second, U256::from(6));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_manager_reseeds_after_invalidate() {
+        let manager = NonceManager::new();
+        manager
+            .allocate(|| async { Ok(U256::from(5)) })
+            .await
+            .unwrap();
+        manager
+            .allocate(|| async { Ok(U256::from(5)) })
+            .await
+            .unwrap();
+
+        manager.invalidate().await;
+        let nonce = manager
+            .allocate(|| async { Ok(U256::from(6)) })
+            .await
+            .unwrap();
+        assert_eq!(nonce, U256::from(6));
+
+        manager
+            .resync(|| async { Ok(U256::from(10)) })
+            .await
+            .unwrap();
+        let nonce = manager
+            .allocate(|| async { Ok(U256::zero()) })
+            .await
+            .unwrap();
+        assert_eq!(nonce, U256::from(10));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_manager_failed_seed_is_retried() {
+        let manager = NonceManager::new();
+        assert!(manager
+            .allocate(|| async { Err(anyhow!("rpc down")) })
+            .await
+            .is_err());
+        let nonce = manager
+            .allocate(|| async { Ok(U256::from(3)) })
+            .await
+            .unwrap();
+        assert_eq!(nonce, U256::from(3));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_manager_concurrent_allocations_are_unique() {
+        let manager = Arc::new(NonceManager::new());
+        let mut handles = Vec::new();
+        for _ in 0..32 {
+            let manager = manager.clone();
+            handles.push(tokio::spawn(async move {
+                manager
+                    .allocate(|| async { Ok(U256::from(100)) })
+                    .await
+                    .unwrap()
+            }));
+        }
+
+        let mut nonces = Vec::new();
+        for handle in handles {
+            nonces.push(handle.await.unwrap().as_u64());
+        }
+        nonces.sort_unstable();
+        assert_eq!(nonces, (100..132).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn test_shared_types_are_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<NonceManager>();
+        assert_send_sync::<Web3Client>();
+    }
+
+    #[test]
+    fn test_gwei_to_wei() {
+        assert_eq!(gwei_to_wei(1.0), U256::from(1_000_000_000u64));
+        assert_eq!(gwei_to_wei(0.05), U256::from(50_000_000u64));
+        assert_eq!(gwei_to_wei(-3.0), U256::zero());
+    }
+
+    #[test]
+    fn test_fee_bump() {
+        let fees = TxFees::Eip1559 {
+            max_fee_per_gas: U256::from(1000),
+            max_priority_fee_per_gas: U256::from(1),
+        };
+        assert_eq!(
+            fees.bumped(15),
+            TxFees::Eip1559 {
+                max_fee_per_gas: U256::from(1150),
+                max_priority_fee_per_gas: U256::from(2),
+            }
+        );
+
+        let legacy = TxFees::Legacy {
+            gas_price: U256::from(100),
+        };
+        assert_eq!(
+            legacy.bumped(10),
+            TxFees::Legacy {
+                gas_price: U256::from(110)
+            }
+        );
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Deferral of on-chain submissions while gas is above the chain's ceiling
+//!
+//! Submission paths push work into a `DeferredQueue` instead of paying an
+//! absurd gas price. `spawn_gas_retry_task` rechecks gas every
+//! `gas_ceiling_retry_after` and hands the queued items back once it drops.
+
+use ethers::types::U256;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::client::{GasCeilingExceeded, Web3Client};
+
+/// A submission that was queued for the gas retry task instead of sent
+///
+/// Returned as the error of a submission attempt. Retry loops must treat it
+/// as final: the item is already queued, and trying again would only queue
+/// it again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubmissionDeferred {
+    pub job_id: U256,
+    pub exceeded: GasCeilingExceeded,
+}
+
+impl std::fmt::Display for SubmissionDeferred {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "submission for job {} deferred: {}",
+            self.job_id, self.exceeded
+        )
+    }
+}
+
+impl std::error::Error for SubmissionDeferred {}
+
+/// Whether a failed submission was deferred rather than attempted
+pub fn is_deferred(error: &anyhow::Error) -> bool {
+    error.is::<SubmissionDeferred>()
+}
+
+/// An item waiting for gas to drop
+#[derive(Debug, Clone)]
+pub struct DeferredItem<T> {
+    pub item: T,
+    pub deferred_at: Instant,
+}
+
+/// Thread-safe queue of deferred submissions
+#[derive(Debug)]
+pub struct DeferredQueue<T> {
+    items: Mutex<Vec<DeferredItem<T>>>,
+}
+
+impl<T> Default for DeferredQueue<T> {
+    fn default() -> Self {
+        Self {
+            items: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<T> DeferredQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an item for a later retry
+    pub fn push(&self, item: T) {
+        self.items.lock().unwrap().push(DeferredItem {
+            item,
+            deferred_at: Instant::now(),
+        });
+    }
+
+    /// Queue an item unless one with the same key is already waiting.
+    /// Returns whether it was queued.
+    pub fn push_unique<K: PartialEq>(&self, item: T, key: impl Fn(&T) -> K) -> bool {
+        let mut items = self.items.lock().unwrap();
+        let item_key = key(&item);
+        if items.iter().any(|queued| key(&queued.item) == item_key) {
+            return false;
+        }
+        items.push(DeferredItem {
+            item,
+            deferred_at: Instant::now(),
+        });
+        true
+    }
+
+    /// Remove and return every queued item, oldest first
+    pub fn take_all(&self) -> Vec<DeferredItem<T>> {
+        std::mem::take(&mut *self.items.lock().unwrap())
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Retry deferred items whenever gas is back under the ceiling
+///
+/// `retry` is called once per item. It should run the normal submission
+/// path, which defers the item again if gas has spiked in the meantime.
+pub fn spawn_gas_retry_task<T, F, Fut>(
+    web3_client: Arc<Web3Client>,
+    queue: Arc<DeferredQueue<T>>,
+    retry: F,
+) -> JoinHandle<()>
+where
+    T: Send + 'static,
+    F: Fn(T) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(web3_client.gas_ceiling_retry_after()).await;
+
+            if queue.is_empty() {
+                continue;
+            }
+
+            match web3_client.check_gas_ceiling().await {
+                Ok(None) => {
+                    let items = queue.take_all();
+                    info!(
+                        "⛽ Gas back under ceiling, retrying {} deferred submissions",
+                        items.len()
+                    );
+                    for deferred in items {
+                        debug!(
+                            "Retrying submission deferred for {:?}",
+                            deferred.deferred_at.elapsed()
+                        );
+                        retry(deferred.item).await;
+                    }
+                }
+                Ok(Some(exceeded)) => {
+                    debug!(
+                        "⛽ Still deferring {} submissions: {}",
+                        queue.len(),
+                        exceeded
+                    );
+                }
+                Err(e) => {
+                    warn!("Failed to check gas price for deferred submissions: {}", e);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_take_all_preserves_order() {
+        let queue = DeferredQueue::new();
+        queue.push(1);
+        queue.push(2);
+        queue.push(3);
+        assert_eq!(queue.len(), 3);
+
+        let items: Vec<i32> = queue.take_all().into_iter().map(|d| d.item).collect();
+        assert_eq!(items, vec![1, 2, 3]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_requeue_after_take() {
+        let queue = DeferredQueue::new();
+        queue.push("proof");
+        let taken = queue.take_all();
+        // A retry that is deferred again lands back in the queue
+        queue.push(taken[0].item);
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn test_push_unique_skips_queued_key() {
+        let queue = DeferredQueue::new();
+        assert!(queue.push_unique((1, "first"), |item| item.0));
+        assert!(!queue.push_unique((1, "again"), |item| item.0));
+        assert!(queue.push_unique((2, "other"), |item| item.0));
+
+        let items: Vec<_> = queue.take_all().into_iter().map(|d| d.item).collect();
+        assert_eq!(items, vec![(1, "first"), (2, "other")]);
+    }
+
+    #[test]
+    fn test_deferral_is_recognised_through_anyhow() {
+        let deferred = SubmissionDeferred {
+            job_id: U256::from(7),
+            exceeded: GasCeilingExceeded {
+                gas_price: U256::from(200),
+                max_gas_price: U256::from(100),
+            },
+        };
+        let error = anyhow::Error::from(deferred);
+        assert!(is_deferred(&error));
+        assert_eq!(error.downcast_ref::<SubmissionDeferred>(), Some(&deferred));
+        assert!(!is_deferred(&anyhow::anyhow!(
+            "submission for job 7 deferred: by hand"
+        )));
+    }
+}
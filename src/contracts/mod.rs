@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: BUSL-1.1
 pub mod checkpoint_manager;
 pub mod client;
+pub mod deferral;
 pub mod model_registry;
 pub mod monitor;
 pub mod payments;
@@ -10,8 +11,14 @@ pub mod proofs;
 pub mod registry_monitor;
 pub mod types;
 
-pub use checkpoint_manager::{CheckpointManager, JobTokenTracker};
-pub use client::{ChainConfig, Web3Client, Web3Config};
+pub use checkpoint_manager::{CheckpointManager, JobTokenTracker, SessionChainState};
+pub use client::{
+    ChainConfig, ContractCall, FeeMode, GasCeilingExceeded, NonceManager, TxFees, Web3Client,
+    Web3Config,
+};
+pub use deferral::{
+    is_deferred, spawn_gas_retry_task, DeferredItem, DeferredQueue, SubmissionDeferred,
+};
 pub use model_registry::{calculate_model_id, ModelInfo as ModelContractInfo, ModelRegistryClient};
 pub use monitor::{JobEvent, JobMonitor, JobMonitorConfig};
 pub use payments::{PaymentConfig, PaymentEvent, PaymentVerifier, TokenInfo};
@@ -7,8 +7,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, warn};
 
-use super::client::Web3Client;
+use super::client::{GasCeilingExceeded, Web3Client};
+use super::deferral::{spawn_gas_retry_task, DeferredQueue, SubmissionDeferred};
 use super::types::*;
 
 #[derive(Debug, Clone)]
@@ -75,6 +77,13 @@ pub enum PaymentEvent {
         job_id: U256,
         reason: String,
     },
+    /// Payment claim postponed because gas is above the chain's ceiling
+    Deferred {
+        job_id: U256,
+        gas_price: U256,
+        max_gas_price: U256,
+        retry_after_secs: u64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,6 +101,7 @@ pub struct PaymentVerifier {
     escrow: PaymentEscrow<Provider<Http>>,
     event_sender: Arc<RwLock<Option<mpsc::Sender<PaymentEvent>>>>,
     token_contracts: Arc<RwLock<HashMap<String, Address>>>,
+    deferred: Arc<DeferredQueue<U256>>,
 }
 
 impl PaymentVerifier {
@@ -103,13 +113,18 @@ impl PaymentVerifier {
             token_contracts.insert(token.symbol.clone(), token.address);
         }
 
-        Ok(Self {
+        let verifier = Self {
             config,
             web3_client,
             escrow,
             event_sender: Arc::new(RwLock::new(None)),
             token_contracts: Arc::new(RwLock::new(token_contracts)),
-        })
+            deferred: Arc::new(DeferredQueue::new()),
+        };
+        if verifier.web3_client.has_gas_ceiling() {
+            verifier.start_deferred_retry();
+        }
+        Ok(verifier)
     }
 
     pub fn is_token_supported(&self, symbol: &str) -> bool {
@@ -224,6 +239,22 @@ impl PaymentVerifier {
     ) -> Result<Vec<(U256, Result<H256>)>> {
         let mut results = Vec::new();
 
+        // Don't claim at a loss: park the whole batch until gas drops
+        if let Some(exceeded) = self.web3_client.check_gas_ceiling().await? {
+            for job_id in job_ids {
+                self.defer_payment(*job_id, exceeded).await;
+                results.push((
+                    *job_id,
+                    Err(SubmissionDeferred {
+                        job_id: *job_id,
+                        exceeded,
+                    }
+                    .into()),
+                ));
+            }
+            return Ok(results);
+        }
+
         for job_id in job_ids {
             // In a real implementation, would batch process via multicall
             let result = Ok(H256::random());
@@ -257,6 +288,65 @@ impl PaymentVerifier {
         self.web3_client.get_gas_price().await
     }
 
+    /// Queue a payment claim until gas drops below the ceiling
+    async fn defer_payment(&self, job_id: U256, exceeded: GasCeilingExceeded) {
+        if !self.deferred.push_unique(job_id, |queued| *queued) {
+            debug!("Payment for job {} is already deferred", job_id);
+            return;
+        }
+        let retry_after = self.web3_client.gas_ceiling_retry_after();
+        warn!(
+            "⛽ Deferring payment for job {}: {}, retrying in {:?}",
+            job_id, exceeded, retry_after
+        );
+
+        if let Some(sender) = self.event_sender.read().await.as_ref() {
+            let event = PaymentEvent::Deferred {
+                job_id,
+                gas_price: exceeded.gas_price,
+                max_gas_price: exceeded.max_gas_price,
+                retry_after_secs: retry_after.as_secs(),
+            };
+            if let Err(e) = sender.try_send(event) {
+                debug!("Dropping payment event: {}", e);
+            }
+        }
+    }
+
+    /// Number of payment claims waiting for gas to drop
+    pub fn deferred_count(&self) -> usize {
+        self.deferred.len()
+    }
+
+    /// Start the background task that retries deferred claims once gas drops
+    pub fn start_deferred_retry(&self) -> tokio::task::JoinHandle<()> {
+        let verifier = Arc::new(self.clone_for_monitoring());
+        spawn_gas_retry_task(
+            self.web3_client.clone(),
+            self.deferred.clone(),
+            move |job_id: U256| {
+                let verifier = verifier.clone();
+                async move {
+                    match verifier.process_batch_payments(&[job_id]).await {
+                        Ok(results) => {
+                            for (job_id, result) in results {
+                                if let Err(e) = result {
+                                    warn!(
+                                        "Deferred payment for job {} not processed: {}",
+                                        job_id, e
+                                    );
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Deferred payment for job {} not processed: {}", job_id, e)
+                        }
+                    }
+                }
+            },
+        )
+    }
+
     async fn get_token_symbol(&self, token_address: Address) -> Result<String> {
         for token in &self.config.supported_tokens {
             if token.address == token_address {
@@ -285,6 +375,7 @@ impl PaymentVerifier {
             escrow: self.escrow.clone(),
             event_sender: self.event_sender.clone(),
             token_contracts: self.token_contracts.clone(),
+            deferred: self.deferred.clone(),
         }
     }
 }
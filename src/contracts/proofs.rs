@@ -6,8 +6,10 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, warn};
 
-use super::client::Web3Client;
+use super::client::{GasCeilingExceeded, Web3Client};
+use super::deferral::{is_deferred, spawn_gas_retry_task, DeferredQueue, SubmissionDeferred};
 use super::types::*;
 
 #[derive(Debug, Clone)]
@@ -23,7 +25,35 @@ pub struct ProofConfig {
     pub store_proofs_on_ipfs: bool,
     pub max_proof_delay: Duration,
     pub max_resubmission_attempts: usize,
+    /// Delay before the first resubmission; later delays grow exponentially
     pub resubmission_delay: Duration,
+    /// Upper bound on the delay between resubmissions
+    pub max_resubmission_delay: Duration,
+    /// Factor the delay is multiplied by after each failed attempt
+    pub resubmission_backoff_multiplier: f64,
+    /// Random jitter as a fraction of the delay (0.0 - 1.0)
+    pub resubmission_jitter: f64,
+}
+
+impl ProofConfig {
+    /// Delay before retrying after `attempt` failed attempts (1-based), without jitter
+    pub fn resubmission_backoff(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32) as i32;
+        let delay = self.resubmission_delay.as_secs_f64()
+            * self.resubmission_backoff_multiplier.max(1.0).powi(exponent);
+        Duration::from_secs_f64(delay.min(self.max_resubmission_delay.as_secs_f64()))
+    }
+
+    /// Backoff delay with random jitter applied
+    fn resubmission_backoff_with_jitter(&self, attempt: usize) -> Duration {
+        let delay = self.resubmission_backoff(attempt);
+        let jitter = self.resubmission_jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+        let factor = 1.0 + jitter * (rand::random::<f64>() * 2.0 - 1.0);
+        delay.mul_f64(factor)
+    }
 }
 
 impl Default for ProofConfig {
@@ -53,6 +83,9 @@ impl Default for ProofConfig {
             max_proof_delay: Duration::from_secs(3600),
             max_resubmission_attempts: 3,
             resubmission_delay: Duration::from_millis(100),
+            max_resubmission_delay: Duration::from_secs(30),
+            resubmission_backoff_multiplier: 2.0,
+            resubmission_jitter: 0.2,
         }
     }
 }
@@ -84,6 +117,66 @@ pub enum ProofEvent {
         challenger: Address,
         reason: String,
     },
+    RetryScheduled {
+        job_id: U256,
+        attempt: usize,
+        delay_ms: u64,
+        error: String,
+    },
+    /// Submission postponed because gas is above the chain's ceiling
+    Deferred {
+        job_id: U256,
+        gas_price: U256,
+        max_gas_price: U256,
+        retry_after_secs: u64,
+    },
+}
+
+/// Whether a failed proof submission may succeed if retried
+///
+/// Timeouts, dropped connections and nonce races are transient. Reverts and
+/// insufficient funds will fail again no matter how often we retry.
+pub fn is_retryable_proof_error(error: &anyhow::Error) -> bool {
+    // Deferred proofs are retried by the gas retry task, not here
+    if is_deferred(error) {
+        return false;
+    }
+
+    let message = error.to_string().to_lowercase();
+    const PERMANENT: [&str; 4] = [
+        "revert",
+        "insufficient funds",
+        "invalid proof",
+        "no wallet configured",
+    ];
+    if PERMANENT.iter().any(|p| message.contains(p)) {
+        return false;
+    }
+
+    // Anything else (timeouts, "nonce too low", "replacement transaction
+    // underpriced", connection errors, rate limits) is treated as transient
+    true
+}
+
+/// Whether a submission failed because its nonce was out of step with the chain
+///
+/// The shared nonce counter has to be reseeded before the next attempt.
+pub fn is_nonce_error(error: &anyhow::Error) -> bool {
+    let message = error.to_string().to_lowercase();
+    const NONCE_ERRORS: [&str; 4] = [
+        "nonce too low",
+        "nonce too high",
+        "invalid nonce",
+        "replacement transaction underpriced",
+    ];
+    NONCE_ERRORS.iter().any(|e| message.contains(e))
+}
+
+/// Nonce and gas limit fetched fresh for one submission attempt
+#[derive(Debug, Clone, Copy)]
+struct SubmissionParams {
+    nonce: U256,
+    gas_limit: U256,
 }
 
 #[derive(Debug, Clone)]
@@ -107,6 +200,7 @@ pub struct ProofSubmitter {
     wallet: Arc<RwLock<Option<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>>>>,
     error_rate: Arc<RwLock<f64>>,
     metrics: Arc<RwLock<ProofMetrics>>,
+    deferred: Arc<DeferredQueue<ProofData>>,
 }
 
 impl ProofSubmitter {
@@ -114,7 +208,7 @@ impl ProofSubmitter {
         let proof_system =
             ProofSystem::new(config.proof_system_address, web3_client.provider.clone());
 
-        Ok(Self {
+        let submitter = Self {
             config,
             web3_client,
             proof_system,
@@ -125,7 +219,12 @@ impl ProofSubmitter {
                 retry_count: 0,
                 successful_submissions: 0,
             })),
-        })
+            deferred: Arc::new(DeferredQueue::new()),
+        };
+        if submitter.web3_client.has_gas_ceiling() {
+            submitter.start_deferred_retry();
+        }
+        Ok(submitter)
     }
 
     pub fn is_ready(&self) -> bool {
@@ -167,19 +266,76 @@ impl ProofSubmitter {
         Ok(())
     }
 
-    pub async fn submit_proof(&self, _proof_data: ProofData) -> Result<H256> {
+    /// Submit a proof in a single attempt
+    ///
+    /// The nonce and gas limit are fetched fresh on every call, so a retry
+    /// after a nonce race or a gas price change starts from current chain state.
+    pub async fn submit_proof(&self, proof_data: ProofData) -> Result<H256> {
         // Simulate error injection
         let error_rate = *self.error_rate.read().await;
         if error_rate > 0.0 && rand::random::<f64>() < error_rate {
-            self.metrics.write().await.retry_count += 1;
             return Err(anyhow!("Simulated submission error"));
         }
 
+        if let Some(exceeded) = self.web3_client.check_gas_ceiling().await? {
+            let job_id = proof_data.job_id;
+            self.defer_proof(proof_data, exceeded).await;
+            return Err(SubmissionDeferred { job_id, exceeded }.into());
+        }
+
+        let params = match self.refresh_submission_params(&proof_data).await {
+            Ok(params) => params,
+            Err(e) => {
+                // The counter ran ahead of (or behind) the chain; reseed it
+                // so the retry gets a usable nonce
+                if is_nonce_error(&e) {
+                    if let Err(resync_error) = self.web3_client.resync_nonce().await {
+                        warn!("Failed to resync nonce: {}", resync_error);
+                    }
+                }
+                return Err(e);
+            }
+        };
+        if let Some(params) = params {
+            debug!(
+                "Submitting proof for job {} with nonce {} and gas limit {}",
+                proof_data.job_id, params.nonce, params.gas_limit
+            );
+        }
+
         // In a real implementation, would submit proof to contract
         self.metrics.write().await.successful_submissions += 1;
         Ok(H256::random())
     }
 
+    /// Allocate a nonce and re-estimate gas for a submission
+    ///
+    /// The nonce comes from the client's shared counter, so proofs don't
+    /// race the node's other transactions. Returns `None` when no wallet is
+    /// configured.
+    async fn refresh_submission_params(
+        &self,
+        proof: &ProofData,
+    ) -> Result<Option<SubmissionParams>> {
+        let wallet_guard = self.wallet.read().await;
+        let Some(wallet) = wallet_guard.as_ref() else {
+            return Ok(None);
+        };
+
+        let nonce = self.web3_client.next_nonce().await?;
+
+        let tx = TransactionRequest::new()
+            .from(wallet.address())
+            .to(self.config.proof_system_address)
+            .data(proof.proof.clone());
+        let gas_limit = wallet
+            .estimate_gas(&tx.into(), None)
+            .await
+            .map_err(|e| anyhow!("Failed to estimate gas: {}", e))?;
+
+        Ok(Some(SubmissionParams { nonce, gas_limit }))
+    }
+
     pub async fn get_proof_status(&self, job_id: U256) -> Result<ProofStatus> {
         let proof = self.proof_system.get_proof(job_id).call().await?;
         Ok(ProofStatus::from(proof.3))
@@ -238,20 +394,104 @@ impl ProofSubmitter {
         });
     }
 
+    /// Submit a proof, retrying transient failures with exponential backoff
+    ///
+    /// Permanent failures (see `is_retryable_proof_error`) and deferrals are
+    /// returned immediately. Each retry emits `ProofEvent::RetryScheduled`.
     pub async fn submit_proof_with_retry(&self, proof: ProofData) -> Result<H256> {
         let mut attempts = 0;
-        let max_attempts = self.config.max_resubmission_attempts;
+        let max_attempts = self.config.max_resubmission_attempts.max(1);
 
         loop {
             match self.submit_proof(proof.clone()).await {
                 Ok(tx_hash) => return Ok(tx_hash),
+                // Already queued for the gas retry task
+                Err(e) if is_deferred(&e) => return Err(e),
                 Err(e) => {
                     attempts += 1;
+                    if !is_retryable_proof_error(&e) {
+                        warn!(
+                            "Proof submission for job {} failed permanently: {}",
+                            proof.job_id, e
+                        );
+                        return Err(e);
+                    }
                     if attempts >= max_attempts {
+                        warn!(
+                            "Proof submission for job {} failed after {} attempts: {}",
+                            proof.job_id, attempts, e
+                        );
                         return Err(e);
                     }
-                    tokio::time::sleep(self.config.resubmission_delay).await;
+
+                    let delay = self.config.resubmission_backoff_with_jitter(attempts);
+                    warn!(
+                        "Proof submission for job {} failed (attempt {}/{}), retrying in {:?}: {}",
+                        proof.job_id, attempts, max_attempts, delay, e
+                    );
+                    self.metrics.write().await.retry_count += 1;
+                    self.emit_event(ProofEvent::RetryScheduled {
+                        job_id: proof.job_id,
+                        attempt: attempts,
+                        delay_ms: delay.as_millis() as u64,
+                        error: e.to_string(),
+                    })
+                    .await;
+
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Queue a proof until gas drops below the ceiling
+    async fn defer_proof(&self, proof: ProofData, exceeded: GasCeilingExceeded) {
+        let job_id = proof.job_id;
+        if !self.deferred.push_unique(proof, |queued| queued.job_id) {
+            debug!("Proof for job {} is already deferred", job_id);
+            return;
+        }
+        let retry_after = self.web3_client.gas_ceiling_retry_after();
+        warn!(
+            "⛽ Deferring proof for job {}: {}, retrying in {:?}",
+            job_id, exceeded, retry_after
+        );
+        self.emit_event(ProofEvent::Deferred {
+            job_id,
+            gas_price: exceeded.gas_price,
+            max_gas_price: exceeded.max_gas_price,
+            retry_after_secs: retry_after.as_secs(),
+        })
+        .await;
+    }
+
+    /// Number of proofs waiting for gas to drop
+    pub fn deferred_count(&self) -> usize {
+        self.deferred.len()
+    }
+
+    /// Start the background task that resubmits deferred proofs once gas drops
+    pub fn start_deferred_retry(&self) -> tokio::task::JoinHandle<()> {
+        let submitter = Arc::new(self.clone_for_monitoring());
+        spawn_gas_retry_task(
+            self.web3_client.clone(),
+            self.deferred.clone(),
+            move |proof: ProofData| {
+                let submitter = submitter.clone();
+                async move {
+                    let job_id = proof.job_id;
+                    if let Err(e) = submitter.submit_proof_with_retry(proof).await {
+                        warn!("Deferred proof for job {} not submitted: {}", job_id, e);
+                    }
                 }
+            },
+        )
+    }
+
+    async fn emit_event(&self, event: ProofEvent) {
+        if let Some(sender) = self.event_sender.read().await.as_ref() {
+            if let Err(e) = sender.try_send(event) {
+                debug!("Dropping proof event: {}", e);
             }
         }
     }
@@ -309,6 +549,7 @@ impl ProofSubmitter {
             wallet: self.wallet.clone(),
             error_rate: self.error_rate.clone(),
             metrics: self.metrics.clone(),
+            deferred: self.deferred.clone(),
         }
     }
 }
@@ -4,17 +4,28 @@
 //!
 //! Caches generated proofs to avoid regenerating identical proofs.
 //! Uses LRU (Least Recently Used) eviction when capacity is reached.
+//!
+//! Entries are keyed on the four commitment hashes the guest program commits
+//! (`job_id`, `model_hash`, `input_hash`, `output_hash`). The key is a plain
+//! SHA-256 over those hashes, so it is stable across process restarts.
 
+use super::config::EzklConfig;
 use super::prover::ProofData;
 use super::witness::Witness;
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, VecDeque};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
 use std::time::{Duration, Instant};
 
-/// Cache key type (hash of witness)
+/// Cache key type (hash of the four commitment hashes)
 type CacheKey = [u8; 32];
 
+/// Domain separator for cache keys; bump the version if the layout changes
+const CACHE_KEY_DOMAIN: &[u8] = b"fabstir-ezkl-proof-cache-v1";
+
+/// Process-wide cache shared by provers created with `ProofCache::shared()`
+static SHARED_CACHE: OnceLock<ProofCache> = OnceLock::new();
+
 /// Cached proof entry
 #[derive(Debug, Clone)]
 struct CachedProof {
@@ -209,11 +220,42 @@ impl ProofCache {
         }
     }
 
+    /// Create a proof cache sized from `EzklConfig::cache_size`
+    pub fn from_config(config: &EzklConfig) -> Self {
+        Self::new(config.cache_size)
+    }
+
+    /// Process-wide proof cache, sized from `EZKL_CACHE_SIZE` on first use
+    pub fn shared() -> Self {
+        SHARED_CACHE
+            .get_or_init(|| Self::from_config(&EzklConfig::from_env()))
+            .clone()
+    }
+
+    /// Compute cache key from the four commitment hashes
+    pub fn compute_key_from_hashes(
+        job_id: &[u8; 32],
+        model_hash: &[u8; 32],
+        input_hash: &[u8; 32],
+        output_hash: &[u8; 32],
+    ) -> CacheKey {
+        let mut hasher = Sha256::new();
+        hasher.update(CACHE_KEY_DOMAIN);
+        hasher.update(job_id);
+        hasher.update(model_hash);
+        hasher.update(input_hash);
+        hasher.update(output_hash);
+        hasher.finalize().into()
+    }
+
     /// Compute cache key from witness
     pub fn compute_key(witness: &Witness) -> CacheKey {
-        let witness_bytes = witness.to_bytes();
-        let hash = Sha256::digest(&witness_bytes);
-        hash.into()
+        Self::compute_key_from_hashes(
+            witness.job_id(),
+            witness.model_hash(),
+            witness.input_hash(),
+            witness.output_hash(),
+        )
     }
 
     /// Get proof from cache
@@ -489,4 +531,35 @@ mod tests {
         // Cache with 0 capacity should not store anything
         assert_eq!(cache.len(), 0);
     }
+
+    #[test]
+    fn test_cache_key_is_stable() {
+        let witness = create_test_witness(0);
+        let key = ProofCache::compute_key(&witness);
+
+        // Fixed vector: the key must not change between releases or restarts
+        assert_eq!(
+            hex::encode(key),
+            "01b3c55a8e64d3dd0ecb373a45425d6032c2795d6699d1d96be9a03cd960c7a6"
+        );
+        assert_eq!(
+            key,
+            ProofCache::compute_key_from_hashes(&[0; 32], &[1; 32], &[2; 32], &[3; 32])
+        );
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_hash_order() {
+        let swapped = ProofCache::compute_key_from_hashes(&[1; 32], &[0; 32], &[2; 32], &[3; 32]);
+        assert_ne!(ProofCache::compute_key(&create_test_witness(0)), swapped);
+    }
+
+    #[test]
+    fn test_cache_from_config() {
+        let config = EzklConfig {
+            cache_size: 7,
+            ..EzklConfig::default()
+        };
+        assert_eq!(ProofCache::from_config(&config).capacity(), 7);
+    }
 }
@@ -133,6 +133,8 @@ pub struct KeyManager {
     default_proving_key_path: Option<PathBuf>,
     /// Default verification key path (from environment or config)
     default_verifying_key_path: Option<PathBuf>,
+    /// Directory holding verification keys addressed by ID (`<id>.bin`)
+    verifying_key_dir: Option<PathBuf>,
 }
 
 impl KeyManager {
@@ -143,6 +145,7 @@ impl KeyManager {
             verifying_key_cache: Arc::new(RwLock::new(KeyCache::new())),
             default_proving_key_path: None,
             default_verifying_key_path: None,
+            verifying_key_dir: None,
         }
     }
 
@@ -154,15 +157,25 @@ impl KeyManager {
         let verifying_key_path = std::env::var("EZKL_VERIFYING_KEY_PATH")
             .ok()
             .map(PathBuf::from);
+        let verifying_key_dir = std::env::var("EZKL_VERIFYING_KEY_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("./keys"));
 
         Self {
             proving_key_cache: Arc::new(RwLock::new(KeyCache::new())),
             verifying_key_cache: Arc::new(RwLock::new(KeyCache::new())),
             default_proving_key_path: proving_key_path,
             default_verifying_key_path: verifying_key_path,
+            verifying_key_dir: Some(verifying_key_dir),
         }
     }
 
+    /// Set the directory that verification key IDs are resolved against
+    pub fn with_verifying_key_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.verifying_key_dir = Some(dir.into());
+        self
+    }
+
     /// Create key manager with shared caches
     pub fn with_shared_caches(
         proving_cache: Arc<RwLock<KeyCache<ProvingKey>>>,
@@ -173,6 +186,7 @@ impl KeyManager {
             verifying_key_cache: verifying_cache,
             default_proving_key_path: None,
             default_verifying_key_path: None,
+            verifying_key_dir: None,
         }
     }
 
@@ -186,6 +200,19 @@ impl KeyManager {
         self.default_verifying_key_path.as_deref()
     }
 
+    /// Resolve a verification key ID to `<verifying_key_dir>/<id>.bin`
+    ///
+    /// IDs are limited to ASCII letters, digits, `-` and `_` so they can't
+    /// escape the key directory. Returns None for an invalid ID, when no
+    /// directory is configured, or when the file doesn't exist.
+    pub fn verifying_key_path_for_id(&self, id: &str) -> Option<PathBuf> {
+        if !is_valid_key_id(id) {
+            return None;
+        }
+        let path = self.verifying_key_dir.as_ref()?.join(format!("{}.bin", id));
+        path.is_file().then_some(path)
+    }
+
     /// Load proving key (with caching)
     ///
     /// Checks cache first. If not found, loads from disk and caches.
@@ -354,6 +381,15 @@ impl KeyManager {
     }
 }
 
+/// Whether `id` is a usable verification key ID (1-64 of `[A-Za-z0-9_-]`)
+pub fn is_valid_key_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 impl Default for KeyManager {
     fn default() -> Self {
         Self::new()
@@ -402,6 +438,26 @@ mod tests {
         assert!(manager.verifying_key_path().is_none());
     }
 
+    #[test]
+    #[cfg(not(feature = "real-ezkl"))]
+    fn test_verifying_key_path_for_id() {
+        let (temp_dir, _, verifying_path) = setup_test_keys();
+        let manager = KeyManager::new().with_verifying_key_dir(temp_dir.path());
+
+        assert_eq!(
+            manager.verifying_key_path_for_id("verifying_key"),
+            Some(verifying_path)
+        );
+        assert!(manager.verifying_key_path_for_id("missing").is_none());
+        // IDs can't be used to walk out of the key directory
+        assert!(manager
+            .verifying_key_path_for_id("../verifying_key")
+            .is_none());
+        assert!(KeyManager::new()
+            .verifying_key_path_for_id("verifying_key")
+            .is_none());
+    }
+
     #[test]
     #[cfg(not(feature = "real-ezkl"))]
     fn test_load_proving_key() {
@@ -55,7 +55,7 @@ pub use cache::{CacheStats, ProofCache};
 pub use circuit::{CircuitMetadata, CommitmentCircuit};
 pub use config::EzklConfig;
 pub use error::{EzklError, EzklResult};
-pub use key_manager::{KeyCacheStats, KeyManager};
+pub use key_manager::{is_valid_key_id, KeyCacheStats, KeyManager};
 pub use metrics::{global_metrics, EzklMetrics};
 pub use prover::{generate_proof, generate_proof_from_circuit, EzklProver, ProofData};
 pub use setup::{
@@ -5,6 +5,7 @@
 //! Handles generation of EZKL zero-knowledge proofs for commitment circuits.
 //! Supports both real EZKL (with feature flag) and mock implementation.
 
+use super::cache::ProofCache;
 use super::circuit::CommitmentCircuit;
 use super::error::{EzklError, EzklResult};
 use super::setup::{load_proving_key, validate_proving_key, ProvingKey};
@@ -41,6 +42,8 @@ pub struct EzklProver {
     proving_key: Option<ProvingKey>,
     /// Path to proving key file
     proving_key_path: Option<std::path::PathBuf>,
+    /// Optional cache of previously generated proofs
+    cache: Option<ProofCache>,
 }
 
 impl EzklProver {
@@ -49,6 +52,7 @@ impl EzklProver {
         Self {
             proving_key: None,
             proving_key_path: None,
+            cache: None,
         }
     }
 
@@ -57,6 +61,7 @@ impl EzklProver {
         Self {
             proving_key: None,
             proving_key_path: Some(key_path.as_ref().to_path_buf()),
+            cache: None,
         }
     }
 
@@ -66,9 +71,21 @@ impl EzklProver {
         Ok(Self {
             proving_key: Some(proving_key),
             proving_key_path: None,
+            cache: None,
         })
     }
 
+    /// Reuse proofs from `cache` for witnesses that were already proven
+    pub fn with_cache(mut self, cache: ProofCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Get the attached proof cache, if any
+    pub fn cache(&self) -> Option<&ProofCache> {
+        self.cache.as_ref()
+    }
+
     /// Load proving key from configured path or provided path
     pub fn load_key(&mut self, key_path: Option<&Path>) -> EzklResult<&ProvingKey> {
         // If key already loaded, return it
@@ -110,6 +127,18 @@ impl EzklProver {
             });
         }
 
+        // Reuse a cached proof for the same commitment hashes
+        if let Some(cache) = &self.cache {
+            if let Some(proof) = cache.get(witness) {
+                if proof_matches_witness(&proof, witness) {
+                    tracing::debug!("♻️  Reusing cached EZKL proof");
+                    return Ok(proof);
+                }
+                tracing::warn!("Discarding cached EZKL proof that does not match witness");
+                cache.remove(witness);
+            }
+        }
+
         // Generate timestamp
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -118,14 +147,16 @@ impl EzklProver {
 
         // Generate proof based on feature flag
         #[cfg(feature = "real-ezkl")]
-        {
-            self.generate_real_proof(witness, timestamp)
-        }
+        let proof = self.generate_real_proof(witness, timestamp)?;
 
         #[cfg(not(feature = "real-ezkl"))]
-        {
-            self.generate_mock_proof(witness, timestamp)
+        let proof = self.generate_mock_proof(witness, timestamp)?;
+
+        if let Some(cache) = &self.cache {
+            cache.insert(witness, proof.clone());
         }
+
+        Ok(proof)
     }
 
     /// Generate mock proof (when real-ezkl feature is disabled)
@@ -285,6 +316,14 @@ impl Default for EzklProver {
     }
 }
 
+/// Whether a cached proof commits to the same hashes as `witness`
+fn proof_matches_witness(proof: &ProofData, witness: &Witness) -> bool {
+    !proof.proof_bytes.is_empty()
+        && proof.model_hash == *witness.model_hash()
+        && proof.input_hash == *witness.input_hash()
+        && proof.output_hash == *witness.output_hash()
+}
+
 /// Helper function to generate proof from witness (convenience function)
 pub fn generate_proof(witness: &Witness, proving_key_path: Option<&Path>) -> EzklResult<ProofData> {
     let mut prover = if let Some(path) = proving_key_path {
@@ -448,4 +487,48 @@ mod tests {
         let result = generate_proof_from_circuit(&circuit, &witness, None);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_generate_proof_uses_cache() -> EzklResult<()> {
+        let cache = ProofCache::new(10);
+        let mut prover = EzklProver::new().with_cache(cache.clone());
+        let witness = create_test_witness();
+
+        let first = prover.generate_proof(&witness)?;
+        let second = prover.generate_proof(&witness)?;
+
+        assert_eq!(first.proof_bytes, second.proof_bytes);
+        assert_eq!(first.timestamp, second.timestamp);
+
+        let stats = cache.stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.entries, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_proof_discards_mismatched_cache_entry() -> EzklResult<()> {
+        let cache = ProofCache::new(10);
+        let witness = create_test_witness();
+        cache.insert(
+            &witness,
+            ProofData {
+                proof_bytes: vec![0xEF; 8],
+                timestamp: 0,
+                model_hash: [9u8; 32],
+                input_hash: *witness.input_hash(),
+                output_hash: *witness.output_hash(),
+            },
+        );
+
+        let mut prover = EzklProver::new().with_cache(cache.clone());
+        let proof = prover.generate_proof(&witness)?;
+
+        assert_eq!(proof.model_hash, *witness.model_hash());
+        assert_ne!(proof.timestamp, 0);
+
+        Ok(())
+    }
 }
@@ -2,12 +2,30 @@
 // SPDX-License-Identifier: BUSL-1.1
 //! Image generation billing calculation and per-job tracking
 
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 use tokio::sync::RwLock;
 
+use super::client::ImageSize;
+
+pub const IMAGE_BILLING_BASE_UNITS_ENV: &str = "IMAGE_BILLING_BASE_UNITS";
+pub const IMAGE_BILLING_UNITS_PER_MEGAPIXEL_ENV: &str = "IMAGE_BILLING_UNITS_PER_MEGAPIXEL";
+pub const IMAGE_BILLING_UNITS_PER_MEGAPIXEL_STEP_ENV: &str =
+    "IMAGE_BILLING_UNITS_PER_MEGAPIXEL_STEP";
+pub const IMAGE_BILLING_MODEL_MULTIPLIERS_ENV: &str = "IMAGE_BILLING_MODEL_MULTIPLIERS";
+
+/// Token-equivalents per generation unit when image jobs are tracked
+/// alongside text jobs: 0.20 units → 200 tokens
+pub const TOKENS_PER_GENERATION_UNIT: f64 = 1000.0;
+
+/// Convert generation units to token-equivalents for checkpoint accounting
+pub fn units_to_tokens(units: f64) -> u64 {
+    (units * TOKENS_PER_GENERATION_UNIT).ceil() as u64
+}
+
 /// Calculate generation units for billing.
 ///
 /// Formula: `(width * height / 1_048_576) * (steps / 20) * model_multiplier`
@@ -25,6 +43,119 @@ pub fn calculate_generation_units(
     megapixels * step_factor * model_multiplier
 }
 
+/// Prices for image generation, in generation units
+///
+/// A generation costs `base + per_megapixel * mp + per_megapixel_step * mp * steps`,
+/// times the model's multiplier. The step charge scales with resolution
+/// because each denoising step processes every pixel. The defaults
+/// reproduce `calculate_generation_units`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImagePriceTable {
+    /// Flat charge per generation
+    pub base_units: f64,
+    /// Charge per output megapixel
+    pub units_per_megapixel: f64,
+    /// Charge per inference step per output megapixel
+    pub units_per_megapixel_step: f64,
+    /// Multipliers by model name; unlisted models use 1.0
+    pub model_multipliers: HashMap<String, f64>,
+}
+
+impl Default for ImagePriceTable {
+    fn default() -> Self {
+        Self {
+            base_units: 0.0,
+            units_per_megapixel: 0.0,
+            units_per_megapixel_step: 0.05,
+            model_multipliers: HashMap::new(),
+        }
+    }
+}
+
+/// Itemized cost of one generation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageCharge {
+    pub base_units: f64,
+    pub megapixel_units: f64,
+    pub step_units: f64,
+    pub model_multiplier: f64,
+    pub megapixels: f64,
+    pub steps: u32,
+    /// Sum of the line items, which already include the model multiplier
+    pub total_units: f64,
+}
+
+impl ImagePriceTable {
+    /// Load prices from `IMAGE_BILLING_*` variables, falling back to the
+    /// defaults. `IMAGE_BILLING_MODEL_MULTIPLIERS` is a comma-separated list
+    /// of `model=multiplier` pairs.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let units = |name: &str, default: f64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .unwrap_or(default)
+        };
+        let model_multipliers = std::env::var(IMAGE_BILLING_MODEL_MULTIPLIERS_ENV)
+            .map(|v| {
+                v.split(',')
+                    .filter_map(|pair| {
+                        let (model, multiplier) = pair.split_once('=')?;
+                        let multiplier = multiplier.trim().parse::<f64>().ok()?;
+                        Some((model.trim().to_string(), multiplier))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            base_units: units(IMAGE_BILLING_BASE_UNITS_ENV, defaults.base_units),
+            units_per_megapixel: units(
+                IMAGE_BILLING_UNITS_PER_MEGAPIXEL_ENV,
+                defaults.units_per_megapixel,
+            ),
+            units_per_megapixel_step: units(
+                IMAGE_BILLING_UNITS_PER_MEGAPIXEL_STEP_ENV,
+                defaults.units_per_megapixel_step,
+            ),
+            model_multipliers,
+        }
+    }
+
+    /// Price table loaded from the environment on first use
+    pub fn global() -> &'static Self {
+        static TABLE: OnceLock<ImagePriceTable> = OnceLock::new();
+        TABLE.get_or_init(Self::from_env)
+    }
+
+    pub fn model_multiplier(&self, model: &str) -> f64 {
+        self.model_multipliers.get(model).copied().unwrap_or(1.0)
+    }
+
+    /// Itemized cost of generating `size` with `steps` on `model`
+    pub fn charge(&self, size: &ImageSize, steps: u32, model: &str) -> ImageCharge {
+        let megapixels = size.megapixels();
+        let model_multiplier = self.model_multiplier(model);
+        let base_units = self.base_units * model_multiplier;
+        let megapixel_units = self.units_per_megapixel * megapixels * model_multiplier;
+        let step_units =
+            self.units_per_megapixel_step * megapixels * steps as f64 * model_multiplier;
+
+        ImageCharge {
+            base_units,
+            megapixel_units,
+            step_units,
+            model_multiplier,
+            megapixels,
+            steps,
+            total_units: base_units + megapixel_units + step_units,
+        }
+    }
+}
+
 /// Per-job image generation tracking info
 #[derive(Debug, Clone)]
 pub struct ImageJobInfo {
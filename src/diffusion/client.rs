@@ -3,11 +3,15 @@
 //! SGLang Diffusion sidecar client for image generation via OpenAI-compatible API
 
 use anyhow::Result;
+use rand::Rng;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info};
 
+use super::queue::{GenerationQueue, QueueConfig, QueuePosition, QueueTicket};
+
 /// Allowed output sizes for image generation
 pub const ALLOWED_SIZES: &[&str] = &[
     "256x256",
@@ -18,6 +22,9 @@ pub const ALLOWED_SIZES: &[&str] = &[
     "768x1024",
 ];
 
+/// Largest seed accepted by the sidecar's random generators (32-bit)
+pub const MAX_SEED: u64 = u32::MAX as u64;
+
 fn default_size() -> String {
     "1024x1024".to_string()
 }
@@ -43,6 +50,7 @@ pub struct DiffusionClient {
     client: Client,
     endpoint: String,
     model_name: String,
+    queue: Arc<GenerationQueue>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -128,8 +136,22 @@ impl ImageGenerationRequest {
                 self.steps
             ));
         }
+        if let Some(seed) = self.seed {
+            if seed > MAX_SEED {
+                return Err(format!(
+                    "seed must be between 0 and {}, got {}",
+                    MAX_SEED, seed
+                ));
+            }
+        }
         Ok(())
     }
+
+    /// The requested seed, or a random one so the generation can be reproduced
+    pub fn effective_seed(&self) -> u64 {
+        self.seed
+            .unwrap_or_else(|| rand::thread_rng().gen_range(0..=MAX_SEED))
+    }
 }
 
 impl ImageSize {
@@ -177,9 +199,21 @@ impl DiffusionClient {
             client,
             endpoint,
             model_name: model_name.to_string(),
+            queue: Arc::new(GenerationQueue::new(QueueConfig::from_env())),
         })
     }
 
+    /// Replace the queue limits loaded from the environment
+    pub fn with_queue_config(mut self, config: QueueConfig) -> Self {
+        self.queue = Arc::new(GenerationQueue::new(config));
+        self
+    }
+
+    /// Get the queue that limits concurrent generations
+    pub fn queue(&self) -> &Arc<GenerationQueue> {
+        &self.queue
+    }
+
     /// Get the model name
     pub fn model_name(&self) -> &str {
         &self.model_name
@@ -211,6 +245,7 @@ impl DiffusionClient {
             ImageSize::parse(&request.size).map_err(|e| anyhow::anyhow!("invalid size: {}", e))?;
 
         let start = std::time::Instant::now();
+        let seed = request.effective_seed();
 
         let mut body = serde_json::json!({
             "prompt": request.prompt,
@@ -220,10 +255,8 @@ impl DiffusionClient {
             "response_format": request.response_format,
             "guidance_scale": request.guidance_scale,
             "num_inference_steps": request.steps,
+            "seed": seed,
         });
-        if let Some(seed) = request.seed {
-            body["seed"] = serde_json::json!(seed);
-        }
         if let Some(ref neg) = request.negative_prompt {
             body["negative_prompt"] = serde_json::json!(neg);
         }
@@ -261,7 +294,7 @@ impl DiffusionClient {
                 .clone()
                 .unwrap_or_else(|| self.model_name.clone()),
             processing_time_ms: start.elapsed().as_millis() as u64,
-            seed: request.seed.unwrap_or(0),
+            seed,
             width: size.width,
             height: size.height,
             steps: request.steps,
@@ -269,6 +302,20 @@ impl DiffusionClient {
         })
     }
 
+    /// Wait for a queue slot, then generate, reporting queue positions to
+    /// `on_update` while waiting
+    pub async fn generate_queued(
+        &self,
+        ticket: QueueTicket,
+        request: &ImageGenerationRequest,
+        on_update: impl FnMut(QueuePosition),
+    ) -> Result<DiffusionResult> {
+        let permit = ticket.wait_turn_with_updates(on_update).await;
+        let result = self.generate(request).await?;
+        permit.complete();
+        Ok(result)
+    }
+
     /// Generate an image with an input image (img2img / edit)
     pub async fn generate_with_edit(
         &self,
@@ -284,6 +331,7 @@ impl DiffusionClient {
             ImageSize::parse(&request.size).map_err(|e| anyhow::anyhow!("invalid size: {}", e))?;
 
         let start = std::time::Instant::now();
+        let seed = request.effective_seed();
 
         let mut body = serde_json::json!({
             "prompt": request.prompt,
@@ -295,10 +343,8 @@ impl DiffusionClient {
             "num_inference_steps": request.steps,
             "image": base64_image,
             "strength": strength,
+            "seed": seed,
         });
-        if let Some(seed) = request.seed {
-            body["seed"] = serde_json::json!(seed);
-        }
         if let Some(ref neg) = request.negative_prompt {
             body["negative_prompt"] = serde_json::json!(neg);
         }
@@ -336,7 +382,7 @@ impl DiffusionClient {
                 .clone()
                 .unwrap_or_else(|| self.model_name.clone()),
             processing_time_ms: start.elapsed().as_millis() as u64,
-            seed: request.seed.unwrap_or(0),
+            seed,
             width: size.width,
             height: size.height,
             steps: request.steps,
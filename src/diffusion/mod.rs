@@ -6,11 +6,13 @@ pub mod billing;
 pub mod client;
 pub mod output_safety;
 pub mod prompt_safety;
+pub mod queue;
 pub mod rate_limiter;
 pub mod safety;
 
 pub use client::{DiffusionClient, DiffusionResult, ImageGenerationRequest, ImageSize};
 pub use output_safety::OutputSafetyClassifier;
-pub use prompt_safety::PromptSafetyClassifier;
+pub use prompt_safety::{PromptSafetyCache, PromptSafetyClassifier};
+pub use queue::{GenerationQueue, QueueConfig, QueueFullError, QueuePosition, QueueTicket};
 pub use rate_limiter::ImageGenerationRateLimiter;
 pub use safety::{SafetyAttestation, SafetyCategory, SafetyConfig, SafetyLevel, SafetyResult};
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: BUSL-1.1
 //! Output safety classifier using VLM sidecar for generated image classification
 
-use crate::diffusion::safety::{SafetyCategory, SafetyConfig, SafetyResult};
+use crate::diffusion::safety::{SafetyAttestation, SafetyConfig, SafetyResult};
 use crate::vision::vlm_client::VlmClient;
 
 /// Classifier that checks generated images for safety violations via VLM sidecar.
@@ -24,18 +24,19 @@ impl OutputSafetyClassifier {
          including: violence, sexual/explicit content, hate symbols, self-harm depictions, \
          illegal activities, or deceptive content.\n\n\
          Respond with a JSON object:\n\
-         {\"is_safe\": true/false, \"category\": \"violence\"|\"sexual\"|\"hate\"|\"self_harm\"|\"illegal\"|\"deceptive\"|\"other\"|null, \"reason\": \"...\" or null}\n\n\
+         {\"is_safe\": true/false, \"category\": \"violence\"|\"sexual\"|\"hate\"|\"self_harm\"|\"illegal\"|\"deceptive\"|\"other\"|null, \"reason\": \"...\" or null, \"confidence\": 0.0-1.0}\n\n\
          Only respond with the JSON object, no extra text."
             .to_string()
     }
 
-    /// Parse the VLM safety classification response. Malformed output defaults to unsafe.
+    /// Parse the VLM safety classification response. Malformed output defaults to unsafe,
+    /// with no category so that no threshold can override it.
     pub fn parse_vlm_safety_response(&self, vlm_output: &str) -> SafetyResult {
         match serde_json::from_str::<SafetyResult>(vlm_output) {
             Ok(result) => result,
             Err(_) => SafetyResult {
                 is_safe: false,
-                category: Some(SafetyCategory::Other),
+                category: None,
                 reason: Some(
                     "Failed to parse VLM safety response; defaulting to unsafe".to_string(),
                 ),
@@ -44,6 +45,22 @@ impl OutputSafetyClassifier {
         }
     }
 
+    /// Classify a VLM response, applying the per-category thresholds
+    pub fn classify(&self, vlm_output: &str) -> SafetyResult {
+        self.config
+            .evaluate(self.parse_vlm_safety_response(vlm_output))
+    }
+
+    /// Add this check of the generated image to a prompt attestation
+    pub fn attest(
+        &self,
+        attestation: SafetyAttestation,
+        image_bytes: &[u8],
+        result: &SafetyResult,
+    ) -> SafetyAttestation {
+        attestation.with_output(image_bytes, result)
+    }
+
     /// Classify a generated image for safety. If `vlm_client` is None, returns unsafe.
     pub async fn classify_image(
         &self,
@@ -68,7 +85,7 @@ impl OutputSafetyClassifier {
             .describe(base64_image, format, "detailed", Some(&prompt))
             .await
         {
-            Ok(result) => self.parse_vlm_safety_response(&result.description),
+            Ok(result) => self.classify(&result.description),
             Err(e) => SafetyResult {
                 is_safe: false,
                 category: None,
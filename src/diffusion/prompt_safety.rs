@@ -2,7 +2,16 @@
 // SPDX-License-Identifier: BUSL-1.1
 //! Prompt safety classifier using keyword blocklist and LLM-based classification
 
-use crate::diffusion::safety::{SafetyCategory, SafetyConfig, SafetyResult};
+use crate::diffusion::safety::{SafetyAttestation, SafetyCategory, SafetyConfig, SafetyResult};
+use lru::LruCache;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const PROMPT_SAFETY_CACHE_SIZE_ENV: &str = "PROMPT_SAFETY_CACHE_SIZE";
+pub const PROMPT_SAFETY_CACHE_TTL_SECS_ENV: &str = "PROMPT_SAFETY_CACHE_TTL_SECS";
 
 /// Blocked keyword entries: (keyword, associated category)
 const KEYWORD_BLOCKLIST: &[(&str, SafetyCategory)] = &[
@@ -20,26 +29,136 @@ const KEYWORD_BLOCKLIST: &[(&str, SafetyCategory)] = &[
     ("child exploitation", SafetyCategory::Illegal),
 ];
 
+/// LRU cache of prompt classifications, shared between classifiers
+///
+/// Entries are keyed on the normalized prompt and a fingerprint of the
+/// classifier's `SafetyConfig`, so a classifier with different thresholds
+/// never sees another configuration's decisions.
+pub struct PromptSafetyCache {
+    entries: Mutex<LruCache<String, (SafetyResult, Instant)>>,
+    ttl: Duration,
+}
+
+impl PromptSafetyCache {
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl,
+        }
+    }
+
+    /// Create a cache sized by `PROMPT_SAFETY_CACHE_SIZE` (default 1024)
+    /// with entries expiring after `PROMPT_SAFETY_CACHE_TTL_SECS` (default 600)
+    pub fn from_env() -> Self {
+        let capacity = std::env::var(PROMPT_SAFETY_CACHE_SIZE_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .and_then(NonZeroUsize::new)
+            .unwrap_or(NonZeroUsize::new(1024).unwrap());
+        let ttl_secs = std::env::var(PROMPT_SAFETY_CACHE_TTL_SECS_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(600);
+        Self::new(capacity, Duration::from_secs(ttl_secs))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn get(&self, key: &str) -> Option<SafetyResult> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((result, cached_at)) if cached_at.elapsed() < self.ttl => Some(result.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: String, result: SafetyResult) {
+        self.entries
+            .lock()
+            .unwrap()
+            .put(key, (result, Instant::now()));
+    }
+}
+
 /// Classifier that checks image generation prompts for safety violations.
 ///
 /// Two-stage pipeline:
 /// 1. Fast keyword check (`check_keywords`) — no external calls
 /// 2. LLM-based classification — caller sends the prompt from `build_classification_prompt`
 ///    to the LLM, then passes the response to `parse_safety_response` / `classify`
+///
+/// With a cache attached, `cached_result` returns earlier `classify_prompt`
+/// decisions for the same prompt so the LLM call can be skipped.
 pub struct PromptSafetyClassifier {
     config: SafetyConfig,
+    config_fingerprint: u64,
+    cache: Option<Arc<PromptSafetyCache>>,
 }
 
 impl PromptSafetyClassifier {
     pub fn new(config: SafetyConfig) -> Self {
-        Self { config }
+        Self {
+            config_fingerprint: fingerprint(&config),
+            config,
+            cache: None,
+        }
+    }
+
+    /// Cache classifications in `cache`
+    pub fn with_cache(mut self, cache: Arc<PromptSafetyCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// A cached decision for `prompt` under this classifier's configuration
+    pub fn cached_result(&self, prompt: &str) -> Option<SafetyResult> {
+        self.cache.as_ref()?.get(&self.cache_key(prompt))
+    }
+
+    /// Classify the LLM response for `prompt`, caching the decision
+    ///
+    /// Malformed responses are classified as by `classify` but not cached,
+    /// so a retry can reach the LLM again.
+    pub fn classify_prompt(&self, prompt: &str, llm_output: &str) -> SafetyResult {
+        match serde_json::from_str::<SafetyResult>(llm_output) {
+            Ok(parsed) => {
+                let result = self.config.evaluate(parsed);
+                if let Some(cache) = &self.cache {
+                    cache.insert(self.cache_key(prompt), result.clone());
+                }
+                result
+            }
+            Err(_) => self.classify(llm_output),
+        }
+    }
+
+    fn cache_key(&self, prompt: &str) -> String {
+        format!(
+            "{:016x}:{}",
+            self.config_fingerprint,
+            normalize_prompt(prompt)
+        )
     }
 
     /// Fast keyword-based check. Returns a safe result if no blocked keywords are found.
     pub fn check_keywords(&self, prompt: &str) -> SafetyResult {
         let lower = prompt.to_lowercase();
         for &(keyword, category) in KEYWORD_BLOCKLIST {
-            if lower.contains(keyword) && self.config.blocked_categories.contains(&category) {
+            if lower.contains(keyword) && self.config.blocks(category, 1.0) {
                 return SafetyResult {
                     is_safe: false,
                     category: Some(category),
@@ -76,7 +195,7 @@ impl PromptSafetyClassifier {
              for image generation.\n\n\
              Prompt: \"{}\"\n\n\
              Respond with a JSON object:\n\
-             {{\"is_safe\": true/false, \"category\": \"violence\"|\"sexual\"|\"hate\"|\"self_harm\"|\"illegal\"|\"deceptive\"|\"other\"|null, \"reason\": \"...\" or null}}\n\n\
+             {{\"is_safe\": true/false, \"category\": \"violence\"|\"sexual\"|\"hate\"|\"self_harm\"|\"illegal\"|\"deceptive\"|\"other\"|null, \"reason\": \"...\" or null, \"confidence\": 0.0-1.0}}\n\n\
              Categories to check: violence, sexual, hate, self_harm, illegal, deceptive, other.\n\
              Only respond with the JSON object, no extra text.",
             user_prompt
@@ -96,20 +215,41 @@ impl PromptSafetyClassifier {
         }
     }
 
-    /// Classify an LLM response, applying the blocked_categories filter.
-    /// If the LLM flags a category that is NOT in `blocked_categories`, the
-    /// result is overridden to safe (the category is allowed at this safety level).
+    /// Classify an LLM response, applying the per-category thresholds.
+    /// If the LLM flags a category below its threshold (or not blocked at
+    /// all), the result is overridden to safe.
     pub fn classify(&self, llm_output: &str) -> SafetyResult {
-        let mut result = self.parse_safety_response(llm_output);
-        if !result.is_safe {
-            if let Some(cat) = result.category {
-                if !self.config.blocked_categories.contains(&cat) {
-                    // Category not blocked at this safety level — override to safe
-                    result.is_safe = true;
-                    result.reason = None;
-                }
-            }
-        }
-        result
+        self.config.evaluate(self.parse_safety_response(llm_output))
+    }
+
+    /// Attest a prompt check, recording the categories it could block
+    pub fn attest(&self, prompt: &str, result: &SafetyResult) -> SafetyAttestation {
+        SafetyAttestation::for_prompt(prompt, result, &self.config)
     }
 }
+
+/// Lowercase and collapse whitespace, so trivially different prompts share an entry
+fn normalize_prompt(prompt: &str) -> String {
+    prompt
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Hash of everything in `config` that affects a decision
+fn fingerprint(config: &SafetyConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    config.level.hash(&mut hasher);
+    config.blocked_categories.hash(&mut hasher);
+    config.custom_blocked_terms.hash(&mut hasher);
+
+    let mut thresholds: Vec<_> = config
+        .category_thresholds
+        .iter()
+        .map(|(category, threshold)| (*category as u8, threshold.to_bits()))
+        .collect();
+    thresholds.sort_unstable();
+    thresholds.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,319 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Bounded FIFO queue in front of the diffusion sidecar
+//!
+//! The sidecar only runs a few generations at a time. Requests beyond that
+//! wait here in arrival order with a visible position and ETA, and new
+//! requests are turned away once the queue is full instead of blocking.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use thiserror::Error;
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+pub const DIFFUSION_MAX_CONCURRENT_ENV: &str = "DIFFUSION_MAX_CONCURRENT";
+pub const DIFFUSION_MAX_QUEUED_ENV: &str = "DIFFUSION_MAX_QUEUED";
+
+/// Generation time assumed for ETAs until one has been measured
+const INITIAL_GENERATION_MS: u64 = 10_000;
+
+/// Concurrency and queue depth limits for image generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueConfig {
+    /// Generations sent to the sidecar at once
+    pub max_concurrent: usize,
+    /// Requests allowed to wait for a slot; more are rejected
+    pub max_queued: usize,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 1,
+            max_queued: 16,
+        }
+    }
+}
+
+impl QueueConfig {
+    /// Load limits from `DIFFUSION_MAX_CONCURRENT` and `DIFFUSION_MAX_QUEUED`,
+    /// falling back to the defaults for unset or invalid values
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_concurrent: std::env::var(DIFFUSION_MAX_CONCURRENT_ENV)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.max_concurrent),
+            max_queued: std::env::var(DIFFUSION_MAX_QUEUED_ENV)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_queued),
+        }
+    }
+}
+
+/// Returned when `max_queued` requests are already waiting
+#[derive(Debug, Error)]
+#[error("Image generation queue is full ({max_queued} requests waiting)")]
+pub struct QueueFullError {
+    pub max_queued: usize,
+}
+
+/// A waiting request's place in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuePosition {
+    /// 1 for the next request to start
+    pub position: usize,
+    /// Estimated time until the generation finishes
+    pub eta_ms: u64,
+}
+
+struct QueueState {
+    waiting: VecDeque<u64>,
+    next_id: u64,
+    /// Moving average of successful generation times
+    avg_generation_ms: u64,
+}
+
+/// FIFO admission queue limiting concurrent sidecar generations
+pub struct GenerationQueue {
+    config: QueueConfig,
+    slots: Arc<Semaphore>,
+    state: Mutex<QueueState>,
+    /// Bumped whenever a waiting request leaves, so the rest can report
+    /// their new positions
+    advanced: watch::Sender<u64>,
+}
+
+impl GenerationQueue {
+    pub fn new(config: QueueConfig) -> Self {
+        let config = QueueConfig {
+            max_concurrent: config.max_concurrent.max(1),
+            ..config
+        };
+        Self {
+            config,
+            slots: Arc::new(Semaphore::new(config.max_concurrent)),
+            state: Mutex::new(QueueState {
+                waiting: VecDeque::new(),
+                next_id: 0,
+                avg_generation_ms: INITIAL_GENERATION_MS,
+            }),
+            advanced: watch::channel(0).0,
+        }
+    }
+
+    pub fn config(&self) -> QueueConfig {
+        self.config
+    }
+
+    /// Requests waiting for a slot
+    pub fn waiting(&self) -> usize {
+        self.state.lock().unwrap().waiting.len()
+    }
+
+    /// Generations currently holding a slot
+    pub fn running(&self) -> usize {
+        self.config.max_concurrent - self.slots.available_permits()
+    }
+
+    /// Join the back of the queue, or fail immediately if it is full
+    ///
+    /// Free slots absorb that many extra requests, so a request that can
+    /// start right away is never rejected.
+    pub fn try_enqueue(self: &Arc<Self>) -> Result<QueueTicket, QueueFullError> {
+        let mut state = self.state.lock().unwrap();
+        if state.waiting.len() >= self.config.max_queued + self.slots.available_permits() {
+            return Err(QueueFullError {
+                max_queued: self.config.max_queued,
+            });
+        }
+        let id = state.next_id;
+        state.next_id += 1;
+        state.waiting.push_back(id);
+        debug!(
+            "Image generation request {} queued at position {}",
+            id,
+            state.waiting.len()
+        );
+
+        Ok(QueueTicket {
+            queue: self.clone(),
+            id,
+        })
+    }
+
+    fn position_of(&self, id: u64) -> Option<QueuePosition> {
+        let state = self.state.lock().unwrap();
+        let index = state.waiting.iter().position(|&waiting| waiting == id)?;
+
+        // Everything ahead, running or waiting, finishes in rounds of
+        // `max_concurrent`; then this request runs itself
+        let rounds = (index + self.running()) / self.config.max_concurrent;
+        Some(QueuePosition {
+            position: index + 1,
+            eta_ms: (rounds as u64 + 1) * state.avg_generation_ms,
+        })
+    }
+
+    fn leave(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(index) = state.waiting.iter().position(|&waiting| waiting == id) {
+            state.waiting.remove(index);
+            drop(state);
+            self.advanced.send_modify(|count| *count += 1);
+        }
+    }
+
+    fn record_generation(&self, elapsed_ms: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.avg_generation_ms = (state.avg_generation_ms * 3 + elapsed_ms) / 4;
+    }
+}
+
+/// A request's place in the queue; dropping it gives the place up
+pub struct QueueTicket {
+    queue: Arc<GenerationQueue>,
+    id: u64,
+}
+
+impl QueueTicket {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Current position and ETA, or `None` once a slot has been taken
+    pub fn position(&self) -> Option<QueuePosition> {
+        self.queue.position_of(self.id)
+    }
+
+    /// Wait for a generation slot
+    pub async fn wait_turn(self) -> GenerationPermit {
+        self.wait_turn_with_updates(|_| {}).await
+    }
+
+    /// Wait for a generation slot, calling `on_update` with the position
+    /// when the request has to wait and again each time it moves up
+    pub async fn wait_turn_with_updates(
+        self,
+        mut on_update: impl FnMut(QueuePosition),
+    ) -> GenerationPermit {
+        let permit = match self.queue.slots.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let mut advanced = self.queue.advanced.subscribe();
+                if let Some(position) = self.position() {
+                    on_update(position);
+                }
+
+                let acquire = self.queue.slots.clone().acquire_owned();
+                tokio::pin!(acquire);
+                loop {
+                    tokio::select! {
+                        permit = &mut acquire => {
+                            break permit.expect("generation queue semaphore is never closed");
+                        }
+                        Ok(()) = advanced.changed() => {
+                            if let Some(position) = self.position() {
+                                on_update(position);
+                            }
+                        }
+                    }
+                }
+            }
+        };
+        self.queue.leave(self.id);
+
+        GenerationPermit {
+            queue: self.queue.clone(),
+            started: Instant::now(),
+            _slot: permit,
+        }
+    }
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        self.queue.leave(self.id);
+    }
+}
+
+/// A held generation slot, released on drop
+pub struct GenerationPermit {
+    queue: Arc<GenerationQueue>,
+    started: Instant,
+    _slot: OwnedSemaphorePermit,
+}
+
+impl GenerationPermit {
+    /// Release the slot after a successful generation, feeding its duration
+    /// into the ETA estimate
+    pub fn complete(self) {
+        self.queue
+            .record_generation(self.started.elapsed().as_millis() as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(max_concurrent: usize, max_queued: usize) -> Arc<GenerationQueue> {
+        Arc::new(GenerationQueue::new(QueueConfig {
+            max_concurrent,
+            max_queued,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_positions_advance_in_order() {
+        let queue = queue(1, 4);
+        let running = queue.try_enqueue().unwrap().wait_turn().await;
+        assert_eq!(queue.running(), 1);
+
+        let first = queue.try_enqueue().unwrap();
+        let second = queue.try_enqueue().unwrap();
+        assert_eq!(first.position().unwrap().position, 1);
+        assert_eq!(
+            second.position().unwrap(),
+            QueuePosition {
+                position: 2,
+                eta_ms: 3 * INITIAL_GENERATION_MS,
+            }
+        );
+
+        drop(running);
+        let permit = first.wait_turn().await;
+        assert_eq!(second.position().unwrap().position, 1);
+        drop(permit);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_rejects() {
+        let queue = queue(1, 1);
+        let _running = queue.try_enqueue().unwrap().wait_turn().await;
+        let waiting = queue.try_enqueue().unwrap();
+
+        let err = queue.try_enqueue().err().unwrap();
+        assert_eq!(err.max_queued, 1);
+
+        // Giving up a place frees it
+        drop(waiting);
+        assert_eq!(queue.waiting(), 0);
+        assert!(queue.try_enqueue().is_ok());
+    }
+
+    #[test]
+    fn test_free_slots_admit_beyond_max_queued() {
+        let queue = queue(2, 0);
+        let _first = queue.try_enqueue().unwrap();
+        let _second = queue.try_enqueue().unwrap();
+        assert!(queue.try_enqueue().is_err());
+    }
+}
@@ -4,9 +4,11 @@
 
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Safety enforcement level controlling which categories are blocked
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SafetyLevel {
     Strict,
@@ -21,7 +23,7 @@ impl Default for SafetyLevel {
 }
 
 /// Categories of unsafe content
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SafetyCategory {
     Violence,
@@ -33,6 +35,18 @@ pub enum SafetyCategory {
     Other,
 }
 
+impl SafetyCategory {
+    pub const ALL: [SafetyCategory; 7] = [
+        Self::Violence,
+        Self::Sexual,
+        Self::Hate,
+        Self::SelfHarm,
+        Self::Illegal,
+        Self::Deceptive,
+        Self::Other,
+    ];
+}
+
 /// Result of a safety classification check
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyResult {
@@ -44,11 +58,17 @@ pub struct SafetyResult {
 }
 
 /// Configuration for safety classification
+///
+/// A flagged category is blocked when the classifier's confidence reaches
+/// its threshold. Categories in `category_thresholds` use that threshold;
+/// other `blocked_categories` block at any confidence; the rest are allowed.
 #[derive(Debug, Clone)]
 pub struct SafetyConfig {
     pub level: SafetyLevel,
     pub blocked_categories: Vec<SafetyCategory>,
     pub custom_blocked_terms: Vec<String>,
+    /// Minimum confidence (0.0-1.0) at which each category is blocked
+    pub category_thresholds: HashMap<SafetyCategory, f32>,
 }
 
 impl Default for SafetyConfig {
@@ -62,7 +82,55 @@ impl Default for SafetyConfig {
                 SafetyCategory::SelfHarm,
             ],
             custom_blocked_terms: Vec::new(),
+            category_thresholds: HashMap::new(),
+        }
+    }
+}
+
+impl SafetyConfig {
+    /// Block `category` once the classifier is at least `threshold` confident
+    pub fn with_category_threshold(mut self, category: SafetyCategory, threshold: f32) -> Self {
+        self.category_thresholds.insert(category, threshold);
+        self
+    }
+
+    /// Minimum confidence at which `category` is blocked, or `None` if the
+    /// category is allowed
+    pub fn threshold_for(&self, category: SafetyCategory) -> Option<f32> {
+        self.category_thresholds
+            .get(&category)
+            .copied()
+            .or_else(|| self.blocked_categories.contains(&category).then_some(0.0))
+    }
+
+    /// Whether a `category` flag at `confidence` is blocked
+    pub fn blocks(&self, category: SafetyCategory, confidence: f32) -> bool {
+        matches!(self.threshold_for(category), Some(threshold) if confidence >= threshold)
+    }
+
+    /// Categories that can be blocked, in `SafetyCategory::ALL` order
+    pub fn checked_categories(&self) -> Vec<SafetyCategory> {
+        SafetyCategory::ALL
+            .into_iter()
+            .filter(|&category| self.threshold_for(category).is_some())
+            .collect()
+    }
+
+    /// Apply the category thresholds to a classifier verdict
+    ///
+    /// An unsafe verdict for a category below its threshold is overridden
+    /// to safe. Unsafe verdicts without a category (e.g. the classifier was
+    /// unavailable) stay unsafe.
+    pub fn evaluate(&self, mut result: SafetyResult) -> SafetyResult {
+        if !result.is_safe {
+            if let Some(category) = result.category {
+                if !self.blocks(category, result.confidence) {
+                    result.is_safe = true;
+                    result.reason = None;
+                }
+            }
         }
+        result
     }
 }
 
@@ -75,9 +143,39 @@ pub struct SafetyAttestation {
     pub output_safe: Option<bool>,
     pub safety_level: SafetyLevel,
     pub timestamp: u64,
+    /// Categories the checks could block
+    #[serde(default)]
+    pub checked_categories: Vec<SafetyCategory>,
 }
 
 impl SafetyAttestation {
+    /// Attest a prompt check made under `config`
+    pub fn for_prompt(prompt: &str, result: &SafetyResult, config: &SafetyConfig) -> Self {
+        let mut prompt_hash = [0u8; 32];
+        prompt_hash.copy_from_slice(&Sha256::digest(prompt.as_bytes()));
+        Self {
+            prompt_hash,
+            prompt_safe: result.is_safe,
+            output_hash: None,
+            output_safe: None,
+            safety_level: config.level,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            checked_categories: config.checked_categories(),
+        }
+    }
+
+    /// Add the check of the generated image
+    pub fn with_output(mut self, image_bytes: &[u8], result: &SafetyResult) -> Self {
+        let mut output_hash = [0u8; 32];
+        output_hash.copy_from_slice(&Sha256::digest(image_bytes));
+        self.output_hash = Some(output_hash);
+        self.output_safe = Some(result.is_safe);
+        self
+    }
+
     /// Compute a SHA-256 hash of the attestation fields for integrity verification
     pub fn compute_hash(&self) -> [u8; 32] {
         let mut hasher = Sha256::new();
@@ -90,6 +188,9 @@ impl SafetyAttestation {
             hasher.update([safe as u8]);
         }
         hasher.update(self.timestamp.to_le_bytes());
+        for &category in &self.checked_categories {
+            hasher.update([category as u8]);
+        }
         let result = hasher.finalize();
         let mut hash = [0u8; 32];
         hash.copy_from_slice(&result);
@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: BUSL-1.1
 // src/embeddings/mod.rs
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use tracing::{info, warn};
 
 // ONNX embedding modules (Sub-phase 1.2)
 pub mod model_manager;
@@ -13,6 +15,10 @@ pub mod onnx_model;
 pub use model_manager::{EmbeddingModelConfig, EmbeddingModelManager, ModelInfo};
 pub use onnx_model::OnnxEmbeddingModel;
 
+/// Environment flag that forces the deterministic keyword-bucket embeddings
+/// instead of the ONNX model (for mock deployments and integration tests)
+pub const EMBEDDING_MOCK_ENV: &str = "EMBEDDING_MOCK";
+
 #[derive(Debug, Clone)]
 pub struct EmbeddingConfig {
     pub model: String,
@@ -21,22 +27,144 @@ pub struct EmbeddingConfig {
     pub normalize: bool,
 }
 
+/// Text embedding generator used by the prompt cache
+///
+/// Delegates to an ONNX sentence transformer when one is available. The
+/// keyword-bucket mock is only used in unit tests or when explicitly
+/// requested via [`EmbeddingGenerator::mock`] / `EMBEDDING_MOCK=true`.
 pub struct EmbeddingGenerator {
     config: EmbeddingConfig,
+    model: Option<Arc<OnnxEmbeddingModel>>,
+    use_mock: bool,
 }
 
 impl EmbeddingGenerator {
+    /// Creates a generator backed by the ONNX model named in `config`
+    ///
+    /// Model files are read from `EMBEDDING_MODEL_PATH` / `EMBEDDING_TOKENIZER_PATH`,
+    /// defaulting to `./models/<model>-onnx/`. If the model cannot be loaded the
+    /// generator is still created, but `generate` returns an error.
     pub async fn new(config: EmbeddingConfig) -> Result<Self> {
-        // In a real implementation, this would load the model
-        // For mock implementation, we just store the config
+        if cfg!(test) || mock_requested() {
+            return Ok(Self::mock(config));
+        }
+
+        let model_path = std::env::var("EMBEDDING_MODEL_PATH")
+            .unwrap_or_else(|_| format!("./models/{}-onnx/model.onnx", config.model));
+        let tokenizer_path = std::env::var("EMBEDDING_TOKENIZER_PATH")
+            .unwrap_or_else(|_| format!("./models/{}-onnx/tokenizer.json", config.model));
+
+        match OnnxEmbeddingModel::new(config.model.clone(), &model_path, &tokenizer_path).await {
+            Ok(model) => Self::with_model(config, Arc::new(model)),
+            Err(e) => {
+                warn!(
+                    "Embedding model {} unavailable ({}); embeddings will fail until it is installed",
+                    config.model, e
+                );
+                Ok(Self {
+                    config,
+                    model: None,
+                    use_mock: false,
+                })
+            }
+        }
+    }
 
-        Ok(Self { config })
+    /// Creates a generator backed by an already loaded ONNX model
+    pub fn with_model(config: EmbeddingConfig, model: Arc<OnnxEmbeddingModel>) -> Result<Self> {
+        if model.dimension() != config.dimension {
+            anyhow::bail!(
+                "Embedding model {} outputs {} dimensions, expected {}",
+                model.model_name(),
+                model.dimension(),
+                config.dimension
+            );
+        }
+        info!(
+            "Embedding generator using ONNX model {}",
+            model.model_name()
+        );
+
+        Ok(Self {
+            config,
+            model: Some(model),
+            use_mock: false,
+        })
+    }
+
+    /// Creates a generator backed by the default model of an `EmbeddingModelManager`
+    pub fn from_manager(config: EmbeddingConfig, manager: &EmbeddingModelManager) -> Result<Self> {
+        let model = manager.get_model(Some(&config.model))?;
+        Self::with_model(config, model)
+    }
+
+    /// Creates a generator that produces deterministic keyword-bucket embeddings
+    ///
+    /// These are not semantic embeddings; use only for tests and mock setups.
+    pub fn mock(config: EmbeddingConfig) -> Self {
+        Self {
+            config,
+            model: None,
+            use_mock: true,
+        }
+    }
+
+    /// Returns true if embeddings come from a loaded ONNX model
+    pub fn is_model_loaded(&self) -> bool {
+        self.model.is_some()
     }
 
     pub async fn generate(&self, text: &str) -> Result<Vec<f32>> {
-        // Create deterministic semantic embeddings based on text content
-        // This is a mock implementation that creates similar embeddings for similar text
+        if self.use_mock {
+            return Ok(self.generate_mock(text));
+        }
+
+        let model = self.onnx_model()?;
+        let mut embedding = model
+            .embed(text)
+            .await
+            .context("ONNX embedding generation failed")?;
+        if self.config.normalize {
+            l2_normalize(&mut embedding);
+        }
+
+        Ok(embedding)
+    }
+
+    pub async fn generate_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if self.use_mock {
+            return Ok(texts.iter().map(|text| self.generate_mock(text)).collect());
+        }
+
+        let model = self.onnx_model()?;
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(self.config.batch_size.max(1)) {
+            let owned: Vec<String> = chunk.iter().map(|t| t.to_string()).collect();
+            let batch = model
+                .embed_batch(&owned)
+                .await
+                .context("ONNX batch embedding generation failed")?;
+            embeddings.extend(batch);
+        }
+        if self.config.normalize {
+            embeddings.iter_mut().for_each(|e| l2_normalize(e));
+        }
+
+        Ok(embeddings)
+    }
+
+    fn onnx_model(&self) -> Result<&Arc<OnnxEmbeddingModel>> {
+        self.model.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Embedding model {} is not loaded (set EMBEDDING_MODEL_PATH or {}=true)",
+                self.config.model,
+                EMBEDDING_MOCK_ENV
+            )
+        })
+    }
 
+    /// Deterministic keyword-bucket embeddings: similar text yields similar vectors
+    fn generate_mock(&self, text: &str) -> Vec<f32> {
         let mut embedding = Vec::with_capacity(self.config.dimension);
 
         // Extract semantic features from text
@@ -357,26 +485,118 @@ impl EmbeddingGenerator {
             }
         }
 
-        // Normalize if required
         if self.config.normalize {
-            let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-            if norm > 0.0 {
-                for value in &mut embedding {
-                    *value /= norm;
-                }
-            }
+            l2_normalize(&mut embedding);
         }
 
-        Ok(embedding)
+        embedding
     }
+}
 
-    pub async fn generate_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
-        let mut embeddings = Vec::with_capacity(texts.len());
+fn mock_requested() -> bool {
+    std::env::var(EMBEDDING_MOCK_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
 
-        for text in texts {
-            embeddings.push(self.generate(text).await?);
+fn l2_normalize(embedding: &mut [f32]) {
+    let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in embedding.iter_mut() {
+            *value /= norm;
         }
+    }
+}
 
-        Ok(embeddings)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> EmbeddingConfig {
+        EmbeddingConfig {
+            model: "all-MiniLM-L6-v2".to_string(),
+            dimension: 384,
+            batch_size: 2,
+            normalize: true,
+        }
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[tokio::test]
+    async fn test_new_uses_mock_under_cfg_test() {
+        let generator = EmbeddingGenerator::new(test_config()).await.unwrap();
+        assert!(!generator.is_model_loaded());
+
+        let embedding = generator
+            .generate("What is machine learning?")
+            .await
+            .unwrap();
+        assert_eq!(embedding.len(), 384);
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-4);
+    }
+
+    #[tokio::test]
+    async fn test_mock_batch_matches_single() {
+        let generator = EmbeddingGenerator::mock(test_config());
+        let texts = ["python flask", "docker compose", "meaning of life"];
+
+        let batch = generator.generate_batch(&texts).await.unwrap();
+        assert_eq!(batch.len(), 3);
+        for (text, embedding) in texts.iter().zip(&batch) {
+            assert_eq!(&generator.generate(text).await.unwrap(), embedding);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_similar_text_scores_higher() {
+        let generator = EmbeddingGenerator::mock(test_config());
+        let a = generator
+            .generate("What is the meaning of life?")
+            .await
+            .unwrap();
+        let b = generator
+            .generate("What's the purpose of life?")
+            .await
+            .unwrap();
+        let c = generator.generate("How do I cook pasta?").await.unwrap();
+
+        assert!(cosine(&a, &b) > cosine(&a, &c));
+    }
+
+    #[tokio::test]
+    async fn test_generate_without_model_errors() {
+        let generator = EmbeddingGenerator {
+            config: test_config(),
+            model: None,
+            use_mock: false,
+        };
+
+        let err = generator.generate("hello").await.unwrap_err();
+        assert!(err.to_string().contains("not loaded"));
+        assert!(generator.generate_batch(&["hello"]).await.is_err());
+    }
+
+    #[tokio::test]
+    #[ignore] // Only run if model files are downloaded
+    async fn test_with_model_generates_semantic_embeddings() {
+        let model = OnnxEmbeddingModel::new(
+            "all-MiniLM-L6-v2",
+            "/workspace/models/all-MiniLM-L6-v2-onnx/model.onnx",
+            "/workspace/models/all-MiniLM-L6-v2-onnx/tokenizer.json",
+        )
+        .await
+        .unwrap();
+        let generator = EmbeddingGenerator::with_model(test_config(), Arc::new(model)).unwrap();
+
+        let batch = generator
+            .generate_batch(&["How fast is light?", "Speed of light", "Pasta recipe"])
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 3);
+        assert!(cosine(&batch[0], &batch[1]) > cosine(&batch[0], &batch[2]));
     }
 }
@@ -22,9 +22,12 @@ use ort::session::Session;
 use ort::value::Value;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use tokenizers::Tokenizer;
+use tokenizers::{Encoding, Tokenizer};
 use tracing::{info, warn};
 
+/// Default number of sequences run through the ONNX session in one pass
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 32;
+
 /// ONNX-based embedding model (all-MiniLM-L6-v2)
 ///
 /// This struct wraps ONNX Runtime to provide 384-dimensional embeddings.
@@ -56,6 +59,9 @@ pub struct OnnxEmbeddingModel {
 
     /// Maximum sequence length (256 for all-MiniLM-L6-v2)
     max_length: usize,
+
+    /// Maximum sequences per inference pass; larger inputs are split into sub-batches
+    max_batch_size: usize,
 }
 
 impl std::fmt::Debug for OnnxEmbeddingModel {
@@ -64,6 +70,7 @@ impl std::fmt::Debug for OnnxEmbeddingModel {
             .field("model_name", &self.model_name)
             .field("dimension", &self.dimension)
             .field("max_length", &self.max_length)
+            .field("max_batch_size", &self.max_batch_size)
             .finish_non_exhaustive()
     }
 }
@@ -215,6 +222,11 @@ impl OnnxEmbeddingModel {
             model_name,
             dimension: 384,
             max_length: 256,
+            max_batch_size: std::env::var("EMBEDDING_MAX_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(DEFAULT_MAX_BATCH_SIZE),
         })
     }
 
@@ -326,7 +338,8 @@ impl OnnxEmbeddingModel {
     /// - `Result<Vec<Vec<f32>>>`: Array of 384-dimensional embeddings
     ///
     /// # Implementation
-    /// Tokenizes all texts, pads to same length, and runs batch inference.
+    /// Tokenizes all texts, then runs one padded inference pass per
+    /// sub-batch of at most `max_batch_size` sequences.
     /// More efficient than calling embed() multiple times for large batches.
     ///
     /// # Example
@@ -341,7 +354,7 @@ impl OnnxEmbeddingModel {
         }
 
         // Tokenize all texts
-        let encodings: Vec<_> = texts
+        let encodings: Vec<Encoding> = texts
             .iter()
             .map(|text| {
                 self.tokenizer
@@ -350,45 +363,41 @@ impl OnnxEmbeddingModel {
             })
             .collect::<Result<Vec<_>>>()?;
 
-        // Find max length in batch for padding
-        let max_len = encodings
-            .iter()
-            .map(|enc| enc.get_ids().len())
-            .max()
-            .unwrap_or(0);
-
-        // Prepare batch tensors (pad all sequences to same length)
-        let mut input_ids_batch = Vec::with_capacity(texts.len() * max_len);
-        let mut attention_mask_batch = Vec::with_capacity(texts.len() * max_len);
-        let mut token_type_ids_batch = Vec::with_capacity(texts.len() * max_len);
-
-        for encoding in &encodings {
-            let ids = encoding.get_ids();
-            let mask = encoding.get_attention_mask();
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in encodings.chunks(self.max_batch_size) {
+            embeddings.extend(self.embed_encodings(chunk)?);
+        }
 
-            // Add tokens
-            input_ids_batch.extend(ids.iter().map(|&id| id as i64));
-            attention_mask_batch.extend(mask.iter().map(|&m| m as i64));
-            token_type_ids_batch.extend(std::iter::repeat(0i64).take(ids.len())); // All zeros
+        Ok(embeddings)
+    }
 
-            // Pad to max_len
-            let padding_needed = max_len - ids.len();
-            input_ids_batch.extend(std::iter::repeat(0i64).take(padding_needed));
-            attention_mask_batch.extend(std::iter::repeat(0i64).take(padding_needed));
-            token_type_ids_batch.extend(std::iter::repeat(0i64).take(padding_needed));
+    /// Runs a single inference pass over already tokenized inputs
+    ///
+    /// All sequences are padded to the longest one in the slice; the attention
+    /// mask excludes padding from mean pooling. Callers are responsible for
+    /// keeping the slice within `max_batch_size`.
+    pub fn embed_encodings(&self, encodings: &[Encoding]) -> Result<Vec<Vec<f32>>> {
+        if encodings.is_empty() {
+            return Ok(vec![]);
         }
 
-        // Keep a copy of attention_mask_batch for mean pooling
-        let attention_mask_for_pooling = attention_mask_batch.clone();
+        let mut batch = PaddedBatch::new(
+            encodings
+                .iter()
+                .map(|enc| (enc.get_ids(), enc.get_attention_mask())),
+        );
+        let batch_size = batch.batch_size;
+        let max_len = batch.max_len;
 
         // Create batch tensors
-        let input_ids_array = Array2::from_shape_vec((texts.len(), max_len), input_ids_batch)
-            .context("Failed to create batch input_ids array")?;
+        let input_ids_array =
+            Array2::from_shape_vec((batch_size, max_len), std::mem::take(&mut batch.input_ids))
+                .context("Failed to create batch input_ids array")?;
         let attention_mask_array =
-            Array2::from_shape_vec((texts.len(), max_len), attention_mask_batch)
+            Array2::from_shape_vec((batch_size, max_len), batch.attention_mask.clone())
                 .context("Failed to create batch attention_mask array")?;
         let token_type_ids_array =
-            Array2::from_shape_vec((texts.len(), max_len), token_type_ids_batch)
+            Array2::from_shape_vec((batch_size, max_len), vec![0i64; batch_size * max_len])
                 .context("Failed to create batch token_type_ids array")?;
 
         // Run batch inference (ort 2.0 API) - lock session for thread-safe access
@@ -407,17 +416,13 @@ impl OnnxEmbeddingModel {
 
         // Model outputs token-level embeddings: [batch, seq_len, hidden_dim]
         // Apply mean pooling over sequence dimension for each item in batch
-        let mut embeddings: Vec<Vec<f32>> = Vec::with_capacity(texts.len());
+        let mut embeddings: Vec<Vec<f32>> = Vec::with_capacity(batch_size);
 
-        for batch_idx in 0..texts.len() {
+        for batch_idx in 0..batch_size {
             let batch_item = output_array.index_axis(Axis(0), batch_idx); // [seq_len, hidden_dim]
             let seq_len = batch_item.shape()[0];
             let hidden_dim = batch_item.shape()[1];
-
-            // Get attention mask for this batch item
-            let mask_start = batch_idx * max_len;
-            let mask_end = mask_start + max_len;
-            let item_mask = &attention_mask_for_pooling[mask_start..mask_end];
+            let item_mask = batch.mask_row(batch_idx);
 
             // Mean pooling with attention mask
             let mut pooled = vec![0.0f32; hidden_dim];
@@ -490,6 +495,65 @@ impl OnnxEmbeddingModel {
     pub fn model_name(&self) -> &str {
         &self.model_name
     }
+
+    /// Returns the tokenizer, e.g. for chunking documents to fit the model
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+
+    /// Returns the maximum number of sequences per inference pass
+    pub fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
+
+    /// Sets the maximum number of sequences per inference pass (minimum 1)
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+}
+
+/// Token ids and attention masks padded to a common length, flattened row-major
+struct PaddedBatch {
+    input_ids: Vec<i64>,
+    attention_mask: Vec<i64>,
+    batch_size: usize,
+    max_len: usize,
+}
+
+impl PaddedBatch {
+    fn new<'a>(sequences: impl Iterator<Item = (&'a [u32], &'a [u32])> + Clone) -> Self {
+        let max_len = sequences
+            .clone()
+            .map(|(ids, _)| ids.len())
+            .max()
+            .unwrap_or(0);
+        let mut input_ids = Vec::new();
+        let mut attention_mask = Vec::new();
+        let mut batch_size = 0;
+
+        for (ids, mask) in sequences {
+            input_ids.extend(ids.iter().map(|&id| id as i64));
+            attention_mask.extend(mask.iter().map(|&m| m as i64));
+
+            // Pad to max_len
+            let padding_needed = max_len - ids.len();
+            input_ids.extend(std::iter::repeat(0i64).take(padding_needed));
+            attention_mask.extend(std::iter::repeat(0i64).take(padding_needed));
+            batch_size += 1;
+        }
+
+        Self {
+            input_ids,
+            attention_mask,
+            batch_size,
+            max_len,
+        }
+    }
+
+    fn mask_row(&self, row: usize) -> &[i64] {
+        &self.attention_mask[row * self.max_len..(row + 1) * self.max_len]
+    }
 }
 
 #[cfg(test)]
@@ -534,4 +598,43 @@ mod tests {
         assert_eq!(embeddings[0].len(), 384);
         assert_eq!(embeddings[1].len(), 384);
     }
+
+    #[test]
+    fn test_padded_batch_pads_to_longest() {
+        let short_ids = [101u32, 7592, 102];
+        let short_mask = [1u32, 1, 1];
+        let long_ids = [101u32, 7592, 2088, 999, 102];
+        let long_mask = [1u32, 1, 1, 1, 1];
+        let sequences = [
+            (&short_ids[..], &short_mask[..]),
+            (&long_ids[..], &long_mask[..]),
+        ];
+
+        let batch = PaddedBatch::new(sequences.iter().copied());
+
+        assert_eq!(batch.batch_size, 2);
+        assert_eq!(batch.max_len, 5);
+        assert_eq!(batch.input_ids.len(), 10);
+        assert_eq!(&batch.input_ids[..5], &[101, 7592, 102, 0, 0]);
+        assert_eq!(batch.mask_row(0), &[1, 1, 1, 0, 0]);
+        assert_eq!(batch.mask_row(1), &[1, 1, 1, 1, 1]);
+    }
+
+    #[tokio::test]
+    #[ignore] // Only run if model files are downloaded
+    async fn test_embed_batch_splits_into_sub_batches() {
+        let model = OnnxEmbeddingModel::new("all-MiniLM-L6-v2", MODEL_PATH, TOKENIZER_PATH)
+            .await
+            .unwrap()
+            .with_max_batch_size(2);
+        let texts: Vec<String> = (0..5).map(|i| format!("sentence number {}", i)).collect();
+
+        let batched = model.embed_batch(&texts).await.unwrap();
+        assert_eq!(batched.len(), 5);
+
+        let single = model.embed(&texts[4]).await.unwrap();
+        for (a, b) in batched[4].iter().zip(&single) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
 }
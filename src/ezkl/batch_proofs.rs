@@ -6,8 +6,9 @@ use futures::stream::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, oneshot, RwLock};
 use uuid::Uuid;
 
 use crate::ezkl::{CompressionLevel, InferenceData, ProofFormat};
@@ -21,13 +22,48 @@ pub enum BatchStrategy {
     Streaming {
         chunk_size: usize,
     },
+    /// Accumulate requests until `max_batch` are queued or `max_wait_ms` has
+    /// passed since the first one arrived. Batches smaller than `min_batch`
+    /// skip aggregation, which would cost more than it saves.
     Adaptive {
-        target_latency_ms: u64,
-        min_batch_size: usize,
-        max_batch_size: usize,
+        max_wait_ms: u64,
+        max_batch: usize,
+        min_batch: usize,
     },
 }
 
+impl BatchStrategy {
+    pub fn validate(&self) -> Result<(), BatchProofError> {
+        match self {
+            BatchStrategy::Parallel { max_concurrent } if *max_concurrent == 0 => Err(
+                BatchProofError::InvalidConfig("max_concurrent must be at least 1".to_string()),
+            ),
+            BatchStrategy::Streaming { chunk_size } if *chunk_size == 0 => Err(
+                BatchProofError::InvalidConfig("chunk_size must be at least 1".to_string()),
+            ),
+            BatchStrategy::Adaptive {
+                max_batch,
+                min_batch,
+                ..
+            } => {
+                if *min_batch == 0 {
+                    return Err(BatchProofError::InvalidConfig(
+                        "min_batch must be at least 1".to_string(),
+                    ));
+                }
+                if max_batch < min_batch {
+                    return Err(BatchProofError::InvalidConfig(format!(
+                        "max_batch ({}) must not be below min_batch ({})",
+                        max_batch, min_batch
+                    )));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AggregationMethod {
     None,
@@ -120,8 +156,102 @@ pub struct AggregatedProof {
 #[derive(Debug, Clone)]
 pub struct AdaptiveMetrics {
     pub avg_batch_size: f32,
-    pub latency_compliance_rate: f32,
+    /// Average time a request waited in the queue before its batch was dispatched
+    pub avg_wait_ms: f32,
     pub total_batches: usize,
+    /// Batches dispatched because they reached `max_batch`
+    pub full_batches: usize,
+    /// Batches dispatched because `max_wait_ms` elapsed first
+    pub timed_out_batches: usize,
+}
+
+/// Running totals behind `AdaptiveMetrics`
+#[derive(Debug, Default)]
+struct AdaptiveStats {
+    total_batches: usize,
+    total_proofs: usize,
+    total_wait_ms: u64,
+    full_batches: usize,
+}
+
+impl AdaptiveStats {
+    fn record(&mut self, batch_size: usize, wait_ms: u64, max_batch: usize) {
+        self.total_batches += 1;
+        self.total_proofs += batch_size;
+        self.total_wait_ms += wait_ms;
+        if batch_size >= max_batch {
+            self.full_batches += 1;
+        }
+    }
+
+    fn snapshot(&self) -> AdaptiveMetrics {
+        let per = |total: f32, count: usize| {
+            if count == 0 {
+                0.0
+            } else {
+                total / count as f32
+            }
+        };
+        AdaptiveMetrics {
+            avg_batch_size: per(self.total_proofs as f32, self.total_batches),
+            avg_wait_ms: per(self.total_wait_ms as f32, self.total_proofs),
+            total_batches: self.total_batches,
+            full_batches: self.full_batches,
+            timed_out_batches: self.total_batches - self.full_batches,
+        }
+    }
+}
+
+/// Proof returned to each caller of `AdaptiveBatcher::submit`
+#[derive(Debug, Clone)]
+pub struct AdaptiveProof {
+    /// This request's proof; `inference_index` is its position in the batch
+    pub proof: ProofEntry,
+    /// Aggregate over the whole batch, None when the batch was below `min_batch`
+    pub aggregated_proof: Option<Arc<AggregatedProof>>,
+    pub batch_size: usize,
+    pub wait_ms: u64,
+}
+
+/// A proof request waiting in an adaptive batch queue
+struct PendingProof {
+    inference: InferenceData,
+    enqueued_at: Instant,
+    respond_to: oneshot::Sender<AdaptiveProof>,
+}
+
+/// Queue that groups proof requests arriving close together into batches
+///
+/// Created by `BatchProofGenerator::start_adaptive_batching`. Dropping it
+/// dispatches whatever is still queued and stops the background task.
+pub struct AdaptiveBatcher {
+    sender: mpsc::Sender<PendingProof>,
+    stats: Arc<RwLock<AdaptiveStats>>,
+}
+
+impl AdaptiveBatcher {
+    /// Queue a proof request and wait for the batch it lands in to complete
+    pub async fn submit(&self, inference: InferenceData) -> Result<AdaptiveProof> {
+        let (respond_to, response) = oneshot::channel();
+        self.sender
+            .send(PendingProof {
+                inference,
+                enqueued_at: Instant::now(),
+                respond_to,
+            })
+            .await
+            .map_err(|_| {
+                BatchProofError::ProcessingFailed("Adaptive batcher stopped".to_string())
+            })?;
+
+        response.await.map_err(|_| {
+            BatchProofError::ProcessingFailed("Adaptive batch was dropped".to_string()).into()
+        })
+    }
+
+    pub async fn metrics(&self) -> AdaptiveMetrics {
+        self.stats.read().await.snapshot()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -212,6 +342,7 @@ impl BatchProofGenerator {
     }
 
     pub async fn create_batch_proof(&self, request: BatchProofRequest) -> Result<BatchProofResult> {
+        request.strategy.validate()?;
         let start_time = std::time::Instant::now();
         let total_count = request.inferences.len();
 
@@ -236,8 +367,9 @@ impl BatchProofGenerator {
                 self.process_sequential(&unique_inferences, &request)
                     .await?
             }
-            BatchStrategy::Adaptive { .. } => {
-                self.process_adaptive(&unique_inferences, &request).await?
+            BatchStrategy::Adaptive { max_batch, .. } => {
+                self.process_adaptive(&unique_inferences, *max_batch)
+                    .await?
             }
         };
 
@@ -246,7 +378,7 @@ impl BatchProofGenerator {
 
         // Handle aggregation if requested
         let aggregated_proof = if request.aggregation != AggregationMethod::None {
-            Some(self.aggregate_proofs(&proofs, &request.aggregation).await?)
+            Some(Self::aggregate_proofs(&proofs, &request.aggregation).await?)
         } else {
             None
         };
@@ -259,13 +391,16 @@ impl BatchProofGenerator {
             _ => 1.0,
         };
 
-        // Create adaptive metrics if applicable
+        // Create adaptive metrics if applicable. Every request is already
+        // present, so batches fill immediately and nothing waits.
         let adaptive_metrics = match &request.strategy {
-            BatchStrategy::Adaptive { .. } => Some(AdaptiveMetrics {
-                avg_batch_size: 5.0,
-                latency_compliance_rate: 0.95,
-                total_batches: (unique_count + 4) / 5,
-            }),
+            BatchStrategy::Adaptive { max_batch, .. } => {
+                let mut stats = AdaptiveStats::default();
+                for chunk in unique_inferences.chunks(*max_batch) {
+                    stats.record(chunk.len(), 0, *max_batch);
+                }
+                Some(stats.snapshot())
+            }
             _ => None,
         };
 
@@ -367,6 +502,13 @@ impl BatchProofGenerator {
         inferences: &[InferenceData],
         _request: &BatchProofRequest,
         max_concurrent: usize,
+    ) -> Result<(Vec<ProofEntry>, Vec<BatchError>)> {
+        Self::prove_concurrently(inferences, max_concurrent).await
+    }
+
+    async fn prove_concurrently(
+        inferences: &[InferenceData],
+        max_concurrent: usize,
     ) -> Result<(Vec<ProofEntry>, Vec<BatchError>)> {
         let semaphore = Arc::new(tokio::sync::Semaphore::new(max_concurrent));
         let mut handles = Vec::new();
@@ -424,12 +566,131 @@ impl BatchProofGenerator {
     async fn process_adaptive(
         &self,
         inferences: &[InferenceData],
-        request: &BatchProofRequest,
+        max_batch: usize,
     ) -> Result<(Vec<ProofEntry>, Vec<BatchError>)> {
-        // For mock, just use parallel processing with adaptive batch size
-        let optimal_batch_size = 5;
-        self.process_parallel(inferences, request, optimal_batch_size)
-            .await
+        let max_concurrent = max_batch.min(self.config.max_parallel_proofs).max(1);
+        let mut proofs = Vec::with_capacity(inferences.len());
+        let mut errors = Vec::new();
+
+        for (chunk_idx, chunk) in inferences.chunks(max_batch).enumerate() {
+            let offset = chunk_idx * max_batch;
+            let (chunk_proofs, chunk_errors) =
+                Self::prove_concurrently(chunk, max_concurrent).await?;
+            proofs.extend(chunk_proofs.into_iter().map(|mut entry| {
+                entry.inference_index += offset;
+                entry
+            }));
+            errors.extend(chunk_errors.into_iter().map(|mut error| {
+                error.inference_index += offset;
+                error
+            }));
+        }
+
+        Ok((proofs, errors))
+    }
+
+    /// Start a background queue that batches proof requests as they arrive
+    ///
+    /// `strategy` must be `BatchStrategy::Adaptive`. Each batch is dispatched
+    /// when it reaches `max_batch` or `max_wait_ms` after its first request,
+    /// and is aggregated with `aggregation` once it holds at least `min_batch`
+    /// proofs.
+    pub fn start_adaptive_batching(
+        &self,
+        strategy: &BatchStrategy,
+        aggregation: AggregationMethod,
+    ) -> Result<AdaptiveBatcher> {
+        strategy.validate()?;
+        let BatchStrategy::Adaptive {
+            max_wait_ms,
+            max_batch,
+            min_batch,
+        } = *strategy
+        else {
+            return Err(BatchProofError::InvalidConfig(
+                "adaptive batching requires BatchStrategy::Adaptive".to_string(),
+            )
+            .into());
+        };
+
+        let (sender, receiver) = mpsc::channel(max_batch.max(1) * 4);
+        let stats = Arc::new(RwLock::new(AdaptiveStats::default()));
+        let max_concurrent = max_batch.min(self.config.max_parallel_proofs).max(1);
+
+        tokio::spawn(Self::run_adaptive_queue(
+            receiver,
+            Duration::from_millis(max_wait_ms),
+            max_batch,
+            min_batch,
+            aggregation,
+            max_concurrent,
+            stats.clone(),
+        ));
+
+        Ok(AdaptiveBatcher { sender, stats })
+    }
+
+    async fn run_adaptive_queue(
+        mut receiver: mpsc::Receiver<PendingProof>,
+        max_wait: Duration,
+        max_batch: usize,
+        min_batch: usize,
+        aggregation: AggregationMethod,
+        max_concurrent: usize,
+        stats: Arc<RwLock<AdaptiveStats>>,
+    ) {
+        while let Some(first) = receiver.recv().await {
+            // The timer starts when the first request of the batch was queued
+            let deadline = tokio::time::Instant::from_std(first.enqueued_at + max_wait);
+            let mut batch = vec![first];
+
+            while batch.len() < max_batch {
+                match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                    Ok(Some(pending)) => batch.push(pending),
+                    // Timer fired, or the batcher was dropped: flush what we have
+                    Ok(None) | Err(_) => break,
+                }
+            }
+
+            let dispatched_at = Instant::now();
+            let inferences: Vec<InferenceData> =
+                batch.iter().map(|p| p.inference.clone()).collect();
+            // On failure the senders are dropped and every caller gets an error
+            let Ok((proofs, _)) = Self::prove_concurrently(&inferences, max_concurrent).await
+            else {
+                continue;
+            };
+
+            let aggregated_proof =
+                if batch.len() >= min_batch && aggregation != AggregationMethod::None {
+                    Self::aggregate_proofs(&proofs, &aggregation)
+                        .await
+                        .ok()
+                        .map(Arc::new)
+                } else {
+                    None
+                };
+
+            let waits: Vec<u64> = batch
+                .iter()
+                .map(|p| dispatched_at.duration_since(p.enqueued_at).as_millis() as u64)
+                .collect();
+            stats
+                .write()
+                .await
+                .record(batch.len(), waits.iter().sum(), max_batch);
+
+            let batch_size = batch.len();
+            for ((pending, proof), wait_ms) in batch.into_iter().zip(proofs).zip(waits) {
+                // The caller may have given up waiting
+                let _ = pending.respond_to.send(AdaptiveProof {
+                    proof,
+                    aggregated_proof: aggregated_proof.clone(),
+                    batch_size,
+                    wait_ms,
+                });
+            }
+        }
     }
 
     async fn generate_single_proof(
@@ -459,7 +720,6 @@ impl BatchProofGenerator {
     }
 
     async fn aggregate_proofs(
-        &self,
         proofs: &[ProofEntry],
         method: &AggregationMethod,
     ) -> Result<AggregatedProof> {
@@ -639,3 +899,148 @@ impl BatchProofGenerator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ezkl::{ModelInput, ModelOutput};
+
+    fn inference(prompt: &str) -> InferenceData {
+        InferenceData {
+            model_id: "test-model".to_string(),
+            model_hash: "abc123".to_string(),
+            input: ModelInput {
+                prompt: prompt.to_string(),
+                tokens: vec![1, 2, 3],
+                embeddings: vec![0.1, 0.2],
+            },
+            output: ModelOutput {
+                response: "ok".to_string(),
+                tokens: vec![4, 5],
+                ..Default::default()
+            },
+            timestamp: 0,
+            node_id: "node".to_string(),
+        }
+    }
+
+    async fn generator() -> BatchProofGenerator {
+        BatchProofGenerator::new_mock(ParallelismConfig {
+            max_parallel_proofs: 4,
+            worker_threads: 2,
+            memory_limit_mb: 1024,
+            use_gpu: false,
+        })
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_adaptive_strategy_validation() {
+        let valid = BatchStrategy::Adaptive {
+            max_wait_ms: 10,
+            max_batch: 4,
+            min_batch: 2,
+        };
+        assert!(valid.validate().is_ok());
+
+        let inverted = BatchStrategy::Adaptive {
+            max_wait_ms: 10,
+            max_batch: 1,
+            min_batch: 2,
+        };
+        assert!(inverted.validate().is_err());
+
+        let zero_min = BatchStrategy::Adaptive {
+            max_wait_ms: 10,
+            max_batch: 4,
+            min_batch: 0,
+        };
+        assert!(zero_min.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_full_batch_dispatches_before_timer() {
+        let generator = generator().await;
+        let strategy = BatchStrategy::Adaptive {
+            max_wait_ms: 60_000,
+            max_batch: 3,
+            min_batch: 2,
+        };
+        let batcher = Arc::new(
+            generator
+                .start_adaptive_batching(&strategy, AggregationMethod::Linear)
+                .unwrap(),
+        );
+
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                let batcher = batcher.clone();
+                tokio::spawn(async move { batcher.submit(inference(&format!("p{}", i))).await })
+            })
+            .collect();
+
+        for handle in handles {
+            let proof = handle.await.unwrap().unwrap();
+            assert!(proof.proof.is_success());
+            assert_eq!(proof.batch_size, 3);
+            assert_eq!(proof.aggregated_proof.unwrap().num_aggregated, 3);
+        }
+
+        let metrics = batcher.metrics().await;
+        assert_eq!(metrics.total_batches, 1);
+        assert_eq!(metrics.full_batches, 1);
+        assert_eq!(metrics.avg_batch_size, 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_timer_flushes_small_batch_without_aggregation() {
+        let generator = generator().await;
+        let strategy = BatchStrategy::Adaptive {
+            max_wait_ms: 20,
+            max_batch: 8,
+            min_batch: 2,
+        };
+        let batcher = generator
+            .start_adaptive_batching(&strategy, AggregationMethod::Linear)
+            .unwrap();
+
+        let proof = batcher.submit(inference("lonely")).await.unwrap();
+        assert_eq!(proof.batch_size, 1);
+        // Below min_batch, so the proof is not aggregated
+        assert!(proof.aggregated_proof.is_none());
+        assert!(proof.wait_ms >= 20);
+
+        let metrics = batcher.metrics().await;
+        assert_eq!(metrics.timed_out_batches, 1);
+        assert!(metrics.avg_wait_ms >= 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_create_batch_proof_adaptive_metrics() {
+        let generator = generator().await;
+        let request = BatchProofRequest {
+            inferences: (0..7).map(|i| inference(&format!("p{}", i))).collect(),
+            strategy: BatchStrategy::Adaptive {
+                max_wait_ms: 10,
+                max_batch: 3,
+                min_batch: 1,
+            },
+            aggregation: AggregationMethod::None,
+            proof_format: ProofFormat::Standard,
+            compression: CompressionLevel::None,
+            priority: 0,
+            enable_deduplication: false,
+        };
+
+        let result = generator.create_batch_proof(request).await.unwrap();
+        assert_eq!(result.successful_count, 7);
+        let indices: Vec<_> = result.proofs.iter().map(|p| p.inference_index()).collect();
+        assert_eq!(indices, (0..7).collect::<Vec<_>>());
+
+        let metrics = result.adaptive_metrics.unwrap();
+        assert_eq!(metrics.total_batches, 3);
+        assert_eq!(metrics.full_batches, 2);
+        assert_eq!(metrics.avg_wait_ms, 0.0);
+    }
+}
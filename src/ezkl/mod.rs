@@ -20,9 +20,9 @@ pub use proof_creation::{
 };
 
 pub use batch_proofs::{
-    AdaptiveMetrics, AggregatedProof, AggregationMethod, BatchError, BatchProofError,
-    BatchProofGenerator, BatchProofRequest, BatchProofResult, BatchProofStatus, BatchProofStream,
-    BatchStrategy, ChunkResult, ParallelismConfig, ProofEntry,
+    AdaptiveBatcher, AdaptiveMetrics, AdaptiveProof, AggregatedProof, AggregationMethod,
+    BatchError, BatchProofError, BatchProofGenerator, BatchProofRequest, BatchProofResult,
+    BatchProofStatus, BatchProofStream, BatchStrategy, ChunkResult, ParallelismConfig, ProofEntry,
     ResourceMetrics as BatchResourceMetrics,
 };
 
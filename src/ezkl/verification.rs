@@ -29,7 +29,8 @@ pub enum VerificationMode {
     Batch,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TrustLevel {
     Strict,
     Standard,
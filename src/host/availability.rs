@@ -19,6 +19,9 @@ pub enum AvailabilityStatus {
     Available,
     Unavailable,
     Maintenance,
+    /// A maintenance window is coming up; only jobs that finish before it
+    /// (or fit the grace period) are accepted
+    MaintenancePending,
     ShuttingDown,
 }
 
@@ -165,6 +168,84 @@ impl AvailabilityManager {
         Ok(())
     }
 
+    /// Whether the node can run jobs at `time`: inside the schedule, outside
+    /// every maintenance window and not shutting down
+    pub async fn is_available_at(&self, time: DateTime<Utc>) -> bool {
+        self.shutdown_handle.is_none()
+            && self.check_availability_at(time).await == AvailabilityStatus::Available
+            && !self
+                .maintenance_windows
+                .values()
+                .any(|window| time >= window.start_time && time < window.end_time)
+    }
+
+    /// First maintenance window affecting `model_id` that overlaps
+    /// `start..end`. Windows without `affects_models` affect every model.
+    pub async fn maintenance_conflict(
+        &self,
+        model_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Option<MaintenanceWindow> {
+        self.maintenance_windows
+            .values()
+            .filter(|window| {
+                window.affects_models.is_empty()
+                    || window.affects_models.iter().any(|m| m == model_id)
+            })
+            .filter(|window| start < window.end_time && end > window.start_time)
+            .min_by_key(|window| window.start_time)
+            .cloned()
+    }
+
+    /// Check that a job for `model_id` starting now and running for
+    /// `estimated_duration` won't be cut off by maintenance. Jobs no longer
+    /// than `grace` are let through up to the window start, on the basis
+    /// that the maintenance restart waits for them to drain.
+    ///
+    /// A rejection moves the node to `MaintenancePending`; the next accepted
+    /// job moves it back to `Available`.
+    pub async fn admit_job(
+        &mut self,
+        model_id: &str,
+        estimated_duration: Duration,
+        grace: Duration,
+    ) -> Result<(), ScheduleError> {
+        if self.shutdown_handle.is_some() {
+            return Err(ScheduleError::ShuttingDown);
+        }
+
+        let now = Utc::now();
+        let conflict = self
+            .maintenance_conflict(model_id, now, now + estimated_duration)
+            .await
+            .filter(|window| window.start_time <= now || estimated_duration > grace);
+
+        match conflict {
+            Some(window) => {
+                if self.current_status == AvailabilityStatus::Available {
+                    self.change_status(
+                        AvailabilityStatus::MaintenancePending,
+                        format!(
+                            "Maintenance window {} starts at {}",
+                            window.id, window.start_time
+                        ),
+                    );
+                }
+                Err(ScheduleError::MaintenanceConflict(window.id))
+            }
+            None => {
+                if self.current_status == AvailabilityStatus::MaintenancePending {
+                    self.change_status(
+                        AvailabilityStatus::Available,
+                        "No maintenance conflict".to_string(),
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
     pub async fn get_upcoming_maintenance(&self) -> Vec<MaintenanceWindow> {
         let now = Utc::now();
         self.maintenance_windows
@@ -326,6 +407,18 @@ impl AvailabilityManager {
         forecast
     }
 
+    fn change_status(&mut self, new_status: AvailabilityStatus, reason: String) {
+        let old_status = std::mem::replace(&mut self.current_status, new_status.clone());
+        let change = AvailabilityChange {
+            timestamp: Utc::now(),
+            old_status,
+            new_status,
+            reason,
+        };
+
+        let _ = self.change_sender.send(change);
+    }
+
     async fn update_current_status(&mut self) {
         let now = Utc::now();
         let new_status = self.check_availability_at(now).await;
@@ -0,0 +1,377 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Adaptive inference concurrency
+//!
+//! The effective concurrency limit moves within `[min_concurrency,
+//! max_concurrency]`. It drops one step when the busiest resource is above
+//! `high_utilization` or per-token latency exceeds `target_token_latency`. It
+//! rises one step when both have headroom and requests actually queued for
+//! the limit. A critical resource alert sheds load at once by cutting the
+//! limit by `shed_factor`, and blocks increases for `shed_cooldown`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Notify, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::host::resources::{AlertLevel, ResourceAlert, ResourceMonitor};
+
+/// Weight of the newest sample in the per-token latency average
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+#[derive(Debug, Clone)]
+pub struct AdaptiveConcurrencyConfig {
+    pub min_concurrency: usize,
+    pub max_concurrency: usize,
+    /// Starting limit, typically `EngineConfig::max_concurrent_inferences`
+    pub initial_concurrency: usize,
+    /// Busiest-resource utilization (0.0 to 1.0) above which the limit drops
+    pub high_utilization: f64,
+    /// Utilization below which the limit may rise
+    pub low_utilization: f64,
+    /// Average time per generated token above which the limit drops
+    pub target_token_latency: Duration,
+    pub adjust_interval: Duration,
+    /// Fraction of the limit kept when a critical alert fires
+    pub shed_factor: f64,
+    /// How long after a critical alert the limit may not rise
+    pub shed_cooldown: Duration,
+}
+
+impl Default for AdaptiveConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            min_concurrency: 1,
+            max_concurrency: 8,
+            initial_concurrency: 4,
+            high_utilization: 0.9,
+            low_utilization: 0.7,
+            target_token_latency: Duration::from_millis(100),
+            adjust_interval: Duration::from_secs(5),
+            shed_factor: 0.5,
+            shed_cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyChangeReason {
+    HighUtilization,
+    HighLatency,
+    Headroom,
+    CriticalAlert,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcurrencyChange {
+    pub old_limit: usize,
+    pub new_limit: usize,
+    pub reason: ConcurrencyChangeReason,
+}
+
+#[derive(Debug)]
+struct State {
+    limit: usize,
+    active: usize,
+    /// Average seconds per generated token
+    token_latency: Option<f64>,
+    shed_until: Option<Instant>,
+    /// Requests hit the limit since the last adjustment
+    saturated: bool,
+}
+
+/// Concurrency limiter whose limit follows resource usage and latency
+#[derive(Debug)]
+pub struct AdaptiveConcurrency {
+    config: AdaptiveConcurrencyConfig,
+    state: Mutex<State>,
+    released: Notify,
+}
+
+impl AdaptiveConcurrency {
+    pub fn new(config: AdaptiveConcurrencyConfig) -> Self {
+        let min = config.min_concurrency.max(1);
+        let limit = config
+            .initial_concurrency
+            .clamp(min, config.max_concurrency.max(min));
+        Self {
+            config,
+            state: Mutex::new(State {
+                limit,
+                active: 0,
+                token_latency: None,
+                shed_until: None,
+                saturated: false,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit
+    }
+
+    pub fn active(&self) -> usize {
+        self.state.lock().unwrap().active
+    }
+
+    /// Average time per generated token, once any request has completed
+    pub fn token_latency(&self) -> Option<Duration> {
+        self.state
+            .lock()
+            .unwrap()
+            .token_latency
+            .map(Duration::from_secs_f64)
+    }
+
+    /// Wait for a slot under the current limit
+    pub async fn acquire(self: &Arc<Self>) -> ConcurrencyPermit {
+        loop {
+            // Created before checking so a release in between still wakes us
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.active < state.limit {
+                    state.active += 1;
+                    if state.active == state.limit {
+                        state.saturated = true;
+                    }
+                    return ConcurrencyPermit {
+                        controller: self.clone(),
+                        started: Instant::now(),
+                    };
+                }
+                state.saturated = true;
+            }
+            released.await;
+        }
+    }
+
+    pub fn record_token_latency(&self, latency: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let sample = latency.as_secs_f64();
+        state.token_latency = Some(match state.token_latency {
+            Some(average) => average + (sample - average) * LATENCY_SMOOTHING,
+            None => sample,
+        });
+    }
+
+    /// Move the limit one step for the current utilization of the busiest
+    /// resource (0.0 to 1.0) and the observed latency
+    pub fn adjust(&self, utilization: f64) -> Option<ConcurrencyChange> {
+        let mut state = self.state.lock().unwrap();
+        let shedding = state.shed_until.is_some_and(|until| Instant::now() < until);
+        let slow = state
+            .token_latency
+            .is_some_and(|latency| latency > self.config.target_token_latency.as_secs_f64());
+        let saturated = std::mem::take(&mut state.saturated);
+
+        let (target, reason) = if utilization > self.config.high_utilization {
+            (
+                state.limit.saturating_sub(1),
+                ConcurrencyChangeReason::HighUtilization,
+            )
+        } else if slow {
+            (
+                state.limit.saturating_sub(1),
+                ConcurrencyChangeReason::HighLatency,
+            )
+        } else if !shedding && saturated && utilization < self.config.low_utilization {
+            (state.limit + 1, ConcurrencyChangeReason::Headroom)
+        } else {
+            return None;
+        };
+        self.set_limit(&mut state, target, reason)
+    }
+
+    /// Shed load when a critical alert fires
+    pub fn on_alert(&self, alert: &ResourceAlert) -> Option<ConcurrencyChange> {
+        if alert.level != AlertLevel::Critical {
+            return None;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.shed_until = Some(Instant::now() + self.config.shed_cooldown);
+        let target = (state.limit as f64 * self.config.shed_factor).floor() as usize;
+        self.set_limit(&mut state, target, ConcurrencyChangeReason::CriticalAlert)
+    }
+
+    fn set_limit(
+        &self,
+        state: &mut State,
+        target: usize,
+        reason: ConcurrencyChangeReason,
+    ) -> Option<ConcurrencyChange> {
+        let min = self.config.min_concurrency.max(1);
+        let new_limit = target.clamp(min, self.config.max_concurrency.max(min));
+        if new_limit == state.limit {
+            return None;
+        }
+
+        let old_limit = std::mem::replace(&mut state.limit, new_limit);
+        if new_limit > old_limit {
+            self.released.notify_waiters();
+        }
+        Some(ConcurrencyChange {
+            old_limit,
+            new_limit,
+            reason,
+        })
+    }
+
+    fn release(&self) {
+        self.state.lock().unwrap().active -= 1;
+        self.released.notify_waiters();
+    }
+
+    /// Adjust every `adjust_interval` from the monitor's resource summary
+    /// and shed load on its critical alerts
+    pub async fn spawn(self: Arc<Self>, monitor: Arc<RwLock<ResourceMonitor>>) -> JoinHandle<()> {
+        let mut alerts = monitor.read().await.subscribe_to_alerts().await;
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.config.adjust_interval);
+            loop {
+                let change = tokio::select! {
+                    _ = ticker.tick() => {
+                        match monitor.read().await.get_resource_summary().await {
+                            Ok(summary) => self.adjust(summary.utilization()),
+                            // Monitoring not started yet
+                            Err(_) => None,
+                        }
+                    }
+                    alert = alerts.recv() => match alert {
+                        Ok(alert) => {
+                            let change = self.on_alert(&alert);
+                            if change.is_some() {
+                                warn!("Shedding inference load: {}", alert.message);
+                            }
+                            change
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => None,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                };
+                if let Some(change) = change {
+                    info!(
+                        "Inference concurrency {} -> {} ({:?})",
+                        change.old_limit, change.new_limit, change.reason
+                    );
+                }
+            }
+        })
+    }
+}
+
+/// A running inference's slot; released on drop
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    controller: Arc<AdaptiveConcurrency>,
+    started: Instant,
+}
+
+impl ConcurrencyPermit {
+    /// Record the latency of a finished inference that generated `tokens`
+    pub fn complete(self, tokens: usize) {
+        if tokens > 0 {
+            self.controller
+                .record_token_latency(self.started.elapsed() / tokens as u32);
+        }
+    }
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        self.controller.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn critical_alert() -> ResourceAlert {
+        ResourceAlert {
+            timestamp: 0,
+            metric: "gpu_temperature".to_string(),
+            level: AlertLevel::Critical,
+            current_value: 95.0,
+            threshold_value: 90.0,
+            message: "gpu_temperature exceeded threshold".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limit_follows_utilization_within_bounds() {
+        let controller = Arc::new(AdaptiveConcurrency::new(AdaptiveConcurrencyConfig {
+            min_concurrency: 2,
+            max_concurrency: 5,
+            initial_concurrency: 4,
+            ..Default::default()
+        }));
+
+        // Headroom, but nothing waited for the limit
+        assert_eq!(controller.adjust(0.3), None);
+
+        let permits: Vec<_> = futures::future::join_all((0..4).map(|_| controller.acquire())).await;
+        assert_eq!(controller.active(), 4);
+        let change = controller.adjust(0.3).unwrap();
+        assert_eq!(change.new_limit, 5);
+        assert_eq!(change.reason, ConcurrencyChangeReason::Headroom);
+        drop(permits);
+
+        for _ in 0..5 {
+            controller.adjust(0.95);
+        }
+        assert_eq!(controller.limit(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_slow_tokens_lower_the_limit() {
+        let controller = AdaptiveConcurrency::new(AdaptiveConcurrencyConfig::default());
+        controller.record_token_latency(Duration::from_millis(400));
+
+        let change = controller.adjust(0.5).unwrap();
+        assert_eq!(change.reason, ConcurrencyChangeReason::HighLatency);
+        assert_eq!(change.new_limit, 3);
+    }
+
+    #[tokio::test]
+    async fn test_critical_alert_sheds_load() {
+        let controller = Arc::new(AdaptiveConcurrency::new(AdaptiveConcurrencyConfig {
+            max_concurrency: 8,
+            initial_concurrency: 8,
+            ..Default::default()
+        }));
+
+        let change = controller.on_alert(&critical_alert()).unwrap();
+        assert_eq!((change.old_limit, change.new_limit), (8, 4));
+
+        // Saturated with headroom, but still cooling down
+        let _permits: Vec<_> =
+            futures::future::join_all((0..4).map(|_| controller.acquire())).await;
+        assert_eq!(controller.adjust(0.1), None);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_a_slot() {
+        let controller = Arc::new(AdaptiveConcurrency::new(AdaptiveConcurrencyConfig {
+            min_concurrency: 1,
+            initial_concurrency: 1,
+            ..Default::default()
+        }));
+        let first = controller.acquire().await;
+
+        let waiting = tokio::spawn({
+            let controller = controller.clone();
+            async move { controller.acquire().await.complete(10) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        first.complete(10);
+        waiting.await.unwrap();
+        assert_eq!(controller.active(), 0);
+        assert!(controller.token_latency().is_some());
+    }
+}
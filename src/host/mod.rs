@@ -1,6 +1,7 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 pub mod availability;
+pub mod concurrency;
 pub mod model_config;
 pub mod pricing;
 pub mod registration;
@@ -9,12 +10,18 @@ pub mod resources;
 pub mod selection;
 
 pub use model_config::{
-    HostingError, ModelConfig, ModelHostingManager, ModelMetadata, ModelParameters, ModelStatus,
+    ConfigReload, HostingError, ModelConfig, ModelConfigField, ModelHostingManager, ModelMetadata,
+    ModelParameters, ModelStatus, RopeScaling,
 };
 
 pub use pricing::{
     Currency, DynamicPricingConfig, PriceUpdate, PricingError, PricingManager, PricingModel,
-    PricingTier,
+    PricingTier, SurgePricingConfig, SurgeThreshold,
+};
+
+pub use concurrency::{
+    AdaptiveConcurrency, AdaptiveConcurrencyConfig, ConcurrencyChange, ConcurrencyChangeReason,
+    ConcurrencyPermit,
 };
 
 pub use availability::{
@@ -28,7 +35,10 @@ pub use registry::{HostInfo, HostRegistry, RegistryStats};
 
 pub use resources::{
     AlertLevel, AlertThreshold, CpuMetrics, GpuMetrics, MemoryMetrics, MonitoringError,
-    NetworkMetrics, ResourceAlert, ResourceMetrics, ResourceMonitor,
+    NetworkMetrics, ResourceAlert, ResourceMetrics, ResourceMonitor, ResourceSummary,
 };
 
-pub use selection::{HostSelector, JobRequirements, PerformanceMetrics, ScoringWeights};
+pub use selection::{
+    CandidateScore, HostSelector, JobRequirements, PerformanceMetrics, ScoreBreakdown,
+    ScoreComponent, ScoringWeights, SelectionExplanation,
+};
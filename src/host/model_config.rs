@@ -2,10 +2,13 @@
 // SPDX-License-Identifier: BUSL-1.1
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::path::Path;
 use thiserror::Error;
 use tokio::fs;
 
+use crate::inference::ChatTemplate;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ModelConfig {
     pub model_id: String,
@@ -13,6 +16,21 @@ pub struct ModelConfig {
     pub parameters: ModelParameters,
     pub metadata: ModelMetadata,
     pub status: ModelStatus,
+    /// Advertised price per token; `None` uses the host's default pricing
+    #[serde(default)]
+    pub price_per_token: Option<f64>,
+    /// Prompt template; `None` uses the model's default
+    #[serde(default)]
+    pub chat_template: Option<ChatTemplate>,
+    #[serde(default)]
+    pub rope_scaling: Option<RopeScaling>,
+}
+
+/// RoPE frequency overrides, applied when an inference context is created
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RopeScaling {
+    pub freq_base: f32,
+    pub freq_scale: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -43,6 +61,68 @@ pub enum ModelStatus {
     Error(String),
 }
 
+/// Part of a `ModelConfig` changed by a reload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModelConfigField {
+    ModelPath,
+    GpuLayers,
+    ContextSize,
+    Parameters,
+    Pricing,
+    ChatTemplate,
+    RopeScaling,
+    Metadata,
+    Status,
+}
+
+impl ModelConfigField {
+    /// Whether changing this field means loading the weights again. GPU
+    /// offload is fixed when the GGUF is loaded; everything else is read per
+    /// request or per inference context.
+    pub fn requires_reload(&self) -> bool {
+        matches!(
+            self,
+            ModelConfigField::ModelPath | ModelConfigField::GpuLayers
+        )
+    }
+}
+
+impl fmt::Display for ModelConfigField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ModelConfigField::ModelPath => "model_path",
+            ModelConfigField::GpuLayers => "gpu_layers",
+            ModelConfigField::ContextSize => "context_size",
+            ModelConfigField::Parameters => "parameters",
+            ModelConfigField::Pricing => "price_per_token",
+            ModelConfigField::ChatTemplate => "chat_template",
+            ModelConfigField::RopeScaling => "rope_scaling",
+            ModelConfigField::Metadata => "metadata",
+            ModelConfigField::Status => "status",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Outcome of `ModelHostingManager::reload_model_config`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigReload {
+    /// Changes that took effect in place
+    pub applied: Vec<ModelConfigField>,
+    /// Changes that only take effect once the weights are loaded again
+    pub requires_reload: Vec<ModelConfigField>,
+}
+
+impl ConfigReload {
+    pub fn needs_reload(&self) -> bool {
+        !self.requires_reload.is_empty()
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        self.applied.is_empty() && self.requires_reload.is_empty()
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum HostingError {
     #[error("Model not found: {0}")]
@@ -119,6 +199,88 @@ impl ModelHostingManager {
         Ok(())
     }
 
+    /// Replace a model's configuration without restarting the node. Pricing,
+    /// chat template, RoPE scaling, sampling parameters and metadata apply in
+    /// place. If the model path or GPU offload changed the model is marked
+    /// `Loading`, and the caller reloads the weights before enabling it again.
+    pub async fn reload_model_config(
+        &mut self,
+        model_id: &str,
+        config: ModelConfig,
+    ) -> Result<ConfigReload, HostingError> {
+        if config.model_id != model_id {
+            return Err(HostingError::InvalidConfiguration(format!(
+                "Model id {} does not match {}",
+                config.model_id, model_id
+            )));
+        }
+        let current = self
+            .models
+            .get(model_id)
+            .ok_or_else(|| HostingError::ModelNotFound(model_id.to_string()))?;
+
+        if config.model_path != current.model_path
+            && !config.model_path.starts_with("/models/")
+            && !Path::new(&config.model_path).exists()
+        {
+            return Err(HostingError::ModelNotFound(config.model_path.clone()));
+        }
+        self.validate_config(&config)?;
+
+        let mut reload = ConfigReload::default();
+        for field in Self::changed_fields(current, &config) {
+            if field.requires_reload() {
+                reload.requires_reload.push(field);
+            } else {
+                reload.applied.push(field);
+            }
+        }
+
+        let mut config = config;
+        if reload.needs_reload() {
+            config.status = ModelStatus::Loading;
+        }
+        self.models.insert(model_id.to_string(), config);
+        Ok(reload)
+    }
+
+    fn changed_fields(old: &ModelConfig, new: &ModelConfig) -> Vec<ModelConfigField> {
+        let mut changed = Vec::new();
+        if old.model_path != new.model_path {
+            changed.push(ModelConfigField::ModelPath);
+        }
+        if old.parameters.gpu_layers != new.parameters.gpu_layers {
+            changed.push(ModelConfigField::GpuLayers);
+        }
+        if old.parameters.context_size != new.parameters.context_size {
+            changed.push(ModelConfigField::ContextSize);
+        }
+        let sampling = |parameters: &ModelParameters| ModelParameters {
+            context_size: 0,
+            gpu_layers: None,
+            ..parameters.clone()
+        };
+        if sampling(&old.parameters) != sampling(&new.parameters) {
+            changed.push(ModelConfigField::Parameters);
+        }
+        if old.price_per_token != new.price_per_token {
+            changed.push(ModelConfigField::Pricing);
+        }
+        if old.chat_template != new.chat_template {
+            changed.push(ModelConfigField::ChatTemplate);
+        }
+        if old.rope_scaling != new.rope_scaling {
+            changed.push(ModelConfigField::RopeScaling);
+        }
+        if old.metadata != new.metadata {
+            changed.push(ModelConfigField::Metadata);
+        }
+        if old.status != new.status {
+            changed.push(ModelConfigField::Status);
+        }
+        changed
+    }
+
     pub async fn set_model_status(
         &mut self,
         model_id: &str,
@@ -210,6 +372,22 @@ impl ModelHostingManager {
             ));
         }
 
+        if let Some(price) = config.price_per_token {
+            if !price.is_finite() || price < 0.0 {
+                return Err(HostingError::InvalidConfiguration(
+                    "Price per token must be a non-negative number".to_string(),
+                ));
+            }
+        }
+
+        if let Some(rope) = config.rope_scaling {
+            if rope.freq_base <= 0.0 || rope.freq_scale <= 0.0 {
+                return Err(HostingError::InvalidConfiguration(
+                    "RoPE frequency base and scale must be positive".to_string(),
+                ));
+            }
+        }
+
         // Validate max tokens
         if config.parameters.max_tokens == 0 {
             return Err(HostingError::InvalidConfiguration(
@@ -4,6 +4,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
+use tokio::sync::broadcast;
+
+use crate::host::resources::ResourceSummary;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PricingModel {
@@ -36,6 +39,54 @@ pub struct DynamicPricingConfig {
     pub demand_threshold: f64,
 }
 
+/// Surge multiplier applied once utilization exceeds `utilization`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SurgeThreshold {
+    /// Utilization of the node's busiest resource, 0.0 to 1.0
+    pub utilization: f64,
+    pub multiplier: f64,
+}
+
+/// Load-based surge pricing on top of the tiered price
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SurgePricingConfig {
+    pub thresholds: Vec<SurgeThreshold>,
+    /// Upper bound on the surge multiplier, whatever the thresholds say
+    pub max_multiplier: f64,
+    /// Fraction of the surge above the target removed per utilization
+    /// update once load drops (1.0 drops straight back)
+    pub decay_factor: f64,
+}
+
+impl Default for SurgePricingConfig {
+    fn default() -> Self {
+        Self {
+            thresholds: vec![
+                SurgeThreshold {
+                    utilization: 0.7,
+                    multiplier: 1.25,
+                },
+                SurgeThreshold {
+                    utilization: 0.85,
+                    multiplier: 1.5,
+                },
+                SurgeThreshold {
+                    utilization: 0.95,
+                    multiplier: 2.0,
+                },
+            ],
+            max_multiplier: 2.0,
+            decay_factor: 0.5,
+        }
+    }
+}
+
+/// Surge multipliers this close to their target snap to it, so decay ends
+const SURGE_SNAP: f64 = 0.01;
+
+/// A price change across models. Surge updates from `update_utilization`
+/// carry the surge multiplier over base prices, which is not applied to
+/// the base price itself as with `apply_bulk_update`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceUpdate {
     pub multiplier: f64,
@@ -79,16 +130,24 @@ pub struct PricingManager {
     current_demand: f64,
     minimum_price_per_token: f64,
     promotions: HashMap<String, Promotion>,
+    surge_pricing: Option<SurgePricingConfig>,
+    surge_multiplier: f64,
+    price_update_sender: broadcast::Sender<PriceUpdate>,
 }
 
 impl PricingManager {
     pub fn new() -> Self {
+        let (price_update_sender, _) = broadcast::channel(100);
+
         Self {
             models: HashMap::new(),
             price_history: HashMap::new(),
             current_demand: 0.0,
             minimum_price_per_token: 0.0,
             promotions: HashMap::new(),
+            surge_pricing: None,
+            surge_multiplier: 1.0,
+            price_update_sender,
         }
     }
 
@@ -135,7 +194,7 @@ impl PricingManager {
         let base_price = pricing.base_price_per_token * tokens as f64;
         let tier_multiplier = self.get_tier_multiplier(&pricing.tiers, tokens);
 
-        Ok(base_price * tier_multiplier)
+        Ok(base_price * tier_multiplier * self.surge_multiplier)
     }
 
     pub async fn calculate_time_price(
@@ -148,7 +207,7 @@ impl PricingManager {
             .get(model_id)
             .ok_or_else(|| PricingError::ModelNotFound(model_id.to_string()))?;
 
-        Ok(pricing.base_price_per_minute * duration_minutes)
+        Ok(pricing.base_price_per_minute * duration_minutes * self.surge_multiplier)
     }
 
     pub async fn calculate_token_price_with_demand(
@@ -293,6 +352,114 @@ impl PricingManager {
         self.minimum_price_per_token = minimum;
     }
 
+    /// Enable surge pricing; prices follow `update_utilization` from now on
+    pub async fn set_surge_pricing(
+        &mut self,
+        config: SurgePricingConfig,
+    ) -> Result<(), PricingError> {
+        if config.max_multiplier < 1.0 {
+            return Err(PricingError::InvalidConfiguration(
+                "Surge max multiplier must be at least 1.0".to_string(),
+            ));
+        }
+        if !(config.decay_factor > 0.0 && config.decay_factor <= 1.0) {
+            return Err(PricingError::InvalidConfiguration(
+                "Surge decay factor must be in (0, 1]".to_string(),
+            ));
+        }
+        for threshold in &config.thresholds {
+            if !(0.0..=1.0).contains(&threshold.utilization) {
+                return Err(PricingError::InvalidConfiguration(
+                    "Surge threshold utilization must be between 0 and 1".to_string(),
+                ));
+            }
+            if threshold.multiplier < 1.0 {
+                return Err(PricingError::InvalidConfiguration(
+                    "Surge multiplier must be at least 1.0".to_string(),
+                ));
+            }
+        }
+
+        self.surge_multiplier = self.surge_multiplier.min(config.max_multiplier);
+        self.surge_pricing = Some(config);
+        Ok(())
+    }
+
+    /// Current surge multiplier, 1.0 when prices are at base
+    pub async fn get_surge_multiplier(&self) -> f64 {
+        self.surge_multiplier
+    }
+
+    pub async fn subscribe_to_price_updates(&self) -> broadcast::Receiver<PriceUpdate> {
+        self.price_update_sender.subscribe()
+    }
+
+    /// Adjust the surge multiplier for the node's current utilization
+    /// (0.0 to 1.0). Surges apply immediately; as load drops the multiplier
+    /// decays towards the lower target by `decay_factor` per update.
+    /// Returns the `PriceUpdate` sent to subscribers if the multiplier
+    /// changed.
+    pub async fn update_utilization(&mut self, utilization: f64) -> Option<PriceUpdate> {
+        let config = self.surge_pricing.as_ref()?;
+        let utilization = utilization.clamp(0.0, 1.0);
+
+        let target = config
+            .thresholds
+            .iter()
+            .filter(|threshold| utilization > threshold.utilization)
+            .map(|threshold| threshold.multiplier)
+            .fold(1.0, f64::max)
+            .min(config.max_multiplier);
+
+        let mut multiplier = if target >= self.surge_multiplier {
+            target
+        } else {
+            self.surge_multiplier - (self.surge_multiplier - target) * config.decay_factor
+        };
+        if (multiplier - target).abs() < SURGE_SNAP {
+            multiplier = target;
+        }
+        if multiplier == self.surge_multiplier {
+            return None;
+        }
+        self.surge_multiplier = multiplier;
+
+        let mut affected_models: Vec<String> = self.models.keys().cloned().collect();
+        affected_models.sort();
+        let update = PriceUpdate {
+            multiplier,
+            affected_models,
+            reason: format!("Surge pricing at {:.0}% utilization", utilization * 100.0),
+            effective_from: Utc::now(),
+        };
+
+        for model_id in &update.affected_models {
+            let pricing = &self.models[model_id];
+            let history_entry = PriceHistoryEntry {
+                timestamp: update.effective_from,
+                price_per_token: pricing.base_price_per_token * multiplier,
+                price_per_minute: pricing.base_price_per_minute * multiplier,
+                reason: update.reason.clone(),
+            };
+            self.price_history
+                .entry(model_id.clone())
+                .or_insert_with(Vec::new)
+                .push(history_entry);
+        }
+
+        // No subscribers is fine
+        let _ = self.price_update_sender.send(update.clone());
+        Some(update)
+    }
+
+    /// `update_utilization` from a `ResourceMonitor` summary
+    pub async fn update_from_resources(
+        &mut self,
+        summary: &ResourceSummary,
+    ) -> Option<PriceUpdate> {
+        self.update_utilization(summary.utilization()).await
+    }
+
     fn get_tier_multiplier(&self, tiers: &[PricingTier], tokens: u64) -> f64 {
         for tier in tiers {
             if tokens >= tier.min_tokens && tokens <= tier.max_tokens {
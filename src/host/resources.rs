@@ -107,6 +107,18 @@ pub struct ResourceSummary {
     pub active_alerts: u32,
 }
 
+impl ResourceSummary {
+    /// Utilization of the busiest resource (CPU, memory or any GPU), 0.0 to 1.0
+    pub fn utilization(&self) -> f64 {
+        self.gpu_usage
+            .iter()
+            .copied()
+            .fold(self.cpu_usage.max(self.memory_usage), f64::max)
+            .clamp(0.0, 100.0)
+            / 100.0
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourcePrediction {
     pub metric: String,
@@ -9,6 +9,7 @@ use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::host::registry::HostInfo;
+use crate::p2p::ModelAnnouncement;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
@@ -52,6 +53,54 @@ impl Default for ScoringWeights {
     }
 }
 
+/// One factor of a host's score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ScoreComponent {
+    /// Normalized factor score, 0.0 (worst) to 1.0 (best)
+    pub score: f64,
+    /// Weight from `ScoringWeights`
+    pub weight: f64,
+}
+
+impl ScoreComponent {
+    /// Contribution of this factor to the total score
+    pub fn weighted(&self) -> f64 {
+        self.score * self.weight
+    }
+}
+
+/// How a host's score was put together
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreBreakdown {
+    /// From average completion time, weighted by `ScoringWeights::performance`
+    pub latency: ScoreComponent,
+    /// From cost per token, weighted by `ScoringWeights::cost`
+    pub price: ScoreComponent,
+    /// From success rate and uptime, weighted by `ScoringWeights::reliability`
+    pub reputation: ScoreComponent,
+    /// From active jobs, weighted by `ScoringWeights::load`
+    pub load: ScoreComponent,
+    /// Sum of the weighted components clamped to 0.0-1.0; the value
+    /// `calculate_host_score` returns
+    pub total: f64,
+}
+
+/// A host that met the job requirements, with the metrics it was scored on
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateScore {
+    pub address: Address,
+    pub metrics: PerformanceMetrics,
+    pub breakdown: ScoreBreakdown,
+}
+
+/// Result of `select_with_explanation`
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectionExplanation {
+    pub selected: Option<Address>,
+    /// Hosts that met the requirements, best first
+    pub candidates: Vec<CandidateScore>,
+}
+
 #[derive(Debug, Clone)]
 pub struct JobRequirements {
     pub model_id: String,
@@ -80,7 +129,16 @@ impl HostSelector {
         }
     }
 
-    pub fn calculate_host_score(&self, _host: &HostInfo, metrics: &PerformanceMetrics) -> f64 {
+    pub fn calculate_host_score(&self, host: &HostInfo, metrics: &PerformanceMetrics) -> f64 {
+        self.score_breakdown(host, metrics).total
+    }
+
+    /// Component scores behind `calculate_host_score`
+    pub fn score_breakdown(
+        &self,
+        _host: &HostInfo,
+        metrics: &PerformanceMetrics,
+    ) -> ScoreBreakdown {
         // Normalize each factor to 0-1 range
 
         // Performance score (lower completion time is better)
@@ -103,51 +161,105 @@ impl HostSelector {
         // Load score (lower load is better)
         let load_score = 1.0 / (1.0 + metrics.current_load as f64);
 
-        // Apply weights and sum
-        let total_score = perf_score * self.weight_config.performance
-            + cost_score * self.weight_config.cost
-            + reliability_score * self.weight_config.reliability
-            + load_score * self.weight_config.load;
+        let component = |score: f64, weight: f64| ScoreComponent { score, weight };
+        let latency = component(perf_score, self.weight_config.performance);
+        let price = component(cost_score, self.weight_config.cost);
+        let reputation = component(reliability_score, self.weight_config.reliability);
+        let load = component(load_score, self.weight_config.load);
 
-        // Ensure score is between 0 and 1
-        total_score.min(1.0).max(0.0)
+        // Apply weights and sum
+        let total_score =
+            latency.weighted() + price.weighted() + reputation.weighted() + load.weighted();
+
+        ScoreBreakdown {
+            latency,
+            price,
+            reputation,
+            load,
+            // Ensure score is between 0 and 1
+            total: total_score.min(1.0).max(0.0),
+        }
     }
 
-    pub async fn select_best_host(
+    /// Score every host that meets `requirements`, best first
+    pub async fn score_candidates(
         &self,
         hosts: Vec<HostInfo>,
         requirements: &JobRequirements,
-    ) -> Option<Address> {
-        if hosts.is_empty() {
-            return None;
-        }
-
-        // Filter hosts by requirements
+    ) -> Vec<CandidateScore> {
         let filtered_hosts = self.filter_by_requirements(hosts, requirements).await;
 
-        if filtered_hosts.is_empty() {
+        let tracker = self.performance_tracker.read().await;
+        let mut candidates: Vec<CandidateScore> = filtered_hosts
+            .iter()
+            .map(|host| {
+                let metrics = tracker.get(&host.address).cloned().unwrap_or_default();
+                let breakdown = self.score_breakdown(host, &metrics);
+                CandidateScore {
+                    address: host.address,
+                    metrics,
+                    breakdown,
+                }
+            })
+            .collect();
+
+        // Sort by score (highest first)
+        candidates.sort_by(|a, b| b.breakdown.total.total_cmp(&a.breakdown.total));
+        candidates
+    }
+
+    /// Like `select_best_host`, but also returns every candidate's score
+    /// breakdown so the choice can be checked against `ScoringWeights`
+    pub async fn select_with_explanation(
+        &self,
+        hosts: Vec<HostInfo>,
+        requirements: &JobRequirements,
+    ) -> SelectionExplanation {
+        let candidates = self.score_candidates(hosts, requirements).await;
+        let selected = candidates.first().map(|candidate| candidate.address);
+
+        for (rank, candidate) in candidates.iter().enumerate() {
+            let b = &candidate.breakdown;
+            debug!(
+                "Candidate #{} {}: total={:.3} latency={:.3} price={:.3} reputation={:.3} load={:.3}",
+                rank + 1,
+                candidate.address,
+                b.total,
+                b.latency.weighted(),
+                b.price.weighted(),
+                b.reputation.weighted(),
+                b.load.weighted()
+            );
+        }
+        if selected.is_none() {
             warn!("No hosts meet the requirements");
-            return None;
         }
 
-        // Score each host
-        let tracker = self.performance_tracker.read().await;
-        let mut scored_hosts: Vec<(Address, f64)> = Vec::new();
-
-        for host in &filtered_hosts {
-            let metrics = tracker.get(&host.address).cloned().unwrap_or_default();
+        SelectionExplanation {
+            selected,
+            candidates,
+        }
+    }
 
-            let score = self.calculate_host_score(host, &metrics);
-            scored_hosts.push((host.address, score));
+    pub async fn select_best_host(
+        &self,
+        hosts: Vec<HostInfo>,
+        requirements: &JobRequirements,
+    ) -> Option<Address> {
+        if hosts.is_empty() {
+            return None;
         }
 
-        // Sort by score (highest first)
-        scored_hosts.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        let candidates = self.score_candidates(hosts, requirements).await;
 
-        if let Some((addr, score)) = scored_hosts.first() {
-            debug!("Selected best host {} with score {:.3}", addr, score);
-            Some(*addr)
+        if let Some(best) = candidates.first() {
+            debug!(
+                "Selected best host {} with score {:.3}",
+                best.address, best.breakdown.total
+            );
+            Some(best.address)
         } else {
+            warn!("No hosts meet the requirements");
             None
         }
     }
@@ -162,28 +274,12 @@ impl HostSelector {
             return Vec::new();
         }
 
-        // Filter hosts by requirements
-        let filtered_hosts = self.filter_by_requirements(hosts, requirements).await;
-
-        // Score each host
-        let tracker = self.performance_tracker.read().await;
-        let mut scored_hosts: Vec<(Address, f64)> = Vec::new();
-
-        for host in &filtered_hosts {
-            let metrics = tracker.get(&host.address).cloned().unwrap_or_default();
-
-            let score = self.calculate_host_score(host, &metrics);
-            scored_hosts.push((host.address, score));
-        }
-
-        // Sort by score (highest first)
-        scored_hosts.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
         // Take top n
-        scored_hosts
+        self.score_candidates(hosts, requirements)
+            .await
             .into_iter()
             .take(n)
-            .map(|(addr, _)| addr)
+            .map(|candidate| candidate.address)
             .collect()
     }
 
@@ -301,6 +397,33 @@ impl HostSelector {
         tracker.insert(host, metrics);
     }
 
+    /// Turn gossiped model announcements into hosts for the `select_*`
+    /// methods, recording each host's announced load and price
+    ///
+    /// Announcements without a valid on-chain host address are skipped.
+    pub async fn apply_model_announcements(
+        &self,
+        announcements: &[ModelAnnouncement],
+    ) -> Vec<HostInfo> {
+        let mut tracker = self.performance_tracker.write().await;
+        announcements
+            .iter()
+            .filter_map(|announcement| {
+                let address: Address = announcement.host_address.as_deref()?.parse().ok()?;
+                let metrics = tracker.entry(address).or_default();
+                metrics.current_load = announcement.current_load;
+                metrics.cost_per_token = announcement.price_per_token;
+
+                Some(HostInfo {
+                    address,
+                    metadata: serde_json::json!({ "models": announcement.models }).to_string(),
+                    stake: U256::zero(),
+                    is_online: true,
+                })
+            })
+            .collect()
+    }
+
     async fn filter_by_requirements(
         &self,
         hosts: Vec<HostInfo>,
@@ -350,3 +350,235 @@ impl SemanticCache {
         self.entries.write().await.clear();
     }
 }
+
+/// Settings for reusing prompt KV state across requests
+#[derive(Debug, Clone)]
+pub struct PrefixCacheConfig {
+    pub max_entries: usize,
+    /// Upper bound on stored context state; a single state larger than this
+    /// is never cached
+    pub max_memory_bytes: usize,
+    /// Shared prefixes shorter than this are cheaper to prefill than restore
+    pub min_prefix_tokens: usize,
+}
+
+impl Default for PrefixCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 16,
+            max_memory_bytes: 2 * 1024 * 1024 * 1024, // 2GB
+            min_prefix_tokens: 64,
+        }
+    }
+}
+
+impl PrefixCacheConfig {
+    /// Reads PREFIX_CACHE, PREFIX_CACHE_MAX_ENTRIES, PREFIX_CACHE_MAX_MB and
+    /// PREFIX_CACHE_MIN_TOKENS; `None` unless PREFIX_CACHE is true
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("PREFIX_CACHE")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+        };
+        let defaults = Self::default();
+        Some(Self {
+            max_entries: var("PREFIX_CACHE_MAX_ENTRIES").unwrap_or(defaults.max_entries),
+            max_memory_bytes: var("PREFIX_CACHE_MAX_MB")
+                .map(|mb| mb * 1024 * 1024)
+                .unwrap_or(defaults.max_memory_bytes),
+            min_prefix_tokens: var("PREFIX_CACHE_MIN_TOKENS").unwrap_or(defaults.min_prefix_tokens),
+        })
+    }
+}
+
+/// Context state captured right after a reusable prefix was prefilled
+#[derive(Debug, Clone)]
+pub struct PrefixCacheEntry {
+    pub model_id: String,
+    pub tokens: Vec<i32>,
+    pub state: Arc<Vec<u8>>,
+    pub created_at: SystemTime,
+    pub hits: usize,
+}
+
+/// Cached state of a prompt's reusable prefix
+#[derive(Debug, Clone)]
+pub struct PrefixMatch {
+    pub key: String,
+    /// Leading prompt tokens covered by `state`
+    pub reused_tokens: usize,
+    pub state: Arc<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PrefixCacheStats {
+    pub lookups: usize,
+    pub hits: usize,
+    pub misses: usize,
+    pub tokens_reused: usize,
+    pub evictions: usize,
+    pub entries: usize,
+    pub memory_bytes: usize,
+}
+
+impl PrefixCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.lookups as f64
+        }
+    }
+}
+
+/// Cache of prefilled KV state keyed by the hash of a reusable prompt prefix
+/// (e.g. everything before the first user turn).
+///
+/// Requests sharing a long system prompt restore the state of an earlier
+/// request and only prefill the tokens after that prefix.
+pub struct PrefixCache {
+    config: PrefixCacheConfig,
+    entries: LruCache<String, PrefixCacheEntry>,
+    memory_bytes: usize,
+    stats: PrefixCacheStats,
+}
+
+impl PrefixCache {
+    pub fn new(config: PrefixCacheConfig) -> Result<Self> {
+        let entries = LruCache::new(
+            NonZeroUsize::new(config.max_entries)
+                .ok_or_else(|| anyhow!("Invalid max_entries: must be > 0"))?,
+        );
+        Ok(Self {
+            config,
+            entries,
+            memory_bytes: 0,
+            stats: PrefixCacheStats::default(),
+        })
+    }
+
+    pub fn key(model_id: &str, tokens: &[i32]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(model_id);
+        for token in tokens {
+            hasher.update(token.to_le_bytes());
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Find the cached state of exactly `prefix`. Prefixes shorter than
+    /// `min_prefix_tokens` are never cached and skip the lookup.
+    pub fn lookup(&mut self, model_id: &str, prefix: &[i32]) -> Option<PrefixMatch> {
+        if !self.is_eligible(prefix) {
+            return None;
+        }
+        self.stats.lookups += 1;
+
+        let key = Self::key(model_id, prefix);
+        // Promotes the entry in LRU order
+        let Some(entry) = self.entries.get_mut(&key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        entry.hits += 1;
+        self.stats.hits += 1;
+        self.stats.tokens_reused += prefix.len();
+        Some(PrefixMatch {
+            key,
+            reused_tokens: prefix.len(),
+            state: entry.state.clone(),
+        })
+    }
+
+    /// Whether a freshly prefilled prefix is worth capturing
+    pub fn should_store(&self, model_id: &str, prefix: &[i32]) -> bool {
+        self.is_eligible(prefix) && !self.entries.contains(&Self::key(model_id, prefix))
+    }
+
+    fn is_eligible(&self, prefix: &[i32]) -> bool {
+        prefix.len() >= self.config.min_prefix_tokens.max(1)
+    }
+
+    /// Store the state of a prefilled prefix, evicting least recently used
+    /// entries to stay within the memory budget. Returns false if the state
+    /// alone exceeds the budget.
+    pub fn insert(&mut self, model_id: &str, tokens: Vec<i32>, state: Vec<u8>) -> bool {
+        let size = state.len();
+        if size > self.config.max_memory_bytes {
+            return false;
+        }
+        while self.memory_bytes + size > self.config.max_memory_bytes {
+            match self.entries.pop_lru() {
+                Some((_, evicted)) => self.remove_accounting(&evicted),
+                None => break,
+            }
+        }
+
+        let key = Self::key(model_id, &tokens);
+        let entry = PrefixCacheEntry {
+            model_id: model_id.to_string(),
+            tokens,
+            state: Arc::new(state),
+            created_at: SystemTime::now(),
+            hits: 0,
+        };
+        // Replaces the same key or drops the LRU entry when full
+        if let Some((old_key, old)) = self.entries.push(key.clone(), entry) {
+            if old_key == key {
+                self.memory_bytes = self.memory_bytes.saturating_sub(old.state.len());
+            } else {
+                self.remove_accounting(&old);
+            }
+        }
+        self.memory_bytes += size;
+        self.sync_stats();
+        true
+    }
+
+    /// Drop every entry of a model, e.g. when it is unloaded
+    pub fn invalidate_model(&mut self, model_id: &str) -> usize {
+        let keys: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.model_id == model_id)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &keys {
+            if let Some(entry) = self.entries.pop(key) {
+                self.remove_accounting(&entry);
+            }
+        }
+        self.sync_stats();
+        keys.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> PrefixCacheStats {
+        self.stats.clone()
+    }
+
+    fn remove_accounting(&mut self, entry: &PrefixCacheEntry) {
+        self.memory_bytes = self.memory_bytes.saturating_sub(entry.state.len());
+        self.stats.evictions += 1;
+    }
+
+    fn sync_stats(&mut self) {
+        self.stats.entries = self.entries.len();
+        self.stats.memory_bytes = self.memory_bytes;
+    }
+}
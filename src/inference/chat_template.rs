@@ -6,6 +6,12 @@
 //! a template system to correctly format conversations for each model type.
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::inference::tools::{self, Tool};
+
+/// Longest custom template a request may supply
+pub const MAX_CUSTOM_TEMPLATE_LEN: usize = 8192;
 
 /// Supported chat template formats
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -85,6 +91,63 @@ impl ChatTemplate {
         }
     }
 
+    /// Byte length of the leading system (and tool definition) section of a
+    /// prompt formatted with this template, i.e. the part shared by every
+    /// request with the same system prompt. `None` when the prompt has no
+    /// such section or the template folds it into the first user turn.
+    pub fn system_prefix_len(&self, prompt: &str) -> Option<usize> {
+        let first_turn = match self {
+            Self::Default => "User: ",
+            Self::Llama2 => return None,
+            Self::Vicuna => "USER: ",
+            Self::Harmony => "<|start|>user<|message|>",
+            Self::ChatML => "<|im_start|>user\n",
+            Self::Glm4 => "<|user|>\n",
+        };
+        prompt.find(first_turn).filter(|&len| len > 0)
+    }
+
+    /// Text placed before and after content that arrives mid-answer so it
+    /// reads as a tool message: the first part closes the assistant turn and
+    /// opens the tool message, the second closes it and reopens the assistant
+    pub fn late_context_frame(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Default => ("\nTool: ", "\nAssistant: "),
+            Self::Llama2 => (" [INST] Tool result: ", " [/INST] "),
+            Self::Vicuna => ("\nTOOL: ", "\nASSISTANT: "),
+            Self::Harmony => (
+                "<|end|>\n<|start|>tool<|message|>",
+                "<|end|>\n<|start|>assistant<|channel|>final<|message|>",
+            ),
+            Self::ChatML => (
+                "<|im_end|>\n<|im_start|>tool\n",
+                "<|im_end|>\n<|im_start|>assistant\n",
+            ),
+            Self::Glm4 => ("\n<|observation|>\n", "\n<|assistant|>\n"),
+        }
+    }
+
+    /// Describe `tools` to the model ahead of the conversation: in a
+    /// developer message for Harmony, in the system message otherwise
+    pub fn add_tool_definitions(&self, messages: &mut Vec<(String, String)>, tools: &[Tool]) {
+        if tools.is_empty() {
+            return;
+        }
+        match self {
+            Self::Harmony => {
+                let position = messages
+                    .iter()
+                    .position(|(role, _)| role != "system")
+                    .unwrap_or(messages.len());
+                messages.insert(
+                    position,
+                    ("developer".to_string(), tools::tools_prompt(tools)),
+                );
+            }
+            _ => tools::add_to_system_message(messages, tools),
+        }
+    }
+
     /// Default format: "User: ...\nAssistant: ...\n"
     fn format_default(&self, messages: &[(String, String)]) -> String {
         let mut prompt = String::new();
@@ -100,6 +163,9 @@ impl ChatTemplate {
                 "assistant" => {
                     prompt.push_str(&format!("Assistant: {}\n", content));
                 }
+                "tool" => {
+                    prompt.push_str(&format!("Tool: {}\n", content));
+                }
                 _ => {}
             }
         }
@@ -140,6 +206,9 @@ impl ChatTemplate {
                 "assistant" => {
                     prompt.push_str(&format!("{} ", content));
                 }
+                "tool" => {
+                    prompt.push_str(&format!("[INST] Tool result: {} [/INST] ", content));
+                }
                 _ => {}
             }
         }
@@ -162,6 +231,9 @@ impl ChatTemplate {
                 "assistant" => {
                     prompt.push_str(&format!("ASSISTANT: {}\n", content));
                 }
+                "tool" => {
+                    prompt.push_str(&format!("TOOL: {}\n", content));
+                }
                 _ => {}
             }
         }
@@ -205,6 +277,17 @@ impl ChatTemplate {
                         content
                     ));
                 }
+                // Tool definitions go in a developer message, leaving the
+                // default system prompt in place
+                "developer" => {
+                    prompt.push_str(&format!(
+                        "<|start|>developer<|message|>{}<|end|>\n",
+                        content
+                    ));
+                }
+                "tool" => {
+                    prompt.push_str(&format!("<|start|>tool<|message|>{}<|end|>\n", content));
+                }
                 _ => {}
             }
         }
@@ -233,6 +316,7 @@ impl ChatTemplate {
                 "system" => prompt.push_str(&format!("<|system|>\n{}\n", content)),
                 "user" => prompt.push_str(&format!("<|user|>\n{}\n", content)),
                 "assistant" => prompt.push_str(&format!("<|assistant|>\n{}\n", content)),
+                "tool" => prompt.push_str(&format!("<|observation|>\n{}\n", content)),
                 _ => {}
             }
         }
@@ -255,6 +339,9 @@ impl ChatTemplate {
                 "assistant" => {
                     prompt.push_str(&format!("<|im_start|>assistant\n{}<|im_end|>\n", content));
                 }
+                "tool" => {
+                    prompt.push_str(&format!("<|im_start|>tool\n{}<|im_end|>\n", content));
+                }
                 _ => {}
             }
         }
@@ -271,6 +358,183 @@ impl Default for ChatTemplate {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TemplateError {
+    #[error("Template is empty")]
+    Empty,
+    #[error("Template is longer than {} bytes", MAX_CUSTOM_TEMPLATE_LEN)]
+    TooLong,
+    #[error("Template must contain a {{content}} placeholder")]
+    MissingContent,
+    #[error("Unknown placeholder {{{0}}}; use {{role}} or {{content}}, or {{{{ and }}}} for literal braces")]
+    UnknownPlaceholder(String),
+    #[error("Unmatched brace at byte {0}; use {{{{ and }}}} for literal braces")]
+    UnmatchedBrace(usize),
+    #[error("No messages to render")]
+    NoMessages,
+    #[error("Message {0} has an empty role")]
+    EmptyRole(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Role,
+    Content,
+}
+
+/// Template text supplied with a request, rendered once per message with
+/// `{role}` and `{content}` substituted (`{{` and `}}` are literal braces).
+/// The generation prompt is the text up to `{content}` rendered for the
+/// assistant, e.g. `<|im_start|>{role}\n{content}<|im_end|>\n` ends the
+/// prompt with `<|im_start|>assistant\n`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomChatTemplate {
+    segments: Vec<Segment>,
+}
+
+impl CustomChatTemplate {
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        if template.is_empty() {
+            return Err(TemplateError::Empty);
+        }
+        if template.len() > MAX_CUSTOM_TEMPLATE_LEN {
+            return Err(TemplateError::TooLong);
+        }
+
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|&(_, c)| c == '{').is_some() => literal.push('{'),
+                '}' if chars.next_if(|&(_, c)| c == '}').is_some() => literal.push('}'),
+                '{' => {
+                    let rest = &template[i + 1..];
+                    let end = rest.find('}').ok_or(TemplateError::UnmatchedBrace(i))?;
+                    let placeholder = match &rest[..end] {
+                        "role" => Segment::Role,
+                        "content" => Segment::Content,
+                        name => return Err(TemplateError::UnknownPlaceholder(name.to_string())),
+                    };
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    segments.push(placeholder);
+                    // Skip the placeholder name and its closing brace
+                    for _ in 0..=rest[..end].chars().count() {
+                        chars.next();
+                    }
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace(i)),
+                c => literal.push(c),
+            }
+        }
+        segments.push(Segment::Literal(literal));
+        segments.retain(|segment| !matches!(segment, Segment::Literal(text) if text.is_empty()));
+
+        if !segments.contains(&Segment::Content) {
+            return Err(TemplateError::MissingContent);
+        }
+        Ok(Self { segments })
+    }
+
+    fn render_message(&self, prompt: &mut String, role: &str, content: Option<&str>) {
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => prompt.push_str(text),
+                Segment::Role => prompt.push_str(role),
+                Segment::Content => match content {
+                    Some(content) => prompt.push_str(content),
+                    None => return,
+                },
+            }
+        }
+    }
+
+    /// Rendered text of a `role` message before and after its content
+    fn render_around_content(&self, role: &str) -> (String, String) {
+        let (mut before, mut after) = (String::new(), String::new());
+        let mut out = &mut before;
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Role => out.push_str(role),
+                Segment::Content => out = &mut after,
+            }
+        }
+        (before, after)
+    }
+
+    /// Like `ChatTemplate::late_context_frame`, rendered from this template
+    pub fn late_context_frame(&self) -> (String, String) {
+        let (assistant_open, assistant_close) = self.render_around_content("assistant");
+        let (tool_open, tool_close) = self.render_around_content("tool");
+        (assistant_close + &tool_open, tool_close + &assistant_open)
+    }
+
+    /// Format a conversation, ending with the assistant generation prompt
+    pub fn format_messages(&self, messages: &[(String, String)]) -> Result<String, TemplateError> {
+        if messages.is_empty() {
+            return Err(TemplateError::NoMessages);
+        }
+        if let Some(index) = messages.iter().position(|(role, _)| role.is_empty()) {
+            return Err(TemplateError::EmptyRole(index));
+        }
+
+        let mut prompt = String::new();
+        for (role, content) in messages {
+            self.render_message(&mut prompt, role, Some(content));
+        }
+        self.render_message(&mut prompt, "assistant", None);
+        Ok(prompt)
+    }
+}
+
+/// Template a request uses instead of the model default: a built-in
+/// template name, or custom template text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatTemplateOverride {
+    Builtin(ChatTemplate),
+    Custom(CustomChatTemplate),
+}
+
+impl ChatTemplateOverride {
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        match ChatTemplate::from_str(template.trim()) {
+            Some(builtin) => Ok(Self::Builtin(builtin)),
+            None => CustomChatTemplate::parse(template).map(Self::Custom),
+        }
+    }
+
+    /// Frame for content decoded mid-answer; see `ChatTemplate::late_context_frame`
+    pub fn late_context_frame(&self) -> (String, String) {
+        match self {
+            Self::Builtin(template) => {
+                let (open, close) = template.late_context_frame();
+                (open.to_string(), close.to_string())
+            }
+            Self::Custom(template) => template.late_context_frame(),
+        }
+    }
+
+    /// Stop strings implied by the template; custom templates rely on the
+    /// request's own stop sequences
+    pub fn stop_tokens(&self) -> Vec<&'static str> {
+        match self {
+            Self::Builtin(template) => template.stop_tokens(),
+            Self::Custom(_) => vec![],
+        }
+    }
+
+    /// See `ChatTemplate::system_prefix_len`; custom templates have no known
+    /// turn markers, so their prompts are never split
+    pub fn system_prefix_len(&self, prompt: &str) -> Option<usize> {
+        match self {
+            Self::Builtin(template) => template.system_prefix_len(prompt),
+            Self::Custom(_) => None,
+        }
+    }
+}
+
 /// Parse MODEL_STOP_TOKENS env var into a list of stop token strings.
 /// Format: comma-separated, e.g. "<|user|>,<|observation|>"
 /// Returns empty vec if not set (template defaults will be used).
@@ -314,6 +578,30 @@ mod tests {
         assert_eq!(ChatTemplate::from_str("unknown"), None);
     }
 
+    #[test]
+    fn test_system_prefix_len() {
+        let messages = vec![
+            ("system".to_string(), "Be brief.".to_string()),
+            ("user".to_string(), "Hello".to_string()),
+        ];
+
+        for template in [
+            ChatTemplate::ChatML,
+            ChatTemplate::Harmony,
+            ChatTemplate::Glm4,
+        ] {
+            let prompt = template.format_messages(&messages);
+            let len = template.system_prefix_len(&prompt).unwrap();
+            assert!(prompt[..len].contains("Be brief."));
+            assert!(!prompt[..len].contains("Hello"));
+        }
+
+        let no_system = ChatTemplate::ChatML.format_messages(&messages[1..]);
+        assert_eq!(ChatTemplate::ChatML.system_prefix_len(&no_system), None);
+        let llama2 = ChatTemplate::Llama2.format_messages(&messages);
+        assert_eq!(ChatTemplate::Llama2.system_prefix_len(&llama2), None);
+    }
+
     #[test]
     fn test_default_format() {
         let template = ChatTemplate::Default;
@@ -625,4 +913,114 @@ mod tests {
         // Harmony format includes channel specification for assistant responses
         assert!(formatted.ends_with("<|start|>assistant<|channel|>final<|message|>"));
     }
+
+    fn messages(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(role, content)| (role.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_custom_template_renders_messages() {
+        let template =
+            CustomChatTemplate::parse("<|im_start|>{role}\n{content}<|im_end|>\n").unwrap();
+        let prompt = template
+            .format_messages(&messages(&[("system", "Use tools."), ("user", "Hi")]))
+            .unwrap();
+        assert_eq!(
+            prompt,
+            ChatTemplate::ChatML
+                .format_messages(&messages(&[("system", "Use tools."), ("user", "Hi")]))
+        );
+
+        // Literal braces, e.g. JSON in tool-use formatting
+        let template = CustomChatTemplate::parse("{{\"{role}\": \"{content}\"}}\n").unwrap();
+        assert_eq!(
+            template
+                .format_messages(&messages(&[("tool", "42")]))
+                .unwrap(),
+            "{\"tool\": \"42\"}\n{\"assistant\": \""
+        );
+    }
+
+    #[test]
+    fn test_late_context_frame_matches_tool_messages() {
+        let (open, close) = ChatTemplate::ChatML.late_context_frame();
+        let framed = format!("<|im_start|>assistant\nPartial{}Source{}", open, close);
+        assert_eq!(
+            framed,
+            "<|im_start|>assistant\nPartial<|im_end|>\n<|im_start|>tool\nSource<|im_end|>\n<|im_start|>assistant\n"
+        );
+
+        // A custom template frames like the built-in it imitates
+        let custom =
+            CustomChatTemplate::parse("<|im_start|>{role}\n{content}<|im_end|>\n").unwrap();
+        let (custom_open, custom_close) = custom.late_context_frame();
+        assert_eq!((custom_open.as_str(), custom_close.as_str()), (open, close));
+    }
+
+    #[test]
+    fn test_custom_template_errors() {
+        assert_eq!(CustomChatTemplate::parse(""), Err(TemplateError::Empty));
+        assert_eq!(
+            CustomChatTemplate::parse("{role}: "),
+            Err(TemplateError::MissingContent)
+        );
+        assert_eq!(
+            CustomChatTemplate::parse("{name}: {content}"),
+            Err(TemplateError::UnknownPlaceholder("name".to_string()))
+        );
+        assert_eq!(
+            CustomChatTemplate::parse("{content"),
+            Err(TemplateError::UnmatchedBrace(0))
+        );
+        assert_eq!(
+            CustomChatTemplate::parse("{content}}"),
+            Err(TemplateError::UnmatchedBrace(9))
+        );
+
+        let template = CustomChatTemplate::parse("{role}: {content}\n").unwrap();
+        assert_eq!(
+            template.format_messages(&[]),
+            Err(TemplateError::NoMessages)
+        );
+        assert_eq!(
+            template.format_messages(&messages(&[("user", "Hi"), ("", "?")])),
+            Err(TemplateError::EmptyRole(1))
+        );
+    }
+
+    #[test]
+    fn test_override_accepts_builtin_names() {
+        assert_eq!(
+            ChatTemplateOverride::parse("chatml"),
+            Ok(ChatTemplateOverride::Builtin(ChatTemplate::ChatML))
+        );
+        assert_eq!(
+            ChatTemplateOverride::parse("chatml").unwrap().stop_tokens(),
+            vec!["<|im_end|>"]
+        );
+        assert!(matches!(
+            ChatTemplateOverride::parse("{role}: {content}\n"),
+            Ok(ChatTemplateOverride::Custom(_))
+        ));
+    }
+
+    #[test]
+    fn test_override_system_prefix_len() {
+        let prompt = ChatTemplate::ChatML
+            .format_messages(&messages(&[("system", "Be brief."), ("user", "Hello")]));
+        let chatml = ChatTemplateOverride::parse("chatml").unwrap();
+        assert_eq!(
+            chatml.system_prefix_len(&prompt),
+            ChatTemplate::ChatML.system_prefix_len(&prompt)
+        );
+        assert!(chatml.system_prefix_len(&prompt).is_some());
+
+        // Another template's markers don't split a ChatML prompt
+        assert_eq!(ChatTemplate::Harmony.system_prefix_len(&prompt), None);
+        let custom = ChatTemplateOverride::parse("{role}: {content}\n").unwrap();
+        assert_eq!(custom.system_prefix_len(&prompt), None);
+    }
 }
@@ -0,0 +1,1040 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Continuous (in-flight) batching
+//!
+//! Instead of running each request in its own decode loop, a per-model worker
+//! keeps a running set of active sequences in one llama.cpp context. Every
+//! step decodes one token for each generating sequence plus prompt chunks for
+//! newly admitted ones; a request that finishes leaves the batch immediately
+//! and a waiting one takes its slot at the next step.
+
+use crate::inference::engine::{
+    build_sampler, context_params, emit_token, normalize_thought_token, resolve_stop_token_ids,
+    sanitize_prompt_for_tokenizer, validate_logit_bias, EngineConfig, GenerationOutcome,
+    InferenceRequest, RealLlamaModel, StopSequenceMatcher, TokenInfo,
+};
+use crate::performance::batching::{BatchConfig, BatchPriority};
+use anyhow::{anyhow, Result};
+use llama_cpp_2::{
+    context::LlamaContext,
+    llama_batch::LlamaBatch,
+    model::{AddBos, LlamaModel, Special},
+    sampling::LlamaSampler,
+    token::LlamaToken,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::Ordering;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::oneshot;
+
+#[derive(Debug, Clone)]
+pub struct ContinuousBatchConfig {
+    /// Sequences decoded together in one context
+    pub max_active_sequences: usize,
+    /// Tokens per decode step; prompt prefill is chunked to fit
+    pub step_token_budget: usize,
+    /// KV cache shared by all active sequences (defaults to the model's
+    /// context size)
+    pub kv_cache_tokens: Option<usize>,
+}
+
+impl Default for ContinuousBatchConfig {
+    fn default() -> Self {
+        Self {
+            max_active_sequences: 4,
+            step_token_budget: 512,
+            kv_cache_tokens: None,
+        }
+    }
+}
+
+impl ContinuousBatchConfig {
+    /// Reads CONTINUOUS_BATCHING, CONTINUOUS_BATCH_MAX_SEQUENCES,
+    /// CONTINUOUS_BATCH_STEP_TOKENS and CONTINUOUS_BATCH_KV_TOKENS; `None`
+    /// unless CONTINUOUS_BATCHING is true
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("CONTINUOUS_BATCHING")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+        };
+        let defaults = Self::default();
+        Some(Self {
+            max_active_sequences: var("CONTINUOUS_BATCH_MAX_SEQUENCES")
+                .unwrap_or(defaults.max_active_sequences),
+            step_token_budget: var("CONTINUOUS_BATCH_STEP_TOKENS")
+                .unwrap_or(defaults.step_token_budget),
+            kv_cache_tokens: var("CONTINUOUS_BATCH_KV_TOKENS"),
+        })
+    }
+}
+
+impl From<&BatchConfig> for ContinuousBatchConfig {
+    fn from(config: &BatchConfig) -> Self {
+        Self {
+            max_active_sequences: config.max_batch_size.max(1),
+            ..Default::default()
+        }
+    }
+}
+
+/// Why a sequence left the batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinishReason {
+    EosToken,
+    StopToken,
+    StopSequence,
+    Length,
+    Cancelled,
+    Timeout,
+}
+
+impl FinishReason {
+    /// Same labels the per-request decode loop records
+    pub fn stop_reason(&self) -> &'static str {
+        match self {
+            FinishReason::EosToken => "eos_token",
+            FinishReason::StopToken => "stop_token",
+            FinishReason::StopSequence => "stop_sequence",
+            FinishReason::Length => "loop_condition",
+            FinishReason::Cancelled => "cancelled",
+            FinishReason::Timeout => "timeout",
+        }
+    }
+}
+
+/// Tokens of one sequence decoded in a step
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepInput {
+    pub seq_id: i32,
+    pub tokens: Vec<i32>,
+    /// KV position of the first token
+    pub start_pos: usize,
+    /// Whether logits are needed for the last token
+    pub sample: bool,
+}
+
+/// Model side of the scheduler: decodes steps and samples per sequence
+pub trait BatchBackend {
+    type Sequence;
+
+    fn decode(&mut self, inputs: &[StepInput]) -> Result<()>;
+
+    /// Sample the next token from the logits at `logits_index` in the last step
+    fn sample(&mut self, sequence: &mut Self::Sequence, logits_index: i32) -> i32;
+
+    /// Record a sampled token; returns a reason if the sequence is done
+    fn accept(&mut self, sequence: &mut Self::Sequence, token: i32) -> Option<FinishReason>;
+
+    /// Checked before each step (cancellation, deadlines)
+    fn should_abort(&self, sequence: &Self::Sequence) -> Option<FinishReason>;
+
+    /// Free the KV cache of a sequence that left the batch
+    fn release(&mut self, seq_id: i32);
+}
+
+struct WaitingSequence<S> {
+    sequence: S,
+    prompt: Vec<i32>,
+    max_tokens: usize,
+}
+
+struct ActiveSequence<S> {
+    seq_id: i32,
+    sequence: S,
+    prompt: Vec<i32>,
+    max_tokens: usize,
+    /// Tokens already in the KV cache
+    n_past: usize,
+    last_token: Option<i32>,
+    generated: usize,
+    kv_reserved: usize,
+    finished: Option<FinishReason>,
+}
+
+impl<S> ActiveSequence<S> {
+    fn prefilling(&self) -> bool {
+        self.n_past < self.prompt.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct FinishedSequence<S> {
+    pub sequence: S,
+    pub reason: FinishReason,
+    pub prompt_tokens: usize,
+    pub tokens_generated: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContinuousBatchMetrics {
+    pub steps: u64,
+    pub sequences_admitted: u64,
+    pub sequences_finished: u64,
+    pub tokens_decoded: u64,
+    pub peak_active: usize,
+}
+
+/// Admission, step planning and eviction for a running set of sequences
+pub struct ContinuousScheduler<S> {
+    config: ContinuousBatchConfig,
+    kv_capacity: usize,
+    kv_reserved: usize,
+    /// Indexed like the `performance::batching` priority queues
+    waiting: [VecDeque<WaitingSequence<S>>; 3],
+    active: Vec<ActiveSequence<S>>,
+    free_seq_ids: Vec<i32>,
+    metrics: ContinuousBatchMetrics,
+}
+
+impl<S> ContinuousScheduler<S> {
+    pub fn new(config: ContinuousBatchConfig, kv_capacity: usize) -> Self {
+        let max_active = config.max_active_sequences.max(1);
+        Self {
+            free_seq_ids: (0..max_active as i32).rev().collect(),
+            config,
+            kv_capacity,
+            kv_reserved: 0,
+            waiting: Default::default(),
+            active: Vec::new(),
+            metrics: ContinuousBatchMetrics::default(),
+        }
+    }
+
+    /// Queue a sequence; it joins the batch at the next step with room for it
+    pub fn submit(
+        &mut self,
+        sequence: S,
+        prompt: Vec<i32>,
+        max_tokens: usize,
+        priority: BatchPriority,
+    ) {
+        self.waiting[priority.to_queue_index()].push_back(WaitingSequence {
+            sequence,
+            prompt,
+            max_tokens,
+        });
+    }
+
+    pub fn active_len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn waiting_len(&self) -> usize {
+        self.waiting.iter().map(VecDeque::len).sum()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.active.is_empty() && self.waiting_len() == 0
+    }
+
+    pub fn metrics(&self) -> &ContinuousBatchMetrics {
+        &self.metrics
+    }
+
+    /// Move waiting sequences into free slots while their prompt plus
+    /// completion fits the KV cache. Queues are served in order so a large
+    /// request is not starved by smaller ones behind it.
+    fn admit(&mut self) {
+        'queues: for queue in self.waiting.iter_mut() {
+            while let Some(next) = queue.front() {
+                // A sequence larger than the cache may still run on its own
+                let reserve = (next.prompt.len() + next.max_tokens).min(self.kv_capacity);
+                if self.free_seq_ids.is_empty() || self.kv_reserved + reserve > self.kv_capacity {
+                    break 'queues;
+                }
+                let next = queue.pop_front().unwrap();
+                let seq_id = self.free_seq_ids.pop().unwrap();
+                self.kv_reserved += reserve;
+                self.active.push(ActiveSequence {
+                    seq_id,
+                    sequence: next.sequence,
+                    prompt: next.prompt,
+                    max_tokens: next.max_tokens,
+                    n_past: 0,
+                    last_token: None,
+                    generated: 0,
+                    kv_reserved: reserve,
+                    finished: None,
+                });
+                self.metrics.sequences_admitted += 1;
+            }
+        }
+        self.metrics.peak_active = self.metrics.peak_active.max(self.active.len());
+    }
+
+    /// One decode token per generating sequence, then prompt chunks for
+    /// prefilling sequences within the step budget
+    fn plan_step(&self) -> Vec<StepInput> {
+        let mut inputs: Vec<StepInput> = self
+            .active
+            .iter()
+            .filter(|seq| !seq.prefilling())
+            .filter_map(|seq| {
+                seq.last_token.map(|token| StepInput {
+                    seq_id: seq.seq_id,
+                    tokens: vec![token],
+                    start_pos: seq.n_past,
+                    sample: true,
+                })
+            })
+            .collect();
+
+        // Always make progress, even with a budget smaller than the batch
+        let mut budget = self.config.step_token_budget.saturating_sub(inputs.len());
+        if inputs.is_empty() {
+            budget = budget.max(1);
+        }
+        for seq in self.active.iter().filter(|seq| seq.prefilling()) {
+            if budget == 0 {
+                break;
+            }
+            let end = (seq.n_past + budget).min(seq.prompt.len());
+            inputs.push(StepInput {
+                seq_id: seq.seq_id,
+                tokens: seq.prompt[seq.n_past..end].to_vec(),
+                start_pos: seq.n_past,
+                sample: end == seq.prompt.len() && seq.max_tokens > 0,
+            });
+            budget -= end - seq.n_past;
+        }
+        inputs
+    }
+
+    /// Admit waiting sequences, decode one step and evict every sequence
+    /// that finished
+    pub fn step<B>(&mut self, backend: &mut B) -> Result<Vec<FinishedSequence<S>>>
+    where
+        B: BatchBackend<Sequence = S>,
+    {
+        for seq in self.active.iter_mut() {
+            seq.finished = backend.should_abort(&seq.sequence);
+        }
+        let mut finished = self.evict_finished(backend);
+
+        self.admit();
+        let inputs = self.plan_step();
+        if inputs.is_empty() {
+            return Ok(finished);
+        }
+        backend.decode(&inputs)?;
+        self.metrics.steps += 1;
+
+        let mut logits_index = 0i32;
+        for input in &inputs {
+            logits_index += input.tokens.len() as i32;
+            let seq = self
+                .active
+                .iter_mut()
+                .find(|seq| seq.seq_id == input.seq_id)
+                .ok_or_else(|| anyhow!("Sequence {} left the batch mid-step", input.seq_id))?;
+            seq.n_past += input.tokens.len();
+
+            if !input.sample {
+                if !seq.prefilling() && seq.max_tokens == 0 {
+                    seq.finished = Some(FinishReason::Length);
+                }
+                continue;
+            }
+
+            let token = backend.sample(&mut seq.sequence, logits_index - 1);
+            seq.generated += 1;
+            seq.last_token = Some(token);
+            self.metrics.tokens_decoded += 1;
+            seq.finished = backend
+                .accept(&mut seq.sequence, token)
+                .or((seq.generated >= seq.max_tokens).then_some(FinishReason::Length));
+        }
+
+        finished.extend(self.evict_finished(backend));
+        Ok(finished)
+    }
+
+    fn evict_finished<B>(&mut self, backend: &mut B) -> Vec<FinishedSequence<S>>
+    where
+        B: BatchBackend<Sequence = S>,
+    {
+        let mut finished = Vec::new();
+        let mut i = 0;
+        while i < self.active.len() {
+            let Some(reason) = self.active[i].finished else {
+                i += 1;
+                continue;
+            };
+            let seq = self.active.remove(i);
+            backend.release(seq.seq_id);
+            self.free_seq_ids.push(seq.seq_id);
+            self.kv_reserved -= seq.kv_reserved;
+            self.metrics.sequences_finished += 1;
+            finished.push(FinishedSequence {
+                sequence: seq.sequence,
+                reason,
+                prompt_tokens: seq.prompt.len(),
+                tokens_generated: seq.generated,
+            });
+        }
+        finished
+    }
+
+    /// Remove every sequence, e.g. after a decode failure
+    pub fn drain<B>(&mut self, backend: &mut B) -> Vec<S>
+    where
+        B: BatchBackend<Sequence = S>,
+    {
+        let mut sequences = Vec::new();
+        for seq in self.active.drain(..) {
+            backend.release(seq.seq_id);
+            self.free_seq_ids.push(seq.seq_id);
+            sequences.push(seq.sequence);
+        }
+        self.kv_reserved = 0;
+        for queue in self.waiting.iter_mut() {
+            sequences.extend(queue.drain(..).map(|waiting| waiting.sequence));
+        }
+        sequences
+    }
+}
+
+/// A request handed to a model's batching worker
+pub(crate) struct BatchedJob {
+    pub(crate) request: InferenceRequest,
+    pub(crate) start_time: Instant,
+    pub(crate) deadline: Option<Instant>,
+    pub(crate) reply: oneshot::Sender<Result<(InferenceRequest, GenerationOutcome)>>,
+}
+
+/// Generation state of one request inside the batch
+struct EngineSequence {
+    job: BatchedJob,
+    sampler: LlamaSampler,
+    /// Constrained requests never reset, since that would rewind the grammar
+    sampler_reset_done: bool,
+    stop_matcher: StopSequenceMatcher,
+    output: String,
+    /// Decoded tokens held back while they could start a stop sequence
+    pending_tokens: VecDeque<TokenInfo>,
+    emitted_len: usize,
+    token_info_list: Vec<TokenInfo>,
+    tokens_generated: usize,
+}
+
+impl EngineSequence {
+    /// Append decoded text, streaming whatever can no longer be part of a
+    /// stop sequence
+    fn push_text(&mut self, token_id: i32, text: String) -> Option<FinishReason> {
+        self.output.push_str(&text);
+
+        // Thinking tokens would otherwise dominate the penalty window
+        if !self.sampler_reset_done
+            && (self.output.contains("</think>") || self.output.contains("</thought>"))
+        {
+            self.sampler.reset();
+            self.sampler_reset_done = true;
+        }
+
+        self.pending_tokens.push_back(TokenInfo {
+            token_id,
+            text,
+            logprob: None,
+            timestamp: None,
+            top_logprobs: Vec::new(),
+        });
+
+        if let Some(match_pos) = self.stop_matcher.find_match(&self.output, self.emitted_len) {
+            self.output.truncate(match_pos);
+            while let Some(mut token_info) = self.pending_tokens.pop_front() {
+                if self.emitted_len >= match_pos {
+                    break;
+                }
+                let remaining = match_pos - self.emitted_len;
+                if token_info.text.len() > remaining {
+                    token_info.text.truncate(remaining);
+                }
+                self.emitted_len += token_info.text.len();
+                emit_token(
+                    &self.job.request.token_sender,
+                    &mut self.token_info_list,
+                    token_info,
+                );
+            }
+            self.pending_tokens.clear();
+            return Some(FinishReason::StopSequence);
+        }
+
+        let safe_len = self.output.len() - self.stop_matcher.holdback_len(&self.output);
+        while let Some(token_info) = self.pending_tokens.front() {
+            if self.emitted_len + token_info.text.len() > safe_len {
+                break;
+            }
+            let token_info = self.pending_tokens.pop_front().unwrap();
+            self.emitted_len += token_info.text.len();
+            emit_token(
+                &self.job.request.token_sender,
+                &mut self.token_info_list,
+                token_info,
+            );
+        }
+        None
+    }
+
+    fn finish(mut self, reason: FinishReason, context_size: usize, prompt_tokens: usize) {
+        while let Some(token_info) = self.pending_tokens.pop_front() {
+            emit_token(
+                &self.job.request.token_sender,
+                &mut self.token_info_list,
+                token_info,
+            );
+        }
+
+        tracing::info!(
+            "🏁 Batched generation ended: tokens_generated={}, output_chars={}, stop_reason={}",
+            self.tokens_generated,
+            self.output.len(),
+            reason.stop_reason()
+        );
+
+        let outcome = GenerationOutcome {
+            output: self.output,
+            tokens_generated: self.tokens_generated,
+            generation_time: self.job.start_time.elapsed(),
+            token_info_list: self.token_info_list,
+            stop_reason: reason.stop_reason(),
+            prompt_tokens,
+            late_context_tokens: 0,
+            context_size,
+            speculative: None,
+        };
+        let _ = self.job.reply.send(Ok((self.job.request, outcome)));
+    }
+}
+
+struct LlamaBatchBackend<'a> {
+    model: &'a LlamaModel,
+    /// The model's decoding lock, taken for each step
+    decoding: &'a Mutex<()>,
+    context: LlamaContext<'a>,
+    batch: LlamaBatch,
+    eos: LlamaToken,
+    stop_ids: Vec<LlamaToken>,
+}
+
+impl BatchBackend for LlamaBatchBackend<'_> {
+    type Sequence = EngineSequence;
+
+    fn decode(&mut self, inputs: &[StepInput]) -> Result<()> {
+        let _decoding = self.decoding.lock().unwrap();
+        self.batch.clear();
+        for input in inputs {
+            let last = input.tokens.len() - 1;
+            for (i, &token) in input.tokens.iter().enumerate() {
+                self.batch
+                    .add(
+                        LlamaToken::new(token),
+                        (input.start_pos + i) as i32,
+                        &[input.seq_id],
+                        input.sample && i == last,
+                    )
+                    .map_err(|e| anyhow!("Failed to add token to batch: {:?}", e))?;
+            }
+        }
+        self.context
+            .decode(&mut self.batch)
+            .map_err(|e| anyhow!("Batched decode failed: {:?}", e))
+    }
+
+    fn sample(&mut self, sequence: &mut EngineSequence, logits_index: i32) -> i32 {
+        sequence.sampler.sample(&self.context, logits_index).0
+    }
+
+    fn accept(&mut self, sequence: &mut EngineSequence, token: i32) -> Option<FinishReason> {
+        let token = LlamaToken::new(token);
+        if token == self.eos {
+            return Some(FinishReason::EosToken);
+        }
+        if self.stop_ids.contains(&token) {
+            return Some(FinishReason::StopToken);
+        }
+        sequence.tokens_generated += 1;
+
+        // Invalid UTF-8 still advances the sequence but is never streamed
+        match self.model.token_to_str(token, Special::Tokenize) {
+            Ok(text) => sequence.push_text(token.0, normalize_thought_token(&text).to_string()),
+            Err(_) => {
+                tracing::warn!(
+                    token_id = token.0,
+                    "Invalid UTF-8 token detected - this may indicate chat template mismatch"
+                );
+                None
+            }
+        }
+    }
+
+    fn should_abort(&self, sequence: &EngineSequence) -> Option<FinishReason> {
+        if let Some(ref flag) = sequence.job.request.cancel_flag {
+            if flag.load(Ordering::Acquire) {
+                return Some(FinishReason::Cancelled);
+            }
+        }
+        if sequence
+            .job
+            .deadline
+            .map_or(false, |deadline| Instant::now() >= deadline)
+        {
+            return Some(FinishReason::Timeout);
+        }
+        None
+    }
+
+    fn release(&mut self, seq_id: i32) {
+        if let Err(e) = self
+            .context
+            .clear_kv_cache_seq(Some(seq_id as u32), None, None)
+        {
+            tracing::warn!("Failed to clear KV cache of sequence {}: {:?}", seq_id, e);
+        }
+    }
+}
+
+type SharedModels = Arc<Mutex<HashMap<String, Arc<RealLlamaModel>>>>;
+
+/// Start the batching worker for `model_id` and return its job queue. The
+/// worker sleeps while idle and exits once the queue is dropped.
+pub(crate) fn spawn_batch_worker(
+    models: SharedModels,
+    model_id: String,
+    engine_config: EngineConfig,
+    config: ContinuousBatchConfig,
+) -> Result<mpsc::Sender<BatchedJob>> {
+    let (sender, jobs) = mpsc::channel();
+    std::thread::Builder::new()
+        .name(format!("batch-{}", model_id))
+        .spawn(move || {
+            while let Ok(first) = jobs.recv() {
+                // The registry is only locked for the lookup; the model's
+                // decoding lock is taken per step
+                let model = models.lock().unwrap().get(&model_id).cloned();
+                match model {
+                    Some(model) => run_batch(&model, &engine_config, &config, first, &jobs),
+                    None => {
+                        let _ = first
+                            .reply
+                            .send(Err(anyhow!("Model {} is not loaded in memory", model_id)));
+                    }
+                }
+            }
+            tracing::info!("Continuous batching worker for {} stopped", model_id);
+        })
+        .map_err(|e| anyhow!("Failed to start continuous batching worker: {}", e))?;
+    Ok(sender)
+}
+
+/// Decode until no sequence is active or waiting, taking new jobs between steps
+fn run_batch(
+    model: &RealLlamaModel,
+    engine_config: &EngineConfig,
+    config: &ContinuousBatchConfig,
+    first: BatchedJob,
+    jobs: &mpsc::Receiver<BatchedJob>,
+) {
+    let max_active = config.max_active_sequences.max(1);
+    let kv_capacity = config.kv_cache_tokens.unwrap_or(model.context_size);
+    let step_budget = config
+        .step_token_budget
+        .min(engine_config.batch_size)
+        .max(1);
+    let batch_capacity = step_budget.max(max_active);
+
+    let params = context_params(engine_config, kv_capacity)
+        .with_n_batch(engine_config.batch_size.max(batch_capacity) as u32)
+        .with_n_seq_max(max_active as u32);
+    let context = match model.model.new_context(&model.backend, params) {
+        Ok(context) => context,
+        Err(e) => {
+            let _ = first
+                .reply
+                .send(Err(anyhow!("Failed to create context: {:?}", e)));
+            return;
+        }
+    };
+
+    let mut backend = LlamaBatchBackend {
+        model: &model.model,
+        decoding: &model.decoding,
+        context,
+        batch: LlamaBatch::new(batch_capacity, 1),
+        eos: model.model.token_eos(),
+        stop_ids: resolve_stop_token_ids(&model.model),
+    };
+    let mut scheduler = ContinuousScheduler::new(
+        ContinuousBatchConfig {
+            max_active_sequences: max_active,
+            step_token_budget: step_budget,
+            ..config.clone()
+        },
+        kv_capacity,
+    );
+    let result = drive_batch(
+        &mut scheduler,
+        &mut backend,
+        first,
+        jobs,
+        |job| match prepare_sequence(model, kv_capacity, job) {
+            Ok(prepared) => Some(prepared),
+            Err((job, e)) => {
+                let _ = job.reply.send(Err(e));
+                None
+            }
+        },
+        |done| {
+            done.sequence
+                .finish(done.reason, model.context_size, done.prompt_tokens)
+        },
+    );
+    if let Err(e) = result {
+        tracing::error!("Continuous batch step failed: {}", e);
+        for sequence in scheduler.drain(&mut backend) {
+            let _ = sequence.job.reply.send(Err(anyhow!("{}", e)));
+        }
+    }
+
+    let metrics = scheduler.metrics();
+    tracing::debug!(
+        "Continuous batch drained: steps={}, admitted={}, tokens={}, peak_active={}",
+        metrics.steps,
+        metrics.sequences_admitted,
+        metrics.tokens_decoded,
+        metrics.peak_active
+    );
+}
+
+/// Step `scheduler` until no sequence is active or waiting. Jobs arriving on
+/// `jobs` are admitted before every step, so they join the running batch;
+/// `admit` returns `None` for jobs it rejected (and replied to) itself.
+fn drive_batch<B, J>(
+    scheduler: &mut ContinuousScheduler<B::Sequence>,
+    backend: &mut B,
+    first: J,
+    jobs: &mpsc::Receiver<J>,
+    mut admit: impl FnMut(J) -> Option<(B::Sequence, Vec<i32>, usize)>,
+    mut finished: impl FnMut(FinishedSequence<B::Sequence>),
+) -> Result<()>
+where
+    B: BatchBackend,
+{
+    let mut incoming = Some(first);
+    loop {
+        for job in incoming.take().into_iter().chain(jobs.try_iter()) {
+            if let Some((sequence, prompt, max_tokens)) = admit(job) {
+                scheduler.submit(sequence, prompt, max_tokens, BatchPriority::Normal);
+            }
+        }
+        if scheduler.is_idle() {
+            return Ok(());
+        }
+        for done in scheduler.step(backend)? {
+            finished(done);
+        }
+    }
+}
+
+/// Tokenize a job's prompt and build its sampler; failures are returned with
+/// the job so the caller can reply
+#[allow(clippy::result_large_err)]
+fn prepare_sequence(
+    model: &RealLlamaModel,
+    kv_capacity: usize,
+    job: BatchedJob,
+) -> std::result::Result<(EngineSequence, Vec<i32>, usize), (BatchedJob, anyhow::Error)> {
+    let request = &job.request;
+    let prepared = (|| -> Result<(Vec<i32>, LlamaSampler)> {
+        let grammar = match request.response_format {
+            Some(ref format) => format
+                .to_gbnf()
+                .map_err(|e| anyhow!("Invalid response_format: {}", e))?,
+            None => None,
+        };
+        let n_vocab = model.model.n_vocab();
+        let logit_biases = validate_logit_bias(&request.logit_bias, n_vocab as u32)?;
+
+        let prompt = model
+            .model
+            .str_to_token(
+                &sanitize_prompt_for_tokenizer(&request.prompt),
+                AddBos::Always,
+            )
+            .map_err(|e| anyhow!("Failed to tokenize: {:?}", e))?;
+        let window = model.context_size.min(kv_capacity);
+        if prompt.len() >= window {
+            return Err(anyhow!(
+                "Prompt ({} tokens) exceeds context window ({} tokens) by {} tokens",
+                prompt.len(),
+                window,
+                prompt.len() - window
+            ));
+        }
+
+        let sampler = build_sampler(
+            &model.model,
+            request,
+            grammar.as_deref(),
+            &logit_biases,
+            n_vocab,
+        )?;
+        Ok((prompt.iter().map(|t| t.0).collect(), sampler))
+    })();
+
+    match prepared {
+        Ok((prompt, sampler)) => {
+            let max_tokens = job.request.max_tokens;
+            let sequence = EngineSequence {
+                sampler_reset_done: job.request.response_format.is_some(),
+                stop_matcher: StopSequenceMatcher::new(&job.request.stop_sequences),
+                job,
+                sampler,
+                output: String::new(),
+                pending_tokens: VecDeque::new(),
+                emitted_len: 0,
+                token_info_list: Vec::new(),
+                tokens_generated: 0,
+            };
+            Ok((sequence, prompt, max_tokens))
+        }
+        Err(e) => Err((job, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockSequence {
+        name: &'static str,
+        eos_after: Option<usize>,
+        tokens: Vec<i32>,
+        cancelled: bool,
+    }
+
+    fn seq(name: &'static str, eos_after: Option<usize>) -> MockSequence {
+        MockSequence {
+            name,
+            eos_after,
+            tokens: Vec::new(),
+            cancelled: false,
+        }
+    }
+
+    type MockJob = (MockSequence, Vec<i32>, usize);
+
+    #[derive(Default)]
+    struct MockBackend {
+        steps: Vec<Vec<StepInput>>,
+        released: Vec<i32>,
+        /// Queued on the channel once the backend has decoded this many steps
+        arrivals: Vec<(usize, MockJob)>,
+        jobs: Option<mpsc::Sender<MockJob>>,
+    }
+
+    impl BatchBackend for MockBackend {
+        type Sequence = MockSequence;
+
+        fn decode(&mut self, inputs: &[StepInput]) -> Result<()> {
+            self.steps.push(inputs.to_vec());
+            let step = self.steps.len();
+            while let Some(index) = self.arrivals.iter().position(|(at, _)| *at == step) {
+                let (_, job) = self.arrivals.remove(index);
+                self.jobs.as_ref().unwrap().send(job).unwrap();
+            }
+            Ok(())
+        }
+
+        fn sample(&mut self, sequence: &mut MockSequence, _logits_index: i32) -> i32 {
+            100 + sequence.tokens.len() as i32
+        }
+
+        fn accept(&mut self, sequence: &mut MockSequence, token: i32) -> Option<FinishReason> {
+            sequence.tokens.push(token);
+            (sequence.eos_after == Some(sequence.tokens.len())).then_some(FinishReason::EosToken)
+        }
+
+        fn should_abort(&self, sequence: &MockSequence) -> Option<FinishReason> {
+            sequence.cancelled.then_some(FinishReason::Cancelled)
+        }
+
+        fn release(&mut self, seq_id: i32) {
+            self.released.push(seq_id);
+        }
+    }
+
+    fn config(max_active: usize, budget: usize) -> ContinuousBatchConfig {
+        ContinuousBatchConfig {
+            max_active_sequences: max_active,
+            step_token_budget: budget,
+            kv_cache_tokens: None,
+        }
+    }
+
+    fn run_to_idle(
+        scheduler: &mut ContinuousScheduler<MockSequence>,
+        backend: &mut MockBackend,
+    ) -> Vec<FinishedSequence<MockSequence>> {
+        let mut finished = Vec::new();
+        while !scheduler.is_idle() {
+            finished.extend(scheduler.step(backend).unwrap());
+        }
+        finished
+    }
+
+    #[test]
+    fn test_short_sequence_leaves_without_waiting() {
+        let mut scheduler = ContinuousScheduler::new(config(4, 64), 1024);
+        let mut backend = MockBackend::default();
+        scheduler.submit(seq("long", None), vec![1, 2, 3], 6, BatchPriority::Normal);
+        scheduler.submit(seq("short", Some(2)), vec![4, 5], 6, BatchPriority::Normal);
+
+        let finished = run_to_idle(&mut scheduler, &mut backend);
+        let order: Vec<_> = finished.iter().map(|f| f.sequence.name).collect();
+        assert_eq!(order, vec!["short", "long"]);
+        assert_eq!(finished[0].reason, FinishReason::EosToken);
+        assert_eq!(finished[1].reason, FinishReason::Length);
+        assert_eq!(finished[1].tokens_generated, 6);
+
+        // Both prompts prefill in the first step; the short one is evicted
+        // after its second token while the long one keeps decoding alone
+        assert_eq!(backend.steps[0].len(), 2);
+        assert_eq!(backend.steps[1].len(), 2);
+        assert!(backend.steps[2..].iter().all(|step| step.len() == 1));
+        assert_eq!(backend.released.len(), 2);
+    }
+
+    #[test]
+    fn test_new_sequence_joins_at_next_step() {
+        let mut scheduler = ContinuousScheduler::new(config(4, 64), 1024);
+        let mut backend = MockBackend::default();
+        scheduler.submit(seq("a", None), vec![1, 2], 5, BatchPriority::Normal);
+        scheduler.step(&mut backend).unwrap();
+        scheduler.step(&mut backend).unwrap();
+
+        scheduler.submit(seq("b", None), vec![7, 8, 9], 1, BatchPriority::Normal);
+        scheduler.step(&mut backend).unwrap();
+
+        let step = backend.steps.last().unwrap();
+        assert_eq!(step.len(), 2);
+        // Decode tokens come before prefill chunks
+        assert_eq!(step[0].tokens, vec![101]);
+        assert_eq!(step[0].start_pos, 3);
+        assert_eq!(step[1].tokens, vec![7, 8, 9]);
+        assert_eq!(step[1].start_pos, 0);
+        assert!(step[1].sample);
+    }
+
+    #[test]
+    fn test_request_joins_running_batch() {
+        let (sender, jobs) = mpsc::channel();
+        let mut scheduler = ContinuousScheduler::new(config(4, 64), 1024);
+        let mut backend = MockBackend {
+            arrivals: vec![(3, (seq("late", None), vec![7, 8], 2))],
+            jobs: Some(sender),
+            ..Default::default()
+        };
+
+        let mut finished = Vec::new();
+        drive_batch(
+            &mut scheduler,
+            &mut backend,
+            (seq("first", None), vec![1, 2, 3], 8),
+            &jobs,
+            Some,
+            |done| finished.push((done.sequence.name, done.tokens_generated)),
+        )
+        .unwrap();
+
+        // The late request arrived while "first" was decoding, joined the
+        // next step and finished without waiting for "first"
+        assert_eq!(finished, vec![("late", 2), ("first", 8)]);
+        assert_eq!(backend.steps[2].len(), 1);
+        assert_eq!(backend.steps[3].len(), 2);
+        assert_eq!(backend.steps[3][1].tokens, vec![7, 8]);
+        assert!(backend.steps[5..].iter().all(|step| step.len() == 1));
+    }
+
+    #[test]
+    fn test_prefill_chunked_to_step_budget() {
+        let mut scheduler = ContinuousScheduler::new(config(2, 4), 1024);
+        let mut backend = MockBackend::default();
+        scheduler.submit(seq("a", None), (0..10).collect(), 1, BatchPriority::Normal);
+
+        run_to_idle(&mut scheduler, &mut backend);
+        let chunks: Vec<_> = backend
+            .steps
+            .iter()
+            .map(|step| (step[0].start_pos, step[0].tokens.len(), step[0].sample))
+            .collect();
+        assert_eq!(chunks, vec![(0, 4, false), (4, 4, false), (8, 2, true)]);
+    }
+
+    #[test]
+    fn test_kv_capacity_limits_admission() {
+        let mut scheduler = ContinuousScheduler::new(config(4, 64), 20);
+        let mut backend = MockBackend::default();
+        scheduler.submit(seq("a", None), vec![1; 5], 10, BatchPriority::Normal);
+        scheduler.submit(seq("b", None), vec![2; 5], 10, BatchPriority::Normal);
+
+        scheduler.step(&mut backend).unwrap();
+        assert_eq!(scheduler.active_len(), 1);
+        assert_eq!(scheduler.waiting_len(), 1);
+
+        let finished = run_to_idle(&mut scheduler, &mut backend);
+        assert_eq!(finished.len(), 2);
+        assert_eq!(scheduler.metrics().peak_active, 1);
+    }
+
+    #[test]
+    fn test_slots_and_priority() {
+        let mut scheduler = ContinuousScheduler::new(config(1, 64), 1024);
+        let mut backend = MockBackend::default();
+        scheduler.submit(seq("normal", None), vec![1], 1, BatchPriority::Normal);
+        scheduler.submit(seq("critical", None), vec![2], 1, BatchPriority::Critical);
+
+        let finished = run_to_idle(&mut scheduler, &mut backend);
+        let order: Vec<_> = finished.iter().map(|f| f.sequence.name).collect();
+        assert_eq!(order, vec!["critical", "normal"]);
+        // The single slot is reused
+        assert_eq!(backend.released, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_cancelled_sequence_evicted_before_decode() {
+        let mut scheduler = ContinuousScheduler::new(config(2, 64), 1024);
+        let mut backend = MockBackend::default();
+        scheduler.submit(seq("a", None), vec![1, 2], 10, BatchPriority::Normal);
+        scheduler.step(&mut backend).unwrap();
+
+        scheduler.active[0].sequence.cancelled = true;
+        let finished = scheduler.step(&mut backend).unwrap();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].reason, FinishReason::Cancelled);
+        assert_eq!(finished[0].tokens_generated, 1);
+        assert_eq!(backend.steps.len(), 1);
+        assert!(scheduler.is_idle());
+    }
+
+    #[test]
+    fn test_config_from_batch_config() {
+        let batch = BatchConfig {
+            max_batch_size: 8,
+            ..Default::default()
+        };
+        let config = ContinuousBatchConfig::from(&batch);
+        assert_eq!(config.max_active_sequences, 8);
+        assert_eq!(config.step_token_budget, 512);
+    }
+}
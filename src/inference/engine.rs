@@ -1,16 +1,29 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
+use crate::host::concurrency::AdaptiveConcurrency;
+use crate::inference::cache::{PrefixCache, PrefixCacheConfig, PrefixCacheStats};
+use crate::inference::continuous_batching::{
+    spawn_batch_worker, BatchedJob, ContinuousBatchConfig,
+};
+use crate::inference::grammar::{ResponseFormat, GRAMMAR_ROOT};
+use crate::inference::speculative::{
+    DraftModelConfig, SpeculativeDecoder, SpeculativeStats, DEFAULT_DRAFT_TOKENS,
+};
 use anyhow::{anyhow, Result};
 use futures::FutureExt;
 use llama_cpp_2::{
-    context::params::{KvCacheType, LlamaContextParams},
+    context::{
+        params::{KvCacheType, LlamaContextParams},
+        LlamaContext,
+    },
     llama_backend::LlamaBackend,
     llama_batch::LlamaBatch,
     model::{params::LlamaModelParams, AddBos, LlamaModel, Special},
     sampling::LlamaSampler,
+    token::{logit_bias::LlamaLogitBias, LlamaToken},
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -28,7 +41,7 @@ use uuid::Uuid;
 ///
 /// This is necessary when prompt content comes from PDFs or other binary sources
 /// that may contain embedded null bytes or invalid Unicode.
-fn sanitize_prompt_for_tokenizer(prompt: &str) -> String {
+pub(crate) fn sanitize_prompt_for_tokenizer(prompt: &str) -> String {
     prompt
         .chars()
         .filter(|c| {
@@ -42,7 +55,7 @@ fn sanitize_prompt_for_tokenizer(prompt: &str) -> String {
 
 /// v8.21.2: Normalize `<thought>` → `<think>` for consistent thinking tags.
 /// GLM-4 emits `<thought>` (special token) but `</think>` (text), creating a mismatch.
-fn normalize_thought_token(token: &str) -> &str {
+pub(crate) fn normalize_thought_token(token: &str) -> &str {
     if token == "<thought>" {
         "<think>"
     } else if token == "</thought>" {
@@ -52,6 +65,308 @@ fn normalize_thought_token(token: &str) -> &str {
     }
 }
 
+/// Matches user-supplied stop sequences against generated text.
+///
+/// Stop strings may span token boundaries (e.g. `"\n\n"` decoded as two `"\n"`
+/// tokens), so the engine holds back any output tail that could still grow into
+/// a stop sequence and only emits it once the match is ruled out.
+pub(crate) struct StopSequenceMatcher {
+    sequences: Vec<String>,
+}
+
+impl StopSequenceMatcher {
+    pub(crate) fn new(sequences: &[String]) -> Self {
+        Self {
+            sequences: sequences
+                .iter()
+                .filter(|s| !s.is_empty())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Byte offset of the earliest stop sequence in `text[from..]`, if any.
+    pub(crate) fn find_match(&self, text: &str, from: usize) -> Option<usize> {
+        let haystack = &text[from..];
+        self.sequences
+            .iter()
+            .filter_map(|seq| haystack.find(seq.as_str()))
+            .min()
+            .map(|pos| from + pos)
+    }
+
+    /// Length of the longest suffix of `text` that is a proper prefix of a stop
+    /// sequence. These bytes must not be emitted yet.
+    pub(crate) fn holdback_len(&self, text: &str) -> usize {
+        let mut longest = 0;
+        for seq in &self.sequences {
+            for (idx, _) in text.char_indices().rev() {
+                let suffix = &text[idx..];
+                if suffix.len() >= seq.len() {
+                    break;
+                }
+                if seq.starts_with(suffix) && suffix.len() > longest {
+                    longest = suffix.len();
+                }
+            }
+        }
+        longest
+    }
+}
+
+/// Maximum absolute logit bias; -100 effectively bans a token, +100 forces it
+pub const LOGIT_BIAS_LIMIT: f32 = 100.0;
+
+/// Validate logit bias token IDs against the model vocabulary and clamp the
+/// bias values to ±LOGIT_BIAS_LIMIT. Entries are sorted by token ID so the
+/// sampler is built deterministically.
+pub fn validate_logit_bias(
+    logit_bias: &HashMap<u32, f32>,
+    n_vocab: u32,
+) -> Result<Vec<(u32, f32)>> {
+    let mut biases = Vec::with_capacity(logit_bias.len());
+    for (&token_id, &bias) in logit_bias {
+        if token_id >= n_vocab {
+            return Err(anyhow!(
+                "Invalid logit_bias token id {} (model vocab size is {})",
+                token_id,
+                n_vocab
+            ));
+        }
+        if !bias.is_finite() {
+            return Err(anyhow!(
+                "Invalid logit_bias value for token {}: must be a finite number",
+                token_id
+            ));
+        }
+        biases.push((token_id, bias.clamp(-LOGIT_BIAS_LIMIT, LOGIT_BIAS_LIMIT)));
+    }
+    biases.sort_by_key(|(token_id, _)| *token_id);
+    Ok(biases)
+}
+
+/// Log-softmax over one step's raw logits. Returns the chosen token's logprob
+/// and the `top_n` most likely (token id, logprob) pairs, most likely first.
+fn token_logprobs(logits: &[f32], chosen: i32, top_n: usize) -> (f32, Vec<(i32, f32)>) {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let log_norm = max + logits.iter().map(|&l| (l - max).exp()).sum::<f32>().ln();
+    let chosen_logprob = usize::try_from(chosen)
+        .ok()
+        .and_then(|i| logits.get(i))
+        .map_or(f32::NEG_INFINITY, |&l| l - log_norm);
+
+    let top_n = top_n.min(logits.len());
+    if top_n == 0 {
+        return (chosen_logprob, Vec::new());
+    }
+    let by_logit_desc = |a: &usize, b: &usize| logits[*b].total_cmp(&logits[*a]);
+    let mut ids: Vec<usize> = (0..logits.len()).collect();
+    if top_n < ids.len() {
+        ids.select_nth_unstable_by(top_n - 1, by_logit_desc);
+        ids.truncate(top_n);
+    }
+    ids.sort_unstable_by(by_logit_desc);
+
+    let top = ids
+        .into_iter()
+        .map(|i| (i as i32, logits[i] - log_norm))
+        .collect();
+    (chosen_logprob, top)
+}
+
+/// Send a token to the streaming channel (if any) and record it in the result.
+pub(crate) fn emit_token(
+    sender: &Option<mpsc::Sender<Result<TokenInfo>>>,
+    token_info_list: &mut Vec<TokenInfo>,
+    token_info: TokenInfo,
+) {
+    if let Some(ref tx) = sender {
+        let _ = tx.try_send(Ok(token_info.clone()));
+    }
+    token_info_list.push(token_info);
+}
+
+/// Template named by MODEL_CHAT_TEMPLATE (Harmony if unset or unknown)
+fn configured_chat_template() -> crate::inference::ChatTemplate {
+    let template_name =
+        std::env::var("MODEL_CHAT_TEMPLATE").unwrap_or_else(|_| "harmony".to_string());
+    crate::inference::ChatTemplate::from_str(&template_name)
+        .unwrap_or(crate::inference::ChatTemplate::Harmony)
+}
+
+/// Resolve the template's stop strings (or the MODEL_STOP_TOKENS env
+/// override) to token ids for this model
+pub(crate) fn resolve_stop_token_ids(model: &LlamaModel) -> Vec<LlamaToken> {
+    let template = configured_chat_template();
+
+    let stop_token_strings = {
+        let env_overrides = crate::inference::chat_template::parse_stop_tokens_env();
+        if env_overrides.is_empty() {
+            template
+                .stop_tokens()
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+        } else {
+            env_overrides
+        }
+    };
+
+    let mut stop_ids: Vec<LlamaToken> = Vec::new();
+    for token_str in &stop_token_strings {
+        if let Ok(tokens) = model.str_to_token(token_str, AddBos::Never) {
+            if let Some(&tok) = tokens.first() {
+                stop_ids.push(tok);
+            }
+        }
+    }
+
+    tracing::debug!(
+        "🎯 Stop tokens: eos={}, template={}, strings={:?}, ids={:?}",
+        model.token_eos(),
+        template.as_str(),
+        stop_token_strings,
+        stop_ids.iter().map(|t| t.0).collect::<Vec<_>>()
+    );
+
+    stop_ids
+}
+
+/// Sampler chain for a request:
+/// grammar → logit_bias → temp → penalties → top_p → min_p → dist/greedy
+pub(crate) fn build_sampler(
+    model: &LlamaModel,
+    request: &InferenceRequest,
+    grammar: Option<&str>,
+    logit_biases: &[(u32, f32)],
+    n_vocab: i32,
+) -> Result<LlamaSampler> {
+    let (_, _, _, penalty_last_n) = get_penalty_defaults();
+
+    let mut samplers: Vec<LlamaSampler> = Vec::new();
+    if let Some(grammar) = grammar {
+        // Masks every token the grammar can't accept before anything else sees the logits
+        let grammar_sampler = LlamaSampler::grammar(model, grammar, GRAMMAR_ROOT)
+            .map_err(|e| anyhow!("Invalid response_format grammar: {:?}", e))?;
+        samplers.push(grammar_sampler);
+    }
+    if !logit_biases.is_empty() {
+        let biases: Vec<LlamaLogitBias> = logit_biases
+            .iter()
+            .map(|&(token_id, bias)| LlamaLogitBias::new(LlamaToken::new(token_id as i32), bias))
+            .collect();
+        samplers.push(LlamaSampler::logit_bias(n_vocab, &biases));
+    }
+    samplers.push(LlamaSampler::temp(request.temperature));
+    if request.repeat_penalty != 1.0
+        || request.frequency_penalty != 0.0
+        || request.presence_penalty != 0.0
+    {
+        samplers.push(LlamaSampler::penalties(
+            penalty_last_n,
+            request.repeat_penalty,
+            request.frequency_penalty,
+            request.presence_penalty,
+        ));
+    }
+    samplers.push(LlamaSampler::top_p(request.top_p, 1));
+    if request.min_p > 0.0 {
+        samplers.push(LlamaSampler::min_p(request.min_p, 1));
+    }
+    if request.temperature > 0.0 {
+        samplers.push(LlamaSampler::dist(sampler_seed(request.seed)));
+    } else {
+        samplers.push(LlamaSampler::greedy());
+    }
+    Ok(LlamaSampler::chain_simple(samplers))
+}
+
+/// llama.cpp reads this seed as "pick one at random"
+const LLAMA_RANDOM_SEED: u32 = u32::MAX;
+
+/// RNG seed for the `dist` sampler. 64-bit seeds are folded to 32 bits
+/// (seeds below 2^32 are used as-is), steering clear of the value llama.cpp
+/// would replace with a random seed.
+pub(crate) fn sampler_seed(seed: Option<u64>) -> u32 {
+    let seed = seed.unwrap_or(0);
+    let folded = (seed ^ (seed >> 32)) as u32;
+    folded.min(LLAMA_RANDOM_SEED - 1)
+}
+
+/// Fingerprint of what produced an output: the model, identified by the
+/// digest of its GGUF file (`model_digest`, or its path for a model that isn't
+/// loaded yet), and the engine settings that can change which tokens are
+/// sampled. Seeded (or greedy) requests reproduce the same output only
+/// under the same fingerprint on the same GPU and backend build; kernels and
+/// reduction order differ across hardware.
+pub fn system_fingerprint(
+    model: &ModelConfig,
+    model_digest: Option<&str>,
+    engine: &EngineConfig,
+    batched: bool,
+) -> String {
+    use sha2::{Digest, Sha256};
+
+    let model_hash = match model_digest {
+        Some(digest) => Sha256::digest(digest.as_bytes()),
+        None => Sha256::digest(model.model_path.to_string_lossy().as_bytes()),
+    };
+    let settings = format!(
+        "{}|ctx={}|gpu_layers={}|rope={}:{}|batch={}|threads={}|kv={:?}:{:?}|draft={:?}|batched={}",
+        env!("CARGO_PKG_VERSION"),
+        model.context_size,
+        model.gpu_layers,
+        model.rope_freq_base,
+        model.rope_freq_scale,
+        engine.batch_size,
+        engine.thread_count,
+        engine.kv_cache_type_k,
+        engine.kv_cache_type_v,
+        engine
+            .draft_model
+            .as_ref()
+            .map(|draft| (&draft.model_path, draft.draft_tokens)),
+        batched,
+    );
+    let config_hash = Sha256::digest(settings.as_bytes());
+    format!(
+        "fp_{}_{}",
+        hex::encode(&model_hash[..6]),
+        hex::encode(&config_hash[..6])
+    )
+}
+
+/// Hex SHA-256 of a model file's contents
+fn file_digest(path: &std::path::Path) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Context parameters for a context of `n_ctx` tokens, honouring the
+/// configured batch size and KV cache types
+pub(crate) fn context_params(config: &EngineConfig, n_ctx: usize) -> LlamaContextParams {
+    let mut ctx_params = LlamaContextParams::default()
+        .with_n_ctx(NonZeroU32::new(n_ctx as u32))
+        .with_n_batch(config.batch_size as u32);
+
+    if let Some(ref type_k_str) = config.kv_cache_type_k {
+        if let Some(kv_type) = parse_kv_cache_type(type_k_str) {
+            ctx_params = ctx_params.with_type_k(kv_type);
+            tracing::info!("KV cache K type set to: {}", type_k_str);
+        }
+    }
+    if let Some(ref type_v_str) = config.kv_cache_type_v {
+        if let Some(kv_type) = parse_kv_cache_type(type_v_str) {
+            ctx_params = ctx_params.with_type_v(kv_type);
+            tracing::info!("KV cache V type set to: {}", type_v_str);
+        }
+    }
+    ctx_params
+}
+
 /// Parse a KV cache type string into a KvCacheType enum.
 /// Supports: "q8_0", "q4_0", "f16", "bf16", "f32" (case-insensitive).
 /// Returns None for unrecognized types (will use llama.cpp default = fp16).
@@ -102,12 +417,19 @@ pub fn get_penalty_defaults() -> (f32, f32, f32, i32) {
 }
 
 // Wrapper around the real LLama model
-struct RealLlamaModel {
-    backend: LlamaBackend,
-    model: LlamaModel,
-    context_size: usize,
+pub(crate) struct RealLlamaModel {
+    pub(crate) backend: Arc<LlamaBackend>,
+    pub(crate) model: LlamaModel,
+    pub(crate) context_size: usize,
+    /// Template the model's prompts are rendered with unless a request overrides it
+    pub(crate) chat_template: crate::inference::ChatTemplate,
+    /// Held for a whole per-request generation, or for one continuous
+    /// batching step, so decodes on this model take turns
+    pub(crate) decoding: std::sync::Mutex<()>,
 }
 
+pub const INFERENCE_TIMEOUT_MS_ENV: &str = "INFERENCE_TIMEOUT_MS";
+
 #[derive(Debug, Clone)]
 pub struct EngineConfig {
     pub models_directory: PathBuf,
@@ -122,6 +444,12 @@ pub struct EngineConfig {
     pub model_eviction_policy: String,
     pub kv_cache_type_k: Option<String>,
     pub kv_cache_type_v: Option<String>,
+    /// Default per-request inference timeout in milliseconds (None = no limit).
+    /// Requests can override it with `InferenceRequest::timeout_ms`.
+    pub default_timeout_ms: Option<u64>,
+    /// Small model that drafts tokens for the loaded model to verify
+    /// (speculative decoding). Without one, decoding is one token per pass.
+    pub draft_model: Option<DraftModelConfig>,
 }
 
 impl Default for EngineConfig {
@@ -142,10 +470,22 @@ impl Default for EngineConfig {
             model_eviction_policy: "lru".to_string(),
             kv_cache_type_k: None,
             kv_cache_type_v: None,
+            default_timeout_ms: Self::timeout_from_env(),
+            draft_model: DraftModelConfig::from_env(),
         }
     }
 }
 
+impl EngineConfig {
+    /// `INFERENCE_TIMEOUT_MS`; unset, invalid or 0 means no limit
+    pub fn timeout_from_env() -> Option<u64> {
+        std::env::var(INFERENCE_TIMEOUT_MS_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|&ms| ms > 0)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ModelConfig {
     pub model_path: PathBuf,
@@ -174,7 +514,30 @@ pub struct InferenceRequest {
     /// Min-P sampling threshold (0.0 = disabled, typical: 0.01-0.1)
     pub min_p: f32,
     pub seed: Option<u64>,
+    /// Custom stop strings — generation halts at the first match and the
+    /// matched text is excluded from the output (accepts `stop` or `stop_sequences`)
+    #[serde(default, alias = "stop")]
     pub stop_sequences: Vec<String>,
+    /// Additive logit bias per token ID, applied before sampling.
+    /// Values are clamped to ±LOGIT_BIAS_LIMIT; -100 effectively bans a token.
+    #[serde(default)]
+    pub logit_bias: HashMap<u32, f32>,
+    /// Structured output constraint; JSON formats are compiled to a grammar
+    /// that masks tokens which cannot continue a valid document
+    #[serde(default)]
+    pub response_format: Option<ResponseFormat>,
+    /// Template the prompt was rendered with, when it overrides the model's
+    /// own; decides which leading tokens are shared via the prefix cache
+    #[serde(skip)]
+    pub chat_template: Option<crate::inference::ChatTemplateOverride>,
+    /// Return each generated token's logprob plus this many top alternatives
+    /// (capped at MAX_LOGPROBS); None skips the per-token softmax entirely
+    #[serde(default)]
+    pub logprobs: Option<u32>,
+    /// Wall-clock limit for this request in milliseconds; falls back to
+    /// `EngineConfig::default_timeout_ms` when None
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
     pub stream: bool,
     /// Cancellation flag — set to true to abort generation between tokens
     #[serde(skip)]
@@ -185,6 +548,10 @@ pub struct InferenceRequest {
     /// Result sender — sends the complete InferenceResult after generation (for streaming metadata)
     #[serde(skip)]
     pub result_sender: Option<tokio::sync::oneshot::Sender<InferenceResult>>,
+    /// Late context — text received during the first `LATE_CONTEXT_TOKEN_WINDOW`
+    /// generated tokens is decoded into the context without being emitted
+    #[serde(skip)]
+    pub late_context: Option<LateContext>,
 }
 
 impl Clone for InferenceRequest {
@@ -202,10 +569,16 @@ impl Clone for InferenceRequest {
             min_p: self.min_p,
             seed: self.seed,
             stop_sequences: self.stop_sequences.clone(),
+            logit_bias: self.logit_bias.clone(),
+            response_format: self.response_format.clone(),
+            chat_template: self.chat_template.clone(),
+            logprobs: self.logprobs,
+            timeout_ms: self.timeout_ms,
             stream: self.stream,
             cancel_flag: self.cancel_flag.clone(),
             token_sender: self.token_sender.clone(),
             result_sender: None, // oneshot::Sender is not cloneable
+            late_context: None,  // receivers are not cloneable
         }
     }
 }
@@ -214,6 +587,12 @@ impl Clone for InferenceRequest {
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    /// Calls made by an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<crate::inference::ToolCall>>,
+    /// The call a `tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -227,6 +606,75 @@ pub struct InferenceResult {
     pub token_info: Vec<TokenInfo>,
     pub was_cancelled: bool,
     pub context_usage: Option<ContextUsage>,
+    /// Per-token logprobs, present when the request asked for them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprobs>>,
+    /// Model and engine settings the result was generated with; see
+    /// `system_fingerprint`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+}
+
+/// A request's time limit, counted from when it was submitted
+#[derive(Debug, Clone, Copy)]
+struct Deadline {
+    start: Instant,
+    timeout_ms: Option<u64>,
+}
+
+impl Deadline {
+    fn at(&self) -> Option<Instant> {
+        self.timeout_ms
+            .map(|ms| self.start + Duration::from_millis(ms))
+    }
+
+    fn passed(&self) -> bool {
+        self.at().map_or(false, |at| Instant::now() >= at)
+    }
+
+    /// Timeout of a request that generated nothing before the deadline
+    fn expired(&self, model_id: &str, context_usage: Option<ContextUsage>) -> anyhow::Error {
+        InferenceError::Timeout {
+            timeout_ms: self.timeout_ms.unwrap_or_default(),
+            partial: Box::new(InferenceResult {
+                text: String::new(),
+                tokens_generated: 0,
+                generation_time: self.start.elapsed(),
+                tokens_per_second: 0.0,
+                model_id: model_id.to_string(),
+                finish_reason: "timeout".to_string(),
+                token_info: Vec::new(),
+                was_cancelled: false,
+                logprobs: None,
+                system_fingerprint: None,
+                context_usage,
+            }),
+        }
+        .into()
+    }
+
+    /// Awaits `future` unless the deadline passes first
+    async fn run<F: std::future::Future>(&self, model_id: &str, future: F) -> Result<F::Output> {
+        match self.at() {
+            Some(at) => tokio::time::timeout_at(at.into(), future)
+                .await
+                .map_err(|_| self.expired(model_id, None)),
+            None => Ok(future.await),
+        }
+    }
+}
+
+/// Structured inference failures that callers may need to inspect
+/// (recover with `anyhow::Error::downcast_ref::<InferenceError>()`).
+#[derive(Debug, thiserror::Error)]
+pub enum InferenceError {
+    /// The request exceeded its timeout. `partial` holds the text and token
+    /// count generated before the deadline so the work can still be billed.
+    #[error("Inference timed out after {timeout_ms}ms ({} tokens generated)", partial.tokens_generated)]
+    Timeout {
+        timeout_ms: u64,
+        partial: Box<InferenceResult>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -235,12 +683,106 @@ pub struct TokenInfo {
     pub text: String,
     pub logprob: Option<f32>,
     pub timestamp: Option<f32>,
+    /// Most likely alternatives at this position, most likely first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// Upper bound on the number of alternatives returned per token
+pub const MAX_LOGPROBS: u32 = 20;
+
+/// Late context is accepted only while fewer than this many tokens have been
+/// generated; past that the answer is too far along for new sources to shape it
+pub const LATE_CONTEXT_TOKEN_WINDOW: usize = 32;
+
+/// Sources (e.g. slow search fetches) that arrive after generation started.
+/// Each batch is decoded as a tool message between the partial answer and a
+/// reopened assistant turn, so the model reads it as a source rather than
+/// as its own words.
+#[derive(Debug)]
+pub struct LateContext {
+    receiver: mpsc::UnboundedReceiver<String>,
+    /// Closes the assistant turn and opens the tool message
+    open: String,
+    /// Closes the tool message and reopens the assistant turn
+    close: String,
+}
+
+impl LateContext {
+    /// `frame` comes from the prompt's chat template, e.g.
+    /// `ChatTemplateOverride::late_context_frame`
+    pub fn new(receiver: mpsc::UnboundedReceiver<String>, frame: (String, String)) -> Self {
+        let (open, close) = frame;
+        Self {
+            receiver,
+            open,
+            close,
+        }
+    }
+
+    /// Everything received so far, framed as one tool message, while fewer
+    /// than `LATE_CONTEXT_TOKEN_WINDOW` tokens are generated. Once the window
+    /// has passed the channel is closed so senders stop fetching.
+    fn take(&mut self, generated: usize) -> Option<String> {
+        if generated >= LATE_CONTEXT_TOKEN_WINDOW {
+            self.receiver.close();
+            return None;
+        }
+        let mut sources = String::new();
+        while let Ok(text) = self.receiver.try_recv() {
+            sources.push_str(&text);
+        }
+        (!sources.is_empty()).then(|| format!("{}{}{}", self.open, sources, self.close))
+    }
+}
+
+/// Tokens to decode for a framed late context message. The partial answer is
+/// already in the KV cache, so only the tool message and the reopened
+/// assistant header are decoded (and counted as late context).
+fn tokenize_late_context<F>(framed: &str, tokenize: F) -> Result<Vec<LlamaToken>>
+where
+    F: FnOnce(&str) -> Result<Vec<LlamaToken>>,
+{
+    tokenize(&sanitize_prompt_for_tokenizer(framed))
+}
+
+/// A candidate token and its log-probability under the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopLogprob {
+    pub token_id: i32,
+    pub text: String,
+    pub logprob: f32,
+}
+
+/// Logprob of a generated token together with the top alternatives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprobs {
+    pub token_id: i32,
+    pub text: String,
+    pub logprob: f32,
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+impl TokenLogprobs {
+    /// None when logprobs weren't captured for this token
+    pub fn from_token_info(token: &TokenInfo) -> Option<Self> {
+        Some(Self {
+            token_id: token.token_id,
+            text: token.text.clone(),
+            logprob: token.logprob?,
+            top_logprobs: token.top_logprobs.clone(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextUsage {
     pub prompt_tokens: usize,
     pub completion_tokens: usize,
+    /// Late search context decoded during generation, with its framing;
+    /// counted in `total_tokens` but not in `prompt_tokens`
+    #[serde(default)]
+    pub late_context_tokens: usize,
     pub total_tokens: usize,
     pub context_window_size: usize,
 }
@@ -252,6 +794,125 @@ pub struct Model {
     pub status: ModelStatus,
     pub loaded_at: std::time::SystemTime,
     pub usage_count: usize,
+    /// Last time an inference was routed to this model (drives LRU eviction)
+    pub last_used: std::time::SystemTime,
+    /// GGUF header fields, populated once the model has finished loading
+    pub metadata: Option<GgufMetadata>,
+    /// SHA-256 of the GGUF file, computed once at load
+    pub file_digest: Option<String>,
+}
+
+/// GGUF header fields read from a model file at load time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GgufMetadata {
+    pub architecture: Option<String>,
+    pub name: Option<String>,
+    pub context_length: Option<u64>,
+    pub embedding_length: Option<u64>,
+    pub block_count: Option<u64>,
+    pub rope_freq_base: Option<f32>,
+    pub rope_scaling_type: Option<String>,
+    pub rope_scaling_factor: Option<f32>,
+    /// Quantization derived from `general.file_type` (e.g. "Q4_K_M")
+    pub quantization: Option<String>,
+    /// Chat template the engine formats prompts with
+    pub chat_template: String,
+    /// Whether the file embeds its own `tokenizer.chat_template`
+    pub has_embedded_chat_template: bool,
+    pub n_params: u64,
+    pub n_vocab: u32,
+    pub size_bytes: u64,
+    /// All header key-values as reported by llama.cpp (large arrays are elided)
+    pub kv: BTreeMap<String, String>,
+}
+
+impl GgufMetadata {
+    /// Builds metadata from raw header key-values, resolving architecture-scoped keys
+    pub fn from_kv(kv: BTreeMap<String, String>, chat_template: &str) -> Self {
+        let architecture = kv.get("general.architecture").cloned();
+        let arch_key = |suffix: &str| {
+            architecture
+                .as_ref()
+                .and_then(|arch| kv.get(&format!("{}.{}", arch, suffix)))
+        };
+        let parse_u64 = |v: Option<&String>| v.and_then(|v| v.trim().parse::<u64>().ok());
+        let parse_f32 = |v: Option<&String>| v.and_then(|v| v.trim().parse::<f32>().ok());
+
+        Self {
+            name: kv.get("general.name").cloned(),
+            context_length: parse_u64(arch_key("context_length")),
+            embedding_length: parse_u64(arch_key("embedding_length")),
+            block_count: parse_u64(arch_key("block_count")),
+            rope_freq_base: parse_f32(arch_key("rope.freq_base")),
+            rope_scaling_type: arch_key("rope.scaling.type").cloned(),
+            rope_scaling_factor: parse_f32(arch_key("rope.scaling.factor")),
+            quantization: parse_u64(kv.get("general.file_type"))
+                .map(|ft| gguf_file_type_name(ft as u32)),
+            chat_template: chat_template.to_string(),
+            has_embedded_chat_template: kv.contains_key("tokenizer.chat_template"),
+            architecture,
+            kv,
+            ..Default::default()
+        }
+    }
+}
+
+/// Picks the model to unload under `policy` ("lru", "lfu" or "fifo"; unknown
+/// policies fall back to LRU). Pinned models and models still loading are
+/// never chosen.
+fn select_eviction_candidate<'a>(
+    models: impl Iterator<Item = &'a Model>,
+    pinned: &HashSet<String>,
+    policy: &str,
+) -> Option<String> {
+    let candidates = models.filter(|m| m.status != ModelStatus::Loading && !pinned.contains(&m.id));
+    let victim = match policy.to_lowercase().as_str() {
+        "fifo" => candidates.min_by_key(|m| m.loaded_at),
+        "lfu" => candidates.min_by_key(|m| (m.usage_count, m.last_used)),
+        _ => candidates.min_by_key(|m| m.last_used),
+    };
+    victim.map(|m| m.id.clone())
+}
+
+/// Maps a llama.cpp `llama_ftype` value to its conventional quantization name
+fn gguf_file_type_name(file_type: u32) -> String {
+    let name = match file_type {
+        0 => "F32",
+        1 => "F16",
+        2 => "Q4_0",
+        3 => "Q4_1",
+        7 => "Q8_0",
+        8 => "Q5_0",
+        9 => "Q5_1",
+        10 => "Q2_K",
+        11 => "Q3_K_S",
+        12 => "Q3_K_M",
+        13 => "Q3_K_L",
+        14 => "Q4_K_S",
+        15 => "Q4_K_M",
+        16 => "Q5_K_S",
+        17 => "Q5_K_M",
+        18 => "Q6_K",
+        19 => "IQ2_XXS",
+        20 => "IQ2_XS",
+        21 => "Q2_K_S",
+        22 => "IQ3_XS",
+        23 => "IQ3_XXS",
+        24 => "IQ1_S",
+        25 => "IQ4_NL",
+        26 => "IQ3_S",
+        27 => "IQ3_M",
+        28 => "IQ2_S",
+        29 => "IQ2_M",
+        30 => "IQ4_XS",
+        31 => "IQ1_M",
+        32 => "BF16",
+        36 => "TQ1_0",
+        37 => "TQ2_0",
+        38 => "MXFP4_MOE",
+        other => return format!("unknown({})", other),
+    };
+    name.to_string()
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -275,17 +936,58 @@ pub struct EngineMetrics {
     pub total_tokens_generated: usize,
     pub average_tokens_per_second: f32,
     pub total_inference_time: Duration,
+    /// Tokens proposed by the draft model
+    pub speculative_tokens_drafted: usize,
+    /// Drafted tokens the main model agreed with
+    pub speculative_tokens_accepted: usize,
+    pub speculative_acceptance_rate: f32,
 }
 
 pub type TokenStream = ReceiverStream<Result<TokenInfo>>;
 
+/// What a decode loop produced for one request, before it is turned into an
+/// `InferenceResult`
+pub(crate) struct GenerationOutcome {
+    pub(crate) output: String,
+    pub(crate) tokens_generated: usize,
+    pub(crate) generation_time: Duration,
+    pub(crate) token_info_list: Vec<TokenInfo>,
+    pub(crate) stop_reason: &'static str,
+    pub(crate) prompt_tokens: usize,
+    pub(crate) late_context_tokens: usize,
+    pub(crate) context_size: usize,
+    /// Draft statistics when the generation used speculative decoding
+    pub(crate) speculative: Option<SpeculativeStats>,
+}
+
 #[derive(Clone)]
 pub struct LlmEngine {
     config: EngineConfig,
-    models: Arc<std::sync::Mutex<HashMap<String, RealLlamaModel>>>,
+    /// llama.cpp backend shared by all loaded models (it can only be initialized once)
+    backend: Arc<std::sync::Mutex<Option<Arc<LlamaBackend>>>>,
+    /// Only held to look a model up; decoding holds the model's own lock
+    models: Arc<std::sync::Mutex<HashMap<String, Arc<RealLlamaModel>>>>,
     model_info: Arc<RwLock<HashMap<String, Model>>>,
+    /// Configs of every model that may be (re)loaded on demand, keyed by model id
+    known_models: Arc<RwLock<HashMap<String, ModelConfig>>>,
+    /// Serializes loads so concurrent requests don't load the same model twice
+    load_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Models that eviction must never unload
+    pinned_models: Arc<RwLock<HashSet<String>>>,
+    /// Model that requests fall back to; evicted only when nothing else can be
+    default_model: Arc<RwLock<Option<String>>>,
     inference_count: Arc<RwLock<usize>>,
     metrics: Arc<RwLock<EngineMetrics>>,
+    /// When set, eligible requests share a per-model decode loop
+    continuous_batching: Option<ContinuousBatchConfig>,
+    /// Job queues of the running continuous batching workers, keyed by model id
+    batch_workers: Arc<std::sync::Mutex<HashMap<String, std::sync::mpsc::Sender<BatchedJob>>>>,
+    /// Prefilled prompt state reused by requests sharing a prefix
+    prefix_cache: Option<Arc<std::sync::Mutex<PrefixCache>>>,
+    /// Draft model loaded on first use; `None` inside once loading failed
+    draft_model: Arc<std::sync::OnceLock<Option<Arc<LlamaModel>>>>,
+    /// When set, bounds how many inferences run at once
+    concurrency: Option<Arc<AdaptiveConcurrency>>,
 }
 
 impl LlmEngine {
@@ -295,18 +997,150 @@ impl LlmEngine {
 
         Ok(Self {
             config,
+            backend: Arc::new(std::sync::Mutex::new(None)),
             models: Arc::new(std::sync::Mutex::new(HashMap::new())),
             model_info: Arc::new(RwLock::new(HashMap::new())),
+            known_models: Arc::new(RwLock::new(HashMap::new())),
+            load_lock: Arc::new(tokio::sync::Mutex::new(())),
+            pinned_models: Arc::new(RwLock::new(HashSet::new())),
+            default_model: Arc::new(RwLock::new(None)),
             inference_count: Arc::new(RwLock::new(0)),
             metrics: Arc::new(RwLock::new(EngineMetrics {
                 total_inferences: 0,
                 total_tokens_generated: 0,
                 average_tokens_per_second: 0.0,
                 total_inference_time: Duration::default(),
+                speculative_tokens_drafted: 0,
+                speculative_tokens_accepted: 0,
+                speculative_acceptance_rate: 0.0,
             })),
+            continuous_batching: None,
+            batch_workers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            prefix_cache: None,
+            draft_model: Arc::new(std::sync::OnceLock::new()),
+            concurrency: None,
         })
     }
 
+    /// Decode concurrent requests for the same model in one shared loop:
+    /// new requests join at the next decode step and finished ones leave
+    /// without waiting for the rest of the batch. Requests using late context
+    /// or logprobs keep the dedicated per-request loop.
+    pub fn with_continuous_batching(mut self, config: ContinuousBatchConfig) -> Self {
+        self.continuous_batching = Some(config);
+        self
+    }
+
+    pub fn continuous_batching_config(&self) -> Option<&ContinuousBatchConfig> {
+        self.continuous_batching.as_ref()
+    }
+
+    /// Keep the KV state of prefilled system prompts so later requests
+    /// sharing one skip that part of prefill
+    pub fn with_prefix_cache(mut self, config: PrefixCacheConfig) -> Result<Self> {
+        self.prefix_cache = Some(Arc::new(std::sync::Mutex::new(PrefixCache::new(config)?)));
+        Ok(self)
+    }
+
+    /// Queue inferences beyond a limit that follows resource load and
+    /// per-token latency
+    pub fn with_adaptive_concurrency(mut self, concurrency: Arc<AdaptiveConcurrency>) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    pub fn adaptive_concurrency(&self) -> Option<&Arc<AdaptiveConcurrency>> {
+        self.concurrency.as_ref()
+    }
+
+    pub fn prefix_cache_stats(&self) -> Option<PrefixCacheStats> {
+        self.prefix_cache
+            .as_ref()
+            .map(|cache| cache.lock().unwrap().stats())
+    }
+
+    /// Number of leading prompt tokens covering the template's system
+    /// section, the part of a prompt worth caching across requests
+    fn reusable_prefix_tokens(
+        &self,
+        model: &RealLlamaModel,
+        template: Option<&crate::inference::ChatTemplateOverride>,
+        prompt: &str,
+        tokens: &[LlamaToken],
+    ) -> usize {
+        if self.prefix_cache.is_none() {
+            return 0;
+        }
+        let prefix_len = match template {
+            Some(template) => template.system_prefix_len(prompt),
+            None => model.chat_template.system_prefix_len(prompt),
+        };
+        let Some(len) = prefix_len else {
+            return 0;
+        };
+        let Ok(prefix) = model.model.str_to_token(&prompt[..len], AddBos::Always) else {
+            return 0;
+        };
+        // A token merged across the boundary belongs to the user turn
+        let shared = prefix
+            .iter()
+            .zip(tokens)
+            .take_while(|(a, b)| a == b)
+            .count();
+        shared.min(tokens.len().saturating_sub(1))
+    }
+
+    /// Load the cached state of `prefix` into a fresh context and return how
+    /// many prompt tokens it covers
+    fn restore_prefix(
+        &self,
+        context: &mut LlamaContext<'_>,
+        model_id: &str,
+        prefix: &[i32],
+    ) -> usize {
+        let Some(cache) = &self.prefix_cache else {
+            return 0;
+        };
+        let Some(hit) = cache.lock().unwrap().lookup(model_id, prefix) else {
+            return 0;
+        };
+
+        // SAFETY: the state was captured from a context of the same model
+        // created with the same parameters
+        let read = unsafe { context.set_state_data(&hit.state) };
+        if read == 0 {
+            tracing::warn!("Prefix cache state could not be restored; prefilling in full");
+            context.clear_kv_cache();
+            return 0;
+        }
+
+        tracing::info!(
+            "♻️ Prefix cache hit: reusing {} prompt tokens",
+            hit.reused_tokens
+        );
+        hit.reused_tokens
+    }
+
+    /// Capture the context state right after `prefix` was prefilled, before
+    /// any request-specific tokens are decoded
+    fn store_prefix(&self, context: &LlamaContext<'_>, model_id: &str, prefix: &[i32]) {
+        let Some(cache) = &self.prefix_cache else {
+            return;
+        };
+        if !cache.lock().unwrap().should_store(model_id, prefix) {
+            return;
+        }
+
+        let mut state = vec![0u8; context.get_state_size()];
+        // SAFETY: the buffer is sized by llama.cpp for this context's state
+        let written = unsafe { context.copy_state_data(state.as_mut_ptr()) };
+        state.truncate(written);
+        cache
+            .lock()
+            .unwrap()
+            .insert(model_id, prefix.to_vec(), state);
+    }
+
     pub fn is_ready(&self) -> bool {
         true
     }
@@ -325,26 +1159,210 @@ impl LlmEngine {
         }
     }
 
+    /// Loads a model under a freshly generated id
     pub async fn load_model(&mut self, config: ModelConfig) -> Result<String> {
         let model_id = Uuid::new_v4().to_string();
+        self.load_model_as(&model_id, config).await
+    }
+
+    /// Loads a model under `model_id`, evicting another model first if
+    /// `max_loaded_models` would be exceeded. The config is remembered so the
+    /// model can be lazily reloaded after eviction.
+    pub async fn load_model_as(&self, model_id: &str, config: ModelConfig) -> Result<String> {
+        let _guard = self.load_lock.lock().await;
+        self.known_models
+            .write()
+            .await
+            .insert(model_id.to_string(), config.clone());
+        if self.is_model_ready(model_id).await {
+            return Ok(model_id.to_string());
+        }
+        self.load_model_locked(model_id, config).await
+    }
+
+    /// Registers a model that is loaded on first use rather than up front
+    pub async fn register_model(&self, model_id: impl Into<String>, config: ModelConfig) {
+        self.known_models
+            .write()
+            .await
+            .insert(model_id.into(), config);
+    }
+
+    /// True if the model is loaded or registered for lazy loading
+    pub async fn is_model_known(&self, model_id: &str) -> bool {
+        self.model_info.read().await.contains_key(model_id)
+            || self.known_models.read().await.contains_key(model_id)
+    }
+
+    /// True if the model is in memory and ready for inference
+    async fn is_model_ready(&self, model_id: &str) -> bool {
+        self.model_info
+            .read()
+            .await
+            .get(model_id)
+            .is_some_and(|model| model.status == ModelStatus::Ready)
+    }
+
+    /// Makes sure `model_id` is in memory, lazily loading a registered model
+    pub async fn ensure_model_loaded(&self, model_id: &str) -> Result<()> {
+        if self.is_model_ready(model_id).await {
+            return Ok(());
+        }
+
+        let _guard = self.load_lock.lock().await;
+        // Another request may have loaded it while we waited
+        if self.is_model_ready(model_id).await {
+            return Ok(());
+        }
+        let config = self
+            .known_models
+            .read()
+            .await
+            .get(model_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Model not found: {}", model_id))?;
+
+        tracing::info!("Lazily loading model {}", model_id);
+        self.load_model_locked(model_id, config).await.map(|_| ())
+    }
+
+    /// Protects a loaded or registered model from eviction
+    pub async fn pin_model(&self, model_id: &str) -> Result<()> {
+        if !self.is_model_known(model_id).await {
+            return Err(anyhow!("Model not found: {}", model_id));
+        }
+        self.pinned_models
+            .write()
+            .await
+            .insert(model_id.to_string());
+        Ok(())
+    }
+
+    /// Makes a model evictable again; returns whether it was pinned
+    pub async fn unpin_model(&self, model_id: &str) -> bool {
+        self.pinned_models.write().await.remove(model_id)
+    }
+
+    pub async fn is_model_pinned(&self, model_id: &str) -> bool {
+        self.pinned_models.read().await.contains(model_id)
+    }
+
+    /// Sets the model requests fall back to, which eviction unloads last
+    pub async fn set_default_model(&self, model_id: impl Into<String>) {
+        *self.default_model.write().await = Some(model_id.into());
+    }
+
+    fn shared_backend(&self) -> Result<Arc<LlamaBackend>> {
+        let mut backend = self.backend.lock().unwrap();
+        if let Some(backend) = backend.as_ref() {
+            return Ok(backend.clone());
+        }
+        let initialized = Arc::new(
+            LlamaBackend::init().map_err(|e| anyhow!("Failed to initialize backend: {:?}", e))?,
+        );
+        *backend = Some(initialized.clone());
+        Ok(initialized)
+    }
+
+    /// The configured draft model, loaded on first use. Generations share
+    /// the weights but each drafts in its own context, so nothing stays
+    /// locked while they decode. `None` when speculative decoding is not
+    /// configured or the draft failed to load.
+    fn draft_model(&self) -> Option<Arc<LlamaModel>> {
+        let config = self.config.draft_model.as_ref()?;
+        self.draft_model
+            .get_or_init(|| {
+                let params =
+                    LlamaModelParams::default().with_n_gpu_layers(config.gpu_layers as u32);
+                let loaded = self.shared_backend().and_then(|backend| {
+                    LlamaModel::load_from_file(&backend, &config.model_path, &params)
+                        .map_err(|e| anyhow!("Failed to load draft model: {:?}", e))
+                });
+                match loaded {
+                    Ok(model) => {
+                        tracing::info!(
+                            "Draft model loaded from {:?} ({} tokens per pass)",
+                            config.model_path,
+                            config.draft_tokens
+                        );
+                        Some(Arc::new(model))
+                    }
+                    Err(e) => {
+                        // Decoding falls back to one token per pass
+                        tracing::warn!("Speculative decoding disabled: {}", e);
+                        None
+                    }
+                }
+            })
+            .clone()
+    }
+
+    /// Loads a model; the caller must hold `load_lock`
+    async fn load_model_locked(&self, model_id: &str, config: ModelConfig) -> Result<String> {
+        self.evict_for_new_model().await?;
 
         // Update model info
+        let now = std::time::SystemTime::now();
         let model = Model {
-            id: model_id.clone(),
+            id: model_id.to_string(),
             config: config.clone(),
             status: ModelStatus::Loading,
-            loaded_at: std::time::SystemTime::now(),
+            loaded_at: now,
             usage_count: 0,
+            last_used: now,
+            metadata: None,
+            file_digest: None,
         };
 
         self.model_info
             .write()
             .await
-            .insert(model_id.clone(), model.clone());
+            .insert(model_id.to_string(), model);
+
+        let loaded = self.load_gguf(&config);
+        let (real_model, metadata) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.model_info.write().await.remove(model_id);
+                return Err(e);
+            }
+        };
 
-        // Initialize backend
-        let backend =
-            LlamaBackend::init().map_err(|e| anyhow!("Failed to initialize backend: {:?}", e))?;
+        // Identifies the weights in `system_fingerprint`; hashed once here
+        // rather than per request
+        let model_path = config.model_path.clone();
+        let file_digest = match tokio::task::spawn_blocking(move || file_digest(&model_path)).await
+        {
+            Ok(Ok(digest)) => Some(digest),
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to hash model file for {}: {}", model_id, e);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Model hashing task for {} failed: {}", model_id, e);
+                None
+            }
+        };
+
+        // Store the loaded model
+        self.models
+            .lock()
+            .unwrap()
+            .insert(model_id.to_string(), Arc::new(real_model));
+
+        // Update status to ready
+        if let Some(model) = self.model_info.write().await.get_mut(model_id) {
+            model.status = ModelStatus::Ready;
+            model.metadata = Some(metadata);
+            model.file_digest = file_digest;
+        }
+
+        println!("Model loaded successfully!");
+        Ok(model_id.to_string())
+    }
+
+    fn load_gguf(&self, config: &ModelConfig) -> Result<(RealLlamaModel, GgufMetadata)> {
+        let backend = self.shared_backend()?;
 
         // Load the GGUF model
         let model_params = LlamaModelParams::default().with_n_gpu_layers(config.gpu_layers as u32);
@@ -352,25 +1370,117 @@ impl LlmEngine {
         let model = LlamaModel::load_from_file(&backend, &config.model_path, &model_params)
             .map_err(|e| anyhow!("Failed to load model: {:?}", e))?;
 
+        let chat_template = config
+            .chat_template
+            .unwrap_or_else(configured_chat_template);
+        let metadata = Self::read_gguf_metadata(&model, chat_template.as_str());
+
         let real_model = RealLlamaModel {
             backend,
             model,
             context_size: config.context_size,
+            chat_template,
+            decoding: std::sync::Mutex::new(()),
         };
 
-        // Store the loaded model
-        self.models
-            .lock()
-            .unwrap()
-            .insert(model_id.clone(), real_model);
+        Ok((real_model, metadata))
+    }
 
-        // Update status to ready
-        if let Some(model) = self.model_info.write().await.get_mut(&model_id) {
-            model.status = ModelStatus::Ready;
+    /// Unloads models per `model_eviction_policy` until there is room for one more
+    async fn evict_for_new_model(&self) -> Result<()> {
+        let limit = self.config.max_loaded_models.max(1);
+        loop {
+            let victim = {
+                let info = self.model_info.read().await;
+                if info.len() < limit {
+                    return Ok(());
+                }
+                // The default goes last: when it is the only unpinned model
+                // it makes way and is lazily reloaded on its next request
+                let pinned = self.pinned_models.read().await.clone();
+                let mut protected = pinned.clone();
+                protected.extend(self.default_model.read().await.clone());
+                select_eviction_candidate(
+                    info.values(),
+                    &protected,
+                    &self.config.model_eviction_policy,
+                )
+                .or_else(|| {
+                    select_eviction_candidate(
+                        info.values(),
+                        &pinned,
+                        &self.config.model_eviction_policy,
+                    )
+                })
+            };
+            match victim {
+                Some(victim) => {
+                    tracing::info!(
+                        "Evicting model {} ({} policy, limit {})",
+                        victim,
+                        self.config.model_eviction_policy,
+                        limit
+                    );
+                    self.unload_model(&victim).await?;
+                }
+                None => {
+                    return Err(anyhow!(
+                        "Cannot load model: all {} loaded models (limit {}) are pinned or still loading",
+                        self.model_info.read().await.len(),
+                        limit
+                    ))
+                }
+            }
         }
+    }
 
-        println!("Model loaded successfully!");
-        Ok(model_id)
+    /// Records that an inference was routed to `model_id`
+    async fn touch_model(&self, model_id: &str) {
+        if let Some(model) = self.model_info.write().await.get_mut(model_id) {
+            model.usage_count += 1;
+            model.last_used = std::time::SystemTime::now();
+        }
+    }
+
+    fn read_gguf_metadata(model: &LlamaModel, chat_template: &str) -> GgufMetadata {
+        let mut kv = BTreeMap::new();
+        for i in 0..model.meta_count() {
+            if let (Ok(key), Ok(value)) =
+                (model.meta_key_by_index(i), model.meta_val_str_by_index(i))
+            {
+                kv.insert(key, value);
+            }
+        }
+
+        GgufMetadata {
+            n_params: model.n_params(),
+            n_vocab: model.n_vocab() as u32,
+            size_bytes: model.size(),
+            ..GgufMetadata::from_kv(kv, chat_template)
+        }
+    }
+
+    /// GGUF header fields parsed when the model was loaded
+    pub async fn model_metadata(&self, model_id: &str) -> Option<GgufMetadata> {
+        self.model_info
+            .read()
+            .await
+            .get(model_id)
+            .and_then(|m| m.metadata.clone())
+    }
+
+    /// `system_fingerprint` of a loaded or registered model
+    pub async fn system_fingerprint(&self, model_id: &str) -> Option<String> {
+        let (config, file_digest) = match self.model_info.read().await.get(model_id) {
+            Some(model) => (model.config.clone(), model.file_digest.clone()),
+            None => (self.known_models.read().await.get(model_id)?.clone(), None),
+        };
+        Some(system_fingerprint(
+            &config,
+            file_digest.as_deref(),
+            &self.config,
+            self.continuous_batching.is_some(),
+        ))
     }
 
     pub async fn is_model_loaded(&self, model_id: &str) -> bool {
@@ -381,318 +1491,521 @@ impl LlmEngine {
         self.model_info.read().await.keys().cloned().collect()
     }
 
-    pub async fn run_inference(&self, mut request: InferenceRequest) -> Result<InferenceResult> {
-        let start_time = Instant::now();
+    #[tracing::instrument(name = "inference", skip_all, fields(model = %request.model_id))]
+    pub async fn run_inference(&self, request: InferenceRequest) -> Result<InferenceResult> {
+        // Time spent queueing for a slot counts against the timeout too
+        let deadline = Deadline {
+            start: Instant::now(),
+            timeout_ms: request.timeout_ms.or(self.config.default_timeout_ms),
+        };
+        let Some(concurrency) = &self.concurrency else {
+            return self.execute_inference(request, deadline).await;
+        };
 
-        // Check if model exists
-        if !self.model_info.read().await.contains_key(&request.model_id) {
-            return Err(anyhow!("Model not found: {}", request.model_id));
+        let permit = deadline
+            .run(&request.model_id, concurrency.acquire())
+            .await?;
+        let result = self.execute_inference(request, deadline).await;
+        if let Ok(result) = &result {
+            permit.complete(result.tokens_generated);
         }
+        result
+    }
+
+    async fn execute_inference(
+        &self,
+        mut request: InferenceRequest,
+        deadline: Deadline,
+    ) -> Result<InferenceResult> {
+        // Route to the requested model, lazily loading it if it's registered
+        deadline
+            .run(
+                &request.model_id,
+                self.ensure_model_loaded(&request.model_id),
+            )
+            .await??;
+        self.touch_model(&request.model_id).await;
 
         // Update metrics
         *self.inference_count.write().await += 1;
 
-        // Check if we have a real model loaded and perform generation
+        let late_context = request.late_context.take();
+
+        if self.continuous_batching.is_some()
+            && late_context.is_none()
+            && request.logprobs.is_none()
+        {
+            let (request, outcome) = self
+                .submit_batched(request, deadline.start, deadline.at())
+                .await?;
+            return self
+                .complete_inference(request, outcome, deadline.timeout_ms)
+                .await;
+        }
+
+        // Until the decode loop is generating (waiting for the model's
+        // decoding lock, prefill) the deadline is enforced here; after that the loop stops
+        // itself and returns the partial output so it can be billed.
+        let generating = Arc::new(AtomicBool::new(false));
+        let model_id = request.model_id.clone();
+        let engine = self.clone();
+        let flag = generating.clone();
+        let span = tracing::Span::current();
+        let mut task = tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            let outcome = engine.generate(&request, late_context, &deadline, &flag);
+            (request, outcome)
+        });
+        let joined = match deadline.run(&model_id, &mut task).await {
+            Ok(joined) => joined,
+            Err(_) if generating.load(Ordering::Acquire) => task.await,
+            Err(e) => return Err(e),
+        };
+        let (request, outcome) = joined.map_err(|e| anyhow!("Inference task failed: {}", e))?;
+        self.complete_inference(request, outcome?, deadline.timeout_ms)
+            .await
+    }
+
+    /// The decode loop of one request. It holds the model's decoding lock
+    /// throughout, so it runs on a blocking thread. `generating` is set once prefill is
+    /// done, after which the loop stops itself at the deadline.
+    fn generate(
+        &self,
+        request: &InferenceRequest,
+        mut late_context: Option<LateContext>,
+        deadline: &Deadline,
+        generating: &AtomicBool,
+    ) -> Result<GenerationOutcome> {
+        let loaded = self
+            .models
+            .lock()
+            .unwrap()
+            .get(&request.model_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Model {} is not loaded in memory", request.model_id))?;
+        let _decoding = loaded.decoding.lock().unwrap();
+
+        // Compile the response format up front so schema errors fail fast
+        let grammar = match request.response_format {
+            Some(ref format) => format
+                .to_gbnf()
+                .map_err(|e| anyhow!("Invalid response_format: {}", e))?,
+            None => None,
+        };
+
+        // Create necessary data before borrowing the model
         let (
-            output,
-            tokens_generated,
-            generation_time,
-            token_info_list,
-            stop_reason,
-            total_prompt_tokens,
+            prompt_tokens,
+            prefix_tokens,
             context_size,
+            eos_token,
+            stop_token_ids,
+            n_vocab,
+            logit_biases,
         ) = {
-            let mut models = self.models.lock().unwrap();
-            let has_real_model = models.contains_key(&request.model_id);
-
-            if !has_real_model {
-                return Err(anyhow!(
-                    "Model {} is not loaded in memory",
-                    request.model_id
-                ));
+            let model = &*loaded;
+
+            // Sanitize prompt before tokenization to prevent NulError
+            // Remove null bytes and other problematic characters that break C string handling
+            let sanitized_prompt = sanitize_prompt_for_tokenizer(&request.prompt);
+            if sanitized_prompt.len() != request.prompt.len() {
+                tracing::warn!(
+                    "🧹 Sanitized prompt: removed {} problematic bytes (original: {}, sanitized: {})",
+                    request.prompt.len() - sanitized_prompt.len(),
+                    request.prompt.len(),
+                    sanitized_prompt.len()
+                );
             }
 
-            // Create necessary data before borrowing the model
-            let (prompt_tokens, context_size, eos_token, stop_token_ids) = {
-                let model = models
-                    .get_mut(&request.model_id)
-                    .ok_or_else(|| anyhow!("Model not found in storage"))?;
-
-                // Sanitize prompt before tokenization to prevent NulError
-                // Remove null bytes and other problematic characters that break C string handling
-                let sanitized_prompt = sanitize_prompt_for_tokenizer(&request.prompt);
-                if sanitized_prompt.len() != request.prompt.len() {
-                    tracing::warn!(
-                        "🧹 Sanitized prompt: removed {} problematic bytes (original: {}, sanitized: {})",
-                        request.prompt.len() - sanitized_prompt.len(),
-                        request.prompt.len(),
-                        sanitized_prompt.len()
-                    );
-                }
+            // Tokenize the sanitized prompt
+            let tokens_list = model
+                .model
+                .str_to_token(&sanitized_prompt, AddBos::Always)
+                .map_err(|e| anyhow!("Failed to tokenize: {:?}", e))?;
 
-                // Tokenize the sanitized prompt
-                let tokens_list = model
-                    .model
-                    .str_to_token(&sanitized_prompt, AddBos::Always)
-                    .map_err(|e| anyhow!("Failed to tokenize: {:?}", e))?;
-
-                let eos = model.model.token_eos();
-
-                // Resolve stop tokens from template (or MODEL_STOP_TOKENS env override)
-                let template_name =
-                    std::env::var("MODEL_CHAT_TEMPLATE").unwrap_or_else(|_| "harmony".to_string());
-                let template = crate::inference::ChatTemplate::from_str(&template_name)
-                    .unwrap_or(crate::inference::ChatTemplate::Harmony);
-
-                let stop_token_strings = {
-                    let env_overrides = crate::inference::chat_template::parse_stop_tokens_env();
-                    if env_overrides.is_empty() {
-                        template
-                            .stop_tokens()
-                            .iter()
-                            .map(|s| s.to_string())
-                            .collect::<Vec<_>>()
-                    } else {
-                        env_overrides
-                    }
-                };
+            let eos = model.model.token_eos();
 
-                let mut stop_ids: Vec<llama_cpp_2::token::LlamaToken> = Vec::new();
-                for token_str in &stop_token_strings {
-                    if let Ok(tokens) = model.model.str_to_token(token_str, AddBos::Never) {
-                        if let Some(&tok) = tokens.first() {
-                            stop_ids.push(tok);
-                        }
-                    }
-                }
+            // Validate logit bias against the model vocabulary
+            let n_vocab = model.model.n_vocab();
+            let logit_biases = validate_logit_bias(&request.logit_bias, n_vocab as u32)?;
 
-                tracing::debug!(
-                    "🎯 Stop tokens: eos={}, template={}, strings={:?}, ids={:?}",
-                    eos,
-                    template_name,
-                    stop_token_strings,
-                    stop_ids.iter().map(|t| t.0).collect::<Vec<_>>()
-                );
+            let stop_ids = resolve_stop_token_ids(&model.model);
+            let prefix_tokens = self.reusable_prefix_tokens(
+                model,
+                request.chat_template.as_ref(),
+                &sanitized_prompt,
+                &tokens_list,
+            );
 
-                (tokens_list, model.context_size, eos, stop_ids)
-            };
+            (
+                tokens_list,
+                prefix_tokens,
+                model.context_size,
+                eos,
+                stop_ids,
+                n_vocab,
+                logit_biases,
+            )
+        };
 
-            // Check for context overflow before creating context
-            if prompt_tokens.len() >= context_size {
-                let overflow = prompt_tokens.len() - context_size;
-                return Err(anyhow!(
-                    "Prompt ({} tokens) exceeds context window ({} tokens) by {} tokens",
-                    prompt_tokens.len(),
-                    context_size,
-                    overflow
+        // Check for context overflow before creating context
+        if prompt_tokens.len() >= context_size {
+            let overflow = prompt_tokens.len() - context_size;
+            return Err(anyhow!(
+                "Prompt ({} tokens) exceeds context window ({} tokens) by {} tokens",
+                prompt_tokens.len(),
+                context_size,
+                overflow
+            ));
+        }
+
+        // Now work with the model again for context creation and generation
+        let model = &*loaded;
+
+        // Create context
+        let ctx_params = context_params(&self.config, context_size);
+
+        let mut context = model
+            .model
+            .new_context(&model.backend, ctx_params)
+            .map_err(|e| anyhow!("Failed to create context: {:?}", e))?;
+
+        // Create batch with configured batch size
+        let mut batch = LlamaBatch::new(self.config.batch_size, 1);
+
+        // Resume from the cached system prompt so only the tokens after it
+        // need prefilling
+        let prefix_ids: Vec<i32> = prompt_tokens[..prefix_tokens].iter().map(|t| t.0).collect();
+        let mut processed = self.restore_prefix(&mut context, &request.model_id, &prefix_ids);
+
+        // Process prompt tokens in chunks of batch_size (v8.15.4+)
+        // Previously all tokens were added to a single batch, causing
+        // InsufficientSpace errors when prompt exceeded batch_size.
+        let total_prompt_tokens = prompt_tokens.len();
+        while processed < total_prompt_tokens {
+            if deadline.passed() {
+                return Err(deadline.expired(
+                    &request.model_id,
+                    Some(ContextUsage {
+                        prompt_tokens: total_prompt_tokens,
+                        completion_tokens: 0,
+                        late_context_tokens: 0,
+                        total_tokens: total_prompt_tokens,
+                        context_window_size: context_size,
+                    }),
                 ));
             }
+            batch.clear();
+            let mut chunk_end = (processed + self.config.batch_size).min(total_prompt_tokens);
+            // End a chunk at the prefix so its state can be captured alone
+            if processed < prefix_tokens {
+                chunk_end = chunk_end.min(prefix_tokens);
+            }
+            for i in processed..chunk_end {
+                let is_last = i == total_prompt_tokens - 1;
+                batch
+                    .add(prompt_tokens[i], i as i32, &[0], is_last)
+                    .map_err(|e| anyhow!("Failed to add token to batch: {:?}", e))?;
+            }
+            context.decode(&mut batch).map_err(|e| {
+                anyhow!(
+                    "Decode failed at chunk {}/{}: {:?}",
+                    processed,
+                    total_prompt_tokens,
+                    e
+                )
+            })?;
+            processed = chunk_end;
+            if processed == prefix_tokens {
+                self.store_prefix(&context, &request.model_id, &prefix_ids);
+            }
+        }
 
-            // Now work with the model again for context creation and generation
-            let model = models
-                .get_mut(&request.model_id)
-                .ok_or_else(|| anyhow!("Model not found in storage"))?;
+        // Speculative decoding needs a draft sharing the model's vocabulary.
+        // Late context and logprobs rely on single-token decoding.
+        let draft_model = if late_context.is_none() && request.logprobs.is_none() {
+            self.draft_model()
+        } else {
+            None
+        };
+        let mut speculative = draft_model
+            .as_deref()
+            .filter(|draft| draft.n_vocab() == n_vocab)
+            .and_then(|draft| {
+                let draft_tokens = self
+                    .config
+                    .draft_model
+                    .as_ref()
+                    .map_or(DEFAULT_DRAFT_TOKENS, |d| d.draft_tokens);
+                SpeculativeDecoder::new(
+                    draft,
+                    &model.backend,
+                    context_params(&self.config, context_size),
+                    self.config.batch_size,
+                    draft_tokens,
+                    &prompt_tokens,
+                )
+                .map_err(|e| tracing::warn!("Speculative decoding disabled: {}", e))
+                .ok()
+            });
 
-            // Create context
-            let mut ctx_params = LlamaContextParams::default()
-                .with_n_ctx(NonZeroU32::new(context_size as u32))
-                .with_n_batch(self.config.batch_size as u32);
+        // Generate tokens
+        let mut output = String::new();
+        let mut token_info_list: Vec<TokenInfo> = Vec::new();
+        let mut n_cur = prompt_tokens.len();
+        // Grows when late context is decoded, so `n_cur - prompt_len` stays
+        // the number of generated tokens
+        let mut prompt_len = prompt_tokens.len();
+        let mut late_context_tokens = 0usize;
+        let max_tokens = request.max_tokens;
+        let mut consecutive_invalid_utf8 = 0; // Track consecutive invalid UTF-8 tokens
+        const MAX_CONSECUTIVE_INVALID: u32 = 10; // Break if stuck generating invalid tokens
+        let mut stop_reason = "loop_condition"; // v8.4.18: Track why we stopped
+
+        let (_, _, _, penalty_last_n) = get_penalty_defaults();
+        tracing::info!(
+            "🚀 Starting generation: prompt_tokens={}, max_tokens={}, context_size={}, limit={}, penalties(repeat={}, freq={}, pres={}, last_n={})",
+            prompt_len,
+            max_tokens,
+            context_size,
+            prompt_len + max_tokens,
+            request.repeat_penalty,
+            request.frequency_penalty,
+            request.presence_penalty,
+            penalty_last_n
+        );
 
-            if let Some(ref type_k_str) = self.config.kv_cache_type_k {
-                if let Some(kv_type) = parse_kv_cache_type(type_k_str) {
-                    ctx_params = ctx_params.with_type_k(kv_type);
-                    tracing::info!("KV cache K type set to: {}", type_k_str);
+        // Build sampler chain ONCE before loop so penalties sampler persists
+        // and accumulates token history across all generated tokens.
+        let mut sampler = build_sampler(
+            &model.model,
+            &request,
+            grammar.as_deref(),
+            &logit_biases,
+            n_vocab,
+        )?;
+        let logprobs_top_n = request.logprobs.map(|n| n.min(MAX_LOGPROBS) as usize);
+        // Resetting would also rewind the grammar state, so constrained
+        // requests never take the post-thinking reset below
+        let mut sampler_reset_done = grammar.is_some();
+
+        // Tokens decoded but not yet emitted because their text could be the
+        // start of a stop sequence. `emitted_len` is the byte length of output
+        // already streamed to the client.
+        let stop_matcher = StopSequenceMatcher::new(&request.stop_sequences);
+        let mut pending_tokens: std::collections::VecDeque<TokenInfo> =
+            std::collections::VecDeque::new();
+        let mut emitted_len = 0usize;
+
+        generating.store(true, Ordering::Release);
+        while n_cur < prompt_len + max_tokens {
+            // Check cancellation flag between tokens
+            if let Some(ref flag) = request.cancel_flag {
+                if flag.load(Ordering::Acquire) {
+                    stop_reason = "cancelled";
+                    tracing::info!("🛑 Inference cancelled after {} tokens", n_cur - prompt_len);
+                    break;
                 }
             }
-            if let Some(ref type_v_str) = self.config.kv_cache_type_v {
-                if let Some(kv_type) = parse_kv_cache_type(type_v_str) {
-                    ctx_params = ctx_params.with_type_v(kv_type);
-                    tracing::info!("KV cache V type set to: {}", type_v_str);
-                }
+
+            if deadline.passed() {
+                stop_reason = "timeout";
+                tracing::warn!(
+                    "⏱️ Inference timed out after {} tokens ({}ms limit)",
+                    n_cur - prompt_len,
+                    deadline.timeout_ms.unwrap_or_default()
+                );
+                break;
             }
 
-            let mut context = model
-                .model
-                .new_context(&model.backend, ctx_params)
-                .map_err(|e| anyhow!("Failed to create context: {:?}", e))?;
-
-            // Create batch with configured batch size
-            let mut batch = LlamaBatch::new(self.config.batch_size, 1);
-
-            // Process prompt tokens in chunks of batch_size (v8.15.4+)
-            // Previously all tokens were added to a single batch, causing
-            // InsufficientSpace errors when prompt exceeded batch_size.
-            let total_prompt_tokens = prompt_tokens.len();
-            let mut processed = 0;
-            while processed < total_prompt_tokens {
-                batch.clear();
-                let chunk_end = (processed + self.config.batch_size).min(total_prompt_tokens);
-                for i in processed..chunk_end {
-                    let is_last = i == total_prompt_tokens - 1;
-                    batch
-                        .add(prompt_tokens[i], i as i32, &[0], is_last)
-                        .map_err(|e| anyhow!("Failed to add token to batch: {:?}", e))?;
-                }
-                context.decode(&mut batch).map_err(|e| {
-                    anyhow!(
-                        "Decode failed at chunk {}/{}: {:?}",
-                        processed,
-                        total_prompt_tokens,
-                        e
-                    )
+            // Late context (e.g. slow search fetches) is decoded while the
+            // answer is still short
+            let generated = n_cur - prompt_len;
+            let late_text = late_context.as_mut().and_then(|late| late.take(generated));
+            if generated >= LATE_CONTEXT_TOKEN_WINDOW {
+                late_context = None;
+            }
+            if let Some(text) = late_text {
+                let late_tokens = tokenize_late_context(&text, |text| {
+                    model
+                        .model
+                        .str_to_token(text, AddBos::Never)
+                        .map_err(|e| anyhow!("Failed to tokenize late context: {:?}", e))
                 })?;
-                processed = chunk_end;
+                let remaining = max_tokens - generated;
+                if n_cur + late_tokens.len() + remaining > context_size {
+                    tracing::debug!(
+                        "Dropping {} late context tokens (context window full)",
+                        late_tokens.len()
+                    );
+                } else {
+                    for chunk in late_tokens.chunks(self.config.batch_size) {
+                        batch.clear();
+                        for (i, &token) in chunk.iter().enumerate() {
+                            batch
+                                .add(token, (n_cur + i) as i32, &[0], i + 1 == chunk.len())
+                                .map_err(|e| anyhow!("Failed to add token: {:?}", e))?;
+                        }
+                        context
+                            .decode(&mut batch)
+                            .map_err(|e| anyhow!("Late context decode failed: {:?}", e))?;
+                        n_cur += chunk.len();
+                    }
+                    prompt_len += late_tokens.len();
+                    late_context_tokens += late_tokens.len();
+                    tracing::info!(
+                        "📎 Appended {} late context tokens after {} generated tokens",
+                        late_tokens.len(),
+                        generated
+                    );
+                }
             }
 
-            // Generate tokens
-            let mut output = String::new();
-            let mut token_info_list: Vec<TokenInfo> = Vec::new();
-            let mut n_cur = prompt_tokens.len();
-            let max_tokens = request.max_tokens;
-            let mut consecutive_invalid_utf8 = 0; // Track consecutive invalid UTF-8 tokens
-            const MAX_CONSECUTIVE_INVALID: u32 = 10; // Break if stuck generating invalid tokens
-            let mut stop_reason = "loop_condition"; // v8.4.18: Track why we stopped
+            let sample_index = speculative.as_ref().map_or(-1, |s| s.sample_index());
+            let new_token_id = sampler.sample(&context, sample_index);
 
-            let (_, _, _, penalty_last_n) = get_penalty_defaults();
-            tracing::info!(
-                "🚀 Starting generation: prompt_tokens={}, max_tokens={}, context_size={}, limit={}, penalties(repeat={}, freq={}, pres={}, last_n={})",
-                prompt_tokens.len(),
-                max_tokens,
-                context_size,
-                prompt_tokens.len() + max_tokens,
-                request.repeat_penalty,
-                request.frequency_penalty,
-                request.presence_penalty,
-                penalty_last_n
-            );
+            // Sampling works on its own copy of the candidates, so the
+            // context still holds this step's unmodified logits
+            let step_logprobs =
+                logprobs_top_n.map(|n| token_logprobs(context.get_logits(), new_token_id.0, n));
 
-            // Build sampler chain ONCE before loop so penalties sampler persists
-            // and accumulates token history across all generated tokens.
-            // temp → penalties → top_p → min_p → dist/greedy
-            let mut samplers: Vec<LlamaSampler> = Vec::new();
-            samplers.push(LlamaSampler::temp(request.temperature));
-            if request.repeat_penalty != 1.0
-                || request.frequency_penalty != 0.0
-                || request.presence_penalty != 0.0
-            {
-                samplers.push(LlamaSampler::penalties(
-                    penalty_last_n,
-                    request.repeat_penalty,
-                    request.frequency_penalty,
-                    request.presence_penalty,
-                ));
-            }
-            samplers.push(LlamaSampler::top_p(request.top_p, 1));
-            if request.min_p > 0.0 {
-                samplers.push(LlamaSampler::min_p(request.min_p, 1));
+            let tokens_so_far = n_cur - prompt_len;
+            let is_special = new_token_id == eos_token || stop_token_ids.contains(&new_token_id);
+
+            // Stop on EOS token
+            if new_token_id == eos_token {
+                stop_reason = "eos_token";
+                tracing::info!(
+                    "🛑 EOS token after {} chars, {} tokens",
+                    output.len(),
+                    token_info_list.len()
+                );
+                break;
             }
-            if request.temperature > 0.0 {
-                let seed = request.seed.unwrap_or(0) as u32;
-                samplers.push(LlamaSampler::dist(seed));
-            } else {
-                samplers.push(LlamaSampler::greedy());
+
+            // Stop on template-specific stop tokens
+            if stop_token_ids.contains(&new_token_id) {
+                stop_reason = "stop_token";
+                tracing::info!(
+                    "🛑 Stop token {} after {} chars, {} tokens",
+                    new_token_id,
+                    output.len(),
+                    token_info_list.len()
+                );
+                break;
             }
-            let mut sampler = LlamaSampler::chain_simple(samplers);
-            let mut sampler_reset_done = false;
-
-            while n_cur < prompt_tokens.len() + max_tokens {
-                // Check cancellation flag between tokens
-                if let Some(ref flag) = request.cancel_flag {
-                    if flag.load(Ordering::Acquire) {
-                        stop_reason = "cancelled";
-                        tracing::info!(
-                            "🛑 Inference cancelled after {} tokens",
-                            n_cur - prompt_tokens.len()
-                        );
-                        break;
-                    }
-                }
 
-                let new_token_id = sampler.sample(&context, -1);
+            // v8.4.19 FIX: Convert token to string - handle invalid UTF-8 by still advancing model state
+            let token_str_result = model.model.token_to_str(new_token_id, Special::Tokenize);
+
+            let is_valid_utf8 = token_str_result.is_ok();
+            let token_str = token_str_result.unwrap_or_else(|_| String::new());
+
+            // v8.21.2: Normalize <thought> → <think> for consistent thinking tags
+            let token_str = normalize_thought_token(&token_str).to_string();
 
-                let tokens_so_far = n_cur - prompt_tokens.len();
-                let is_special =
-                    new_token_id == eos_token || stop_token_ids.contains(&new_token_id);
+            if is_valid_utf8 {
+                consecutive_invalid_utf8 = 0; // Reset counter on valid token
 
-                // Stop on EOS token
-                if new_token_id == eos_token {
-                    stop_reason = "eos_token";
+                // Add valid token to output
+                output.push_str(&token_str);
+
+                // v8.22.3: Reset sampler after thinking block to clear penalty history.
+                // Thinking tokens pollute the penalty window, causing the answer
+                // portion to degenerate into garbage with aggressive penalties.
+                if !sampler_reset_done
+                    && (output.contains("</think>") || output.contains("</thought>"))
+                {
+                    sampler.reset();
+                    sampler_reset_done = true;
                     tracing::info!(
-                        "🛑 EOS token after {} chars, {} tokens",
-                        output.len(),
-                        token_info_list.len()
+                        "🔄 Sampler reset after thinking block (token {})",
+                        n_cur.saturating_sub(prompt_len)
                     );
-                    break;
                 }
 
-                // Stop on template-specific stop tokens
-                if stop_token_ids.contains(&new_token_id) {
-                    stop_reason = "stop_token";
+                // Store token info for streaming
+                let (logprob, top_logprobs) = match step_logprobs {
+                    Some((logprob, top)) => {
+                        let top = top
+                            .into_iter()
+                            .map(|(token_id, logprob)| TopLogprob {
+                                token_id,
+                                text: model
+                                    .model
+                                    .token_to_str(LlamaToken::new(token_id), Special::Tokenize)
+                                    .unwrap_or_default(),
+                                logprob,
+                            })
+                            .collect();
+                        (Some(logprob), top)
+                    }
+                    None => (None, Vec::new()),
+                };
+
+                pending_tokens.push_back(TokenInfo {
+                    token_id: new_token_id.0 as i32,
+                    text: token_str,
+                    logprob,
+                    timestamp: None,
+                    top_logprobs,
+                });
+
+                // Custom stop sequences: truncate at the first match and
+                // emit only the text that precedes it
+                if let Some(match_pos) = stop_matcher.find_match(&output, emitted_len) {
+                    output.truncate(match_pos);
+                    while let Some(mut token_info) = pending_tokens.pop_front() {
+                        if emitted_len >= match_pos {
+                            break;
+                        }
+                        let remaining = match_pos - emitted_len;
+                        if token_info.text.len() > remaining {
+                            token_info.text.truncate(remaining);
+                        }
+                        emitted_len += token_info.text.len();
+                        emit_token(&request.token_sender, &mut token_info_list, token_info);
+                    }
+                    stop_reason = "stop_sequence";
+                    n_cur += 1;
                     tracing::info!(
-                        "🛑 Stop token {} after {} chars, {} tokens",
-                        new_token_id,
+                        "🛑 Stop sequence matched after {} chars, {} tokens",
                         output.len(),
                         token_info_list.len()
                     );
                     break;
                 }
 
-                // v8.4.19 FIX: Convert token to string - handle invalid UTF-8 by still advancing model state
-                let token_str_result = model.model.token_to_str(new_token_id, Special::Tokenize);
-
-                let is_valid_utf8 = token_str_result.is_ok();
-                let token_str = token_str_result.unwrap_or_else(|_| String::new());
-
-                // v8.21.2: Normalize <thought> → <think> for consistent thinking tags
-                let token_str = normalize_thought_token(&token_str).to_string();
-
-                if is_valid_utf8 {
-                    consecutive_invalid_utf8 = 0; // Reset counter on valid token
-
-                    // Add valid token to output
-                    output.push_str(&token_str);
-
-                    // v8.22.3: Reset sampler after thinking block to clear penalty history.
-                    // Thinking tokens pollute the penalty window, causing the answer
-                    // portion to degenerate into garbage with aggressive penalties.
-                    if !sampler_reset_done
-                        && (output.contains("</think>") || output.contains("</thought>"))
-                    {
-                        sampler.reset();
-                        sampler_reset_done = true;
-                        tracing::info!(
-                            "🔄 Sampler reset after thinking block (token {})",
-                            n_cur.saturating_sub(prompt_tokens.len())
-                        );
-                    }
-
-                    // Store token info for streaming
-                    let token_info = TokenInfo {
-                        token_id: new_token_id.0 as i32,
-                        text: token_str,
-                        logprob: None,
-                        timestamp: None,
-                    };
-                    // Send token as it's generated (true streaming)
-                    if let Some(ref tx) = request.token_sender {
-                        let _ = tx.try_send(Ok(token_info.clone()));
+                // Send tokens as they're generated (true streaming), holding
+                // back any tail that could still become a stop sequence
+                let safe_len = output.len() - stop_matcher.holdback_len(&output);
+                while let Some(token_info) = pending_tokens.front() {
+                    if emitted_len + token_info.text.len() > safe_len {
+                        break;
                     }
-                    token_info_list.push(token_info);
-                } else {
-                    // Invalid UTF-8 - don't add to output but MUST advance model state
-                    consecutive_invalid_utf8 += 1;
-                    tracing::warn!(
-                        token_id = new_token_id.0,
-                        consecutive_invalid = consecutive_invalid_utf8,
-                        output_chars = output.len(),
-                        valid_tokens = token_info_list.len(),
-                        "Invalid UTF-8 token detected - this may indicate chat template mismatch"
-                    );
-                    // DON'T add to token_info_list - we don't want to stream garbage to client
+                    let token_info = pending_tokens.pop_front().unwrap();
+                    emitted_len += token_info.text.len();
+                    emit_token(&request.token_sender, &mut token_info_list, token_info);
                 }
+            } else {
+                // Invalid UTF-8 - don't add to output but MUST advance model state
+                consecutive_invalid_utf8 += 1;
+                tracing::warn!(
+                    token_id = new_token_id.0,
+                    consecutive_invalid = consecutive_invalid_utf8,
+                    output_chars = output.len(),
+                    valid_tokens = token_info_list.len(),
+                    "Invalid UTF-8 token detected - this may indicate chat template mismatch"
+                );
+                // DON'T add to token_info_list - we don't want to stream garbage to client
+            }
 
-                // CRITICAL: Always add token to batch and decode to advance model state
-                // This prevents infinite loops on invalid UTF-8 tokens
+            // CRITICAL: Always add token to batch and decode to advance model state
+            // This prevents infinite loops on invalid UTF-8 tokens
+            if let Some(spec) = speculative.as_mut() {
+                let limit = (prompt_len + max_tokens).min(context_size);
+                spec.advance(&mut context, &mut batch, new_token_id, n_cur, limit)?;
+            } else {
                 batch.clear();
                 batch
                     .add(new_token_id, n_cur as i32, &[0], true)
@@ -700,32 +2013,116 @@ impl LlmEngine {
                 context
                     .decode(&mut batch)
                     .map_err(|e| anyhow!("Decode failed: {:?}", e))?;
+            }
 
-                n_cur += 1;
-            } // end generation loop
+            n_cur += 1;
+        } // end generation loop
 
-            let tokens_generated = n_cur - prompt_tokens.len();
-            let generation_time = start_time.elapsed();
+        // Generation ended without a stop-sequence match: release held-back tokens
+        while let Some(token_info) = pending_tokens.pop_front() {
+            emit_token(&request.token_sender, &mut token_info_list, token_info);
+        }
+
+        let tokens_generated = n_cur - prompt_len;
+        let generation_time = deadline.start.elapsed();
+        let speculative_stats = speculative.map(|s| s.stats());
+        if let Some(stats) = speculative_stats {
+            tracing::info!(
+                "🔮 Speculative decoding: {}/{} drafted tokens accepted ({:.0}%)",
+                stats.accepted,
+                stats.drafted,
+                stats.acceptance_rate() * 100.0
+            );
+        }
+
+        tracing::info!(
+            "🏁 Generation ended: tokens_generated={}, output_chars={}, n_cur={}, limit={}, stop_reason={}",
+            tokens_generated,
+            output.len(),
+            n_cur,
+            prompt_len + max_tokens,
+            stop_reason
+        );
+
+        Ok(GenerationOutcome {
+            output,
+            tokens_generated,
+            generation_time,
+            token_info_list,
+            stop_reason,
+            prompt_tokens: total_prompt_tokens,
+            late_context_tokens,
+            context_size,
+            speculative: speculative_stats,
+        })
+    }
+
+    /// Queue a request on its model's continuous batching worker, starting
+    /// the worker if needed, and wait for the request to finish
+    async fn submit_batched(
+        &self,
+        request: InferenceRequest,
+        start_time: Instant,
+        deadline: Option<Instant>,
+    ) -> Result<(InferenceRequest, GenerationOutcome)> {
+        let config = self
+            .continuous_batching
+            .clone()
+            .ok_or_else(|| anyhow!("Continuous batching is not enabled"))?;
+        let model_id = request.model_id.clone();
+        let (reply, done) = tokio::sync::oneshot::channel();
+        let job = BatchedJob {
+            request,
+            start_time,
+            deadline,
+            reply,
+        };
+
+        {
+            let mut workers = self.batch_workers.lock().unwrap();
+            // A worker exits once its model is unloaded; start a fresh one
+            let sent = match workers.get(&model_id) {
+                Some(sender) => match sender.send(job) {
+                    Ok(()) => None,
+                    Err(std::sync::mpsc::SendError(returned)) => Some(returned),
+                },
+                None => Some(job),
+            };
+            if let Some(job) = sent {
+                let sender = spawn_batch_worker(
+                    self.models.clone(),
+                    model_id.clone(),
+                    self.config.clone(),
+                    config,
+                )?;
+                sender
+                    .send(job)
+                    .map_err(|_| anyhow!("Continuous batching worker for {} exited", model_id))?;
+                workers.insert(model_id, sender);
+            }
+        }
 
-            tracing::info!(
-                "🏁 Generation ended: tokens_generated={}, output_chars={}, n_cur={}, limit={}, stop_reason={}",
-                tokens_generated,
-                output.len(),
-                n_cur,
-                prompt_tokens.len() + max_tokens,
-                stop_reason
-            );
+        done.await
+            .map_err(|_| anyhow!("Continuous batching worker dropped the request"))?
+    }
 
-            (
-                output,
-                tokens_generated,
-                generation_time,
-                token_info_list,
-                stop_reason,
-                total_prompt_tokens,
-                context_size,
-            )
-        }; // Release the mutex here before any await
+    async fn complete_inference(
+        &self,
+        mut request: InferenceRequest,
+        outcome: GenerationOutcome,
+        timeout_ms: Option<u64>,
+    ) -> Result<InferenceResult> {
+        let GenerationOutcome {
+            output,
+            tokens_generated,
+            generation_time,
+            token_info_list,
+            stop_reason,
+            prompt_tokens: total_prompt_tokens,
+            late_context_tokens,
+            context_size,
+            speculative,
+        } = outcome;
 
         let tokens_per_second = tokens_generated as f32 / generation_time.as_secs_f32();
 
@@ -737,8 +2134,25 @@ impl LlmEngine {
             metrics.total_inference_time += generation_time;
             metrics.average_tokens_per_second =
                 metrics.total_tokens_generated as f32 / metrics.total_inference_time.as_secs_f32();
+            if let Some(stats) = speculative {
+                metrics.speculative_tokens_drafted += stats.drafted;
+                metrics.speculative_tokens_accepted += stats.accepted;
+                metrics.speculative_acceptance_rate = SpeculativeStats {
+                    drafted: metrics.speculative_tokens_drafted,
+                    accepted: metrics.speculative_tokens_accepted,
+                }
+                .acceptance_rate();
+            }
         }
 
+        let logprobs = request.logprobs.map(|_| {
+            token_info_list
+                .iter()
+                .filter_map(TokenLogprobs::from_token_info)
+                .collect()
+        });
+
+        let system_fingerprint = self.system_fingerprint(&request.model_id).await;
         let result = InferenceResult {
             text: output,
             tokens_generated,
@@ -747,15 +2161,19 @@ impl LlmEngine {
             model_id: request.model_id,
             finish_reason: match stop_reason {
                 "cancelled" => "cancelled".to_string(),
+                "timeout" => "timeout".to_string(),
                 "loop_condition" => "length".to_string(),
                 _ => "stop".to_string(),
             },
             token_info: token_info_list,
             was_cancelled: stop_reason == "cancelled",
+            logprobs,
+            system_fingerprint,
             context_usage: Some(ContextUsage {
                 prompt_tokens: total_prompt_tokens,
                 completion_tokens: tokens_generated,
-                total_tokens: total_prompt_tokens + tokens_generated,
+                late_context_tokens,
+                total_tokens: total_prompt_tokens + late_context_tokens + tokens_generated,
                 context_window_size: context_size,
             }),
         };
@@ -764,6 +2182,14 @@ impl LlmEngine {
             let _ = sender.send(result.clone());
         }
 
+        if stop_reason == "timeout" {
+            return Err(InferenceError::Timeout {
+                timeout_ms: timeout_ms.unwrap_or_default(),
+                partial: Box::new(result),
+            }
+            .into());
+        }
+
         Ok(result)
     }
 
@@ -771,18 +2197,13 @@ impl LlmEngine {
         &self,
         request: InferenceRequest,
     ) -> Result<(TokenStream, tokio::sync::oneshot::Receiver<InferenceResult>)> {
-        // Check if model exists
-        if !self.model_info.read().await.contains_key(&request.model_id) {
-            return Err(anyhow!("Model not found: {}", request.model_id));
-        }
+        // Route to the requested model, lazily loading it if it's registered
+        self.ensure_model_loaded(&request.model_id).await?;
 
         let (tx, rx) = mpsc::channel(4096);
         let (result_tx, result_rx) = tokio::sync::oneshot::channel();
 
-        // Check if we have a real model loaded
-        let has_real_model = self.models.lock().unwrap().contains_key(&request.model_id);
-
-        if has_real_model {
+        if self.is_model_ready(&request.model_id).await {
             // True token-by-token streaming via spawn_blocking (v8.19.1)
             // Each token is sent over the channel as it's generated in the loop.
             let mut inference_request = request;
@@ -791,8 +2212,11 @@ impl LlmEngine {
             inference_request.result_sender = Some(result_tx);
             // Clone engine — all fields are Arc, cheap clone
             let engine = self.clone();
+            // Keep generation inside the caller's trace
+            let span = tracing::Span::current();
             // Run generation on blocking thread pool (solves !Send constraint)
             tokio::task::spawn_blocking(move || {
+                let _entered = span.enter();
                 let handle = tokio::runtime::Handle::current();
                 handle.block_on(async move {
                     let _ = engine.run_inference(inference_request).await;
@@ -810,9 +2234,16 @@ impl LlmEngine {
         Ok((ReceiverStream::new(rx), result_rx))
     }
 
-    pub async fn unload_model(&mut self, model_id: &str) -> Result<()> {
-        self.models.lock().unwrap().remove(model_id);
+    pub async fn unload_model(&self, model_id: &str) -> Result<()> {
+        // Dropping the queue lets the model's batching worker exit once idle
+        self.batch_workers.lock().unwrap().remove(model_id);
+        if let Some(cache) = &self.prefix_cache {
+            cache.lock().unwrap().invalidate_model(model_id);
+        }
+        // Forget the model before freeing it so readiness checks stop
+        // routing requests to it
         self.model_info.write().await.remove(model_id);
+        self.models.lock().unwrap().remove(model_id);
         Ok(())
     }
 
@@ -825,15 +2256,24 @@ impl LlmEngine {
         self.metrics.read().await.clone()
     }
 
-    pub async fn run_inference_async(&self, request: InferenceRequest) -> InferenceHandle {
-        // Since we can't move the engine to another thread, we need to run inference
-        // on the current task and wrap the result in a future
-        let result = self.run_inference(request).await;
-
-        // Create a completed future with the result
-        let task = tokio::spawn(async move { result });
+    pub async fn run_inference_async(&self, mut request: InferenceRequest) -> InferenceHandle {
+        // Share a cancellation flag with the decode loop so the handle can stop
+        // generation between tokens and still collect the partial result
+        let cancel_flag = request
+            .cancel_flag
+            .get_or_insert_with(|| Arc::new(AtomicBool::new(false)))
+            .clone();
+
+        // Run generation on blocking thread pool (same as run_inference_stream)
+        let engine = self.clone();
+        let span = tracing::Span::current();
+        let task = tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            let handle = tokio::runtime::Handle::current();
+            handle.block_on(async move { engine.run_inference(request).await })
+        });
 
-        InferenceHandle { task }
+        InferenceHandle { task, cancel_flag }
     }
 
     pub async fn get_model_capabilities(&self, model_id: &str) -> Option<ModelCapabilities> {
@@ -878,7 +2318,11 @@ impl LlmEngine {
     ) -> InferenceRequest {
         let prompt = messages
             .iter()
-            .map(|m| format!("{}: {}", m.role, m.content))
+            .map(|m| {
+                let content =
+                    crate::inference::tools::message_content(&m.content, m.tool_calls.as_deref());
+                format!("{}: {}", m.role, content)
+            })
             .collect::<Vec<_>>()
             .join("\n");
 
@@ -895,16 +2339,79 @@ impl LlmEngine {
             min_p: 0.0,
             seed: None,
             stop_sequences: vec![],
+            logit_bias: HashMap::new(),
+            response_format: None,
+            chat_template: None,
+            logprobs: None,
+            timeout_ms: None,
             stream: false,
             cancel_flag: None,
             token_sender: None,
             result_sender: None,
+            late_context: None,
+        }
+    }
+
+    /// Vocabulary size of a loaded model, or None if the model isn't in memory
+    pub fn vocab_size(&self, model_id: &str) -> Option<u32> {
+        self.models
+            .lock()
+            .unwrap()
+            .get(model_id)
+            .map(|m| m.model.n_vocab() as u32)
+    }
+
+    /// Tokenize text with a loaded model's tokenizer.
+    ///
+    /// No BOS token is added, so the result is the raw token sequence for `text`
+    /// (prompt sanitization is applied first, matching `run_inference`).
+    pub fn tokenize(&self, model_id: &str, text: &str) -> Result<Vec<u32>> {
+        let models = self.models.lock().unwrap();
+        let model = models
+            .get(model_id)
+            .ok_or_else(|| anyhow!("Model {} is not loaded in memory", model_id))?;
+        let tokens = model
+            .model
+            .str_to_token(&sanitize_prompt_for_tokenizer(text), AddBos::Never)
+            .map_err(|e| anyhow!("Failed to tokenize: {:?}", e))?;
+        Ok(tokens.into_iter().map(|t| t.0 as u32).collect())
+    }
+
+    /// Convert token IDs back into text with a loaded model's tokenizer.
+    ///
+    /// Tokens are decoded to raw bytes and concatenated before UTF-8 conversion,
+    /// because byte-level BPE tokenizers can split a multi-byte character across
+    /// several tokens. Byte sequences that are still invalid UTF-8 after joining
+    /// are replaced with U+FFFD, so `detokenize(tokenize(text))` is not guaranteed
+    /// to reproduce `text` exactly (e.g. for stripped control characters or
+    /// tokenizers that normalize whitespace).
+    pub fn detokenize(&self, model_id: &str, tokens: &[u32]) -> Result<String> {
+        let models = self.models.lock().unwrap();
+        let model = models
+            .get(model_id)
+            .ok_or_else(|| anyhow!("Model {} is not loaded in memory", model_id))?;
+        let n_vocab = model.model.n_vocab() as u32;
+        let mut bytes = Vec::new();
+        for &token_id in tokens {
+            if token_id >= n_vocab {
+                return Err(anyhow!(
+                    "Invalid token id {} (model vocab size is {})",
+                    token_id,
+                    n_vocab
+                ));
+            }
+            let piece = model
+                .model
+                .token_to_bytes(LlamaToken::new(token_id as i32), Special::Tokenize)
+                .map_err(|e| anyhow!("Failed to detokenize token {}: {:?}", token_id, e))?;
+            bytes.extend_from_slice(&piece);
         }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
     }
 
     pub async fn count_tokens(&self, model_id: &str, text: &str) -> Result<usize> {
         // Check if we have a real model loaded
-        if self.models.lock().unwrap().contains_key(model_id) {
+        if self.is_model_ready(model_id).await {
             // Note: llama_cpp_rs might not expose direct tokenization
             // For now, we'll use an approximation
             // Typically, one token is roughly 4 characters
@@ -921,6 +2428,9 @@ impl LlmEngine {
             total_tokens_generated: 0,
             average_tokens_per_second: 0.0,
             total_inference_time: Duration::default(),
+            speculative_tokens_drafted: 0,
+            speculative_tokens_accepted: 0,
+            speculative_acceptance_rate: 0.0,
         };
     }
 }
@@ -928,11 +2438,18 @@ impl LlmEngine {
 // Async inference handle for cancellation
 pub struct InferenceHandle {
     task: tokio::task::JoinHandle<Result<InferenceResult>>,
+    cancel_flag: Arc<AtomicBool>,
 }
 
 impl InferenceHandle {
+    /// Request cancellation. The engine stops at the next token boundary and the
+    /// handle resolves to the partial result with `finish_reason: "cancelled"`.
     pub async fn cancel(&self) {
-        self.task.abort();
+        self.cancel_flag.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_flag.load(Ordering::Acquire)
     }
 }
 
@@ -1068,6 +2585,7 @@ mod tests {
         let cu = ContextUsage {
             prompt_tokens: 100,
             completion_tokens: 50,
+            late_context_tokens: 0,
             total_tokens: 150,
             context_window_size: 4096,
         };
@@ -1082,6 +2600,7 @@ mod tests {
         let cu = ContextUsage {
             prompt_tokens: 1250,
             completion_tokens: 150,
+            late_context_tokens: 0,
             total_tokens: 1400,
             context_window_size: 32768,
         };
@@ -1092,6 +2611,56 @@ mod tests {
         assert_eq!(json["context_window_size"], 32768);
     }
 
+    fn late_context() -> (mpsc::UnboundedSender<String>, LateContext) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let frame = ("<open>".to_string(), "<close>".to_string());
+        (tx, LateContext::new(rx, frame))
+    }
+
+    #[test]
+    fn test_late_context_inside_window() {
+        let (tx, mut late) = late_context();
+        assert_eq!(late.take(0), None);
+
+        // Everything pending is framed as a single tool message
+        tx.send("[4] first".to_string()).unwrap();
+        tx.send("[5] second".to_string()).unwrap();
+        assert_eq!(
+            late.take(LATE_CONTEXT_TOKEN_WINDOW - 1).as_deref(),
+            Some("<open>[4] first[5] second<close>")
+        );
+        assert_eq!(late.take(LATE_CONTEXT_TOKEN_WINDOW - 1), None);
+    }
+
+    #[test]
+    fn test_late_context_after_window() {
+        let (tx, mut late) = late_context();
+        tx.send("[4] too late".to_string()).unwrap();
+
+        assert_eq!(late.take(LATE_CONTEXT_TOKEN_WINDOW), None);
+        // Senders see the channel closed and stop forwarding
+        assert!(tx.send("[5] later".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_late_context_token_accounting() {
+        // One token per byte
+        let tokenize = |text: &str| -> Result<Vec<LlamaToken>> {
+            Ok(text.bytes().map(|b| LlamaToken::new(b as i32)).collect())
+        };
+        let partial_answer = "The answer so far";
+
+        let (tx, mut late) = late_context();
+        tx.send("[4] source".to_string()).unwrap();
+        let framed = late.take(partial_answer.len()).unwrap();
+        let late_tokens = tokenize_late_context(&framed, tokenize).unwrap();
+
+        // Only the framed tool message is decoded; the partial answer is not repeated
+        assert_eq!(late_tokens.len(), "<open>[4] source<close>".len());
+        let decoded: Vec<u8> = late_tokens.iter().map(|t| t.0 as u8).collect();
+        assert!(!String::from_utf8(decoded).unwrap().contains(partial_answer));
+    }
+
     #[test]
     fn test_finish_reason_loop_condition_maps_to_length() {
         let stop_reason = "loop_condition";
@@ -1217,6 +2786,242 @@ mod tests {
         assert_eq!(super::normalize_thought_token("</think>"), "</think>");
     }
 
+    // === Stop Sequence Tests ===
+
+    #[test]
+    fn test_stop_matcher_finds_earliest_match() {
+        let matcher = StopSequenceMatcher::new(&["END".to_string(), "\n\n".to_string()]);
+        assert_eq!(matcher.find_match("Hello\n\nWorld END", 0), Some(5));
+        assert_eq!(matcher.find_match("Hello\n\nWorld END", 7), Some(13));
+        assert_eq!(matcher.find_match("Hello World", 0), None);
+    }
+
+    #[test]
+    fn test_stop_matcher_holds_back_partial_match_across_tokens() {
+        let matcher = StopSequenceMatcher::new(&["\n\n".to_string()]);
+        // First "\n" token could be the start of "\n\n" — hold it back
+        assert_eq!(matcher.holdback_len("Hello\n"), 1);
+        // Second "\n" token completes the sequence
+        assert_eq!(matcher.find_match("Hello\n\n", 0), Some(5));
+        assert_eq!(matcher.holdback_len("Hello"), 0);
+    }
+
+    #[test]
+    fn test_stop_matcher_holdback_uses_longest_prefix() {
+        let matcher = StopSequenceMatcher::new(&["</answer>".to_string()]);
+        assert_eq!(matcher.holdback_len("text </ans"), 5);
+        assert_eq!(matcher.holdback_len("text <"), 1);
+        assert_eq!(matcher.holdback_len("text </x"), 0);
+    }
+
+    #[test]
+    fn test_stop_matcher_ignores_empty_sequences() {
+        let matcher = StopSequenceMatcher::new(&["".to_string()]);
+        assert_eq!(matcher.find_match("anything", 0), None);
+        assert_eq!(matcher.holdback_len("anything"), 0);
+    }
+
+    #[test]
+    fn test_stop_matcher_respects_char_boundaries() {
+        let matcher = StopSequenceMatcher::new(&["世界!".to_string()]);
+        assert_eq!(matcher.holdback_len("Hello 世界"), "世界".len());
+        assert_eq!(matcher.find_match("Hello 世界!", 0), Some(6));
+    }
+
+    #[test]
+    fn test_inference_request_accepts_stop_alias() {
+        let json = serde_json::json!({
+            "model_id": "test",
+            "prompt": "hi",
+            "max_tokens": 10,
+            "temperature": 0.7,
+            "top_p": 0.9,
+            "top_k": 40,
+            "min_p": 0.0,
+            "stop": ["\n\n"],
+            "stream": false
+        });
+        let req: InferenceRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(req.stop_sequences, vec!["\n\n".to_string()]);
+    }
+
+    // === Logit Bias Tests ===
+
+    #[test]
+    fn test_validate_logit_bias_clamps_values() {
+        let mut bias = HashMap::new();
+        bias.insert(5u32, -500.0f32);
+        bias.insert(2u32, 250.0f32);
+        bias.insert(9u32, 1.5f32);
+        let result = validate_logit_bias(&bias, 10).unwrap();
+        assert_eq!(result, vec![(2, 100.0), (5, -100.0), (9, 1.5)]);
+    }
+
+    #[test]
+    fn test_validate_logit_bias_rejects_out_of_vocab() {
+        let mut bias = HashMap::new();
+        bias.insert(32000u32, -100.0f32);
+        let err = validate_logit_bias(&bias, 32000).unwrap_err();
+        assert!(err.to_string().contains("logit_bias"));
+    }
+
+    #[test]
+    fn test_validate_logit_bias_rejects_non_finite() {
+        let mut bias = HashMap::new();
+        bias.insert(1u32, f32::NAN);
+        assert!(validate_logit_bias(&bias, 10).is_err());
+    }
+
+    #[test]
+    fn test_sampler_seed_avoids_random_seed() {
+        assert_eq!(sampler_seed(None), 0);
+        assert_eq!(sampler_seed(Some(42)), 42);
+        assert_eq!(sampler_seed(Some(u32::MAX as u64)), u32::MAX - 1);
+        assert_ne!(sampler_seed(Some(1 << 32)), sampler_seed(Some(0)));
+        assert_ne!(sampler_seed(Some(u64::MAX)), LLAMA_RANDOM_SEED);
+    }
+
+    #[test]
+    fn test_system_fingerprint_tracks_model_and_settings() {
+        let model = test_model("llama-7b", 0, 0, 0).config;
+        let engine = EngineConfig::default();
+        let fingerprint = system_fingerprint(&model, None, &engine, false);
+        assert!(fingerprint.starts_with("fp_"));
+        assert_eq!(
+            fingerprint,
+            system_fingerprint(&model, None, &engine, false)
+        );
+
+        let other_model = test_model("mistral-7b", 0, 0, 0).config;
+        assert_ne!(
+            fingerprint,
+            system_fingerprint(&other_model, None, &engine, false)
+        );
+        // Same weights, different settings: only the config half changes
+        let batched = system_fingerprint(&model, None, &engine, true);
+        assert_ne!(fingerprint, batched);
+        assert_eq!(fingerprint[..15], batched[..15]);
+    }
+
+    #[test]
+    fn test_system_fingerprint_uses_file_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        std::fs::write(&path, b"GGUF weights v1").unwrap();
+        let v1 = file_digest(&path).unwrap();
+        assert_eq!(v1, file_digest(&path).unwrap());
+        std::fs::write(&path, b"GGUF weights v2").unwrap();
+        let v2 = file_digest(&path).unwrap();
+        assert_ne!(v1, v2);
+
+        // Replacing the file under the same path changes the fingerprint;
+        // the same file under another path does not
+        let engine = EngineConfig::default();
+        let model = test_model("llama-7b", 0, 0, 0).config;
+        let moved = test_model("llama-7b-copy", 0, 0, 0).config;
+        assert_ne!(
+            system_fingerprint(&model, Some(&v1), &engine, false),
+            system_fingerprint(&model, Some(&v2), &engine, false)
+        );
+        assert_eq!(
+            system_fingerprint(&model, Some(&v1), &engine, false),
+            system_fingerprint(&moved, Some(&v1), &engine, false)
+        );
+    }
+
+    #[test]
+    fn test_inference_request_logit_bias_string_keys() {
+        let json = serde_json::json!({
+            "model_id": "test",
+            "prompt": "hi",
+            "max_tokens": 10,
+            "temperature": 0.7,
+            "top_p": 0.9,
+            "top_k": 40,
+            "min_p": 0.0,
+            "logit_bias": {"50256": -100.0, "13": 5.0},
+            "stream": false
+        });
+        let req: InferenceRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(req.logit_bias.get(&50256), Some(&-100.0));
+        assert_eq!(req.logit_bias.get(&13), Some(&5.0));
+    }
+
+    // === Timeout Tests ===
+
+    #[test]
+    fn test_inference_request_timeout_defaults_to_none() {
+        let json = serde_json::json!({
+            "model_id": "test",
+            "prompt": "hi",
+            "max_tokens": 10,
+            "temperature": 0.7,
+            "top_p": 0.9,
+            "top_k": 40,
+            "min_p": 0.0,
+            "stream": false
+        });
+        let req: InferenceRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(req.timeout_ms, None);
+    }
+
+    #[test]
+    fn test_timeout_error_carries_partial_result() {
+        let err: anyhow::Error = InferenceError::Timeout {
+            timeout_ms: 500,
+            partial: Box::new(InferenceResult {
+                text: "partial".to_string(),
+                tokens_generated: 3,
+                generation_time: Duration::from_millis(500),
+                tokens_per_second: 6.0,
+                model_id: "test".to_string(),
+                finish_reason: "timeout".to_string(),
+                token_info: vec![],
+                was_cancelled: false,
+                logprobs: None,
+                system_fingerprint: None,
+                context_usage: None,
+            }),
+        }
+        .into();
+
+        assert!(err.to_string().contains("500ms"));
+        match err.downcast_ref::<InferenceError>() {
+            Some(InferenceError::Timeout { partial, .. }) => {
+                assert_eq!(partial.tokens_generated, 3);
+                assert_eq!(partial.text, "partial");
+            }
+            None => panic!("expected InferenceError::Timeout"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deadline_covers_waiting_before_generation() {
+        let deadline = Deadline {
+            start: Instant::now(),
+            timeout_ms: Some(20),
+        };
+        let err = deadline
+            .run("test", tokio::time::sleep(Duration::from_secs(5)))
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<InferenceError>() {
+            Some(InferenceError::Timeout { partial, .. }) => {
+                assert_eq!(partial.tokens_generated, 0);
+                assert_eq!(partial.finish_reason, "timeout");
+            }
+            None => panic!("expected InferenceError::Timeout"),
+        }
+        assert!(deadline.passed());
+
+        let unlimited = Deadline {
+            start: Instant::now(),
+            timeout_ms: None,
+        };
+        assert_eq!(unlimited.run("test", async { 7 }).await.unwrap(), 7);
+        assert!(!unlimited.passed());
+    }
+
     // === Configurable Penalties Tests (v8.21.3) ===
 
     #[test]
@@ -1268,10 +3073,16 @@ mod tests {
             presence_penalty: 0.2,
             seed: None,
             stop_sequences: vec![],
+            logit_bias: HashMap::new(),
+            response_format: None,
+            chat_template: None,
+            logprobs: None,
+            timeout_ms: None,
             stream: false,
             cancel_flag: None,
             token_sender: None,
             result_sender: None,
+            late_context: None,
         };
         assert_eq!(req.frequency_penalty, 0.1);
         assert_eq!(req.presence_penalty, 0.2);
@@ -1378,4 +3189,324 @@ mod tests {
             "LlamaSampler::penalties() must use request.presence_penalty, not hardcoded 0.0"
         );
     }
+
+    #[test]
+    fn test_gguf_metadata_from_kv() {
+        let kv: BTreeMap<String, String> = [
+            ("general.architecture", "llama"),
+            ("general.name", "Tiny Vicuna"),
+            ("general.file_type", "15"),
+            ("llama.context_length", "2048"),
+            ("llama.embedding_length", "2048"),
+            ("llama.block_count", "22"),
+            ("llama.rope.freq_base", "10000.000000"),
+            ("llama.rope.scaling.type", "linear"),
+            (
+                "tokenizer.chat_template",
+                "{% for m in messages %}...{% endfor %}",
+            ),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let meta = GgufMetadata::from_kv(kv, "vicuna");
+        assert_eq!(meta.architecture.as_deref(), Some("llama"));
+        assert_eq!(meta.name.as_deref(), Some("Tiny Vicuna"));
+        assert_eq!(meta.context_length, Some(2048));
+        assert_eq!(meta.embedding_length, Some(2048));
+        assert_eq!(meta.block_count, Some(22));
+        assert_eq!(meta.rope_freq_base, Some(10000.0));
+        assert_eq!(meta.rope_scaling_type.as_deref(), Some("linear"));
+        assert_eq!(meta.rope_scaling_factor, None);
+        assert_eq!(meta.quantization.as_deref(), Some("Q4_K_M"));
+        assert_eq!(meta.chat_template, "vicuna");
+        assert!(meta.has_embedded_chat_template);
+        assert_eq!(meta.kv.len(), 9);
+    }
+
+    #[test]
+    fn test_gguf_metadata_missing_architecture() {
+        let kv: BTreeMap<String, String> = [("llama.context_length", "4096")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        let meta = GgufMetadata::from_kv(kv, "harmony");
+        assert_eq!(meta.architecture, None);
+        assert_eq!(meta.context_length, None);
+        assert_eq!(meta.quantization, None);
+        assert!(!meta.has_embedded_chat_template);
+    }
+
+    #[test]
+    fn test_gguf_file_type_name() {
+        assert_eq!(gguf_file_type_name(7), "Q8_0");
+        assert_eq!(gguf_file_type_name(1), "F16");
+        assert_eq!(gguf_file_type_name(99), "unknown(99)");
+    }
+
+    // === Logprobs ===
+
+    #[test]
+    fn test_token_logprobs_matches_softmax() {
+        let logits = [1.0f32, 3.0, 2.0, 0.0];
+        let (chosen, top) = token_logprobs(&logits, 2, 2);
+
+        let norm: f32 = logits.iter().map(|l| l.exp()).sum();
+        assert!((chosen - (2.0f32.exp() / norm).ln()).abs() < 1e-5);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].0, 1);
+        assert_eq!(top[1].0, 2);
+        assert!((top[0].1 - (3.0f32.exp() / norm).ln()).abs() < 1e-5);
+        assert!((top[1].1 - chosen).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_token_logprobs_without_alternatives() {
+        let (chosen, top) = token_logprobs(&[0.0, 0.0], 0, 0);
+        assert!((chosen - 0.5f32.ln()).abs() < 1e-6);
+        assert!(top.is_empty());
+
+        let (chosen, top) = token_logprobs(&[5.0, 1.0], 1, 10);
+        assert!(chosen < 0.0);
+        assert_eq!(top.len(), 2);
+        assert_eq!(token_logprobs(&[1.0], 7, 0).0, f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_token_logprobs_from_token_info() {
+        let mut token = TokenInfo {
+            token_id: 42,
+            text: "hi".to_string(),
+            logprob: None,
+            timestamp: None,
+            top_logprobs: vec![],
+        };
+        assert_eq!(TokenLogprobs::from_token_info(&token), None);
+
+        token.logprob = Some(-0.25);
+        let logprobs = TokenLogprobs::from_token_info(&token).unwrap();
+        assert_eq!(logprobs.token_id, 42);
+        assert_eq!(logprobs.logprob, -0.25);
+
+        let json = serde_json::to_value(&token).unwrap();
+        assert!(json.get("top_logprobs").is_none());
+    }
+
+    // === Multi-model routing ===
+
+    fn test_model(id: &str, loaded_secs: u64, used_secs: u64, usage_count: usize) -> Model {
+        let epoch = std::time::UNIX_EPOCH;
+        Model {
+            id: id.to_string(),
+            config: ModelConfig {
+                model_path: PathBuf::from(format!("/models/{}.gguf", id)),
+                model_type: "llama".to_string(),
+                context_size: 2048,
+                gpu_layers: 0,
+                rope_freq_base: 10000.0,
+                rope_freq_scale: 1.0,
+                chat_template: None,
+            },
+            status: ModelStatus::Ready,
+            loaded_at: epoch + Duration::from_secs(loaded_secs),
+            usage_count,
+            last_used: epoch + Duration::from_secs(used_secs),
+            metadata: None,
+            file_digest: None,
+        }
+    }
+
+    #[test]
+    fn test_select_eviction_candidate_policies() {
+        let models = vec![
+            test_model("a", 1, 30, 5),
+            test_model("b", 2, 10, 9),
+            test_model("c", 3, 20, 1),
+        ];
+        let none = HashSet::new();
+
+        assert_eq!(
+            select_eviction_candidate(models.iter(), &none, "lru").as_deref(),
+            Some("b")
+        );
+        assert_eq!(
+            select_eviction_candidate(models.iter(), &none, "lfu").as_deref(),
+            Some("c")
+        );
+        assert_eq!(
+            select_eviction_candidate(models.iter(), &none, "FIFO").as_deref(),
+            Some("a")
+        );
+        assert_eq!(
+            select_eviction_candidate(models.iter(), &none, "random").as_deref(),
+            Some("b")
+        );
+    }
+
+    #[test]
+    fn test_select_eviction_candidate_skips_loading_models() {
+        let mut loading = test_model("loading", 0, 0, 0);
+        loading.status = ModelStatus::Loading;
+        let models = vec![loading, test_model("ready", 5, 5, 5)];
+        let none = HashSet::new();
+
+        assert_eq!(
+            select_eviction_candidate(models.iter(), &none, "lru").as_deref(),
+            Some("ready")
+        );
+        assert_eq!(
+            select_eviction_candidate(models[..1].iter(), &none, "lru"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_select_eviction_candidate_skips_pinned_models() {
+        let models = vec![test_model("hot", 1, 1, 1), test_model("cold", 2, 2, 2)];
+        let mut pinned = HashSet::new();
+        pinned.insert("hot".to_string());
+
+        assert_eq!(
+            select_eviction_candidate(models.iter(), &pinned, "lru").as_deref(),
+            Some("cold")
+        );
+
+        pinned.insert("cold".to_string());
+        assert_eq!(
+            select_eviction_candidate(models.iter(), &pinned, "lru"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pin_and_unpin_model() {
+        let engine = LlmEngine::new(EngineConfig::default()).await.unwrap();
+        assert!(engine.pin_model("extra").await.is_err());
+
+        engine
+            .register_model("extra", test_model("extra", 0, 0, 0).config)
+            .await;
+        engine.pin_model("extra").await.unwrap();
+        assert!(engine.is_model_pinned("extra").await);
+
+        assert!(engine.unpin_model("extra").await);
+        assert!(!engine.is_model_pinned("extra").await);
+        assert!(!engine.unpin_model("extra").await);
+    }
+
+    #[tokio::test]
+    async fn test_default_model_is_evicted_last() {
+        let engine = LlmEngine::new(EngineConfig {
+            max_loaded_models: 2,
+            ..EngineConfig::default()
+        })
+        .await
+        .unwrap();
+        {
+            let mut info = engine.model_info.write().await;
+            info.insert("default".to_string(), test_model("default", 0, 0, 0));
+            info.insert("extra".to_string(), test_model("extra", 1, 1, 1));
+        }
+        engine.set_default_model("default").await;
+        assert!(engine.is_model_ready("default").await);
+
+        // The least recently used model is the default, so the other goes
+        engine.evict_for_new_model().await.unwrap();
+        assert_eq!(engine.list_loaded_models().await, vec!["default"]);
+
+        // A pinned default stays even when nothing else can make way
+        engine.pin_model("default").await.unwrap();
+        engine
+            .model_info
+            .write()
+            .await
+            .insert("extra".to_string(), test_model("extra", 1, 1, 1));
+        engine.pin_model("extra").await.unwrap();
+        let err = engine.evict_for_new_model().await.unwrap_err();
+        assert!(err.to_string().contains("pinned"));
+    }
+
+    #[tokio::test]
+    async fn test_second_model_loads_with_limit_of_one() {
+        let engine = LlmEngine::new(EngineConfig {
+            max_loaded_models: 1,
+            ..EngineConfig::default()
+        })
+        .await
+        .unwrap();
+        let default = test_model("default", 0, 0, 0);
+        let extra = test_model("extra", 1, 1, 1);
+        engine
+            .register_model("default", default.config.clone())
+            .await;
+        engine.register_model("extra", extra.config.clone()).await;
+        engine
+            .model_info
+            .write()
+            .await
+            .insert("default".to_string(), default);
+        engine.set_default_model("default").await;
+
+        // Loading "extra" unloads the idle default instead of failing
+        engine.evict_for_new_model().await.unwrap();
+        engine
+            .model_info
+            .write()
+            .await
+            .insert("extra".to_string(), extra);
+        assert_eq!(engine.list_loaded_models().await, vec!["extra"]);
+
+        // The default is still registered, so its next request reloads it
+        assert!(engine.is_model_known("default").await);
+    }
+
+    #[tokio::test]
+    async fn test_draft_model_lookups_do_not_wait_on_each_other() {
+        let engine = LlmEngine::new(EngineConfig {
+            draft_model: Some(DraftModelConfig {
+                model_path: PathBuf::from("/nonexistent/draft.gguf"),
+                gpu_layers: 0,
+                draft_tokens: DEFAULT_DRAFT_TOKENS,
+            }),
+            ..EngineConfig::default()
+        })
+        .await
+        .unwrap();
+
+        // Generations starting together each get their own handle; the
+        // lookup returns without leaving anything locked for the decode
+        let held = engine.draft_model();
+        std::thread::scope(|scope| {
+            let lookups: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| engine.draft_model()))
+                .collect();
+            for lookup in lookups {
+                assert!(lookup.join().unwrap().is_none());
+            }
+        });
+        assert!(held.is_none());
+
+        // A failed load is remembered instead of retried per generation
+        assert!(matches!(engine.draft_model.get(), Some(None)));
+    }
+
+    #[tokio::test]
+    async fn test_register_model_is_known_but_not_loaded() {
+        let engine = LlmEngine::new(EngineConfig::default()).await.unwrap();
+        let config = test_model("extra", 0, 0, 0).config;
+
+        assert!(!engine.is_model_known("extra").await);
+        engine.register_model("extra", config).await;
+        assert!(engine.is_model_known("extra").await);
+        assert!(engine.list_loaded_models().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_model_loaded_unknown_model() {
+        let engine = LlmEngine::new(EngineConfig::default()).await.unwrap();
+        let err = engine.ensure_model_loaded("missing").await.unwrap_err();
+        assert!(err.to_string().contains("Model not found"));
+    }
 }
@@ -0,0 +1,470 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Grammar-constrained decoding for structured output
+//!
+//! A request's `response_format` is compiled into a GBNF grammar which the
+//! engine hands to llama.cpp's grammar sampler. The sampler masks every token
+//! that cannot continue a valid document, so the model can only produce
+//! output matching the schema instead of being checked after the fact.
+//!
+//! Supported JSON schema subset: `type` (object, array, string, number,
+//! integer, boolean, null, or a list of these), `properties`, `required`,
+//! `items`, `enum`, `const`, `anyOf` and `oneOf`. Object properties are
+//! emitted in the order of the schema's `properties` map; properties not
+//! listed in `required` are optional and extra properties are not allowed.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Name of the start rule in every compiled grammar
+pub const GRAMMAR_ROOT: &str = "root";
+
+/// Maximum schema nesting depth accepted by the compiler
+pub const MAX_SCHEMA_DEPTH: usize = 32;
+
+/// Output format requested by the client (`response_format`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Unconstrained text (the default)
+    Text,
+    /// Any syntactically valid JSON object
+    JsonObject,
+    /// JSON matching the supplied schema
+    JsonSchema { schema: Value },
+}
+
+impl ResponseFormat {
+    /// Compiles the format into a GBNF grammar; `None` means unconstrained
+    pub fn to_gbnf(&self) -> Result<Option<String>> {
+        match self {
+            ResponseFormat::Text => Ok(None),
+            ResponseFormat::JsonObject => {
+                json_schema_to_gbnf(&json!({ "type": "object" })).map(Some)
+            }
+            ResponseFormat::JsonSchema { schema } => json_schema_to_gbnf(schema).map(Some),
+        }
+    }
+}
+
+/// Compiles a JSON schema into a GBNF grammar whose start rule is `root`
+pub fn json_schema_to_gbnf(schema: &Value) -> Result<String> {
+    let mut builder = GrammarBuilder::default();
+    let rule = builder.visit(schema, GRAMMAR_ROOT, 0)?;
+    if rule != GRAMMAR_ROOT {
+        builder.rules.insert(GRAMMAR_ROOT.to_string(), rule);
+    }
+
+    let mut grammar = format!("{} ::= {}\n", GRAMMAR_ROOT, builder.rules[GRAMMAR_ROOT]);
+    for (name, body) in builder.rules.iter().filter(|(n, _)| *n != GRAMMAR_ROOT) {
+        grammar.push_str(&format!("{} ::= {}\n", name, body));
+    }
+    Ok(grammar)
+}
+
+/// Built-in rules for JSON primitives as (name, body, dependencies)
+const PRIMITIVES: &[(&str, &str, &[&str])] = &[
+    ("ws", r#"| " " | "\n" [ \t]{0,20}"#, &[]),
+    (
+        "char",
+        r#"[^"\\\x7F\x00-\x1F] | "\\" (["\\bfnrt/] | "u" [0-9a-fA-F]{4})"#,
+        &[],
+    ),
+    ("string", r#""\"" char* "\"" ws"#, &["char", "ws"]),
+    ("integral-part", r#"[0] | [1-9] [0-9]{0,15}"#, &[]),
+    (
+        "number",
+        r#"("-"? integral-part) ("." [0-9]+)? ([eE] [-+]? [0-9]+)? ws"#,
+        &["integral-part", "ws"],
+    ),
+    (
+        "integer",
+        r#""-"? integral-part ws"#,
+        &["integral-part", "ws"],
+    ),
+    ("boolean", r#"("true" | "false") ws"#, &["ws"]),
+    ("null", r#""null" ws"#, &["ws"]),
+    (
+        "value",
+        "object | array | string | number | boolean | null",
+        &["object", "array", "string", "number", "boolean", "null"],
+    ),
+    (
+        "object",
+        r#""{" ws ( string ":" ws value ("," ws string ":" ws value)* )? "}" ws"#,
+        &["ws", "string", "value"],
+    ),
+    (
+        "array",
+        r#""[" ws ( value ("," ws value)* )? "]" ws"#,
+        &["ws", "value"],
+    ),
+];
+
+#[derive(Default)]
+struct GrammarBuilder {
+    rules: BTreeMap<String, String>,
+}
+
+impl GrammarBuilder {
+    /// Adds a built-in rule (and the rules it references), returning its name
+    fn primitive(&mut self, name: &str) -> String {
+        if !self.rules.contains_key(name) {
+            let (_, body, deps) = PRIMITIVES
+                .iter()
+                .find(|(n, _, _)| *n == name)
+                .expect("unknown primitive rule");
+            self.rules.insert(name.to_string(), body.to_string());
+            for dep in deps.iter() {
+                self.primitive(dep);
+            }
+        }
+        name.to_string()
+    }
+
+    /// Adds a schema-derived rule, reusing an identical existing rule
+    fn add_rule(&mut self, name: &str, body: String) -> String {
+        let base: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let mut candidate = base.clone();
+        let mut suffix = 1;
+        while let Some(existing) = self.rules.get(&candidate) {
+            if *existing == body {
+                return candidate;
+            }
+            candidate = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        self.rules.insert(candidate.clone(), body);
+        candidate
+    }
+
+    fn visit(&mut self, schema: &Value, name: &str, depth: usize) -> Result<String> {
+        if depth > MAX_SCHEMA_DEPTH {
+            return Err(anyhow!(
+                "JSON schema is nested deeper than {} levels",
+                MAX_SCHEMA_DEPTH
+            ));
+        }
+
+        let schema = match schema {
+            Value::Bool(true) => return Ok(self.primitive("value")),
+            Value::Object(schema) => schema,
+            other => return Err(anyhow!("Unsupported JSON schema: {}", other)),
+        };
+
+        if schema.contains_key("$ref") {
+            return Err(anyhow!("JSON schema $ref is not supported"));
+        }
+
+        if let Some(values) = schema.get("enum") {
+            let values = values
+                .as_array()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| anyhow!("JSON schema enum must be a non-empty array"))?;
+            return Ok(self.add_literals(name, values));
+        }
+
+        if let Some(value) = schema.get("const") {
+            return Ok(self.add_literals(name, std::slice::from_ref(value)));
+        }
+
+        if let Some(variants) = schema.get("anyOf").or_else(|| schema.get("oneOf")) {
+            let variants = variants
+                .as_array()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| anyhow!("JSON schema anyOf/oneOf must be a non-empty array"))?;
+            let alternatives = variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| self.visit(variant, &format!("{}-{}", name, i), depth + 1))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(self.add_rule(name, alternatives.join(" | ")));
+        }
+
+        match schema.get("type") {
+            Some(Value::String(schema_type)) => self.visit_type(schema_type, schema, name, depth),
+            Some(Value::Array(types)) => {
+                let alternatives = types
+                    .iter()
+                    .map(|t| {
+                        let t = t
+                            .as_str()
+                            .ok_or_else(|| anyhow!("JSON schema type must be a string"))?;
+                        self.visit_type(t, schema, &format!("{}-{}", name, t), depth)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(self.add_rule(name, alternatives.join(" | ")))
+            }
+            Some(other) => Err(anyhow!("Unsupported JSON schema type: {}", other)),
+            None if schema.contains_key("properties") => {
+                self.visit_type("object", schema, name, depth)
+            }
+            None if schema.contains_key("items") => self.visit_type("array", schema, name, depth),
+            None => Ok(self.primitive("value")),
+        }
+    }
+
+    fn visit_type(
+        &mut self,
+        schema_type: &str,
+        schema: &Map<String, Value>,
+        name: &str,
+        depth: usize,
+    ) -> Result<String> {
+        match schema_type {
+            "object" => self.visit_object(schema, name, depth),
+            "array" => {
+                let item = match schema.get("items") {
+                    Some(items) => self.visit(items, &format!("{}-item", name), depth + 1)?,
+                    None => self.primitive("value"),
+                };
+                self.primitive("ws");
+                Ok(self.add_rule(
+                    name,
+                    format!(r#""[" ws ( {item} ("," ws {item})* )? "]" ws"#, item = item),
+                ))
+            }
+            "string" | "number" | "integer" | "boolean" | "null" => Ok(self.primitive(schema_type)),
+            other => Err(anyhow!("Unsupported JSON schema type: {}", other)),
+        }
+    }
+
+    fn visit_object(
+        &mut self,
+        schema: &Map<String, Value>,
+        name: &str,
+        depth: usize,
+    ) -> Result<String> {
+        let properties = match schema.get("properties").and_then(Value::as_object) {
+            Some(properties) if !properties.is_empty() => properties,
+            _ => return Ok(self.primitive("object")),
+        };
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        self.primitive("ws");
+        let mut required_kvs = Vec::new();
+        let mut optional_kvs = Vec::new();
+        for (key, property) in properties {
+            let value = self.visit(property, &format!("{}-{}", name, key), depth + 1)?;
+            let kv = format!(
+                r#"{} ":" ws {}"#,
+                gbnf_literal(&Value::String(key.clone())),
+                value
+            );
+            let kv = self.add_rule(&format!("{}-{}-kv", name, key), kv);
+            if required.contains(&key.as_str()) {
+                required_kvs.push(kv);
+            } else {
+                optional_kvs.push(kv);
+            }
+        }
+
+        let mut body = String::from(r#""{" ws "#);
+        if required_kvs.is_empty() {
+            // Any subset of the optional properties, in property order
+            let alternatives: Vec<String> = (0..optional_kvs.len())
+                .map(|first| {
+                    let mut alternative = optional_kvs[first].clone();
+                    for kv in &optional_kvs[first + 1..] {
+                        alternative.push_str(&format!(r#" ("," ws {})?"#, kv));
+                    }
+                    alternative
+                })
+                .collect();
+            body.push_str(&format!("( {} )? ", alternatives.join(" | ")));
+        } else {
+            body.push_str(&required_kvs.join(r#" "," ws "#));
+            for kv in &optional_kvs {
+                body.push_str(&format!(r#" ("," ws {})?"#, kv));
+            }
+            body.push(' ');
+        }
+        body.push_str(r#""}" ws"#);
+
+        Ok(self.add_rule(name, body))
+    }
+
+    /// Rule matching exactly one of the given JSON values
+    fn add_literals(&mut self, name: &str, values: &[Value]) -> String {
+        self.primitive("ws");
+        let alternatives: Vec<String> = values.iter().map(gbnf_literal).collect();
+        self.add_rule(name, format!("({}) ws", alternatives.join(" | ")))
+    }
+}
+
+/// Quotes the JSON serialization of `value` as a GBNF string literal
+fn gbnf_literal(value: &Value) -> String {
+    let json = value.to_string();
+    let mut literal = String::with_capacity(json.len() + 2);
+    literal.push('"');
+    for c in json.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule<'a>(grammar: &'a str, name: &str) -> &'a str {
+        let prefix = format!("{} ::= ", name);
+        grammar
+            .lines()
+            .find_map(|line| line.strip_prefix(prefix.as_str()))
+            .unwrap_or_else(|| panic!("rule {} missing from:\n{}", name, grammar))
+    }
+
+    #[test]
+    fn test_response_format_deserializes() {
+        let format: ResponseFormat = serde_json::from_value(json!({
+            "type": "json_schema",
+            "schema": { "type": "string" }
+        }))
+        .unwrap();
+        assert_eq!(
+            format,
+            ResponseFormat::JsonSchema {
+                schema: json!({ "type": "string" })
+            }
+        );
+
+        let text: ResponseFormat = serde_json::from_value(json!({ "type": "text" })).unwrap();
+        assert_eq!(text.to_gbnf().unwrap(), None);
+    }
+
+    #[test]
+    fn test_json_object_format_uses_generic_object() {
+        let grammar = ResponseFormat::JsonObject.to_gbnf().unwrap().unwrap();
+        assert_eq!(rule(&grammar, "root"), "object");
+        assert!(grammar.contains("value ::= "));
+        assert!(grammar.contains("string ::= "));
+    }
+
+    #[test]
+    fn test_object_with_required_and_optional_properties() {
+        let grammar = json_schema_to_gbnf(&json!({
+            "type": "object",
+            "properties": {
+                "age": { "type": "integer" },
+                "name": { "type": "string" },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["name", "age"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            rule(&grammar, "root"),
+            r#""{" ws root-age-kv "," ws root-name-kv ("," ws root-tags-kv)? "}" ws"#
+        );
+        assert_eq!(
+            rule(&grammar, "root-name-kv"),
+            r#""\"name\"" ":" ws string"#
+        );
+        assert_eq!(rule(&grammar, "root-age-kv"), r#""\"age\"" ":" ws integer"#);
+        assert_eq!(
+            rule(&grammar, "root-tags"),
+            r#""[" ws ( string ("," ws string)* )? "]" ws"#
+        );
+    }
+
+    #[test]
+    fn test_object_with_only_optional_properties() {
+        let grammar = json_schema_to_gbnf(&json!({
+            "properties": {
+                "a": { "type": "number" },
+                "b": { "type": "boolean" }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            rule(&grammar, "root"),
+            r#""{" ws ( root-a-kv ("," ws root-b-kv)? | root-b-kv )? "}" ws"#
+        );
+    }
+
+    #[test]
+    fn test_enum_and_const_become_literals() {
+        let grammar = json_schema_to_gbnf(&json!({
+            "type": "object",
+            "properties": {
+                "color": { "enum": ["red", "say \"hi\"", 3, null] },
+                "kind": { "const": "fixed" }
+            },
+            "required": ["color", "kind"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            rule(&grammar, "root-color"),
+            r#"("\"red\"" | "\"say \\\"hi\\\"\"" | "3" | "null") ws"#
+        );
+        assert_eq!(rule(&grammar, "root-kind"), r#"("\"fixed\"") ws"#);
+    }
+
+    #[test]
+    fn test_any_of_and_type_lists() {
+        let grammar = json_schema_to_gbnf(&json!({
+            "anyOf": [{ "type": "string" }, { "type": ["number", "null"] }]
+        }))
+        .unwrap();
+
+        assert_eq!(rule(&grammar, "root"), "string | root-1");
+        assert_eq!(rule(&grammar, "root-1"), "number | null");
+    }
+
+    #[test]
+    fn test_nested_objects_get_unique_rule_names() {
+        let grammar = json_schema_to_gbnf(&json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": { "id": { "type": "integer" } },
+                "required": ["id"]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            rule(&grammar, "root"),
+            r#""[" ws ( root-item ("," ws root-item)* )? "]" ws"#
+        );
+        assert_eq!(
+            rule(&grammar, "root-item"),
+            r#""{" ws root-item-id-kv "}" ws"#
+        );
+    }
+
+    #[test]
+    fn test_unsupported_schemas_are_rejected() {
+        assert!(json_schema_to_gbnf(&json!({ "$ref": "#/definitions/x" })).is_err());
+        assert!(json_schema_to_gbnf(&json!({ "type": "date" })).is_err());
+        assert!(json_schema_to_gbnf(&json!({ "enum": [] })).is_err());
+        assert!(json_schema_to_gbnf(&json!("string")).is_err());
+
+        let mut deep = json!({ "type": "string" });
+        for _ in 0..=MAX_SCHEMA_DEPTH {
+            deep = json!({ "type": "array", "items": deep });
+        }
+        assert!(json_schema_to_gbnf(&deep).is_err());
+    }
+}
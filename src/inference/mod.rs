@@ -3,28 +3,44 @@
 // Export all submodules and their public types
 pub mod cache;
 pub mod chat_template;
+pub mod continuous_batching;
 pub mod engine;
 pub mod format;
+pub mod grammar;
 pub mod models;
+pub mod speculative;
+pub mod tools;
 
 // Re-export main types for convenience
-pub use chat_template::ChatTemplate;
+pub use chat_template::{ChatTemplate, ChatTemplateOverride, CustomChatTemplate, TemplateError};
+pub use continuous_batching::{
+    BatchBackend, ContinuousBatchConfig, ContinuousBatchMetrics, ContinuousScheduler, FinishReason,
+    FinishedSequence, StepInput,
+};
 pub use engine::{
-    get_penalty_defaults, ChatMessage, ContextUsage, EngineCapabilities, EngineConfig,
-    EngineMetrics, InferenceHandle, InferenceRequest, InferenceResult, LlmEngine, Model,
-    ModelCapabilities, ModelCapability, ModelConfig, TokenInfo, TokenStream,
+    get_penalty_defaults, system_fingerprint, ChatMessage, ContextUsage, EngineCapabilities,
+    EngineConfig, EngineMetrics, GgufMetadata, InferenceError, InferenceHandle, InferenceRequest,
+    InferenceResult, LateContext, LlmEngine, Model, ModelCapabilities, ModelCapability,
+    ModelConfig, TokenInfo, TokenLogprobs, TokenStream, TopLogprob, LATE_CONTEXT_TOKEN_WINDOW,
+    LOGIT_BIAS_LIMIT, MAX_LOGPROBS,
 };
 
 // Create alias for all uses (tests expect this name)
 pub use cache::{
-    CacheConfig, CacheEntry, CacheKey, CacheStats, EvictionPolicy, InferenceCache, SemanticCache,
+    CacheConfig, CacheEntry, CacheKey, CacheStats, EvictionPolicy, InferenceCache, PrefixCache,
+    PrefixCacheConfig, PrefixCacheEntry, PrefixCacheStats, PrefixMatch, SemanticCache,
 };
 pub use engine::LlmEngine as InferenceEngine;
 pub use format::{
     Citation, ContentFilter, FormatConfig, OutputFormat, ResultFormatter, SafetyCheck,
 };
+pub use grammar::{json_schema_to_gbnf, ResponseFormat};
 pub use models::{
     CleanupPolicy, CleanupResult, DownloadProgress, ModelEvent, ModelEventType, ModelInfo,
     ModelManager, ModelMetadata, ModelRegistry, ModelRequest, ModelRequirements, ModelSource,
     ModelStatus, PreloadHandle, StorageUsage, SystemInfo,
 };
+pub use speculative::{DraftModelConfig, SpeculativeStats, DEFAULT_DRAFT_TOKENS};
+pub use tools::{
+    FunctionCall, FunctionDefinition, ParsedToolCalls, Tool, ToolCall, TOOL_CALLS_FINISH_REASON,
+};
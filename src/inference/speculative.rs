@@ -0,0 +1,262 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Speculative decoding with a draft model
+//!
+//! A small draft model sharing the main model's vocabulary proposes a few
+//! tokens greedily; the main model decodes them all in one batch. The engine
+//! keeps sampling from the main model's logits at each drafted position and
+//! only continues while its samples agree with the draft, so the output is
+//! exactly what normal decoding would produce.
+
+use anyhow::{anyhow, Result};
+use llama_cpp_2::{
+    context::{params::LlamaContextParams, LlamaContext},
+    llama_backend::LlamaBackend,
+    llama_batch::LlamaBatch,
+    model::LlamaModel,
+    sampling::LlamaSampler,
+    token::LlamaToken,
+};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// Tokens proposed per verification pass when not configured
+pub const DEFAULT_DRAFT_TOKENS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct DraftModelConfig {
+    pub model_path: PathBuf,
+    pub gpu_layers: usize,
+    /// Tokens the draft proposes per main-model pass
+    pub draft_tokens: usize,
+}
+
+impl DraftModelConfig {
+    /// Reads DRAFT_MODEL_PATH, DRAFT_MODEL_GPU_LAYERS and
+    /// SPECULATIVE_DRAFT_TOKENS; `None` when no draft model is set
+    pub fn from_env() -> Option<Self> {
+        let model_path = std::env::var("DRAFT_MODEL_PATH")
+            .ok()
+            .filter(|p| !p.is_empty())?;
+        Some(Self {
+            model_path: PathBuf::from(model_path),
+            gpu_layers: std::env::var("DRAFT_MODEL_GPU_LAYERS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(99),
+            draft_tokens: std::env::var("SPECULATIVE_DRAFT_TOKENS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(DEFAULT_DRAFT_TOKENS),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpeculativeStats {
+    pub drafted: usize,
+    pub accepted: usize,
+}
+
+impl SpeculativeStats {
+    pub fn acceptance_rate(&self) -> f32 {
+        if self.drafted == 0 {
+            0.0
+        } else {
+            self.accepted as f32 / self.drafted as f32
+        }
+    }
+}
+
+/// Draft-and-verify state for one generation, replacing the single-token
+/// decode of the main loop
+pub(crate) struct SpeculativeDecoder<'a> {
+    draft: &'a LlamaModel,
+    draft_context: LlamaContext<'a>,
+    draft_batch: LlamaBatch,
+    batch_size: usize,
+    max_draft: usize,
+    /// Prompt plus every generated token, by position
+    history: Vec<LlamaToken>,
+    /// Tokens currently in the draft KV cache, by position
+    draft_cached: Vec<LlamaToken>,
+    /// Drafted tokens already decoded by the main model, not yet verified
+    pending: VecDeque<LlamaToken>,
+    /// Batch index of the main-model logits for the next sample
+    logits_index: i32,
+    stats: SpeculativeStats,
+}
+
+impl<'a> SpeculativeDecoder<'a> {
+    pub(crate) fn new(
+        draft: &'a LlamaModel,
+        backend: &LlamaBackend,
+        params: LlamaContextParams,
+        batch_size: usize,
+        max_draft: usize,
+        prompt: &[LlamaToken],
+    ) -> Result<Self> {
+        let draft_context = draft
+            .new_context(backend, params)
+            .map_err(|e| anyhow!("Failed to create draft context: {:?}", e))?;
+        Ok(Self {
+            draft,
+            draft_context,
+            draft_batch: LlamaBatch::new(batch_size, 1),
+            batch_size,
+            max_draft: max_draft.max(1),
+            history: prompt.to_vec(),
+            draft_cached: Vec::new(),
+            pending: VecDeque::new(),
+            logits_index: -1,
+            stats: SpeculativeStats::default(),
+        })
+    }
+
+    /// Where the main sampler should read logits for the next token
+    pub(crate) fn sample_index(&self) -> i32 {
+        self.logits_index
+    }
+
+    pub(crate) fn stats(&self) -> SpeculativeStats {
+        self.stats
+    }
+
+    /// Put `token` at position `n_cur` of the main context. A token matching
+    /// the next drafted one is already decoded; otherwise the rejected drafts
+    /// are dropped and `token` is decoded together with a fresh draft, up to
+    /// `limit` positions.
+    pub(crate) fn advance(
+        &mut self,
+        context: &mut LlamaContext<'_>,
+        batch: &mut LlamaBatch,
+        token: LlamaToken,
+        n_cur: usize,
+        limit: usize,
+    ) -> Result<()> {
+        self.history.push(token);
+
+        if self.pending.front() == Some(&token) {
+            self.pending.pop_front();
+            self.logits_index += 1;
+            self.stats.accepted += 1;
+            return Ok(());
+        }
+        if !self.pending.is_empty() {
+            self.pending.clear();
+            context
+                .clear_kv_cache_seq(Some(0), Some(n_cur as u32), None)
+                .map_err(|e| anyhow!("Failed to drop rejected draft tokens: {:?}", e))?;
+        }
+
+        let n_draft = self
+            .max_draft
+            .min(limit.saturating_sub(n_cur + 1))
+            .min(self.batch_size.saturating_sub(1));
+        let drafts = if n_draft > 0 {
+            // A failing draft only costs the speedup, never the generation
+            self.propose(n_draft).unwrap_or_else(|e| {
+                tracing::warn!("Draft model failed, decoding without speculation: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+
+        batch.clear();
+        batch
+            .add(token, n_cur as i32, &[0], true)
+            .map_err(|e| anyhow!("Failed to add token: {:?}", e))?;
+        for (i, &draft_token) in drafts.iter().enumerate() {
+            batch
+                .add(draft_token, (n_cur + 1 + i) as i32, &[0], true)
+                .map_err(|e| anyhow!("Failed to add draft token: {:?}", e))?;
+        }
+        context
+            .decode(batch)
+            .map_err(|e| anyhow!("Decode failed: {:?}", e))?;
+
+        self.stats.drafted += drafts.len();
+        self.pending = drafts.into();
+        self.logits_index = 0;
+        Ok(())
+    }
+
+    /// Greedily draft `n` tokens continuing `history`
+    fn propose(&mut self, n: usize) -> Result<Vec<LlamaToken>> {
+        // Keep whatever the draft cache shares with the real sequence, but
+        // always re-decode the last token so fresh logits are available
+        let shared = self
+            .draft_cached
+            .iter()
+            .zip(&self.history)
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(self.history.len() - 1);
+        if shared < self.draft_cached.len() {
+            self.draft_context
+                .clear_kv_cache_seq(Some(0), Some(shared as u32), None)
+                .map_err(|e| anyhow!("Failed to trim draft cache: {:?}", e))?;
+            self.draft_cached.truncate(shared);
+        }
+
+        let catch_up = self.history[shared..].to_vec();
+        for (chunk_idx, chunk) in catch_up.chunks(self.batch_size).enumerate() {
+            let start = shared + chunk_idx * self.batch_size;
+            let is_last_chunk = start + chunk.len() == self.history.len();
+            self.draft_batch.clear();
+            for (i, &token) in chunk.iter().enumerate() {
+                self.draft_batch
+                    .add(
+                        token,
+                        (start + i) as i32,
+                        &[0],
+                        is_last_chunk && i + 1 == chunk.len(),
+                    )
+                    .map_err(|e| anyhow!("Failed to add token to draft batch: {:?}", e))?;
+            }
+            self.draft_context
+                .decode(&mut self.draft_batch)
+                .map_err(|e| anyhow!("Draft decode failed: {:?}", e))?;
+            self.draft_cached.extend_from_slice(chunk);
+        }
+
+        let mut sampler = LlamaSampler::greedy();
+        let mut drafts = Vec::with_capacity(n);
+        for i in 0..n {
+            let token = sampler.sample(&self.draft_context, -1);
+            if token == self.draft.token_eos() {
+                break;
+            }
+            drafts.push(token);
+            if i + 1 == n {
+                break;
+            }
+            self.draft_batch.clear();
+            self.draft_batch
+                .add(token, self.draft_cached.len() as i32, &[0], true)
+                .map_err(|e| anyhow!("Failed to add token to draft batch: {:?}", e))?;
+            self.draft_context
+                .decode(&mut self.draft_batch)
+                .map_err(|e| anyhow!("Draft decode failed: {:?}", e))?;
+            self.draft_cached.push(token);
+        }
+        Ok(drafts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acceptance_rate() {
+        assert_eq!(SpeculativeStats::default().acceptance_rate(), 0.0);
+        let stats = SpeculativeStats {
+            drafted: 8,
+            accepted: 6,
+        };
+        assert_eq!(stats.acceptance_rate(), 0.75);
+    }
+}
@@ -0,0 +1,352 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Tool (function) calling
+//!
+//! Tool definitions are described to the model in the prompt, which asks it
+//! to answer with a JSON object `{"name": ..., "arguments": {...}}` (or an
+//! array of them) when it wants a function called. `parse_tool_calls`
+//! recognises that output, bare, in a ```json fence or wrapped in
+//! `<tool_call>` tags, and turns it into OpenAI-style `tool_calls`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// `finish_reason` of a response that requests tool calls
+pub const TOOL_CALLS_FINISH_REASON: &str = "tool_calls";
+
+/// Maximum number of tools per request
+pub const MAX_TOOLS: usize = 64;
+
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+fn empty_parameters() -> Value {
+    serde_json::json!({"type": "object", "properties": {}})
+}
+
+/// A function the model may call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tool {
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: FunctionDefinition,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON Schema of the arguments object
+    #[serde(default = "empty_parameters")]
+    pub parameters: Value,
+}
+
+/// A call the model asked for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: FunctionCall,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    /// Arguments as a JSON-encoded object, as in the OpenAI API
+    pub arguments: String,
+}
+
+impl ToolCall {
+    pub fn new(name: &str, arguments: &Value) -> Self {
+        Self {
+            id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+            kind: function_type(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+            },
+        }
+    }
+
+    /// The call in the form the model is asked to emit
+    fn to_prompt_json(&self) -> Value {
+        let arguments = serde_json::from_str(&self.function.arguments)
+            .unwrap_or_else(|_| Value::String(self.function.arguments.clone()));
+        serde_json::json!({"name": self.function.name, "arguments": arguments})
+    }
+}
+
+pub fn validate_tools(tools: &[Tool]) -> Result<(), String> {
+    if tools.len() > MAX_TOOLS {
+        return Err(format!("At most {} tools are allowed", MAX_TOOLS));
+    }
+
+    let mut names = std::collections::HashSet::new();
+    for tool in tools {
+        let name = &tool.function.name;
+        if tool.kind != "function" {
+            return Err(format!("Unsupported tool type '{}'", tool.kind));
+        }
+        let valid_name = !name.is_empty()
+            && name.len() <= MAX_TOOL_NAME_LEN
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid_name {
+            return Err(format!(
+                "Invalid tool name '{}': use 1-{} letters, digits, '_' or '-'",
+                name, MAX_TOOL_NAME_LEN
+            ));
+        }
+        if !names.insert(name) {
+            return Err(format!("Duplicate tool name '{}'", name));
+        }
+        if !tool.function.parameters.is_object() {
+            return Err(format!(
+                "Parameters of tool '{}' must be a JSON Schema object",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Instructions describing `tools` to the model
+pub fn tools_prompt(tools: &[Tool]) -> String {
+    let mut prompt = String::from("You can call the following functions:\n");
+    for tool in tools {
+        let definition = serde_json::to_string(&tool.function).unwrap_or_default();
+        prompt.push_str(&definition);
+        prompt.push('\n');
+    }
+    prompt.push_str(
+        "\nTo call a function, respond with only a JSON object of the form \
+         {\"name\": <function name>, \"arguments\": <arguments object>}. \
+         To call several, respond with a JSON array of such objects. \
+         Function results are returned in tool messages. \
+         If no function is needed, answer normally.",
+    );
+    prompt
+}
+
+/// Append the tool instructions to the first system message, adding one if
+/// the conversation has none
+pub fn add_to_system_message(messages: &mut Vec<(String, String)>, tools: &[Tool]) {
+    let prompt = tools_prompt(tools);
+    match messages.iter_mut().find(|(role, _)| role == "system") {
+        Some((_, content)) => {
+            content.push_str("\n\n");
+            content.push_str(&prompt);
+        }
+        None => messages.insert(0, ("system".to_string(), prompt)),
+    }
+}
+
+/// Content of a message as it appears in the prompt: an assistant message
+/// that made tool calls carries them as the model would have written them
+pub fn message_content(content: &str, tool_calls: Option<&[ToolCall]>) -> String {
+    match tool_calls {
+        Some(calls) if !calls.is_empty() => format_tool_calls(content, calls),
+        _ => content.to_string(),
+    }
+}
+
+fn format_tool_calls(content: &str, calls: &[ToolCall]) -> String {
+    let json = match calls {
+        [call] => call.to_prompt_json(),
+        calls => Value::Array(calls.iter().map(ToolCall::to_prompt_json).collect()),
+    };
+    if content.trim().is_empty() {
+        json.to_string()
+    } else {
+        format!("{}\n{}", content, json)
+    }
+}
+
+/// Model output split into text and the tool calls it requested
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedToolCalls {
+    /// Text outside the calls, usually empty
+    pub content: String,
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Extract tool calls from model output. Returns `None` unless every call
+/// found is well formed and names one of `tools`, so ordinary answers that
+/// happen to contain JSON are left alone.
+pub fn parse_tool_calls(text: &str, tools: &[Tool]) -> Option<ParsedToolCalls> {
+    if tools.is_empty() {
+        return None;
+    }
+
+    let (candidates, content) = if text.contains("<tool_call>") {
+        extract_between(text, "<tool_call>", "</tool_call>")?
+    } else if text.contains("```") {
+        let (mut bodies, content) = extract_between(text, "```", "```")?;
+        for body in &mut bodies {
+            if let Some(rest) = body.strip_prefix("json") {
+                *body = rest.to_string();
+            }
+        }
+        (bodies, content)
+    } else {
+        (vec![text.to_string()], String::new())
+    };
+
+    let mut tool_calls = Vec::new();
+    for candidate in candidates {
+        match serde_json::from_str::<Value>(candidate.trim()).ok()? {
+            Value::Array(calls) => {
+                for call in calls {
+                    tool_calls.push(parse_call(&call, tools)?);
+                }
+            }
+            call => tool_calls.push(parse_call(&call, tools)?),
+        }
+    }
+
+    (!tool_calls.is_empty()).then_some(ParsedToolCalls {
+        content,
+        tool_calls,
+    })
+}
+
+fn parse_call(call: &Value, tools: &[Tool]) -> Option<ToolCall> {
+    let name = call.get("name")?.as_str()?;
+    if !tools.iter().any(|tool| tool.function.name == name) {
+        return None;
+    }
+
+    let arguments = match call.get("arguments").or_else(|| call.get("parameters")) {
+        None => serde_json::json!({}),
+        // Some models emit the arguments JSON-encoded
+        Some(Value::String(encoded)) => serde_json::from_str(encoded).ok()?,
+        Some(arguments) => arguments.clone(),
+    };
+    if !arguments.is_object() {
+        return None;
+    }
+    Some(ToolCall::new(name, &arguments))
+}
+
+/// Bodies of every `open`...`close` block, plus the text outside them
+fn extract_between(text: &str, open: &str, close: &str) -> Option<(Vec<String>, String)> {
+    let mut bodies = Vec::new();
+    let mut outside = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(open) {
+        outside.push_str(&rest[..start]);
+        let after = &rest[start + open.len()..];
+        let end = after.find(close)?;
+        bodies.push(after[..end].to_string());
+        rest = &after[end + close.len()..];
+    }
+    outside.push_str(rest);
+    Some((bodies, outside.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weather_tool() -> Tool {
+        serde_json::from_value(serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "get_weather",
+                "description": "Current weather for a city",
+                "parameters": {
+                    "type": "object",
+                    "properties": {"city": {"type": "string"}},
+                    "required": ["city"]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parses_json_function_calls() {
+        let tools = [weather_tool()];
+
+        let parsed = parse_tool_calls(
+            r#"{"name": "get_weather", "arguments": {"city": "Paris"}}"#,
+            &tools,
+        )
+        .unwrap();
+        assert_eq!(parsed.content, "");
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].function.name, "get_weather");
+        assert_eq!(
+            serde_json::from_str::<Value>(&parsed.tool_calls[0].function.arguments).unwrap(),
+            serde_json::json!({"city": "Paris"})
+        );
+
+        let parsed = parse_tool_calls(
+            "Checking both.\n<tool_call>{\"name\": \"get_weather\", \"arguments\": \"{\\\"city\\\": \\\"Oslo\\\"}\"}</tool_call>\n<tool_call>{\"name\": \"get_weather\", \"parameters\": {\"city\": \"Rome\"}}</tool_call>",
+            &tools,
+        )
+        .unwrap();
+        assert_eq!(parsed.content, "Checking both.");
+        assert_eq!(parsed.tool_calls.len(), 2);
+        assert_ne!(parsed.tool_calls[0].id, parsed.tool_calls[1].id);
+
+        let parsed = parse_tool_calls(
+            "```json\n[{\"name\": \"get_weather\", \"arguments\": {\"city\": \"Lima\"}}]\n```",
+            &tools,
+        )
+        .unwrap();
+        assert_eq!(parsed.tool_calls.len(), 1);
+    }
+
+    #[test]
+    fn test_ordinary_answers_are_not_tool_calls() {
+        let tools = [weather_tool()];
+        assert_eq!(parse_tool_calls("It is sunny in Paris.", &tools), None);
+        // Unknown function
+        assert_eq!(
+            parse_tool_calls(r#"{"name": "book_flight", "arguments": {}}"#, &tools),
+            None
+        );
+        // JSON answer that is not a call
+        assert_eq!(parse_tool_calls(r#"{"city": "Paris"}"#, &tools), None);
+        // No tools offered
+        assert_eq!(
+            parse_tool_calls(r#"{"name": "get_weather", "arguments": {}}"#, &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_tools() {
+        assert!(validate_tools(&[weather_tool()]).is_ok());
+        assert!(validate_tools(&[weather_tool(), weather_tool()])
+            .unwrap_err()
+            .contains("Duplicate"));
+
+        let mut tool = weather_tool();
+        tool.function.name = "get weather".to_string();
+        assert!(validate_tools(&[tool]).is_err());
+
+        let mut tool = weather_tool();
+        tool.kind = "retrieval".to_string();
+        assert!(validate_tools(&[tool]).is_err());
+    }
+
+    #[test]
+    fn test_previous_calls_render_in_prompt_convention() {
+        let call = ToolCall::new("get_weather", &serde_json::json!({"city": "Paris"}));
+        let rendered = format_tool_calls("", &[call]);
+        assert_eq!(
+            serde_json::from_str::<Value>(&rendered).unwrap(),
+            serde_json::json!({"name": "get_weather", "arguments": {"city": "Paris"}})
+        );
+        assert!(parse_tool_calls(&rendered, &[weather_tool()]).is_some());
+        assert!(tools_prompt(&[weather_tool()]).contains("\"name\":\"get_weather\""));
+    }
+}
@@ -3,15 +3,18 @@
 use anyhow::{anyhow, Result};
 use ethers::prelude::*;
 use ethers::types::{Address, H256, U256};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tokio::time::{sleep, Duration};
 use tracing::{debug, error, info, warn};
 
+use crate::blockchain::GasBalanceSignal;
 use crate::contracts::pricing_constants::PRICE_PRECISION;
 use crate::contracts::Web3Client;
+use crate::host::availability::{AvailabilityManager, ScheduleError};
 use crate::host::registry::HostRegistry;
 use crate::host::selection::{HostSelector, JobRequirements};
 use crate::job_assignment_types::{AssignmentRecord, AssignmentStatus, JobClaimConfig};
@@ -26,6 +29,9 @@ pub enum ClaimError {
     BelowMinimumThreshold,
     UnsupportedModel,
     InvalidJob,
+    InsufficientGasBalance,
+    /// The job would still be running when this maintenance window starts
+    MaintenanceWindow(String),
     ContractError(String),
     Other(String),
 }
@@ -40,6 +46,12 @@ impl std::fmt::Display for ClaimError {
             ClaimError::BelowMinimumThreshold => write!(f, "Below minimum threshold"),
             ClaimError::UnsupportedModel => write!(f, "Unsupported model"),
             ClaimError::InvalidJob => write!(f, "Invalid job parameters"),
+            ClaimError::InsufficientGasBalance => {
+                write!(f, "Gas balance too low to settle new jobs")
+            }
+            ClaimError::MaintenanceWindow(id) => {
+                write!(f, "Job would run into maintenance window {}", id)
+            }
             ClaimError::ContractError(e) => write!(f, "Contract error: {}", e),
             ClaimError::Other(e) => write!(f, "Other error: {}", e),
         }
@@ -62,8 +74,138 @@ pub struct ClaimEvent {
     pub node_address: Address,
     pub event_type: String,
     pub timestamp: u64,
+    /// Delay applied before a claim because of recently lost races
+    pub backoff: Option<Duration>,
+}
+
+impl ClaimEvent {
+    /// Claiming stopped because the gas balance can't cover proof submissions
+    pub const PAUSED_LOW_BALANCE: &'static str = "PausedLowBalance";
+    /// Claiming restarted after the gas balance was topped up
+    pub const RESUMED_BALANCE: &'static str = "ResumedBalance";
+    /// Another host claimed the job first; the claim was aborted locally
+    pub const CLAIM_LOST: &'static str = "ClaimLost";
+    /// Claim delayed because this node recently lost claim races
+    pub const CLAIM_BACKOFF: &'static str = "ClaimBackoff";
+    /// Claim refused because the job would run into a maintenance window
+    pub const MAINTENANCE_PENDING: &'static str = "MaintenancePending";
+}
+
+/// Scores pending jobs so the most valuable are claimed first
+///
+/// Higher scores are claimed first. Jobs are only compared against others
+/// that arrived within the same `contention_window`, so a steady stream of
+/// lucrative jobs cannot starve older ones indefinitely.
+pub trait ClaimPriority: Send + Sync + std::fmt::Debug {
+    fn score(&self, job: &JobRequest) -> f64;
+}
+
+/// Claim in arrival order (every job scores the same)
+#[derive(Debug, Clone, Default)]
+pub struct ArrivalOrder;
+
+impl ClaimPriority for ArrivalOrder {
+    fn score(&self, _job: &JobRequest) -> f64 {
+        0.0
+    }
+}
+
+/// Prefer jobs paying the most per unit of estimated compute
+///
+/// Effort is `max_tokens` times the model's relative per-token cost, taken
+/// from `model_costs` or `default_cost` for unlisted models.
+#[derive(Debug, Clone)]
+pub struct RewardToEffort {
+    pub model_costs: HashMap<String, f64>,
+    pub default_cost: f64,
+}
+
+impl Default for RewardToEffort {
+    fn default() -> Self {
+        Self {
+            model_costs: HashMap::new(),
+            default_cost: 1.0,
+        }
+    }
+}
+
+impl ClaimPriority for RewardToEffort {
+    fn score(&self, job: &JobRequest) -> f64 {
+        let cost = self
+            .model_costs
+            .get(&job.model_id)
+            .copied()
+            .unwrap_or(self.default_cost);
+        let effort = f64::from(job.max_tokens.max(1)) * cost.max(f64::EPSILON);
+        u256_to_f64(job.payment_amount) / effort
+    }
+}
+
+/// Scale down the score of jobs whose model isn't loaded
+///
+/// Avoids evicting a warm model for a marginally better job. The set of
+/// loaded models is shared with whatever loads and unloads them.
+#[derive(Debug, Clone)]
+pub struct PreferLoadedModels {
+    pub inner: Arc<dyn ClaimPriority>,
+    pub loaded_models: Arc<std::sync::RwLock<HashSet<String>>>,
+    /// Multiplier applied to the inner score of jobs for unloaded models
+    pub unloaded_penalty: f64,
+}
+
+impl PreferLoadedModels {
+    pub fn new(inner: Arc<dyn ClaimPriority>) -> Self {
+        Self {
+            inner,
+            loaded_models: Arc::new(std::sync::RwLock::new(HashSet::new())),
+            unloaded_penalty: 0.25,
+        }
+    }
+
+    pub fn set_loaded_models<I: IntoIterator<Item = String>>(&self, models: I) {
+        *self.loaded_models.write().unwrap() = models.into_iter().collect();
+    }
 }
 
+impl ClaimPriority for PreferLoadedModels {
+    fn score(&self, job: &JobRequest) -> f64 {
+        let score = self.inner.score(job);
+        if self.loaded_models.read().unwrap().contains(&job.model_id) {
+            score
+        } else {
+            score * self.unloaded_penalty
+        }
+    }
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    if value > U256::from(u128::MAX) {
+        f64::MAX
+    } else {
+        value.as_u128() as f64
+    }
+}
+
+/// Default window within which pending jobs compete on priority
+const DEFAULT_CONTENTION_WINDOW: Duration = Duration::from_secs(30);
+
+/// Default base delay per recently lost claim race
+const DEFAULT_CONTENTION_BACKOFF: Duration = Duration::from_millis(200);
+/// Default period over which lost claim races are counted
+const DEFAULT_CLAIM_LOSS_WINDOW: Duration = Duration::from_secs(300);
+/// Cap on lost races counted towards the backoff
+const MAX_COUNTED_CLAIM_LOSSES: usize = 10;
+
+/// Default generation speed used to estimate how long a job will run
+const DEFAULT_ESTIMATED_TOKENS_PER_SECOND: f64 = 20.0;
+/// Default length of job that may be claimed right up to a maintenance window
+const DEFAULT_MAINTENANCE_GRACE: Duration = Duration::from_secs(60);
+
+/// Default gas used by a single proof submission
+const DEFAULT_PROOF_SUBMISSION_GAS: u64 = 300_000;
+/// Default number of proof submissions the gas balance must cover
+const DEFAULT_MIN_FUNDED_PROOFS: u64 = 5;
+
 // Extended NodeConfig with claim-specific fields
 #[derive(Debug, Clone)]
 pub struct ClaimConfig {
@@ -74,6 +216,42 @@ pub struct ClaimConfig {
     pub max_gas_price: U256,
     pub supported_models: Vec<String>,
     pub min_payment_per_token: U256,
+    /// Stop claiming when the gas balance can't cover this many proof submissions
+    pub min_funded_proofs: u64,
+    pub proof_submission_gas: U256,
+    /// Order in which pending jobs are claimed
+    pub priority: Arc<dyn ClaimPriority>,
+    pub contention_window: Duration,
+    /// Delay per recently lost claim race, jittered by ±50%
+    pub contention_backoff: Duration,
+    pub claim_loss_window: Duration,
+    /// Generation speed used to estimate job duration from `max_tokens`
+    pub estimated_tokens_per_second: f64,
+    /// Jobs estimated to take no longer than this may be claimed even if a
+    /// maintenance window starts before they finish
+    pub maintenance_grace: Duration,
+}
+
+impl Default for ClaimConfig {
+    fn default() -> Self {
+        Self {
+            node_address: Address::zero(),
+            max_concurrent_jobs: 1,
+            claim_retry_attempts: 3,
+            claim_retry_delay: Duration::from_millis(1000),
+            max_gas_price: U256::from(100_000_000_000u64),
+            supported_models: vec![],
+            min_payment_per_token: U256::zero(),
+            min_funded_proofs: DEFAULT_MIN_FUNDED_PROOFS,
+            proof_submission_gas: U256::from(DEFAULT_PROOF_SUBMISSION_GAS),
+            priority: Arc::new(ArrivalOrder),
+            contention_window: DEFAULT_CONTENTION_WINDOW,
+            contention_backoff: DEFAULT_CONTENTION_BACKOFF,
+            claim_loss_window: DEFAULT_CLAIM_LOSS_WINDOW,
+            estimated_tokens_per_second: DEFAULT_ESTIMATED_TOKENS_PER_SECOND,
+            maintenance_grace: DEFAULT_MAINTENANCE_GRACE,
+        }
+    }
 }
 
 impl From<NodeConfig> for ClaimConfig {
@@ -86,6 +264,7 @@ impl From<NodeConfig> for ClaimConfig {
             max_gas_price: config.max_gas_price,
             supported_models: config.supported_models,
             min_payment_per_token: config.min_payment_per_token,
+            ..Default::default()
         }
     }
 }
@@ -116,6 +295,10 @@ pub struct JobClaimer {
     assignments: Arc<RwLock<HashMap<String, AssignmentRecord>>>,
     host_registry: Option<Arc<HostRegistry>>,
     host_selector: Option<Arc<HostSelector>>,
+    balance_signal: Option<GasBalanceSignal>,
+    paused_low_balance: Arc<RwLock<bool>>,
+    claim_losses: Arc<RwLock<VecDeque<std::time::Instant>>>,
+    availability: Option<Arc<RwLock<AvailabilityManager>>>,
 }
 
 impl JobClaimer {
@@ -132,24 +315,20 @@ impl JobClaimer {
             assignments: Arc::new(RwLock::new(HashMap::new())),
             host_registry: None,
             host_selector: None,
+            balance_signal: None,
+            paused_low_balance: Arc::new(RwLock::new(false)),
+            claim_losses: Arc::new(RwLock::new(VecDeque::new())),
+            availability: None,
         }
     }
 
     pub async fn new(config: JobClaimConfig) -> Result<Self> {
         let claim_config = ClaimConfig {
-            node_address: Address::zero(),
             max_concurrent_jobs: config.max_concurrent_jobs,
-            claim_retry_attempts: 3,
             claim_retry_delay: Duration::from_millis(config.claim_timeout_ms),
-            max_gas_price: U256::from(100_000_000_000u64),
-            supported_models: vec![],
-            min_payment_per_token: U256::zero(),
+            ..Default::default()
         };
-        let marketplace = Arc::new(MockMarketplace {
-            registered_nodes: Arc::new(RwLock::new(HashSet::new())),
-            jobs: Arc::new(RwLock::new(HashMap::new())),
-            claimed_jobs: Arc::new(RwLock::new(HashSet::new())),
-        }) as Arc<dyn JobMarketplaceTrait>;
+        let marketplace = Arc::new(MockMarketplace::new()) as Arc<dyn JobMarketplaceTrait>;
 
         Ok(Self::new_with_marketplace(claim_config, marketplace))
     }
@@ -164,7 +343,124 @@ impl JobClaimer {
         self
     }
 
+    /// Pause claiming while the host's gas balance is below the threshold
+    pub fn with_balance_signal(mut self, signal: GasBalanceSignal) -> Self {
+        self.balance_signal = Some(signal);
+        self
+    }
+
+    /// Refuse jobs that would still be running when a scheduled maintenance
+    /// window starts
+    pub fn with_availability(mut self, availability: Arc<RwLock<AvailabilityManager>>) -> Self {
+        self.availability = Some(availability);
+        self
+    }
+
+    /// How long `job` is expected to run, from its `max_tokens`
+    pub fn estimate_job_duration(&self, job: &JobRequest) -> Duration {
+        let tokens_per_second = self.config.estimated_tokens_per_second;
+        if tokens_per_second > 0.0 {
+            Duration::from_secs_f64(job.max_tokens as f64 / tokens_per_second)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// Check the job against scheduled maintenance, if availability is wired
+    async fn check_maintenance(&self, job: &JobRequest) -> Result<(), ClaimError> {
+        let availability = match &self.availability {
+            Some(availability) => availability,
+            None => return Ok(()),
+        };
+        let to_chrono =
+            |d: Duration| chrono::Duration::from_std(d).unwrap_or(chrono::Duration::MAX);
+        let estimated = self.estimate_job_duration(job);
+
+        let result = availability
+            .write()
+            .await
+            .admit_job(
+                &job.model_id,
+                to_chrono(estimated),
+                to_chrono(self.config.maintenance_grace),
+            )
+            .await;
+        match result {
+            Ok(()) => Ok(()),
+            Err(ScheduleError::MaintenanceConflict(window)) => {
+                info!(
+                    "Not claiming job {:?} (~{:?}): maintenance window {} is coming up",
+                    job.job_id, estimated, window
+                );
+                self.emit_event(self.claim_event(
+                    job.job_id,
+                    ClaimEvent::MAINTENANCE_PENDING,
+                    None,
+                ))
+                .await;
+                Err(ClaimError::MaintenanceWindow(window))
+            }
+            Err(e) => Err(ClaimError::Other(e.to_string())),
+        }
+    }
+
+    /// Whether claiming is currently paused for a low gas balance
+    pub async fn is_paused_low_balance(&self) -> bool {
+        *self.paused_low_balance.read().await
+    }
+
+    /// Gas balance needed to settle `min_funded_proofs` proof submissions
+    pub async fn min_gas_balance(&self) -> Result<U256, ClaimError> {
+        let gas_price = self.marketplace.get_gas_price().await?;
+        Ok(
+            self.config.proof_submission_gas
+                * gas_price
+                * U256::from(self.config.min_funded_proofs),
+        )
+    }
+
+    /// Re-evaluate the balance signal, pausing or resuming claiming
+    ///
+    /// Returns `true` when new claims are allowed. Without a signal, or before
+    /// the first balance is published, claiming is never paused.
+    async fn check_gas_balance(&self) -> Result<bool, ClaimError> {
+        let balance = match self.balance_signal.as_ref().and_then(|s| s.current()) {
+            Some(balance) => balance,
+            None => return Ok(true),
+        };
+        let required = self.min_gas_balance().await?;
+        let affordable = balance >= required;
+
+        let mut paused = self.paused_low_balance.write().await;
+        if *paused == affordable {
+            *paused = !affordable;
+            drop(paused);
+
+            let event_type = if affordable {
+                info!(
+                    "Gas balance topped up ({} wei >= {} wei), resuming job claims",
+                    balance, required
+                );
+                ClaimEvent::RESUMED_BALANCE
+            } else {
+                warn!(
+                    "Gas balance {} wei below {} wei needed for {} proofs, pausing job claims",
+                    balance, required, self.config.min_funded_proofs
+                );
+                ClaimEvent::PAUSED_LOW_BALANCE
+            };
+            self.emit_event(self.claim_event(H256::zero(), event_type, None))
+                .await;
+        }
+
+        Ok(affordable)
+    }
+
     pub async fn claim_job(&self, job_id: H256) -> ClaimResult {
+        if !self.check_gas_balance().await? {
+            return Err(ClaimError::InsufficientGasBalance);
+        }
+
         let mut active = self.active_claims.write().await;
         if *active >= self.config.max_concurrent_jobs {
             return Err(ClaimError::Other("Max concurrent jobs reached".to_string()));
@@ -199,27 +495,99 @@ impl JobClaimer {
         }
 
         self.validate_job(&job)?;
+        self.check_maintenance(&job).await?;
         let gas_cost = self.estimate_claim_gas(job_id).await?;
         if !self.is_profitable(&job, gas_cost).await? {
             return Err(ClaimError::Other("Job not profitable".to_string()));
         }
 
-        self.marketplace
+        // Hosts that keep losing races give the winner time to land first
+        if let Some(backoff) = self.contention_backoff().await {
+            debug!("Backing off {:?} before claiming job {:?}", backoff, job_id);
+            self.emit_event(self.claim_event(job_id, ClaimEvent::CLAIM_BACKOFF, Some(backoff)))
+                .await;
+            sleep(backoff).await;
+        }
+
+        // Re-check right before sending: a reverted claim still costs gas
+        if self.marketplace.is_job_claimed(job_id).await {
+            self.record_claim_loss(job_id).await;
+            return Err(ClaimError::JobAlreadyClaimed);
+        }
+
+        if let Err(e) = self
+            .marketplace
             .claim_job(job_id, self.config.node_address)
-            .await?;
+            .await
+        {
+            if matches!(e, ClaimError::JobAlreadyClaimed) {
+                self.record_claim_loss(job_id).await;
+            }
+            return Err(e);
+        }
         self.claimed_jobs.write().await.insert(job_id);
-        self.emit_event(ClaimEvent {
+        self.emit_event(self.claim_event(job_id, "JobClaimed", None))
+            .await;
+
+        Ok(H256::random())
+    }
+
+    /// Number of claim races lost within `claim_loss_window`
+    pub async fn recent_claim_losses(&self) -> usize {
+        let mut losses = self.claim_losses.write().await;
+        Self::prune_claim_losses(&mut losses, self.config.claim_loss_window);
+        losses.len()
+    }
+
+    /// Jittered delay proportional to recently lost claim races
+    async fn contention_backoff(&self) -> Option<Duration> {
+        let losses = self
+            .recent_claim_losses()
+            .await
+            .min(MAX_COUNTED_CLAIM_LOSSES);
+        if losses == 0 || self.config.contention_backoff.is_zero() {
+            return None;
+        }
+
+        let jitter = rand::thread_rng().gen_range(0.5..1.5);
+        Some(
+            self.config
+                .contention_backoff
+                .mul_f64(losses as f64 * jitter),
+        )
+    }
+
+    async fn record_claim_loss(&self, job_id: H256) {
+        {
+            let mut losses = self.claim_losses.write().await;
+            losses.push_back(std::time::Instant::now());
+            Self::prune_claim_losses(&mut losses, self.config.claim_loss_window);
+        }
+        info!(
+            "Job {:?} was claimed by another host, aborting claim",
+            job_id
+        );
+        self.emit_event(self.claim_event(job_id, ClaimEvent::CLAIM_LOST, None))
+            .await;
+    }
+
+    fn prune_claim_losses(losses: &mut VecDeque<std::time::Instant>, window: Duration) {
+        while losses.front().is_some_and(|t| t.elapsed() > window) {
+            losses.pop_front();
+        }
+    }
+
+    fn claim_event(&self, job_id: H256, event_type: &str, backoff: Option<Duration>) -> ClaimEvent {
+        ClaimEvent {
             job_id,
             node_address: self.config.node_address,
-            event_type: "JobClaimed".to_string(),
+            event_type: event_type.to_string(),
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs(),
-        })
-        .await;
-
-        Ok(H256::random())
+            backoff,
+        }
     }
 
     pub async fn claim_batch(&self, job_ids: &[H256]) -> Vec<ClaimResult> {
@@ -232,6 +600,49 @@ impl JobClaimer {
         results
     }
 
+    /// Order jobs for claiming
+    ///
+    /// Jobs are grouped by arrival into `contention_window` buckets; buckets
+    /// are taken oldest first and jobs within a bucket by descending score.
+    pub fn rank_jobs(&self, mut jobs: Vec<JobRequest>) -> Vec<JobRequest> {
+        let window = self.config.contention_window.as_secs();
+        let bucket = |job: &JobRequest| {
+            if window == 0 {
+                U256::zero()
+            } else {
+                job.timestamp / U256::from(window)
+            }
+        };
+
+        let mut scored: Vec<(U256, f64, JobRequest)> = jobs
+            .drain(..)
+            .map(|job| (bucket(&job), self.config.priority.score(&job), job))
+            .collect();
+        // Stable sort keeps arrival order for equal scores
+        scored.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.total_cmp(&a.1)));
+        scored.into_iter().map(|(_, _, job)| job).collect()
+    }
+
+    /// Claim the highest-priority claimable jobs up to the free capacity
+    pub async fn claim_pending(&self) -> Vec<(H256, ClaimResult)> {
+        let capacity = self
+            .config
+            .max_concurrent_jobs
+            .saturating_sub(*self.active_claims.read().await);
+        let ranked = self.get_claimable_jobs().await;
+
+        let mut results = Vec::new();
+        for job in ranked.into_iter().take(capacity) {
+            debug!(
+                "Claiming job {:?} (score {:.4})",
+                job.job_id,
+                self.config.priority.score(&job)
+            );
+            results.push((job.job_id, self.claim_job(job.job_id).await));
+        }
+        results
+    }
+
     pub async fn claim_job_with_retry(&self, job_id: H256) -> ClaimResult {
         let mut attempts = 0;
         let mut last_error = None;
@@ -247,7 +658,8 @@ impl JobClaimer {
                         ClaimError::NodeNotRegistered
                         | ClaimError::JobNotFound
                         | ClaimError::JobAlreadyClaimed
-                        | ClaimError::UnsupportedModel => return Err(e),
+                        | ClaimError::UnsupportedModel
+                        | ClaimError::InsufficientGasBalance => return Err(e),
                         _ => {}
                     }
 
@@ -315,6 +727,10 @@ impl JobClaimer {
     }
 
     pub async fn get_claimable_jobs(&self) -> Vec<JobRequest> {
+        if !self.check_gas_balance().await.unwrap_or(true) {
+            return Vec::new();
+        }
+
         // Get all jobs from the marketplace
         let all_jobs = self.marketplace.get_all_jobs().await;
 
@@ -339,7 +755,7 @@ impl JobClaimer {
             }
         }
 
-        claimable_jobs
+        self.rank_jobs(claimable_jobs)
     }
 
     pub async fn unclaim_job(&self, job_id: H256) -> Result<(), ClaimError> {
@@ -526,10 +942,35 @@ impl JobClaimer {
 }
 
 // Mock marketplace for testing
+#[derive(Default)]
 pub struct MockMarketplace {
     registered_nodes: Arc<RwLock<HashSet<Address>>>,
     jobs: Arc<RwLock<HashMap<H256, JobRequest>>>,
     claimed_jobs: Arc<RwLock<HashSet<H256>>>,
+    /// Jobs a rival host claims while our claim is in flight
+    contested_jobs: Arc<RwLock<HashSet<H256>>>,
+}
+
+impl MockMarketplace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn register_node(&self, node_address: Address) {
+        self.registered_nodes.write().await.insert(node_address);
+    }
+
+    pub async fn add_job(&self, job: JobRequest) {
+        self.jobs.write().await.insert(job.job_id, job);
+    }
+
+    /// Simulate a rival host claiming `job_id` mid-flight
+    ///
+    /// The job looks unclaimed on the first check, then gets claimed while
+    /// the claimer is estimating gas.
+    pub async fn simulate_contention(&self, job_id: H256) {
+        self.contested_jobs.write().await.insert(job_id);
+    }
 }
 
 #[async_trait::async_trait]
@@ -543,7 +984,10 @@ impl JobMarketplaceTrait for MockMarketplace {
     }
 
     async fn claim_job(&self, job_id: H256, _node_address: Address) -> Result<(), ClaimError> {
-        self.claimed_jobs.write().await.insert(job_id);
+        // A second claim reverts on-chain
+        if !self.claimed_jobs.write().await.insert(job_id) {
+            return Err(ClaimError::JobAlreadyClaimed);
+        }
         Ok(())
     }
 
@@ -560,7 +1004,10 @@ impl JobMarketplaceTrait for MockMarketplace {
         self.jobs.read().await.values().cloned().collect()
     }
 
-    async fn estimate_gas(&self, _job_id: H256) -> Result<U256> {
+    async fn estimate_gas(&self, job_id: H256) -> Result<U256> {
+        if self.contested_jobs.write().await.remove(&job_id) {
+            self.claimed_jobs.write().await.insert(job_id);
+        }
         Ok(U256::from(100_000))
     }
 
@@ -605,4 +1052,212 @@ mod tests {
         // $5/million = 5000, should pass threshold of 1000
         assert!(price_per_token >= min_threshold);
     }
+
+    async fn claimer_with_signal(signal: GasBalanceSignal) -> JobClaimer {
+        let config = JobClaimConfig {
+            max_concurrent_jobs: 4,
+            claim_timeout_ms: 10,
+            enable_auto_claim: false,
+        };
+        JobClaimer::new(config)
+            .await
+            .unwrap()
+            .with_balance_signal(signal)
+    }
+
+    #[tokio::test]
+    async fn test_claiming_pauses_and_resumes_with_gas_balance() {
+        let signal = GasBalanceSignal::new();
+        let claimer = claimer_with_signal(signal.clone()).await;
+        let mut events = claimer.subscribe_to_events().await;
+
+        // 20 gwei * 300k gas * 5 proofs
+        let required = claimer.min_gas_balance().await.unwrap();
+        assert_eq!(required, U256::from(30_000_000_000_000_000u64));
+
+        signal.update(required - 1);
+        let result = claimer.claim_job(H256::random()).await;
+        assert!(matches!(result, Err(ClaimError::InsufficientGasBalance)));
+        assert!(claimer.is_paused_low_balance().await);
+        assert_eq!(
+            events.recv().await.unwrap().event_type,
+            ClaimEvent::PAUSED_LOW_BALANCE
+        );
+
+        // Still low: no duplicate pause event
+        assert!(claimer.claim_job(H256::random()).await.is_err());
+        assert!(events.try_recv().is_err());
+
+        signal.update(required);
+        // Gets past the balance check; the mock node is not registered
+        let result = claimer.claim_job(H256::random()).await;
+        assert!(matches!(result, Err(ClaimError::NodeNotRegistered)));
+        assert!(!claimer.is_paused_low_balance().await);
+        assert_eq!(
+            events.recv().await.unwrap().event_type,
+            ClaimEvent::RESUMED_BALANCE
+        );
+    }
+
+    fn job(model: &str, payment: u64, max_tokens: u32, timestamp: u64) -> JobRequest {
+        JobRequest {
+            job_id: H256::random(),
+            model_id: model.to_string(),
+            max_tokens,
+            payment_amount: U256::from(payment),
+            timestamp: U256::from(timestamp),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reward_to_effort_uses_model_cost() {
+        let mut strategy = RewardToEffort::default();
+        strategy.model_costs.insert("large".to_string(), 4.0);
+
+        let small = job("small", 1_000, 100, 0);
+        let large = job("large", 1_000, 100, 0);
+        assert_eq!(strategy.score(&small), 10.0);
+        assert_eq!(strategy.score(&large), 2.5);
+    }
+
+    #[test]
+    fn test_prefer_loaded_models_penalizes_cold_models() {
+        let strategy = PreferLoadedModels::new(Arc::new(RewardToEffort::default()));
+        strategy.set_loaded_models(vec!["warm".to_string()]);
+
+        let warm = job("warm", 1_000, 100, 0);
+        let cold = job("cold", 2_000, 100, 0);
+        assert_eq!(strategy.score(&warm), 10.0);
+        assert_eq!(strategy.score(&cold), 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_rank_jobs_by_score_within_window() {
+        let mut claimer = claimer_with_signal(GasBalanceSignal::new()).await;
+        claimer.config.priority = Arc::new(RewardToEffort::default());
+        claimer.config.contention_window = Duration::from_secs(30);
+
+        let cheap = job("m", 100, 100, 0);
+        let rich = job("m", 10_000, 100, 10);
+        let tie = job("m", 100, 100, 20);
+        // Next window: claimed after the first window despite a higher score
+        let later = job("m", 100_000, 100, 40);
+
+        let ranked = claimer.rank_jobs(vec![
+            later.clone(),
+            cheap.clone(),
+            rich.clone(),
+            tie.clone(),
+        ]);
+        let ids: Vec<H256> = ranked.iter().map(|j| j.job_id).collect();
+        assert_eq!(
+            ids,
+            vec![rich.job_id, cheap.job_id, tie.job_id, later.job_id]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_claim_aborted_when_rival_claims_mid_flight() {
+        let marketplace = Arc::new(MockMarketplace::new());
+        marketplace.register_node(Address::zero()).await;
+        let contested = job("m", 1_000_000_000_000_000_000, 100, 0);
+        let next = job("m", 1_000_000_000_000_000_000, 100, 0);
+        marketplace.add_job(contested.clone()).await;
+        marketplace.add_job(next.clone()).await;
+        marketplace.simulate_contention(contested.job_id).await;
+
+        let config = ClaimConfig {
+            max_concurrent_jobs: 4,
+            contention_backoff: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let claimer = JobClaimer::new_with_marketplace(config, marketplace.clone());
+        let mut events = claimer.subscribe_to_events().await;
+
+        let result = claimer.claim_job(contested.job_id).await;
+        assert!(matches!(result, Err(ClaimError::JobAlreadyClaimed)));
+        assert_eq!(claimer.recent_claim_losses().await, 1);
+        let lost = events.recv().await.unwrap();
+        assert_eq!(lost.event_type, ClaimEvent::CLAIM_LOST);
+        assert_eq!(lost.job_id, contested.job_id);
+
+        // One recent loss: back off 10ms ± 50% before the next claim
+        assert!(claimer.claim_job(next.job_id).await.is_ok());
+        let backoff = events.recv().await.unwrap();
+        assert_eq!(backoff.event_type, ClaimEvent::CLAIM_BACKOFF);
+        let delay = backoff.backoff.unwrap();
+        assert!(delay >= Duration::from_millis(5) && delay <= Duration::from_millis(15));
+        assert_eq!(events.recv().await.unwrap().event_type, "JobClaimed");
+    }
+
+    #[tokio::test]
+    async fn test_no_backoff_without_losses() {
+        let claimer = claimer_with_signal(GasBalanceSignal::new()).await;
+        assert_eq!(claimer.recent_claim_losses().await, 0);
+        assert!(claimer.contention_backoff().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_claim_refused_before_maintenance_window() {
+        use crate::host::availability::{AvailabilityStatus, MaintenanceWindow};
+
+        let marketplace = Arc::new(MockMarketplace::new());
+        marketplace.register_node(Address::zero()).await;
+        let payment = 1_000_000_000_000_000_000;
+        // At 20 tokens/s: 120s and 95s, both past the window start at 90s
+        let long = job("m", payment, 2_400, 0);
+        let short = job("m", payment, 1_900, 0);
+        marketplace.add_job(long.clone()).await;
+        marketplace.add_job(short.clone()).await;
+
+        let availability = Arc::new(RwLock::new(AvailabilityManager::new()));
+        availability
+            .write()
+            .await
+            .schedule_maintenance(MaintenanceWindow {
+                id: "upgrade".to_string(),
+                start_time: chrono::Utc::now() + chrono::Duration::seconds(90),
+                end_time: chrono::Utc::now() + chrono::Duration::hours(1),
+                description: "Driver upgrade".to_string(),
+                affects_models: vec![],
+            })
+            .await
+            .unwrap();
+
+        let config = ClaimConfig {
+            max_concurrent_jobs: 4,
+            maintenance_grace: Duration::from_secs(100),
+            ..Default::default()
+        };
+        let claimer = JobClaimer::new_with_marketplace(config, marketplace)
+            .with_availability(availability.clone());
+        let mut events = claimer.subscribe_to_events().await;
+
+        let result = claimer.claim_job(long.job_id).await;
+        assert!(matches!(result, Err(ClaimError::MaintenanceWindow(id)) if id == "upgrade"));
+        assert_eq!(
+            events.recv().await.unwrap().event_type,
+            ClaimEvent::MAINTENANCE_PENDING
+        );
+        assert_eq!(
+            availability.read().await.get_current_status().await,
+            AvailabilityStatus::MaintenancePending
+        );
+
+        // Within the grace period: claimed despite the window
+        assert!(claimer.claim_job(short.job_id).await.is_ok());
+        assert_eq!(
+            availability.read().await.get_current_status().await,
+            AvailabilityStatus::Available
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_pause_before_first_balance() {
+        let claimer = claimer_with_signal(GasBalanceSignal::new()).await;
+        let result = claimer.claim_job(H256::random()).await;
+        assert!(matches!(result, Err(ClaimError::NodeNotRegistered)));
+        assert!(!claimer.is_paused_low_balance().await);
+    }
 }
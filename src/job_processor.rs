@@ -9,12 +9,13 @@ use std::collections::{BinaryHeap, HashMap};
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tokio::time::{interval, sleep, Duration};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 
 use crate::contracts::{
     JobEvent as ContractJobEvent, JobMonitor, JobStatus as ContractJobStatus, Web3Client,
 };
-use crate::inference::{InferenceRequest, LlmEngine};
+use crate::inference::{InferenceRequest, LlmEngine, ToolCall};
+use crate::monitoring::TraceContext;
 
 // Message struct for conversation context
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +24,12 @@ pub struct Message {
     pub content: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<i64>,
+    /// Calls made by an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// The call a `tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
 }
 
 // Extended JobStatus for internal processing states
@@ -307,6 +314,12 @@ impl JobProcessor {
     }
 
     pub async fn process_job_event(&self, event: JobEvent) -> Result<()> {
+        // On-chain jobs carry no request headers; the job id seeds the trace
+        let span = TraceContext::from_request_id(&format!("{:#x}", event.job_id)).span("job");
+        self.enqueue_job_event(event).instrument(span).await
+    }
+
+    async fn enqueue_job_event(&self, event: JobEvent) -> Result<()> {
         let job = JobRequest {
             job_id: event.job_id,
             requester: event.requester,
@@ -39,20 +39,23 @@ pub mod vision;
 // Re-export main types from new modules
 pub use job_assignment_types::{AssignmentRecord, AssignmentStatus, JobClaimConfig};
 pub use job_claim::{
-    ClaimConfig, ClaimError, ClaimEvent, ClaimResult, JobClaimer,
-    JobMarketplaceTrait as ClaimMarketplaceTrait, MockMarketplace,
+    ArrivalOrder, ClaimConfig, ClaimError, ClaimEvent, ClaimPriority, ClaimResult, JobClaimer,
+    JobMarketplaceTrait as ClaimMarketplaceTrait, MockMarketplace, PreferLoadedModels,
+    RewardToEffort,
 };
 pub use job_processor::{
     ContractClientTrait, JobEvent, JobProcessor, JobRequest, JobResult, JobStatus, LLMService,
     Message, NodeConfig, NodeConfig as JobNodeConfig,
 };
 pub use payment_claim::{
-    EscrowManager, PaymentClaimer, PaymentConfig, PaymentError, PaymentEvent, PaymentSplitter,
-    PaymentStatistics, PaymentStatus, PaymentSystemTrait,
+    EscrowManager, EscrowPayment, PaymentClaimer, PaymentConfig, PaymentError, PaymentEvent,
+    PaymentSplitter, PaymentStatistics, PaymentStatus, PaymentSystemTrait, ReleaseReceipt,
+    SplitConfig, SplitRecipient,
 };
 pub use result_submission::{
-    InferenceResult, JobMarketplaceTrait as SubmissionMarketplaceTrait, ProofData, ProofGenerator,
-    ResultSubmitter, StorageClient, SubmissionConfig, SubmissionError,
+    InferenceResult, JobMarketplaceTrait as SubmissionMarketplaceTrait, JournalState,
+    OnChainResult, ProofData, ProofGenerator, ResultSubmitter, StorageClient, StoredCids,
+    SubmissionConfig, SubmissionError, SubmissionJournal,
 };
 
 // Re-export types from existing modules
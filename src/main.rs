@@ -2,15 +2,21 @@
 // SPDX-License-Identifier: BUSL-1.1
 use anyhow::Result;
 use fabstir_llm_node::{
-    api::{ApiConfig, ApiServer},
+    api::{auth, ApiConfig, ApiServer, RateLimitConfig, RequestLogConfig},
+    cache::{CacheConfig, PromptCache},
     contracts::{
         checkpoint_manager::CheckpointManager, model_registry::ModelRegistryClient, Web3Client,
         Web3Config,
     },
-    inference::{EngineConfig, LlmEngine, ModelConfig},
+    inference::{
+        ContinuousBatchConfig, DraftModelConfig, EngineConfig, LlmEngine, ModelConfig,
+        PrefixCacheConfig,
+    },
     model_validation::ModelValidator,
     p2p::{Node, NodeEvent},
-    p2p_config::NodeConfig,
+    p2p_config::{NodeConfig, TransportMode, P2P_TRANSPORT_ENV},
+    storage::enhanced_s5_client::{EnhancedS5Client, S5Config},
+    vector::DistanceMetric,
 };
 use std::{env, path::PathBuf, sync::Arc, time::Duration};
 use tokio::signal;
@@ -21,7 +27,9 @@ async fn main() -> Result<()> {
     if env::var("RUST_LOG").is_err() {
         env::set_var("RUST_LOG", "info");
     }
-    tracing_subscriber::fmt::init();
+    // Spans are exported over OTLP when built with `otel` and
+    // OTEL_EXPORTER_OTLP_ENDPOINT is set
+    let _telemetry = fabstir_llm_node::monitoring::init_tracing("fabstir-llm-node")?;
 
     println!("🚀 Starting Fabstir LLM Node...\n");
     println!("📦 BUILD VERSION: {}", fabstir_llm_node::version::VERSION);
@@ -30,6 +38,10 @@ async fn main() -> Result<()> {
 
     // Parse environment variables for configuration
     let p2p_port = env::var("P2P_PORT").unwrap_or_else(|_| "9000".to_string());
+    let p2p_transport = match env::var(P2P_TRANSPORT_ENV) {
+        Ok(value) => value.parse::<TransportMode>().map_err(anyhow::Error::msg)?,
+        Err(_) => TransportMode::default(),
+    };
     let api_port = env::var("API_PORT").unwrap_or_else(|_| "8080".to_string());
     let model_path = env::var("MODEL_PATH")
         .unwrap_or_else(|_| "./models/tiny-vicuna-1b.q4_k_m.gguf".to_string());
@@ -56,9 +68,20 @@ async fn main() -> Result<()> {
     // Read KV cache type from environment variable (sets both K and V)
     let kv_cache_type = env::var("KV_CACHE_TYPE").ok();
 
+    // How many models may be resident at once; others are loaded on demand.
+    // Defaults to room for the main model plus every ADDITIONAL_MODELS entry.
+    let additional_model_count = env::var("ADDITIONAL_MODELS")
+        .map(|models| models.split(',').filter(|e| !e.trim().is_empty()).count())
+        .unwrap_or(0);
+    let max_loaded_models = env::var("MAX_LOADED_MODELS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(1 + additional_model_count);
+
     let engine_config = EngineConfig {
         models_directory: PathBuf::from("./models"),
-        max_loaded_models: 1,
+        max_loaded_models,
         max_context_length,
         gpu_layers,
         thread_count: 8,
@@ -69,9 +92,26 @@ async fn main() -> Result<()> {
         model_eviction_policy: "lru".to_string(),
         kv_cache_type_k: kv_cache_type.clone(),
         kv_cache_type_v: kv_cache_type,
+        default_timeout_ms: EngineConfig::timeout_from_env(),
+        draft_model: DraftModelConfig::from_env(),
     };
 
     let mut llm_engine = LlmEngine::new(engine_config).await?;
+    if let Some(batching) = ContinuousBatchConfig::from_env() {
+        println!(
+            "🔀 Continuous batching enabled ({} sequences per model)",
+            batching.max_active_sequences
+        );
+        llm_engine = llm_engine.with_continuous_batching(batching);
+    }
+    if let Some(prefix_cache) = PrefixCacheConfig::from_env() {
+        println!(
+            "♻️ Prefix cache enabled ({} system prompts, {} MB)",
+            prefix_cache.max_entries,
+            prefix_cache.max_memory_bytes / (1024 * 1024)
+        );
+        llm_engine = llm_engine.with_prefix_cache(prefix_cache)?;
+    }
     println!("✅ Inference engine initialized");
 
     // ========================================================================
@@ -271,14 +311,59 @@ async fn main() -> Result<()> {
         return Err(anyhow::anyhow!("Model file not found"));
     }
 
+    // Register additional models (ADDITIONAL_MODELS=id=path,id=path). They are
+    // loaded lazily the first time a request names them, evicting the least
+    // recently used model once MAX_LOADED_MODELS is reached.
+    if let Ok(additional_models) = env::var("ADDITIONAL_MODELS") {
+        for entry in additional_models
+            .split(',')
+            .filter(|e| !e.trim().is_empty())
+        {
+            let Some((id, path)) = entry.split_once('=') else {
+                eprintln!("⚠️  Ignoring malformed ADDITIONAL_MODELS entry: {}", entry);
+                continue;
+            };
+            let (id, path) = (id.trim(), PathBuf::from(path.trim()));
+            if !path.exists() {
+                eprintln!(
+                    "⚠️  Additional model {} not found at: {}",
+                    id,
+                    path.display()
+                );
+                continue;
+            }
+            llm_engine
+                .register_model(
+                    id,
+                    ModelConfig {
+                        model_path: path.clone(),
+                        model_type: "llama".to_string(),
+                        context_size: max_context_length,
+                        gpu_layers,
+                        rope_freq_base: 10000.0,
+                        rope_freq_scale: 1.0,
+                        chat_template: None,
+                    },
+                )
+                .await;
+            println!("📦 Registered model {} ({})", id, path.display());
+        }
+    }
+
     // Configure P2P node
     println!("\n📡 Configuring P2P networking...");
+    println!("   Transport: {}", p2p_transport);
+    let listen_addresses: Vec<libp2p::Multiaddr> = vec![
+        format!("/ip4/0.0.0.0/tcp/{}", p2p_port).parse()?,
+        format!("/ip4/0.0.0.0/tcp/{}", p2p_port.parse::<u16>()? + 1).parse()?,
+        format!("/ip4/0.0.0.0/udp/{}/quic-v1", p2p_port.parse::<u16>()? + 2).parse()?,
+    ]
+    .into_iter()
+    .filter(|addr| p2p_transport.supports(addr))
+    .collect();
     let node_config = NodeConfig {
-        listen_addresses: vec![
-            format!("/ip4/0.0.0.0/tcp/{}", p2p_port).parse()?,
-            format!("/ip4/0.0.0.0/tcp/{}", p2p_port.parse::<u16>()? + 1).parse()?,
-            format!("/ip4/0.0.0.0/udp/{}/quic-v1", p2p_port.parse::<u16>()? + 2).parse()?,
-        ],
+        listen_addresses,
+        transport: p2p_transport,
         capabilities: vec![
             "llama".to_string(),
             "vicuna".to_string(),
@@ -307,12 +392,18 @@ async fn main() -> Result<()> {
 
     // Configure and start API server
     println!("\n🌐 Starting API server...");
+    let api_keys = auth::api_keys_from_env();
     let api_config = ApiConfig {
         listen_addr: format!("0.0.0.0:{}", api_port),
         enable_websocket: true,
-        cors_allowed_origins: vec!["*".to_string()],
+        require_api_key: !api_keys.is_empty(),
+        api_keys,
+        public_paths: auth::public_paths_from_env(),
+        rate_limits: RateLimitConfig::from_env(),
+        request_log: RequestLogConfig::from_env(),
         ..Default::default()
-    };
+    }
+    .with_cors_from_env();
 
     // Create API server and pass the loaded model ID
     let api_server = ApiServer::new(api_config).await?;
@@ -410,6 +501,20 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Initialize searchVectors reranker (optional cross-encoder)
+    if let Ok(reranker_dir) = env::var(fabstir_llm_node::rag::reranker::RERANKER_MODEL_DIR_ENV) {
+        match fabstir_llm_node::rag::OnnxReranker::new(&reranker_dir).await {
+            Ok(reranker) => {
+                api_server.set_reranker(Arc::new(reranker)).await;
+                println!("✅ Reranker loaded from {}", reranker_dir);
+            }
+            Err(e) => {
+                println!("⚠️  Failed to load reranker from {}: {}", reranker_dir, e);
+                println!("   searchVectors results will keep vector order");
+            }
+        }
+    }
+
     // Initialize Diffusion Client (v8.16.0+ - image generation)
     // Optional: requires DIFFUSION_ENDPOINT env var
     let diffusion_endpoint = env::var("DIFFUSION_ENDPOINT").ok();
@@ -419,12 +524,17 @@ async fn main() -> Result<()> {
     if let Some(ref endpoint) = diffusion_endpoint {
         match fabstir_llm_node::diffusion::DiffusionClient::new(endpoint, &diffusion_model_name) {
             Ok(client) => {
+                let queue_config = client.queue().config();
                 let client = Arc::new(client);
                 api_server.set_diffusion_client(client).await;
                 println!(
                     "🎨 Diffusion sidecar configured: endpoint={}, model={}",
                     endpoint, diffusion_model_name
                 );
+                println!(
+                    "   Generation queue: {} concurrent, {} waiting max",
+                    queue_config.max_concurrent, queue_config.max_queued
+                );
             }
             Err(e) => {
                 println!("⚠️  Failed to create diffusion client: {}", e);
@@ -476,6 +586,7 @@ async fn main() -> Result<()> {
         match Web3Client::new(web3_config).await {
             Ok(web3_client) => {
                 let web3_client = Arc::new(web3_client);
+                api_server.set_web3_client(web3_client.clone()).await;
                 match CheckpointManager::new(web3_client).await {
                     Ok(checkpoint_manager) => {
                         api_server
@@ -499,6 +610,50 @@ async fn main() -> Result<()> {
         println!("   To enable payments, set HOST_PRIVATE_KEY environment variable");
     }
 
+    // Let /health/ready probe the S5 bridge when one is configured
+    if let Ok(s5_url) = env::var("ENHANCED_S5_URL") {
+        match EnhancedS5Client::new(S5Config {
+            api_url: s5_url,
+            api_key: None,
+            timeout_secs: 5,
+        }) {
+            Ok(client) => api_server.set_s5_client(Arc::new(client)).await,
+            Err(e) => println!("⚠️  S5 readiness check disabled: {}", e),
+        }
+    }
+
+    // Prompt cache reported on /metrics, persisted to S5 and reloaded from
+    // there so cached answers survive a restart
+    if let (Ok(s5_url), Ok(vector_db_url)) =
+        (env::var("ENHANCED_S5_URL"), env::var("VECTOR_DB_URL"))
+    {
+        let warm_entries = env::var("PROMPT_CACHE_WARM_ENTRIES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(1000);
+        let cache_config = CacheConfig {
+            s5_url,
+            vector_db_url,
+            similarity_threshold: 0.95,
+            ttl_seconds: 3600,
+            max_cache_size_mb: 100,
+            metric: DistanceMetric::Cosine,
+        };
+        match PromptCache::new(cache_config).await {
+            Ok(cache) => {
+                let cache = Arc::new(cache);
+                api_server.set_prompt_cache(cache.clone()).await;
+                tokio::spawn(async move {
+                    match cache.warm_from_s5(warm_entries).await {
+                        Ok(loaded) => println!("🗄️  Prompt cache warmed with {} entries", loaded),
+                        Err(e) => println!("⚠️  Prompt cache warm-up failed: {}", e),
+                    }
+                });
+            }
+            Err(e) => println!("⚠️  Prompt cache disabled: {}", e),
+        }
+    }
+
     // The API server is already running in the background (started in new())
     // We don't need to call run() or spawn a task
 
@@ -523,6 +678,8 @@ async fn main() -> Result<()> {
     println!("GPU Layers:     {}", gpu_layers);
     println!("\nAPI Endpoints:");
     println!("  Health:       http://localhost:{}/health", api_port);
+    println!("  Liveness:     http://localhost:{}/health/live", api_port);
+    println!("  Readiness:    http://localhost:{}/health/ready", api_port);
     println!("  Models:       http://localhost:{}/v1/models", api_port);
     println!(
         "  Inference:    POST http://localhost:{}/v1/inference",
@@ -533,6 +690,10 @@ async fn main() -> Result<()> {
         api_port
     );
     println!("  OCR:          POST http://localhost:{}/v1/ocr", api_port);
+    println!(
+        "  OCR stream:   POST http://localhost:{}/v1/ocr/stream",
+        api_port
+    );
     println!(
         "  Describe:     POST http://localhost:{}/v1/describe-image",
         api_port
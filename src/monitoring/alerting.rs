@@ -5,6 +5,7 @@
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
@@ -13,6 +14,9 @@ use std::time::Duration;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// Firing alerts sharing a value for this label are notified together
+pub const GROUP_BY_LABEL: &str = "service_group";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlertConfig {
     pub enable_alerts: bool,
@@ -183,6 +187,14 @@ pub struct Alert {
     pub acknowledged_by: Option<String>,
     pub acknowledged_at: Option<DateTime<Utc>>,
     pub message: String,
+    /// Hash of the rule id and labels; one active alert exists per fingerprint
+    #[serde(default)]
+    pub fingerprint: String,
+    /// Number of evaluations the condition has held for
+    #[serde(default)]
+    pub occurrences: u64,
+    #[serde(default)]
+    pub last_notified_at: Option<DateTime<Utc>>,
 }
 
 impl Alert {
@@ -193,6 +205,37 @@ impl Alert {
     pub fn state(&self) -> &AlertStatus {
         &self.status
     }
+
+    /// Name of the group this alert is notified with, if it carries the
+    /// grouping label
+    pub fn group_name(&self) -> Option<String> {
+        self.labels
+            .get(GROUP_BY_LABEL)
+            .map(|value| format!("{}={}", GROUP_BY_LABEL, value))
+    }
+}
+
+/// Stable identity of an alert: its rule plus its label set. Repeated firings
+/// with the same fingerprint update one `Alert` instead of creating new ones.
+pub fn alert_fingerprint(rule_id: &str, labels: &HashMap<String, String>) -> String {
+    let mut labels: Vec<_> = labels.iter().collect();
+    labels.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(rule_id.as_bytes());
+    for (key, value) in labels {
+        hasher.update([0u8]);
+        hasher.update(key.as_bytes());
+        hasher.update([b'=']);
+        hasher.update(value.as_bytes());
+    }
+    hex::encode(&hasher.finalize()[..8])
+}
+
+/// Groups are rebuilt every evaluation, so their id is derived from the name
+/// to keep notifications for the same group under one id
+fn group_id(name: &str) -> String {
+    alert_fingerprint(name, &HashMap::new())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -252,6 +295,22 @@ pub struct AlertSilence {
     pub ends_at: DateTime<Utc>,
 }
 
+impl AlertSilence {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+
+    /// A silence matches when its rule (if set) and every one of its labels
+    /// match; a silence with neither set matches nothing
+    pub fn matches(&self, rule_id: &str, labels: &HashMap<String, String>) -> bool {
+        if self.rule_id.is_none() && self.labels.is_empty() {
+            return false;
+        }
+        self.rule_id.iter().all(|id| id == rule_id)
+            && self.labels.iter().all(|(k, v)| labels.get(k) == Some(v))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EscalationPolicy {
     pub id: String,
@@ -415,6 +474,7 @@ pub struct AlertManager {
 
 struct AlertManagerState {
     rules: HashMap<String, AlertRule>,
+    /// Keyed by fingerprint
     active_alerts: HashMap<String, Alert>,
     alert_history: Vec<Alert>,
     silences: HashMap<String, AlertSilence>,
@@ -426,6 +486,41 @@ struct AlertManagerState {
     channels: Vec<NotificationChannel>,
 }
 
+impl AlertManagerState {
+    fn is_silenced(&self, rule_id: &str, labels: &HashMap<String, String>) -> bool {
+        let now = Utc::now();
+        self.silences
+            .values()
+            .any(|s| s.is_active(now) && s.matches(rule_id, labels))
+    }
+
+    fn record_notifications(&mut self, subject_id: &str, action_type: &str) {
+        for channel in &self.channels {
+            let channel = match channel {
+                NotificationChannel::Log => "log".to_string(),
+                NotificationChannel::Webhook { url, .. } => format!("webhook:{}", url),
+                NotificationChannel::Email { .. } => "email".to_string(),
+                NotificationChannel::Slack { .. } => "slack".to_string(),
+            };
+            tracing::debug!(
+                "Sending {} notification for {} via {}",
+                action_type,
+                subject_id,
+                channel
+            );
+            self.notification_history.push(AlertNotification {
+                id: Uuid::new_v4().to_string(),
+                alert_id: subject_id.to_string(),
+                channel,
+                status: NotificationStatus::Sent,
+                sent_at: Utc::now(),
+                error: None,
+                action_type: action_type.to_string(),
+            });
+        }
+    }
+}
+
 impl AlertManager {
     pub async fn new(config: AlertConfig) -> Result<Self> {
         let state = Arc::new(RwLock::new(AlertManagerState {
@@ -532,14 +627,16 @@ impl AlertManager {
         let all_rules = state.rules.clone();
         drop(state);
 
+        let mut firing = Vec::new();
+        let mut resolved = Vec::new();
         for rule in rules {
             // Check dependencies
             if let Some(deps) = rule.annotations.get("dependencies") {
                 let deps: Vec<&str> = deps.split(',').collect();
                 let has_dependency_alert = deps.iter().any(|dep| {
-                    all_rules
-                        .values()
-                        .any(|r| r.name == *dep && active_alerts.contains_key(&r.id))
+                    all_rules.values().any(|r| {
+                        r.name == *dep && active_alerts.values().any(|a| a.rule_id == r.id)
+                    })
                 });
 
                 if has_dependency_alert {
@@ -550,15 +647,18 @@ impl AlertManager {
             let should_fire = self.evaluate_condition(&rule.condition, &metrics).await?;
 
             if should_fire {
-                self.fire_alert(&rule, &metrics).await?;
+                firing.extend(self.fire_alert(&rule, &metrics).await?);
             } else {
-                self.resolve_alert(&rule.id).await?;
+                resolved.extend(self.resolve_alert(&rule.id).await?);
             }
         }
 
         // Group alerts after evaluation
         self.group_alerts().await?;
 
+        self.dispatch_notifications(&firing, "alert").await;
+        self.dispatch_notifications(&resolved, "resolved").await;
+
         // Execute recovery actions
         self.execute_recovery_actions().await?;
 
@@ -639,92 +739,145 @@ impl AlertManager {
         }
     }
 
-    async fn fire_alert(&self, rule: &AlertRule, metrics: &HashMap<String, f64>) -> Result<()> {
+    /// Returns the alert if a notification is due: on first firing, or once
+    /// `repeat_interval_minutes` has passed since the last one
+    async fn fire_alert(
+        &self,
+        rule: &AlertRule,
+        metrics: &HashMap<String, f64>,
+    ) -> Result<Option<Alert>> {
         let mut state = self.state.write().await;
+        let now = Utc::now();
+        let fingerprint = alert_fingerprint(&rule.id, &rule.labels);
 
-        let alert_id = format!("{}_{}", rule.id, Utc::now().timestamp());
+        if state.is_silenced(&rule.id, &rule.labels) {
+            if let Some(mut alert) = state.active_alerts.remove(&fingerprint) {
+                alert.status = AlertStatus::Silenced;
+                state.alert_history.push(alert);
+            }
+            return Ok(None);
+        }
 
-        if let Some(existing) = state.active_alerts.get_mut(&rule.id) {
-            // Update existing alert
-            existing.last_triggered_at = Utc::now();
-        } else {
-            // Check if alert is silenced
-            let is_silenced = state.silences.values().any(|s| {
-                s.ends_at > Utc::now()
-                    && (s.rule_id.as_ref() == Some(&rule.id)
-                        || s.labels.iter().all(|(k, v)| rule.labels.get(k) == Some(v)))
-            });
+        let metric = self.get_metric_from_condition(&rule.condition);
+        let value = metrics.get(&metric).copied().unwrap_or(0.0);
+        let threshold = self.get_threshold_from_condition(&rule.condition);
+        let message = format!(
+            "{} triggered: {} = {} (threshold: {})",
+            rule.name, metric, value, threshold
+        );
 
-            if is_silenced {
-                return Ok(());
-            }
+        if let Some(existing) = state.active_alerts.get_mut(&fingerprint) {
+            // Same alert firing again; update it in place
+            existing.last_triggered_at = now;
+            existing.value = value;
+            existing.message = message;
+            existing.occurrences += 1;
+
+            let repeat_interval =
+                ChronoDuration::minutes(self.config.repeat_interval_minutes as i64);
+            let repeat_due = existing
+                .last_notified_at
+                .iter()
+                .all(|sent| now - *sent >= repeat_interval);
+            return Ok(repeat_due.then(|| existing.clone()));
+        }
+
+        let alert = Alert {
+            id: format!("{}_{}", rule.id, now.timestamp()),
+            rule_id: rule.id.clone(),
+            rule_name: rule.name.clone(),
+            description: rule.description.clone(),
+            level: rule.level,
+            status: AlertStatus::Firing,
+            value,
+            threshold,
+            labels: rule.labels.clone(),
+            annotations: rule.annotations.clone(),
+            first_triggered_at: now,
+            last_triggered_at: now,
+            resolved_at: None,
+            acknowledged_by: None,
+            acknowledged_at: None,
+            message,
+            fingerprint: fingerprint.clone(),
+            occurrences: 1,
+            last_notified_at: None,
+        };
 
-            // Create new alert
-            let alert = Alert {
-                id: alert_id,
-                rule_id: rule.id.clone(),
-                rule_name: rule.name.clone(),
-                description: rule.description.clone(),
-                level: rule.level,
-                status: AlertStatus::Firing,
-                value: metrics
-                    .get(&self.get_metric_from_condition(&rule.condition))
-                    .copied()
-                    .unwrap_or(0.0),
-                threshold: self.get_threshold_from_condition(&rule.condition),
-                labels: rule.labels.clone(),
-                annotations: rule.annotations.clone(),
-                first_triggered_at: Utc::now(),
-                last_triggered_at: Utc::now(),
-                resolved_at: None,
-                acknowledged_by: None,
-                acknowledged_at: None,
-                message: format!(
-                    "{} triggered: {} = {} (threshold: {})",
-                    rule.name,
-                    self.get_metric_from_condition(&rule.condition),
-                    metrics
-                        .get(&self.get_metric_from_condition(&rule.condition))
-                        .copied()
-                        .unwrap_or(0.0),
-                    self.get_threshold_from_condition(&rule.condition)
-                ),
+        state.active_alerts.insert(fingerprint, alert.clone());
+        state.alert_history.push(alert.clone());
+
+        // Record recovery action if present
+        if rule.annotations.get("has_recovery_actions") == Some(&"true".to_string()) {
+            let notification = AlertNotification {
+                id: Uuid::new_v4().to_string(),
+                alert_id: alert.id.clone(),
+                channel: "recovery_action".to_string(),
+                status: NotificationStatus::Sent,
+                sent_at: Utc::now(),
+                error: None,
+                action_type: "recovery".to_string(),
             };
+            state.notification_history.push(notification);
+        }
 
-            state.active_alerts.insert(rule.id.clone(), alert.clone());
-            state.alert_history.push(alert.clone());
+        Ok(Some(alert))
+    }
 
-            // Send notifications
-            self.send_notifications(&alert, &state.channels).await;
+    /// Returns the resolved alerts that had been notified as firing, which
+    /// are owed a resolve notification
+    async fn resolve_alert(&self, rule_id: &str) -> Result<Vec<Alert>> {
+        let mut state = self.state.write().await;
 
-            // Record recovery action if present
-            if rule.annotations.get("has_recovery_actions") == Some(&"true".to_string()) {
-                let notification = AlertNotification {
-                    id: Uuid::new_v4().to_string(),
-                    alert_id: alert.id.clone(),
-                    channel: "recovery_action".to_string(),
-                    status: NotificationStatus::Sent,
-                    sent_at: Utc::now(),
-                    error: None,
-                    action_type: "recovery".to_string(),
-                };
-                state.notification_history.push(notification);
+        let fingerprints: Vec<String> = state
+            .active_alerts
+            .values()
+            .filter(|a| a.rule_id == rule_id)
+            .map(|a| a.fingerprint.clone())
+            .collect();
+
+        let mut notify = Vec::new();
+        for fingerprint in fingerprints {
+            if let Some(mut alert) = state.active_alerts.remove(&fingerprint) {
+                alert.status = AlertStatus::Resolved;
+                alert.resolved_at = Some(Utc::now());
+                if alert.last_notified_at.is_some() {
+                    notify.push(alert.clone());
+                }
+                state.alert_history.push(alert);
             }
         }
 
-        Ok(())
+        Ok(notify)
     }
 
-    async fn resolve_alert(&self, rule_id: &str) -> Result<()> {
+    /// One notification per channel for each group with alerts to report,
+    /// plus one per ungrouped alert
+    async fn dispatch_notifications(&self, alerts: &[Alert], action_type: &str) {
+        if alerts.is_empty() {
+            return;
+        }
+
         let mut state = self.state.write().await;
+        let now = Utc::now();
+        let mut subjects: Vec<String> = Vec::new();
 
-        if let Some(mut alert) = state.active_alerts.remove(rule_id) {
-            alert.status = AlertStatus::Resolved;
-            alert.resolved_at = Some(Utc::now());
-            state.alert_history.push(alert);
+        for alert in alerts {
+            let subject = match alert.group_name() {
+                Some(name) => group_id(&name),
+                None => alert.id.clone(),
+            };
+            if !subjects.contains(&subject) {
+                subjects.push(subject);
+            }
+            if let Some(active) = state.active_alerts.get_mut(&alert.fingerprint) {
+                active.last_notified_at = Some(now);
+            }
         }
 
-        Ok(())
+        for subject in subjects {
+            state.record_notifications(&subject, action_type);
+        }
     }
 
     fn get_metric_from_condition(&self, condition: &AlertCondition) -> String {
@@ -763,14 +916,14 @@ impl AlertManager {
         let mut state = self.state.write().await;
 
         // Find alert by its ID in active alerts
-        let rule_id = state
+        let fingerprint = state
             .active_alerts
             .values()
             .find(|a| a.id == alert_id)
-            .map(|a| a.rule_id.clone());
+            .map(|a| a.fingerprint.clone());
 
-        if let Some(rule_id) = rule_id {
-            if let Some(alert) = state.active_alerts.get_mut(&rule_id) {
+        if let Some(fingerprint) = fingerprint {
+            if let Some(alert) = state.active_alerts.get_mut(&fingerprint) {
                 alert.status = AlertStatus::Silenced;
 
                 let silence = AlertSilence {
@@ -786,7 +939,7 @@ impl AlertManager {
                 state.silences.insert(silence.id.clone(), silence);
 
                 // Remove from active alerts when silenced
-                state.active_alerts.remove(&rule_id);
+                state.active_alerts.remove(&fingerprint);
                 Ok(())
             } else {
                 Err(AlertError::AlertNotFound(alert_id.to_string()).into())
@@ -799,7 +952,7 @@ impl AlertManager {
     pub async fn acknowledge_alert(&self, alert_id: &str, user: &str) -> Result<()> {
         let mut state = self.state.write().await;
 
-        if let Some(alert) = state.active_alerts.get_mut(alert_id) {
+        if let Some(alert) = state.active_alerts.values_mut().find(|a| a.id == alert_id) {
             alert.acknowledged_by = Some(user.to_string());
             alert.acknowledged_at = Some(Utc::now());
             Ok(())
@@ -824,7 +977,7 @@ impl AlertManager {
 
         if state.rules.remove(rule_id).is_some() {
             // Also remove any active alerts for this rule
-            state.active_alerts.remove(rule_id);
+            state.active_alerts.retain(|_, a| a.rule_id != rule_id);
             Ok(())
         } else {
             Err(AlertError::RuleNotFound(rule_id.to_string()).into())
@@ -1085,28 +1238,6 @@ impl AlertManager {
         }
     }
 
-    async fn send_notifications(&self, alert: &Alert, channels: &[NotificationChannel]) {
-        for channel in channels {
-            let notification = AlertNotification {
-                id: Uuid::new_v4().to_string(),
-                alert_id: alert.id.clone(),
-                channel: match channel {
-                    NotificationChannel::Log => "log".to_string(),
-                    NotificationChannel::Webhook { url, .. } => format!("webhook:{}", url),
-                    NotificationChannel::Email { .. } => "email".to_string(),
-                    NotificationChannel::Slack { .. } => "slack".to_string(),
-                },
-                status: NotificationStatus::Sent,
-                sent_at: Utc::now(),
-                error: None,
-                action_type: "alert".to_string(),
-            };
-
-            let mut state = self.state.write().await;
-            state.notification_history.push(notification);
-        }
-    }
-
     async fn group_alerts(&self) -> Result<()> {
         let mut state = self.state.write().await;
         let active_alerts = state.active_alerts.clone();
@@ -1118,9 +1249,7 @@ impl AlertManager {
         let mut groups_by_label: HashMap<String, Vec<Alert>> = HashMap::new();
 
         for alert in active_alerts.values() {
-            // Group by service_group label if present
-            if let Some(group) = alert.labels.get("service_group") {
-                let key = format!("service_group={}", group);
+            if let Some(key) = alert.group_name() {
                 groups_by_label
                     .entry(key)
                     .or_insert_with(Vec::new)
@@ -1132,7 +1261,7 @@ impl AlertManager {
         for (label, alerts) in groups_by_label {
             if !alerts.is_empty() {
                 let group = AlertGroup {
-                    id: Uuid::new_v4().to_string(),
+                    id: group_id(&label),
                     name: label.clone(),
                     alerts: alerts.clone(),
                     labels: HashMap::from([("grouped_by".to_string(), label.clone())]),
@@ -4,8 +4,9 @@
 
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -105,6 +106,77 @@ impl Dashboard {
         };
         self.variables.insert(name.to_string(), variable);
     }
+
+    /// Export as a Grafana dashboard model. Fields Grafana has no place for
+    /// (creation times, panel names, query ids) are not exported.
+    pub fn to_export(&self) -> Result<DashboardExport> {
+        let mut variables: Vec<&Variable> = self.variables.values().collect();
+        variables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let model = json!({
+            "uid": self.name,
+            "title": self.title,
+            "description": self.description,
+            "tags": self.tags,
+            "refresh": grafana_interval(self.refresh_interval_seconds),
+            "time": grafana_time_range(self.time_range),
+            "version": self.version,
+            "schemaVersion": GRAFANA_SCHEMA_VERSION,
+            "templating": {
+                "list": variables.into_iter().map(grafana_variable).collect::<Vec<_>>(),
+            },
+            "panels": self
+                .panels
+                .iter()
+                .enumerate()
+                .map(|(index, panel)| grafana_panel(index, panel))
+                .collect::<Result<Vec<_>>>()?,
+        });
+
+        Ok(DashboardExport {
+            format: GRAFANA_EXPORT_FORMAT.to_string(),
+            content: serde_json::to_string_pretty(&model)?,
+            metadata: ExportMetadata {
+                exported_at: Utc::now(),
+                exported_by: self.created_by.clone(),
+                version: crate::version::VERSION_NUMBER.to_string(),
+            },
+        })
+    }
+
+    /// Rebuild a dashboard from a Grafana export. Panels of a type this node
+    /// does not know are kept as `Widget::Passthrough` and exported unchanged.
+    pub fn from_export(export: &DashboardExport) -> Result<Self> {
+        if export.format != GRAFANA_EXPORT_FORMAT {
+            return Err(anyhow!("Unsupported export format: {}", export.format));
+        }
+        let model: JsonValue = serde_json::from_str(&export.content)?;
+        let text = |key: &str| model[key].as_str().unwrap_or_default().to_string();
+
+        let mut dashboard = Dashboard::new(&text("uid"), &text("title"), &text("description"));
+        dashboard.tags = model["tags"]
+            .as_array()
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        dashboard.refresh_interval_seconds = parse_grafana_interval(&model["refresh"]);
+        dashboard.time_range = parse_grafana_time_range(&model["time"]);
+        dashboard.version = model["version"].as_u64().unwrap_or(1) as u32;
+        dashboard.created_by = export.metadata.exported_by.clone();
+
+        for variable in model["templating"]["list"].as_array().into_iter().flatten() {
+            let variable = parse_grafana_variable(variable);
+            dashboard.variables.insert(variable.name.clone(), variable);
+        }
+        for panel in model["panels"].as_array().into_iter().flatten() {
+            dashboard.panels.push(parse_grafana_panel(panel));
+        }
+
+        Ok(dashboard)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -175,6 +247,26 @@ pub enum Widget {
     Stat(StatWidget),
     PieChart(PieChartWidget),
     BarChart(BarChartWidget),
+    /// A panel type this node does not model, carried through imports and
+    /// exports untouched
+    Passthrough(PassthroughWidget),
+}
+
+impl Widget {
+    /// Grafana panel plugin id
+    pub fn grafana_type(&self) -> &str {
+        match self {
+            Widget::Graph(_) => "timeseries",
+            Widget::Gauge(_) => "gauge",
+            Widget::Table(_) => "table",
+            Widget::Heatmap(_) => "heatmap",
+            Widget::Log(_) => "logs",
+            Widget::Stat(_) => "stat",
+            Widget::PieChart(_) => "piechart",
+            Widget::BarChart(_) => "barchart",
+            Widget::Passthrough(widget) => &widget.panel_type,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -191,6 +283,7 @@ pub enum WidgetType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct GraphWidget {
     pub legend_enabled: bool,
     pub y_axis_label: String,
@@ -201,6 +294,7 @@ pub struct GraphWidget {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct GaugeWidget {
     pub min_value: f64,
     pub max_value: f64,
@@ -210,6 +304,7 @@ pub struct GaugeWidget {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct TableWidget {
     pub columns: Vec<TableColumn>,
     pub show_header: bool,
@@ -217,6 +312,7 @@ pub struct TableWidget {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct HeatmapWidget {
     pub color_scheme: String,
     pub bucket_size: u32,
@@ -225,6 +321,7 @@ pub struct HeatmapWidget {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct LogWidget {
     pub wrap_lines: bool,
     pub show_timestamps: bool,
@@ -232,6 +329,7 @@ pub struct LogWidget {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct StatWidget {
     pub unit: String,
     pub decimals: u32,
@@ -240,6 +338,7 @@ pub struct StatWidget {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct PieChartWidget {
     pub legend_enabled: bool,
     pub show_percentages: bool,
@@ -247,12 +346,20 @@ pub struct PieChartWidget {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct BarChartWidget {
     pub orientation: BarOrientation,
     pub stacked: bool,
     pub show_values: bool,
 }
 
+/// The original Grafana panel JSON of an unrecognised panel type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassthroughWidget {
+    pub panel_type: String,
+    pub panel: JsonValue,
+}
+
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub enum BarOrientation {
     Horizontal,
@@ -415,6 +522,354 @@ pub struct ExportMetadata {
     pub version: String,
 }
 
+pub const GRAFANA_EXPORT_FORMAT: &str = "grafana";
+const GRAFANA_SCHEMA_VERSION: u32 = 39;
+const REF_IDS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+fn grafana_interval(seconds: u64) -> String {
+    match seconds {
+        0 => String::new(),
+        s if s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
+/// Split a Grafana duration such as `15m` into `(15, 'm')`
+fn split_duration(text: &str) -> Option<(u64, char)> {
+    let unit = text.chars().last()?;
+    let number = text[..text.len() - unit.len_utf8()].parse().ok()?;
+    Some((number, unit))
+}
+
+fn parse_grafana_interval(value: &JsonValue) -> u64 {
+    match value.as_str().and_then(split_duration) {
+        Some((hours, 'h')) => hours * 3600,
+        Some((minutes, 'm')) => minutes * 60,
+        Some((seconds, 's')) => seconds,
+        _ => 0,
+    }
+}
+
+fn grafana_time_range(range: TimeRange) -> JsonValue {
+    let (from, to) = match range {
+        TimeRange::LastHour => ("now-1h".to_string(), "now".to_string()),
+        TimeRange::LastDay => ("now-24h".to_string(), "now".to_string()),
+        TimeRange::LastWeek => ("now-7d".to_string(), "now".to_string()),
+        TimeRange::LastMonth => ("now-30d".to_string(), "now".to_string()),
+        TimeRange::Custom {
+            from_hours,
+            to_hours,
+        } => (
+            format!("now-{}h", from_hours),
+            if to_hours == 0 {
+                "now".to_string()
+            } else {
+                format!("now-{}h", to_hours)
+            },
+        ),
+    };
+    json!({ "from": from, "to": to })
+}
+
+fn parse_grafana_time_range(value: &JsonValue) -> TimeRange {
+    let from = value["from"].as_str().unwrap_or("now-1h");
+    let to = value["to"].as_str().unwrap_or("now");
+    let hours_ago = |t: &str| -> Option<i64> {
+        if t == "now" {
+            return Some(0);
+        }
+        match split_duration(t.strip_prefix("now-")?)? {
+            (hours, 'h') => Some(hours as i64),
+            (days, 'd') => Some(days as i64 * 24),
+            _ => None,
+        }
+    };
+
+    match (hours_ago(from), hours_ago(to)) {
+        (Some(1), Some(0)) => TimeRange::LastHour,
+        (Some(24), Some(0)) => TimeRange::LastDay,
+        (Some(168), Some(0)) => TimeRange::LastWeek,
+        (Some(720), Some(0)) => TimeRange::LastMonth,
+        (Some(from_hours), Some(to_hours)) => TimeRange::Custom {
+            from_hours,
+            to_hours,
+        },
+        _ => TimeRange::LastHour,
+    }
+}
+
+fn grafana_variable(variable: &Variable) -> JsonValue {
+    let variable_type = match variable.variable_type {
+        VariableType::Query => "query",
+        VariableType::Custom => "custom",
+        VariableType::Interval => "interval",
+        VariableType::Datasource => "datasource",
+        VariableType::Constant => "constant",
+    };
+    json!({
+        "name": variable.name,
+        "label": variable.label,
+        "type": variable_type,
+        "current": { "text": variable.default_value, "value": variable.default_value },
+        "options": variable
+            .options
+            .iter()
+            .map(|o| json!({
+                "text": o.label,
+                "value": o.value,
+                "selected": o.value == variable.default_value,
+            }))
+            .collect::<Vec<_>>(),
+        "query": variable
+            .options
+            .iter()
+            .map(|o| o.value.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+    })
+}
+
+fn parse_grafana_variable(value: &JsonValue) -> Variable {
+    let text = |v: &JsonValue| v.as_str().unwrap_or_default().to_string();
+    Variable {
+        name: text(&value["name"]),
+        label: text(&value["label"]),
+        variable_type: match value["type"].as_str() {
+            Some("query") => VariableType::Query,
+            Some("interval") => VariableType::Interval,
+            Some("datasource") => VariableType::Datasource,
+            Some("constant") => VariableType::Constant,
+            _ => VariableType::Custom,
+        },
+        default_value: text(&value["current"]["value"]),
+        options: value["options"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|o| VariableOption {
+                label: text(&o["text"]),
+                value: text(&o["value"]),
+            })
+            .collect(),
+    }
+}
+
+/// Grafana writes the base threshold step with a `null` value
+fn grafana_thresholds(thresholds: &[Threshold]) -> JsonValue {
+    json!({
+        "mode": "absolute",
+        "steps": thresholds
+            .iter()
+            .map(|t| json!({
+                "color": t.color,
+                "value": if t.value.is_finite() { json!(t.value) } else { JsonValue::Null },
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn parse_grafana_thresholds(value: &JsonValue) -> Option<Vec<Threshold>> {
+    let steps = value["steps"].as_array()?;
+    Some(
+        steps
+            .iter()
+            .map(|step| Threshold {
+                value: step["value"].as_f64().unwrap_or(f64::NEG_INFINITY),
+                color: step["color"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect(),
+    )
+}
+
+/// Display settings Grafana reads from `fieldConfig.defaults`
+fn grafana_field_defaults(widget: &Widget) -> JsonValue {
+    match widget {
+        Widget::Gauge(gauge) => json!({
+            "unit": gauge.unit,
+            "min": gauge.min_value,
+            "max": gauge.max_value,
+            "thresholds": grafana_thresholds(&gauge.thresholds),
+        }),
+        Widget::Stat(stat) => json!({
+            "unit": stat.unit,
+            "decimals": stat.decimals,
+            "thresholds": grafana_thresholds(&stat.thresholds),
+        }),
+        _ => json!({}),
+    }
+}
+
+fn grafana_panel(index: usize, panel: &Panel) -> Result<JsonValue> {
+    let mut model = match &panel.widget {
+        Widget::Passthrough(widget) => widget.panel.as_object().cloned().unwrap_or_default(),
+        widget => {
+            // The widget's own settings ride along in `options`; Grafana
+            // ignores the keys it does not recognise
+            let mut options = serde_json::to_value(widget)?;
+            if let Some(options) = options.as_object_mut() {
+                options.remove("type");
+                // Carried in fieldConfig, where Grafana expects them
+                options.remove("thresholds");
+            }
+            let mut model = JsonMap::new();
+            model.insert("options".to_string(), options);
+            model.insert(
+                "fieldConfig".to_string(),
+                json!({ "defaults": grafana_field_defaults(widget), "overrides": [] }),
+            );
+            model
+        }
+    };
+
+    model.insert("id".to_string(), json!(index + 1));
+    model.insert("type".to_string(), json!(panel.widget.grafana_type()));
+    model.insert("title".to_string(), json!(panel.title));
+    model.insert(
+        "gridPos".to_string(),
+        json!({
+            "x": panel.position.x,
+            "y": panel.position.y,
+            "w": panel.position.width,
+            "h": panel.position.height,
+        }),
+    );
+    model.insert(
+        "targets".to_string(),
+        JsonValue::Array(
+            panel
+                .queries
+                .iter()
+                .enumerate()
+                .map(|(i, query)| {
+                    let mut target = json!({
+                        "refId": (REF_IDS[i % REF_IDS.len()] as char).to_string(),
+                        "datasource": { "uid": query.datasource },
+                        "expr": query.query,
+                    });
+                    if let Some(legend) = &query.legend {
+                        target["legendFormat"] = json!(legend);
+                    }
+                    if let Some(aggregation) = &query.aggregation {
+                        target["aggregation"] = json!(aggregation);
+                    }
+                    if let Some(seconds) = query.refetch_interval_seconds {
+                        target["interval"] = json!(grafana_interval(seconds));
+                    }
+                    target
+                })
+                .collect(),
+        ),
+    );
+    if let Some(seconds) = panel.refresh_interval_seconds {
+        model.insert("interval".to_string(), json!(grafana_interval(seconds)));
+    }
+
+    Ok(JsonValue::Object(model))
+}
+
+/// Widget settings from a panel's `options`, or `None` if they do not fit
+/// the widget this node models for that panel type
+fn parse_widget_options<T: DeserializeOwned>(value: &JsonValue) -> Option<T> {
+    match &value["options"] {
+        JsonValue::Null => serde_json::from_value(json!({})).ok(),
+        options => serde_json::from_value(options.clone()).ok(),
+    }
+}
+
+fn parse_grafana_panel(value: &JsonValue) -> Panel {
+    let panel_type = value["type"].as_str().unwrap_or_default();
+    let defaults = &value["fieldConfig"]["defaults"];
+
+    let widget = match panel_type {
+        "timeseries" | "graph" => parse_widget_options(value).map(Widget::Graph),
+        "table" => parse_widget_options(value).map(Widget::Table),
+        "heatmap" => parse_widget_options(value).map(Widget::Heatmap),
+        "logs" => parse_widget_options(value).map(Widget::Log),
+        "piechart" => parse_widget_options(value).map(Widget::PieChart),
+        "barchart" => parse_widget_options(value).map(Widget::BarChart),
+        "gauge" => parse_widget_options(value).map(|mut gauge: GaugeWidget| {
+            // Edits made in Grafana land in fieldConfig, so it wins
+            if let Some(unit) = defaults["unit"].as_str() {
+                gauge.unit = unit.to_string();
+            }
+            if let Some(min) = defaults["min"].as_f64() {
+                gauge.min_value = min;
+            }
+            if let Some(max) = defaults["max"].as_f64() {
+                gauge.max_value = max;
+            }
+            if let Some(thresholds) = parse_grafana_thresholds(&defaults["thresholds"]) {
+                gauge.thresholds = thresholds;
+            }
+            Widget::Gauge(gauge)
+        }),
+        "stat" | "singlestat" => parse_widget_options(value).map(|mut stat: StatWidget| {
+            if let Some(unit) = defaults["unit"].as_str() {
+                stat.unit = unit.to_string();
+            }
+            if let Some(decimals) = defaults["decimals"].as_u64() {
+                stat.decimals = decimals as u32;
+            }
+            if let Some(thresholds) = parse_grafana_thresholds(&defaults["thresholds"]) {
+                stat.thresholds = thresholds;
+            }
+            Widget::Stat(stat)
+        }),
+        _ => None,
+    }
+    .unwrap_or_else(|| {
+        Widget::Passthrough(PassthroughWidget {
+            panel_type: panel_type.to_string(),
+            panel: value.clone(),
+        })
+    });
+
+    let title = value["title"].as_str().unwrap_or_default();
+    let grid = &value["gridPos"];
+    let coordinate = |key: &str| grid[key].as_i64().unwrap_or(0) as i32;
+
+    let queries = value["targets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|target| {
+            let datasource = target["datasource"]["uid"]
+                .as_str()
+                .or_else(|| target["datasource"].as_str())
+                .unwrap_or_default();
+            let mut query = Query::new(datasource, target["expr"].as_str().unwrap_or_default());
+            query.legend = target["legendFormat"].as_str().map(str::to_string);
+            query.aggregation = target["aggregation"].as_str().map(str::to_string);
+            query.refetch_interval_seconds = target
+                .get("interval")
+                .map(parse_grafana_interval)
+                .filter(|&s| s > 0);
+            query
+        })
+        .collect();
+
+    Panel {
+        id: Uuid::new_v4().to_string(),
+        // Grafana panels have no name separate from the title
+        name: title.to_string(),
+        title: title.to_string(),
+        widget,
+        position: GridPosition {
+            x: coordinate("x"),
+            y: coordinate("y"),
+            width: coordinate("w"),
+            height: coordinate("h"),
+        },
+        queries,
+        refresh_interval_seconds: value
+            .get("interval")
+            .map(parse_grafana_interval)
+            .filter(|&s| s > 0),
+        responsive_positions: HashMap::new(),
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResult {
     pub data: JsonValue,
@@ -730,6 +1185,7 @@ impl DashboardManager {
 
         match format {
             "json" => Ok(serde_json::to_string_pretty(dashboard)?),
+            GRAFANA_EXPORT_FORMAT => Ok(dashboard.to_export()?.content),
             "yaml" => {
                 // For yaml, we'll just use JSON for now
                 Ok(serde_json::to_string_pretty(dashboard)?)
@@ -772,6 +1228,17 @@ impl DashboardManager {
         Ok(id)
     }
 
+    /// Import a dashboard produced by `Dashboard::to_export`
+    pub async fn import_export(&self, export: &DashboardExport) -> Result<String> {
+        let dashboard = Dashboard::from_export(export)?;
+        let id = dashboard.id.clone();
+
+        let mut state = self.state.write().await;
+        state.dashboards.insert(id.clone(), dashboard);
+
+        Ok(id)
+    }
+
     pub async fn add_annotation(
         &self,
         dashboard_id: &str,
@@ -99,6 +99,9 @@ pub struct LivenessProbe {
 pub struct ReadinessProbe {
     pub is_ready: bool,
     pub components_ready: HashMap<String, bool>,
+    /// Per-dependency detail behind `components_ready`
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub dependencies: HashMap<String, ComponentHealth>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -397,6 +400,7 @@ impl HealthChecker {
             return Ok(ReadinessProbe {
                 is_ready: false,
                 components_ready: state.ready_components.clone(),
+                dependencies: HashMap::new(),
             });
         }
 
@@ -405,6 +409,7 @@ impl HealthChecker {
         Ok(ReadinessProbe {
             is_ready: all_ready,
             components_ready: state.ready_components.clone(),
+            dependencies: HashMap::new(),
         })
     }
 
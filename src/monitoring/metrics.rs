@@ -6,7 +6,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -172,8 +172,11 @@ struct MetricsState {
     subscriptions: HashMap<String, Vec<tokio::sync::mpsc::Sender<Metric>>>,
 }
 
+/// Shared registry of metric families, each possibly split into labeled
+/// series, rendered together on scrape
 pub struct MetricsRegistry {
-    collectors: HashMap<String, Arc<dyn MetricCollector>>,
+    collectors: RwLock<HashMap<String, Arc<dyn MetricCollector>>>,
+    series: RwLock<BTreeMap<(String, Vec<(String, String)>), Metric>>,
 }
 
 #[async_trait]
@@ -184,6 +187,122 @@ pub trait MetricCollector: Send + Sync {
     async fn collect(&self) -> Result<MetricValue>;
 }
 
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self {
+            collectors: RwLock::new(HashMap::new()),
+            series: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Add a collector sampled on every `gather`
+    pub async fn register(&self, collector: Arc<dyn MetricCollector>) {
+        self.collectors
+            .write()
+            .await
+            .insert(collector.name().to_string(), collector);
+    }
+
+    pub async fn set_gauge(&self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        self.set(
+            name,
+            help,
+            labels,
+            MetricType::Gauge,
+            MetricValue::Gauge(value),
+        )
+        .await;
+    }
+
+    /// Counters mirror a running total kept by the owning subsystem, so they
+    /// are set rather than incremented
+    pub async fn set_counter(&self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        self.set(
+            name,
+            help,
+            labels,
+            MetricType::Counter,
+            MetricValue::Counter(value),
+        )
+        .await;
+    }
+
+    /// Drop every series of a family, e.g. before re-recording per-model
+    /// series so unloaded models disappear
+    pub async fn clear_family(&self, name: &str) {
+        self.series
+            .write()
+            .await
+            .retain(|(family, _), _| family != name);
+    }
+
+    async fn set(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        metric_type: MetricType,
+        value: MetricValue,
+    ) {
+        let key: Vec<(String, String)> = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let metric = Metric {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type,
+            value,
+            labels: key
+                .iter()
+                .map(|(name, value)| MetricLabel {
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+                .collect(),
+            timestamp: Utc::now(),
+            last_updated: Instant::now(),
+        };
+        self.series
+            .write()
+            .await
+            .insert((name.to_string(), key), metric);
+    }
+
+    /// All series, ordered by name so each family is rendered contiguously
+    pub async fn gather(&self) -> Vec<Metric> {
+        let mut metrics: Vec<Metric> = self.series.read().await.values().cloned().collect();
+
+        for collector in self.collectors.read().await.values() {
+            match collector.collect().await {
+                Ok(value) => metrics.push(Metric {
+                    name: collector.name().to_string(),
+                    help: collector.help().to_string(),
+                    metric_type: collector.metric_type(),
+                    value,
+                    labels: vec![],
+                    timestamp: Utc::now(),
+                    last_updated: Instant::now(),
+                }),
+                Err(e) => tracing::warn!("Metric collector {} failed: {}", collector.name(), e),
+            }
+        }
+
+        metrics.sort_by(|a, b| a.name.cmp(&b.name));
+        metrics
+    }
+
+    pub async fn export(&self, exporter: &dyn MetricsExporter) -> Result<String> {
+        exporter.export(self.gather().await).await
+    }
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Counter implementation
 #[derive(Clone)]
 pub struct Counter {
@@ -341,6 +460,20 @@ pub struct Histogram {
 }
 
 impl Histogram {
+    /// Creates a standalone histogram that is not registered with a collector
+    pub fn new(name: &str, help: &str, buckets: Vec<f64>) -> Self {
+        Histogram {
+            name: name.to_string(),
+            help: help.to_string(),
+            bucket_counts: Arc::new(RwLock::new(vec![0; buckets.len()])),
+            buckets,
+            sum: Arc::new(RwLock::new(0.0)),
+            count: Arc::new(RwLock::new(0)),
+            observations: Arc::new(RwLock::new(vec![])),
+            collector: None,
+        }
+    }
+
     pub async fn observe(&self, v: f64) {
         // Update buckets
         let mut bucket_counts = self.bucket_counts.write().await;
@@ -410,6 +543,26 @@ impl Histogram {
             p99,
         }
     }
+
+    /// Returns `(quantile, value)` pairs for arbitrary quantiles in `0.0..=1.0`
+    pub async fn quantiles(&self, quantiles: &[f64]) -> Vec<(f64, f64)> {
+        let mut sorted = self.observations.read().await.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        quantiles
+            .iter()
+            .map(|&q| (q, percentile(&sorted, q.clamp(0.0, 1.0))))
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for Histogram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Histogram")
+            .field("name", &self.name)
+            .field("buckets", &self.buckets)
+            .finish_non_exhaustive()
+    }
 }
 
 fn percentile(sorted: &[f64], p: f64) -> f64 {
@@ -532,33 +685,39 @@ impl PrometheusExporter {
 impl MetricsExporter for PrometheusExporter {
     async fn export(&self, metrics: Vec<Metric>) -> Result<String> {
         let mut output = String::new();
+        let mut described = HashSet::new();
 
         for metric in metrics {
-            // Write HELP and TYPE
-            output.push_str(&format!("# HELP {} {}\n", metric.name, metric.help));
-            output.push_str(&format!(
-                "# TYPE {} {}\n",
-                metric.name,
-                match metric.metric_type {
-                    MetricType::Counter => "counter",
-                    MetricType::Gauge => "gauge",
-                    MetricType::Histogram => "histogram",
-                    MetricType::Summary => "summary",
-                }
-            ));
+            // Write HELP and TYPE once per metric family, even when several
+            // label sets share the same name
+            if described.insert(metric.name.clone()) {
+                output.push_str(&format!("# HELP {} {}\n", metric.name, metric.help));
+                output.push_str(&format!(
+                    "# TYPE {} {}\n",
+                    metric.name,
+                    match metric.metric_type {
+                        MetricType::Counter => "counter",
+                        MetricType::Gauge => "gauge",
+                        MetricType::Histogram => "histogram",
+                        MetricType::Summary => "summary",
+                    }
+                ));
+            }
+
+            let labels = format_labels(&metric.labels, None);
 
             // Write metric value
             match &metric.value {
                 MetricValue::Counter(v) => {
                     // Format counters as integers if they're whole numbers
                     if v.fract() == 0.0 {
-                        output.push_str(&format!("{} {}\n", metric.name, *v as i64));
+                        output.push_str(&format!("{}{} {}\n", metric.name, labels, *v as i64));
                     } else {
-                        output.push_str(&format!("{} {}\n", metric.name, v));
+                        output.push_str(&format!("{}{} {}\n", metric.name, labels, v));
                     }
                 }
                 MetricValue::Gauge(v) => {
-                    output.push_str(&format!("{} {}\n", metric.name, v));
+                    output.push_str(&format!("{}{} {}\n", metric.name, labels, v));
                 }
                 MetricValue::Histogram {
                     buckets,
@@ -568,16 +727,20 @@ impl MetricsExporter for PrometheusExporter {
                     // Write bucket values
                     for (bucket_bound, bucket_count) in buckets {
                         output.push_str(&format!(
-                            "{}_bucket{{le=\"{}\"}} {}\n",
-                            metric.name, bucket_bound, bucket_count
+                            "{}_bucket{} {}\n",
+                            metric.name,
+                            format_labels(&metric.labels, Some(("le", bucket_bound.to_string()))),
+                            bucket_count
                         ));
                     }
                     output.push_str(&format!(
-                        "{}_bucket{{le=\"+Inf\"}} {}\n",
-                        metric.name, count
+                        "{}_bucket{} {}\n",
+                        metric.name,
+                        format_labels(&metric.labels, Some(("le", "+Inf".to_string()))),
+                        count
                     ));
-                    output.push_str(&format!("{}_sum {}\n", metric.name, sum));
-                    output.push_str(&format!("{}_count {}\n", metric.name, count));
+                    output.push_str(&format!("{}_sum{} {}\n", metric.name, labels, sum));
+                    output.push_str(&format!("{}_count{} {}\n", metric.name, labels, count));
                 }
                 MetricValue::Summary {
                     quantiles,
@@ -587,12 +750,14 @@ impl MetricsExporter for PrometheusExporter {
                     // Write quantile values
                     for (quantile, value) in quantiles {
                         output.push_str(&format!(
-                            "{}{{quantile=\"{}\"}} {}\n",
-                            metric.name, quantile, value
+                            "{}{} {}\n",
+                            metric.name,
+                            format_labels(&metric.labels, Some(("quantile", quantile.to_string()))),
+                            value
                         ));
                     }
-                    output.push_str(&format!("{}_sum {}\n", metric.name, sum));
-                    output.push_str(&format!("{}_count {}\n", metric.name, count));
+                    output.push_str(&format!("{}_sum{} {}\n", metric.name, labels, sum));
+                    output.push_str(&format!("{}_count{} {}\n", metric.name, labels, count));
                 }
             }
         }
@@ -601,6 +766,31 @@ impl MetricsExporter for PrometheusExporter {
     }
 }
 
+/// Renders `{name="value",...}` for a sample line, or an empty string when
+/// there are no labels
+fn format_labels(labels: &[MetricLabel], extra: Option<(&str, String)>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|label| format!("{}=\"{}\"", label.name, escape_label_value(&label.value)))
+        .collect();
+    if let Some((name, value)) = extra {
+        pairs.push(format!("{}=\"{}\"", name, escape_label_value(&value)));
+    }
+
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
 impl MetricsCollector {
     pub async fn new(config: MetricsConfig) -> Result<Self> {
         let state = Arc::new(RwLock::new(MetricsState {
@@ -726,16 +916,7 @@ impl MetricsCollector {
         help: &str,
         buckets: Vec<f64>,
     ) -> Result<Arc<Histogram>> {
-        let histogram = Arc::new(Histogram {
-            name: name.to_string(),
-            help: help.to_string(),
-            buckets: buckets.clone(),
-            bucket_counts: Arc::new(RwLock::new(vec![0; buckets.len()])),
-            sum: Arc::new(RwLock::new(0.0)),
-            count: Arc::new(RwLock::new(0)),
-            observations: Arc::new(RwLock::new(vec![])),
-            collector: None,
-        });
+        let histogram = Arc::new(Histogram::new(name, help, buckets.clone()));
 
         let mut state = self.state.write().await;
         state.histograms.insert(name.to_string(), histogram.clone());
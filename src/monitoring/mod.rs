@@ -7,6 +7,7 @@ pub mod dashboards;
 pub mod health_checks;
 pub mod metrics;
 pub mod s5_metrics;
+pub mod telemetry;
 
 // Re-export main types
 pub use metrics::{
@@ -31,9 +32,10 @@ pub use alerting::{
 pub use dashboards::{
     Annotation, BarChartWidget, Dashboard, DashboardConfig, DashboardError, DashboardExport,
     DashboardManager, DashboardUpdate, DataSource, GaugeWidget, GraphWidget, GridPosition,
-    HeatmapWidget, Layout, LogWidget, Panel, PieChartWidget, PublicLink, Query as DashboardQuery,
-    Query, QueryResult, RefreshInterval, StatWidget, TableWidget, TimeRange, Variable,
-    Visualization, Widget, WidgetType,
+    HeatmapWidget, Layout, LogWidget, Panel, PassthroughWidget, PieChartWidget, PublicLink,
+    Query as DashboardQuery, Query, QueryResult, RefreshInterval, StatWidget, TableWidget,
+    TimeRange, Variable, Visualization, Widget, WidgetType, GRAFANA_EXPORT_FORMAT,
 };
 
 pub use s5_metrics::S5Metrics;
+pub use telemetry::{init_tracing, TelemetryGuard, TraceContext};
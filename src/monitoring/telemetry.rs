@@ -0,0 +1,280 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Distributed tracing for the request lifecycle
+//!
+//! Requests are wrapped in spans as they move from API receipt through cache
+//! lookup, inference, proof generation and settlement. With the `otel`
+//! feature enabled and `OTEL_EXPORTER_OTLP_ENDPOINT` set, those spans are
+//! exported over OTLP; otherwise they only reach the log subscriber.
+//!
+//! A request's trace id is seeded from its `traceparent` header, or failing
+//! that its `x-request-id` header, so spans recorded by the SDK and the node
+//! share one trace. A request id that is already a 32-digit hex string or a
+//! UUID is used as the trace id directly; anything else is hashed. Jobs
+//! picked up from the chain use their `0x`-prefixed job id as the request id.
+
+use anyhow::{anyhow, Result};
+use axum::http::HeaderMap;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Flushes pending spans when dropped; keep it alive for the process lifetime
+pub struct TelemetryGuard {
+    exporting: bool,
+}
+
+impl TelemetryGuard {
+    /// Whether spans are being exported over OTLP
+    pub fn is_exporting(&self) -> bool {
+        self.exporting
+    }
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        {
+            if self.exporting {
+                opentelemetry::global::shutdown_tracer_provider();
+            }
+        }
+    }
+}
+
+/// Install the global tracing subscriber: log output always, plus an OTLP
+/// span exporter when built with `otel` and the endpoint env var is set
+pub fn init_tracing(service_name: &str) -> Result<TelemetryGuard> {
+    let endpoint = std::env::var(OTLP_ENDPOINT_ENV)
+        .ok()
+        .filter(|e| !e.is_empty());
+
+    #[cfg(feature = "otel")]
+    if let Some(endpoint) = endpoint.as_deref() {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry::KeyValue;
+        use opentelemetry_otlp::WithExportConfig;
+        use tracing_subscriber::layer::SubscriberExt;
+        use tracing_subscriber::util::SubscriberInitExt;
+
+        let provider = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint),
+            )
+            .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(
+                opentelemetry_sdk::Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    service_name.to_string(),
+                )]),
+            ))
+            .install_batch(opentelemetry_sdk::runtime::Tokio)
+            .map_err(|e| anyhow!("Failed to start OTLP exporter: {}", e))?;
+        let tracer = provider.tracer(service_name.to_string());
+        opentelemetry::global::set_tracer_provider(provider);
+
+        tracing_subscriber::registry()
+            .with(tracing_subscriber::fmt::layer())
+            .with(tracing_opentelemetry::layer().with_tracer(tracer))
+            .try_init()
+            .map_err(|e| anyhow!("Failed to install tracing subscriber: {}", e))?;
+        tracing::info!("Exporting traces to {}", endpoint);
+        return Ok(TelemetryGuard { exporting: true });
+    }
+
+    #[cfg(not(feature = "otel"))]
+    if endpoint.is_some() {
+        eprintln!(
+            "{} is set but this build lacks the `otel` feature; traces are not exported",
+            OTLP_ENDPOINT_ENV
+        );
+    }
+
+    tracing_subscriber::fmt()
+        .try_init()
+        .map_err(|e| anyhow!("Failed to install tracing subscriber: {}", e))?;
+    Ok(TelemetryGuard { exporting: false })
+}
+
+/// Trace identity carried by one request, in W3C trace-context form
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceContext {
+    /// 32 lowercase hex digits
+    pub trace_id: String,
+    /// 16 lowercase hex digits; the remote span the request's spans hang off
+    pub parent_span_id: String,
+    pub request_id: String,
+}
+
+impl TraceContext {
+    /// Fresh trace for a request that carried no ids
+    pub fn new() -> Self {
+        Self::from_request_id(&uuid::Uuid::new_v4().to_string())
+    }
+
+    /// `traceparent` wins over `x-request-id`; with neither a new trace starts
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        Self::resolve(header(TRACEPARENT_HEADER), header(REQUEST_ID_HEADER))
+    }
+
+    /// Browsers cannot set headers on a WebSocket upgrade, so the ids may
+    /// also arrive as `traceparent` / `request_id` query parameters
+    pub fn from_headers_or_query(headers: &HeaderMap, query: &HashMap<String, String>) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        Self::resolve(
+            header(TRACEPARENT_HEADER).or(query.get("traceparent").map(String::as_str)),
+            header(REQUEST_ID_HEADER).or(query.get("request_id").map(String::as_str)),
+        )
+    }
+
+    fn resolve(traceparent: Option<&str>, request_id: Option<&str>) -> Self {
+        let request_id = request_id.map(str::trim).filter(|id| !id.is_empty());
+        if let Some(mut ctx) = traceparent.and_then(Self::from_traceparent) {
+            if let Some(id) = request_id {
+                ctx.request_id = id.to_string();
+            }
+            return ctx;
+        }
+        match request_id {
+            Some(id) => Self::from_request_id(id),
+            None => Self::new(),
+        }
+    }
+
+    /// Parse a W3C `traceparent` (`00-<trace id>-<span id>-<flags>`)
+    pub fn from_traceparent(value: &str) -> Option<Self> {
+        let parts: Vec<&str> = value.trim().split('-').collect();
+        if parts.len() != 4 || parts[0] != "00" {
+            return None;
+        }
+        let (trace_id, span_id) = (parts[1].to_ascii_lowercase(), parts[2].to_ascii_lowercase());
+        if !is_nonzero_hex(&trace_id, 32) || !is_nonzero_hex(&span_id, 16) {
+            return None;
+        }
+        Some(Self {
+            request_id: trace_id.clone(),
+            trace_id,
+            parent_span_id: span_id,
+        })
+    }
+
+    /// Seed a trace from a request id. The same id always maps to the same
+    /// trace, so the SDK can derive it too.
+    pub fn from_request_id(request_id: &str) -> Self {
+        let digest = Sha256::digest(request_id.as_bytes());
+        let candidate = request_id.replace('-', "").to_ascii_lowercase();
+        let trace_id = if is_nonzero_hex(&candidate, 32) {
+            candidate
+        } else {
+            hex::encode(&digest[..16])
+        };
+        Self {
+            trace_id,
+            parent_span_id: hex::encode(&digest[16..24]),
+            request_id: request_id.to_string(),
+        }
+    }
+
+    /// Value for an outgoing `traceparent` header
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.parent_span_id)
+    }
+
+    /// Root span for work done on behalf of this request
+    pub fn span(&self, name: &'static str) -> tracing::Span {
+        let span = tracing::info_span!(
+            "request",
+            otel.name = name,
+            trace_id = %self.trace_id,
+            request_id = %self.request_id,
+        );
+
+        #[cfg(feature = "otel")]
+        {
+            use opentelemetry::trace::{
+                SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+            };
+            use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+            if let (Ok(trace_id), Ok(span_id)) = (
+                TraceId::from_hex(&self.trace_id),
+                SpanId::from_hex(&self.parent_span_id),
+            ) {
+                let parent = SpanContext::new(
+                    trace_id,
+                    span_id,
+                    TraceFlags::SAMPLED,
+                    true,
+                    TraceState::default(),
+                );
+                span.set_parent(opentelemetry::Context::new().with_remote_span_context(parent));
+            }
+        }
+
+        span
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_nonzero_hex(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value.chars().all(|c| c.is_ascii_hexdigit())
+        && value.chars().any(|c| c != '0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_wins_over_request_id() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TRACEPARENT_HEADER,
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+                .parse()
+                .unwrap(),
+        );
+        headers.insert(REQUEST_ID_HEADER, "req-42".parse().unwrap());
+
+        let ctx = TraceContext::from_headers(&headers);
+        assert_eq!(ctx.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(ctx.parent_span_id, "00f067aa0ba902b7");
+        assert_eq!(ctx.request_id, "req-42");
+        assert_eq!(
+            ctx.traceparent(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+    }
+
+    #[test]
+    fn test_request_id_seeds_trace() {
+        let uuid = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        assert_eq!(
+            TraceContext::from_request_id(uuid).trace_id,
+            "0f8fad5bd9cb469fa16570867728950e"
+        );
+
+        // Arbitrary ids hash to a stable trace id
+        let a = TraceContext::from_request_id("req-42");
+        assert_eq!(a, TraceContext::from_request_id("req-42"));
+        assert!(is_nonzero_hex(&a.trace_id, 32));
+        assert!(is_nonzero_hex(&a.parent_span_id, 16));
+
+        assert!(TraceContext::from_traceparent(
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01"
+        )
+        .is_none());
+    }
+}
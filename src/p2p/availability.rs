@@ -0,0 +1,226 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Model availability announcements over gossipsub
+//!
+//! Nodes periodically publish the models they have loaded, their price and
+//! current load. Announcements are signed with the node's identity key and
+//! only accepted when the key matches the announced peer ID, so a peer
+//! cannot advertise capabilities on another node's behalf.
+
+use anyhow::{anyhow, Result};
+use libp2p::{
+    identity::{Keypair, PublicKey},
+    PeerId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Gossipsub topic carrying model availability announcements
+pub const MODEL_AVAILABILITY_TOPIC: &str = "/fabstir/models/1.0.0";
+
+/// Announcements further ahead of our clock than this are rejected
+const MAX_CLOCK_SKEW_SECS: u64 = 60;
+
+/// What a node currently offers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelAnnouncement {
+    pub peer_id: String,
+    /// On-chain host address, if the node is registered
+    #[serde(default)]
+    pub host_address: Option<String>,
+    pub models: Vec<String>,
+    pub price_per_token: f64,
+    /// Active jobs on the node
+    pub current_load: u32,
+    /// Unix time the announcement was made
+    pub timestamp: u64,
+}
+
+/// Models, price and load this node announces
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocalAvailability {
+    pub models: Vec<String>,
+    pub price_per_token: f64,
+    pub current_load: u32,
+}
+
+/// Announcement as published: the serialized announcement, the announcing
+/// node's public key and its signature over the payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedModelAnnouncement {
+    pub payload: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl SignedModelAnnouncement {
+    pub fn sign(announcement: &ModelAnnouncement, keypair: &Keypair) -> Result<Self> {
+        let payload = serde_json::to_vec(announcement)?;
+        let signature = keypair.sign(&payload)?;
+        Ok(Self {
+            payload,
+            public_key: keypair.public().encode_protobuf(),
+            signature,
+        })
+    }
+
+    /// Check the signature and that the signing key belongs to the announced
+    /// peer, returning the announcement
+    pub fn verify(&self) -> Result<ModelAnnouncement> {
+        let public_key = PublicKey::try_decode_protobuf(&self.public_key)
+            .map_err(|e| anyhow!("Invalid announcement public key: {}", e))?;
+        if !public_key.verify(&self.payload, &self.signature) {
+            return Err(anyhow!("Invalid announcement signature"));
+        }
+
+        let announcement: ModelAnnouncement = serde_json::from_slice(&self.payload)?;
+        if announcement.peer_id != public_key.to_peer_id().to_string() {
+            return Err(anyhow!(
+                "Announcement for {} signed by another peer",
+                announcement.peer_id
+            ));
+        }
+        Ok(announcement)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Latest verified announcement from each peer
+pub struct ModelAvailability {
+    ttl: Duration,
+    announcements: HashMap<PeerId, (ModelAnnouncement, Instant)>,
+}
+
+impl ModelAvailability {
+    /// Announcements not refreshed within `ttl` are dropped
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            announcements: HashMap::new(),
+        }
+    }
+
+    /// Record an announcement from `peer_id`, ignoring it if it is not newer
+    /// than the one we have or is dated in the future. Returns whether it
+    /// was accepted.
+    pub fn update(&mut self, peer_id: PeerId, announcement: ModelAnnouncement) -> bool {
+        if announcement.timestamp > unix_now() + MAX_CLOCK_SKEW_SECS {
+            return false;
+        }
+        if let Some((current, _)) = self.announcements.get(&peer_id) {
+            if announcement.timestamp <= current.timestamp {
+                return false;
+            }
+        }
+        self.announcements
+            .insert(peer_id, (announcement, Instant::now()));
+        true
+    }
+
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        self.announcements.remove(peer_id);
+    }
+
+    /// Drop announcements that have not been refreshed within the TTL
+    pub fn prune(&mut self) {
+        let ttl = self.ttl;
+        self.announcements
+            .retain(|_, (_, received)| received.elapsed() < ttl);
+    }
+
+    /// Current announcements from every peer
+    pub fn all(&self) -> Vec<ModelAnnouncement> {
+        self.announcements
+            .values()
+            .filter(|(_, received)| received.elapsed() < self.ttl)
+            .map(|(announcement, _)| announcement.clone())
+            .collect()
+    }
+
+    /// Current announcements from peers serving `model`
+    pub fn hosts_for_model(&self, model: &str) -> Vec<ModelAnnouncement> {
+        self.all()
+            .into_iter()
+            .filter(|announcement| announcement.models.iter().any(|m| m == model))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcement(keypair: &Keypair, timestamp: u64) -> ModelAnnouncement {
+        ModelAnnouncement {
+            peer_id: keypair.public().to_peer_id().to_string(),
+            host_address: None,
+            models: vec!["llama-7b".to_string()],
+            price_per_token: 0.001,
+            current_load: 2,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_signed_announcement_round_trip() {
+        let keypair = Keypair::generate_ed25519();
+        let signed =
+            SignedModelAnnouncement::sign(&announcement(&keypair, unix_now()), &keypair).unwrap();
+        let decoded = SignedModelAnnouncement::from_bytes(&signed.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            decoded.verify().unwrap().peer_id,
+            keypair.public().to_peer_id().to_string()
+        );
+    }
+
+    #[test]
+    fn test_spoofed_announcement_rejected() {
+        let (victim, attacker) = (Keypair::generate_ed25519(), Keypair::generate_ed25519());
+
+        // Signed by the attacker on the victim's behalf
+        let spoofed =
+            SignedModelAnnouncement::sign(&announcement(&victim, unix_now()), &attacker).unwrap();
+        assert!(spoofed.verify().is_err());
+
+        // Payload altered after signing
+        let mut tampered =
+            SignedModelAnnouncement::sign(&announcement(&victim, unix_now()), &victim).unwrap();
+        tampered.payload = serde_json::to_vec(&ModelAnnouncement {
+            current_load: 0,
+            ..announcement(&victim, unix_now())
+        })
+        .unwrap();
+        assert!(tampered.verify().is_err());
+    }
+
+    #[test]
+    fn test_availability_keeps_newest_announcement() {
+        let keypair = Keypair::generate_ed25519();
+        let peer_id = keypair.public().to_peer_id();
+        let now = unix_now();
+        let mut availability = ModelAvailability::new(Duration::from_secs(60));
+
+        assert!(availability.update(peer_id, announcement(&keypair, now)));
+        assert!(!availability.update(peer_id, announcement(&keypair, now - 10)));
+        assert!(!availability.update(peer_id, announcement(&keypair, now + 3600)));
+
+        assert_eq!(availability.hosts_for_model("llama-7b").len(), 1);
+        assert!(availability.hosts_for_model("mistral-7b").is_empty());
+    }
+}
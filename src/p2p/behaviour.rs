@@ -1,11 +1,12 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use libp2p::{
-    identify, kad, mdns, rendezvous, request_response, swarm::NetworkBehaviour, StreamProtocol,
+    gossipsub, identify, kad, mdns, rendezvous, request_response, swarm::NetworkBehaviour,
+    StreamProtocol,
 };
 use std::time::Duration;
 
-use super::protocol_impl::FabstirCodec;
+use super::{availability::MODEL_AVAILABILITY_TOPIC, discovery, protocol_impl::FabstirCodec};
 use crate::p2p_config::NodeConfig;
 
 #[derive(NetworkBehaviour)]
@@ -15,6 +16,7 @@ pub struct NodeBehaviour {
     pub identify: identify::Behaviour,
     pub rendezvous: rendezvous::client::Behaviour,
     pub request_response: request_response::Behaviour<FabstirCodec>,
+    pub gossipsub: gossipsub::Behaviour,
 }
 
 impl NodeBehaviour {
@@ -28,6 +30,8 @@ impl NodeBehaviour {
         let mut kad_config = kad::Config::new(libp2p::kad::PROTOCOL_NAME);
         kad_config.set_query_timeout(Duration::from_secs(60));
         kad_config.set_replication_factor(20.try_into().unwrap());
+        kad_config.set_record_ttl(Some(config.dht_record_ttl));
+        kad_config.set_provider_record_ttl(Some(config.dht_record_ttl));
 
         let store = kad::store::MemoryStore::new(peer_id);
         let mut kad = kad::Behaviour::with_config(peer_id, store, kad_config);
@@ -55,7 +59,10 @@ impl NodeBehaviour {
         // Configure Identify
         let identify_config =
             identify::Config::new("/fabstir/id/1.0.0".to_string(), keypair.public())
-                .with_agent_version(format!("fabstir-llm-node/{}", config.protocol_version));
+                .with_agent_version(discovery::agent_version(
+                    &config.protocol_version,
+                    &config.capabilities,
+                ));
 
         let identify = identify::Behaviour::new(identify_config);
 
@@ -84,12 +91,24 @@ impl NodeBehaviour {
             request_response_config,
         );
 
+        // Configure Gossipsub for model availability announcements
+        let gossipsub_config = gossipsub::ConfigBuilder::default()
+            .heartbeat_interval(Duration::from_secs(10))
+            .validation_mode(gossipsub::ValidationMode::Strict)
+            .build()?;
+        let mut gossipsub = gossipsub::Behaviour::new(
+            gossipsub::MessageAuthenticity::Signed(keypair.clone()),
+            gossipsub_config,
+        )?;
+        gossipsub.subscribe(&gossipsub::IdentTopic::new(MODEL_AVAILABILITY_TOPIC))?;
+
         Ok(Self {
             kad,
             mdns,
             identify,
             rendezvous,
             request_response,
+            gossipsub,
         })
     }
 }
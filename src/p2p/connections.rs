@@ -0,0 +1,328 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Connection limit enforcement
+//!
+//! When the node is at `max_connections`, a new connection evicts the
+//! unprotected peer with the oldest protocol activity instead of being
+//! refused, so idle peers cannot hold slots that useful peers need.
+//!
+//! A peer reachable over both TCP and QUIC is kept on one transport only:
+//! the TCP connection is closed in favour of QUIC.
+
+use libp2p::{multiaddr::Protocol, swarm::ConnectionId, Multiaddr, PeerId};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use crate::p2p_config::ConnectionLimits;
+
+/// What to do with a newly established connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Accept,
+    /// Accept, and disconnect this peer to make room
+    Evict(PeerId),
+    /// Close the new connection
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PeerConnections {
+    count: usize,
+    last_activity: Instant,
+}
+
+/// Connection counts for `NodeMetrics`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    pub connections: usize,
+    pub protected_peers: usize,
+    pub evicted_peers: u64,
+    pub rejected_connections: u64,
+}
+
+/// Tracks open connections and decides admissions against `ConnectionLimits`
+pub struct ConnectionTracker {
+    limits: ConnectionLimits,
+    protected: HashSet<PeerId>,
+    peers: HashMap<PeerId, PeerConnections>,
+    evicted_peers: u64,
+    rejected_connections: u64,
+}
+
+impl ConnectionTracker {
+    pub fn new(limits: ConnectionLimits, protected: impl IntoIterator<Item = PeerId>) -> Self {
+        Self {
+            limits,
+            protected: protected.into_iter().collect(),
+            peers: HashMap::new(),
+            evicted_peers: 0,
+            rejected_connections: 0,
+        }
+    }
+
+    pub fn is_protected(&self, peer_id: &PeerId) -> bool {
+        self.protected.contains(peer_id)
+    }
+
+    /// Record a new connection; `num_established` counts connections to
+    /// `peer_id` including this one
+    pub fn on_established(&mut self, peer_id: PeerId, num_established: usize) -> Admission {
+        if num_established > self.limits.max_connections_per_peer {
+            self.rejected_connections += 1;
+            return Admission::Reject;
+        }
+
+        let now = Instant::now();
+        self.peers
+            .entry(peer_id)
+            .and_modify(|peer| peer.count = num_established)
+            .or_insert(PeerConnections {
+                count: num_established,
+                last_activity: now,
+            });
+
+        if self.connections() <= self.limits.max_connections {
+            return Admission::Accept;
+        }
+
+        // Least recently useful unprotected peer, other than the new one
+        let victim = self
+            .peers
+            .iter()
+            .filter(|(id, _)| **id != peer_id && !self.protected.contains(id))
+            .min_by_key(|(_, peer)| peer.last_activity)
+            .map(|(id, _)| *id);
+
+        match victim {
+            Some(victim) => {
+                // Forget the victim now so its pending close is not counted
+                // when the next connection arrives
+                self.peers.remove(&victim);
+                self.evicted_peers += 1;
+                Admission::Evict(victim)
+            }
+            // Protected peers are admitted even over the limit
+            None if self.protected.contains(&peer_id) => Admission::Accept,
+            None => {
+                self.on_closed(peer_id, num_established - 1);
+                self.rejected_connections += 1;
+                Admission::Reject
+            }
+        }
+    }
+
+    /// Record a closed connection; `remaining` is the peer's open connections
+    pub fn on_closed(&mut self, peer_id: PeerId, remaining: usize) {
+        if remaining == 0 {
+            self.peers.remove(&peer_id);
+        } else if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.count = remaining;
+        }
+    }
+
+    /// Mark a peer as useful, e.g. after a protocol request or response
+    pub fn record_activity(&mut self, peer_id: &PeerId) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.last_activity = Instant::now();
+        }
+    }
+
+    pub fn connections(&self) -> usize {
+        self.peers.values().map(|peer| peer.count).sum()
+    }
+
+    pub fn stats(&self) -> ConnectionStats {
+        ConnectionStats {
+            connections: self.connections(),
+            protected_peers: self
+                .peers
+                .keys()
+                .filter(|id| self.protected.contains(id))
+                .count(),
+            evicted_peers: self.evicted_peers,
+            rejected_connections: self.rejected_connections,
+        }
+    }
+}
+
+/// Transport a connection runs over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionTransport {
+    Tcp,
+    Quic,
+    Other,
+}
+
+impl ConnectionTransport {
+    pub fn of(addr: &Multiaddr) -> Self {
+        let mut transport = ConnectionTransport::Other;
+        for protocol in addr.iter() {
+            match protocol {
+                Protocol::Quic | Protocol::QuicV1 => return ConnectionTransport::Quic,
+                Protocol::Tcp(_) => transport = ConnectionTransport::Tcp,
+                _ => {}
+            }
+        }
+        transport
+    }
+}
+
+/// Keeps each peer on a single transport. Both ends of a connection see the
+/// same transports, so closing TCP whenever QUIC is also open means they
+/// agree on which connection survives instead of each closing a different
+/// one.
+#[derive(Default)]
+pub struct TransportDedup {
+    peers: HashMap<PeerId, Vec<(ConnectionId, ConnectionTransport)>>,
+}
+
+impl TransportDedup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a new connection and return the connections to close, which
+    /// may include the new one
+    pub fn on_established(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        transport: ConnectionTransport,
+    ) -> Vec<ConnectionId> {
+        let connections = self.peers.entry(peer_id).or_default();
+        let has = |wanted: ConnectionTransport| {
+            connections
+                .iter()
+                .any(|(_, transport)| *transport == wanted)
+        };
+
+        match transport {
+            ConnectionTransport::Tcp if has(ConnectionTransport::Quic) => vec![connection_id],
+            ConnectionTransport::Quic if has(ConnectionTransport::Tcp) => {
+                let mut close = Vec::new();
+                connections.retain(|(id, transport)| {
+                    let tcp = *transport == ConnectionTransport::Tcp;
+                    if tcp {
+                        close.push(*id);
+                    }
+                    !tcp
+                });
+                connections.push((connection_id, transport));
+                close
+            }
+            _ => {
+                connections.push((connection_id, transport));
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn on_closed(&mut self, peer_id: &PeerId, connection_id: ConnectionId) {
+        if let Some(connections) = self.peers.get_mut(peer_id) {
+            connections.retain(|(id, _)| *id != connection_id);
+            if connections.is_empty() {
+                self.peers.remove(peer_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p2p_config::TransportMode;
+    use std::time::Duration;
+
+    fn tracker(max_connections: usize, protected: Vec<PeerId>) -> ConnectionTracker {
+        ConnectionTracker::new(
+            ConnectionLimits {
+                max_connections,
+                max_connections_per_peer: 2,
+                idle_timeout: Duration::from_secs(120),
+            },
+            protected,
+        )
+    }
+
+    #[test]
+    fn test_evicts_least_recently_active_peer() {
+        let (idle, busy, new) = (PeerId::random(), PeerId::random(), PeerId::random());
+        let mut tracker = tracker(2, vec![]);
+        assert_eq!(tracker.on_established(idle, 1), Admission::Accept);
+        assert_eq!(tracker.on_established(busy, 1), Admission::Accept);
+        std::thread::sleep(Duration::from_millis(5));
+        tracker.record_activity(&busy);
+
+        assert_eq!(tracker.on_established(new, 1), Admission::Evict(idle));
+        assert_eq!(tracker.connections(), 2);
+        assert_eq!(tracker.stats().evicted_peers, 1);
+    }
+
+    #[test]
+    fn test_protected_peers_are_not_evicted() {
+        let (bootstrap, new) = (PeerId::random(), PeerId::random());
+        let mut tracker = tracker(1, vec![bootstrap]);
+        assert_eq!(tracker.on_established(bootstrap, 1), Admission::Accept);
+
+        assert_eq!(tracker.on_established(new, 1), Admission::Reject);
+        assert_eq!(tracker.connections(), 1);
+        assert_eq!(
+            tracker.stats(),
+            ConnectionStats {
+                connections: 1,
+                protected_peers: 1,
+                evicted_peers: 0,
+                rejected_connections: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_per_peer_limit_rejects_extra_connections() {
+        let peer = PeerId::random();
+        let mut tracker = tracker(10, vec![]);
+        assert_eq!(tracker.on_established(peer, 1), Admission::Accept);
+        assert_eq!(tracker.on_established(peer, 2), Admission::Accept);
+        assert_eq!(tracker.on_established(peer, 3), Admission::Reject);
+        assert_eq!(tracker.connections(), 2);
+    }
+
+    #[test]
+    fn test_quic_replaces_tcp_connection_to_same_peer() {
+        let peer = PeerId::random();
+        let (tcp, quic, tcp_again) = (
+            ConnectionId::new_unchecked(1),
+            ConnectionId::new_unchecked(2),
+            ConnectionId::new_unchecked(3),
+        );
+        let mut dedup = TransportDedup::new();
+
+        assert!(dedup
+            .on_established(peer, tcp, ConnectionTransport::Tcp)
+            .is_empty());
+        assert_eq!(
+            dedup.on_established(peer, quic, ConnectionTransport::Quic),
+            vec![tcp]
+        );
+        dedup.on_closed(&peer, tcp);
+        assert_eq!(
+            dedup.on_established(peer, tcp_again, ConnectionTransport::Tcp),
+            vec![tcp_again]
+        );
+    }
+
+    #[test]
+    fn test_transport_mode_filters_addresses() {
+        let tcp: Multiaddr = "/ip4/0.0.0.0/tcp/9000".parse().unwrap();
+        let quic: Multiaddr = "/ip4/0.0.0.0/udp/9002/quic-v1".parse().unwrap();
+        assert_eq!(ConnectionTransport::of(&tcp), ConnectionTransport::Tcp);
+        assert_eq!(ConnectionTransport::of(&quic), ConnectionTransport::Quic);
+
+        assert!(TransportMode::TcpOnly.supports(&tcp));
+        assert!(!TransportMode::TcpOnly.supports(&quic));
+        assert!(!TransportMode::QuicOnly.supports(&tcp));
+        assert!(TransportMode::Both.supports(&quic));
+        assert_eq!("QUIC".parse(), Ok(TransportMode::QuicOnly));
+        assert!("udp".parse::<TransportMode>().is_err());
+    }
+}
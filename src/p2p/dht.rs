@@ -11,7 +11,7 @@ use std::{
 };
 use tokio::sync::{mpsc, oneshot};
 
-use crate::p2p::{DhtEvent, NodeEvent};
+use crate::p2p::{DhtEvent, NodeEvent, RepublishOutcome};
 
 pub struct DhtHandler {
     // Pending DHT queries
@@ -26,6 +26,12 @@ pub struct DhtHandler {
     announced_capabilities: HashSet<String>,
     stored_records: HashMap<RecordKey, StoredRecord>,
     published_records: HashMap<RecordKey, PublishedRecord>,
+    provided_keys: HashSet<RecordKey>,
+
+    // Republish tracking
+    republish_queries: HashSet<QueryId>,
+    next_republish: Option<Instant>,
+    last_republish: Option<RepublishOutcome>,
 
     // Configuration
     bootstrap_interval: Duration,
@@ -56,6 +62,10 @@ impl DhtHandler {
             announced_capabilities: HashSet::new(),
             stored_records: HashMap::new(),
             published_records: HashMap::new(),
+            provided_keys: HashSet::new(),
+            republish_queries: HashSet::new(),
+            next_republish: None,
+            last_republish: None,
             bootstrap_interval,
             republish_interval,
         }
@@ -64,6 +74,17 @@ impl DhtHandler {
     pub fn handle_event(&mut self, event: KademliaEvent, event_tx: &mpsc::Sender<NodeEvent>) {
         match event {
             KademliaEvent::OutboundQueryProgressed { id, result, .. } => {
+                if self.republish_queries.remove(&id)
+                    && matches!(
+                        result,
+                        QueryResult::PutRecord(Err(_)) | QueryResult::StartProviding(Err(_))
+                    )
+                {
+                    if let Some(outcome) = &mut self.last_republish {
+                        outcome.failed += 1;
+                    }
+                }
+
                 match result {
                     QueryResult::GetRecord(Ok(GetRecordOk::FoundRecord(record))) => {
                         if let Some((tx, key)) = self.get_record_queries.remove(&id) {
@@ -241,6 +262,49 @@ impl DhtHandler {
         records_to_republish
     }
 
+    /// Remember a key we provide so it is re-announced on every republish
+    pub fn track_provided_key(&mut self, key: RecordKey) {
+        self.provided_keys.insert(key);
+    }
+
+    /// Keys we provide, re-announced on every republish regardless of age
+    pub fn provided_keys(&self) -> Vec<RecordKey> {
+        self.provided_keys.iter().cloned().collect()
+    }
+
+    /// Record a republish round and schedule the next one
+    ///
+    /// `queries` are the round's in-flight queries; any that later fail are
+    /// added to the outcome's `failed` count. Queries from earlier rounds
+    /// are forgotten.
+    pub fn record_republish(
+        &mut self,
+        queries: Vec<QueryId>,
+        records: usize,
+        providers: usize,
+        failed: usize,
+    ) -> RepublishOutcome {
+        let now = Instant::now();
+        self.republish_queries = queries.into_iter().collect();
+        let outcome = RepublishOutcome {
+            completed_at: now,
+            records,
+            providers,
+            failed,
+        };
+        self.last_republish = Some(outcome.clone());
+        self.next_republish = Some(now + self.republish_interval);
+        outcome
+    }
+
+    pub fn next_republish(&self) -> Option<Instant> {
+        self.next_republish
+    }
+
+    pub fn last_republish(&self) -> Option<&RepublishOutcome> {
+        self.last_republish.as_ref()
+    }
+
     pub fn cleanup_expired_records(&mut self) {
         let now = Instant::now();
         self.stored_records.retain(|_, record| {
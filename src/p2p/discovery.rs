@@ -1,8 +1,15 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
-use libp2p::{kad::RecordKey, Multiaddr, PeerId};
+use libp2p::{kad::RecordKey, Multiaddr, PeerId, StreamProtocol};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+use super::protocol_impl::INFERENCE_PROTOCOL;
+
+/// Identify agent version prefix used by our nodes
+pub const AGENT_VERSION_PREFIX: &str = "fabstir-llm-node/";
+/// Separates the version from the advertised capability list in the agent version
+const CAPABILITIES_MARKER: &str = "; capabilities=";
 
 #[derive(Debug, Clone)]
 pub enum DiscoveryEvent {
@@ -14,6 +21,17 @@ pub enum DiscoveryEvent {
     PeerExpired {
         peer_id: PeerId,
     },
+    /// A peer's signed model availability announcement was accepted
+    ModelsAnnounced {
+        peer_id: PeerId,
+        models: Vec<String>,
+    },
+    /// A connected peer advertised capabilities it does not serve
+    CapabilityMismatch {
+        peer_id: PeerId,
+        advertised: Vec<String>,
+        served: Vec<String>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,4 +65,90 @@ pub enum DhtEvent {
     RecordRepublished {
         key: RecordKey,
     },
+    /// A periodic republish round was sent out
+    Republished {
+        records: usize,
+        providers: usize,
+        failed: usize,
+    },
+}
+
+/// Identify agent version advertising `capabilities`
+pub fn agent_version(protocol_version: &str, capabilities: &[String]) -> String {
+    if capabilities.is_empty() {
+        format!("{}{}", AGENT_VERSION_PREFIX, protocol_version)
+    } else {
+        format!(
+            "{}{}{}{}",
+            AGENT_VERSION_PREFIX,
+            protocol_version,
+            CAPABILITIES_MARKER,
+            capabilities.join(",")
+        )
+    }
+}
+
+/// Capabilities advertised in a peer's identify agent version, or `None`
+/// if the peer is not one of our nodes
+pub fn parse_advertised_capabilities(agent_version: &str) -> Option<HashSet<String>> {
+    let rest = agent_version.strip_prefix(AGENT_VERSION_PREFIX)?;
+    Some(match rest.split_once(CAPABILITIES_MARKER) {
+        Some((_, capabilities)) => capabilities
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(String::from)
+            .collect(),
+        None => HashSet::new(),
+    })
+}
+
+/// Advertised capabilities the peer can actually serve given the protocols
+/// it reported over identify
+///
+/// Every capability is a model or inference service, so none can be served
+/// without the inference protocol.
+pub fn served_capabilities(
+    advertised: &HashSet<String>,
+    protocols: &[StreamProtocol],
+) -> HashSet<String> {
+    if protocols.iter().any(|p| p.as_ref() == INFERENCE_PROTOCOL) {
+        advertised.clone()
+    } else {
+        HashSet::new()
+    }
+}
+
+/// Capabilities of discovered peers, as verified on connection
+#[derive(Debug, Default)]
+pub struct PeerCapabilities {
+    peers: HashMap<PeerId, HashSet<String>>,
+}
+
+impl PeerCapabilities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace what `peer_id` is known to serve
+    pub fn insert(&mut self, peer_id: PeerId, capabilities: HashSet<String>) {
+        self.peers.insert(peer_id, capabilities);
+    }
+
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        self.peers.remove(peer_id);
+    }
+
+    pub fn get(&self, peer_id: &PeerId) -> Option<&HashSet<String>> {
+        self.peers.get(peer_id)
+    }
+
+    /// Peers known to serve `capability`
+    pub fn peers_with_capability(&self, capability: &str) -> Vec<PeerId> {
+        self.peers
+            .iter()
+            .filter(|(_, capabilities)| capabilities.contains(capability))
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
 }
@@ -1,15 +1,21 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
+pub mod availability;
 pub mod behaviour;
+pub mod connections;
 pub mod dht;
 pub mod discovery;
 pub mod node;
 pub mod protocol_impl;
 pub mod protocols;
+pub mod reputation;
 
 pub use crate::p2p_config::{
-    ConnectionLimits, DhtRoutingTableHealth, NodeConfig, NodeMetrics, PeerInfo,
+    ConnectionLimits, DhtRoutingTableHealth, NodeConfig, NodeMetrics, PeerInfo, RepublishOutcome,
+    ReputationConfig, TransportMode,
 };
-pub use discovery::{DhtEvent, DiscoveryEvent};
+pub use availability::{LocalAvailability, ModelAnnouncement, ModelAvailability};
+pub use discovery::{DhtEvent, DiscoveryEvent, PeerCapabilities};
 pub use node::{Node, NodeEvent};
 pub use protocols::{InferenceRequest, InferenceResponse, JobClaim, JobResult, ProtocolEvent};
+pub use reputation::{ReputationEvent, ReputationTracker};
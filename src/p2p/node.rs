@@ -3,7 +3,14 @@
 use anyhow::{anyhow, Result};
 use futures::{channel::mpsc, StreamExt};
 use libp2p::{
-    identity::Keypair, kad::RecordKey, swarm::SwarmEvent, Multiaddr, PeerId, SwarmBuilder,
+    identity::Keypair,
+    kad::RecordKey,
+    request_response::OutboundRequestId,
+    swarm::{
+        dial_opts::{DialOpts, PeerCondition},
+        SwarmEvent,
+    },
+    Multiaddr, PeerId, Swarm, SwarmBuilder,
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -17,19 +24,31 @@ use tokio::{
 };
 
 use crate::p2p::{
+    availability::{
+        unix_now, LocalAvailability, ModelAnnouncement, ModelAvailability, SignedModelAnnouncement,
+        MODEL_AVAILABILITY_TOPIC,
+    },
     behaviour::NodeBehaviour,
+    connections::{
+        Admission, ConnectionStats, ConnectionTracker, ConnectionTransport, TransportDedup,
+    },
     dht::DhtHandler,
-    discovery::{DhtEvent, DiscoveryEvent},
+    discovery::{
+        parse_advertised_capabilities, served_capabilities, DhtEvent, DiscoveryEvent,
+        PeerCapabilities,
+    },
     protocol_impl::{
-        FabstirRequest, FabstirResponse, RateLimiter, RequestTracker, ResponseChannel,
-        StreamingHandler,
+        FabstirRequest, FabstirResponse, InferenceForwarder, RateLimiter, RequestTracker,
+        ResponseChannel, StreamingHandler, MAX_FORWARD_HOPS,
     },
     protocols::{
         InferenceRequest, InferenceResponse, JobClaim, JobResult, ProtocolEvent, ProtocolHandler,
     },
+    reputation::{ReputationEvent, ReputationTracker},
 };
 use crate::p2p_config::{
-    ConnectionLimits, DhtRoutingTableHealth, NodeConfig, NodeMetrics, PeerInfo,
+    ConnectionLimits, DhtRoutingTableHealth, NodeConfig, NodeMetrics, PeerInfo, RepublishOutcome,
+    TransportMode,
 };
 
 #[derive(Debug, Clone)]
@@ -37,6 +56,8 @@ pub enum NodeEvent {
     NewListenAddr { address: Multiaddr },
     ConnectionEstablished { peer_id: PeerId },
     ConnectionClosed { peer_id: PeerId },
+    PeerEvicted { peer_id: PeerId },
+    PeerBanned { peer_id: PeerId, score: f64 },
     DiscoveryEvent(DiscoveryEvent),
     DhtEvent(DhtEvent),
     ProtocolEvent(ProtocolEvent),
@@ -96,6 +117,13 @@ enum Command {
     Shutdown,
 }
 
+/// Republish state shared from the swarm task for health reporting
+#[derive(Default)]
+struct RepublishStatus {
+    next_republish: Option<Instant>,
+    last_republish: Option<RepublishOutcome>,
+}
+
 pub struct Node {
     peer_id: PeerId,
     config: NodeConfig,
@@ -105,6 +133,7 @@ pub struct Node {
     start_time: Instant,
     connected_peers: Arc<RwLock<HashSet<PeerId>>>,
     discovered_peers: Arc<RwLock<HashSet<PeerId>>>,
+    peer_capabilities: Arc<RwLock<PeerCapabilities>>,
     peer_metadata: Arc<RwLock<HashMap<PeerId, serde_json::Value>>>,
     protocol_handler: Arc<Mutex<ProtocolHandler>>,
     streaming_handlers: Arc<Mutex<HashMap<String, mpsc::Sender<InferenceResponse>>>>,
@@ -112,6 +141,11 @@ pub struct Node {
     bandwidth_counter: Arc<Mutex<(u64, u64)>>,
     swarm_task: Option<JoinHandle<()>>,
     listeners: Arc<RwLock<Vec<Multiaddr>>>,
+    republish_status: Arc<RwLock<RepublishStatus>>,
+    connection_stats: Arc<RwLock<ConnectionStats>>,
+    model_availability: Arc<RwLock<ModelAvailability>>,
+    local_availability: Arc<RwLock<LocalAvailability>>,
+    reputation: Arc<RwLock<ReputationTracker>>,
 }
 
 impl Node {
@@ -122,28 +156,23 @@ impl Node {
             .unwrap_or_else(|| Keypair::generate_ed25519());
         let peer_id = PeerId::from(keypair.public());
 
-        let mut swarm = SwarmBuilder::with_existing_identity(keypair)
-            .with_tokio()
-            .with_tcp(
-                libp2p::tcp::Config::default(),
-                libp2p::noise::Config::new,
-                libp2p::yamux::Config::default,
-            )?
-            .with_quic()
-            .with_behaviour(|key| {
-                NodeBehaviour::new(key, &config).expect("Failed to create behaviour")
-            })?
-            .with_swarm_config(|cfg| {
-                cfg.with_idle_connection_timeout(config.connection_idle_timeout)
-            })
-            .build();
+        let mut swarm = build_swarm(keypair.clone(), &config)?;
 
-        // Listen on configured addresses
+        // Listen on configured addresses the selected transport supports
         let mut initial_listeners = Vec::new();
         for addr in &config.listen_addresses {
+            if !config.transport.supports(addr) {
+                continue;
+            }
             swarm.listen_on(addr.clone())?;
             initial_listeners.push(addr.clone());
         }
+        if initial_listeners.is_empty() && !config.listen_addresses.is_empty() {
+            return Err(anyhow!(
+                "No listen address supported by transport mode {}",
+                config.transport
+            ));
+        }
 
         // Add external addresses
         for addr in &config.external_addresses {
@@ -151,7 +180,11 @@ impl Node {
         }
 
         // Bootstrap with configured peers
-        for (peer_id, addr) in &config.bootstrap_peers {
+        for (peer_id, addr) in config
+            .bootstrap_peers
+            .iter()
+            .filter(|(_, addr)| config.transport.supports(addr))
+        {
             swarm.dial(addr.clone())?;
             swarm.behaviour_mut().kad.add_address(peer_id, addr.clone());
         }
@@ -162,29 +195,73 @@ impl Node {
 
         let connected_peers = Arc::new(RwLock::new(HashSet::new()));
         let discovered_peers = Arc::new(RwLock::new(HashSet::new()));
+        let peer_capabilities = Arc::new(RwLock::new(PeerCapabilities::new()));
         let is_running = Arc::new(RwLock::new(false));
         let listeners = Arc::new(RwLock::new(initial_listeners));
+        let republish_status = Arc::new(RwLock::new(RepublishStatus::default()));
+        let connection_stats = Arc::new(RwLock::new(ConnectionStats::default()));
+        // Peers announce every interval (assumed to match ours), so three
+        // missed announcements mean the peer is gone
+        let announcement_interval = if config.model_announcement_interval > Duration::ZERO {
+            config.model_announcement_interval
+        } else {
+            NodeConfig::default().model_announcement_interval
+        };
+        let model_availability = Arc::new(RwLock::new(ModelAvailability::new(
+            announcement_interval * 3,
+        )));
+        let local_availability = Arc::new(RwLock::new(LocalAvailability {
+            models: config.capabilities.clone(),
+            ..Default::default()
+        }));
+        let reputation = Arc::new(RwLock::new(ReputationTracker::new(
+            config.reputation.clone(),
+        )));
 
         // Clone for the swarm task
         let connected_peers_clone = connected_peers.clone();
         let discovered_peers_clone = discovered_peers.clone();
+        let peer_capabilities_clone = peer_capabilities.clone();
         let is_running_clone = is_running.clone();
         let listeners_clone = listeners.clone();
+        let republish_status_clone = republish_status.clone();
+        let connection_stats_clone = connection_stats.clone();
+        let model_availability_clone = model_availability.clone();
+        let local_availability_clone = local_availability.clone();
+        let reputation_clone = reputation.clone();
         let config_clone = config.clone();
         let peer_id_clone = peer_id;
 
         // Spawn swarm event loop
         let swarm_task = tokio::spawn(async move {
             let mut swarm = swarm;
-            let mut dht_handler = DhtHandler::new(
-                config_clone.dht_bootstrap_interval,
-                config_clone.dht_republish_interval,
-            );
+            let republish_every = config_clone.effective_dht_republish_interval();
+            let mut dht_handler =
+                DhtHandler::new(config_clone.dht_bootstrap_interval, republish_every);
             let mut peer_last_seen: HashMap<PeerId, Instant> = HashMap::new();
+            let protected_peers = config_clone
+                .bootstrap_peers
+                .iter()
+                .chain(&config_clone.rendezvous_servers)
+                .map(|(peer_id, _)| *peer_id)
+                .chain(config_clone.protected_peers.iter().copied());
+            let mut connection_tracker = ConnectionTracker::new(
+                ConnectionLimits {
+                    max_connections: config_clone.max_connections,
+                    max_connections_per_peer: config_clone.max_connections_per_peer,
+                    idle_timeout: config_clone.connection_idle_timeout,
+                },
+                protected_peers,
+            );
             let mut request_tracker = RequestTracker::new(Duration::from_secs(60));
             let mut rate_limiter = RateLimiter::new(config_clone.max_requests_per_minute);
             let mut streaming_handler = StreamingHandler::new();
             let mut pending_responses: HashMap<String, ResponseChannel> = HashMap::new();
+            let mut inference_forwarder = InferenceForwarder::new(peer_id_clone, MAX_FORWARD_HOPS);
+            let mut transport_dedup = TransportDedup::new();
+            // Outbound inference requests in flight, for latency scoring
+            let mut inference_requests_sent: HashMap<OutboundRequestId, (PeerId, Instant)> =
+                HashMap::new();
 
             // Start bootstrap if we have bootstrap peers
             if !config_clone.bootstrap_peers.is_empty() {
@@ -204,9 +281,18 @@ impl Node {
                 None
             };
 
-            // Set up periodic republish
-            let mut republish_interval = if config_clone.dht_republish_interval > Duration::ZERO {
-                Some(interval(config_clone.dht_republish_interval))
+            // Set up periodic republish, kept well under the record TTL so our
+            // records and provider announcements never expire on other peers
+            let mut republish_interval = if republish_every > Duration::ZERO {
+                Some(interval(republish_every))
+            } else {
+                None
+            };
+
+            // Set up periodic model availability announcements
+            let announce_every = config_clone.model_announcement_interval;
+            let mut announce_interval = if announce_every > Duration::ZERO {
+                Some(interval(announce_every))
             } else {
                 None
             };
@@ -226,6 +312,12 @@ impl Node {
                     Some(command) = command_rx.recv() => {
                         match command {
                             Command::Connect { peer_id, addr, result_sender } => {
+                                if !config_clone.transport.supports(&addr) {
+                                    let _ = result_sender.send(Err(anyhow!(
+                                        "Cannot dial {}: transport mode is {}", addr, config_clone.transport
+                                    )));
+                                    continue;
+                                }
                                 let result = swarm.dial(addr.clone()).map(|_| {
                                     swarm.behaviour_mut().kad.add_address(&peer_id, addr);
                                 });
@@ -249,9 +341,10 @@ impl Node {
                                 dht_handler.register_get_record(query_id, key, result_sender);
                             }
                             Command::DhtStartProviding { key, result_sender } => {
-                                match swarm.behaviour_mut().kad.start_providing(key) {
+                                match swarm.behaviour_mut().kad.start_providing(key.clone()) {
                                     Ok(query_id) => {
                                         dht_handler.register_start_providing(query_id, result_sender);
+                                        dht_handler.track_provided_key(key);
                                     }
                                     Err(e) => {
                                         let _ = result_sender.send(Err(anyhow!(e.to_string())));
@@ -274,8 +367,9 @@ impl Node {
                                 // Check rate limit
                                 match rate_limiter.check_rate_limit(&peer_id_clone) {
                                     Ok(_) => {
-                                        let _request_id = swarm.behaviour_mut().request_response
+                                        let outbound_id = swarm.behaviour_mut().request_response
                                             .send_request(&peer_id, FabstirRequest::Inference(request.clone()));
+                                        inference_requests_sent.insert(outbound_id, (peer_id, Instant::now()));
 
                                         // Track the request for timeout
                                         let _ = request_tracker.track_request(request.request_id.clone());
@@ -293,9 +387,11 @@ impl Node {
                                     }
                                 }
                             }
-                            Command::SendInferenceResponse { peer_id: _, response, result_sender } => {
+                            Command::SendInferenceResponse { peer_id: _, mut response, result_sender } => {
                                 // Find the response channel for this request
                                 if let Some(channel) = pending_responses.remove(&response.request_id) {
+                                    // We served it, so the work is ours
+                                    response.served_by.get_or_insert_with(|| peer_id_clone.to_string());
                                     let result = swarm.behaviour_mut().request_response
                                         .send_response(channel, FabstirResponse::Inference(response))
                                         .map_err(|_| anyhow::anyhow!("Failed to send response"));
@@ -325,28 +421,71 @@ impl Node {
                                 listeners_clone.write().await.push(address.clone());
                                 let _ = event_tx.send(NodeEvent::NewListenAddr { address }).await;
                             }
-                            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                            SwarmEvent::ConnectionEstablished { peer_id, connection_id, endpoint, num_established, .. } => {
+                                if reputation_clone.read().await.is_banned(&peer_id) {
+                                    swarm.close_connection(connection_id);
+                                    continue;
+                                }
+                                // One transport per peer: drop TCP when QUIC is also connected
+                                let transport = ConnectionTransport::of(endpoint.get_remote_address());
+                                let duplicates = transport_dedup.on_established(peer_id, connection_id, transport);
+                                for duplicate in &duplicates {
+                                    swarm.close_connection(*duplicate);
+                                }
+                                if duplicates.contains(&connection_id) {
+                                    continue;
+                                }
+                                let admission = connection_tracker.on_established(peer_id, num_established.get() as usize);
+                                *connection_stats_clone.write().await = connection_tracker.stats();
+                                match admission {
+                                    Admission::Accept => {}
+                                    Admission::Evict(victim) => {
+                                        let _ = swarm.disconnect_peer_id(victim);
+                                        let _ = event_tx.send(NodeEvent::PeerEvicted { peer_id: victim }).await;
+                                    }
+                                    Admission::Reject => {
+                                        swarm.close_connection(connection_id);
+                                        continue;
+                                    }
+                                }
                                 connected_peers_clone.write().await.insert(peer_id);
                                 peer_last_seen.insert(peer_id, Instant::now());
                                 let _ = event_tx.send(NodeEvent::ConnectionEstablished { peer_id }).await;
                             }
-                            SwarmEvent::ConnectionClosed { peer_id, .. } => {
-                                connected_peers_clone.write().await.remove(&peer_id);
-                                peer_last_seen.remove(&peer_id);
+                            SwarmEvent::ConnectionClosed { peer_id, connection_id, num_established, .. } => {
+                                transport_dedup.on_closed(&peer_id, connection_id);
+                                connection_tracker.on_closed(peer_id, num_established as usize);
+                                *connection_stats_clone.write().await = connection_tracker.stats();
+                                if num_established == 0 {
+                                    connected_peers_clone.write().await.remove(&peer_id);
+                                    peer_last_seen.remove(&peer_id);
+                                }
                                 let _ = event_tx.send(NodeEvent::ConnectionClosed { peer_id }).await;
                             }
                             SwarmEvent::Behaviour(event) => {
                                 match event {
                                     crate::p2p::behaviour::NodeBehaviourEvent::Kad(kad_event) => {
                                         dht_handler.handle_event(kad_event, &event_tx);
+                                        republish_status_clone.write().await.last_republish =
+                                            dht_handler.last_republish().cloned();
                                     }
                                     crate::p2p::behaviour::NodeBehaviourEvent::Mdns(mdns_event) => {
                                         match mdns_event {
                                             libp2p::mdns::Event::Discovered(peers) => {
                                                 for (peer_id, addr) in peers {
+                                                    if !config_clone.transport.supports(&addr) || reputation_clone.read().await.is_banned(&peer_id) {
+                                                        continue;
+                                                    }
                                                     discovered_peers_clone.write().await.insert(peer_id);
                                                     peer_last_seen.insert(peer_id, Instant::now());
                                                     swarm.behaviour_mut().kad.add_address(&peer_id, addr.clone());
+                                                    // Connect so identify reports the peer's capabilities
+                                                    let _ = swarm.dial(
+                                                        DialOpts::peer_id(peer_id)
+                                                            .addresses(vec![addr.clone()])
+                                                            .condition(PeerCondition::DisconnectedAndNotDialing)
+                                                            .build(),
+                                                    );
                                                     let _ = event_tx.send(NodeEvent::DiscoveryEvent(
                                                         DiscoveryEvent::PeerDiscovered {
                                                             peer_id,
@@ -359,6 +498,7 @@ impl Node {
                                             libp2p::mdns::Event::Expired(peers) => {
                                                 for (peer_id, _) in peers {
                                                     discovered_peers_clone.write().await.remove(&peer_id);
+                                                    peer_capabilities_clone.write().await.remove(&peer_id);
                                                     peer_last_seen.remove(&peer_id);
                                                     let _ = event_tx.send(NodeEvent::DiscoveryEvent(
                                                         DiscoveryEvent::PeerExpired { peer_id }
@@ -367,11 +507,60 @@ impl Node {
                                             }
                                         }
                                     }
+                                    crate::p2p::behaviour::NodeBehaviourEvent::Identify(
+                                        libp2p::identify::Event::Received { peer_id, info, .. }
+                                    ) => {
+                                        if let Some(advertised) = parse_advertised_capabilities(&info.agent_version) {
+                                            let served = served_capabilities(&advertised, &info.protocols);
+                                            if served != advertised {
+                                                let mut advertised: Vec<_> = advertised.into_iter().collect();
+                                                advertised.sort();
+                                                let mut served_list: Vec<_> = served.iter().cloned().collect();
+                                                served_list.sort();
+                                                let _ = event_tx.send(NodeEvent::DiscoveryEvent(
+                                                    DiscoveryEvent::CapabilityMismatch {
+                                                        peer_id,
+                                                        advertised,
+                                                        served: served_list,
+                                                    }
+                                                )).await;
+                                            }
+                                            peer_capabilities_clone.write().await.insert(peer_id, served);
+                                        }
+                                    }
+                                    crate::p2p::behaviour::NodeBehaviourEvent::Gossipsub(
+                                        libp2p::gossipsub::Event::Message { message, .. }
+                                    ) => {
+                                        // Only signed announcements whose key matches the announced peer are accepted
+                                        let announcement = SignedModelAnnouncement::from_bytes(&message.data)
+                                            .and_then(|signed| signed.verify());
+                                        let announcement = match announcement {
+                                            Ok(announcement) => Some(announcement),
+                                            Err(_) => {
+                                                // Gossipsub already checked the message signature, so the source sent it
+                                                if let Some(source) = message.source {
+                                                    record_reputation(&mut swarm, &reputation_clone, &event_tx, source, ReputationEvent::MalformedMessage).await;
+                                                }
+                                                None
+                                            }
+                                        };
+                                        if let Some(announcement) = announcement {
+                                            if let Ok(peer_id) = announcement.peer_id.parse::<PeerId>() {
+                                                let models = announcement.models.clone();
+                                                if model_availability_clone.write().await.update(peer_id, announcement) {
+                                                    let _ = event_tx.send(NodeEvent::DiscoveryEvent(
+                                                        DiscoveryEvent::ModelsAnnounced { peer_id, models }
+                                                    )).await;
+                                                }
+                                            }
+                                        }
+                                    }
                                     crate::p2p::behaviour::NodeBehaviourEvent::RequestResponse(req_resp_event) => {
-                                        use libp2p::request_response::{Event as ReqRespEvent, Message};
+                                        use libp2p::request_response::{Event as ReqRespEvent, InboundFailure, Message, OutboundFailure};
 
                                         match req_resp_event {
                                             ReqRespEvent::Message { peer, message } => {
+                                                connection_tracker.record_activity(&peer);
                                                 match message {
                                                     Message::Request { request, channel, .. } => {
                                                         // Check rate limit for incoming requests
@@ -389,7 +578,7 @@ impl Node {
                                                         }
 
                                                         match request {
-                                                            FabstirRequest::Inference(req) => {
+                                                            FabstirRequest::Inference(req) if serves_model(&config_clone, &req.model) => {
                                                                 // Store the channel so we can respond later
                                                                 pending_responses.insert(req.request_id.clone(), channel);
 
@@ -400,6 +589,44 @@ impl Node {
                                                                     }
                                                                 )).await;
                                                             }
+                                                            FabstirRequest::Inference(req) => {
+                                                                // Model not loaded here: pass the request to a peer that serves it
+                                                                let connected = connected_peers_clone.read().await.clone();
+                                                                let capable: Vec<PeerId> = peer_capabilities_clone
+                                                                    .read()
+                                                                    .await
+                                                                    .peers_with_capability(&req.model)
+                                                                    .into_iter()
+                                                                    .filter(|candidate| connected.contains(candidate))
+                                                                    .collect();
+                                                                // Best reputation first; banned peers are never used
+                                                                let candidates = reputation_clone.read().await.rank(capable);
+
+                                                                match inference_forwarder.prepare(&req, &peer, &candidates) {
+                                                                    Ok((target, forwarded)) => {
+                                                                        let outbound_id = swarm.behaviour_mut().request_response
+                                                                            .send_request(&target, FabstirRequest::Inference(forwarded));
+                                                                        inference_requests_sent.insert(outbound_id, (target, Instant::now()));
+                                                                        inference_forwarder.track(outbound_id, &req, peer, target, channel);
+                                                                        let _ = event_tx.send(NodeEvent::ProtocolEvent(
+                                                                            ProtocolEvent::InferenceForwarded {
+                                                                                request_id: req.request_id,
+                                                                                from: peer,
+                                                                                to: target,
+                                                                            }
+                                                                        )).await;
+                                                                    }
+                                                                    Err(e) => {
+                                                                        let response = InferenceForwarder::error_response(
+                                                                            &req.request_id,
+                                                                            &req.model,
+                                                                            &e.to_string(),
+                                                                        );
+                                                                        let _ = swarm.behaviour_mut().request_response
+                                                                            .send_response(channel, FabstirResponse::Inference(response));
+                                                                    }
+                                                                }
+                                                            }
                                                             FabstirRequest::JobClaim(claim) => {
                                                                 // For job claims, we might send an acknowledgment
                                                                 let ack = FabstirResponse::JobClaimAck {
@@ -434,9 +661,28 @@ impl Node {
                                                             }
                                                         }
                                                     }
-                                                    Message::Response { response, .. } => {
+                                                    Message::Response { request_id, response } => {
+                                                        if let Some((_, sent_at)) = inference_requests_sent.remove(&request_id) {
+                                                            let latency = sent_at.elapsed();
+                                                            record_reputation(&mut swarm, &reputation_clone, &event_tx, peer, ReputationEvent::DeliverySucceeded { latency }).await;
+                                                        }
                                                         match response {
                                                             FabstirResponse::Inference(resp) => {
+                                                                // Responses to forwarded requests go back to the original requester
+                                                                if let Some((requester, channel, resp)) = inference_forwarder.relay(&request_id, resp.clone()) {
+                                                                    let _ = event_tx.send(NodeEvent::ProtocolEvent(
+                                                                        ProtocolEvent::ForwardedResponseRelayed {
+                                                                            request_id: resp.request_id.clone(),
+                                                                            to: requester,
+                                                                            served_by: resp.served_by.clone(),
+                                                                            tokens_used: resp.tokens_used,
+                                                                        }
+                                                                    )).await;
+                                                                    let _ = swarm.behaviour_mut().request_response
+                                                                        .send_response(channel, FabstirResponse::Inference(resp));
+                                                                    continue;
+                                                                }
+
                                                                 // Complete the tracked request
                                                                 request_tracker.complete_request(&resp.request_id, resp.clone());
 
@@ -456,7 +702,29 @@ impl Node {
                                                     }
                                                 }
                                             }
-                                            _ => {} // Handle other events like OutboundFailure, ResponseSent, etc.
+                                            ReqRespEvent::OutboundFailure { peer, request_id, error, .. } => {
+                                                inference_requests_sent.remove(&request_id);
+                                                let reputation_event = match &error {
+                                                    OutboundFailure::Io(e) if e.kind() == std::io::ErrorKind::InvalidData => ReputationEvent::MalformedMessage,
+                                                    _ => ReputationEvent::DeliveryFailed,
+                                                };
+                                                record_reputation(&mut swarm, &reputation_clone, &event_tx, peer, reputation_event).await;
+
+                                                // Tell the requester if a forwarded request could not be delivered
+                                                if let Some((_, channel, response)) = inference_forwarder.fail(
+                                                    &request_id,
+                                                    &format!("Forwarding failed: {}", error),
+                                                ) {
+                                                    let _ = swarm.behaviour_mut().request_response
+                                                        .send_response(channel, FabstirResponse::Inference(response));
+                                                }
+                                            }
+                                            ReqRespEvent::InboundFailure { peer, error: InboundFailure::Io(e), .. }
+                                                if e.kind() == std::io::ErrorKind::InvalidData =>
+                                            {
+                                                record_reputation(&mut swarm, &reputation_clone, &event_tx, peer, ReputationEvent::MalformedMessage).await;
+                                            }
+                                            _ => {} // Handle other events like ResponseSent, etc.
                                         }
                                     }
                                     _ => {}
@@ -478,18 +746,69 @@ impl Node {
                         // Clean up expired records
                         dht_handler.cleanup_expired_records();
 
+                        let mut queries = Vec::new();
+                        let mut failed = 0;
+
                         // Republish records that need republishing
                         let records_to_republish = dht_handler.get_records_to_republish();
+                        let records = records_to_republish.len();
                         for (key, value) in records_to_republish {
                             let record = libp2p::kad::Record::new(key.clone(), value);
-                            if let Ok(_) = swarm.behaviour_mut().kad.put_record(record, libp2p::kad::Quorum::One) {
-                                let _ = event_tx.send(NodeEvent::DhtEvent(DhtEvent::RecordRepublished { key })).await;
+                            match swarm.behaviour_mut().kad.put_record(record, libp2p::kad::Quorum::One) {
+                                Ok(query_id) => {
+                                    queries.push(query_id);
+                                    let _ = event_tx.send(NodeEvent::DhtEvent(DhtEvent::RecordRepublished { key })).await;
+                                }
+                                Err(_) => failed += 1,
+                            }
+                        }
+
+                        // Re-announce everything we provide (capabilities, model ids)
+                        let provided_keys = dht_handler.provided_keys();
+                        let providers = provided_keys.len();
+                        for key in provided_keys {
+                            match swarm.behaviour_mut().kad.start_providing(key) {
+                                Ok(query_id) => queries.push(query_id),
+                                Err(_) => failed += 1,
                             }
                         }
+
+                        let outcome = dht_handler.record_republish(queries, records, providers, failed);
+                        {
+                            let mut status = republish_status_clone.write().await;
+                            status.next_republish = dht_handler.next_republish();
+                            status.last_republish = Some(outcome);
+                        }
+                        let _ = event_tx.send(NodeEvent::DhtEvent(DhtEvent::Republished {
+                            records,
+                            providers,
+                            failed,
+                        })).await;
+                    }
+                    _ = announce_interval.as_mut().unwrap().tick(), if announce_interval.is_some() => {
+                        let local = local_availability_clone.read().await.clone();
+                        let announcement = ModelAnnouncement {
+                            peer_id: peer_id_clone.to_string(),
+                            host_address: config_clone.host_address.clone(),
+                            models: local.models,
+                            price_per_token: local.price_per_token,
+                            current_load: local.current_load,
+                            timestamp: unix_now(),
+                        };
+                        if let Ok(data) = SignedModelAnnouncement::sign(&announcement, &keypair)
+                            .and_then(|signed| signed.to_bytes())
+                        {
+                            // Fails until a peer subscribes to the topic; the next tick retries
+                            let _ = swarm.behaviour_mut().gossipsub.publish(
+                                libp2p::gossipsub::IdentTopic::new(MODEL_AVAILABILITY_TOPIC),
+                                data,
+                            );
+                        }
                     }
                     _ = cleanup_interval.tick() => {
                         // Periodic cleanup of expired records
                         dht_handler.cleanup_expired_records();
+                        model_availability_clone.write().await.prune();
                     }
                     _ = peer_check_interval.tick() => {
                         // Check for expired peers
@@ -512,6 +831,7 @@ impl Node {
                         // Remove expired peers
                         for peer_id in expired_peers {
                             discovered_peers_clone.write().await.remove(&peer_id);
+                            peer_capabilities_clone.write().await.remove(&peer_id);
                             peer_last_seen.remove(&peer_id);
                             let _ = event_tx.send(NodeEvent::DiscoveryEvent(DiscoveryEvent::PeerExpired { peer_id })).await;
                         }
@@ -541,6 +861,7 @@ impl Node {
             start_time: Instant::now(),
             connected_peers,
             discovered_peers,
+            peer_capabilities,
             peer_metadata: Arc::new(RwLock::new(HashMap::new())),
             protocol_handler: Arc::new(Mutex::new(ProtocolHandler::new(
                 config.protocol_version.clone(),
@@ -551,6 +872,11 @@ impl Node {
             bandwidth_counter: Arc::new(Mutex::new((0, 0))),
             swarm_task: Some(swarm_task),
             listeners,
+            republish_status,
+            connection_stats,
+            model_availability,
+            local_availability,
+            reputation,
         })
     }
 
@@ -622,11 +948,27 @@ impl Node {
             .try_read()
             .map(|p| p.len())
             .unwrap_or(0);
+        let connections = self
+            .connection_stats
+            .try_read()
+            .map(|stats| *stats)
+            .unwrap_or_default();
+        let (peer_scores, banned_peers) = self
+            .reputation
+            .try_read()
+            .map(|reputation| (reputation.scores(), reputation.banned_count()))
+            .unwrap_or_default();
         NodeMetrics {
             connected_peers,
             bandwidth_in: bandwidth.0,
             bandwidth_out: bandwidth.1,
             uptime: self.start_time.elapsed(),
+            connections: connections.connections,
+            protected_peers: connections.protected_peers,
+            evicted_peers: connections.evicted_peers,
+            rejected_connections: connections.rejected_connections,
+            peer_scores,
+            banned_peers,
         }
     }
 
@@ -672,6 +1014,44 @@ impl Node {
         self.discovered_peers.read().await.clone()
     }
 
+    /// Discovered or connected peers that serve `capability`
+    ///
+    /// Only peers whose identify info has been received are included, and
+    /// only for capabilities they can actually serve.
+    pub async fn peers_with_capability(&self, capability: &str) -> Vec<PeerId> {
+        let peers = self
+            .peer_capabilities
+            .read()
+            .await
+            .peers_with_capability(capability);
+        self.reputation.read().await.rank(peers)
+    }
+
+    /// Current reputation score of `peer_id`, 0.0 if unscored
+    pub async fn peer_score(&self, peer_id: &PeerId) -> f64 {
+        self.reputation.read().await.score(peer_id)
+    }
+
+    pub async fn is_peer_banned(&self, peer_id: &PeerId) -> bool {
+        self.reputation.read().await.is_banned(peer_id)
+    }
+
+    /// Latest model announcements from other nodes, usable with
+    /// `HostSelector::apply_model_announcements`
+    pub async fn model_availability(&self) -> Vec<ModelAnnouncement> {
+        self.model_availability.read().await.all()
+    }
+
+    /// Announcements from nodes serving `model`
+    pub async fn hosts_for_model(&self, model: &str) -> Vec<ModelAnnouncement> {
+        self.model_availability.read().await.hosts_for_model(model)
+    }
+
+    /// Set the models, price and load included in our next announcement
+    pub async fn set_local_availability(&self, local: LocalAvailability) {
+        *self.local_availability.write().await = local;
+    }
+
     // DHT operations
     pub async fn dht_put(&mut self, key: RecordKey, value: Vec<u8>) -> Result<()> {
         if let Some(tx) = &self.command_sender {
@@ -766,10 +1146,24 @@ impl Node {
             .try_read()
             .map(|peers| peers.len())
             .unwrap_or(0);
+        let (time_to_next_republish, last_republish) = self
+            .republish_status
+            .try_read()
+            .map(|status| {
+                (
+                    status
+                        .next_republish
+                        .map(|next| next.saturating_duration_since(Instant::now())),
+                    status.last_republish.clone(),
+                )
+            })
+            .unwrap_or((None, None));
         DhtRoutingTableHealth {
             num_peers,
             num_buckets: 20, // Kademlia default
             pending_queries: 0,
+            time_to_next_republish,
+            last_republish,
         }
     }
 
@@ -794,9 +1188,8 @@ impl Node {
 
     pub async fn find_nodes_with_capability(&mut self, capability: &str) -> Result<Vec<PeerId>> {
         let key = RecordKey::new(&format!("capability:{}", capability).as_bytes());
-        self.dht_get_providers(key)
-            .await
-            .map(|set| set.into_iter().collect())
+        let providers = self.dht_get_providers(key).await?;
+        Ok(self.reputation.read().await.rank(providers))
     }
 
     pub async fn discover_peers_with_capability(
@@ -1020,3 +1413,66 @@ impl Node {
         }
     }
 }
+
+/// Whether requests for `model` are served here rather than forwarded; a
+/// node without configured capabilities serves every request itself
+fn serves_model(config: &NodeConfig, model: &str) -> bool {
+    config.capabilities.is_empty() || config.capabilities.iter().any(|c| c == model)
+}
+
+/// Build the swarm with the transports selected by `config.transport`
+fn build_swarm(keypair: Keypair, config: &NodeConfig) -> Result<Swarm<NodeBehaviour>> {
+    let behaviour =
+        |key: &Keypair| NodeBehaviour::new(key, config).expect("Failed to create behaviour");
+    let swarm_config = |cfg: libp2p::swarm::Config| {
+        cfg.with_idle_connection_timeout(config.connection_idle_timeout)
+    };
+
+    let swarm = match config.transport {
+        TransportMode::TcpOnly => SwarmBuilder::with_existing_identity(keypair)
+            .with_tokio()
+            .with_tcp(
+                libp2p::tcp::Config::default(),
+                libp2p::noise::Config::new,
+                libp2p::yamux::Config::default,
+            )?
+            .with_behaviour(behaviour)?
+            .with_swarm_config(swarm_config)
+            .build(),
+        TransportMode::QuicOnly => SwarmBuilder::with_existing_identity(keypair)
+            .with_tokio()
+            .with_quic()
+            .with_behaviour(behaviour)?
+            .with_swarm_config(swarm_config)
+            .build(),
+        TransportMode::Both => SwarmBuilder::with_existing_identity(keypair)
+            .with_tokio()
+            .with_tcp(
+                libp2p::tcp::Config::default(),
+                libp2p::noise::Config::new,
+                libp2p::yamux::Config::default,
+            )?
+            .with_quic()
+            .with_behaviour(behaviour)?
+            .with_swarm_config(swarm_config)
+            .build(),
+    };
+    Ok(swarm)
+}
+
+/// Score `peer_id` for `event`, disconnecting it if the score falls to the
+/// ban threshold
+async fn record_reputation(
+    swarm: &mut Swarm<NodeBehaviour>,
+    reputation: &RwLock<ReputationTracker>,
+    event_tx: &tokio_mpsc::Sender<NodeEvent>,
+    peer_id: PeerId,
+    event: ReputationEvent,
+) {
+    if let Some(score) = reputation.write().await.record(peer_id, event) {
+        let _ = swarm.disconnect_peer_id(peer_id);
+        let _ = event_tx
+            .send(NodeEvent::PeerBanned { peer_id, score })
+            .await;
+    }
+}
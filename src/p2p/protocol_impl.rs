@@ -5,12 +5,12 @@ use async_trait::async_trait;
 use futures::io::{AsyncRead, AsyncWrite};
 use futures::prelude::*;
 use libp2p::{
-    request_response::{self, Codec},
-    StreamProtocol,
+    request_response::{self, Codec, OutboundRequestId},
+    PeerId, StreamProtocol,
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     time::{Duration, Instant},
 };
@@ -25,6 +25,9 @@ pub type ResponseChannel = request_response::ResponseChannel<FabstirResponse>;
 pub const INFERENCE_PROTOCOL: &str = "/fabstir/inference/1.0.0";
 pub const JOB_PROTOCOL: &str = "/fabstir/job/1.0.0";
 
+/// Times an inference request may be forwarded before it is rejected
+pub const MAX_FORWARD_HOPS: usize = 3;
+
 // Codec for encoding/decoding messages
 #[derive(Debug, Clone, Default)]
 pub struct FabstirCodec;
@@ -294,3 +297,179 @@ impl StreamingHandler {
         self.active_streams.remove(request_id);
     }
 }
+
+// Inference forwarding
+struct ForwardedRequest {
+    request_id: String,
+    model: String,
+    from: PeerId,
+    to: PeerId,
+    channel: ResponseChannel,
+}
+
+/// Forwards inference requests for models this node does not serve to a
+/// peer that does, and relays the response back to the requester
+pub struct InferenceForwarder {
+    local_peer: PeerId,
+    max_hops: usize,
+    pending: HashMap<OutboundRequestId, ForwardedRequest>,
+}
+
+impl InferenceForwarder {
+    pub fn new(local_peer: PeerId, max_hops: usize) -> Self {
+        Self {
+            local_peer,
+            max_hops,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Choose a peer among `candidates` for a request received from `from`
+    /// and return the request with this node added to its route
+    ///
+    /// Peers already on the route are skipped, and requests that have been
+    /// forwarded `max_hops` times are refused, so requests cannot loop.
+    pub fn prepare(
+        &self,
+        request: &InferenceRequest,
+        from: &PeerId,
+        candidates: &[PeerId],
+    ) -> Result<(PeerId, InferenceRequest)> {
+        if request.route.len() >= self.max_hops {
+            return Err(anyhow::anyhow!(
+                "Request {} reached the forwarding hop limit of {}",
+                request.request_id,
+                self.max_hops
+            ));
+        }
+
+        let visited: HashSet<String> = request
+            .route
+            .iter()
+            .cloned()
+            .chain([from.to_string(), self.local_peer.to_string()])
+            .collect();
+        let target = candidates
+            .iter()
+            .find(|peer| !visited.contains(&peer.to_string()))
+            .ok_or_else(|| anyhow::anyhow!("No peer available to serve model {}", request.model))?;
+
+        let mut forwarded = request.clone();
+        forwarded.route.push(self.local_peer.to_string());
+        Ok((*target, forwarded))
+    }
+
+    /// Remember where to relay the response to a forwarded request
+    pub fn track(
+        &mut self,
+        outbound_id: OutboundRequestId,
+        request: &InferenceRequest,
+        from: PeerId,
+        to: PeerId,
+        channel: ResponseChannel,
+    ) {
+        self.pending.insert(
+            outbound_id,
+            ForwardedRequest {
+                request_id: request.request_id.clone(),
+                model: request.model.clone(),
+                from,
+                to,
+                channel,
+            },
+        );
+    }
+
+    /// Take the requester's channel for a response to a forwarded request
+    ///
+    /// The work stays attributed to the peer that served it; if the response
+    /// does not name one, it is the peer we forwarded to.
+    pub fn relay(
+        &mut self,
+        outbound_id: &OutboundRequestId,
+        mut response: InferenceResponse,
+    ) -> Option<(PeerId, ResponseChannel, InferenceResponse)> {
+        let forwarded = self.pending.remove(outbound_id)?;
+        response
+            .served_by
+            .get_or_insert_with(|| forwarded.to.to_string());
+        Some((forwarded.from, forwarded.channel, response))
+    }
+
+    /// Take the requester's channel for a forwarded request that failed,
+    /// with an error response to send back
+    pub fn fail(
+        &mut self,
+        outbound_id: &OutboundRequestId,
+        error: &str,
+    ) -> Option<(PeerId, ResponseChannel, InferenceResponse)> {
+        let forwarded = self.pending.remove(outbound_id)?;
+        let response = Self::error_response(&forwarded.request_id, &forwarded.model, error);
+        Some((forwarded.from, forwarded.channel, response))
+    }
+
+    /// Response telling the requester its request could not be served
+    pub fn error_response(request_id: &str, model: &str, error: &str) -> InferenceResponse {
+        InferenceResponse {
+            request_id: request_id.to_string(),
+            content: error.to_string(),
+            tokens_used: 0,
+            model_used: model.to_string(),
+            finish_reason: "error".to_string(),
+            served_by: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(route: Vec<String>) -> InferenceRequest {
+        InferenceRequest {
+            request_id: "req-1".to_string(),
+            model: "llama-7b".to_string(),
+            prompt: "Hello".to_string(),
+            max_tokens: 10,
+            temperature: 0.7,
+            stream: false,
+            route,
+        }
+    }
+
+    #[test]
+    fn test_forward_skips_peers_on_route() {
+        let local = PeerId::random();
+        let (requester, earlier_hop, capable) =
+            (PeerId::random(), PeerId::random(), PeerId::random());
+        let forwarder = InferenceForwarder::new(local, MAX_FORWARD_HOPS);
+
+        let (target, forwarded) = forwarder
+            .prepare(
+                &request(vec![earlier_hop.to_string()]),
+                &requester,
+                &[requester, earlier_hop, capable],
+            )
+            .unwrap();
+        assert_eq!(target, capable);
+        assert_eq!(
+            forwarded.route,
+            vec![earlier_hop.to_string(), local.to_string()]
+        );
+
+        // Only peers already on the route serve the model
+        assert!(forwarder
+            .prepare(&request(vec![]), &requester, &[requester, local])
+            .is_err());
+    }
+
+    #[test]
+    fn test_forward_refused_at_hop_limit() {
+        let forwarder = InferenceForwarder::new(PeerId::random(), 2);
+        let route = vec![PeerId::random().to_string(), PeerId::random().to_string()];
+        let err = forwarder
+            .prepare(&request(route), &PeerId::random(), &[PeerId::random()])
+            .unwrap_err();
+        assert!(err.to_string().contains("hop limit"));
+    }
+}
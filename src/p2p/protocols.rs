@@ -16,6 +16,9 @@ pub struct InferenceRequest {
     pub max_tokens: usize,
     pub temperature: f32,
     pub stream: bool,
+    /// Peers that forwarded this request, oldest first
+    #[serde(default)]
+    pub route: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +28,10 @@ pub struct InferenceResponse {
     pub tokens_used: usize,
     pub model_used: String,
     pub finish_reason: String,
+    /// Peer that ran the inference and is owed payment for it; forwarding
+    /// peers relay the response without claiming the work
+    #[serde(default)]
+    pub served_by: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +87,19 @@ pub enum ProtocolEvent {
         requests_made: usize,
         limit: usize,
     },
+    /// A request for a model we do not serve was passed on to `to`
+    InferenceForwarded {
+        request_id: String,
+        from: PeerId,
+        to: PeerId,
+    },
+    /// The response to a forwarded request was relayed back to `to`
+    ForwardedResponseRelayed {
+        request_id: String,
+        to: PeerId,
+        served_by: Option<String>,
+        tokens_used: usize,
+    },
 }
 
 pub type StreamingResponse = mpsc::Receiver<InferenceResponse>;
@@ -0,0 +1,218 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Peer reputation scoring
+//!
+//! Peers gain score for delivering responses (more for fast ones) and lose
+//! it for malformed messages and failed deliveries. Scores decay back
+//! towards zero over time, so old behaviour is gradually forgiven. Peers
+//! below `deprioritize_threshold` are tried last; peers at or below
+//! `ban_threshold` are disconnected and ignored until the ban expires.
+
+use libp2p::PeerId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::p2p_config::ReputationConfig;
+
+const MALFORMED_MESSAGE_PENALTY: f64 = -20.0;
+const DELIVERY_FAILED_PENALTY: f64 = -5.0;
+const FAST_DELIVERY_REWARD: f64 = 2.0;
+const SLOW_DELIVERY_REWARD: f64 = 1.0;
+/// Scores are capped so a long good history cannot hide misbehaviour
+const MAX_SCORE: f64 = 100.0;
+
+/// Peer behaviour that affects its reputation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReputationEvent {
+    /// Sent a message that could not be decoded or violated the protocol
+    MalformedMessage,
+    /// Failed to deliver a response or timed out
+    DeliveryFailed,
+    /// Delivered a response after `latency`
+    DeliverySucceeded { latency: Duration },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PeerReputation {
+    score: f64,
+    updated: Instant,
+    banned_until: Option<Instant>,
+}
+
+pub struct ReputationTracker {
+    config: ReputationConfig,
+    peers: HashMap<PeerId, PeerReputation>,
+}
+
+impl ReputationTracker {
+    pub fn new(config: ReputationConfig) -> Self {
+        Self {
+            config,
+            peers: HashMap::new(),
+        }
+    }
+
+    fn decayed(&self, peer: &PeerReputation, now: Instant) -> f64 {
+        let half_life = self.config.decay_half_life.as_secs_f64();
+        if half_life <= 0.0 {
+            return peer.score;
+        }
+        let elapsed = now.saturating_duration_since(peer.updated).as_secs_f64();
+        peer.score * 0.5f64.powf(elapsed / half_life)
+    }
+
+    /// Apply `event` to `peer_id`'s score. Returns the new score if this
+    /// event got the peer banned.
+    pub fn record(&mut self, peer_id: PeerId, event: ReputationEvent) -> Option<f64> {
+        let delta = match event {
+            ReputationEvent::MalformedMessage => MALFORMED_MESSAGE_PENALTY,
+            ReputationEvent::DeliveryFailed => DELIVERY_FAILED_PENALTY,
+            ReputationEvent::DeliverySucceeded { latency }
+                if latency <= self.config.fast_response =>
+            {
+                FAST_DELIVERY_REWARD
+            }
+            ReputationEvent::DeliverySucceeded { .. } => SLOW_DELIVERY_REWARD,
+        };
+
+        let now = Instant::now();
+        let current = self.peers.get(&peer_id).copied();
+        let score = current.map(|peer| self.decayed(&peer, now)).unwrap_or(0.0) + delta;
+        let was_banned = current.is_some_and(|peer| Self::banned_at(&peer, now));
+        let newly_banned = !was_banned && score <= self.config.ban_threshold;
+
+        self.peers.insert(
+            peer_id,
+            PeerReputation {
+                score: score.min(MAX_SCORE),
+                updated: now,
+                banned_until: if newly_banned {
+                    Some(now + self.config.ban_duration)
+                } else {
+                    current.and_then(|peer| peer.banned_until)
+                },
+            },
+        );
+        newly_banned.then_some(score)
+    }
+
+    fn banned_at(peer: &PeerReputation, now: Instant) -> bool {
+        peer.banned_until.is_some_and(|until| now < until)
+    }
+
+    /// Current score, 0.0 for peers we know nothing about
+    pub fn score(&self, peer_id: &PeerId) -> f64 {
+        self.peers
+            .get(peer_id)
+            .map(|peer| self.decayed(peer, Instant::now()))
+            .unwrap_or(0.0)
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .is_some_and(|peer| Self::banned_at(peer, Instant::now()))
+    }
+
+    pub fn is_deprioritized(&self, peer_id: &PeerId) -> bool {
+        self.score(peer_id) < self.config.deprioritize_threshold
+    }
+
+    /// Drop banned peers and order the rest by score, best first
+    pub fn rank(&self, peers: impl IntoIterator<Item = PeerId>) -> Vec<PeerId> {
+        let mut ranked: Vec<(PeerId, f64)> = peers
+            .into_iter()
+            .filter(|peer_id| !self.is_banned(peer_id))
+            .map(|peer_id| (peer_id, self.score(&peer_id)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.into_iter().map(|(peer_id, _)| peer_id).collect()
+    }
+
+    /// Current score of every tracked peer
+    pub fn scores(&self) -> HashMap<PeerId, f64> {
+        let now = Instant::now();
+        self.peers
+            .iter()
+            .map(|(peer_id, peer)| (*peer_id, self.decayed(peer, now)))
+            .collect()
+    }
+
+    pub fn banned_count(&self) -> usize {
+        let now = Instant::now();
+        self.peers
+            .values()
+            .filter(|peer| Self::banned_at(peer, now))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_malformed_messages_lead_to_ban() {
+        let mut tracker = ReputationTracker::new(ReputationConfig::default());
+        let peer = PeerId::random();
+
+        assert_eq!(
+            tracker.record(peer, ReputationEvent::MalformedMessage),
+            None
+        );
+        assert!(tracker.is_deprioritized(&peer));
+        assert_eq!(
+            tracker.record(peer, ReputationEvent::MalformedMessage),
+            None
+        );
+        let banned = tracker.record(peer, ReputationEvent::MalformedMessage);
+        assert!(banned.unwrap() <= -50.0);
+        assert!(tracker.is_banned(&peer));
+        assert_eq!(tracker.banned_count(), 1);
+
+        // Already banned: no second ban notification
+        assert_eq!(
+            tracker.record(peer, ReputationEvent::MalformedMessage),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rank_orders_by_score_and_drops_banned() {
+        let mut tracker = ReputationTracker::new(ReputationConfig {
+            ban_threshold: -10.0,
+            ..Default::default()
+        });
+        let (good, unknown, slow, banned) = (
+            PeerId::random(),
+            PeerId::random(),
+            PeerId::random(),
+            PeerId::random(),
+        );
+        tracker.record(
+            good,
+            ReputationEvent::DeliverySucceeded {
+                latency: Duration::from_millis(100),
+            },
+        );
+        tracker.record(slow, ReputationEvent::DeliveryFailed);
+        tracker.record(banned, ReputationEvent::MalformedMessage);
+
+        assert_eq!(
+            tracker.rank([banned, slow, unknown, good]),
+            vec![good, unknown, slow]
+        );
+    }
+
+    #[test]
+    fn test_scores_decay_towards_zero() {
+        let mut tracker = ReputationTracker::new(ReputationConfig {
+            decay_half_life: Duration::from_millis(10),
+            ..Default::default()
+        });
+        let peer = PeerId::random();
+        tracker.record(peer, ReputationEvent::DeliveryFailed);
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(tracker.score(&peer) > -0.5);
+    }
+}
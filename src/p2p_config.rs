@@ -1,16 +1,30 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use libp2p::{identity::Keypair, Multiaddr, PeerId};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::p2p::connections::ConnectionTransport;
+
+/// Environment variable selecting the P2P transport: `tcp`, `quic` or `both`
+pub const P2P_TRANSPORT_ENV: &str = "P2P_TRANSPORT";
 
 #[derive(Clone, Debug)]
 pub struct NodeConfig {
     pub keypair: Option<Keypair>,
     pub listen_addresses: Vec<Multiaddr>,
+    /// Transports to build; listen and dial addresses for other transports
+    /// are skipped
+    pub transport: TransportMode,
     pub external_addresses: Vec<Multiaddr>,
     pub bootstrap_peers: Vec<(PeerId, Multiaddr)>,
     pub max_connections: usize,
     pub max_connections_per_peer: usize,
+    /// Peers never evicted to make room for new connections, in addition
+    /// to bootstrap peers and rendezvous servers
+    pub protected_peers: Vec<PeerId>,
     pub connection_idle_timeout: Duration,
     pub capabilities: Vec<String>,
     pub enable_auto_reconnect: bool,
@@ -28,6 +42,14 @@ pub struct NodeConfig {
     pub peer_expiration_time: Duration,
     pub dht_bootstrap_interval: Duration,
     pub dht_republish_interval: Duration,
+    /// Lifetime of our DHT records and provider records on other peers
+    pub dht_record_ttl: Duration,
+    /// How often loaded models, price and load are announced over gossipsub;
+    /// zero disables announcements
+    pub model_announcement_interval: Duration,
+    /// On-chain host address included in model announcements
+    pub host_address: Option<String>,
+    pub reputation: ReputationConfig,
 }
 
 impl Default for NodeConfig {
@@ -38,10 +60,12 @@ impl Default for NodeConfig {
                 "/ip4/0.0.0.0/tcp/0".parse().unwrap(),
                 "/ip4/0.0.0.0/udp/0/quic-v1".parse().unwrap(),
             ],
+            transport: TransportMode::Both,
             external_addresses: vec![],
             bootstrap_peers: vec![],
             max_connections: 200,
             max_connections_per_peer: 5,
+            protected_peers: vec![],
             connection_idle_timeout: Duration::from_secs(120),
             capabilities: vec![],
             enable_auto_reconnect: false,
@@ -62,6 +86,78 @@ impl Default for NodeConfig {
             peer_expiration_time: Duration::from_secs(300),
             dht_bootstrap_interval: Duration::from_secs(300),
             dht_republish_interval: Duration::from_secs(3600),
+            dht_record_ttl: Duration::from_secs(36 * 3600),
+            model_announcement_interval: Duration::from_secs(30),
+            host_address: None,
+            reputation: ReputationConfig::default(),
+        }
+    }
+}
+
+impl NodeConfig {
+    /// Republish interval actually used, capped at a quarter of the record
+    /// TTL. A record published just after a republish tick waits almost two
+    /// intervals for its first refresh, so this keeps every record refreshed
+    /// well before it expires.
+    pub fn effective_dht_republish_interval(&self) -> Duration {
+        if self.dht_record_ttl > Duration::ZERO {
+            self.dht_republish_interval.min(self.dht_record_ttl / 4)
+        } else {
+            self.dht_republish_interval
+        }
+    }
+}
+
+/// Transports the node listens and dials on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransportMode {
+    TcpOnly,
+    QuicOnly,
+    #[default]
+    Both,
+}
+
+impl TransportMode {
+    pub fn tcp(&self) -> bool {
+        matches!(self, TransportMode::TcpOnly | TransportMode::Both)
+    }
+
+    pub fn quic(&self) -> bool {
+        matches!(self, TransportMode::QuicOnly | TransportMode::Both)
+    }
+
+    /// Whether `addr` can be listened on or dialed in this mode
+    pub fn supports(&self, addr: &Multiaddr) -> bool {
+        match ConnectionTransport::of(addr) {
+            ConnectionTransport::Tcp => self.tcp(),
+            ConnectionTransport::Quic => self.quic(),
+            ConnectionTransport::Other => false,
+        }
+    }
+}
+
+impl fmt::Display for TransportMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransportMode::TcpOnly => "tcp",
+            TransportMode::QuicOnly => "quic",
+            TransportMode::Both => "both",
+        })
+    }
+}
+
+impl FromStr for TransportMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "tcp" => Ok(TransportMode::TcpOnly),
+            "quic" => Ok(TransportMode::QuicOnly),
+            "both" => Ok(TransportMode::Both),
+            other => Err(format!(
+                "Unknown P2P transport: {} (expected tcp, quic or both)",
+                other
+            )),
         }
     }
 }
@@ -73,12 +169,51 @@ pub struct ConnectionLimits {
     pub idle_timeout: Duration,
 }
 
+/// Peer reputation scoring and ban thresholds
+#[derive(Clone, Debug)]
+pub struct ReputationConfig {
+    /// Peers scoring below this are tried after all others
+    pub deprioritize_threshold: f64,
+    /// Peers scoring at or below this are disconnected and ignored
+    pub ban_threshold: f64,
+    /// How long a ban lasts
+    pub ban_duration: Duration,
+    /// Time for a score to decay halfway back to zero
+    pub decay_half_life: Duration,
+    /// Responses faster than this earn the full latency bonus
+    pub fast_response: Duration,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            deprioritize_threshold: -10.0,
+            ban_threshold: -50.0,
+            ban_duration: Duration::from_secs(3600),
+            decay_half_life: Duration::from_secs(1800),
+            fast_response: Duration::from_secs(5),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct NodeMetrics {
     pub connected_peers: usize,
     pub bandwidth_in: u64,
     pub bandwidth_out: u64,
     pub uptime: Duration,
+    /// Open connections across all peers
+    pub connections: usize,
+    /// Connected peers that are protected from eviction
+    pub protected_peers: usize,
+    /// Peers disconnected to make room for new connections
+    pub evicted_peers: u64,
+    /// Connections closed because no peer could be evicted
+    pub rejected_connections: u64,
+    /// Current reputation of each scored peer
+    pub peer_scores: HashMap<PeerId, f64>,
+    /// Peers currently banned for low reputation
+    pub banned_peers: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -86,6 +221,28 @@ pub struct DhtRoutingTableHealth {
     pub num_peers: usize,
     pub num_buckets: usize,
     pub pending_queries: usize,
+    /// Time until the next scheduled republish; `None` before the first
+    /// republish or when republishing is disabled
+    pub time_to_next_republish: Option<Duration>,
+    pub last_republish: Option<RepublishOutcome>,
+}
+
+/// Result of the most recent DHT republish round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepublishOutcome {
+    pub completed_at: Instant,
+    /// Stored records re-put
+    pub records: usize,
+    /// Provider records (capabilities, model ids) re-announced
+    pub providers: usize,
+    /// Republishes that could not be started or whose query later failed
+    pub failed: usize,
+}
+
+impl RepublishOutcome {
+    pub fn is_success(&self) -> bool {
+        self.failed == 0
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -20,6 +20,7 @@ pub enum PaymentError {
     BelowMinimumThreshold,
     WithdrawalFailed,
     InsufficientBalance,
+    InvalidSplitConfig(String),
     ContractError(String),
     Other(String),
 }
@@ -32,6 +33,7 @@ impl std::fmt::Display for PaymentError {
             PaymentError::BelowMinimumThreshold => write!(f, "Below minimum threshold"),
             PaymentError::WithdrawalFailed => write!(f, "Withdrawal failed"),
             PaymentError::InsufficientBalance => write!(f, "Insufficient balance"),
+            PaymentError::InvalidSplitConfig(e) => write!(f, "Invalid split config: {}", e),
             PaymentError::ContractError(e) => write!(f, "Contract error: {}", e),
             PaymentError::Other(e) => write!(f, "Other error: {}", e),
         }
@@ -50,9 +52,22 @@ impl From<anyhow::Error> for PaymentError {
 pub enum PaymentStatus {
     Pending,
     Claimed,
+    Completed,
     Failed,
 }
 
+/// Event emitted by the escrow contract when a job's payment is released
+pub const PAYMENT_RELEASED_EVENT: &str = "PaymentReleased";
+
+/// Outcome of an escrow release transaction as seen on-chain
+#[derive(Debug, Clone)]
+pub struct ReleaseReceipt {
+    pub tx_hash: H256,
+    pub success: bool,
+    pub revert_reason: Option<String>,
+    pub events: Vec<PaymentEvent>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PaymentEvent {
     pub job_id: H256,
@@ -99,39 +114,169 @@ impl From<NodeConfig> for PaymentConfig {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct PaymentSplitter {
-    pub host_percentage: u16,     // 8500 = 85%
-    pub treasury_percentage: u16, // 1000 = 10%
-    pub stakers_percentage: u16,  // 500 = 5%
+/// Shares are expressed in basis points; all shares sum to this
+pub const TOTAL_BASIS_POINTS: u16 = 10_000;
+
+pub const HOST_RECIPIENT: &str = "host";
+pub const TREASURY_RECIPIENT: &str = "treasury";
+pub const STAKERS_RECIPIENT: &str = "stakers";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitRecipient {
+    pub name: String,
+    pub address: Option<Address>,
+    pub basis_points: u16, // 8500 = 85%
 }
 
-impl PaymentSplitter {
-    pub fn new(host: u16, treasury: u16, stakers: u16) -> Self {
-        assert_eq!(
-            host + treasury + stakers,
-            10000,
-            "Percentages must sum to 10000"
-        );
+impl SplitRecipient {
+    pub fn new(name: impl Into<String>, basis_points: u16) -> Self {
         Self {
-            host_percentage: host,
-            treasury_percentage: treasury,
-            stakers_percentage: stakers,
+            name: name.into(),
+            address: None,
+            basis_points,
         }
     }
 
-    pub fn calculate_splits(&self, amount: U256) -> (U256, U256, U256) {
-        let host_share = amount * U256::from(self.host_percentage) / U256::from(10000);
-        let treasury_share = amount * U256::from(self.treasury_percentage) / U256::from(10000);
-        let stakers_share = amount * U256::from(self.stakers_percentage) / U256::from(10000);
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+}
+
+/// Named recipients and their shares of each payment
+///
+/// The first recipient is the primary and receives any rounding remainder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitConfig {
+    recipients: Vec<SplitRecipient>,
+}
+
+impl SplitConfig {
+    pub fn new(recipients: Vec<SplitRecipient>) -> Result<Self, PaymentError> {
+        if recipients.is_empty() {
+            return Err(PaymentError::InvalidSplitConfig(
+                "at least one recipient is required".to_string(),
+            ));
+        }
+
+        let mut names = std::collections::HashSet::new();
+        for recipient in &recipients {
+            if !names.insert(recipient.name.as_str()) {
+                return Err(PaymentError::InvalidSplitConfig(format!(
+                    "duplicate recipient '{}'",
+                    recipient.name
+                )));
+            }
+        }
+
+        let total: u32 = recipients.iter().map(|r| u32::from(r.basis_points)).sum();
+        if total != u32::from(TOTAL_BASIS_POINTS) {
+            return Err(PaymentError::InvalidSplitConfig(format!(
+                "shares sum to {} basis points, expected {}",
+                total, TOTAL_BASIS_POINTS
+            )));
+        }
+
+        Ok(Self { recipients })
+    }
+
+    /// Recipient that absorbs rounding remainders
+    pub fn primary(&self) -> &SplitRecipient {
+        &self.recipients[0]
+    }
 
-        (host_share, treasury_share, stakers_share)
+    pub fn recipients(&self) -> &[SplitRecipient] {
+        &self.recipients
     }
 }
 
-impl Default for PaymentSplitter {
+impl Default for SplitConfig {
     fn default() -> Self {
-        Self::new(8500, 1000, 500)
+        Self {
+            recipients: vec![
+                SplitRecipient::new(HOST_RECIPIENT, 8500),
+                SplitRecipient::new(TREASURY_RECIPIENT, 1000),
+                SplitRecipient::new(STAKERS_RECIPIENT, 500),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PaymentSplitter {
+    config: SplitConfig,
+}
+
+impl PaymentSplitter {
+    pub fn new(host: u16, treasury: u16, stakers: u16) -> Self {
+        let config = SplitConfig::new(vec![
+            SplitRecipient::new(HOST_RECIPIENT, host),
+            SplitRecipient::new(TREASURY_RECIPIENT, treasury),
+            SplitRecipient::new(STAKERS_RECIPIENT, stakers),
+        ])
+        .expect("Percentages must sum to 10000");
+        Self::from_config(config)
+    }
+
+    pub fn from_config(config: SplitConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &SplitConfig {
+        &self.config
+    }
+
+    /// Per-recipient amounts, in config order, summing exactly to `amount`
+    ///
+    /// Each share is rounded down and the remainder goes to the primary
+    /// recipient, so no wei is lost.
+    pub fn split(&self, amount: U256) -> Vec<(String, U256)> {
+        let total = U256::from(TOTAL_BASIS_POINTS);
+        // Split the multiplication so huge amounts can't overflow
+        let (whole, rest) = (amount / total, amount % total);
+
+        let mut shares: Vec<(String, U256)> = self
+            .config
+            .recipients
+            .iter()
+            .map(|r| {
+                let bps = U256::from(r.basis_points);
+                (r.name.clone(), whole * bps + rest * bps / total)
+            })
+            .collect();
+
+        let allocated = shares
+            .iter()
+            .fold(U256::zero(), |sum, (_, share)| sum + *share);
+        shares[0].1 += amount - allocated;
+        shares
+    }
+
+    /// Amount going to the named recipient, zero if it isn't configured
+    pub fn share_of(&self, name: &str, amount: U256) -> U256 {
+        self.split(amount)
+            .into_iter()
+            .find(|(recipient, _)| recipient == name)
+            .map(|(_, share)| share)
+            .unwrap_or_default()
+    }
+
+    /// Host, treasury and stakers shares
+    pub fn calculate_splits(&self, amount: U256) -> (U256, U256, U256) {
+        let shares = self.split(amount);
+        let get = |name: &str| {
+            shares
+                .iter()
+                .find(|(recipient, _)| recipient == name)
+                .map(|(_, share)| *share)
+                .unwrap_or_default()
+        };
+
+        (
+            get(HOST_RECIPIENT),
+            get(TREASURY_RECIPIENT),
+            get(STAKERS_RECIPIENT),
+        )
     }
 }
 
@@ -148,6 +293,8 @@ pub trait PaymentSystemTrait: Send + Sync {
     async fn get_node_balance(&self, node: Address) -> U256;
     async fn estimate_gas(&self, job_id: H256) -> Result<U256>;
     async fn get_gas_price(&self) -> Result<U256>;
+    /// Receipt of a release transaction, `None` while it is unmined
+    async fn get_release_receipt(&self, tx_hash: H256) -> Result<Option<ReleaseReceipt>>;
     async fn withdraw(
         &self,
         node: Address,
@@ -156,7 +303,130 @@ pub trait PaymentSystemTrait: Send + Sync {
     ) -> Result<H256, PaymentError>;
 }
 
-pub struct EscrowManager;
+#[derive(Debug, Clone)]
+pub struct EscrowPayment {
+    pub job_id: H256,
+    pub tx_hash: H256,
+    pub amount: U256,
+    pub status: PaymentStatus,
+    pub failure_reason: Option<String>,
+}
+
+/// Tracks escrow releases until their receipts confirm them
+///
+/// A payment only becomes `Completed` once the release receipt succeeded
+/// and carries the `PaymentReleased` event for the job.
+#[derive(Clone)]
+pub struct EscrowManager {
+    payment_system: Arc<dyn PaymentSystemTrait>,
+    payments: Arc<RwLock<HashMap<H256, EscrowPayment>>>,
+}
+
+impl EscrowManager {
+    pub fn new(payment_system: Arc<dyn PaymentSystemTrait>) -> Self {
+        Self {
+            payment_system,
+            payments: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Record a sent release transaction as pending
+    pub async fn record_release(&self, job_id: H256, tx_hash: H256, amount: U256) {
+        self.payments.write().await.insert(
+            job_id,
+            EscrowPayment {
+                job_id,
+                tx_hash,
+                amount,
+                status: PaymentStatus::Pending,
+                failure_reason: None,
+            },
+        );
+    }
+
+    /// Check the release receipt and settle the payment status
+    pub async fn verify_release(&self, job_id: H256) -> Result<PaymentStatus, PaymentError> {
+        let tx_hash = self
+            .payments
+            .read()
+            .await
+            .get(&job_id)
+            .map(|p| p.tx_hash)
+            .ok_or_else(|| PaymentError::Other(format!("No release recorded for {}", job_id)))?;
+
+        let receipt = match self.payment_system.get_release_receipt(tx_hash).await? {
+            Some(receipt) => receipt,
+            None => return Ok(PaymentStatus::Pending),
+        };
+
+        let (status, reason) = if !receipt.success {
+            let reason = receipt
+                .revert_reason
+                .unwrap_or_else(|| "Release transaction reverted".to_string());
+            (PaymentStatus::Failed, Some(reason))
+        } else if !receipt
+            .events
+            .iter()
+            .any(|e| e.job_id == job_id && e.event_type == PAYMENT_RELEASED_EVENT)
+        {
+            (
+                PaymentStatus::Failed,
+                Some(format!(
+                    "{} event missing from receipt",
+                    PAYMENT_RELEASED_EVENT
+                )),
+            )
+        } else {
+            (PaymentStatus::Completed, None)
+        };
+
+        if let Some(payment) = self.payments.write().await.get_mut(&job_id) {
+            payment.status = status;
+            payment.failure_reason = reason.clone();
+        }
+
+        match reason {
+            Some(reason) => {
+                warn!("Escrow release for job {} failed: {}", job_id, reason);
+                Err(PaymentError::ContractError(reason))
+            }
+            None => Ok(status),
+        }
+    }
+
+    /// Re-check every payment still pending and return those that settled
+    pub async fn reconcile_pending(&self) -> Vec<(H256, PaymentStatus)> {
+        let pending: Vec<H256> = self
+            .payments
+            .read()
+            .await
+            .values()
+            .filter(|p| p.status == PaymentStatus::Pending)
+            .map(|p| p.job_id)
+            .collect();
+
+        let mut settled = Vec::new();
+        for job_id in pending {
+            match self.verify_release(job_id).await {
+                Ok(PaymentStatus::Pending) => {}
+                Ok(status) => settled.push((job_id, status)),
+                Err(PaymentError::ContractError(_)) => {
+                    settled.push((job_id, PaymentStatus::Failed))
+                }
+                Err(e) => warn!("Failed to reconcile payment for job {}: {}", job_id, e),
+            }
+        }
+        settled
+    }
+
+    pub async fn get_payment(&self, job_id: H256) -> Option<EscrowPayment> {
+        self.payments.read().await.get(&job_id).cloned()
+    }
+
+    pub async fn get_status(&self, job_id: H256) -> Option<PaymentStatus> {
+        self.payments.read().await.get(&job_id).map(|p| p.status)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct PaymentStatistics {
@@ -172,6 +442,7 @@ pub struct PaymentClaimer {
     config: PaymentConfig,
     payment_system: Arc<dyn PaymentSystemTrait>,
     payment_splitter: PaymentSplitter,
+    escrow: EscrowManager,
     accumulated_jobs: Arc<RwLock<Vec<H256>>>,
     accumulated_amount: Arc<RwLock<U256>>,
     payment_stats: Arc<RwLock<PaymentStatistics>>,
@@ -189,6 +460,7 @@ impl PaymentClaimer {
 
         Self {
             config,
+            escrow: EscrowManager::new(payment_system.clone()),
             payment_system,
             payment_splitter: PaymentSplitter::default(),
             accumulated_jobs: Arc::new(RwLock::new(Vec::new())),
@@ -205,6 +477,12 @@ impl PaymentClaimer {
         }
     }
 
+    /// Use custom payment split shares instead of the default 85/10/5
+    pub fn with_split_config(mut self, config: SplitConfig) -> Self {
+        self.payment_splitter = PaymentSplitter::from_config(config);
+        self
+    }
+
     pub async fn claim_payment(&self, job_id: H256) -> Result<(U256, H256), PaymentError> {
         // Check if job is payable
         if !self.payment_system.is_job_payable(job_id).await {
@@ -242,25 +520,51 @@ impl PaymentClaimer {
             .claim_payment(job_id, self.config.node_address)
             .await?;
 
-        // Update statistics
+        // Only count the payment once the release is confirmed on-chain
+        self.escrow
+            .record_release(job_id, tx_hash, amount_received)
+            .await;
+        if self.escrow.verify_release(job_id).await? == PaymentStatus::Completed {
+            self.finalize_payment(job_id, amount_received).await;
+        }
+
+        Ok((amount_received, tx_hash))
+    }
+
+    pub fn escrow(&self) -> &EscrowManager {
+        &self.escrow
+    }
+
+    /// Settle payments whose release receipt wasn't available at claim time
+    pub async fn reconcile_pending_payments(&self) -> Vec<(H256, PaymentStatus)> {
+        let settled = self.escrow.reconcile_pending().await;
+        for (job_id, status) in &settled {
+            if *status != PaymentStatus::Completed {
+                continue;
+            }
+            if let Some(payment) = self.escrow.get_payment(*job_id).await {
+                self.finalize_payment(*job_id, payment.amount).await;
+            }
+        }
+        settled
+    }
+
+    async fn finalize_payment(&self, job_id: H256, amount: U256) {
         if self.config.track_payment_stats {
-            self.update_statistics(amount_received).await;
+            self.update_statistics(amount).await;
         }
 
-        // Emit event
         self.emit_event(PaymentEvent {
             job_id,
             node_address: self.config.node_address,
             event_type: "PaymentClaimed".to_string(),
-            amount: amount_received,
+            amount,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs(),
         })
         .await;
-
-        Ok((amount_received, tx_hash))
     }
 
     pub async fn claim_batch(&self, job_ids: &[H256]) -> Vec<Result<(U256, H256), PaymentError>> {
@@ -463,6 +767,26 @@ mod tests {
         completed_jobs: Arc<RwLock<Vec<H256>>>,
         paid_jobs: Arc<RwLock<Vec<H256>>>,
         node_balances: Arc<RwLock<HashMap<Address, U256>>>,
+        receipts: Arc<RwLock<HashMap<H256, ReleaseReceipt>>>,
+        revert_releases: bool,
+    }
+
+    impl MockPaymentSystem {
+        fn new(revert_releases: bool) -> Self {
+            Self {
+                escrow_balances: Arc::new(RwLock::new(HashMap::new())),
+                completed_jobs: Arc::new(RwLock::new(Vec::new())),
+                paid_jobs: Arc::new(RwLock::new(Vec::new())),
+                node_balances: Arc::new(RwLock::new(HashMap::new())),
+                receipts: Arc::new(RwLock::new(HashMap::new())),
+                revert_releases,
+            }
+        }
+
+        async fn add_completed_job(&self, job_id: H256, amount: U256) {
+            self.escrow_balances.write().await.insert(job_id, amount);
+            self.completed_jobs.write().await.push(job_id);
+        }
     }
 
     #[async_trait::async_trait]
@@ -499,7 +823,28 @@ mod tests {
 
             self.paid_jobs.write().await.push(job_id);
 
-            Ok((host_share, H256::random()))
+            let tx_hash = H256::random();
+            let receipt = ReleaseReceipt {
+                tx_hash,
+                success: !self.revert_releases,
+                revert_reason: self
+                    .revert_releases
+                    .then(|| "execution reverted: escrow locked".to_string()),
+                events: vec![PaymentEvent {
+                    job_id,
+                    node_address,
+                    event_type: PAYMENT_RELEASED_EVENT.to_string(),
+                    amount: host_share,
+                    timestamp: 0,
+                }],
+            };
+            self.receipts.write().await.insert(tx_hash, receipt);
+
+            Ok((host_share, tx_hash))
+        }
+
+        async fn get_release_receipt(&self, tx_hash: H256) -> Result<Option<ReleaseReceipt>> {
+            Ok(self.receipts.read().await.get(&tx_hash).cloned())
         }
 
         async fn get_node_balance(&self, node: Address) -> U256 {
@@ -536,4 +881,170 @@ mod tests {
             Ok(H256::random())
         }
     }
+
+    #[test]
+    fn test_split_config_must_sum_to_total() {
+        let err = SplitConfig::new(vec![
+            SplitRecipient::new(HOST_RECIPIENT, 9000),
+            SplitRecipient::new(TREASURY_RECIPIENT, 500),
+        ])
+        .unwrap_err();
+        assert!(matches!(err, PaymentError::InvalidSplitConfig(_)));
+
+        assert!(SplitConfig::new(vec![]).is_err());
+        assert!(SplitConfig::new(vec![
+            SplitRecipient::new(HOST_RECIPIENT, 5000),
+            SplitRecipient::new(HOST_RECIPIENT, 5000),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_remainder_goes_to_primary() {
+        let splitter = PaymentSplitter::default();
+        // 85/10/5 of 19 wei floors to 16/1/0; the 2 wei left go to the host
+        assert_eq!(
+            splitter.calculate_splits(U256::from(19)),
+            (U256::from(18), U256::from(1), U256::zero())
+        );
+    }
+
+    #[test]
+    fn test_split_sums_to_input_for_odd_amounts() {
+        use rand::Rng;
+
+        let configs = vec![
+            SplitConfig::default(),
+            SplitConfig::new(vec![
+                SplitRecipient::new("a", 3333),
+                SplitRecipient::new("b", 3333),
+                SplitRecipient::new("c", 3334),
+            ])
+            .unwrap(),
+            SplitConfig::new(vec![
+                SplitRecipient::new("primary", 1),
+                SplitRecipient::new("rest", 9999),
+            ])
+            .unwrap(),
+            SplitConfig::new(vec![SplitRecipient::new("solo", TOTAL_BASIS_POINTS)]).unwrap(),
+        ];
+
+        let mut rng = rand::thread_rng();
+        for config in configs {
+            let splitter = PaymentSplitter::from_config(config);
+            let mut amounts = vec![U256::zero(), U256::one(), U256::from(9_999), U256::MAX];
+            for _ in 0..1000 {
+                amounts.push(U256::from(rng.gen::<u64>() | 1));
+                amounts.push(U256(rng.gen::<[u64; 4]>()));
+            }
+
+            for amount in amounts {
+                let total = splitter
+                    .split(amount)
+                    .iter()
+                    .fold(U256::zero(), |sum, (_, share)| sum + *share);
+                assert_eq!(total, amount, "split of {} lost wei", amount);
+            }
+        }
+    }
+
+    fn test_config() -> PaymentConfig {
+        PaymentConfig {
+            node_address: Address::random(),
+            batch_claim_size: 10,
+            accept_fab_payments: true,
+            max_gas_price: U256::from(100_000_000_000u64),
+            min_claim_amount: U256::zero(),
+            enable_payment_accumulation: false,
+            accumulation_threshold: U256::zero(),
+            payment_retry_attempts: 1,
+            payment_retry_delay: Duration::from_millis(10),
+            withdrawal_address: None,
+            min_withdrawal_amount: U256::zero(),
+            track_payment_stats: true,
+            max_concurrent_claims: 4,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_completed_only_after_successful_release() {
+        let system = Arc::new(MockPaymentSystem::new(false));
+        let job_id = H256::random();
+        system.add_completed_job(job_id, U256::exp10(18)).await;
+
+        let claimer = PaymentClaimer::new(test_config(), system.clone());
+        claimer.claim_payment(job_id).await.unwrap();
+
+        assert_eq!(
+            claimer.escrow().get_status(job_id).await,
+            Some(PaymentStatus::Completed)
+        );
+        assert_eq!(claimer.get_payment_statistics().await.total_jobs_paid, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_release_marks_failed() {
+        let system = Arc::new(MockPaymentSystem::new(true));
+        let job_id = H256::random();
+        system.add_completed_job(job_id, U256::exp10(18)).await;
+
+        let claimer = PaymentClaimer::new(test_config(), system.clone());
+        let err = claimer.claim_payment(job_id).await.unwrap_err();
+        assert!(matches!(err, PaymentError::ContractError(ref r) if r.contains("escrow locked")));
+
+        let payment = claimer.escrow().get_payment(job_id).await.unwrap();
+        assert_eq!(payment.status, PaymentStatus::Failed);
+        assert!(payment.failure_reason.unwrap().contains("escrow locked"));
+        assert_eq!(claimer.get_payment_statistics().await.total_jobs_paid, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_settles_pending_releases() {
+        let system = Arc::new(MockPaymentSystem::new(false));
+        let escrow = EscrowManager::new(system.clone());
+
+        let confirmed = H256::random();
+        let missing_event = H256::random();
+        let unmined = H256::random();
+        for (job_id, events) in [(confirmed, true), (missing_event, false)] {
+            let tx_hash = H256::random();
+            system.receipts.write().await.insert(
+                tx_hash,
+                ReleaseReceipt {
+                    tx_hash,
+                    success: true,
+                    revert_reason: None,
+                    events: if events {
+                        vec![PaymentEvent {
+                            job_id,
+                            node_address: Address::zero(),
+                            event_type: PAYMENT_RELEASED_EVENT.to_string(),
+                            amount: U256::one(),
+                            timestamp: 0,
+                        }]
+                    } else {
+                        Vec::new()
+                    },
+                },
+            );
+            escrow.record_release(job_id, tx_hash, U256::one()).await;
+        }
+        escrow
+            .record_release(unmined, H256::random(), U256::one())
+            .await;
+
+        let mut settled = escrow.reconcile_pending().await;
+        settled.sort_by_key(|(job_id, _)| *job_id == missing_event);
+        assert_eq!(
+            settled,
+            vec![
+                (confirmed, PaymentStatus::Completed),
+                (missing_event, PaymentStatus::Failed)
+            ]
+        );
+        assert_eq!(
+            escrow.get_status(unmined).await,
+            Some(PaymentStatus::Pending)
+        );
+    }
 }
@@ -5,7 +5,9 @@ use chrono::{DateTime, Utc};
 use ethers::types::{Address, H256, U256};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::RwLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,12 +71,145 @@ pub struct FeeStats {
     pub distribution_count: u64,
 }
 
+/// A fee transfer as observed on-chain
+#[derive(Debug, Clone)]
+pub struct OnChainTransfer {
+    pub recipient: Address,
+    pub amount: U256,
+    pub block_number: u64,
+    pub success: bool,
+}
+
+/// One distribution sent on-chain, as recorded in the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub recipient: Address,
+    pub role: Option<RecipientRole>,
+    pub amount: U256,
+    pub tx_hash: H256,
+    /// Block the transfer was mined in, if known when recorded
+    pub block_number: Option<u64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Append-only record of fee distributions
+///
+/// Entries are never modified or removed. When opened with a path, each
+/// entry is also appended to the file as a JSON line.
+pub struct FeeAuditLog {
+    entries: RwLock<Vec<AuditEntry>>,
+    path: Option<PathBuf>,
+}
+
+impl FeeAuditLog {
+    pub fn in_memory() -> Self {
+        Self {
+            entries: RwLock::new(Vec::new()),
+            path: None,
+        }
+    }
+
+    /// Open a log file, loading existing entries
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let entries = match tokio::fs::read_to_string(&path).await {
+            Ok(data) => data
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<std::result::Result<Vec<AuditEntry>, _>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            entries: RwLock::new(entries),
+            path: Some(path),
+        })
+    }
+
+    pub async fn append(
+        &self,
+        recipient: Address,
+        role: Option<RecipientRole>,
+        amount: U256,
+        tx_hash: H256,
+        block_number: Option<u64>,
+    ) -> Result<AuditEntry> {
+        let mut entries = self.entries.write().await;
+        let entry = AuditEntry {
+            sequence: entries.len() as u64,
+            recipient,
+            role,
+            amount,
+            tx_hash,
+            block_number,
+            recorded_at: Utc::now(),
+        };
+
+        if let Some(path) = &self.path {
+            let mut line = serde_json::to_vec(&entry)?;
+            line.push(b'\n');
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await?;
+            file.write_all(&line).await?;
+            file.sync_data().await?;
+        }
+
+        entries.push(entry.clone());
+        Ok(entry)
+    }
+
+    pub async fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.read().await.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Discrepancy {
+    /// Transfer not found on-chain yet
+    Unconfirmed { sequence: u64, tx_hash: H256 },
+    /// Transfer was mined but reverted
+    Reverted { sequence: u64, tx_hash: H256 },
+    /// Amount or recipient on-chain differs from the log
+    AmountMismatch {
+        sequence: u64,
+        tx_hash: H256,
+        logged: U256,
+        on_chain: U256,
+    },
+    /// Computed distributions don't match confirmed transfers
+    TotalMismatch { computed: U256, confirmed: U256 },
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconciliationReport {
+    /// `FeeStats::total_distributed` at reconciliation time
+    pub computed_distributed: U256,
+    pub logged_total: U256,
+    pub confirmed_total: U256,
+    pub discrepancies: Vec<Discrepancy>,
+    pub reconciled_at: DateTime<Utc>,
+}
+
+impl ReconciliationReport {
+    pub fn is_balanced(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
 pub struct FeeDistributor {
     config: FeeDistributionConfig,
     contract_client: Arc<dyn ContractClient>,
     recipients: Arc<RwLock<HashMap<RecipientRole, FeeRecipient>>>,
     fee_allocations: Arc<RwLock<Vec<FeeAllocation>>>,
     pending_fees: Arc<RwLock<HashMap<Address, U256>>>,
+    burned_total: Arc<RwLock<U256>>,
+    audit_log: Arc<FeeAuditLog>,
 }
 
 #[async_trait::async_trait]
@@ -86,6 +221,13 @@ pub trait ContractClient: Send + Sync {
     async fn burn_tokens(&self, amount: U256) -> Result<H256>;
 
     async fn get_fee_balance(&self) -> Result<U256>;
+
+    /// Transfer to `recipient` made by `tx_hash`, `None` while unmined
+    async fn get_transfer(
+        &self,
+        tx_hash: H256,
+        recipient: Address,
+    ) -> Result<Option<OnChainTransfer>>;
 }
 
 impl FeeDistributor {
@@ -96,9 +238,20 @@ impl FeeDistributor {
             recipients: Arc::new(RwLock::new(HashMap::new())),
             fee_allocations: Arc::new(RwLock::new(Vec::new())),
             pending_fees: Arc::new(RwLock::new(HashMap::new())),
+            burned_total: Arc::new(RwLock::new(U256::zero())),
+            audit_log: Arc::new(FeeAuditLog::in_memory()),
         }
     }
 
+    pub fn with_audit_log(mut self, audit_log: FeeAuditLog) -> Self {
+        self.audit_log = Arc::new(audit_log);
+        self
+    }
+
+    pub fn audit_log(&self) -> Arc<FeeAuditLog> {
+        self.audit_log.clone()
+    }
+
     pub async fn allocate_fee(
         &self,
         job_id: H256,
@@ -163,29 +316,133 @@ impl FeeDistributor {
                 .contract_client
                 .batch_distribute(distributions.clone())
                 .await?;
-            tx_hashes.extend(hashes);
 
             // Clear distributed amounts
-            for (address, _) in distributions {
+            for (i, (address, amount)) in distributions.into_iter().enumerate() {
                 pending.remove(&address);
+
+                // One hash per transfer, or a single hash for the whole batch
+                if let Some(tx_hash) = hashes.get(i).or(hashes.first()) {
+                    self.record_distribution(address, amount, *tx_hash).await?;
+                }
             }
+            tx_hashes.extend(hashes);
         }
 
-        // Handle burn
+        // Handle burn; only what hasn't been burned by earlier runs
         let allocations = self.fee_allocations.read().await;
         let total_burn = allocations
             .iter()
             .map(|a| a.burn_amount)
             .fold(U256::zero(), |acc, amt| acc + amt);
-
-        if total_burn > U256::zero() {
-            let burn_hash = self.contract_client.burn_tokens(total_burn).await?;
+        drop(allocations);
+
+        let mut burned = self.burned_total.write().await;
+        let unburned = total_burn.saturating_sub(*burned);
+        if unburned > U256::zero() {
+            let burn_hash = self.contract_client.burn_tokens(unburned).await?;
+            *burned += unburned;
+            self.record_distribution(Address::zero(), unburned, burn_hash)
+                .await?;
             tx_hashes.push(burn_hash);
         }
 
         Ok(tx_hashes)
     }
 
+    async fn record_distribution(
+        &self,
+        recipient: Address,
+        amount: U256,
+        tx_hash: H256,
+    ) -> Result<()> {
+        let role = if recipient == Address::zero() {
+            Some(RecipientRole::BurnAddress)
+        } else {
+            self.recipients
+                .read()
+                .await
+                .values()
+                .find(|r| r.address == recipient)
+                .map(|r| r.role.clone())
+        };
+
+        // Best effort: the block may not be known until the tx is mined
+        let block_number = match self.contract_client.get_transfer(tx_hash, recipient).await {
+            Ok(transfer) => transfer.map(|t| t.block_number),
+            Err(_) => None,
+        };
+
+        self.audit_log
+            .append(recipient, role, amount, tx_hash, block_number)
+            .await?;
+        Ok(())
+    }
+
+    /// Compare computed distributions against confirmed on-chain transfers
+    ///
+    /// Every audit entry is checked on-chain; missing, reverted or mismatched
+    /// transfers are flagged, as is any gap between `FeeStats::total_distributed`
+    /// and the confirmed total.
+    pub async fn reconcile(&self) -> Result<ReconciliationReport> {
+        let stats = self.get_fee_stats().await?;
+        let entries = self.audit_log.entries().await;
+
+        let mut logged_total = U256::zero();
+        let mut confirmed_total = U256::zero();
+        let mut discrepancies = Vec::new();
+
+        for entry in &entries {
+            logged_total += entry.amount;
+
+            match self
+                .contract_client
+                .get_transfer(entry.tx_hash, entry.recipient)
+                .await?
+            {
+                None => discrepancies.push(Discrepancy::Unconfirmed {
+                    sequence: entry.sequence,
+                    tx_hash: entry.tx_hash,
+                }),
+                Some(transfer) if !transfer.success => discrepancies.push(Discrepancy::Reverted {
+                    sequence: entry.sequence,
+                    tx_hash: entry.tx_hash,
+                }),
+                Some(transfer) => {
+                    let on_chain = if transfer.recipient == entry.recipient {
+                        transfer.amount
+                    } else {
+                        U256::zero()
+                    };
+                    confirmed_total += on_chain;
+                    if on_chain != entry.amount {
+                        discrepancies.push(Discrepancy::AmountMismatch {
+                            sequence: entry.sequence,
+                            tx_hash: entry.tx_hash,
+                            logged: entry.amount,
+                            on_chain,
+                        });
+                    }
+                }
+            }
+        }
+
+        if confirmed_total != stats.total_distributed {
+            discrepancies.push(Discrepancy::TotalMismatch {
+                computed: stats.total_distributed,
+                confirmed: confirmed_total,
+            });
+        }
+
+        Ok(ReconciliationReport {
+            computed_distributed: stats.total_distributed,
+            logged_total,
+            confirmed_total,
+            discrepancies,
+            reconciled_at: Utc::now(),
+        })
+    }
+
     pub async fn claim_fees(&self, recipient: Address) -> Result<H256> {
         let mut pending = self.pending_fees.write().await;
         let amount = pending.get(&recipient).cloned().unwrap_or_default();
@@ -199,6 +456,8 @@ impl FeeDistributor {
             .distribute_fee(recipient, amount)
             .await?;
         pending.remove(&recipient);
+        drop(pending);
+        self.record_distribution(recipient, amount, tx_hash).await?;
 
         // Update recipient's last claim time
         let mut recipients = self.recipients.write().await;
@@ -372,6 +631,7 @@ mod tests {
         distributed_fees: Arc<RwLock<Vec<(Address, U256)>>>,
         burned_amount: Arc<RwLock<U256>>,
         fee_balance: Arc<RwLock<U256>>,
+        transfers: Arc<RwLock<HashMap<H256, OnChainTransfer>>>,
     }
 
     impl MockContractClient {
@@ -380,8 +640,23 @@ mod tests {
                 distributed_fees: Arc::new(RwLock::new(Vec::new())),
                 burned_amount: Arc::new(RwLock::new(U256::zero())),
                 fee_balance: Arc::new(RwLock::new(U256::zero())),
+                transfers: Arc::new(RwLock::new(HashMap::new())),
             }
         }
+
+        async fn mine(&self, recipient: Address, amount: U256) -> H256 {
+            let tx_hash = H256::random();
+            self.transfers.write().await.insert(
+                tx_hash,
+                OnChainTransfer {
+                    recipient,
+                    amount,
+                    block_number: 100,
+                    success: true,
+                },
+            );
+            tx_hash
+        }
     }
 
     #[async_trait::async_trait]
@@ -391,7 +666,7 @@ mod tests {
                 .write()
                 .await
                 .push((recipient, amount));
-            Ok(H256::random())
+            Ok(self.mine(recipient, amount).await)
         }
 
         async fn batch_distribute(&self, distributions: Vec<(Address, U256)>) -> Result<Vec<H256>> {
@@ -404,12 +679,20 @@ mod tests {
 
         async fn burn_tokens(&self, amount: U256) -> Result<H256> {
             *self.burned_amount.write().await += amount;
-            Ok(H256::random())
+            Ok(self.mine(Address::zero(), amount).await)
         }
 
         async fn get_fee_balance(&self) -> Result<U256> {
             Ok(*self.fee_balance.read().await)
         }
+
+        async fn get_transfer(
+            &self,
+            tx_hash: H256,
+            _recipient: Address,
+        ) -> Result<Option<OnChainTransfer>> {
+            Ok(self.transfers.read().await.get(&tx_hash).cloned())
+        }
     }
 
     #[tokio::test]
@@ -422,4 +705,101 @@ mod tests {
         assert_eq!(distributor.config.referrer_percentage, 20);
         assert_eq!(distributor.config.burn_percentage, 10);
     }
+
+    async fn distributor_with_fees(client: Arc<MockContractClient>) -> FeeDistributor {
+        let distributor = FeeDistributor::new(FeeDistributionConfig::default(), client);
+        distributor
+            .register_recipient(RecipientRole::MarketplaceOperator, Address::random(), 40)
+            .await
+            .unwrap();
+        distributor
+            .register_recipient(RecipientRole::NetworkMaintainer, Address::random(), 30)
+            .await
+            .unwrap();
+        distributor
+            .allocate_fee(
+                H256::random(),
+                U256::from(1_000_000_000_000_000_000u64),
+                Some(Address::random()),
+            )
+            .await
+            .unwrap();
+        distributor
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_matches_confirmed_transfers() {
+        let client = Arc::new(MockContractClient::new());
+        let distributor = distributor_with_fees(client.clone()).await;
+
+        distributor.distribute_pending_fees().await.unwrap();
+        // A second run must not burn the same allocations again
+        distributor.distribute_pending_fees().await.unwrap();
+
+        let entries = distributor.audit_log().entries().await;
+        assert_eq!(entries.len(), 4); // marketplace, network, referrer, burn
+        assert!(entries.iter().all(|e| e.block_number == Some(100)));
+
+        let report = distributor.reconcile().await.unwrap();
+        assert!(report.is_balanced(), "{:?}", report.discrepancies);
+        assert_eq!(
+            report.confirmed_total,
+            U256::from(1_000_000_000_000_000_000u64)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_flags_discrepancies() {
+        let client = Arc::new(MockContractClient::new());
+        let distributor = distributor_with_fees(client.clone()).await;
+        distributor.distribute_pending_fees().await.unwrap();
+
+        let entries = distributor.audit_log().entries().await;
+        {
+            let mut transfers = client.transfers.write().await;
+            transfers.get_mut(&entries[0].tx_hash).unwrap().amount = U256::one();
+            transfers.get_mut(&entries[1].tx_hash).unwrap().success = false;
+            transfers.remove(&entries[2].tx_hash);
+        }
+
+        let report = distributor.reconcile().await.unwrap();
+        assert!(!report.is_balanced());
+        assert!(report.discrepancies.contains(&Discrepancy::AmountMismatch {
+            sequence: 0,
+            tx_hash: entries[0].tx_hash,
+            logged: entries[0].amount,
+            on_chain: U256::one(),
+        }));
+        assert!(report.discrepancies.contains(&Discrepancy::Reverted {
+            sequence: 1,
+            tx_hash: entries[1].tx_hash,
+        }));
+        assert!(report.discrepancies.contains(&Discrepancy::Unconfirmed {
+            sequence: 2,
+            tx_hash: entries[2].tx_hash,
+        }));
+        assert!(matches!(
+            report.discrepancies.last(),
+            Some(Discrepancy::TotalMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_persists_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fee_audit.jsonl");
+
+        let log = FeeAuditLog::open(&path).await.unwrap();
+        let tx_hash = H256::random();
+        log.append(Address::random(), None, U256::from(5), tx_hash, Some(7))
+            .await
+            .unwrap();
+        drop(log);
+
+        let reopened = FeeAuditLog::open(&path).await.unwrap();
+        let entries = reopened.entries().await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].tx_hash, tx_hash);
+        assert_eq!(entries[0].block_number, Some(7));
+    }
 }
@@ -24,10 +24,14 @@ pub mod fee_distributor {
 
 // Also re-export main types at top level
 pub use fees::{
-    FeeAllocation, FeeDistributionConfig, FeeDistributor, FeeRecipient, FeeStats, RecipientRole,
+    AuditEntry, Discrepancy, FeeAllocation, FeeAuditLog, FeeDistributionConfig, FeeDistributor,
+    FeeRecipient, FeeStats, OnChainTransfer, RecipientRole, ReconciliationReport,
+};
+pub use revenue::{
+    FeeStructure, JobMetrics, Revenue, RevenueCalculator, RevenueForecast, RevenueStats,
 };
-pub use revenue::{FeeStructure, JobMetrics, Revenue, RevenueCalculator, RevenueStats};
 pub use tracker::{PaymentEvent, PaymentEventType, PaymentFilter, PaymentStats, PaymentTracker};
 pub use withdrawal::{
-    WithdrawalConfig, WithdrawalManager, WithdrawalRequest, WithdrawalStats, WithdrawalStatus,
+    BatchReport, BatchSettlement, WithdrawalConfig, WithdrawalManager, WithdrawalRequest,
+    WithdrawalStats, WithdrawalStatus,
 };
@@ -29,6 +29,28 @@ pub struct RevenueStats {
     pub average_job_revenue: U256,
     pub revenue_by_model: HashMap<String, U256>,
     pub revenue_by_period: HashMap<String, U256>,
+    pub forecast: RevenueForecast,
+}
+
+/// Days of history the forecast looks back over
+pub const FORECAST_WINDOW_DAYS: i64 = 14;
+/// Horizon of the forecast included in `RevenueStats`
+pub const DEFAULT_FORECAST_HORIZON_DAYS: u32 = 7;
+/// Weight of the most recent day in the moving averages
+const FORECAST_EWMA_ALPHA: f64 = 0.3;
+/// Two-sided 95% z-score for the confidence band
+const FORECAST_Z_SCORE: f64 = 1.96;
+
+/// Projected net revenue over a horizon with a 95% confidence band
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevenueForecast {
+    pub horizon_days: u32,
+    pub jobs_per_day: f64,
+    pub expected_jobs: f64,
+    pub expected_net_revenue: U256,
+    pub lower_bound: U256,
+    pub upper_bound: U256,
+    pub generated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone)]
@@ -178,6 +200,8 @@ impl RevenueCalculator {
         revenue_by_period.insert("today".to_string(), today_revenue);
         revenue_by_period.insert("yesterday".to_string(), yesterday_revenue);
 
+        let forecast = self.forecast_from(&history, DEFAULT_FORECAST_HORIZON_DAYS, now);
+
         Ok(RevenueStats {
             total_jobs,
             total_gross_revenue,
@@ -186,9 +210,68 @@ impl RevenueCalculator {
             average_job_revenue,
             revenue_by_model,
             revenue_by_period,
+            forecast,
         })
     }
 
+    /// Project net revenue over the next `horizon_days`
+    ///
+    /// Daily job throughput and gross revenue over the last
+    /// `FORECAST_WINDOW_DAYS` are smoothed with an EWMA, and the current fee
+    /// structure is applied to the projected gross. The band widens with the
+    /// day-to-day variance of net revenue.
+    pub async fn forecast(&self, horizon_days: u32) -> Result<RevenueForecast> {
+        let history = self.revenue_history.read().await;
+        Ok(self.forecast_from(&history, horizon_days, Utc::now()))
+    }
+
+    fn forecast_from(
+        &self,
+        history: &[Revenue],
+        horizon_days: u32,
+        now: DateTime<Utc>,
+    ) -> RevenueForecast {
+        // Trailing 24h buckets, oldest first
+        let window = FORECAST_WINDOW_DAYS as usize;
+        let mut jobs = vec![0f64; window];
+        let mut gross = vec![0f64; window];
+        let mut net = vec![0f64; window];
+        for revenue in history {
+            let age = now.signed_duration_since(revenue.timestamp);
+            if age < Duration::zero() || age >= Duration::days(FORECAST_WINDOW_DAYS) {
+                continue;
+            }
+            let bucket = window - 1 - age.num_days() as usize;
+            jobs[bucket] += 1.0;
+            gross[bucket] += u256_to_f64(revenue.gross_amount);
+            net[bucket] += u256_to_f64(revenue.net_amount);
+        }
+
+        let jobs_per_day = ewma(&jobs);
+        let gross_per_day = ewma(&gross);
+
+        let fee_rate = f64::from(self.fee_structure.marketplace_fee_percent) / 100.0;
+        let network_fees = jobs_per_day * u256_to_f64(self.fee_structure.network_fee_fixed);
+        let net_per_day = (gross_per_day * (1.0 - fee_rate) - network_fees).max(0.0);
+
+        let mean_net = net.iter().sum::<f64>() / window as f64;
+        let variance = net.iter().map(|n| (n - mean_net).powi(2)).sum::<f64>() / window as f64;
+
+        let horizon = f64::from(horizon_days);
+        let expected = net_per_day * horizon;
+        let margin = FORECAST_Z_SCORE * variance.sqrt() * horizon.sqrt();
+
+        RevenueForecast {
+            horizon_days,
+            jobs_per_day,
+            expected_jobs: jobs_per_day * horizon,
+            expected_net_revenue: f64_to_u256(expected),
+            lower_bound: f64_to_u256(expected - margin),
+            upper_bound: f64_to_u256(expected + margin),
+            generated_at: now,
+        }
+    }
+
     pub async fn get_revenue_by_period(
         &self,
         start: DateTime<Utc>,
@@ -232,6 +315,30 @@ impl RevenueCalculator {
     }
 }
 
+fn ewma(values: &[f64]) -> f64 {
+    let mut iter = values.iter();
+    let first = iter.next().copied().unwrap_or(0.0);
+    iter.fold(first, |avg, v| {
+        FORECAST_EWMA_ALPHA * v + (1.0 - FORECAST_EWMA_ALPHA) * avg
+    })
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value.0.iter().rev().fold(0.0, |acc, word| {
+        acc * 18_446_744_073_709_551_616.0 + *word as f64
+    })
+}
+
+fn f64_to_u256(value: f64) -> U256 {
+    if value <= 0.0 {
+        U256::zero()
+    } else if value >= u128::MAX as f64 {
+        U256::from(u128::MAX)
+    } else {
+        U256::from(value as u128)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,4 +375,50 @@ mod tests {
         assert_eq!(revenue.penalty_amount, U256::zero());
         assert_eq!(revenue.net_amount, U256::from(94_000_000_000_000_000u64)); // 0.094 ETH
     }
+
+    #[tokio::test]
+    async fn test_forecast_projects_steady_throughput() {
+        let calculator = RevenueCalculator::new(FeeStructure::default());
+        let base_amount = U256::from(100_000_000_000_000_000u64); // 0.1 ETH
+        let now = Utc::now();
+
+        // Two jobs a day for the whole window
+        for day in 0..FORECAST_WINDOW_DAYS {
+            for _ in 0..2 {
+                let metrics = JobMetrics {
+                    completed_at: now - Duration::days(day) - Duration::hours(1),
+                    ..create_test_metrics()
+                };
+                let revenue = calculator
+                    .calculate_revenue(H256::random(), base_amount, metrics)
+                    .await
+                    .unwrap();
+                calculator.record_revenue(revenue).await.unwrap();
+            }
+        }
+
+        let forecast = calculator.forecast(7).await.unwrap();
+        assert!((forecast.jobs_per_day - 2.0).abs() < 1e-9);
+        assert!((forecast.expected_jobs - 14.0).abs() < 1e-9);
+
+        // 14 jobs at 0.094 ETH net each
+        let expected = 14.0 * 94_000_000_000_000_000f64;
+        let projected = u256_to_f64(forecast.expected_net_revenue);
+        assert!((projected - expected).abs() / expected < 1e-6);
+        assert!(forecast.lower_bound <= forecast.expected_net_revenue);
+        assert!(forecast.upper_bound >= forecast.expected_net_revenue);
+
+        let stats = calculator.get_revenue_stats().await.unwrap();
+        assert_eq!(stats.forecast.horizon_days, DEFAULT_FORECAST_HORIZON_DAYS);
+    }
+
+    #[tokio::test]
+    async fn test_forecast_without_history_is_zero() {
+        let calculator = RevenueCalculator::new(FeeStructure::default());
+        let forecast = calculator.forecast(30).await.unwrap();
+
+        assert_eq!(forecast.expected_jobs, 0.0);
+        assert_eq!(forecast.expected_net_revenue, U256::zero());
+        assert_eq!(forecast.upper_bound, U256::zero());
+    }
 }
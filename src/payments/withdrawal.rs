@@ -34,6 +34,12 @@ pub struct WithdrawalConfig {
     pub batch_size: usize,
     pub cooldown_period_secs: u64,
     pub max_pending_withdrawals: usize,
+    /// Settle pending withdrawals together in one disperse transaction
+    pub enable_batching: bool,
+    /// Settle once the pending total reaches this amount
+    pub batch_amount_threshold: U256,
+    /// Settle once the oldest pending request has waited this long
+    pub batch_max_wait_secs: u64,
 }
 
 impl Default for WithdrawalConfig {
@@ -44,6 +50,9 @@ impl Default for WithdrawalConfig {
             batch_size: 10,
             cooldown_period_secs: 3600, // 1 hour
             max_pending_withdrawals: 5,
+            enable_batching: false,
+            batch_amount_threshold: U256::from(100_000_000_000_000_000u64), // 0.1 ETH
+            batch_max_wait_secs: 600,
         }
     }
 }
@@ -56,6 +65,36 @@ pub struct WithdrawalStats {
     pub failed_withdrawals: u64,
     pub average_withdrawal_amount: U256,
     pub last_withdrawal: Option<DateTime<Utc>>,
+    pub batches_settled: u64,
+    pub last_batch: Option<BatchReport>,
+}
+
+/// Per-request outcome of a disperse transaction
+#[derive(Debug, Clone)]
+pub struct BatchSettlement {
+    pub receipt: TransactionReceipt,
+    /// `Err` holds the revert reason of a request that failed inside the batch
+    pub results: Vec<(H256, std::result::Result<(), String>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchReport {
+    pub tx_hash: Option<H256>,
+    pub settled_at: DateTime<Utc>,
+    pub results: Vec<(H256, WithdrawalStatus)>,
+}
+
+impl BatchReport {
+    pub fn succeeded(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, status)| matches!(status, WithdrawalStatus::Completed(_)))
+            .count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.succeeded()
+    }
 }
 
 pub struct WithdrawalManager {
@@ -65,6 +104,9 @@ pub struct WithdrawalManager {
     withdrawal_history: Arc<RwLock<Vec<WithdrawalRequest>>>,
     available_balance: Arc<RwLock<HashMap<Address, U256>>>,
     last_withdrawal_time: Arc<Mutex<Option<DateTime<Utc>>>>,
+    batch_reports: Arc<RwLock<Vec<BatchReport>>>,
+    // Serializes batch settlement between the threshold check and the timer
+    settle_lock: Arc<Mutex<()>>,
 }
 
 #[async_trait::async_trait]
@@ -81,6 +123,9 @@ pub trait ContractClient: Send + Sync {
     async fn execute_withdrawal(&self, request_id: H256) -> Result<TransactionReceipt>;
 
     async fn batch_withdraw(&self, requests: Vec<H256>) -> Result<Vec<TransactionReceipt>>;
+
+    /// Settle several requests in a single disperse-style transaction
+    async fn disperse_withdrawals(&self, requests: Vec<H256>) -> Result<BatchSettlement>;
 }
 
 impl WithdrawalManager {
@@ -92,6 +137,8 @@ impl WithdrawalManager {
             withdrawal_history: Arc::new(RwLock::new(Vec::new())),
             available_balance: Arc::new(RwLock::new(HashMap::new())),
             last_withdrawal_time: Arc::new(Mutex::new(None)),
+            batch_reports: Arc::new(RwLock::new(Vec::new())),
+            settle_lock: Arc::new(Mutex::new(())),
         }
     }
 
@@ -147,6 +194,12 @@ impl WithdrawalManager {
         // Add to pending queue
         self.pending_withdrawals.write().await.push(request.clone());
 
+        if self.config.enable_batching && self.batch_ready().await {
+            if let Err(e) = self.settle_batch().await {
+                tracing::warn!("Batch withdrawal settlement failed: {}", e);
+            }
+        }
+
         Ok(request)
     }
 
@@ -198,6 +251,11 @@ impl WithdrawalManager {
     }
 
     pub async fn process_batch_withdrawals(&self) -> Result<Vec<H256>> {
+        if self.config.enable_batching {
+            let report = self.settle_batch().await?;
+            return Ok(report.and_then(|r| r.tx_hash).into_iter().collect());
+        }
+
         let pending = self.pending_withdrawals.read().await;
         let batch: Vec<_> = pending
             .iter()
@@ -219,6 +277,129 @@ impl WithdrawalManager {
         Ok(tx_hashes)
     }
 
+    /// Whether pending requests hit the count, amount or max-wait threshold
+    pub async fn batch_ready(&self) -> bool {
+        let pending = self.pending_withdrawals.read().await;
+        let queued: Vec<_> = pending
+            .iter()
+            .filter(|r| r.status == WithdrawalStatus::Pending)
+            .collect();
+
+        let Some(oldest) = queued.iter().map(|r| r.requested_at).min() else {
+            return false;
+        };
+        let total = queued.iter().fold(U256::zero(), |acc, r| acc + r.amount);
+        let waited = Utc::now().signed_duration_since(oldest);
+
+        queued.len() >= self.config.batch_size
+            || total >= self.config.batch_amount_threshold
+            || waited >= Duration::seconds(self.config.batch_max_wait_secs as i64)
+    }
+
+    /// Settle up to `batch_size` pending requests in one transaction
+    ///
+    /// Requests that revert inside the batch are marked failed while the rest
+    /// complete; if the whole transaction reverts every request fails.
+    pub async fn settle_batch(&self) -> Result<Option<BatchReport>> {
+        let _guard = self.settle_lock.lock().await;
+
+        let batch: Vec<H256> = {
+            let mut pending = self.pending_withdrawals.write().await;
+            pending
+                .iter_mut()
+                .filter(|r| r.status == WithdrawalStatus::Pending)
+                .take(self.config.batch_size)
+                .map(|r| {
+                    r.status = WithdrawalStatus::Processing;
+                    r.request_id
+                })
+                .collect()
+        };
+        if batch.is_empty() {
+            return Ok(None);
+        }
+
+        let (tx_hash, results) = match self
+            .contract_client
+            .disperse_withdrawals(batch.clone())
+            .await
+        {
+            Ok(settlement) if settlement.receipt.status != Some(0u64.into()) => {
+                let tx_hash = settlement.receipt.transaction_hash;
+                let results = batch
+                    .iter()
+                    .map(|id| {
+                        let status = match settlement.results.iter().find(|(r, _)| r == id) {
+                            Some((_, Ok(()))) => WithdrawalStatus::Completed(tx_hash),
+                            Some((_, Err(reason))) => WithdrawalStatus::Failed(reason.clone()),
+                            None => WithdrawalStatus::Failed(
+                                "Missing from batch settlement".to_string(),
+                            ),
+                        };
+                        (*id, status)
+                    })
+                    .collect();
+                (Some(tx_hash), results)
+            }
+            Ok(settlement) => {
+                let reason = "Batch transaction reverted".to_string();
+                let results = batch
+                    .iter()
+                    .map(|id| (*id, WithdrawalStatus::Failed(reason.clone())))
+                    .collect();
+                (Some(settlement.receipt.transaction_hash), results)
+            }
+            Err(e) => {
+                let results = batch
+                    .iter()
+                    .map(|id| (*id, WithdrawalStatus::Failed(e.to_string())))
+                    .collect();
+                (None, results)
+            }
+        };
+
+        let report = BatchReport {
+            tx_hash,
+            settled_at: Utc::now(),
+            results,
+        };
+
+        let mut pending = self.pending_withdrawals.write().await;
+        let mut history = self.withdrawal_history.write().await;
+        for (request_id, status) in &report.results {
+            if let Some(idx) = pending.iter().position(|r| r.request_id == *request_id) {
+                let mut request = pending.remove(idx);
+                request.status = status.clone();
+                history.push(request);
+            }
+        }
+        drop(history);
+        drop(pending);
+
+        if report.succeeded() > 0 {
+            *self.last_withdrawal_time.lock().await = Some(report.settled_at);
+        }
+        self.batch_reports.write().await.push(report.clone());
+
+        Ok(Some(report))
+    }
+
+    /// Periodically settle batches whose oldest request exceeded the max wait
+    pub fn start_batch_timer(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let tick = std::time::Duration::from_secs(self.config.batch_max_wait_secs.clamp(1, 60));
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tick);
+            loop {
+                interval.tick().await;
+                if self.batch_ready().await {
+                    if let Err(e) = self.settle_batch().await {
+                        tracing::warn!("Batch withdrawal settlement failed: {}", e);
+                    }
+                }
+            }
+        })
+    }
+
     pub async fn update_available_balance(&self, token: Address, amount: U256) -> Result<()> {
         self.available_balance.write().await.insert(token, amount);
         Ok(())
@@ -256,6 +437,8 @@ impl WithdrawalManager {
 
         let last_withdrawal = successful.iter().map(|r| r.requested_at).max();
 
+        let batch_reports = self.batch_reports.read().await;
+
         Ok(WithdrawalStats {
             total_withdrawn,
             total_fees_paid,
@@ -263,6 +446,8 @@ impl WithdrawalManager {
             failed_withdrawals: failed_count,
             average_withdrawal_amount,
             last_withdrawal,
+            batches_settled: batch_reports.len() as u64,
+            last_batch: batch_reports.last().cloned(),
         })
     }
 
@@ -307,6 +492,8 @@ mod tests {
     struct MockContractClient {
         balances: Arc<RwLock<HashMap<(Address, Address), U256>>>,
         executed_withdrawals: Arc<RwLock<Vec<H256>>>,
+        reverting: Arc<RwLock<Vec<H256>>>,
+        disperse_calls: Arc<RwLock<usize>>,
     }
 
     impl MockContractClient {
@@ -314,6 +501,8 @@ mod tests {
             Self {
                 balances: Arc::new(RwLock::new(HashMap::new())),
                 executed_withdrawals: Arc::new(RwLock::new(Vec::new())),
+                reverting: Arc::new(RwLock::new(Vec::new())),
+                disperse_calls: Arc::new(RwLock::new(0)),
             }
         }
     }
@@ -355,6 +544,41 @@ mod tests {
             }
             Ok(receipts)
         }
+
+        async fn disperse_withdrawals(&self, requests: Vec<H256>) -> Result<BatchSettlement> {
+            *self.disperse_calls.write().await += 1;
+            let reverting = self.reverting.read().await;
+            let results = requests
+                .into_iter()
+                .map(|id| {
+                    if reverting.contains(&id) {
+                        (id, Err("transfer failed".to_string()))
+                    } else {
+                        (id, Ok(()))
+                    }
+                })
+                .collect();
+            Ok(BatchSettlement {
+                receipt: TransactionReceipt {
+                    transaction_hash: H256::random(),
+                    block_number: Some(100u64.into()),
+                    status: Some(1u64.into()),
+                    ..Default::default()
+                },
+                results,
+            })
+        }
+    }
+
+    fn batching_config() -> WithdrawalConfig {
+        WithdrawalConfig {
+            cooldown_period_secs: 0,
+            enable_batching: true,
+            batch_size: 3,
+            max_pending_withdrawals: 10,
+            batch_amount_threshold: U256::MAX,
+            ..Default::default()
+        }
     }
 
     #[tokio::test]
@@ -365,4 +589,85 @@ mod tests {
         assert_eq!(manager.config.batch_size, 10);
         assert_eq!(manager.config.cooldown_period_secs, 3600);
     }
+
+    #[tokio::test]
+    async fn test_batch_settles_at_count_threshold() {
+        let client = Arc::new(MockContractClient::new());
+        let manager = WithdrawalManager::new(batching_config(), client.clone());
+        let token = Address::zero();
+        manager
+            .update_available_balance(token, U256::exp10(18))
+            .await
+            .unwrap();
+
+        let amount = U256::exp10(16);
+        for _ in 0..2 {
+            manager
+                .request_withdrawal(amount, token, Address::random())
+                .await
+                .unwrap();
+        }
+        assert_eq!(*client.disperse_calls.read().await, 0);
+
+        manager
+            .request_withdrawal(amount, token, Address::random())
+            .await
+            .unwrap();
+        assert_eq!(*client.disperse_calls.read().await, 1);
+        assert!(manager.get_pending_withdrawals().await.unwrap().is_empty());
+
+        let stats = manager.get_withdrawal_stats().await.unwrap();
+        assert_eq!(stats.batches_settled, 1);
+        assert_eq!(stats.successful_withdrawals, 3);
+        assert_eq!(stats.last_batch.unwrap().succeeded(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_batch_partial_revert() {
+        let client = Arc::new(MockContractClient::new());
+        let config = WithdrawalConfig {
+            batch_size: 10,
+            ..batching_config()
+        };
+        let manager = WithdrawalManager::new(config, client.clone());
+        let token = Address::zero();
+        manager
+            .update_available_balance(token, U256::exp10(18))
+            .await
+            .unwrap();
+
+        let amount = U256::exp10(16);
+        let ok = manager
+            .request_withdrawal(amount, token, Address::random())
+            .await
+            .unwrap();
+        let bad = manager
+            .request_withdrawal(amount, token, Address::random())
+            .await
+            .unwrap();
+        client.reverting.write().await.push(bad.request_id);
+
+        let report = manager.settle_batch().await.unwrap().unwrap();
+        let status_of = |id: H256| {
+            report
+                .results
+                .iter()
+                .find(|(r, _)| *r == id)
+                .map(|(_, s)| s.clone())
+                .unwrap()
+        };
+        assert!(matches!(
+            status_of(ok.request_id),
+            WithdrawalStatus::Completed(_)
+        ));
+        assert_eq!(
+            status_of(bad.request_id),
+            WithdrawalStatus::Failed("transfer failed".to_string())
+        );
+
+        let stats = manager.get_withdrawal_stats().await.unwrap();
+        assert_eq!(stats.successful_withdrawals, 1);
+        assert_eq!(stats.failed_withdrawals, 1);
+        assert_eq!(stats.total_withdrawn, amount);
+    }
 }
@@ -11,6 +11,8 @@ use tokio::sync::{mpsc, RwLock};
 use tokio::time::timeout;
 use uuid::Uuid;
 
+use super::gpu_management::GpuManager;
+
 #[derive(Debug, Clone)]
 pub struct BatchConfig {
     pub max_batch_size: usize,
@@ -38,6 +40,51 @@ impl Default for BatchConfig {
     }
 }
 
+/// Per-token GPU memory cost used to size batches against free VRAM
+#[derive(Debug, Clone)]
+pub struct BatchMemoryModel {
+    /// KV-cache bytes per token (2 x layers x hidden size x dtype bytes)
+    pub kv_cache_bytes_per_token: u64,
+    /// Transient activation bytes per token
+    pub activation_bytes_per_token: u64,
+    /// Rough prompt characters per token
+    pub chars_per_token: usize,
+    /// Fraction of free VRAM left unused as a safety margin
+    pub headroom_fraction: f64,
+}
+
+impl Default for BatchMemoryModel {
+    fn default() -> Self {
+        // 7B model in fp16: 32 layers, 4096 hidden
+        Self {
+            kv_cache_bytes_per_token: 2 * 32 * 4096 * 2,
+            activation_bytes_per_token: 16 * 4096,
+            chars_per_token: 4,
+            headroom_fraction: 0.1,
+        }
+    }
+}
+
+impl BatchMemoryModel {
+    /// Estimated peak memory of one request, capped at the max sequence length
+    pub fn estimate_request(&self, request: &BatchRequest, max_sequence_length: usize) -> u64 {
+        let prompt_tokens = request.prompt.len().div_ceil(self.chars_per_token.max(1));
+        let tokens = (prompt_tokens + request.max_tokens).min(max_sequence_length) as u64;
+        tokens * (self.kv_cache_bytes_per_token + self.activation_bytes_per_token)
+    }
+
+    fn budget(&self, free_memory: u64) -> u64 {
+        (free_memory as f64 * (1.0 - self.headroom_fraction).clamp(0.0, 1.0)) as u64
+    }
+}
+
+#[derive(Clone)]
+struct GpuMemorySource {
+    manager: Arc<GpuManager>,
+    device_id: i32,
+    model: BatchMemoryModel,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum BatchingStrategy {
     Static,
@@ -63,7 +110,7 @@ pub enum BatchPriority {
 }
 
 impl BatchPriority {
-    fn to_queue_index(&self) -> usize {
+    pub(crate) fn to_queue_index(&self) -> usize {
         match self {
             BatchPriority::Critical => 0,
             BatchPriority::High => 1,
@@ -109,6 +156,8 @@ pub struct Batch {
     pub created_at: Instant,
     pub status: BatchStatus,
     pub padding_info: PaddingInfo,
+    /// Estimated KV-cache + activation memory, 0 without a GPU source
+    pub estimated_memory_bytes: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -129,6 +178,13 @@ pub struct BatchMetrics {
     pub batch_efficiency: f64,
     pub throughput_requests_per_sec: f64,
     pub dropped_requests: u64,
+    /// Size of the most recent batch after memory capping
+    pub last_batch_size: usize,
+    pub last_batch_memory_estimate: u64,
+    /// Free VRAM seen when the last batch was formed
+    pub available_gpu_memory: Option<u64>,
+    /// Batches shrunk to fit in GPU memory
+    pub memory_limited_batches: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -166,6 +222,10 @@ struct InternalMetrics {
     total_wait_time_ms: u64,
     dropped_requests: u64,
     start_time: Instant,
+    last_batch_size: usize,
+    last_batch_memory_estimate: u64,
+    available_gpu_memory: Option<u64>,
+    memory_limited_batches: u64,
 }
 
 pub struct BatchProcessor {
@@ -173,6 +233,7 @@ pub struct BatchProcessor {
     state: Arc<RwLock<BatchState>>,
     notify_tx: mpsc::UnboundedSender<()>,
     notify_rx: Arc<RwLock<mpsc::UnboundedReceiver<()>>>,
+    gpu_memory: Option<GpuMemorySource>,
 }
 
 impl BatchProcessor {
@@ -192,6 +253,10 @@ impl BatchProcessor {
                 total_wait_time_ms: 0,
                 dropped_requests: 0,
                 start_time: Instant::now(),
+                last_batch_size: 0,
+                last_batch_memory_estimate: 0,
+                available_gpu_memory: None,
+                memory_limited_batches: 0,
             },
             next_batch_time: None,
         };
@@ -203,9 +268,71 @@ impl BatchProcessor {
             state: Arc::new(RwLock::new(state)),
             notify_tx,
             notify_rx: Arc::new(RwLock::new(notify_rx)),
+            gpu_memory: None,
         })
     }
 
+    /// Cap batches so their estimated memory fits the device's free VRAM
+    pub fn with_gpu_manager(
+        mut self,
+        manager: Arc<GpuManager>,
+        device_id: i32,
+        model: BatchMemoryModel,
+    ) -> Self {
+        self.gpu_memory = Some(GpuMemorySource {
+            manager,
+            device_id,
+            model,
+        });
+        self
+    }
+
+    /// Free VRAM on the configured device, `None` without a GPU source
+    async fn free_gpu_memory(&self) -> Option<u64> {
+        let source = self.gpu_memory.as_ref()?;
+        match source.manager.get_gpu_metrics(source.device_id).await {
+            Ok(metrics) => Some(metrics.memory_total.saturating_sub(metrics.memory_used)),
+            Err(e) => {
+                tracing::warn!(
+                    "GPU {} metrics unavailable, batching without memory cap: {}",
+                    source.device_id,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Trim a collected batch to the memory budget, returning the overflow
+    ///
+    /// The first request is always kept so a single oversized request can't
+    /// stall the queue; smaller batches couldn't help it anyway.
+    fn cap_to_memory(
+        &self,
+        requests: &mut Vec<(BatchRequest, Instant)>,
+        free_memory: u64,
+    ) -> (u64, Vec<(BatchRequest, Instant)>) {
+        let Some(source) = &self.gpu_memory else {
+            return (0, Vec::new());
+        };
+        let budget = source.model.budget(free_memory);
+
+        let mut used = 0u64;
+        let mut keep = 0;
+        for (request, _) in requests.iter() {
+            let cost = source
+                .model
+                .estimate_request(request, self.config.max_sequence_length);
+            if keep > 0 && used + cost > budget {
+                break;
+            }
+            used += cost;
+            keep += 1;
+        }
+
+        (used, requests.split_off(keep))
+    }
+
     pub async fn submit_request(&self, request: BatchRequest) -> Result<()> {
         let mut state = self.state.write().await;
 
@@ -261,10 +388,11 @@ impl BatchProcessor {
     }
 
     async fn try_create_batch(&self) -> Result<Option<Batch>> {
+        let free_memory = self.free_gpu_memory().await;
         let mut state = self.state.write().await;
 
         // Collect requests based on batching strategy
-        let requests = match self.config.batching_strategy {
+        let mut requests = match self.config.batching_strategy {
             BatchingStrategy::Static => self.collect_static_batch(&mut state),
             BatchingStrategy::Dynamic => self.collect_dynamic_batch(&mut state),
             BatchingStrategy::Adaptive => self.collect_adaptive_batch(&mut state),
@@ -275,6 +403,26 @@ impl BatchProcessor {
             return Ok(None);
         }
 
+        // Shrink under memory pressure; overflow goes back to the queue front
+        let mut estimated_memory_bytes = 0;
+        if let Some(free) = free_memory {
+            let (estimate, overflow) = self.cap_to_memory(&mut requests, free);
+            estimated_memory_bytes = estimate;
+            if !overflow.is_empty() {
+                state.metrics.memory_limited_batches += 1;
+                for (request, submitted_at) in overflow.into_iter().rev() {
+                    let index = request
+                        .priority
+                        .to_queue_index()
+                        .min(state.queues.len() - 1);
+                    state.queues[index].push_front((request, submitted_at));
+                }
+            }
+        }
+        state.metrics.available_gpu_memory = free_memory;
+        state.metrics.last_batch_size = requests.len();
+        state.metrics.last_batch_memory_estimate = estimated_memory_bytes;
+
         // Update metrics
         let total_wait_time: u64 = requests
             .iter()
@@ -327,6 +475,7 @@ impl BatchProcessor {
             created_at: Instant::now(),
             status: BatchStatus::Pending,
             padding_info,
+            estimated_memory_bytes,
         };
 
         state
@@ -553,6 +702,10 @@ impl BatchProcessor {
             batch_efficiency,
             throughput_requests_per_sec,
             dropped_requests: state.metrics.dropped_requests,
+            last_batch_size: state.metrics.last_batch_size,
+            last_batch_memory_estimate: state.metrics.last_batch_memory_estimate,
+            available_gpu_memory: state.metrics.available_gpu_memory,
+            memory_limited_batches: state.metrics.memory_limited_batches,
         }
     }
 
@@ -650,6 +803,7 @@ impl Clone for BatchProcessor {
             state: self.state.clone(),
             notify_tx: self.notify_tx.clone(),
             notify_rx: self.notify_rx.clone(),
+            gpu_memory: self.gpu_memory.clone(),
         }
     }
 }
@@ -1,8 +1,11 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use anyhow::Result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -18,6 +21,10 @@ pub struct LoadBalancerConfig {
     pub enable_session_affinity: bool,
     pub load_threshold: f64,
     pub rebalance_interval_secs: u64,
+    /// Consecutive failed health checks before a node is ejected
+    pub unhealthy_threshold: u32,
+    /// How long an ejected node stays out of rotation before it is probed
+    pub ejection_cooldown_secs: u64,
 }
 
 impl Default for LoadBalancerConfig {
@@ -30,6 +37,8 @@ impl Default for LoadBalancerConfig {
             enable_session_affinity: false,
             load_threshold: 0.8,
             rebalance_interval_secs: 60,
+            unhealthy_threshold: 3,
+            ejection_cooldown_secs: 30,
         }
     }
 }
@@ -42,6 +51,9 @@ pub enum LoadStrategy {
     Random,
     LeastResponseTime,
     ResourceBased,
+    /// Sample two healthy nodes at random and take the one with fewer
+    /// in-flight requests, avoiding least-connections herding
+    PowerOfTwoChoices,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -96,6 +108,23 @@ pub struct LoadDistribution {
     pub average_load: f64,
     pub peak_load: f64,
     pub distribution_efficiency: f64,
+    /// Session requests routed to the node already holding the session
+    pub affinity_hits: u64,
+    /// Session requests that had to be placed (new session or failover)
+    pub affinity_misses: u64,
+    /// Sessions moved off a node that could no longer serve them
+    pub affinity_reassignments: u64,
+}
+
+impl LoadDistribution {
+    pub fn affinity_hit_rate(&self) -> f64 {
+        let total = self.affinity_hits + self.affinity_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.affinity_hits as f64 / total as f64
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -108,6 +137,10 @@ pub struct LoadMetrics {
     pub active_nodes: usize,
     pub total_requests: u64,
     pub nodes: HashMap<String, WorkerMetrics>,
+    /// Nodes taken out of rotation by failed health checks or request failures
+    pub ejections: u64,
+    /// Ejected nodes re-admitted after a successful probe
+    pub recoveries: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -143,19 +176,131 @@ pub enum LoadBalancerError {
     HealthCheckFailed { reason: String },
 }
 
+/// Points per node on the hash ring; more points spread sessions more evenly
+const VIRTUAL_NODES_PER_WORKER: usize = 64;
+
+/// Consistent-hash ring placing sessions on nodes. Adding or removing a node
+/// only moves the sessions that hash next to it.
+#[derive(Debug, Default)]
+struct HashRing {
+    points: BTreeMap<u64, String>,
+}
+
+impl HashRing {
+    fn hash(key: &str) -> u64 {
+        let digest = Sha256::digest(key.as_bytes());
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+
+    fn add(&mut self, node_id: &str) {
+        for i in 0..VIRTUAL_NODES_PER_WORKER {
+            self.points.insert(
+                Self::hash(&format!("{}#{}", node_id, i)),
+                node_id.to_string(),
+            );
+        }
+    }
+
+    fn remove(&mut self, node_id: &str) {
+        self.points.retain(|_, id| id != node_id);
+    }
+
+    /// First node clockwise from the session's hash that passes `eligible`
+    fn owner(&self, session_id: &str, eligible: impl Fn(&str) -> bool) -> Option<String> {
+        let start = Self::hash(session_id);
+        self.points
+            .range(start..)
+            .chain(self.points.range(..start))
+            .map(|(_, id)| id)
+            .find(|id| eligible(id))
+            .cloned()
+    }
+}
+
 struct NodeState {
     node: WorkerNode,
     metrics: WorkerMetrics,
     health_check_failures: u32,
     weight: f64,
     active_connections: HashMap<String, Instant>,
+    /// When the node was last taken out of rotation
+    ejected_at: Option<Instant>,
+}
+
+/// Effect of a health check on a node's place in rotation
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HealthTransition {
+    Unchanged,
+    Ejected,
+    Recovered,
+}
+
+impl NodeState {
+    fn is_ejected(&self) -> bool {
+        matches!(
+            self.node.status,
+            NodeStatus::Unhealthy | NodeStatus::CircuitOpen | NodeStatus::CircuitHalfOpen
+        )
+    }
+
+    fn eject(&mut self, status: NodeStatus) {
+        self.node.status = status;
+        self.ejected_at = Some(Instant::now());
+    }
+
+    fn cooldown_elapsed(&self, cooldown: Duration) -> bool {
+        self.ejected_at.map_or(true, |at| at.elapsed() >= cooldown)
+    }
+
+    /// Apply a health check result. Healthy nodes are ejected after
+    /// `unhealthy_threshold` consecutive failures; ejected nodes ignore
+    /// results until the cooldown passes, then a single probe decides
+    /// whether they rejoin or start another cooldown.
+    fn apply_health_check(
+        &mut self,
+        is_healthy: bool,
+        config: &LoadBalancerConfig,
+    ) -> HealthTransition {
+        if self.is_ejected() {
+            if !self.cooldown_elapsed(Duration::from_secs(config.ejection_cooldown_secs)) {
+                return HealthTransition::Unchanged;
+            }
+            if is_healthy {
+                self.node.status = NodeStatus::Healthy;
+                self.health_check_failures = 0;
+                self.ejected_at = None;
+                return HealthTransition::Recovered;
+            }
+            self.eject(NodeStatus::Unhealthy);
+            return HealthTransition::Unchanged;
+        }
+
+        if is_healthy {
+            self.health_check_failures = 0;
+            return HealthTransition::Unchanged;
+        }
+        self.health_check_failures += 1;
+        if self.node.status == NodeStatus::Healthy
+            && self.health_check_failures >= config.unhealthy_threshold.max(1)
+        {
+            self.eject(NodeStatus::Unhealthy);
+            return HealthTransition::Ejected;
+        }
+        HealthTransition::Unchanged
+    }
 }
 
 struct BalancerState {
     nodes: HashMap<String, NodeState>,
     round_robin_index: usize,
     session_affinity_map: HashMap<String, String>,
+    hash_ring: HashRing,
+    affinity_hits: u64,
+    affinity_misses: u64,
+    affinity_reassignments: u64,
     total_requests: u64,
+    ejections: u64,
+    recoveries: u64,
     latency_history: VecDeque<f64>,
     last_rebalance: Instant,
 }
@@ -163,6 +308,8 @@ struct BalancerState {
 pub struct LoadBalancer {
     config: LoadBalancerConfig,
     state: Arc<RwLock<BalancerState>>,
+    /// Drives the random and weighted strategies and power-of-two choices
+    rng: Arc<std::sync::Mutex<StdRng>>,
 }
 
 impl LoadBalancer {
@@ -189,16 +336,28 @@ impl LoadBalancer {
                 health_check_failures: 0,
                 weight: 1.0,
                 active_connections: HashMap::new(),
+                ejected_at: None,
                 node: node.clone(),
             };
             node_states.insert(node.id.clone(), state);
         }
 
+        let mut hash_ring = HashRing::default();
+        for node_id in node_states.keys() {
+            hash_ring.add(node_id);
+        }
+
         let state = BalancerState {
             nodes: node_states,
             round_robin_index: 0,
             session_affinity_map: HashMap::new(),
+            hash_ring,
+            affinity_hits: 0,
+            affinity_misses: 0,
+            affinity_reassignments: 0,
             total_requests: 0,
+            ejections: 0,
+            recoveries: 0,
             latency_history: VecDeque::with_capacity(1000),
             last_rebalance: Instant::now(),
         };
@@ -206,6 +365,7 @@ impl LoadBalancer {
         let balancer = Self {
             config,
             state: Arc::new(RwLock::new(state)),
+            rng: Arc::new(std::sync::Mutex::new(StdRng::from_entropy())),
         };
 
         // Start health check task
@@ -217,6 +377,13 @@ impl LoadBalancer {
         Ok(balancer)
     }
 
+    /// Seed the RNG behind the random selection strategies so their choices
+    /// can be reproduced
+    pub fn with_seed(self, seed: u64) -> Self {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+        self
+    }
+
     pub async fn select_node(
         &self,
         model_id: &str,
@@ -225,27 +392,33 @@ impl LoadBalancer {
         let mut state = self.state.write().await;
         state.total_requests += 1;
 
-        // Check session affinity
-        if self.config.enable_session_affinity {
-            if let Some(session_id) = session_id {
-                if let Some(node_id) = state.session_affinity_map.get(session_id) {
-                    if let Some(node_state) = state.nodes.get(node_id) {
+        // Check session affinity: follow-up prompts go to the node that
+        // already holds the session's KV cache while it can serve them
+        let affinity_session = session_id.filter(|_| self.config.enable_session_affinity);
+        let mut affine_node_lost = false;
+        if let Some(session_id) = affinity_session {
+            if let Some(node_id) = state.session_affinity_map.get(session_id) {
+                match state.nodes.get(node_id) {
+                    Some(node_state)
                         if node_state.node.status == NodeStatus::Healthy
                             && node_state
                                 .node
                                 .capabilities
                                 .models
-                                .contains(&model_id.to_string())
-                        {
-                            return Ok(node_state.node.clone());
-                        }
+                                .contains(&model_id.to_string()) =>
+                    {
+                        let node = node_state.node.clone();
+                        state.affinity_hits += 1;
+                        return Ok(node);
                     }
+                    _ => affine_node_lost = true,
                 }
             }
         }
 
-        // Filter healthy nodes that support the model
-        let eligible_nodes: Vec<String> = state
+        // Filter healthy nodes that support the model, in a stable order so
+        // seeded selections are reproducible
+        let mut eligible_nodes: Vec<String> = state
             .nodes
             .iter()
             .filter(|(_, node_state)| {
@@ -258,6 +431,7 @@ impl LoadBalancer {
             })
             .map(|(id, _)| id.clone())
             .collect();
+        eligible_nodes.sort();
 
         if eligible_nodes.is_empty() {
             return Err(LoadBalancerError::NoHealthyNodes.into());
@@ -284,6 +458,32 @@ impl LoadBalancer {
             return Err(LoadBalancerError::AllNodesOverloaded.into());
         }
 
+        // New sessions, and sessions whose node failed, are placed on the
+        // hash ring so placement stays stable as nodes come and go
+        if let Some(session_id) = affinity_session {
+            let node_id = state
+                .hash_ring
+                .owner(session_id, |id| eligible_nodes.iter().any(|e| e == id))
+                .unwrap_or_else(|| eligible_nodes[0].clone());
+            state.affinity_misses += 1;
+            if affine_node_lost {
+                state.affinity_reassignments += 1;
+                tracing::info!(
+                    "Session {} reassigned to node {} after failover",
+                    session_id,
+                    node_id
+                );
+            }
+            state
+                .session_affinity_map
+                .insert(session_id.to_string(), node_id.clone());
+            return state
+                .nodes
+                .get(&node_id)
+                .map(|node_state| node_state.node.clone())
+                .ok_or_else(|| LoadBalancerError::NoHealthyNodes.into());
+        }
+
         // Select node based on strategy
         let selected_node_id = match self.config.strategy {
             LoadStrategy::RoundRobin => {
@@ -321,7 +521,7 @@ impl LoadBalancer {
 
                 // Select based on weights
                 let total_weight: f64 = weights.iter().map(|(_, w)| w).sum();
-                let mut random_point = rand::random::<f64>() * total_weight;
+                let mut random_point = self.rng.lock().unwrap().gen::<f64>() * total_weight;
 
                 let mut selected = eligible_nodes[0].clone();
                 for (id, weight) in weights {
@@ -334,7 +534,7 @@ impl LoadBalancer {
                 selected
             }
             LoadStrategy::Random => {
-                let idx = rand::random::<usize>() % eligible_nodes.len();
+                let idx = self.rng.lock().unwrap().gen_range(0..eligible_nodes.len());
                 eligible_nodes[idx].clone()
             }
             LoadStrategy::LeastResponseTime => eligible_nodes
@@ -368,16 +568,32 @@ impl LoadBalancer {
                     .cloned()
                     .unwrap()
             }
-        };
-
-        // Update session affinity if enabled
-        if self.config.enable_session_affinity {
-            if let Some(session_id) = session_id {
-                state
-                    .session_affinity_map
-                    .insert(session_id.to_string(), selected_node_id.clone());
+            LoadStrategy::PowerOfTwoChoices => {
+                let (first, second) = {
+                    let mut rng = self.rng.lock().unwrap();
+                    let first = rng.gen_range(0..eligible_nodes.len());
+                    let second = if eligible_nodes.len() > 1 {
+                        (first + 1 + rng.gen_range(0..eligible_nodes.len() - 1))
+                            % eligible_nodes.len()
+                    } else {
+                        first
+                    };
+                    (first, second)
+                };
+                let in_flight = |id: &String| {
+                    state
+                        .nodes
+                        .get(id)
+                        .map(|n| n.metrics.active_connections)
+                        .unwrap_or(usize::MAX)
+                };
+                if in_flight(&eligible_nodes[second]) < in_flight(&eligible_nodes[first]) {
+                    eligible_nodes[second].clone()
+                } else {
+                    eligible_nodes[first].clone()
+                }
             }
-        }
+        };
 
         if let Some(node_state) = state.nodes.get(&selected_node_id) {
             Ok(node_state.node.clone())
@@ -441,9 +657,11 @@ impl LoadBalancer {
             health_check_failures: 0,
             weight: 1.0,
             active_connections: HashMap::new(),
+            ejected_at: None,
             node: node.clone(),
         };
 
+        state.hash_ring.add(&node.id);
         state.nodes.insert(node.id.clone(), node_state);
         Ok(())
     }
@@ -461,6 +679,7 @@ impl LoadBalancer {
 
         // Remove node
         state.nodes.remove(node_id);
+        state.hash_ring.remove(node_id);
 
         // Clear session affinity entries
         state.session_affinity_map.retain(|_, v| v != node_id);
@@ -599,6 +818,9 @@ impl LoadBalancer {
             average_load,
             peak_load,
             distribution_efficiency,
+            affinity_hits: state.affinity_hits,
+            affinity_misses: state.affinity_misses,
+            affinity_reassignments: state.affinity_reassignments,
         }
     }
 
@@ -650,6 +872,8 @@ impl LoadBalancer {
             active_nodes,
             total_requests: state.total_requests,
             nodes,
+            ejections: state.ejections,
+            recoveries: state.recoveries,
         }
     }
 
@@ -700,8 +924,10 @@ impl LoadBalancer {
             node_state.metrics.error_rate = (node_state.metrics.error_rate * 0.9) + 0.1; // Exponential moving average
 
             // Check if we should open circuit breaker
-            if node_state.metrics.error_rate > 0.5 {
-                node_state.node.status = NodeStatus::CircuitOpen;
+            if node_state.metrics.error_rate > 0.5 && !node_state.is_ejected() {
+                node_state.eject(NodeStatus::CircuitOpen);
+                state.ejections += 1;
+                tracing::warn!("Circuit opened for node {}", node_id);
             }
 
             Ok(())
@@ -717,8 +943,10 @@ impl LoadBalancer {
         let mut state = self.state.write().await;
 
         if let Some(node_state) = state.nodes.get_mut(node_id) {
-            node_state.node.status = NodeStatus::Unhealthy;
-            // Log the reason (in real implementation)
+            if !node_state.is_ejected() {
+                state.ejections += 1;
+            }
+            node_state.eject(NodeStatus::Unhealthy);
             tracing::warn!("Node {} marked unhealthy: {}", node_id, reason);
             Ok(())
         } else {
@@ -733,17 +961,8 @@ impl LoadBalancer {
         let mut state = self.state.write().await;
 
         if let Some(node_state) = state.nodes.get_mut(node_id) {
-            if is_healthy {
-                node_state.health_check_failures = 0;
-                if node_state.node.status == NodeStatus::Unhealthy {
-                    node_state.node.status = NodeStatus::Healthy;
-                }
-            } else {
-                node_state.health_check_failures += 1;
-                if node_state.health_check_failures >= 3 {
-                    node_state.node.status = NodeStatus::Unhealthy;
-                }
-            }
+            let transition = node_state.apply_health_check(is_healthy, &self.config);
+            Self::record_transition(&mut state, node_id, transition);
             Ok(())
         } else {
             Err(LoadBalancerError::NodeNotFound {
@@ -757,21 +976,17 @@ impl LoadBalancer {
         let mut state = self.state.write().await;
 
         if let Some(node_state) = state.nodes.get_mut(node_id) {
-            // Mock health check
-            let is_healthy = rand::random::<f64>() > 0.05; // 95% success rate
-
-            if is_healthy {
-                node_state.health_check_failures = 0;
-                if node_state.node.status == NodeStatus::Unhealthy {
-                    node_state.node.status = NodeStatus::Healthy;
-                }
-            } else {
-                node_state.health_check_failures += 1;
-                if node_state.health_check_failures >= 3 {
-                    node_state.node.status = NodeStatus::Unhealthy;
-                }
+            // Ejected nodes past their cooldown get a half-open probe
+            if node_state.is_ejected()
+                && node_state
+                    .cooldown_elapsed(Duration::from_secs(self.config.ejection_cooldown_secs))
+            {
+                node_state.node.status = NodeStatus::CircuitHalfOpen;
             }
 
+            // Mock health check
+            let is_healthy = rand::random::<f64>() > 0.05; // 95% success rate
+            let transition = node_state.apply_health_check(is_healthy, &self.config);
             node_state.metrics.last_health_check = Instant::now();
 
             // Update mock metrics
@@ -787,10 +1002,25 @@ impl LoadBalancer {
             node_state.metrics.gpu_usage = node_state.metrics.gpu_usage_percent / 100.0;
             node_state.metrics.queue_depth = (rand::random::<f64>() * 100.0) as usize;
             node_state.metrics.request_success_rate = 1.0 - node_state.metrics.error_rate;
+            Self::record_transition(&mut state, node_id, transition);
         }
 
         Ok(())
     }
+
+    fn record_transition(state: &mut BalancerState, node_id: &str, transition: HealthTransition) {
+        match transition {
+            HealthTransition::Ejected => {
+                state.ejections += 1;
+                tracing::warn!("Node {} ejected after failed health checks", node_id);
+            }
+            HealthTransition::Recovered => {
+                state.recoveries += 1;
+                tracing::info!("Node {} recovered and re-admitted", node_id);
+            }
+            HealthTransition::Unchanged => {}
+        }
+    }
 }
 
 impl Clone for LoadBalancer {
@@ -798,6 +1028,7 @@ impl Clone for LoadBalancer {
         Self {
             config: self.config.clone(),
             state: self.state.clone(),
+            rng: self.rng.clone(),
         }
     }
 }
@@ -7,6 +7,8 @@ pub mod caching;
 pub mod gpu_management;
 pub mod load_balancing;
 
+use std::sync::Arc;
+
 // Re-export GPU management types
 pub use gpu_management::{
     AllocationStrategy, GpuAllocation, GpuCapabilities, GpuConfig, GpuDevice, GpuError, GpuManager,
@@ -15,8 +17,8 @@ pub use gpu_management::{
 
 // Re-export batching types
 pub use batching::{
-    Batch, BatchConfig, BatchError, BatchMetrics, BatchPriority, BatchProcessor, BatchRequest,
-    BatchResult, BatchStatus, BatchingStrategy, PaddingStrategy, QueueConfig,
+    Batch, BatchConfig, BatchError, BatchMemoryModel, BatchMetrics, BatchPriority, BatchProcessor,
+    BatchRequest, BatchResult, BatchStatus, BatchingStrategy, PaddingStrategy, QueueConfig,
 };
 
 // Re-export caching types
@@ -76,7 +78,7 @@ impl Default for PerformanceConfig {
 
 // Performance optimizer that coordinates all components
 pub struct PerformanceOptimizer {
-    gpu_manager: GpuManager,
+    gpu_manager: Arc<GpuManager>,
     batch_processor: BatchProcessor,
     inference_cache: InferenceCache,
     load_balancer: LoadBalancer,
@@ -85,8 +87,15 @@ pub struct PerformanceOptimizer {
 
 impl PerformanceOptimizer {
     pub async fn new(config: PerformanceConfig) -> anyhow::Result<Self> {
-        let gpu_manager = GpuManager::new(config.gpu_config.clone()).await?;
-        let batch_processor = BatchProcessor::new(config.batch_config.clone()).await?;
+        let gpu_manager = Arc::new(GpuManager::new(config.gpu_config.clone()).await?);
+        let mut batch_processor = BatchProcessor::new(config.batch_config.clone()).await?;
+        if let Some(&device_id) = config.gpu_config.gpu_device_ids.first() {
+            batch_processor = batch_processor.with_gpu_manager(
+                gpu_manager.clone(),
+                device_id,
+                BatchMemoryModel::default(),
+            );
+        }
         let inference_cache = InferenceCache::new(config.cache_config.clone()).await?;
         let load_balancer = LoadBalancer::new(config.load_balancer_config.clone(), vec![]).await?;
 
@@ -14,6 +14,22 @@ pub enum VerificationMethod {
     ConsistencyCheck,
     FormatValidation,
     SemanticSimilarity,
+    /// ROUGE-L F1 (longest common token subsequence) against a reference answer
+    RougeL,
+    /// 1.0 if the normalized output equals the normalized reference, else 0.0
+    ExactMatch,
+}
+
+impl VerificationMethod {
+    /// Scores `output` against `reference` for the reference-based methods;
+    /// None for methods that don't compare against a reference answer
+    pub fn reference_score(&self, output: &str, reference: &str) -> Option<f64> {
+        match self {
+            VerificationMethod::RougeL => Some(rouge_l_score(output, reference)),
+            VerificationMethod::ExactMatch => Some(exact_match_score(output, reference)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +71,20 @@ pub struct QualityScore {
     pub format_component: f64,
 }
 
+impl QualityScore {
+    /// Quality score driven by a reference-based verification. Consistency
+    /// and format aren't measured against a reference, so they stay neutral
+    /// (1.0) and the overall score equals the accuracy component.
+    pub fn from_reference_result(result: &VerificationResult) -> Self {
+        Self {
+            overall_score: result.accuracy_score,
+            accuracy_component: result.accuracy_score,
+            consistency_component: 1.0,
+            format_component: 1.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SamplingStrategy {
     Random,
@@ -228,6 +258,52 @@ impl AccuracyVerifier {
         }
     }
 
+    /// Scores `output` against a reference answer with the reference-based
+    /// methods in the config (`RougeL`, `ExactMatch`; ROUGE-L if neither is
+    /// configured). The first configured method decides `accuracy_score`
+    /// and `is_accurate` (score >= `accuracy_threshold`); every computed
+    /// score is listed in `details`.
+    pub async fn verify_against_reference(
+        &self,
+        output: &str,
+        reference: &str,
+    ) -> VerificationResult {
+        let mut scores: Vec<(VerificationMethod, f64)> = self
+            .config
+            .verification_methods
+            .iter()
+            .filter_map(|m| m.reference_score(output, reference).map(|s| (m.clone(), s)))
+            .collect();
+        if scores.is_empty() {
+            scores.push((VerificationMethod::RougeL, rouge_l_score(output, reference)));
+        }
+
+        let details = scores
+            .iter()
+            .map(|(method, score)| format!("{:?}={:.4}", method, score))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (method_used, accuracy_score) = scores.swap_remove(0);
+
+        let result = VerificationResult {
+            job_id: "".to_string(),
+            is_accurate: accuracy_score >= self.config.accuracy_threshold,
+            accuracy_score,
+            // Text-overlap scores are deterministic
+            confidence: 1.0,
+            method_used,
+            timestamp: Utc::now(),
+            details: Some(details),
+        };
+
+        if self.config.store_results {
+            let mut results = self.verification_results.lock().await;
+            results.push(result.clone());
+        }
+
+        result
+    }
+
     pub async fn check_consistency(
         &self,
         prompt: &str,
@@ -575,3 +651,65 @@ impl AccuracyVerifier {
         hash
     }
 }
+
+/// SQuAD-style answer normalization: lowercase, punctuation removed,
+/// articles dropped, whitespace collapsed
+fn normalize_answer(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && !matches!(*w, "a" | "an" | "the"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 1.0 if output and reference are identical after normalization, else 0.0
+pub fn exact_match_score(output: &str, reference: &str) -> f64 {
+    if normalize_answer(output) == normalize_answer(reference) {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// ROUGE-L F1 over lowercase alphanumeric tokens
+pub fn rouge_l_score(output: &str, reference: &str) -> f64 {
+    let tokenize = |text: &str| -> Vec<String> {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let candidate = tokenize(output);
+    let reference = tokenize(reference);
+    if candidate.is_empty() || reference.is_empty() {
+        return if candidate.is_empty() && reference.is_empty() {
+            1.0
+        } else {
+            0.0
+        };
+    }
+
+    // Longest common subsequence length, keeping one DP row
+    let mut row = vec![0usize; reference.len() + 1];
+    for token in &candidate {
+        let mut diagonal = 0;
+        for (j, ref_token) in reference.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if token == ref_token {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    let lcs = row[reference.len()];
+    if lcs == 0 {
+        return 0.0;
+    }
+
+    let precision = lcs as f64 / candidate.len() as f64;
+    let recall = lcs as f64 / reference.len() as f64;
+    2.0 * precision * recall / (precision + recall)
+}
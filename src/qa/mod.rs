@@ -8,18 +8,18 @@ pub mod uptime;
 // Re-export main types and traits for convenience
 pub use uptime::{
     DowntimeEvent, HistoricalUptime, ServiceStatus, UptimeAlert, UptimeConfig, UptimeError,
-    UptimeMetrics, UptimeTracker,
+    UptimeMetrics, UptimeSnapshot, UptimeTracker,
 };
 
 pub use response_time::{
     LatencyBucket, MetricsAggregation, ModelPerformance, PerformanceAlert, ResponseMetrics,
-    ResponseTimeConfig, ResponseTimeError, ResponseTimeTracker,
+    ResponseTimeConfig, ResponseTimeError, ResponseTimeTracker, MODEL_EXPORT_PERCENTILES,
 };
 
 pub use accuracy::{
-    AccuracyAlert, AccuracyError, AccuracyMetrics, AccuracyVerifier, ConsistencyCheck,
-    QualityScore, SamplingStrategy, ValidationRule, VerificationConfig, VerificationMethod,
-    VerificationResult,
+    exact_match_score, rouge_l_score, AccuracyAlert, AccuracyError, AccuracyMetrics,
+    AccuracyVerifier, ConsistencyCheck, QualityScore, SamplingStrategy, ValidationRule,
+    VerificationConfig, VerificationMethod, VerificationResult,
 };
 
 pub use ratings::{
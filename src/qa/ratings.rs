@@ -34,6 +34,10 @@ pub struct RatingsConfig {
     pub allow_anonymous: bool,
     pub require_verification: bool,
     pub decay_period_days: u32,
+    /// Half-life, in days, of the exponential time decay applied when
+    /// aggregating ratings. `None` weights every rating equally.
+    #[serde(default)]
+    pub decay_half_life_days: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +83,11 @@ pub struct RatingAggregation {
     pub total_ratings: u32,
     pub average_by_category: HashMap<RatingCategory, f64>,
     pub recent_trend: f64,
+    /// Decay-weighted average of the overall ratings
+    pub weighted_average: f64,
+    /// Sum of the decay weights, i.e. how many "fresh" ratings the
+    /// aggregation is worth
+    pub effective_count: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -292,14 +301,18 @@ impl RatingsManager {
         }
 
         let rating_count = host_rating_data.len() as u32;
-        let sum_ratings: u32 = host_rating_data.iter().map(|r| r.overall_rating).sum();
-        let average_rating = sum_ratings as f64 / rating_count as f64;
+        let now = Utc::now();
+        let (average_rating, effective_count) =
+            self.weighted_average(&host_rating_data, now, |r| Some(r.overall_rating));
 
         let current_reputation = self.get_host_reputation(host_id).await;
 
-        // Calculate reputation change based on ratings
+        // Calculate reputation change based on ratings. This equals
+        // factor * sum(w_i * (r_i - 3)), so it only ever increases when any
+        // single rating increases, and decay shrinks old ratings' pull
+        // towards zero without flipping its sign.
         let reputation_change = if rating_count >= self.config.minimum_ratings_for_impact {
-            (average_rating - 3.0) * self.config.reputation_impact_factor * rating_count as f64
+            (average_rating - 3.0) * self.config.reputation_impact_factor * effective_count
         } else {
             0.0
         };
@@ -414,39 +427,10 @@ impl RatingsManager {
     }
 
     pub async fn get_rating_trend(&self, model_id: &str, days: u32) -> RatingTrend {
-        let recent_window = Duration::days(days as i64 / 2);
-        let older_window = Duration::days(days as i64);
-
-        let recent_ratings = self.get_recent_ratings(model_id, recent_window).await;
-        let all_ratings = self.get_recent_ratings(model_id, older_window).await;
-
-        let recent_avg = if !recent_ratings.is_empty() {
-            recent_ratings.iter().map(|r| r.overall_rating).sum::<u32>() as f64
-                / recent_ratings.len() as f64
+        let trend_percentage = if self.config.decay_half_life_days.is_some() {
+            self.decayed_trend(model_id, days).await
         } else {
-            0.0
-        };
-
-        let older_ratings: Vec<_> = all_ratings
-            .iter()
-            .filter(|r| {
-                !recent_ratings
-                    .iter()
-                    .any(|recent| recent.job_id == r.job_id)
-            })
-            .collect();
-
-        let older_avg = if !older_ratings.is_empty() {
-            older_ratings.iter().map(|r| r.overall_rating).sum::<u32>() as f64
-                / older_ratings.len() as f64
-        } else {
-            recent_avg
-        };
-
-        let trend_percentage = if older_avg > 0.0 {
-            ((recent_avg - older_avg) / older_avg) * 100.0
-        } else {
-            0.0
+            self.windowed_trend(model_id, days).await
         };
 
         RatingTrend {
@@ -457,6 +441,61 @@ impl RatingsManager {
         }
     }
 
+    /// Aggregates a model's ratings with exponential time decay applied, see
+    /// `decay_weight` for the formula
+    pub async fn get_rating_aggregation(&self, model_id: &str, days: u32) -> RatingAggregation {
+        let recent_trend = self.get_rating_trend(model_id, days).await;
+
+        let ratings = self.ratings.lock().await;
+        let model_ratings = self.model_ratings.lock().await;
+        let rating_ids = model_ratings.get(model_id).cloned().unwrap_or_default();
+        let model_rating_data: Vec<_> =
+            rating_ids.iter().filter_map(|id| ratings.get(id)).collect();
+
+        let now = Utc::now();
+        let mut average_by_category = HashMap::new();
+        for category in &self.config.categories {
+            let (average, weight) = self.weighted_average(&model_rating_data, now, |r| {
+                match r.category_ratings.get(category) {
+                    Some(&rating) => Some(rating),
+                    None if *category == RatingCategory::Overall => Some(r.overall_rating),
+                    None => None,
+                }
+            });
+            if weight > 0.0 {
+                average_by_category.insert(category.clone(), average);
+            }
+        }
+
+        let (weighted_average, effective_count) =
+            self.weighted_average(&model_rating_data, now, |r| Some(r.overall_rating));
+
+        RatingAggregation {
+            total_ratings: model_rating_data.len() as u32,
+            average_by_category,
+            recent_trend: recent_trend.trend_percentage,
+            weighted_average,
+            effective_count,
+        }
+    }
+
+    /// Weight of a rating made at `timestamp` when evaluated at `now`:
+    ///
+    /// `w = 0.5 ^ (age_days / half_life_days)`
+    ///
+    /// so a rating counts half as much after one half-life and a quarter after
+    /// two. Ratings from the future count fully, and without a configured
+    /// half-life every rating has weight 1.
+    pub fn decay_weight(&self, timestamp: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+        match self.config.decay_half_life_days {
+            Some(half_life) if half_life > 0.0 => {
+                let age_days = (now - timestamp).num_seconds().max(0) as f64 / 86_400.0;
+                0.5_f64.powf(age_days / half_life)
+            }
+            _ => 1.0,
+        }
+    }
+
     pub async fn subscribe_to_alerts(&self) -> broadcast::Receiver<RatingAlert> {
         self.alert_sender.subscribe()
     }
@@ -537,6 +576,92 @@ impl RatingsManager {
         Ok(())
     }
 
+    async fn windowed_trend(&self, model_id: &str, days: u32) -> f64 {
+        let recent_window = Duration::days(days as i64 / 2);
+        let older_window = Duration::days(days as i64);
+
+        let recent_ratings = self.get_recent_ratings(model_id, recent_window).await;
+        let all_ratings = self.get_recent_ratings(model_id, older_window).await;
+
+        let recent_avg = if !recent_ratings.is_empty() {
+            recent_ratings.iter().map(|r| r.overall_rating).sum::<u32>() as f64
+                / recent_ratings.len() as f64
+        } else {
+            0.0
+        };
+
+        let older_ratings: Vec<_> = all_ratings
+            .iter()
+            .filter(|r| {
+                !recent_ratings
+                    .iter()
+                    .any(|recent| recent.job_id == r.job_id)
+            })
+            .collect();
+
+        let older_avg = if !older_ratings.is_empty() {
+            older_ratings.iter().map(|r| r.overall_rating).sum::<u32>() as f64
+                / older_ratings.len() as f64
+        } else {
+            recent_avg
+        };
+
+        if older_avg > 0.0 {
+            ((recent_avg - older_avg) / older_avg) * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Compares the decayed average now with the decayed average as it stood
+    /// half way through the period, using only ratings inside the period
+    async fn decayed_trend(&self, model_id: &str, days: u32) -> f64 {
+        let now = Utc::now();
+        let midpoint = now - Duration::days(days as i64 / 2);
+        let period = self
+            .get_recent_ratings(model_id, Duration::days(days as i64))
+            .await;
+
+        let all: Vec<_> = period.iter().collect();
+        let earlier: Vec<_> = period.iter().filter(|r| r.timestamp <= midpoint).collect();
+
+        let (current_avg, current_weight) =
+            self.weighted_average(&all, now, |r| Some(r.overall_rating));
+        let (earlier_avg, earlier_weight) =
+            self.weighted_average(&earlier, midpoint, |r| Some(r.overall_rating));
+
+        if current_weight == 0.0 || earlier_weight == 0.0 || earlier_avg == 0.0 {
+            return 0.0;
+        }
+
+        ((current_avg - earlier_avg) / earlier_avg) * 100.0
+    }
+
+    /// Returns the decay-weighted average of `value` and the total weight of
+    /// the ratings that had one
+    fn weighted_average(
+        &self,
+        ratings: &[&UserRating],
+        now: DateTime<Utc>,
+        value: impl Fn(&UserRating) -> Option<u32>,
+    ) -> (f64, f64) {
+        let mut weighted_sum = 0.0;
+        let mut total_weight = 0.0;
+        for rating in ratings {
+            if let Some(v) = value(rating) {
+                let weight = self.decay_weight(rating.timestamp, now);
+                weighted_sum += weight * v as f64;
+                total_weight += weight;
+            }
+        }
+
+        if total_weight > 0.0 {
+            (weighted_sum / total_weight, total_weight)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
     fn validate_rating(&self, rating: &UserRating) -> Result<(), RatingsError> {
         // Check rating range
         if rating.overall_rating < self.config.min_rating
@@ -1,9 +1,13 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
+use crate::monitoring::metrics::{
+    Histogram, Metric, MetricLabel, MetricType, MetricValue, MetricsExporter, PrometheusExporter,
+};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 use tokio::sync::{broadcast, Mutex};
 
@@ -18,6 +22,9 @@ pub struct ResponseTimeConfig {
     pub export_interval_sec: u64,
 }
 
+/// Percentiles exported per model by `export_model_prometheus`
+pub const MODEL_EXPORT_PERCENTILES: [f64; 2] = [95.0, 99.0];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseMetrics {
     pub count: u64,
@@ -113,6 +120,7 @@ pub struct ResponseTimeTracker {
     responses: Arc<Mutex<VecDeque<ResponseRecord>>>,
     alert_sender: broadcast::Sender<PerformanceAlert>,
     baselines: Arc<Mutex<HashMap<String, ResponseMetrics>>>,
+    model_histograms: Arc<Mutex<HashMap<String, Histogram>>>,
 }
 
 impl ResponseTimeTracker {
@@ -124,6 +132,7 @@ impl ResponseTimeTracker {
             responses: Arc::new(Mutex::new(VecDeque::new())),
             alert_sender,
             baselines: Arc::new(Mutex::new(HashMap::new())),
+            model_histograms: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -143,6 +152,17 @@ impl ResponseTimeTracker {
             duration_ms,
         };
 
+        if self.config.track_by_model {
+            let histogram = self
+                .model_histograms
+                .lock()
+                .await
+                .entry(model.to_string())
+                .or_insert_with(|| self.new_model_histogram())
+                .clone();
+            histogram.observe(duration_ms as f64).await;
+        }
+
         let mut responses = self.responses.lock().await;
         responses.push_back(record);
 
@@ -150,6 +170,7 @@ impl ResponseTimeTracker {
         while responses.len() > self.config.sliding_window_size {
             responses.pop_front();
         }
+        drop(responses);
 
         // Check for alerts
         self.check_performance_alerts().await;
@@ -349,9 +370,78 @@ impl ResponseTimeTracker {
             metrics.average_ms * metrics.count as f64
         ));
 
+        if let Ok(per_model) = self.export_model_prometheus().await {
+            output.push_str(&per_model);
+        }
+
         output
     }
 
+    /// Returns `(percentile, latency_ms)` pairs for a model, with percentiles
+    /// given on the same 0-100 scale as `ResponseTimeConfig::percentiles`.
+    /// Models with no recorded responses report 0.0 for every percentile.
+    pub async fn percentiles(&self, model: &str, percentiles: &[f64]) -> Vec<(f64, f64)> {
+        let histogram = self.model_histograms.lock().await.get(model).cloned();
+        let Some(histogram) = histogram else {
+            return percentiles.iter().map(|&p| (p, 0.0)).collect();
+        };
+
+        let quantiles: Vec<f64> = percentiles.iter().map(|p| p / 100.0).collect();
+        histogram
+            .quantiles(&quantiles)
+            .await
+            .into_iter()
+            .zip(percentiles)
+            .map(|((_, value), &p)| (p, value))
+            .collect()
+    }
+
+    /// Builds one summary metric per tracked model carrying the p95/p99
+    /// latencies, labelled with `model`
+    pub async fn model_metrics(&self) -> Vec<Metric> {
+        let histograms: Vec<(String, Histogram)> = {
+            let histograms = self.model_histograms.lock().await;
+            let mut entries: Vec<_> = histograms
+                .iter()
+                .map(|(model, histogram)| (model.clone(), histogram.clone()))
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            entries
+        };
+
+        let quantiles: Vec<f64> = MODEL_EXPORT_PERCENTILES.iter().map(|p| p / 100.0).collect();
+        let mut metrics = Vec::with_capacity(histograms.len());
+        for (model, histogram) in histograms {
+            let stats = histogram.get_statistics().await;
+            metrics.push(Metric {
+                name: "response_time_model_milliseconds".to_string(),
+                help: "Response time percentiles per model".to_string(),
+                metric_type: MetricType::Summary,
+                value: MetricValue::Summary {
+                    quantiles: histogram.quantiles(&quantiles).await,
+                    sum: stats.sum,
+                    count: stats.count,
+                },
+                labels: vec![MetricLabel {
+                    name: "model".to_string(),
+                    value: model,
+                }],
+                timestamp: Utc::now(),
+                last_updated: Instant::now(),
+            });
+        }
+
+        metrics
+    }
+
+    /// Renders the per-model p95/p99 summaries through `PrometheusExporter`
+    pub async fn export_model_prometheus(&self) -> Result<String, ResponseTimeError> {
+        PrometheusExporter::new()
+            .export(self.model_metrics().await)
+            .await
+            .map_err(|e| ResponseTimeError::ExportError(e.to_string()))
+    }
+
     pub async fn export_json_format(&self) -> Result<String, ResponseTimeError> {
         let metrics = self.get_current_metrics().await;
         let distribution = self.get_latency_distribution().await;
@@ -402,6 +492,14 @@ impl ResponseTimeTracker {
         })
     }
 
+    fn new_model_histogram(&self) -> Histogram {
+        Histogram::new(
+            "response_time_model_milliseconds",
+            "Response time per model",
+            self.config.buckets_ms.iter().map(|&b| b as f64).collect(),
+        )
+    }
+
     async fn check_performance_alerts(&self) {
         let metrics = self.get_current_metrics().await;
 
@@ -3,6 +3,7 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::{broadcast, Mutex};
@@ -22,6 +23,30 @@ pub struct UptimeConfig {
     pub rolling_window_hours: u32,
     pub persist_metrics: bool,
     pub persistence_path: String,
+    /// Snapshot file for downtime events, rollup windows and the last
+    /// heartbeat. When set, the tracker rewrites it as state changes and
+    /// `UptimeTracker::load` restores from it; takes precedence over
+    /// `persistence_path`.
+    #[serde(default)]
+    pub persist_path: Option<PathBuf>,
+}
+
+/// Reason recorded for downtime inferred across a restart
+pub const RESTART_DOWNTIME_REASON: &str = "node offline across restart";
+
+/// On-disk state of an `UptimeTracker`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeSnapshot {
+    pub saved_at: DateTime<Utc>,
+    pub tracking_start: Option<DateTime<Utc>>,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    pub status: ServiceStatus,
+    pub downtime_events: Vec<DowntimeEvent>,
+    #[serde(default)]
+    pub recovery_events: Vec<RecoveryEvent>,
+    /// Cached uptime per whole-hour rollup window
+    #[serde(default)]
+    pub rollups: Vec<UptimeDataPoint>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,6 +165,14 @@ impl UptimeTracker {
         }
     }
 
+    /// Creates a tracker and restores any persisted snapshot, recording the
+    /// time the node was down across the restart as a downtime event
+    pub async fn load(config: UptimeConfig) -> Result<Self, UptimeError> {
+        let tracker = Self::new(config);
+        tracker.load_metrics().await?;
+        Ok(tracker)
+    }
+
     pub async fn start_tracking(&self) -> Result<(), UptimeError> {
         let mut start_time = self.start_time.lock().await;
         *start_time = Some(Utc::now());
@@ -165,7 +198,10 @@ impl UptimeTracker {
 
         let mut status = self.current_status.lock().await;
         *status = ServiceStatus::Online;
+        drop(status);
+        drop(last_heartbeat);
 
+        self.persist_snapshot().await;
         Ok(())
     }
 
@@ -205,8 +241,8 @@ impl UptimeTracker {
     }
 
     pub async fn record_downtime_event(&self, event: DowntimeEvent) -> Result<(), UptimeError> {
-        let mut events = self.downtime_events.lock().await;
-        events.push(event);
+        self.downtime_events.lock().await.push(event);
+        self.persist_snapshot().await;
         Ok(())
     }
 
@@ -329,31 +365,133 @@ impl UptimeTracker {
             .collect()
     }
 
+    /// File that `save_metrics` / `load_metrics` use, if persistence is on
+    fn snapshot_path(&self) -> Option<&Path> {
+        match self.config.persist_path {
+            Some(ref path) => Some(path.as_path()),
+            None if self.config.persist_metrics => Some(Path::new(&self.config.persistence_path)),
+            None => None,
+        }
+    }
+
+    pub async fn snapshot(&self) -> UptimeSnapshot {
+        let rollups = {
+            let percentages = self.uptime_percentages.lock().await;
+            let now = Utc::now();
+            let mut rollups: Vec<UptimeDataPoint> = percentages
+                .iter()
+                .filter(|(window, _)| **window == Duration::hours(window.num_hours()))
+                .map(|(window, uptime)| UptimeDataPoint {
+                    timestamp: now,
+                    uptime_percentage: *uptime,
+                    window_hours: window.num_hours() as u32,
+                })
+                .collect();
+            rollups.sort_by_key(|r| r.window_hours);
+            rollups
+        };
+
+        UptimeSnapshot {
+            saved_at: Utc::now(),
+            tracking_start: *self.start_time.lock().await,
+            last_heartbeat: *self.last_heartbeat.lock().await,
+            status: self.current_status.lock().await.clone(),
+            downtime_events: self.downtime_events.lock().await.clone(),
+            recovery_events: self.recovery_events.lock().await.clone(),
+            rollups,
+        }
+    }
+
     pub async fn save_metrics(&self) -> Result<(), UptimeError> {
-        if !self.config.persist_metrics {
+        let Some(path) = self.snapshot_path() else {
             return Ok(());
-        }
+        };
 
-        let events = self.downtime_events.lock().await;
-        let data = serde_json::to_string_pretty(&*events)?;
-        tokio::fs::write(&self.config.persistence_path, data).await?;
+        let data = serde_json::to_string_pretty(&self.snapshot().await)?;
+        // Write then rename so a crash mid-write never leaves a truncated file
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, data).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
 
         Ok(())
     }
 
     pub async fn load_metrics(&self) -> Result<(), UptimeError> {
-        if !self.config.persist_metrics {
+        let Some(path) = self.snapshot_path() else {
             return Ok(());
-        }
+        };
 
-        match tokio::fs::read_to_string(&self.config.persistence_path).await {
-            Ok(data) => {
+        let data = match tokio::fs::read_to_string(path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()), // File doesn't exist yet
+            Err(e) => return Err(e.into()),
+        };
+
+        match serde_json::from_str::<UptimeSnapshot>(&data) {
+            Ok(snapshot) => self.restore_snapshot(snapshot, Utc::now()).await,
+            Err(_) => {
+                // Files written before snapshots held only the downtime events
                 let events: Vec<DowntimeEvent> = serde_json::from_str(&data)?;
-                let mut stored_events = self.downtime_events.lock().await;
-                *stored_events = events;
-                Ok(())
+                *self.downtime_events.lock().await = events;
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies a persisted snapshot as of `now`: closes any downtime that was
+    /// still open, infers downtime from the last heartbeat to `now`, and
+    /// derives the current status from how recent that heartbeat is
+    pub async fn restore_snapshot(&self, snapshot: UptimeSnapshot, now: DateTime<Utc>) {
+        let threshold_ms = self.config.downtime_threshold_ms;
+        let mut events = snapshot.downtime_events;
+        let mut recoveries = snapshot.recovery_events;
+
+        let open_event = events.iter_mut().find(|e| e.end_time.is_none());
+        if let Some(event) = open_event {
+            event.end_time = Some(now);
+            event.duration_ms = millis_between(event.start_time, now);
+        } else if let Some(gap) = infer_restart_downtime(
+            snapshot.last_heartbeat.unwrap_or(snapshot.saved_at),
+            now,
+            threshold_ms,
+        ) {
+            recoveries.push(RecoveryEvent {
+                timestamp: now,
+                recovery_time_ms: gap.duration_ms,
+                downtime_duration_ms: gap.duration_ms,
+            });
+            events.push(gap);
+        }
+
+        let status = match (snapshot.status, snapshot.last_heartbeat) {
+            (ServiceStatus::Maintenance, _) => ServiceStatus::Maintenance,
+            (_, Some(beat)) if millis_between(beat, now) <= threshold_ms => ServiceStatus::Online,
+            _ => ServiceStatus::Offline,
+        };
+
+        {
+            let mut percentages = self.uptime_percentages.lock().await;
+            for rollup in snapshot.rollups {
+                percentages.insert(
+                    Duration::hours(rollup.window_hours as i64),
+                    rollup.uptime_percentage,
+                );
             }
-            Err(_) => Ok(()), // File doesn't exist yet
+        }
+        *self.start_time.lock().await = snapshot.tracking_start;
+        *self.last_heartbeat.lock().await = snapshot.last_heartbeat;
+        *self.current_status.lock().await = status;
+        *self.downtime_events.lock().await = events;
+        *self.recovery_events.lock().await = recoveries;
+    }
+
+    /// Best-effort snapshot write used after state changes
+    async fn persist_snapshot(&self) {
+        if self.config.persist_path.is_none() {
+            return;
+        }
+        if let Err(e) = self.save_metrics().await {
+            tracing::warn!("Failed to persist uptime snapshot: {}", e);
         }
     }
 
@@ -455,3 +593,23 @@ impl UptimeTracker {
         }
     }
 }
+
+fn millis_between(start: DateTime<Utc>, end: DateTime<Utc>) -> u64 {
+    end.signed_duration_since(start).num_milliseconds().max(0) as u64
+}
+
+/// Downtime between the last heartbeat before a restart and `now`, if it
+/// exceeds the downtime threshold
+fn infer_restart_downtime(
+    last_seen: DateTime<Utc>,
+    now: DateTime<Utc>,
+    threshold_ms: u64,
+) -> Option<DowntimeEvent> {
+    let duration_ms = millis_between(last_seen, now);
+    (duration_ms > threshold_ms).then(|| DowntimeEvent {
+        start_time: last_seen,
+        end_time: Some(now),
+        duration_ms,
+        reason: RESTART_DOWNTIME_REASON.to_string(),
+    })
+}
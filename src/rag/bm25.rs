@@ -0,0 +1,162 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! In-memory BM25 index for lexical retrieval
+//!
+//! Complements vector similarity in `SessionVectorStore`: embeddings blur
+//! exact identifiers, error codes and rare names, which BM25 ranks highly.
+//! Terms are lowercased runs of alphanumerics and underscores, so `snake_case`
+//! identifiers stay whole.
+
+use std::collections::{HashMap, HashSet};
+
+/// Term-frequency saturation
+const K1: f32 = 1.2;
+/// Document-length normalisation
+const B: f32 = 0.75;
+
+#[derive(Debug, Clone)]
+struct IndexedDoc {
+    len: usize,
+    term_freqs: HashMap<String, u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Bm25Index {
+    docs: HashMap<String, IndexedDoc>,
+    doc_freqs: HashMap<String, usize>,
+    total_len: usize,
+}
+
+/// Split text into lowercase index terms
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl Bm25Index {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index `text` under `id`, replacing anything indexed under it before
+    pub fn insert(&mut self, id: &str, text: &str) {
+        self.remove(id);
+
+        let terms = tokenize(text);
+        let mut term_freqs: HashMap<String, u32> = HashMap::new();
+        for term in &terms {
+            *term_freqs.entry(term.clone()).or_default() += 1;
+        }
+        for term in term_freqs.keys() {
+            *self.doc_freqs.entry(term.clone()).or_default() += 1;
+        }
+        self.total_len += terms.len();
+        self.docs.insert(
+            id.to_string(),
+            IndexedDoc {
+                len: terms.len(),
+                term_freqs,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(doc) = self.docs.remove(id) else {
+            return false;
+        };
+        for term in doc.term_freqs.keys() {
+            if let Some(df) = self.doc_freqs.get_mut(term) {
+                *df -= 1;
+                if *df == 0 {
+                    self.doc_freqs.remove(term);
+                }
+            }
+        }
+        self.total_len -= doc.len;
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.docs.clear();
+        self.doc_freqs.clear();
+        self.total_len = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Documents matching at least one query term, best first
+    pub fn search(&self, query: &str, k: usize) -> Vec<(String, f32)> {
+        if self.docs.is_empty() {
+            return Vec::new();
+        }
+        let query_terms: HashSet<String> = tokenize(query).into_iter().collect();
+        let doc_count = self.docs.len() as f32;
+        let avg_len = (self.total_len as f32 / doc_count).max(1.0);
+
+        let mut scored: Vec<(String, f32)> = self
+            .docs
+            .iter()
+            .filter_map(|(id, doc)| {
+                let length_norm = 1.0 - B + B * doc.len as f32 / avg_len;
+                let score: f32 = query_terms
+                    .iter()
+                    .filter_map(|term| {
+                        let tf = *doc.term_freqs.get(term)? as f32;
+                        let df = self.doc_freqs[term] as f32;
+                        let idf = (1.0 + (doc_count - df + 0.5) / (df + 0.5)).ln();
+                        Some(idf * tf * (K1 + 1.0) / (tf + K1 * length_norm))
+                    })
+                    .sum();
+                (score > 0.0).then(|| (id.clone(), score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        scored.truncate(k);
+        scored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rare_terms_rank_first() {
+        let mut index = Bm25Index::new();
+        index.insert(
+            "a",
+            "the cache returned an error while loading the model weights",
+        );
+        index.insert("b", "the request failed with ERR_CONN_RESET");
+        index.insert("c", "the the the model loaded");
+
+        let results = index.search("err_conn_reset error", 10);
+        assert_eq!(results[0].0, "b");
+        assert_eq!(
+            results.len(),
+            2,
+            "docs without query terms are not returned"
+        );
+
+        // Replacing and removing keep document frequencies consistent
+        index.insert("b", "nothing relevant");
+        assert!(index.search("err_conn_reset", 10).is_empty());
+        assert!(index.remove("a"));
+        assert!(!index.remove("a"));
+        assert_eq!(index.len(), 2);
+        assert!(index.search("error", 10).is_empty());
+    }
+}
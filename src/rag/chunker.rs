@@ -0,0 +1,240 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Token-aware document chunking for RAG
+//!
+//! Splits source documents into windows of at most `chunk_size` tokens, as
+//! counted by the embedding model's tokenizer, so no chunk is silently
+//! truncated at embedding time. Adjacent chunks share `overlap` tokens of
+//! context, and a chunk ends on a sentence boundary when one falls in the
+//! back half of its window. Each chunk keeps the char offsets of the span it
+//! covers in the source text, so citations can point back to it.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokenizers::Tokenizer;
+
+/// Chunk sizing, in tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkerConfig {
+    /// Maximum tokens per chunk; keep below the embedding model's max sequence length
+    pub chunk_size: usize,
+    /// Tokens shared between consecutive chunks
+    pub overlap: usize,
+}
+
+impl Default for ChunkerConfig {
+    fn default() -> Self {
+        // all-MiniLM-L6-v2 accepts 256 tokens including [CLS]/[SEP]
+        Self {
+            chunk_size: 200,
+            overlap: 40,
+        }
+    }
+}
+
+impl ChunkerConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.chunk_size == 0 {
+            return Err(anyhow!("chunk_size must be at least 1"));
+        }
+        if self.overlap >= self.chunk_size {
+            return Err(anyhow!(
+                "overlap ({}) must be smaller than chunk_size ({})",
+                self.overlap,
+                self.chunk_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One chunk of a source document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextChunk {
+    pub index: usize,
+    pub text: String,
+    /// Char offset of the chunk's first character in the source text
+    pub start_char: usize,
+    /// Char offset just past the chunk's last character
+    pub end_char: usize,
+    pub token_count: usize,
+}
+
+impl TextChunk {
+    /// Vector metadata locating this chunk in its source document
+    pub fn metadata(&self, document_id: &str) -> Value {
+        json!({
+            "document_id": document_id,
+            "chunk_index": self.index,
+            "text": self.text,
+            "start_char": self.start_char,
+            "end_char": self.end_char,
+            "token_count": self.token_count,
+        })
+    }
+}
+
+/// Chunk `text` using `tokenizer`, ignoring any truncation or padding it is
+/// configured with for embedding
+pub fn chunk_text(
+    tokenizer: &Tokenizer,
+    text: &str,
+    config: &ChunkerConfig,
+) -> Result<Vec<TextChunk>> {
+    config.validate()?;
+
+    let mut tokenizer = tokenizer.clone();
+    tokenizer
+        .with_truncation(None)
+        .map_err(|e| anyhow!("Failed to disable truncation: {}", e))?;
+    tokenizer.with_padding(None);
+
+    let encoding = tokenizer
+        .encode_char_offsets(text, false)
+        .map_err(|e| anyhow!("Tokenization failed: {}", e))?;
+    let offsets: Vec<(usize, usize)> = encoding
+        .get_offsets()
+        .iter()
+        .copied()
+        .filter(|(start, end)| end > start)
+        .collect();
+
+    Ok(chunk_by_offsets(text, &offsets, config))
+}
+
+/// Chunk `text` given the char span of each of its tokens, in order
+pub fn chunk_by_offsets(
+    text: &str,
+    offsets: &[(usize, usize)],
+    config: &ChunkerConfig,
+) -> Vec<TextChunk> {
+    let chars: Vec<char> = text.chars().collect();
+    let chunk_size = config.chunk_size.max(1);
+    let overlap = config.overlap.min(chunk_size - 1);
+
+    // Whether a chunk may end after token `t` without splitting a sentence
+    let ends_sentence = |t: usize| {
+        let (_, end) = offsets[t];
+        let terminated = matches!(chars.get(end - 1), Some('.' | '!' | '?'));
+        let next_start = offsets.get(t + 1).map_or(chars.len(), |&(start, _)| start);
+        let line_break = chars[end.min(next_start)..next_start].contains(&'\n');
+        terminated || line_break
+    };
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < offsets.len() {
+        let mut end = (start + chunk_size).min(offsets.len());
+        if end < offsets.len() {
+            let earliest = start + (chunk_size / 2).max(1);
+            if let Some(boundary) = (earliest..=end).rev().find(|&e| ends_sentence(e - 1)) {
+                end = boundary;
+            }
+        }
+
+        let (start_char, end_char) = (offsets[start].0, offsets[end - 1].1);
+        chunks.push(TextChunk {
+            index: chunks.len(),
+            text: chars[start_char..end_char].iter().collect(),
+            start_char,
+            end_char,
+            token_count: end - start,
+        });
+
+        if end == offsets.len() {
+            break;
+        }
+        start = end.saturating_sub(overlap).max(start + 1);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whitespace "tokenizer": one token per word, punctuation attached
+    fn word_offsets(text: &str) -> Vec<(usize, usize)> {
+        let mut offsets = Vec::new();
+        let mut start = None;
+        for (i, c) in text.chars().enumerate() {
+            match (c.is_whitespace(), start) {
+                (false, None) => start = Some(i),
+                (true, Some(s)) => {
+                    offsets.push((s, i));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            offsets.push((s, text.chars().count()));
+        }
+        offsets
+    }
+
+    #[test]
+    fn test_chunks_overlap_and_end_on_sentences() {
+        let text = "One two three four. Five six seven eight. Nine ten eleven twelve.";
+        let config = ChunkerConfig {
+            chunk_size: 6,
+            overlap: 2,
+        };
+        let chunks = chunk_by_offsets(text, &word_offsets(text), &config);
+
+        assert_eq!(chunks[0].text, "One two three four.");
+        assert_eq!(chunks[0].token_count, 4);
+        // Second chunk starts two tokens back, inside the first
+        assert_eq!(chunks[1].text, "three four. Five six seven eight.");
+        assert_eq!(
+            chunks.last().unwrap().text,
+            "seven eight. Nine ten eleven twelve."
+        );
+
+        for chunk in &chunks {
+            let span: String = text
+                .chars()
+                .skip(chunk.start_char)
+                .take(chunk.end_char - chunk.start_char)
+                .collect();
+            assert_eq!(span, chunk.text);
+            assert!(chunk.token_count <= config.chunk_size);
+        }
+        for pair in chunks.windows(2) {
+            assert!(pair[1].start_char < pair[0].end_char, "chunks must overlap");
+        }
+    }
+
+    #[test]
+    fn test_offsets_are_chars_not_bytes() {
+        let text = "Café crème brûlée. Déjà vu encore.";
+        let config = ChunkerConfig {
+            chunk_size: 3,
+            overlap: 0,
+        };
+        let chunks = chunk_by_offsets(text, &word_offsets(text), &config);
+
+        assert_eq!(chunks[0].text, "Café crème brûlée.");
+        assert_eq!((chunks[0].start_char, chunks[0].end_char), (0, 18));
+        assert_eq!(chunks[1].text, "Déjà vu encore.");
+        assert_eq!(chunks[1].start_char, 19);
+
+        let metadata = chunks[1].metadata("doc-1");
+        assert_eq!(metadata["document_id"], "doc-1");
+        assert_eq!(metadata["chunk_index"], 1);
+        assert_eq!(metadata["start_char"], 19);
+        assert_eq!(metadata["end_char"], 34);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(ChunkerConfig::default().validate().is_ok());
+        let config = ChunkerConfig {
+            chunk_size: 10,
+            overlap: 10,
+        };
+        assert!(config.validate().is_err());
+        assert!(chunk_by_offsets("", &[], &ChunkerConfig::default()).is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
-//! Error types for S5 vector database loading
+//! Error types for S5 vector database loading and session RAG storage
 //!
 //! Comprehensive error handling for vector loading operations including:
 //! - S5 storage errors (download failures, not found)
@@ -90,6 +90,10 @@ pub enum VectorLoadError {
     #[error("Invalid manifest path: {0}")]
     InvalidPath(String),
 
+    /// Loading was cancelled; vectors loaded so far were freed
+    #[error("Loading cancelled after {loaded_vectors} vectors")]
+    Cancelled { loaded_vectors: usize },
+
     /// Generic I/O error
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
@@ -99,6 +103,24 @@ pub enum VectorLoadError {
     Other(String),
 }
 
+/// Errors from session-scoped RAG storage
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RagError {
+    /// A load needs more memory than one session may hold, even after eviction
+    #[error(
+        "Session {session_id} memory exceeded: load needs {required_bytes} bytes, limit is {limit_bytes} bytes"
+    )]
+    SessionMemoryExceeded {
+        session_id: String,
+        required_bytes: usize,
+        limit_bytes: usize,
+    },
+
+    /// The node-wide RAG budget is exhausted by other sessions
+    #[error("RAG memory exhausted across sessions: limit is {limit_bytes} bytes")]
+    GlobalMemoryExceeded { limit_bytes: usize },
+}
+
 // Implement conversion from anyhow::Error for backward compatibility
 impl From<anyhow::Error> for VectorLoadError {
     fn from(err: anyhow::Error) -> Self {
@@ -166,6 +188,7 @@ impl VectorLoadError {
             VectorLoadError::MemoryLimitExceeded { .. } => "MEMORY_LIMIT_EXCEEDED",
             VectorLoadError::Timeout { .. } => "TIMEOUT",
             VectorLoadError::InvalidPath(_) => "INVALID_PATH",
+            VectorLoadError::Cancelled { .. } => "CANCELLED",
             VectorLoadError::IoError(_) => "IO_ERROR",
             VectorLoadError::Other(_) => "OTHER",
         }
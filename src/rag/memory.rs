@@ -0,0 +1,117 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Memory caps for session vector stores
+//!
+//! Each `SessionVectorStore` created with limits stays under a per-session
+//! byte and vector cap by evicting its least recently used entries, and
+//! reserves its bytes from a node-wide `RagMemoryBudget` shared by every
+//! session, so one session loading a huge document cannot exhaust the node.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const RAG_SESSION_MAX_BYTES_ENV: &str = "RAG_SESSION_MAX_BYTES";
+pub const RAG_SESSION_MAX_VECTORS_ENV: &str = "RAG_SESSION_MAX_VECTORS";
+pub const RAG_GLOBAL_MAX_BYTES_ENV: &str = "RAG_GLOBAL_MAX_BYTES";
+
+/// Per-session and node-wide caps on RAG vector storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RagMemoryLimits {
+    /// Bytes one session's store may hold before evicting
+    pub max_session_bytes: usize,
+    /// Vectors one session's store may hold before evicting
+    pub max_session_vectors: usize,
+    /// Bytes all session stores together may hold
+    pub max_global_bytes: usize,
+}
+
+impl Default for RagMemoryLimits {
+    fn default() -> Self {
+        Self {
+            max_session_bytes: 256 * 1024 * 1024,
+            max_session_vectors: 100_000,
+            max_global_bytes: 2 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+impl RagMemoryLimits {
+    /// Defaults overridden by `RAG_SESSION_MAX_BYTES`,
+    /// `RAG_SESSION_MAX_VECTORS` and `RAG_GLOBAL_MAX_BYTES`
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+        };
+        let defaults = Self::default();
+        Self {
+            max_session_bytes: var(RAG_SESSION_MAX_BYTES_ENV).unwrap_or(defaults.max_session_bytes),
+            max_session_vectors: var(RAG_SESSION_MAX_VECTORS_ENV)
+                .unwrap_or(defaults.max_session_vectors),
+            max_global_bytes: var(RAG_GLOBAL_MAX_BYTES_ENV).unwrap_or(defaults.max_global_bytes),
+        }
+    }
+}
+
+/// Node-wide byte budget shared by all session stores
+#[derive(Debug)]
+pub struct RagMemoryBudget {
+    limit_bytes: usize,
+    used_bytes: AtomicUsize,
+}
+
+impl RagMemoryBudget {
+    pub fn new(limit_bytes: usize) -> Self {
+        Self {
+            limit_bytes,
+            used_bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reserve `bytes`, or return false if that would exceed the limit
+    pub fn try_reserve(&self, bytes: usize) -> bool {
+        self.used_bytes
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(bytes)
+                    .filter(|&total| total <= self.limit_bytes)
+            })
+            .is_ok()
+    }
+
+    pub fn release(&self, bytes: usize) {
+        // Saturate rather than wrap if a caller over-releases
+        let _ = self
+            .used_bytes
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                Some(used.saturating_sub(bytes))
+            });
+    }
+
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes.load(Ordering::Acquire)
+    }
+
+    pub fn limit_bytes(&self) -> usize {
+        self.limit_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_reserve_and_release() {
+        let budget = RagMemoryBudget::new(100);
+        assert!(budget.try_reserve(60));
+        assert!(!budget.try_reserve(41));
+        assert!(budget.try_reserve(40));
+        assert_eq!(budget.used_bytes(), 100);
+
+        budget.release(70);
+        assert_eq!(budget.used_bytes(), 30);
+        budget.release(1_000);
+        assert_eq!(budget.used_bytes(), 0);
+    }
+}
@@ -1,10 +1,18 @@
 // RAG (Retrieval-Augmented Generation) module
 // Session-scoped vector storage for semantic search during chat sessions
 
+pub mod bm25;
+pub mod chunker;
 pub mod errors;
+pub mod memory;
+pub mod reranker;
 pub mod session_vector_store;
 pub mod vector_loader;
 
-pub use errors::VectorLoadError;
+pub use bm25::Bm25Index;
+pub use chunker::{chunk_text, ChunkerConfig, TextChunk};
+pub use errors::{RagError, VectorLoadError};
+pub use memory::{RagMemoryBudget, RagMemoryLimits};
+pub use reranker::{rerank, OnnxReranker, Reranker};
 pub use session_vector_store::{SearchResult, SessionVectorStore, VectorEntry};
 pub use vector_loader::{LoadProgress, VectorLoader};
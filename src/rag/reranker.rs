@@ -0,0 +1,251 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Cross-encoder reranking of retrieved results
+//!
+//! Vector search scores each passage independently of the query's wording;
+//! a cross-encoder reads query and passage together and orders the top
+//! candidates far better. Reranking is optional: without a configured model
+//! results keep their retrieval order.
+//!
+//! The ONNX model is any BERT-style cross-encoder exported with
+//! `input_ids` / `attention_mask` / `token_type_ids` inputs and one relevance
+//! logit per pair (e.g. ms-marco-MiniLM-L-6-v2), loaded from a directory
+//! holding `model.onnx` and `tokenizer.json`.
+
+use anyhow::{anyhow, Context, Result};
+use ndarray::Array2;
+use ort::execution_providers::CPUExecutionProvider;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Value as OrtValue;
+use std::path::Path;
+use std::sync::Mutex;
+use tokenizers::{Tokenizer, TruncationParams};
+
+use crate::rag::session_vector_store::{metadata_text, SearchResult};
+
+/// Env var naming the cross-encoder model directory; unset disables reranking
+pub const RERANKER_MODEL_DIR_ENV: &str = "RERANKER_MODEL_DIR";
+
+/// Longest query + passage pair fed to the cross-encoder
+const MAX_PAIR_TOKENS: usize = 512;
+
+/// Scores passages for relevance to a query; higher is more relevant
+pub trait Reranker: Send + Sync {
+    fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f32>>;
+}
+
+/// ONNX cross-encoder, run on CPU like the vision models
+pub struct OnnxReranker {
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+}
+
+impl std::fmt::Debug for OnnxReranker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnnxReranker").finish_non_exhaustive()
+    }
+}
+
+impl OnnxReranker {
+    /// Load `model.onnx` and `tokenizer.json` from `model_dir`
+    pub async fn new<P: AsRef<Path>>(model_dir: P) -> Result<Self> {
+        let model_dir = model_dir.as_ref();
+        let model_path = model_dir.join("model.onnx");
+        let tokenizer_path = model_dir.join("tokenizer.json");
+        if !model_path.exists() {
+            anyhow::bail!("Reranker model file not found: {}", model_path.display());
+        }
+        if !tokenizer_path.exists() {
+            anyhow::bail!("Tokenizer file not found: {}", tokenizer_path.display());
+        }
+
+        let session = Session::builder()
+            .context("Failed to create session builder")?
+            .with_execution_providers([CPUExecutionProvider::default().build()])
+            .context("Failed to set CPU execution provider")?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .context("Failed to set optimization level")?
+            .with_intra_threads(4)
+            .context("Failed to set intra threads")?
+            .commit_from_file(&model_path)
+            .context(format!(
+                "Failed to load reranker model from {}",
+                model_path.display()
+            ))?;
+
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| anyhow!("Failed to load tokenizer: {}", e))?;
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_PAIR_TOKENS,
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow!("Failed to configure truncation: {}", e))?;
+        tokenizer.with_padding(None);
+
+        Ok(Self {
+            session: Mutex::new(session),
+            tokenizer,
+        })
+    }
+}
+
+impl Reranker for OnnxReranker {
+    fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f32>> {
+        if passages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let encodings = passages
+            .iter()
+            .map(|passage| {
+                self.tokenizer
+                    .encode((query, *passage), true)
+                    .map_err(|e| anyhow!("Tokenization failed: {}", e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let batch_size = encodings.len();
+        let max_len = encodings.iter().map(|e| e.len()).max().unwrap_or(0);
+        let mut input_ids = Vec::with_capacity(batch_size * max_len);
+        let mut attention_mask = Vec::with_capacity(batch_size * max_len);
+        let mut token_type_ids = Vec::with_capacity(batch_size * max_len);
+        for encoding in &encodings {
+            let padding = max_len - encoding.len();
+            input_ids.extend(encoding.get_ids().iter().map(|&id| id as i64));
+            input_ids.extend(std::iter::repeat(0i64).take(padding));
+            attention_mask.extend(encoding.get_attention_mask().iter().map(|&m| m as i64));
+            attention_mask.extend(std::iter::repeat(0i64).take(padding));
+            token_type_ids.extend(encoding.get_type_ids().iter().map(|&t| t as i64));
+            token_type_ids.extend(std::iter::repeat(0i64).take(padding));
+        }
+
+        let shape = (batch_size, max_len);
+        let mut session = self.session.lock().unwrap();
+        let outputs = session.run(ort::inputs![
+            "input_ids" => OrtValue::from_array(Array2::from_shape_vec(shape, input_ids)?)?,
+            "attention_mask" => OrtValue::from_array(Array2::from_shape_vec(shape, attention_mask)?)?,
+            "token_type_ids" => OrtValue::from_array(Array2::from_shape_vec(shape, token_type_ids)?)?
+        ])?;
+
+        // Logits are [batch, 1] (or [batch]); one relevance score per pair
+        let logits = outputs[0]
+            .try_extract_array::<f32>()
+            .context("Failed to extract reranker logits")?;
+        let scores: Vec<f32> = logits.iter().copied().collect();
+        if scores.len() != batch_size {
+            anyhow::bail!(
+                "Reranker returned {} scores for {} passages",
+                scores.len(),
+                batch_size
+            );
+        }
+        Ok(scores)
+    }
+}
+
+/// Text a result is judged on: its `text` metadata field, or failing that
+/// every string in its metadata
+fn passage_text(result: &SearchResult) -> String {
+    match result.metadata.get("text").and_then(|t| t.as_str()) {
+        Some(text) => text.to_string(),
+        None => metadata_text(&result.metadata),
+    }
+}
+
+/// Reorder `results` by cross-encoder relevance to `query` and keep the best
+/// `k`. With no reranker or no query text, results keep retrieval order.
+pub fn rerank(
+    reranker: Option<&dyn Reranker>,
+    query: Option<&str>,
+    mut results: Vec<SearchResult>,
+    k: usize,
+) -> Result<Vec<SearchResult>> {
+    let (Some(reranker), Some(query)) = (reranker, query.filter(|q| !q.trim().is_empty())) else {
+        results.truncate(k);
+        return Ok(results);
+    };
+
+    let passages: Vec<String> = results.iter().map(passage_text).collect();
+    let passages: Vec<&str> = passages.iter().map(String::as_str).collect();
+    let scores = reranker.score(query, &passages)?;
+    if scores.len() != results.len() {
+        return Err(anyhow!(
+            "Reranker returned {} scores for {} results",
+            scores.len(),
+            results.len()
+        ));
+    }
+
+    for (result, score) in results.iter_mut().zip(scores) {
+        result.rerank_score = Some(score);
+    }
+    // Stable sort keeps retrieval order among equal scores
+    results.sort_by(|a, b| {
+        b.rerank_score
+            .partial_cmp(&a.rerank_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results.truncate(k);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Scores passages by how many query words they contain
+    struct WordOverlap;
+
+    impl Reranker for WordOverlap {
+        fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f32>> {
+            Ok(passages
+                .iter()
+                .map(|p| query.split_whitespace().filter(|w| p.contains(w)).count() as f32)
+                .collect())
+        }
+    }
+
+    fn result(id: &str, score: f32, text: &str) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            score,
+            metadata: json!({ "text": text }),
+            vector_score: Some(score),
+            lexical_score: None,
+            rerank_score: None,
+        }
+    }
+
+    #[test]
+    fn test_rerank_reorders_and_truncates() {
+        let results = vec![
+            result("a", 0.9, "payments are settled on chain"),
+            result("b", 0.8, "the node streams tokens over websocket"),
+            result("c", 0.7, "tokens stream over websocket per session"),
+        ];
+
+        let reranked = rerank(
+            Some(&WordOverlap),
+            Some("stream tokens websocket"),
+            results.clone(),
+            2,
+        )
+        .unwrap();
+        let ids: Vec<&str> = reranked.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["b", "c"]);
+        assert_eq!(reranked[0].rerank_score, Some(3.0));
+        assert_eq!(reranked[0].vector_score, Some(0.8));
+
+        // Without a model, retrieval order is kept
+        let passthrough = rerank(None, Some("stream tokens"), results.clone(), 2).unwrap();
+        let ids: Vec<&str> = passthrough.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert!(passthrough[0].rerank_score.is_none());
+
+        let no_query = rerank(Some(&WordOverlap), None, results, 3).unwrap();
+        assert_eq!(no_query[0].id, "a");
+    }
+}
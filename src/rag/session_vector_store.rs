@@ -4,14 +4,23 @@
 use anyhow::{anyhow, Result};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Instant;
 
+use crate::rag::bm25::Bm25Index;
+use crate::rag::chunker::TextChunk;
+use crate::rag::errors::RagError;
+use crate::rag::memory::{RagMemoryBudget, RagMemoryLimits};
 use crate::vector::embeddings::Embedding;
 
 /// Maximum metadata size per vector entry (10KB)
 /// Prevents memory exhaustion attacks (100K vectors × 10KB = 1GB max metadata)
 const MAX_METADATA_SIZE: usize = 10 * 1024;
 
+/// Reciprocal rank fusion constant; damps the influence of top ranks
+const RRF_K: f32 = 60.0;
+
 /// Entry stored in the vector store
 #[derive(Clone, Debug)]
 pub struct VectorEntry {
@@ -26,17 +35,51 @@ pub struct SearchResult {
     pub id: String,
     pub score: f32,
     pub metadata: Value,
+    /// Cosine similarity, when the vector index ranked this result
+    pub vector_score: Option<f32>,
+    /// BM25 score, when the lexical index ranked this result
+    pub lexical_score: Option<f32>,
+    /// Cross-encoder relevance, when a reranker reordered this result
+    pub rerank_score: Option<f32>,
+}
+
+/// Estimated bytes and recency of one stored entry
+#[derive(Debug)]
+struct EntryUsage {
+    bytes: usize,
+    last_used: AtomicU64,
+}
+
+/// Caps a store evicts against, and the node-wide budget it reserves from
+#[derive(Debug)]
+struct MemoryLimits {
+    limits: RagMemoryLimits,
+    budget: Arc<RagMemoryBudget>,
 }
 
 /// Session-scoped vector storage
 /// - Stores vectors in memory during active session
 /// - Cleared when session disconnects
 /// - Supports semantic search via cosine similarity
+/// - Keeps a BM25 index over metadata text for hybrid search
+/// - With memory limits, evicts least recently used entries to stay under them
 #[derive(Debug)]
 pub struct SessionVectorStore {
     session_id: String,
     vectors: HashMap<String, VectorEntry>,
+    lexical: Bm25Index,
     max_vectors: usize,
+    usage: HashMap<String, EntryUsage>,
+    used_bytes: usize,
+    /// Logical clock for LRU ordering; bumped on every add and read
+    clock: AtomicU64,
+    memory: Option<MemoryLimits>,
+}
+
+/// Estimated memory an entry occupies in a store
+pub fn estimate_entry_bytes(id: &str, vector: &[f32], metadata: &Value) -> usize {
+    let metadata_bytes = serde_json::to_string(metadata).map_or(0, |json| json.len());
+    id.len() + std::mem::size_of_val(vector) + metadata_bytes
 }
 
 impl SessionVectorStore {
@@ -49,10 +92,32 @@ impl SessionVectorStore {
         Self {
             session_id,
             vectors: HashMap::new(),
+            lexical: Bm25Index::new(),
             max_vectors,
+            usage: HashMap::new(),
+            used_bytes: 0,
+            clock: AtomicU64::new(0),
+            memory: None,
         }
     }
 
+    /// Cap this store's memory, evicting least recently used entries instead
+    /// of rejecting adds once a cap is reached
+    ///
+    /// # Arguments
+    /// * `limits` - Per-session byte and vector caps; the vector cap also
+    ///   lowers `max_vectors`
+    /// * `budget` - Node-wide budget shared with every other session's store
+    pub fn with_memory_limits(
+        mut self,
+        limits: RagMemoryLimits,
+        budget: Arc<RagMemoryBudget>,
+    ) -> Self {
+        self.max_vectors = self.max_vectors.min(limits.max_session_vectors);
+        self.memory = Some(MemoryLimits { limits, budget });
+        self
+    }
+
     /// Add vector to store
     ///
     /// # Arguments
@@ -90,8 +155,32 @@ impl SessionVectorStore {
             ));
         }
 
-        // Check capacity (unless replacing existing)
-        if !self.vectors.contains_key(&id) && self.vectors.len() >= self.max_vectors {
+        let bytes = id.len() + std::mem::size_of_val(vector.as_slice()) + metadata_size;
+        if let Some((limits, budget)) = self
+            .memory
+            .as_ref()
+            .map(|m| (m.limits, Arc::clone(&m.budget)))
+        {
+            // Evict to make room; only an entry bigger than the whole cap fails
+            self.check_load(bytes)?;
+            self.delete(&id);
+            while self.vectors.len() >= self.max_vectors
+                || self.used_bytes + bytes > limits.max_session_bytes
+            {
+                if !self.evict_lru() {
+                    break;
+                }
+            }
+            while !budget.try_reserve(bytes) {
+                if !self.evict_lru() {
+                    return Err(RagError::GlobalMemoryExceeded {
+                        limit_bytes: budget.limit_bytes(),
+                    }
+                    .into());
+                }
+            }
+        } else if !self.vectors.contains_key(&id) && self.vectors.len() >= self.max_vectors {
+            // Check capacity (unless replacing existing)
             return Err(anyhow!(
                 "Maximum vector capacity reached: {} vectors (max: {})",
                 self.vectors.len(),
@@ -100,6 +189,18 @@ impl SessionVectorStore {
         }
 
         // Add or replace vector
+        if let Some(old) = self.usage.remove(&id) {
+            self.used_bytes -= old.bytes;
+        }
+        self.usage.insert(
+            id.clone(),
+            EntryUsage {
+                bytes,
+                last_used: AtomicU64::new(self.tick()),
+            },
+        );
+        self.used_bytes += bytes;
+        self.lexical.insert(&id, &metadata_text(&metadata));
         self.vectors.insert(
             id,
             VectorEntry {
@@ -112,6 +213,48 @@ impl SessionVectorStore {
         Ok(())
     }
 
+    /// Add the embedded chunks of one document
+    ///
+    /// Each chunk is stored as `{document_id}#{index}`, with `metadata`
+    /// merged with the chunk's text and char offsets in the source document.
+    ///
+    /// # Returns
+    /// * `Ok(count)` of chunks added
+    /// * `Err` on the first chunk that fails validation; earlier chunks stay added
+    pub fn add_chunks(
+        &mut self,
+        document_id: &str,
+        chunks: &[TextChunk],
+        vectors: Vec<Vec<f32>>,
+        metadata: Value,
+    ) -> Result<usize> {
+        if chunks.len() != vectors.len() {
+            return Err(anyhow!(
+                "Got {} vectors for {} chunks",
+                vectors.len(),
+                chunks.len()
+            ));
+        }
+
+        for (chunk, vector) in chunks.iter().zip(vectors) {
+            let mut chunk_metadata = metadata.clone();
+            if !chunk_metadata.is_object() {
+                chunk_metadata = Value::Object(Default::default());
+            }
+            if let (Some(fields), Value::Object(location)) =
+                (chunk_metadata.as_object_mut(), chunk.metadata(document_id))
+            {
+                fields.extend(location);
+            }
+            self.add(
+                format!("{}#{}", document_id, chunk.index),
+                vector,
+                chunk_metadata,
+            )?;
+        }
+        Ok(chunks.len())
+    }
+
     /// Get vector by ID
     ///
     /// # Arguments
@@ -121,6 +264,7 @@ impl SessionVectorStore {
     /// * `Some(&VectorEntry)` if found
     /// * `None` if not found
     pub fn get(&self, id: &str) -> Option<&VectorEntry> {
+        self.touch(id);
         self.vectors.get(id)
     }
 
@@ -133,6 +277,10 @@ impl SessionVectorStore {
     /// * `true` if deleted
     /// * `false` if not found
     pub fn delete(&mut self, id: &str) -> bool {
+        if let Some(usage) = self.usage.remove(id) {
+            self.release(usage.bytes);
+        }
+        self.lexical.remove(id);
         self.vectors.remove(id).is_some()
     }
 
@@ -145,6 +293,66 @@ impl SessionVectorStore {
     /// Called when session disconnects
     pub fn clear(&mut self) {
         self.vectors.clear();
+        self.lexical.clear();
+        self.usage.clear();
+        self.release(self.used_bytes);
+    }
+
+    /// Estimated bytes held by this store's entries
+    pub fn memory_bytes(&self) -> usize {
+        self.used_bytes
+    }
+
+    /// Check that a load of `required_bytes` could fit in this session at
+    /// all, however much is evicted first
+    ///
+    /// # Returns
+    /// * `Err(RagError::SessionMemoryExceeded)` if it exceeds the per-session cap
+    pub fn check_load(&self, required_bytes: usize) -> std::result::Result<(), RagError> {
+        match &self.memory {
+            Some(memory) if required_bytes > memory.limits.max_session_bytes => {
+                Err(RagError::SessionMemoryExceeded {
+                    session_id: self.session_id.clone(),
+                    required_bytes,
+                    limit_bytes: memory.limits.max_session_bytes,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Mark an entry as just used
+    fn touch(&self, id: &str) {
+        if let Some(usage) = self.usage.get(id) {
+            usage.last_used.store(self.tick(), Ordering::Relaxed);
+        }
+    }
+
+    /// Drop the least recently used entry; false if the store is empty
+    fn evict_lru(&mut self) -> bool {
+        let lru = self
+            .usage
+            .iter()
+            .min_by_key(|(_, usage)| usage.last_used.load(Ordering::Relaxed))
+            .map(|(id, _)| id.clone());
+        match lru {
+            Some(id) => {
+                tracing::debug!("Evicting RAG entry {} from session {}", id, self.session_id);
+                self.delete(&id)
+            }
+            None => false,
+        }
+    }
+
+    fn release(&mut self, bytes: usize) {
+        self.used_bytes -= bytes;
+        if let Some(memory) = &self.memory {
+            memory.budget.release(bytes);
+        }
     }
 
     /// Get session ID
@@ -173,6 +381,19 @@ impl SessionVectorStore {
         k: usize,
         threshold: Option<f32>,
     ) -> Result<Vec<SearchResult>> {
+        let results = self.rank(query, k, threshold)?;
+        self.touch_results(&results);
+        Ok(results)
+    }
+
+    fn touch_results(&self, results: &[SearchResult]) {
+        for result in results {
+            self.touch(&result.id);
+        }
+    }
+
+    /// Cosine ranking behind `search`, without marking results as used
+    fn rank(&self, query: Vec<f32>, k: usize, threshold: Option<f32>) -> Result<Vec<SearchResult>> {
         // Validate query dimensions
         if query.len() != 384 {
             return Err(anyhow!(
@@ -201,6 +422,9 @@ impl SessionVectorStore {
                     id: id.clone(),
                     score,
                     metadata: entry.metadata.clone(),
+                    vector_score: Some(score),
+                    lexical_score: None,
+                    rerank_score: None,
                 }
             })
             .collect();
@@ -246,17 +470,86 @@ impl SessionVectorStore {
         metadata_filter: Value,
     ) -> Result<Vec<SearchResult>> {
         // First perform standard search (no threshold, large k to get all matches)
-        let mut all_results = self.search(query, self.vectors.len(), None)?;
+        let mut all_results = self.rank(query, self.vectors.len(), None)?;
 
         // Apply metadata filtering
         all_results.retain(|result| self.matches_filter(&result.metadata, &metadata_filter));
 
         // Return top-k after filtering
         all_results.truncate(k);
+        self.touch_results(&all_results);
 
         Ok(all_results)
     }
 
+    /// Hybrid lexical + semantic search
+    ///
+    /// Ranks every vector by cosine similarity and every entry whose metadata
+    /// text contains a query term by BM25, then fuses the two rankings with
+    /// weighted reciprocal rank fusion: `w / (60 + lexical_rank) +
+    /// (1 - w) / (60 + vector_rank)`. Fusing ranks rather than raw scores
+    /// sidesteps the incomparable scales of BM25 and cosine similarity.
+    ///
+    /// # Arguments
+    /// * `query_vector` - Query embedding (must be 384 dimensions)
+    /// * `query_text` - Raw query text for the lexical index
+    /// * `k` - Number of results to return
+    /// * `lexical_weight` - Weight `w` of the lexical ranking, 0.0 (pure
+    ///   vector) to 1.0 (pure lexical)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SearchResult>)` - Top-k results sorted by fused score, with
+    ///   the underlying `vector_score` and `lexical_score` filled in
+    /// * `Err` if query dimensions or weight invalid
+    pub fn hybrid_search(
+        &self,
+        query_vector: Vec<f32>,
+        query_text: &str,
+        k: usize,
+        lexical_weight: f32,
+    ) -> Result<Vec<SearchResult>> {
+        if !(0.0..=1.0).contains(&lexical_weight) {
+            return Err(anyhow!(
+                "Invalid lexical weight: {} (must be between 0.0 and 1.0)",
+                lexical_weight
+            ));
+        }
+
+        let vector_ranked = self.rank(query_vector, self.vectors.len(), None)?;
+        let lexical_ranked = self.lexical.search(query_text, self.vectors.len());
+
+        let mut fused: HashMap<&str, SearchResult> = HashMap::new();
+        for (rank, result) in vector_ranked.iter().enumerate() {
+            let contribution = (1.0 - lexical_weight) / (RRF_K + rank as f32 + 1.0);
+            fused.insert(
+                &result.id,
+                SearchResult {
+                    score: contribution,
+                    ..result.clone()
+                },
+            );
+        }
+        for (rank, (id, lexical_score)) in lexical_ranked.iter().enumerate() {
+            let contribution = lexical_weight / (RRF_K + rank as f32 + 1.0);
+            if let Some(result) = fused.get_mut(id.as_str()) {
+                result.score += contribution;
+                result.lexical_score = Some(*lexical_score);
+            }
+        }
+
+        let mut results: Vec<SearchResult> = fused.into_values().collect();
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        results.truncate(k);
+        self.touch_results(&results);
+
+        Ok(results)
+    }
+
     /// Check if metadata matches filter
     ///
     /// Supports basic filter operations:
@@ -302,6 +595,31 @@ impl SessionVectorStore {
     }
 }
 
+impl Drop for SessionVectorStore {
+    fn drop(&mut self) {
+        // Hand this session's share back to the node-wide budget
+        if let Some(memory) = &self.memory {
+            memory.budget.release(self.used_bytes);
+        }
+    }
+}
+
+/// Text indexed for lexical search: every string value in the metadata
+pub(crate) fn metadata_text(metadata: &Value) -> String {
+    fn collect<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+        match value {
+            Value::String(text) => out.push(text),
+            Value::Array(items) => items.iter().for_each(|item| collect(item, out)),
+            Value::Object(fields) => fields.values().for_each(|field| collect(field, out)),
+            _ => {}
+        }
+    }
+
+    let mut parts = Vec::new();
+    collect(metadata, &mut parts);
+    parts.join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,4 +669,142 @@ mod tests {
         store.clear();
         assert_eq!(store.count(), 0);
     }
+
+    #[test]
+    fn test_add_chunks_records_source_spans() {
+        let mut store = SessionVectorStore::new("test-session".to_string(), 10);
+        let chunks = vec![
+            TextChunk {
+                index: 0,
+                text: "First sentence.".to_string(),
+                start_char: 0,
+                end_char: 15,
+                token_count: 3,
+            },
+            TextChunk {
+                index: 1,
+                text: "Second one.".to_string(),
+                start_char: 16,
+                end_char: 27,
+                token_count: 3,
+            },
+        ];
+
+        let added = store
+            .add_chunks(
+                "doc-1",
+                &chunks,
+                vec![vec![0.1; 384], vec![0.2; 384]],
+                json!({"title": "Notes"}),
+            )
+            .unwrap();
+        assert_eq!(added, 2);
+
+        let entry = store.get("doc-1#1").unwrap();
+        assert_eq!(entry.metadata["title"], "Notes");
+        assert_eq!(entry.metadata["start_char"], 16);
+        assert_eq!(entry.metadata["end_char"], 27);
+        assert_eq!(entry.metadata["text"], "Second one.");
+
+        assert!(store
+            .add_chunks("doc-2", &chunks, vec![vec![0.1; 384]], json!({}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_memory_limits_evict_least_recently_used() {
+        let entry_bytes = estimate_entry_bytes("doc0", &[0.1; 384], &json!({}));
+        let limits = RagMemoryLimits {
+            max_session_bytes: entry_bytes * 3,
+            max_session_vectors: 100,
+            max_global_bytes: entry_bytes * 5,
+        };
+        let budget = Arc::new(RagMemoryBudget::new(limits.max_global_bytes));
+        let mut store = SessionVectorStore::new("a".to_string(), 1000)
+            .with_memory_limits(limits, budget.clone());
+
+        for i in 0..3 {
+            store
+                .add(format!("doc{}", i), vec![0.1; 384], json!({}))
+                .unwrap();
+        }
+        // Reading doc0 makes doc1 the least recently used
+        assert!(store.get("doc0").is_some());
+        store
+            .add("doc3".to_string(), vec![0.1; 384], json!({}))
+            .unwrap();
+        assert_eq!(store.count(), 3);
+        assert!(store.get("doc1").is_none());
+        assert!(store.get("doc0").is_some());
+        assert_eq!(store.memory_bytes(), entry_bytes * 3);
+        assert_eq!(budget.used_bytes(), entry_bytes * 3);
+
+        // A load bigger than the whole session cap is refused outright
+        let err = store.check_load(entry_bytes * 4).unwrap_err();
+        assert!(matches!(err, RagError::SessionMemoryExceeded { .. }));
+
+        // A second session only gets what is left of the global budget,
+        // evicting its own entries to stay within it
+        let mut other = SessionVectorStore::new("b".to_string(), 1000)
+            .with_memory_limits(limits, budget.clone());
+        for i in 0..3 {
+            other
+                .add(format!("doc{}", i), vec![0.1; 384], json!({}))
+                .unwrap();
+        }
+        assert_eq!(other.count(), 2);
+        assert!(other.get("doc0").is_none());
+        assert_eq!(budget.used_bytes(), entry_bytes * 5);
+
+        drop(store);
+        assert_eq!(budget.used_bytes(), entry_bytes * 2);
+        other.clear();
+        assert_eq!(budget.used_bytes(), 0);
+    }
+
+    #[test]
+    fn test_hybrid_search_weights_lexical_matches() {
+        let mut store = SessionVectorStore::new("test-session".to_string(), 10);
+        let mut near = vec![0.0; 384];
+        near[0] = 1.0;
+        let mut far = vec![0.0; 384];
+        far[1] = 1.0;
+        store
+            .add(
+                "semantic".to_string(),
+                near.clone(),
+                json!({"text": "connection dropped unexpectedly"}),
+            )
+            .unwrap();
+        store
+            .add(
+                "exact".to_string(),
+                far,
+                json!({"text": "client saw ERR_CONN_RESET on upload"}),
+            )
+            .unwrap();
+
+        let vector_only = store
+            .hybrid_search(near.clone(), "ERR_CONN_RESET", 2, 0.0)
+            .unwrap();
+        assert_eq!(vector_only[0].id, "semantic");
+
+        let lexical_heavy = store
+            .hybrid_search(near.clone(), "ERR_CONN_RESET", 2, 0.8)
+            .unwrap();
+        assert_eq!(lexical_heavy[0].id, "exact");
+        assert!(lexical_heavy[0].lexical_score.is_some());
+        assert!(lexical_heavy[0].vector_score.is_some());
+        assert!(lexical_heavy[1].lexical_score.is_none());
+
+        // Deleted entries leave the lexical index too
+        store.delete("exact");
+        let results = store
+            .hybrid_search(near.clone(), "ERR_CONN_RESET", 2, 1.0)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].lexical_score.is_none());
+
+        assert!(store.hybrid_search(near, "x", 2, 1.5).is_err());
+    }
 }
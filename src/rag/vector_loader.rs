@@ -48,6 +48,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc::Sender;
 use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
 
 /// Progress updates during vector loading
 #[derive(Debug, Clone)]
@@ -58,6 +59,10 @@ pub enum LoadProgress {
     /// Chunk downloaded and decrypted
     ChunkDownloaded { chunk_id: usize, total: usize },
 
+    /// Vectors loaded so far, sent after each chunk; `total` is the
+    /// manifest's vector count
+    VectorsLoaded { loaded: usize, total: usize },
+
     /// Building index from loaded vectors
     IndexBuilding,
 
@@ -100,6 +105,9 @@ pub struct VectorLoader {
 
     /// Optional metrics for tracking S5 performance
     metrics: Option<Arc<S5Metrics>>,
+
+    /// Optional token that aborts loading between chunks
+    cancel_token: Option<CancellationToken>,
 }
 
 impl VectorLoader {
@@ -117,6 +125,7 @@ impl VectorLoader {
             memory_limit_mb: None,
             timeout_duration: None,
             metrics: None,
+            cancel_token: None,
         }
     }
 
@@ -145,6 +154,7 @@ impl VectorLoader {
             memory_limit_mb: None,
             timeout_duration: None,
             metrics: None,
+            cancel_token: None,
         }
     }
 
@@ -167,6 +177,7 @@ impl VectorLoader {
             memory_limit_mb: Some(memory_limit_mb),
             timeout_duration: None,
             metrics: None,
+            cancel_token: None,
         }
     }
 
@@ -189,6 +200,7 @@ impl VectorLoader {
             memory_limit_mb: None,
             timeout_duration: Some(timeout_duration),
             metrics: None,
+            cancel_token: None,
         }
     }
 
@@ -201,6 +213,15 @@ impl VectorLoader {
         self
     }
 
+    /// Abort loading when `token` is cancelled (e.g. on client disconnect)
+    ///
+    /// Chunks already downloaded are freed and loading fails with
+    /// `VectorLoadError::Cancelled`.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
     /// Load vectors from S5 storage
     ///
     /// Downloads manifest, verifies owner, downloads chunks in parallel, and returns all vectors.
@@ -460,109 +481,129 @@ impl VectorLoader {
         // Download and decrypt chunks in parallel
         // Clone chunks to avoid lifetime issues with iterator borrows in async closures
         let chunks_owned = manifest.chunks.clone();
-        let chunk_results: Vec<Result<Vec<Vector>, VectorLoadError>> =
-            stream::iter(chunks_owned.into_iter())
-                .map(|chunk_meta| {
-                    let s5_client = s5_client.clone();
-                    let session_key = session_key.clone();
-                    let base_path = base_path.clone();
-                    let chunk_id = chunk_meta.chunk_id;
-                    let expected_vector_count = chunk_meta.vector_count;
-                    let expected_dimensions = expected_dimensions;
-                    let rate_limit = rate_limit.clone();
-
-                    async move {
-                        // Check rate limit before download
-                        if let Some(ref rl) = rate_limit {
-                            Self::check_rate_limit_static(rl).await?;
-                        }
-
-                        // Download encrypted chunk
-                        let chunk_path = format!("{}/chunk-{}.json", base_path, chunk_id);
-                        let chunk_download_start = Instant::now();
-                        let encrypted_chunk = s5_client.get(&chunk_path).await.map_err(|e| {
-                            tracing::error!(
-                                chunk_id,
-                                path = %chunk_path,
-                                error = %e,
-                                "❌ Failed to download chunk"
-                            );
-                            VectorLoadError::ChunkDownloadFailed {
-                                chunk_id,
-                                path: chunk_path.clone(),
-                                source: Box::new(e) as Box<dyn std::error::Error + Send + Sync>,
-                            }
-                        })?;
+        let chunk_stream = stream::iter(chunks_owned.into_iter())
+            .map(|chunk_meta| {
+                let s5_client = s5_client.clone();
+                let session_key = session_key.clone();
+                let base_path = base_path.clone();
+                let chunk_id = chunk_meta.chunk_id;
+                let expected_vector_count = chunk_meta.vector_count;
+                let expected_dimensions = expected_dimensions;
+                let rate_limit = rate_limit.clone();
+
+                async move {
+                    // Check rate limit before download
+                    if let Some(ref rl) = rate_limit {
+                        Self::check_rate_limit_static(rl).await?;
+                    }
 
-                        tracing::trace!(
+                    // Download encrypted chunk
+                    let chunk_path = format!("{}/chunk-{}.json", base_path, chunk_id);
+                    let chunk_download_start = Instant::now();
+                    let encrypted_chunk = s5_client.get(&chunk_path).await.map_err(|e| {
+                        tracing::error!(
                             chunk_id,
                             path = %chunk_path,
-                            duration_ms = chunk_download_start.elapsed().as_millis(),
-                            size_bytes = encrypted_chunk.len(),
-                            "📥 Chunk downloaded"
+                            error = %e,
+                            "❌ Failed to download chunk"
                         );
-
-                        // Decrypt chunk
-                        let chunk = decrypt_chunk(&encrypted_chunk, &session_key).map_err(|e| {
-                            tracing::error!(
-                                chunk_id,
-                                error = %e,
-                                "❌ Failed to decrypt chunk"
-                            );
-                            VectorLoadError::DecryptionFailed(format!("Chunk {}: {}", chunk_id, e))
-                        })?;
-
-                        // Validate dimensions
-                        if !chunk.vectors.is_empty() {
-                            let actual_dimensions = chunk.vectors[0].vector.len();
-                            if actual_dimensions != expected_dimensions {
-                                tracing::error!(
-                                    chunk_id,
-                                    expected = expected_dimensions,
-                                    actual = actual_dimensions,
-                                    "❌ Dimension mismatch detected"
-                                );
-                                return Err(VectorLoadError::DimensionMismatch {
-                                    chunk_id,
-                                    expected: expected_dimensions,
-                                    actual: actual_dimensions,
-                                });
-                            }
+                        VectorLoadError::ChunkDownloadFailed {
+                            chunk_id,
+                            path: chunk_path.clone(),
+                            source: Box::new(e) as Box<dyn std::error::Error + Send + Sync>,
                         }
+                    })?;
+
+                    tracing::trace!(
+                        chunk_id,
+                        path = %chunk_path,
+                        duration_ms = chunk_download_start.elapsed().as_millis(),
+                        size_bytes = encrypted_chunk.len(),
+                        "📥 Chunk downloaded"
+                    );
 
-                        // Validate vector count
-                        if chunk.vectors.len() != expected_vector_count {
+                    // Decrypt chunk
+                    let chunk = decrypt_chunk(&encrypted_chunk, &session_key).map_err(|e| {
+                        tracing::error!(
+                            chunk_id,
+                            error = %e,
+                            "❌ Failed to decrypt chunk"
+                        );
+                        VectorLoadError::DecryptionFailed(format!("Chunk {}: {}", chunk_id, e))
+                    })?;
+
+                    // Validate dimensions
+                    if !chunk.vectors.is_empty() {
+                        let actual_dimensions = chunk.vectors[0].vector.len();
+                        if actual_dimensions != expected_dimensions {
                             tracing::error!(
                                 chunk_id,
-                                expected = expected_vector_count,
-                                actual = chunk.vectors.len(),
-                                "❌ Vector count mismatch detected"
+                                expected = expected_dimensions,
+                                actual = actual_dimensions,
+                                "❌ Dimension mismatch detected"
                             );
-                            return Err(VectorLoadError::VectorCountMismatch {
+                            return Err(VectorLoadError::DimensionMismatch {
                                 chunk_id,
-                                expected: expected_vector_count,
-                                actual: chunk.vectors.len(),
+                                expected: expected_dimensions,
+                                actual: actual_dimensions,
                             });
                         }
+                    }
 
-                        // Validate chunk structure
-                        chunk.validate(expected_dimensions).map_err(|e| {
-                            VectorLoadError::ChunkValidationFailed {
-                                chunk_id,
-                                reason: e.to_string(),
-                            }
-                        })?;
-
-                        Ok(chunk.vectors)
+                    // Validate vector count
+                    if chunk.vectors.len() != expected_vector_count {
+                        tracing::error!(
+                            chunk_id,
+                            expected = expected_vector_count,
+                            actual = chunk.vectors.len(),
+                            "❌ Vector count mismatch detected"
+                        );
+                        return Err(VectorLoadError::VectorCountMismatch {
+                            chunk_id,
+                            expected: expected_vector_count,
+                            actual: chunk.vectors.len(),
+                        });
                     }
-                })
-                .buffer_unordered(self.max_parallel_chunks)
-                .collect()
-                .await;
 
-        // Collect all vectors from successful chunks
+                    // Validate chunk structure
+                    chunk.validate(expected_dimensions).map_err(|e| {
+                        VectorLoadError::ChunkValidationFailed {
+                            chunk_id,
+                            reason: e.to_string(),
+                        }
+                    })?;
+
+                    Ok(chunk.vectors)
+                }
+            })
+            .buffer_unordered(self.max_parallel_chunks);
+        let mut chunk_stream = std::pin::pin!(chunk_stream);
+        let cancel_token = self.cancel_token.clone().unwrap_or_default();
+
+        // Collect vectors as each chunk completes, so progress is live
         let mut all_vectors = Vec::new();
-        for (i, result) in chunk_results.into_iter().enumerate() {
+        let mut i = 0;
+        loop {
+            let result = tokio::select! {
+                // Cancellation wins over a chunk that completed at the same time
+                biased;
+                _ = cancel_token.cancelled() => {
+                    let loaded_vectors = all_vectors.len();
+                    // Free what was loaded before the in-flight downloads drop too
+                    drop(all_vectors);
+                    tracing::warn!(
+                        loaded_vectors,
+                        chunks_done = i,
+                        total_chunks,
+                        "⚠️ Vector loading cancelled, loaded chunks freed"
+                    );
+                    return Err(VectorLoadError::Cancelled { loaded_vectors });
+                }
+                next = chunk_stream.next() => match next {
+                    Some(result) => result,
+                    None => break,
+                },
+            };
             let vectors = result?;
             all_vectors.extend(vectors);
 
@@ -574,6 +615,12 @@ impl VectorLoader {
                         total: total_chunks,
                     })
                     .await;
+                let _ = tx
+                    .send(LoadProgress::VectorsLoaded {
+                        loaded: all_vectors.len(),
+                        total: manifest.vector_count,
+                    })
+                    .await;
             }
 
             tracing::debug!(
@@ -582,6 +629,7 @@ impl VectorLoader {
                 vectors_so_far = all_vectors.len(),
                 "✅ Chunk processed"
             );
+            i += 1;
         }
 
         // Record total vectors loaded
@@ -9,12 +9,14 @@ use serde::{Deserialize, Serialize};
 use sha2::Digest;
 use std::collections::HashMap;
 use std::io::Write;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock, Semaphore};
 use tokio::time::{sleep, Duration};
 use tracing::{debug, error, info, warn};
 
 use crate::contracts::Web3Client;
+use crate::crypto::ezkl::{keys_are_compatible, ProvingKey, VerificationKey};
 use crate::job_processor::{JobResult, NodeConfig};
 
 #[derive(Debug, Clone)]
@@ -24,9 +26,28 @@ pub enum SubmissionError {
     InvalidResult,
     StorageError(String),
     ContractError(String),
+    /// The proof would not verify against the verifying key it is checked with
+    KeyMismatch {
+        local_key_hash: H256,
+        expected_key_hash: H256,
+    },
+    /// This node already submitted a result for the job; not a failure.
+    /// Carries the existing transaction and/or output CID.
+    AlreadySubmitted {
+        tx_hash: Option<H256>,
+        output_cid: Option<String>,
+    },
     Other(String),
 }
 
+impl SubmissionError {
+    /// Whether the error means the result is already on-chain, which callers
+    /// can treat as a successful submission
+    pub fn is_already_submitted(&self) -> bool {
+        matches!(self, SubmissionError::AlreadySubmitted { .. })
+    }
+}
+
 impl std::fmt::Display for SubmissionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -35,6 +56,22 @@ impl std::fmt::Display for SubmissionError {
             SubmissionError::InvalidResult => write!(f, "Invalid result"),
             SubmissionError::StorageError(e) => write!(f, "Storage error: {}", e),
             SubmissionError::ContractError(e) => write!(f, "Contract error: {}", e),
+            SubmissionError::KeyMismatch {
+                local_key_hash,
+                expected_key_hash,
+            } => write!(
+                f,
+                "Verifying key mismatch: local {:?}, expected {:?}",
+                local_key_hash, expected_key_hash
+            ),
+            SubmissionError::AlreadySubmitted {
+                tx_hash,
+                output_cid,
+            } => write!(
+                f,
+                "Result already submitted (tx: {:?}, output: {:?})",
+                tx_hash, output_cid
+            ),
             SubmissionError::Other(e) => write!(f, "Other error: {}", e),
         }
     }
@@ -109,6 +146,19 @@ impl From<NodeConfig> for SubmissionConfig {
 pub trait StorageClient: Send + Sync {
     async fn store(&self, data: Vec<u8>) -> Result<String, String>;
     async fn retrieve(&self, cid: &str) -> Result<Vec<u8>, String>;
+
+    /// CID of an output already stored for `job_id`, if the backend indexes by job
+    async fn find_result(&self, _job_id: H256) -> Result<Option<String>, String> {
+        Ok(None)
+    }
+}
+
+/// A result the contract already holds for a job
+#[derive(Debug, Clone)]
+pub struct OnChainResult {
+    pub node: Address,
+    pub output_cid: String,
+    pub tx_hash: Option<H256>,
 }
 
 // Contract interface trait
@@ -122,6 +172,131 @@ pub trait JobMarketplaceTrait: Send + Sync {
         result: JobResult,
         node: Address,
     ) -> Result<H256, SubmissionError>;
+
+    /// Hash of the verifying key registered on-chain, if the contract exposes one
+    async fn verifying_key_hash(&self) -> Result<Option<H256>, SubmissionError> {
+        Ok(None)
+    }
+
+    /// Result recorded on-chain for `job_id`, if the contract exposes one
+    async fn submitted_result(
+        &self,
+        _job_id: H256,
+    ) -> Result<Option<OnChainResult>, SubmissionError> {
+        Ok(None)
+    }
+}
+
+/// CIDs of the artifacts uploaded for a submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredCids {
+    pub output_cid: String,
+    pub metadata_cid: Option<String>,
+    pub proof_cid: Option<String>,
+}
+
+/// Progress of a single job's submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum JournalState {
+    /// Artifacts uploaded and the transaction about to be sent. After a
+    /// crash the chain must be checked before sending again.
+    Submitting {
+        cids: StoredCids,
+    },
+    Confirmed {
+        tx_hash: H256,
+        output_cid: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalEntry {
+    job_id: H256,
+    #[serde(flatten)]
+    state: JournalState,
+}
+
+/// Local record of submissions, persisted so a restart can tell which jobs
+/// may already have a transaction in flight
+pub struct SubmissionJournal {
+    path: Option<PathBuf>,
+    entries: RwLock<HashMap<H256, JournalState>>,
+}
+
+impl SubmissionJournal {
+    /// Journal that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Load the journal at `path`, starting empty if the file doesn't exist
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let entries = match tokio::fs::read_to_string(&path).await {
+            Ok(data) => serde_json::from_str::<Vec<JournalEntry>>(&data)?
+                .into_iter()
+                .map(|entry| (entry.job_id, entry.state))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            entries: RwLock::new(entries),
+        })
+    }
+
+    pub async fn get(&self, job_id: H256) -> Option<JournalState> {
+        self.entries.read().await.get(&job_id).cloned()
+    }
+
+    /// Record a new state for `job_id` and persist the journal
+    pub async fn record(&self, job_id: H256, state: JournalState) -> Result<()> {
+        let mut entries = self.entries.write().await;
+        entries.insert(job_id, state);
+        self.persist(&entries).await
+    }
+
+    /// Jobs whose transaction may have been sent but was never confirmed
+    pub async fn unconfirmed(&self) -> Vec<H256> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .filter(|(_, state)| matches!(state, JournalState::Submitting { .. }))
+            .map(|(job_id, _)| *job_id)
+            .collect()
+    }
+
+    async fn persist(&self, entries: &HashMap<H256, JournalState>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let list: Vec<JournalEntry> = entries
+            .iter()
+            .map(|(job_id, state)| JournalEntry {
+                job_id: *job_id,
+                state: state.clone(),
+            })
+            .collect();
+        let data = serde_json::to_string_pretty(&list)?;
+        // Write then rename so a crash mid-write never leaves a truncated file
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, data).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+        Ok(())
+    }
+}
+
+/// Hash a key's bytes the same way on-chain key hashes are derived
+fn key_hash(key_data: &[u8]) -> H256 {
+    H256::from_slice(&sha2::Sha256::digest(key_data)[..])
 }
 
 #[derive(Clone)]
@@ -130,6 +305,8 @@ pub struct ResultSubmitter {
     marketplace: Arc<dyn JobMarketplaceTrait>,
     storage: Arc<dyn StorageClient>,
     submission_semaphore: Arc<Semaphore>,
+    proof_keys: Option<Arc<(ProvingKey, VerificationKey)>>,
+    journal: Arc<SubmissionJournal>,
 }
 
 impl ResultSubmitter {
@@ -146,7 +323,59 @@ impl ResultSubmitter {
             marketplace,
             storage,
             submission_semaphore: semaphore,
+            proof_keys: None,
+            journal: Arc::new(SubmissionJournal::in_memory()),
+        }
+    }
+
+    /// Use a persistent journal so submissions survive restarts
+    pub fn with_journal(mut self, journal: SubmissionJournal) -> Self {
+        self.journal = Arc::new(journal);
+        self
+    }
+
+    /// Set the proving key proofs are generated with and the verifying key
+    /// they are expected to verify against
+    pub fn with_proof_keys(
+        mut self,
+        proving_key: ProvingKey,
+        verifying_key: VerificationKey,
+    ) -> Self {
+        self.proof_keys = Some(Arc::new((proving_key, verifying_key)));
+        self
+    }
+
+    /// Preflight check that proofs from our proving key will verify on-chain
+    ///
+    /// Fails with `SubmissionError::KeyMismatch` if the local proving and
+    /// verifying keys are not a pair, or if the local verifying key differs
+    /// from the one registered on-chain (e.g. after a circuit upgrade).
+    /// Succeeds without checking when no proof keys are configured.
+    pub async fn check_proof_verifiable(&self) -> Result<(), SubmissionError> {
+        let Some(keys) = &self.proof_keys else {
+            debug!("No proof keys configured, skipping verifying key check");
+            return Ok(());
+        };
+        let (proving_key, verifying_key) = keys.as_ref();
+        let local_vk_hash = key_hash(&verifying_key.key_data);
+
+        if !keys_are_compatible(proving_key, verifying_key) {
+            return Err(SubmissionError::KeyMismatch {
+                local_key_hash: key_hash(&proving_key.key_data),
+                expected_key_hash: local_vk_hash,
+            });
         }
+
+        if let Some(onchain_vk_hash) = self.marketplace.verifying_key_hash().await? {
+            if onchain_vk_hash != local_vk_hash {
+                return Err(SubmissionError::KeyMismatch {
+                    local_key_hash: local_vk_hash,
+                    expected_key_hash: onchain_vk_hash,
+                });
+            }
+        }
+
+        Ok(())
     }
 
     pub async fn submit_result(&self, result: InferenceResult) -> Result<H256, SubmissionError> {
@@ -162,59 +391,199 @@ impl ResultSubmitter {
             return Err(SubmissionError::JobNotClaimedByNode);
         }
 
-        // Check if already completed
-        if self.marketplace.is_job_completed(result.job_id).await {
-            return Err(SubmissionError::JobAlreadyCompleted);
+        self.submit_idempotent(&result, None).await
+    }
+
+    pub async fn submit_result_with_proof(
+        &self,
+        result: InferenceResult,
+        proof: ProofData,
+    ) -> Result<H256, SubmissionError> {
+        // Fail fast instead of paying gas for a guaranteed revert
+        if let Err(e) = self.check_proof_verifiable().await {
+            error!(
+                "Refusing to submit proof for job {:?}: {}",
+                result.job_id, e
+            );
+            return Err(e);
         }
 
-        // Process and store the output
-        let output_cid = self.store_output(&result).await?;
+        self.submit_idempotent(&result, Some(&proof)).await
+    }
 
-        // Store metadata if present
-        let metadata_cid = if !result.metadata.is_null() {
-            Some(self.store_metadata(&result).await?)
-        } else {
-            None
+    /// Submit once per job, even across retries and restarts
+    ///
+    /// Returns `SubmissionError::AlreadySubmitted` if the journal or the chain
+    /// shows this node's result, and reuses artifacts uploaded by an earlier
+    /// attempt instead of storing them again.
+    async fn submit_idempotent(
+        &self,
+        result: &InferenceResult,
+        proof: Option<&ProofData>,
+    ) -> Result<H256, SubmissionError> {
+        let job_id = result.job_id;
+        let journaled = self.journal.get(job_id).await;
+
+        if let Some(JournalState::Confirmed {
+            tx_hash,
+            output_cid,
+        }) = &journaled
+        {
+            debug!("Result for job {:?} already confirmed in journal", job_id);
+            return Err(SubmissionError::AlreadySubmitted {
+                tx_hash: Some(*tx_hash),
+                output_cid: Some(output_cid.clone()),
+            });
+        }
+
+        // Covers a crash after the transaction was sent but before it was journaled
+        if let Some(onchain) = self.marketplace.submitted_result(job_id).await? {
+            if onchain.node == self.config.node_address {
+                info!(
+                    "Result for job {:?} already on-chain, not resubmitting",
+                    job_id
+                );
+                if let Some(tx_hash) = onchain.tx_hash {
+                    self.journal
+                        .record(
+                            job_id,
+                            JournalState::Confirmed {
+                                tx_hash,
+                                output_cid: onchain.output_cid.clone(),
+                            },
+                        )
+                        .await?;
+                }
+                return Err(SubmissionError::AlreadySubmitted {
+                    tx_hash: onchain.tx_hash,
+                    output_cid: Some(onchain.output_cid),
+                });
+            }
+        }
+
+        if self.marketplace.is_job_completed(job_id).await {
+            return Err(SubmissionError::JobAlreadyCompleted);
+        }
+
+        let cids = match journaled {
+            Some(JournalState::Submitting { cids })
+                if proof.is_none() || cids.proof_cid.is_some() =>
+            {
+                warn!(
+                    "Resuming interrupted submission for job {:?}, reusing stored artifacts",
+                    job_id
+                );
+                cids
+            }
+            _ => self.store_artifacts(result, proof).await?,
         };
+        self.journal
+            .record(job_id, JournalState::Submitting { cids: cids.clone() })
+            .await?;
 
-        // Create job result
         let job_result = JobResult {
-            job_id: result.job_id,
+            job_id,
             output: result.output.clone(),
             tokens_used: result.tokens_used,
             inference_time_ms: result.inference_time_ms,
-            output_cid,
-            proof_cid: None,
-            metadata_cid,
+            output_cid: cids.output_cid.clone(),
+            proof_cid: cids.proof_cid.clone(),
+            metadata_cid: cids.metadata_cid.clone(),
         };
 
-        // Submit to blockchain
-        self.marketplace
-            .submit_result(result.job_id, job_result, self.config.node_address)
-            .await
+        let tx_hash = self
+            .marketplace
+            .submit_result(job_id, job_result, self.config.node_address)
+            .await?;
+        self.journal
+            .record(
+                job_id,
+                JournalState::Confirmed {
+                    tx_hash,
+                    output_cid: cids.output_cid,
+                },
+            )
+            .await?;
+
+        Ok(tx_hash)
     }
 
-    pub async fn submit_result_with_proof(
+    async fn store_artifacts(
         &self,
-        result: InferenceResult,
-        proof: ProofData,
-    ) -> Result<H256, SubmissionError> {
-        // Store proof
-        let proof_bytes =
-            bincode::serialize(&proof).map_err(|e| SubmissionError::Other(e.to_string()))?;
-        let proof_cid = self
-            .storage
-            .store(proof_bytes)
-            .await
-            .map_err(|e| SubmissionError::StorageError(e))?;
+        result: &InferenceResult,
+        proof: Option<&ProofData>,
+    ) -> Result<StoredCids, SubmissionError> {
+        // The output may have been uploaded before a crash that lost the journal
+        let output_cid = match self.storage.find_result(result.job_id).await {
+            Ok(Some(cid)) => {
+                debug!("Reusing stored output {} for job {:?}", cid, result.job_id);
+                cid
+            }
+            Ok(None) => self.store_output(result).await?,
+            Err(e) => {
+                warn!(
+                    "Stored output lookup failed for job {:?}: {}",
+                    result.job_id, e
+                );
+                self.store_output(result).await?
+            }
+        };
 
-        // Submit result with proof CID
-        let mut job_result = self.prepare_result(&result).await?;
-        job_result.proof_cid = Some(proof_cid);
+        let metadata_cid = if !result.metadata.is_null() {
+            Some(self.store_metadata(result).await?)
+        } else {
+            None
+        };
 
-        self.marketplace
-            .submit_result(result.job_id, job_result, self.config.node_address)
-            .await
+        let proof_cid = match proof {
+            Some(proof) => {
+                let proof_bytes =
+                    bincode::serialize(proof).map_err(|e| SubmissionError::Other(e.to_string()))?;
+                Some(
+                    self.storage
+                        .store(proof_bytes)
+                        .await
+                        .map_err(SubmissionError::StorageError)?,
+                )
+            }
+            None => None,
+        };
+
+        Ok(StoredCids {
+            output_cid,
+            metadata_cid,
+            proof_cid,
+        })
+    }
+
+    /// Reconcile journaled submissions that never confirmed, e.g. at startup
+    ///
+    /// Jobs whose result is on-chain are marked confirmed; the rest are
+    /// returned so the caller can resubmit them.
+    pub async fn recover_unconfirmed(&self) -> Result<Vec<H256>, SubmissionError> {
+        let mut pending = Vec::new();
+
+        for job_id in self.journal.unconfirmed().await {
+            match self.marketplace.submitted_result(job_id).await? {
+                Some(onchain) if onchain.node == self.config.node_address => {
+                    info!("Recovered confirmed submission for job {:?}", job_id);
+                    if let Some(tx_hash) = onchain.tx_hash {
+                        self.journal
+                            .record(
+                                job_id,
+                                JournalState::Confirmed {
+                                    tx_hash,
+                                    output_cid: onchain.output_cid,
+                                },
+                            )
+                            .await?;
+                    }
+                }
+                _ => pending.push(job_id),
+            }
+        }
+
+        Ok(pending)
     }
 
     pub async fn submit_batch(
@@ -269,7 +638,9 @@ impl ResultSubmitter {
                     match &e {
                         SubmissionError::JobNotClaimedByNode
                         | SubmissionError::JobAlreadyCompleted
-                        | SubmissionError::InvalidResult => return Err(e),
+                        | SubmissionError::InvalidResult
+                        | SubmissionError::KeyMismatch { .. }
+                        | SubmissionError::AlreadySubmitted { .. } => return Err(e),
                         _ => {}
                     }
 
@@ -342,25 +713,6 @@ impl ResultSubmitter {
         Ok(())
     }
 
-    async fn prepare_result(&self, result: &InferenceResult) -> Result<JobResult, SubmissionError> {
-        let output_cid = self.store_output(result).await?;
-        let metadata_cid = if !result.metadata.is_null() {
-            Some(self.store_metadata(result).await?)
-        } else {
-            None
-        };
-
-        Ok(JobResult {
-            job_id: result.job_id,
-            output: result.output.clone(),
-            tokens_used: result.tokens_used,
-            inference_time_ms: result.inference_time_ms,
-            output_cid,
-            proof_cid: None,
-            metadata_cid,
-        })
-    }
-
     pub async fn would_result_expire(&self, result: &InferenceResult) -> bool {
         let current_time = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -407,6 +759,8 @@ mod tests {
         claimed_jobs: Arc<RwLock<HashMap<H256, Address>>>,
         completed_jobs: Arc<RwLock<Vec<H256>>>,
         results: Arc<RwLock<Vec<(H256, JobResult)>>>,
+        onchain: Arc<RwLock<HashMap<H256, OnChainResult>>>,
+        onchain_vk_hash: Option<H256>,
     }
 
     #[async_trait::async_trait]
@@ -428,15 +782,35 @@ mod tests {
             &self,
             job_id: H256,
             result: JobResult,
-            _node: Address,
+            node: Address,
         ) -> Result<H256, SubmissionError> {
             if self.completed_jobs.read().await.contains(&job_id) {
                 return Err(SubmissionError::JobAlreadyCompleted);
             }
 
+            let tx_hash = H256::random();
+            self.onchain.write().await.insert(
+                job_id,
+                OnChainResult {
+                    node,
+                    output_cid: result.output_cid.clone(),
+                    tx_hash: Some(tx_hash),
+                },
+            );
             self.results.write().await.push((job_id, result));
             self.completed_jobs.write().await.push(job_id);
-            Ok(H256::random())
+            Ok(tx_hash)
+        }
+
+        async fn verifying_key_hash(&self) -> Result<Option<H256>, SubmissionError> {
+            Ok(self.onchain_vk_hash)
+        }
+
+        async fn submitted_result(
+            &self,
+            job_id: H256,
+        ) -> Result<Option<OnChainResult>, SubmissionError> {
+            Ok(self.onchain.read().await.get(&job_id).cloned())
         }
     }
 
@@ -461,4 +835,217 @@ mod tests {
                 .ok_or_else(|| "CID not found".to_string())
         }
     }
+
+    fn create_submitter(
+        onchain_vk_hash: Option<H256>,
+    ) -> (ResultSubmitter, Arc<MockJobMarketplace>) {
+        let marketplace = Arc::new(MockJobMarketplace {
+            claimed_jobs: Arc::new(RwLock::new(HashMap::new())),
+            completed_jobs: Arc::new(RwLock::new(Vec::new())),
+            results: Arc::new(RwLock::new(Vec::new())),
+            onchain: Arc::new(RwLock::new(HashMap::new())),
+            onchain_vk_hash,
+        });
+        let storage = Arc::new(MockStorageClient {
+            stored_data: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let config = SubmissionConfig {
+            node_address: Address::random(),
+            max_result_size: 1024,
+            enable_compression: false,
+            compression_threshold: 1000,
+            batch_submission_size: 5,
+            submission_retry_attempts: 1,
+            submission_retry_delay: Duration::from_millis(1),
+            include_hardware_info: false,
+            result_expiry_time: Duration::from_secs(3600),
+            max_concurrent_submissions: 1,
+        };
+        let submitter = ResultSubmitter::new(config, marketplace.clone(), storage);
+        (submitter, marketplace)
+    }
+
+    fn proving_key() -> ProvingKey {
+        ProvingKey {
+            key_data: vec![0xAA; 64],
+        }
+    }
+
+    fn verifying_key() -> VerificationKey {
+        VerificationKey {
+            key_data: vec![0xBB; 64],
+        }
+    }
+
+    fn test_proof(job_id: H256) -> ProofData {
+        ProofData {
+            job_id,
+            model_hash: H256::zero(),
+            input_hash: H256::zero(),
+            output_hash: H256::zero(),
+            computation_trace: vec![0u8; 32],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_proof_verifiable_without_keys() {
+        let (submitter, _) = create_submitter(Some(H256::random()));
+        assert!(submitter.check_proof_verifiable().await.is_ok());
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "real-ezkl"))]
+    async fn test_check_proof_verifiable_matching_onchain_key() {
+        let onchain = key_hash(&verifying_key().key_data);
+        let (submitter, _) = create_submitter(Some(onchain));
+        let submitter = submitter.with_proof_keys(proving_key(), verifying_key());
+        assert!(submitter.check_proof_verifiable().await.is_ok());
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "real-ezkl"))]
+    async fn test_incompatible_local_keys_rejected() {
+        let (submitter, _) = create_submitter(None);
+        let bad_proving_key = ProvingKey {
+            key_data: vec![0xCC; 64],
+        };
+        let submitter = submitter.with_proof_keys(bad_proving_key.clone(), verifying_key());
+
+        match submitter.check_proof_verifiable().await {
+            Err(SubmissionError::KeyMismatch {
+                local_key_hash,
+                expected_key_hash,
+            }) => {
+                assert_eq!(local_key_hash, key_hash(&bad_proving_key.key_data));
+                assert_eq!(expected_key_hash, key_hash(&verifying_key().key_data));
+            }
+            other => panic!("expected KeyMismatch, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_onchain_key_mismatch_blocks_submission() {
+        let onchain = H256::random();
+        let (submitter, marketplace) = create_submitter(Some(onchain));
+        let submitter = submitter.with_proof_keys(proving_key(), verifying_key());
+        let job_id = H256::random();
+        let result = InferenceResult {
+            job_id,
+            output: "answer".to_string(),
+            tokens_used: 3,
+            inference_time_ms: 10,
+            ..Default::default()
+        };
+
+        let err = submitter
+            .submit_result_with_proof(result, test_proof(job_id))
+            .await
+            .unwrap_err();
+
+        match err {
+            SubmissionError::KeyMismatch {
+                local_key_hash,
+                expected_key_hash,
+            } => {
+                assert_eq!(local_key_hash, key_hash(&verifying_key().key_data));
+                assert_eq!(expected_key_hash, onchain);
+            }
+            other => panic!("expected KeyMismatch, got {:?}", other),
+        }
+        assert!(marketplace.results.read().await.is_empty());
+    }
+
+    fn claimed_result(
+        submitter: &ResultSubmitter,
+        marketplace: &MockJobMarketplace,
+    ) -> InferenceResult {
+        let job_id = H256::random();
+        marketplace
+            .claimed_jobs
+            .try_write()
+            .unwrap()
+            .insert(job_id, submitter.config.node_address);
+        InferenceResult {
+            job_id,
+            output: "answer".to_string(),
+            tokens_used: 3,
+            inference_time_ms: 10,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resubmission_returns_existing_reference() {
+        let (submitter, marketplace) = create_submitter(None);
+        let result = claimed_result(&submitter, &marketplace);
+
+        let tx_hash = submitter.submit_result(result.clone()).await.unwrap();
+        match submitter.submit_result(result).await {
+            Err(SubmissionError::AlreadySubmitted {
+                tx_hash: existing, ..
+            }) => assert_eq!(existing, Some(tx_hash)),
+            other => panic!("expected AlreadySubmitted, got {:?}", other),
+        }
+        assert_eq!(marketplace.results.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_crash_after_send_is_recovered_from_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.json");
+        let (submitter, marketplace) = create_submitter(None);
+        let submitter = submitter.with_journal(SubmissionJournal::open(&path).await.unwrap());
+        let result = claimed_result(&submitter, &marketplace);
+
+        // Simulate a crash: journaled as in flight, and the tx landed on-chain
+        let cids = StoredCids {
+            output_cid: "QmOutput".to_string(),
+            metadata_cid: None,
+            proof_cid: None,
+        };
+        submitter
+            .journal
+            .record(result.job_id, JournalState::Submitting { cids })
+            .await
+            .unwrap();
+        let landed = H256::random();
+        marketplace.onchain.write().await.insert(
+            result.job_id,
+            OnChainResult {
+                node: submitter.config.node_address,
+                output_cid: "QmOutput".to_string(),
+                tx_hash: Some(landed),
+            },
+        );
+
+        // After restart the journal still knows the job is unconfirmed
+        let reopened = SubmissionJournal::open(&path).await.unwrap();
+        assert_eq!(reopened.unconfirmed().await, vec![result.job_id]);
+
+        let submitter = submitter.with_journal(reopened);
+        assert!(submitter.recover_unconfirmed().await.unwrap().is_empty());
+        let err = submitter.submit_result(result).await.unwrap_err();
+        assert!(err.is_already_submitted());
+        assert!(marketplace.results.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_submission_reuses_stored_output() {
+        let (submitter, marketplace) = create_submitter(None);
+        let result = claimed_result(&submitter, &marketplace);
+        let cids = StoredCids {
+            output_cid: "QmEarlier".to_string(),
+            metadata_cid: None,
+            proof_cid: None,
+        };
+        submitter
+            .journal
+            .record(result.job_id, JournalState::Submitting { cids })
+            .await
+            .unwrap();
+
+        submitter.submit_result(result).await.unwrap();
+        let results = marketplace.results.read().await;
+        assert_eq!(results[0].1.output_cid, "QmEarlier");
+    }
 }
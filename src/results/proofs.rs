@@ -8,7 +8,7 @@ use sha2::{Digest, Sha256};
 use std::path::Path;
 
 // EZKL integration (Phase 2.1, Phase 3.1)
-use crate::crypto::ezkl::{EzklProver, EzklVerifier, ProofData, WitnessBuilder};
+use crate::crypto::ezkl::{EzklProver, EzklVerifier, ProofCache, ProofData, WitnessBuilder};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InferenceProof {
@@ -87,7 +87,7 @@ impl ProofGenerator {
                         .build()
                         .map_err(|e| anyhow::anyhow!("Failed to build EZKL witness: {}", e))?;
 
-                    let mut prover = EzklProver::new();
+                    let mut prover = EzklProver::new().with_cache(ProofCache::shared());
                     let proof_data = prover
                         .generate_proof(&witness)
                         .map_err(|e| anyhow::anyhow!("Failed to generate EZKL proof: {}", e))?;
@@ -1,12 +1,23 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use super::packager::PackagedResult;
+use crate::storage::cbor_compat::{CborCompat, DirV1, DirV1Entry};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use cid::Cid;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Size of each chunk in a chunked upload
+pub const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
+/// Uploads smaller than this go up in a single request
+pub const DEFAULT_STREAMING_THRESHOLD: usize = 4 * 1024 * 1024;
+
+const SHA2_256: u64 = 0x12;
+const DAG_CBOR_CODEC: u64 = 0x71;
+const RAW_CODEC: u64 = 0x55;
 
 #[derive(Debug, Clone)]
 pub struct S5StorageConfig {
@@ -39,6 +50,10 @@ pub struct S5StorageClient {
     // In-memory storage for testing
     storage: std::sync::Arc<tokio::sync::Mutex<HashMap<String, Vec<u8>>>>,
     metadata_store: std::sync::Arc<tokio::sync::Mutex<HashMap<String, StorageMetadata>>>,
+    // CIDs and paths that hold a DirV1 chunk manifest rather than content
+    manifests: std::sync::Arc<tokio::sync::Mutex<HashSet<String>>>,
+    chunk_size: usize,
+    streaming_threshold: usize,
 }
 
 #[derive(Clone)]
@@ -63,51 +78,190 @@ impl S5StorageClient {
             cbor_encoder: CborEncoder,
             storage: std::sync::Arc::new(tokio::sync::Mutex::new(HashMap::new())),
             metadata_store: std::sync::Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            manifests: std::sync::Arc::new(tokio::sync::Mutex::new(HashSet::new())),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            streaming_threshold: DEFAULT_STREAMING_THRESHOLD,
         }
     }
 
+    /// Override the chunk size and the size above which uploads are chunked
+    pub fn with_chunking(mut self, chunk_size: usize, streaming_threshold: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self.streaming_threshold = streaming_threshold;
+        self
+    }
+
     pub async fn store_result(&self, result: &PackagedResult) -> Result<StorageResult> {
         // Encode result as CBOR
         let cbor_data = self.cbor_encoder.encode_deterministic(result)?;
-        let size_bytes = cbor_data.len();
 
-        // Calculate CID
-        let mut hasher = Sha256::new();
-        hasher.update(&cbor_data);
-        let hash = hasher.finalize();
+        let job_id = &result.result.job_id;
+        let node_id = &result.result.node_id;
+        if cbor_data.len() >= self.streaming_threshold {
+            return self
+                .store_stream(job_id, node_id, "application/cbor", &cbor_data[..])
+                .await;
+        }
+
+        self.store_single(job_id, node_id, "application/cbor", cbor_data)
+            .await
+    }
+
+    /// Upload content from a reader without buffering all of it
+    ///
+    /// Content below the streaming threshold is uploaded in one request.
+    /// Anything larger is uploaded in `chunk_size` pieces, followed by a
+    /// DirV1 manifest listing the chunks in order; the manifest CID is the
+    /// returned CID. At most one threshold's worth of data is held at once.
+    pub async fn store_stream<R: AsyncRead + Unpin>(
+        &self,
+        job_id: &str,
+        node_id: &str,
+        content_type: &str,
+        mut reader: R,
+    ) -> Result<StorageResult> {
+        let mut buffered: Vec<Vec<u8>> = Vec::new();
+        let mut buffered_len = 0;
+        let mut entries = HashMap::new();
+        let mut total_size = 0u64;
+        let mut chunk_count = 0usize;
+        let mut streaming = false;
+
+        loop {
+            let chunk = self.read_chunk(&mut reader).await?;
+            let at_eof = chunk.len() < self.chunk_size;
+
+            if !chunk.is_empty() {
+                buffered_len += chunk.len();
+                buffered.push(chunk);
+            }
+
+            if !streaming && buffered_len >= self.streaming_threshold {
+                streaming = true;
+            }
+
+            if streaming {
+                for chunk in buffered.drain(..) {
+                    total_size += chunk.len() as u64;
+                    let (name, entry) = self.upload_chunk(chunk_count, chunk).await?;
+                    entries.insert(name, entry);
+                    chunk_count += 1;
+                }
+                buffered_len = 0;
+            }
+
+            if at_eof {
+                break;
+            }
+        }
+
+        if !streaming {
+            return self
+                .store_single(job_id, node_id, content_type, buffered.concat())
+                .await;
+        }
+
+        let manifest = DirV1 {
+            version: 1,
+            entries,
+            metadata: HashMap::from([
+                ("type".to_string(), "chunked".to_string()),
+                ("content_type".to_string(), content_type.to_string()),
+                ("total_size".to_string(), total_size.to_string()),
+                ("chunk_count".to_string(), chunk_count.to_string()),
+                ("chunk_size".to_string(), self.chunk_size.to_string()),
+            ]),
+        };
+        let manifest_data = CborCompat::new()
+            .encode_dirv1(&manifest)
+            .context("Failed to encode chunk manifest")?;
+        let cid_str = compute_cid(DAG_CBOR_CODEC, &manifest_data)?;
+        let path = self.result_path(job_id);
 
-        // Create multihash from the hash
-        let mh = multihash::Multihash::wrap(0x12, &hash).context("Failed to create multihash")?;
+        {
+            let mut storage = self.storage.lock().await;
+            storage.insert(cid_str.clone(), manifest_data.clone());
+            storage.insert(path.clone(), manifest_data);
+        }
+        {
+            let mut manifests = self.manifests.lock().await;
+            manifests.insert(cid_str.clone());
+            manifests.insert(path.clone());
+        }
 
-        // Create a CID (using SHA-256 and CBOR codec)
-        let cid = Cid::new_v1(0x71, mh);
-        let cid_str = cid.to_string();
+        let metadata = StorageMetadata {
+            cid: cid_str.clone(),
+            size_bytes: total_size as usize,
+            content_type: content_type.to_string(),
+            timestamp: Utc::now(),
+            node_id: node_id.to_string(),
+            job_id: job_id.to_string(),
+        };
+        self.record_metadata(&metadata).await;
 
-        // Construct path
-        let path = format!(
-            "{}/results/{}/result.cbor",
-            self.config.base_path, result.result.job_id
-        );
+        Ok(StorageResult {
+            cid: cid_str,
+            path,
+            metadata,
+        })
+    }
+
+    /// Fetch content by CID, reassembling chunked uploads
+    pub async fn retrieve_bytes(&self, cid: &str) -> Result<Vec<u8>> {
+        self.load(cid)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("CID not found"))
+    }
+
+    pub async fn retrieve_result(&self, cid: &str) -> Result<PackagedResult> {
+        let data = self.retrieve_bytes(cid).await?;
+        self.cbor_encoder.decode(&data)
+    }
+
+    pub async fn retrieve_by_path(&self, job_id: &str) -> Result<PackagedResult> {
+        let path = self.result_path(job_id);
+
+        let data = self
+            .load(&path)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Result not found for job_id: {}", job_id))?;
+
+        self.cbor_encoder.decode(&data)
+    }
+
+    fn result_path(&self, job_id: &str) -> String {
+        format!("{}/results/{}/result.cbor", self.config.base_path, job_id)
+    }
+
+    async fn store_single(
+        &self,
+        job_id: &str,
+        node_id: &str,
+        content_type: &str,
+        data: Vec<u8>,
+    ) -> Result<StorageResult> {
+        let size_bytes = data.len();
+        let cid_str = compute_cid(DAG_CBOR_CODEC, &data)?;
+        let path = self.result_path(job_id);
 
         // Store data (in-memory for testing)
         let mut storage = self.storage.lock().await;
-        storage.insert(cid_str.clone(), cbor_data.clone());
-        storage.insert(path.clone(), cbor_data);
+        storage.insert(cid_str.clone(), data.clone());
+        storage.insert(path.clone(), data);
+        drop(storage);
+
+        // A re-upload below the threshold replaces an earlier manifest
+        self.manifests.lock().await.remove(&path);
 
-        // Create metadata
         let metadata = StorageMetadata {
             cid: cid_str.clone(),
             size_bytes,
-            content_type: "application/cbor".to_string(),
+            content_type: content_type.to_string(),
             timestamp: Utc::now(),
-            node_id: result.result.node_id.clone(),
-            job_id: result.result.job_id.clone(),
+            node_id: node_id.to_string(),
+            job_id: job_id.to_string(),
         };
-
-        // Store metadata
-        let mut metadata_store = self.metadata_store.lock().await;
-        metadata_store.insert(cid_str.clone(), metadata.clone());
-        metadata_store.insert(result.result.job_id.clone(), metadata.clone());
+        self.record_metadata(&metadata).await;
 
         Ok(StorageResult {
             cid: cid_str,
@@ -116,24 +270,72 @@ impl S5StorageClient {
         })
     }
 
-    pub async fn retrieve_result(&self, cid: &str) -> Result<PackagedResult> {
-        let storage = self.storage.lock().await;
-        let data = storage
-            .get(cid)
-            .ok_or_else(|| anyhow::anyhow!("CID not found"))?;
+    async fn record_metadata(&self, metadata: &StorageMetadata) {
+        let mut metadata_store = self.metadata_store.lock().await;
+        metadata_store.insert(metadata.cid.clone(), metadata.clone());
+        metadata_store.insert(metadata.job_id.clone(), metadata.clone());
+    }
 
-        self.cbor_encoder.decode(data)
+    /// Read up to `chunk_size` bytes, short only at end of input
+    async fn read_chunk<R: AsyncRead + Unpin>(&self, reader: &mut R) -> Result<Vec<u8>> {
+        let mut chunk = vec![0u8; self.chunk_size];
+        let mut filled = 0;
+        while filled < chunk.len() {
+            let n = reader
+                .read(&mut chunk[filled..])
+                .await
+                .context("Failed to read upload stream")?;
+            if n == 0 {
+                break;
+            }
+            filled += n;
+        }
+        chunk.truncate(filled);
+        Ok(chunk)
     }
 
-    pub async fn retrieve_by_path(&self, job_id: &str) -> Result<PackagedResult> {
-        let path = format!("{}/results/{}/result.cbor", self.config.base_path, job_id);
+    async fn upload_chunk(&self, index: usize, chunk: Vec<u8>) -> Result<(String, DirV1Entry)> {
+        let cid = compute_cid(RAW_CODEC, &chunk)?;
+        let size = chunk.len() as u64;
+        self.storage.lock().await.insert(cid.clone(), chunk);
+
+        // Zero-padded so lexical order matches upload order
+        let name = format!("chunk-{:08}", index);
+        let entry = DirV1Entry {
+            cid,
+            size,
+            entry_type: "chunk".to_string(),
+            metadata: HashMap::from([("index".to_string(), index.to_string())]),
+        };
+        Ok((name, entry))
+    }
 
+    /// Load the content at `key`, following a chunk manifest if there is one
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let is_manifest = self.manifests.lock().await.contains(key);
         let storage = self.storage.lock().await;
-        let data = storage
-            .get(&path)
-            .ok_or_else(|| anyhow::anyhow!("Result not found for job_id: {}", job_id))?;
+        let Some(data) = storage.get(key) else {
+            return Ok(None);
+        };
+        if !is_manifest {
+            return Ok(Some(data.clone()));
+        }
 
-        self.cbor_encoder.decode(data)
+        let manifest = CborCompat::new()
+            .decode_dirv1(data)
+            .context("Failed to decode chunk manifest")?;
+        let mut names: Vec<&String> = manifest.entries.keys().collect();
+        names.sort();
+
+        let mut content = Vec::new();
+        for name in names {
+            let entry = &manifest.entries[name];
+            let chunk = storage
+                .get(&entry.cid)
+                .ok_or_else(|| anyhow::anyhow!("Missing chunk {} ({})", name, entry.cid))?;
+            content.extend_from_slice(chunk);
+        }
+        Ok(Some(content))
     }
 
     pub async fn store_with_metadata(
@@ -161,10 +363,11 @@ impl S5StorageClient {
     }
 
     pub async fn delete_result(&self, job_id: &str) -> Result<()> {
-        let path = format!("{}/results/{}/result.cbor", self.config.base_path, job_id);
+        let path = self.result_path(job_id);
 
         let mut storage = self.storage.lock().await;
         storage.remove(&path);
+        self.manifests.lock().await.remove(&path);
 
         let mut metadata_store = self.metadata_store.lock().await;
         metadata_store.remove(job_id);
@@ -172,3 +375,10 @@ impl S5StorageClient {
         Ok(())
     }
 }
+
+/// CIDv1 over the SHA-256 of `data`
+fn compute_cid(codec: u64, data: &[u8]) -> Result<String> {
+    let hash = Sha256::digest(data);
+    let mh = multihash::Multihash::wrap(SHA2_256, &hash).context("Failed to create multihash")?;
+    Ok(Cid::new_v1(codec, mh).to_string())
+}
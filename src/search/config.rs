@@ -3,6 +3,8 @@
 //! Configuration for web search functionality
 
 use std::env;
+use std::fmt;
+use std::str::FromStr;
 
 /// Configuration for web search functionality
 #[derive(Debug, Clone)]
@@ -23,6 +25,71 @@ pub struct SearchConfig {
     pub default_num_results: usize,
     /// Request timeout in milliseconds
     pub request_timeout_ms: u64,
+    /// Providers to try, in order, until one succeeds
+    pub provider_chain: Vec<ProviderKind>,
+}
+
+/// A web search backend that can appear in the failover chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProviderKind {
+    Brave,
+    Bing,
+    SerpApi,
+    DuckDuckGo,
+}
+
+impl ProviderKind {
+    /// Provider name as reported in `SearchResponse::provider`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProviderKind::Brave => "brave",
+            ProviderKind::Bing => "bing",
+            ProviderKind::SerpApi => "serpapi",
+            ProviderKind::DuckDuckGo => "duckduckgo",
+        }
+    }
+
+    /// Default failover order: keyed APIs first, DuckDuckGo as the fallback
+    pub fn default_chain() -> Vec<ProviderKind> {
+        vec![
+            ProviderKind::Brave,
+            ProviderKind::Bing,
+            ProviderKind::SerpApi,
+            ProviderKind::DuckDuckGo,
+        ]
+    }
+
+    /// Parses a comma-separated chain such as `brave,duckduckgo`
+    pub fn parse_chain(value: &str) -> Result<Vec<ProviderKind>, String> {
+        let mut chain = Vec::new();
+        for name in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let kind = name.parse()?;
+            if !chain.contains(&kind) {
+                chain.push(kind);
+            }
+        }
+        Ok(chain)
+    }
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "brave" => Ok(ProviderKind::Brave),
+            "bing" => Ok(ProviderKind::Bing),
+            "serpapi" | "google" => Ok(ProviderKind::SerpApi),
+            "duckduckgo" | "ddg" => Ok(ProviderKind::DuckDuckGo),
+            other => Err(format!("Unknown search provider: {}", other)),
+        }
+    }
 }
 
 /// Provider-specific configuration
@@ -32,6 +99,11 @@ pub struct SearchProviderConfig {
     pub brave_api_key: Option<String>,
     /// Bing Search API key
     pub bing_api_key: Option<String>,
+    /// SerpAPI key (Google results)
+    pub serpapi_api_key: Option<String>,
+    /// Per-minute limit for SerpAPI, whose plans have smaller quotas than
+    /// the other providers. Falls back to `rate_limit_per_minute` if unset.
+    pub serpapi_rate_limit_per_minute: Option<u32>,
     /// Preferred search provider
     pub preferred_provider: String,
 }
@@ -48,6 +120,10 @@ impl SearchConfig {
             providers: SearchProviderConfig {
                 brave_api_key: env::var("BRAVE_API_KEY").ok(),
                 bing_api_key: env::var("BING_API_KEY").ok(),
+                serpapi_api_key: env::var("SERPAPI_API_KEY").ok(),
+                serpapi_rate_limit_per_minute: env::var("SERPAPI_RATE_LIMIT_PER_MINUTE")
+                    .ok()
+                    .and_then(|v| v.parse().ok()),
                 preferred_provider: env::var("SEARCH_PROVIDER")
                     .unwrap_or_else(|_| "brave".to_string()),
             },
@@ -69,6 +145,12 @@ impl SearchConfig {
                 .unwrap_or(60),
             default_num_results: 10,
             request_timeout_ms: 10000,
+            // e.g. SEARCH_PROVIDER_CHAIN=brave,duckduckgo
+            provider_chain: env::var("SEARCH_PROVIDER_CHAIN")
+                .ok()
+                .and_then(|v| ProviderKind::parse_chain(&v).ok())
+                .filter(|chain| !chain.is_empty())
+                .unwrap_or_else(ProviderKind::default_chain),
         }
     }
 
@@ -82,12 +164,29 @@ impl SearchConfig {
         if self.rate_limit_per_minute == 0 {
             return Err("Rate limit must be greater than 0".to_string());
         }
+        if self.provider_chain.is_empty() {
+            return Err("Provider chain must list at least one provider".to_string());
+        }
         Ok(())
     }
 
     /// Check if any search provider is configured
     pub fn has_any_provider(&self) -> bool {
-        self.providers.brave_api_key.is_some() || self.providers.bing_api_key.is_some()
+        self.providers.brave_api_key.is_some()
+            || self.providers.bing_api_key.is_some()
+            || self.providers.serpapi_api_key.is_some()
+    }
+
+    /// Requests per minute allowed for a single provider in the chain
+    pub fn rate_limit_for(&self, kind: ProviderKind) -> u32 {
+        match kind {
+            ProviderKind::SerpApi => self
+                .providers
+                .serpapi_rate_limit_per_minute
+                .filter(|&limit| limit > 0)
+                .unwrap_or(self.rate_limit_per_minute),
+            _ => self.rate_limit_per_minute,
+        }
     }
 }
 
@@ -98,6 +197,8 @@ impl Default for SearchConfig {
             providers: SearchProviderConfig {
                 brave_api_key: None,
                 bing_api_key: None,
+                serpapi_api_key: None,
+                serpapi_rate_limit_per_minute: None,
                 preferred_provider: "brave".to_string(),
             },
             cache_ttl_secs: 3600,
@@ -106,6 +207,7 @@ impl Default for SearchConfig {
             rate_limit_per_minute: 60,
             default_num_results: 10,
             request_timeout_ms: 10000,
+            provider_chain: ProviderKind::default_chain(),
         }
     }
 }
@@ -140,6 +242,24 @@ mod tests {
 
         config.providers.brave_api_key = Some("key".to_string());
         assert!(config.has_any_provider());
+
+        let mut config = SearchConfig::default();
+        config.providers.serpapi_api_key = Some("key".to_string());
+        assert!(config.has_any_provider());
+    }
+
+    #[test]
+    fn test_serpapi_rate_limit_override() {
+        let mut config = SearchConfig::default();
+        assert_eq!(config.rate_limit_for(ProviderKind::SerpApi), 60);
+
+        config.providers.serpapi_rate_limit_per_minute = Some(5);
+        assert_eq!(config.rate_limit_for(ProviderKind::SerpApi), 5);
+        assert_eq!(config.rate_limit_for(ProviderKind::Brave), 60);
+        assert_eq!(
+            ProviderKind::parse_chain("google").unwrap(),
+            vec![ProviderKind::SerpApi]
+        );
     }
 
     #[test]
@@ -149,6 +269,25 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_parse_provider_chain() {
+        let chain = ProviderKind::parse_chain("duckduckgo, Brave,duckduckgo").unwrap();
+        assert_eq!(chain, vec![ProviderKind::DuckDuckGo, ProviderKind::Brave]);
+
+        assert!(ProviderKind::parse_chain("brave,altavista").is_err());
+        assert_eq!(
+            SearchConfig::default().provider_chain,
+            ProviderKind::default_chain()
+        );
+    }
+
+    #[test]
+    fn test_config_validation_empty_provider_chain() {
+        let mut config = SearchConfig::default();
+        config.provider_chain.clear();
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_config_validation_zero_rate_limit() {
         let mut config = SearchConfig::default();
@@ -12,6 +12,7 @@ pub struct CachedContent {
     pub url: String,
     pub title: String,
     pub text: String,
+    pub quality: f64,
     pub fetched_at: Instant,
 }
 
@@ -58,7 +59,7 @@ impl ContentCache {
     }
 
     /// Insert content into cache
-    pub fn insert(&self, url: &str, title: String, text: String) {
+    pub fn insert(&self, url: &str, title: String, text: String, quality: f64) {
         let mut cache = match self.cache.write() {
             Ok(c) => c,
             Err(_) => return,
@@ -76,6 +77,7 @@ impl ContentCache {
                 url: url.to_string(),
                 title,
                 text,
+                quality,
                 fetched_at: Instant::now(),
             },
         );
@@ -141,6 +143,7 @@ mod tests {
             "https://example.com/page",
             "Example Title".to_string(),
             "Example content".to_string(),
+            1.0,
         );
 
         let result = cache.get("https://example.com/page");
@@ -159,6 +162,7 @@ mod tests {
             "https://example.com/expire",
             "Title".to_string(),
             "Content".to_string(),
+            1.0,
         );
 
         // Should exist immediately
@@ -179,6 +183,7 @@ mod tests {
             "https://Example.COM/Page/",
             "Title".to_string(),
             "Content".to_string(),
+            1.0,
         );
 
         // Should match with different case/trailing slash
@@ -195,6 +200,7 @@ mod tests {
                 &format!("https://example.com/{}", i),
                 format!("Title {}", i),
                 format!("Content {}", i),
+                1.0,
             );
         }
 
@@ -206,8 +212,18 @@ mod tests {
     fn test_cache_stats() {
         let cache = ContentCache::new(3600, 100);
 
-        cache.insert("https://example.com/1", "T1".to_string(), "C1".to_string());
-        cache.insert("https://example.com/2", "T2".to_string(), "C2".to_string());
+        cache.insert(
+            "https://example.com/1",
+            "T1".to_string(),
+            "C1".to_string(),
+            1.0,
+        );
+        cache.insert(
+            "https://example.com/2",
+            "T2".to_string(),
+            "C2".to_string(),
+            1.0,
+        );
 
         let stats = cache.stats();
         assert_eq!(stats.total, 2);
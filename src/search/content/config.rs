@@ -23,6 +23,14 @@ pub struct ContentFetchConfig {
     pub cache_ttl_secs: u64,
     /// Maximum cache entries (default: 500)
     pub max_cache_entries: usize,
+    /// Check robots.txt before fetching a page (default: true)
+    pub respect_robots_txt: bool,
+    /// Hosts exempt from the robots.txt check, e.g. internal docs sites.
+    /// Subdomains of a listed host are exempt too.
+    pub robots_allowlist: Vec<String>,
+    /// Minimum extraction quality (0.0-1.0) for fetched content to be used;
+    /// below it the search snippet is used instead (default: 0.3)
+    pub min_quality_score: f64,
 }
 
 impl ContentFetchConfig {
@@ -58,7 +66,37 @@ impl ContentFetchConfig {
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(1800),
             max_cache_entries: 500,
+            respect_robots_txt: env::var("CONTENT_FETCH_RESPECT_ROBOTS")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            robots_allowlist: env::var("CONTENT_FETCH_ROBOTS_ALLOWLIST")
+                .map(|v| {
+                    v.split(',')
+                        .map(|h| h.trim().to_lowercase())
+                        .filter(|h| !h.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            min_quality_score: env::var("CONTENT_FETCH_MIN_QUALITY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.3),
+        }
+    }
+
+    /// Whether robots.txt should be checked before fetching from `host`
+    pub fn should_check_robots(&self, host: &str) -> bool {
+        if !self.respect_robots_txt {
+            return false;
         }
+
+        let host = host.to_lowercase();
+        !self.robots_allowlist.iter().any(|allowed| {
+            host == *allowed
+                || host
+                    .strip_suffix(allowed.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
     }
 
     /// Validate configuration values
@@ -72,6 +110,9 @@ impl ContentFetchConfig {
         if self.timeout_per_page_secs == 0 {
             return Err("timeout_per_page_secs must be at least 1".to_string());
         }
+        if !(0.0..=1.0).contains(&self.min_quality_score) {
+            return Err("min_quality_score must be between 0.0 and 1.0".to_string());
+        }
         Ok(())
     }
 }
@@ -87,6 +128,9 @@ impl Default for ContentFetchConfig {
             total_timeout_secs: 10,
             cache_ttl_secs: 1800,
             max_cache_entries: 500,
+            respect_robots_txt: true,
+            robots_allowlist: Vec::new(),
+            min_quality_score: 0.3,
         }
     }
 }
@@ -105,6 +149,21 @@ mod tests {
         assert_eq!(config.timeout_per_page_secs, 5);
         assert_eq!(config.total_timeout_secs, 10);
         assert_eq!(config.cache_ttl_secs, 1800);
+        assert!(config.respect_robots_txt);
+    }
+
+    #[test]
+    fn test_robots_allowlist() {
+        let mut config = ContentFetchConfig::default();
+        config.robots_allowlist = vec!["docs.internal".to_string()];
+
+        assert!(config.should_check_robots("example.com"));
+        assert!(!config.should_check_robots("docs.internal"));
+        assert!(!config.should_check_robots("api.Docs.Internal"));
+        assert!(config.should_check_robots("notdocs.internal"));
+
+        config.respect_robots_txt = false;
+        assert!(!config.should_check_robots("example.com"));
     }
 
     #[test]
@@ -118,6 +177,10 @@ mod tests {
         config.max_pages = 3;
         config.max_chars_per_page = 50;
         assert!(config.validate().is_err());
+
+        config.max_chars_per_page = 3000;
+        config.min_quality_score = 1.5;
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -2,7 +2,36 @@
 //!
 //! Extracts main content from web pages using CSS selectors.
 
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
+
+/// Elements whose text is boilerplate rather than page content. `<header>` is
+/// kept because articles often put their title in one.
+const NOISE_TAGS: [&str; 8] = [
+    "script", "style", "noscript", "nav", "footer", "aside", "form", "template",
+];
+
+/// Characters per element at which text density scores as fully dense
+const DENSE_CHARS_PER_ELEMENT: f64 = 80.0;
+
+/// Characters of text at which length scores as fully substantial
+const SUBSTANTIAL_CHARS: f64 = 500.0;
+
+/// Extracted text with a readability-style confidence score
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedContent {
+    /// Cleaned and truncated text
+    pub text: String,
+    /// Extraction quality in `0.0..=1.0`, see `quality_score`
+    pub quality: f64,
+}
+
+/// Raw text statistics gathered while walking an element
+#[derive(Debug, Default)]
+struct TextStats {
+    text: String,
+    link_chars: usize,
+    elements: usize,
+}
 
 /// Extract main content from HTML
 ///
@@ -17,9 +46,12 @@ use scraper::{Html, Selector};
 /// * `html` - Raw HTML string
 /// * `max_chars` - Maximum characters to return
 ///
+/// Script, style, nav, footer and similar boilerplate elements are skipped
+/// in every strategy.
+///
 /// # Returns
-/// Extracted text content, cleaned and truncated
-pub fn extract_main_content(html: &str, max_chars: usize) -> String {
+/// Extracted text content, cleaned and truncated, with its quality score
+pub fn extract_main_content(html: &str, max_chars: usize) -> ExtractedContent {
     let document = Html::parse_document(html);
 
     // Priority order of selectors to try
@@ -41,11 +73,14 @@ pub fn extract_main_content(html: &str, max_chars: usize) -> String {
     for selector_str in selectors {
         if let Ok(selector) = Selector::parse(selector_str) {
             if let Some(element) = document.select(&selector).next() {
-                let text = extract_text_from_element(&element);
-                let cleaned = clean_text(&text);
+                let stats = collect_text(element);
+                let cleaned = clean_text(&stats.text);
                 if cleaned.len() > 200 {
                     // Found substantial content
-                    return truncate_content(&cleaned, max_chars);
+                    return ExtractedContent {
+                        quality: quality_score(&stats, cleaned.len()),
+                        text: truncate_content(&cleaned, max_chars),
+                    };
                 }
             }
         }
@@ -55,22 +90,71 @@ pub fn extract_main_content(html: &str, max_chars: usize) -> String {
     extract_body_text(&document, max_chars)
 }
 
-/// Extract text from an HTML element, stripping tags
-fn extract_text_from_element(element: &scraper::ElementRef) -> String {
-    element.text().collect::<Vec<_>>().join(" ")
+/// Collect text from an element, skipping noise elements and counting how
+/// much of the text sits inside links
+fn collect_text(element: ElementRef) -> TextStats {
+    let mut stats = TextStats::default();
+    walk(element, false, &mut stats);
+    stats
+}
+
+fn walk(element: ElementRef, in_link: bool, stats: &mut TextStats) {
+    stats.elements += 1;
+    let in_link = in_link || element.value().name() == "a";
+
+    for child in element.children() {
+        if let Some(child_element) = ElementRef::wrap(child) {
+            if !NOISE_TAGS.contains(&child_element.value().name()) {
+                walk(child_element, in_link, stats);
+            }
+        } else if let Some(text) = child.value().as_text() {
+            if in_link {
+                stats.link_chars += text.trim().len();
+            }
+            stats.text.push_str(text);
+            stats.text.push(' ');
+        }
+    }
+}
+
+/// Score how much an extraction looks like real content:
+///
+/// `quality = (1 - link_ratio) * (0.6 * density + 0.4 * length)`
+///
+/// where `link_ratio` is the share of text inside `<a>` tags, `density` is
+/// characters per element relative to `DENSE_CHARS_PER_ELEMENT`, and `length`
+/// is the text length relative to `SUBSTANTIAL_CHARS`, both capped at 1.0.
+/// Navigation menus score low on the first two, stub pages on the last.
+fn quality_score(stats: &TextStats, text_chars: usize) -> f64 {
+    if text_chars == 0 {
+        return 0.0;
+    }
+
+    let link_ratio = (stats.link_chars as f64 / text_chars as f64).min(1.0);
+    let density =
+        (text_chars as f64 / stats.elements.max(1) as f64 / DENSE_CHARS_PER_ELEMENT).min(1.0);
+    let length = (text_chars as f64 / SUBSTANTIAL_CHARS).min(1.0);
+
+    (1.0 - link_ratio) * (0.6 * density + 0.4 * length)
 }
 
 /// Extract text from body, removing common noise elements
-fn extract_body_text(document: &Html, max_chars: usize) -> String {
+fn extract_body_text(document: &Html, max_chars: usize) -> ExtractedContent {
     // Try to get body
     if let Ok(body_selector) = Selector::parse("body") {
         if let Some(body) = document.select(&body_selector).next() {
-            let text = extract_text_from_element(&body);
-            let cleaned = clean_text(&text);
-            return truncate_content(&cleaned, max_chars);
+            let stats = collect_text(body);
+            let cleaned = clean_text(&stats.text);
+            return ExtractedContent {
+                quality: quality_score(&stats, cleaned.len()),
+                text: truncate_content(&cleaned, max_chars),
+            };
         }
     }
-    String::new()
+    ExtractedContent {
+        text: String::new(),
+        quality: 0.0,
+    }
 }
 
 /// Clean text: normalize whitespace, remove excess blank lines
@@ -161,7 +245,7 @@ mod tests {
 
     #[test]
     fn test_extract_article_content() {
-        let content = extract_main_content(SAMPLE_HTML_ARTICLE, 3000);
+        let content = extract_main_content(SAMPLE_HTML_ARTICLE, 3000).text;
         assert!(content.contains("Main Article Title"));
         assert!(content.contains("main content"));
         assert!(!content.contains("Navigation"));
@@ -170,7 +254,7 @@ mod tests {
 
     #[test]
     fn test_extract_main_content() {
-        let content = extract_main_content(SAMPLE_HTML_MAIN, 3000);
+        let content = extract_main_content(SAMPLE_HTML_MAIN, 3000).text;
         assert!(content.contains("Page Title"));
         assert!(content.contains("Main content"));
         assert!(!content.contains("Site Header"));
@@ -179,10 +263,51 @@ mod tests {
 
     #[test]
     fn test_extract_content_with_class() {
-        let content = extract_main_content(SAMPLE_HTML_CLASS, 3000);
+        let content = extract_main_content(SAMPLE_HTML_CLASS, 3000).text;
         assert!(content.contains("Blog post content"));
     }
 
+    const SAMPLE_HTML_NAV_ONLY: &str = r#"
+        <!DOCTYPE html>
+        <html>
+        <body>
+            <div class="menu">
+                <ul>
+                    <li><a href="/">Home</a></li>
+                    <li><a href="/products">Products</a></li>
+                    <li><a href="/pricing">Pricing</a></li>
+                    <li><a href="/about">About us</a></li>
+                    <li><a href="/contact">Contact</a></li>
+                </ul>
+            </div>
+            <script>var tracking = "should never be extracted";</script>
+            <footer>Copyright notice</footer>
+        </body>
+        </html>
+    "#;
+
+    #[test]
+    fn test_quality_score_article() {
+        let extracted = extract_main_content(SAMPLE_HTML_ARTICLE, 3000);
+        assert!(extracted.quality > 0.7, "quality {}", extracted.quality);
+    }
+
+    #[test]
+    fn test_quality_score_link_heavy_page() {
+        let extracted = extract_main_content(SAMPLE_HTML_NAV_ONLY, 3000);
+        assert!(extracted.quality < 0.1, "quality {}", extracted.quality);
+        assert!(extracted.text.contains("Pricing"));
+        assert!(!extracted.text.contains("tracking"));
+        assert!(!extracted.text.contains("Copyright"));
+    }
+
+    #[test]
+    fn test_quality_score_empty_body() {
+        let extracted = extract_main_content("<html><body></body></html>", 3000);
+        assert_eq!(extracted.text, "");
+        assert_eq!(extracted.quality, 0.0);
+    }
+
     #[test]
     fn test_clean_whitespace() {
         let dirty = "  Hello   world  \n\n  test  ";
@@ -11,6 +11,10 @@ use url::Url;
 use super::cache::ContentCache;
 use super::config::ContentFetchConfig;
 use super::extractor::extract_main_content;
+use super::robots::{RobotsCache, RobotsRules, MAX_ROBOTS_BYTES};
+
+/// Product token we identify as in robots.txt groups
+const ROBOTS_USER_AGENT: &str = "FabstirBot";
 
 /// Fetched page content
 #[derive(Debug, Clone)]
@@ -18,6 +22,8 @@ pub struct PageContent {
     pub url: String,
     pub title: String,
     pub text: String,
+    /// Extraction quality score in `0.0..=1.0`
+    pub quality: f64,
 }
 
 /// Content fetch error types
@@ -33,6 +39,10 @@ pub enum FetchError {
     NoContent(String),
     /// URL is unsafe (localhost, private IP)
     UnsafeUrl(String),
+    /// URL is disallowed by the host's robots.txt
+    Disallowed(String),
+    /// Extracted content scored below `min_quality_score`
+    LowQuality(f64, String),
 }
 
 impl std::fmt::Display for FetchError {
@@ -43,6 +53,10 @@ impl std::fmt::Display for FetchError {
             Self::HttpStatus(code, url) => write!(f, "HTTP {} for: {}", code, url),
             Self::NoContent(url) => write!(f, "No content extracted from: {}", url),
             Self::UnsafeUrl(url) => write!(f, "Unsafe URL blocked: {}", url),
+            Self::Disallowed(url) => write!(f, "Disallowed by robots.txt: {}", url),
+            Self::LowQuality(score, url) => {
+                write!(f, "Low extraction quality {:.2} for: {}", score, url)
+            }
         }
     }
 }
@@ -53,6 +67,7 @@ impl std::error::Error for FetchError {}
 pub struct ContentFetcher {
     client: Client,
     cache: Arc<ContentCache>,
+    robots: Arc<RobotsCache>,
     config: ContentFetchConfig,
 }
 
@@ -71,9 +86,16 @@ impl ContentFetcher {
             config.max_cache_entries,
         ));
 
+        // robots.txt is cached per host with the same TTL as page content
+        let robots = Arc::new(RobotsCache::new(
+            config.cache_ttl_secs,
+            config.max_cache_entries,
+        ));
+
         Self {
             client,
             cache,
+            robots,
             config,
         }
     }
@@ -98,9 +120,15 @@ impl ContentFetcher {
                 url: cached.url,
                 title: cached.title,
                 text: cached.text,
+                quality: cached.quality,
             });
         }
 
+        if !self.is_allowed_by_robots(url).await {
+            debug!("Skipping URL disallowed by robots.txt: {}", url);
+            return Err(FetchError::Disallowed(url.to_string()));
+        }
+
         debug!("Fetching content from: {}", url);
 
         // Fetch page
@@ -150,24 +178,39 @@ impl ContentFetcher {
         }
 
         // Extract content
-        let text = extract_main_content(&html, self.config.max_chars_per_page);
+        let extracted = extract_main_content(&html, self.config.max_chars_per_page);
+        let (text, quality) = (extracted.text, extracted.quality);
 
         if text.len() < 100 {
             return Err(FetchError::NoContent(url.to_string()));
         }
 
+        if quality < self.config.min_quality_score {
+            debug!(
+                "Dropping low quality content ({:.2}) from: {}",
+                quality, url
+            );
+            return Err(FetchError::LowQuality(quality, url.to_string()));
+        }
+
         // Extract title from HTML
         let title = Self::extract_title(&html).unwrap_or_else(|| url.to_string());
 
         // Cache the result
-        self.cache.insert(url, title.clone(), text.clone());
+        self.cache.insert(url, title.clone(), text.clone(), quality);
 
-        info!("Fetched {} chars from: {}", text.len(), url);
+        info!(
+            "Fetched {} chars (quality {:.2}) from: {}",
+            text.len(),
+            quality,
+            url
+        );
 
         Ok(PageContent {
             url: url.to_string(),
             title,
             text,
+            quality,
         })
     }
 
@@ -204,6 +247,72 @@ impl ContentFetcher {
         }
     }
 
+    /// Check the host's robots.txt, fetching and caching it on first use
+    async fn is_allowed_by_robots(&self, url: &str) -> bool {
+        let Ok(parsed) = Url::parse(url) else {
+            return false;
+        };
+        let Some(host) = parsed.host_str() else {
+            return false;
+        };
+        if !self.config.should_check_robots(host) {
+            return true;
+        }
+
+        let origin = parsed.origin().ascii_serialization();
+        let rules = match self.robots.get(&origin) {
+            Some(rules) => rules,
+            None => {
+                let rules = self.fetch_robots(&origin).await;
+                self.robots.insert(&origin, rules)
+            }
+        };
+
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        rules.is_allowed(&path)
+    }
+
+    /// Fetch and parse `{origin}/robots.txt`
+    ///
+    /// A missing robots.txt (4xx) allows everything, while server or network
+    /// errors disallow everything until the cache entry expires (RFC 9309).
+    async fn fetch_robots(&self, origin: &str) -> RobotsRules {
+        let robots_url = format!("{}/robots.txt", origin);
+        let response = match self.client.get(&robots_url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                debug!("robots.txt unreachable at {}: {}", robots_url, e);
+                return RobotsRules::disallow_all();
+            }
+        };
+
+        let status = response.status();
+        if status.is_client_error() {
+            return RobotsRules::allow_all();
+        }
+        if !status.is_success() {
+            debug!("robots.txt returned HTTP {} at {}", status, robots_url);
+            return RobotsRules::disallow_all();
+        }
+
+        match response.text().await {
+            Ok(mut body) => {
+                if body.len() > MAX_ROBOTS_BYTES {
+                    let mut end = MAX_ROBOTS_BYTES;
+                    while !body.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    body.truncate(end);
+                }
+                RobotsRules::parse(&body, ROBOTS_USER_AGENT)
+            }
+            Err(_) => RobotsRules::disallow_all(),
+        }
+    }
+
     /// Check if URL is safe to fetch (not localhost/private IP)
     pub fn is_safe_url(url: &str) -> bool {
         let parsed = match Url::parse(url) {
@@ -377,6 +486,44 @@ mod tests {
         assert!(matches!(result, Err(FetchError::UnsafeUrl(_))));
     }
 
+    #[tokio::test]
+    async fn test_fetch_disallowed_by_robots() {
+        let config = ContentFetchConfig::default();
+        let fetcher = ContentFetcher::new(config);
+
+        // Seed the robots cache so no request is made
+        fetcher.robots.insert(
+            "https://example.com",
+            RobotsRules::parse("User-agent: *\nDisallow: /private\n", ROBOTS_USER_AGENT),
+        );
+
+        let result = fetcher
+            .fetch_content("https://example.com/private/page")
+            .await;
+        assert!(matches!(result, Err(FetchError::Disallowed(_))));
+        assert!(
+            fetcher
+                .is_allowed_by_robots("https://example.com/public")
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_robots_allowlisted_host_skips_check() {
+        let mut config = ContentFetchConfig::default();
+        config.robots_allowlist = vec!["example.com".to_string()];
+        let fetcher = ContentFetcher::new(config);
+
+        fetcher
+            .robots
+            .insert("https://example.com", RobotsRules::disallow_all());
+        assert!(
+            fetcher
+                .is_allowed_by_robots("https://example.com/private/page")
+                .await
+        );
+    }
+
     #[test]
     fn test_is_binary_url_pdf() {
         assert!(ContentFetcher::is_binary_url(
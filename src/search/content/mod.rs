@@ -9,6 +9,7 @@
 //! Search Results (URLs) → ContentFetcher → HTML → ContentExtractor → Clean Text
 //!                              ↓
 //!                        ContentCache (30min TTL)
+//!                        RobotsCache (per host, same TTL)
 //! ```
 //!
 //! ## Usage
@@ -26,8 +27,10 @@ pub mod cache;
 pub mod config;
 pub mod extractor;
 pub mod fetcher;
+pub mod robots;
 
 pub use cache::{CachedContent, ContentCache, ContentCacheStats};
 pub use config::ContentFetchConfig;
-pub use extractor::extract_main_content;
+pub use extractor::{extract_main_content, ExtractedContent};
 pub use fetcher::{ContentFetcher, FetchError, PageContent};
+pub use robots::{RobotsCache, RobotsRules};
@@ -0,0 +1,285 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! robots.txt parsing and per-host caching
+//!
+//! Follows RFC 9309: the most specific user-agent group applies, the longest
+//! matching rule wins, and `Allow` wins a tie with `Disallow`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Maximum robots.txt size we parse (RFC 9309 requires at least 500 KiB)
+pub const MAX_ROBOTS_BYTES: usize = 500 * 1024;
+
+/// A single `Allow`/`Disallow` line
+#[derive(Debug, Clone)]
+struct RobotsRule {
+    pattern: String,
+    allow: bool,
+}
+
+/// Rules from a robots.txt that apply to one user agent
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    rules: Vec<RobotsRule>,
+}
+
+impl RobotsRules {
+    /// Rules that allow every path (e.g. robots.txt returned 404)
+    pub fn allow_all() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Rules that block every path (e.g. robots.txt returned 5xx)
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![RobotsRule {
+                pattern: "/".to_string(),
+                allow: false,
+            }],
+        }
+    }
+
+    /// Parse a robots.txt body, keeping the group that applies to `user_agent`
+    ///
+    /// Groups naming our product token take precedence over the `*` group.
+    pub fn parse(body: &str, user_agent: &str) -> Self {
+        let agent = user_agent.to_lowercase();
+        let mut specific: Vec<RobotsRule> = Vec::new();
+        let mut wildcard: Vec<RobotsRule> = Vec::new();
+        let mut found_specific = false;
+
+        // Agents named by the group being read, and whether its rules started
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    let name = value.to_lowercase();
+                    if name == agent {
+                        found_specific = true;
+                    }
+                    group_agents.push(name);
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow means "allow everything"
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = RobotsRule {
+                        pattern: value.to_string(),
+                        allow: key == "allow",
+                    };
+                    if group_agents.iter().any(|name| *name == agent) {
+                        specific.push(rule.clone());
+                    }
+                    if group_agents.iter().any(|name| name == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if found_specific { specific } else { wildcard },
+        }
+    }
+
+    /// Whether `path` (including any query string) may be fetched
+    pub fn is_allowed(&self, path: &str) -> bool {
+        // /robots.txt itself is always allowed
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .map(|rule| rule.allow)
+            .unwrap_or(true)
+    }
+}
+
+/// Match a robots.txt path pattern, supporting `*` wildcards and a trailing
+/// `$` end anchor
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    if !path.starts_with(first) {
+        return false;
+    }
+
+    let mut pos = first.len();
+    let rest: Vec<&str> = parts.collect();
+    for (i, part) in rest.iter().enumerate() {
+        let is_last = i == rest.len() - 1;
+        if is_last && anchored {
+            // The final piece must sit at the very end of the path
+            return path.len() >= pos + part.len() && path.ends_with(part);
+        }
+        match path[pos..].find(part) {
+            Some(offset) => pos += offset + part.len(),
+            None => return false,
+        }
+    }
+
+    !anchored || pos == path.len()
+}
+
+/// Per-host robots.txt cache with TTL-based expiration
+pub struct RobotsCache {
+    cache: RwLock<HashMap<String, (Arc<RobotsRules>, Instant)>>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl RobotsCache {
+    /// Create a new robots.txt cache
+    ///
+    /// # Arguments
+    /// * `ttl_secs` - Time-to-live for cached rules in seconds
+    /// * `max_entries` - Maximum number of hosts before eviction
+    pub fn new(ttl_secs: u64, max_entries: usize) -> Self {
+        Self {
+            cache: RwLock::new(HashMap::new()),
+            ttl: Duration::from_secs(ttl_secs),
+            max_entries,
+        }
+    }
+
+    /// Get cached rules for an origin (`scheme://host[:port]`) if not expired
+    pub fn get(&self, origin: &str) -> Option<Arc<RobotsRules>> {
+        let cache = self.cache.read().ok()?;
+        let (rules, fetched_at) = cache.get(&origin.to_lowercase())?;
+
+        if fetched_at.elapsed() > self.ttl {
+            return None; // Expired
+        }
+
+        Some(rules.clone())
+    }
+
+    /// Insert rules for an origin
+    pub fn insert(&self, origin: &str, rules: RobotsRules) -> Arc<RobotsRules> {
+        let rules = Arc::new(rules);
+        let mut cache = match self.cache.write() {
+            Ok(c) => c,
+            Err(_) => return rules,
+        };
+
+        // Evict oldest if at capacity
+        if cache.len() >= self.max_entries {
+            if let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, (_, fetched_at))| *fetched_at)
+                .map(|(k, _)| k.clone())
+            {
+                cache.remove(&oldest);
+            }
+        }
+
+        cache.insert(origin.to_lowercase(), (rules.clone(), Instant::now()));
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "
+# Example robots.txt
+User-agent: *
+Disallow: /private/
+Disallow: /search
+Allow: /private/public-page
+
+User-agent: BadBot
+Disallow: /
+";
+
+    #[test]
+    fn test_wildcard_group() {
+        let rules = RobotsRules::parse(ROBOTS, "FabstirBot");
+        assert!(rules.is_allowed("/"));
+        assert!(rules.is_allowed("/articles/rust"));
+        assert!(!rules.is_allowed("/private/secret"));
+        assert!(!rules.is_allowed("/search?q=test"));
+        // Longer Allow overrides the shorter Disallow
+        assert!(rules.is_allowed("/private/public-page"));
+    }
+
+    #[test]
+    fn test_specific_group_takes_precedence() {
+        let body = "User-agent: *\nDisallow: /\n\nUser-agent: FabstirBot\nDisallow: /admin\n";
+        let rules = RobotsRules::parse(body, "FabstirBot");
+        assert!(rules.is_allowed("/news"));
+        assert!(!rules.is_allowed("/admin/users"));
+
+        let rules = RobotsRules::parse(ROBOTS, "BadBot");
+        assert!(!rules.is_allowed("/anything"));
+    }
+
+    #[test]
+    fn test_grouped_user_agents_share_rules() {
+        let body = "User-agent: googlebot\nUser-agent: fabstirbot\nDisallow: /tmp\n";
+        let rules = RobotsRules::parse(body, "FabstirBot");
+        assert!(!rules.is_allowed("/tmp/file"));
+    }
+
+    #[test]
+    fn test_empty_disallow_allows_everything() {
+        let rules = RobotsRules::parse("User-agent: *\nDisallow:\n", "FabstirBot");
+        assert!(rules.is_allowed("/anything"));
+    }
+
+    #[test]
+    fn test_wildcards_and_anchors() {
+        let body = "User-agent: *\nDisallow: /*.php$\nDisallow: /tmp*/cache\n";
+        let rules = RobotsRules::parse(body, "FabstirBot");
+        assert!(!rules.is_allowed("/index.php"));
+        assert!(rules.is_allowed("/index.php?page=2"));
+        assert!(!rules.is_allowed("/tmp-1/cache/x"));
+        assert!(rules.is_allowed("/tmp-1/other"));
+    }
+
+    #[test]
+    fn test_allow_and_disallow_all() {
+        assert!(RobotsRules::allow_all().is_allowed("/private"));
+        assert!(!RobotsRules::disallow_all().is_allowed("/private"));
+        assert!(RobotsRules::disallow_all().is_allowed("/robots.txt"));
+    }
+
+    #[test]
+    fn test_robots_cache_ttl() {
+        let cache = RobotsCache::new(0, 10);
+        cache.insert("https://example.com", RobotsRules::allow_all());
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get("https://example.com").is_none());
+
+        let cache = RobotsCache::new(3600, 10);
+        cache.insert("https://Example.com", RobotsRules::disallow_all());
+        assert!(cache.get("https://example.com").is_some());
+    }
+}
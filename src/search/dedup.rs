@@ -0,0 +1,214 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Search result deduplication
+//!
+//! Providers often return the same article under slightly different URLs
+//! (tracking parameters, http vs https, `www.` prefixes, trailing slashes).
+//! Results are collapsed on a canonical form of their URL.
+
+use std::collections::HashSet;
+use url::Url;
+
+use super::types::SearchResult;
+
+/// Query parameters that only track clicks and never change the page
+const TRACKING_PARAMS: [&str; 6] = ["fbclid", "gclid", "msclkid", "mc_cid", "mc_eid", "_ga"];
+
+/// Canonicalize a URL for duplicate detection
+///
+/// - `http` and `https` are treated as the same scheme
+/// - the host is lowercased and a leading `www.` removed
+/// - default ports, fragments, `utm_*` and other tracking parameters are dropped
+/// - remaining query parameters are sorted
+/// - percent-encoding is normalized: unreserved characters are decoded and
+///   other escapes use uppercase hex
+/// - a trailing slash on the path is removed
+///
+/// URLs that fail to parse are returned trimmed and lowercased.
+pub fn canonicalize_url(url: &str) -> String {
+    let parsed = match Url::parse(url.trim()) {
+        Ok(u) if matches!(u.scheme(), "http" | "https") => u,
+        _ => return url.trim().to_lowercase(),
+    };
+
+    let host = parsed.host_str().unwrap_or("").to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+
+    let mut canonical = format!("https://{}", host);
+    // Url::port() is None for the scheme's default port
+    if let Some(port) = parsed.port() {
+        canonical.push_str(&format!(":{}", port));
+    }
+
+    let path = normalize_percent_encoding(parsed.path());
+    canonical.push_str(path.trim_end_matches('/'));
+
+    let mut params: Vec<String> = parsed
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or("").to_lowercase();
+            !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_str())
+        })
+        .map(normalize_percent_encoding)
+        .collect();
+    params.sort();
+
+    if !params.is_empty() {
+        canonical.push('?');
+        canonical.push_str(&params.join("&"));
+    }
+
+    canonical
+}
+
+/// Decode percent-escapes of unreserved characters and uppercase the hex
+/// digits of the rest, so `%7e`, `%7E` and `~` compare equal
+fn normalize_percent_encoding(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = String::with_capacity(input.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let hex = &input[i + 1..i + 3];
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                    out.push(byte as char);
+                } else {
+                    out.push('%');
+                    out.push_str(&hex.to_uppercase());
+                }
+                i += 3;
+                continue;
+            }
+        }
+        // Url has already percent-encoded any non-ASCII input
+        out.push(bytes[i] as char);
+        i += 1;
+    }
+
+    out
+}
+
+/// Remove results whose canonical URL was already seen, keeping the first
+/// (highest-ranked) occurrence and preserving order
+pub fn dedup_results(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut seen = HashSet::new();
+    results
+        .into_iter()
+        .filter(|result| seen.insert(canonicalize_url(&result.url)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str, title: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            url: url.to_string(),
+            snippet: String::new(),
+            published_date: None,
+            source: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_scheme_host_and_trailing_slash() {
+        let canonical = canonicalize_url("https://example.com/news/story");
+        assert_eq!(
+            canonicalize_url("http://example.com/news/story/"),
+            canonical
+        );
+        assert_eq!(
+            canonicalize_url("https://WWW.Example.COM/news/story"),
+            canonical
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com:443/news/story"),
+            canonical
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com/"),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn test_tracking_params_removed() {
+        assert_eq!(
+            canonicalize_url("https://example.com/a?utm_source=x&id=7&UTM_Medium=y&fbclid=abc"),
+            "https://example.com/a?id=7"
+        );
+        // Remaining parameters are order-insensitive
+        assert_eq!(
+            canonicalize_url("https://example.com/a?b=2&a=1"),
+            canonicalize_url("https://example.com/a?a=1&b=2")
+        );
+    }
+
+    #[test]
+    fn test_fragment_removed() {
+        assert_eq!(
+            canonicalize_url("https://example.com/page#section-2"),
+            "https://example.com/page"
+        );
+    }
+
+    #[test]
+    fn test_percent_encoding_normalized() {
+        assert_eq!(
+            canonicalize_url("https://example.com/%7euser/caf%c3%a9"),
+            "https://example.com/~user/caf%C3%A9"
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com/%41bc"),
+            canonicalize_url("https://example.com/Abc")
+        );
+        // Reserved characters stay encoded
+        assert_eq!(
+            canonicalize_url("https://example.com/a%2fb"),
+            "https://example.com/a%2Fb"
+        );
+        // A truncated escape is left alone
+        assert_eq!(
+            canonicalize_url("https://example.com/100%"),
+            "https://example.com/100%"
+        );
+    }
+
+    #[test]
+    fn test_non_default_port_kept() {
+        assert_eq!(
+            canonicalize_url("http://example.com:8080/x"),
+            "https://example.com:8080/x"
+        );
+    }
+
+    #[test]
+    fn test_unparsable_url() {
+        assert_eq!(canonicalize_url("  Not A URL "), "not a url");
+    }
+
+    #[test]
+    fn test_dedup_keeps_first_occurrence() {
+        let results = vec![
+            result("https://example.com/story?utm_source=brave", "first"),
+            result("https://other.org/page", "other"),
+            result("http://www.example.com/story/", "duplicate"),
+        ];
+
+        let deduped = dedup_results(results);
+        assert_eq!(deduped.len(), 2);
+        assert_eq!(deduped[0].title, "first");
+        assert_eq!(deduped[1].title, "other");
+    }
+}
@@ -8,7 +8,7 @@
 //! - Deep research with agentic loops (future)
 //!
 //! Key features:
-//! - Multiple search providers (Brave, DuckDuckGo, Bing)
+//! - Multiple search providers (Brave, DuckDuckGo, Bing, SerpAPI)
 //! - TTL-based result caching
 //! - Rate limiting per provider
 //! - Graceful degradation on provider failures
@@ -18,16 +18,19 @@ pub mod brave;
 pub mod cache;
 pub mod config;
 pub mod content;
+pub mod dedup;
 pub mod duckduckgo;
 pub mod provider;
 pub mod query_extractor;
 pub mod rate_limiter;
+pub mod serpapi;
 pub mod service;
 pub mod types;
 
 // Re-export commonly used types
-pub use config::SearchConfig;
-pub use service::SearchService;
+pub use config::{ProviderKind, SearchConfig};
+pub use dedup::{canonicalize_url, dedup_results};
+pub use service::{ProgressiveSearch, SearchService};
 pub use types::{
     SearchError, SearchResponse, SearchResponseWithContent, SearchResult, SearchResultWithContent,
 };
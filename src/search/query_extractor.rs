@@ -325,6 +325,32 @@ pub fn format_results_with_content_for_prompt(
     formatted
 }
 
+/// Format a search result whose content arrived after generation started
+///
+/// `number` continues the numbering of `format_results_with_content_for_prompt`
+/// so the model can relate the late content to the result it already saw.
+pub fn format_late_result_for_prompt(
+    number: usize,
+    result: &super::types::SearchResultWithContent,
+    max_chars: usize,
+) -> String {
+    let text = result.content.as_deref().unwrap_or(&result.snippet);
+    let text = if text.len() > max_chars {
+        let mut end = max_chars.saturating_sub(3);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...", &text[..end])
+    } else {
+        text.to_string()
+    };
+
+    format!(
+        "\n\n[Additional Web Search Result]\n[{}] {}\nURL: {}\n\n{}\n[End Additional Web Search Result]\n\n",
+        number, result.title, result.url, text
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -505,6 +531,27 @@ mod tests {
         assert!(formatted.contains("..."));
     }
 
+    #[test]
+    fn test_format_late_result() {
+        use super::super::types::SearchResultWithContent;
+
+        let result = SearchResultWithContent {
+            title: "Late Article".to_string(),
+            url: "https://example.com/late".to_string(),
+            snippet: "Short snippet".to_string(),
+            content: Some("é".repeat(100)),
+            published_date: None,
+            source: "test".to_string(),
+        };
+
+        let formatted = format_late_result_for_prompt(3, &result, 50);
+        assert!(formatted.contains("[3] Late Article"));
+        assert!(formatted.contains("URL: https://example.com/late"));
+        // Truncation lands on a char boundary
+        assert!(formatted.contains(&format!("{}...", "é".repeat(23))));
+        assert!(formatted.contains("[End Additional Web Search Result]"));
+    }
+
     #[test]
     fn test_format_empty_results_with_content() {
         let results: Vec<super::super::types::SearchResultWithContent> = vec![];
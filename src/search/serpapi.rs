@@ -0,0 +1,236 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! SerpAPI search provider
+//!
+//! Implements web search using SerpAPI's Google engine.
+//! Useful for deployments that already hold a SerpAPI key.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use std::time::Duration;
+
+use super::provider::SearchProvider;
+use super::types::{SearchError, SearchResult};
+
+const SERPAPI_URL: &str = "https://serpapi.com/search.json";
+
+/// SerpAPI (Google results) provider
+pub struct SerpApiProvider {
+    api_key: String,
+    client: Client,
+}
+
+impl SerpApiProvider {
+    /// Create a new SerpAPI provider
+    ///
+    /// # Arguments
+    /// * `api_key` - SerpAPI API key
+    pub fn new(api_key: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { api_key, client }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for SerpApiProvider {
+    async fn search(
+        &self,
+        query: &str,
+        num_results: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        let response = self
+            .client
+            .get(SERPAPI_URL)
+            .query(&[
+                ("engine", "google"),
+                ("q", query),
+                ("num", &num_results.min(100).to_string()),
+                ("api_key", &self.api_key),
+            ])
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    SearchError::Timeout { timeout_ms: 10000 }
+                } else {
+                    SearchError::ApiError {
+                        status: 0,
+                        message: e.to_string(),
+                    }
+                }
+            })?;
+
+        let status = response.status();
+
+        if status == 429 {
+            return Err(SearchError::RateLimited {
+                retry_after_secs: 60,
+            });
+        }
+
+        if status == 401 || status == 403 {
+            return Err(SearchError::NoApiKey {
+                provider: "serpapi".to_string(),
+            });
+        }
+
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(SearchError::ApiError {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        let data: SerpApiResponse = response.json().await.map_err(|e| SearchError::ApiError {
+            status: 0,
+            message: format!("JSON parse error: {}", e),
+        })?;
+
+        parse_results(data, num_results)
+    }
+
+    fn name(&self) -> &'static str {
+        "serpapi"
+    }
+
+    fn is_available(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+
+    fn priority(&self) -> u8 {
+        30 // After the native Brave and Bing APIs
+    }
+}
+
+/// Convert a SerpAPI response into search results
+///
+/// SerpAPI reports some failures (e.g. an exhausted plan) as a 200 with an
+/// `error` field, and omits `organic_results` entirely when Google found
+/// nothing, which is not an error.
+fn parse_results(
+    data: SerpApiResponse,
+    num_results: usize,
+) -> Result<Vec<SearchResult>, SearchError> {
+    if let Some(message) = data.error {
+        if message.contains("hasn't returned any results") {
+            return Ok(Vec::new());
+        }
+        if message.contains("run out of searches") {
+            return Err(SearchError::RateLimited {
+                retry_after_secs: 3600,
+            });
+        }
+        return Err(SearchError::ApiError {
+            status: 200,
+            message,
+        });
+    }
+
+    Ok(data
+        .organic_results
+        .into_iter()
+        .filter(|r| !r.link.is_empty())
+        .take(num_results)
+        .map(|r| SearchResult {
+            title: r.title,
+            url: r.link,
+            snippet: r.snippet.unwrap_or_default(),
+            published_date: r.date,
+            source: "serpapi".to_string(),
+        })
+        .collect())
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SerpApiResponse {
+    #[serde(default)]
+    organic_results: Vec<SerpApiResult>,
+    error: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SerpApiResult {
+    title: String,
+    #[serde(default)]
+    link: String,
+    snippet: Option<String>,
+    date: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serpapi_provider_creation() {
+        let provider = SerpApiProvider::new("test-api-key".to_string());
+        assert_eq!(provider.name(), "serpapi");
+        assert!(provider.is_available());
+        assert_eq!(provider.priority(), 30);
+    }
+
+    #[test]
+    fn test_serpapi_provider_empty_key() {
+        let provider = SerpApiProvider::new(String::new());
+        assert!(!provider.is_available());
+    }
+
+    #[test]
+    fn test_serpapi_response_parsing() {
+        let json = r#"{
+            "search_metadata": { "status": "Success" },
+            "organic_results": [
+                {
+                    "position": 1,
+                    "title": "Test Title",
+                    "link": "https://example.com",
+                    "snippet": "Test snippet",
+                    "date": "Jan 5, 2025"
+                },
+                {
+                    "position": 2,
+                    "title": "No snippet",
+                    "link": "https://example.org"
+                }
+            ]
+        }"#;
+
+        let response: SerpApiResponse = serde_json::from_str(json).unwrap();
+        let results = parse_results(response, 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://example.com");
+        assert_eq!(results[0].published_date.as_deref(), Some("Jan 5, 2025"));
+        assert_eq!(results[1].snippet, "");
+        assert_eq!(results[1].source, "serpapi");
+    }
+
+    #[test]
+    fn test_serpapi_no_results() {
+        let json = r#"{ "error": "Google hasn't returned any results for this query." }"#;
+
+        let response: SerpApiResponse = serde_json::from_str(json).unwrap();
+        assert!(parse_results(response, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_serpapi_error_field() {
+        let json = r#"{ "error": "Your account has run out of searches." }"#;
+        let response: SerpApiResponse = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            parse_results(response, 10),
+            Err(SearchError::RateLimited { .. })
+        ));
+
+        let json = r#"{ "error": "Invalid API key." }"#;
+        let response: SerpApiResponse = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            parse_results(response, 10),
+            Err(SearchError::ApiError { .. })
+        ));
+    }
+}
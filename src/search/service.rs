@@ -5,26 +5,46 @@
 //! Coordinates search providers, caching, and rate limiting.
 
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
 use tracing::{debug, info, warn};
 
 use super::bing::BingSearchProvider;
 use super::brave::BraveSearchProvider;
 use super::cache::SearchCache;
-use super::config::SearchConfig;
-use super::content::{ContentFetchConfig, ContentFetcher};
+use super::config::{ProviderKind, SearchConfig};
+use super::content::{ContentFetchConfig, ContentFetcher, FetchError, PageContent};
+use super::dedup::dedup_results;
 use super::duckduckgo::DuckDuckGoProvider;
 use super::provider::SearchProvider;
 use super::rate_limiter::SearchRateLimiter;
+use super::serpapi::SerpApiProvider;
 use super::types::{
     SearchError, SearchResponse, SearchResponseWithContent, SearchResult, SearchResultWithContent,
 };
 
+/// A provider in the failover chain with its own rate limiter
+struct ChainedProvider {
+    kind: ProviderKind,
+    provider: Box<dyn SearchProvider>,
+    rate_limiter: SearchRateLimiter,
+}
+
+/// Search results whose remaining page content is still being fetched
+pub struct ProgressiveSearch {
+    /// Results with the top result's content filled in; the rest carry snippets
+    pub response: SearchResponseWithContent,
+    /// Remaining results as their content arrives, with their index in
+    /// `response.results`; closes once every fetch has finished
+    pub remaining: mpsc::UnboundedReceiver<(usize, SearchResultWithContent)>,
+}
+
 /// Main search service that orchestrates providers, caching, and rate limiting
 pub struct SearchService {
-    providers: Vec<Box<dyn SearchProvider>>,
+    /// Providers in `config.provider_chain` order
+    providers: Vec<ChainedProvider>,
     cache: SearchCache,
-    rate_limiter: SearchRateLimiter,
     config: SearchConfig,
     /// Content fetcher for retrieving actual page content (Phase 9)
     content_fetcher: Option<Arc<ContentFetcher>>,
@@ -32,34 +52,59 @@ pub struct SearchService {
 
 impl SearchService {
     /// Create a new search service from configuration
+    ///
+    /// Providers are tried in `config.provider_chain` order. Keyed providers
+    /// without an API key are left out of the chain.
     pub fn new(config: SearchConfig) -> Self {
-        let mut providers: Vec<Box<dyn SearchProvider>> = Vec::new();
+        let providers = config
+            .provider_chain
+            .iter()
+            .filter_map(|&kind| Self::build_provider(kind, &config).map(|p| (kind, p)))
+            .collect();
 
-        // Add Brave if configured (priority 10)
-        if let Some(ref api_key) = config.providers.brave_api_key {
-            if !api_key.is_empty() {
-                providers.push(Box::new(BraveSearchProvider::new(api_key.clone())));
-                debug!("Brave Search provider enabled");
-            }
-        }
+        Self::with_providers(config, providers)
+    }
+
+    fn build_provider(
+        kind: ProviderKind,
+        config: &SearchConfig,
+    ) -> Option<Box<dyn SearchProvider>> {
+        let api_key = |key: &Option<String>| key.clone().filter(|k| !k.is_empty());
 
-        // Add Bing if configured (priority 20)
-        if let Some(ref api_key) = config.providers.bing_api_key {
-            if !api_key.is_empty() {
-                providers.push(Box::new(BingSearchProvider::new(api_key.clone())));
+        match kind {
+            ProviderKind::Brave => api_key(&config.providers.brave_api_key).map(|key| {
+                debug!("Brave Search provider enabled");
+                Box::new(BraveSearchProvider::new(key)) as Box<dyn SearchProvider>
+            }),
+            ProviderKind::Bing => api_key(&config.providers.bing_api_key).map(|key| {
                 debug!("Bing Search provider enabled");
+                Box::new(BingSearchProvider::new(key)) as Box<dyn SearchProvider>
+            }),
+            ProviderKind::SerpApi => api_key(&config.providers.serpapi_api_key).map(|key| {
+                debug!("SerpAPI provider enabled");
+                Box::new(SerpApiProvider::new(key)) as Box<dyn SearchProvider>
+            }),
+            ProviderKind::DuckDuckGo => {
+                debug!("DuckDuckGo provider enabled");
+                Some(Box::new(DuckDuckGoProvider::new()))
             }
         }
+    }
 
-        // Always add DuckDuckGo as fallback (priority 50)
-        providers.push(Box::new(DuckDuckGoProvider::new()));
-        debug!("DuckDuckGo provider enabled (fallback)");
-
-        // Sort by priority (lower = preferred)
-        providers.sort_by_key(|p| p.priority());
+    fn with_providers(
+        config: SearchConfig,
+        providers: Vec<(ProviderKind, Box<dyn SearchProvider>)>,
+    ) -> Self {
+        let providers = providers
+            .into_iter()
+            .map(|(kind, provider)| ChainedProvider {
+                kind,
+                provider,
+                rate_limiter: SearchRateLimiter::new(config.rate_limit_for(kind)),
+            })
+            .collect();
 
         let cache = SearchCache::new(config.cache_ttl_secs, 1000);
-        let rate_limiter = SearchRateLimiter::new(config.rate_limit_per_minute);
 
         // Initialize content fetcher (Phase 9)
         let content_fetch_config = ContentFetchConfig::from_env();
@@ -77,7 +122,6 @@ impl SearchService {
         Self {
             providers,
             cache,
-            rate_limiter,
             config,
             content_fetcher,
         }
@@ -115,30 +159,48 @@ impl SearchService {
             });
         }
 
-        // Rate limit check
-        self.rate_limiter.check()?;
-
         let start = Instant::now();
+        let mut failures = Vec::new();
+        let mut rate_limited = false;
+
+        // Try providers in chain order. A rate-limited provider is skipped
+        // without counting as a failure.
+        for entry in &self.providers {
+            let name = entry.kind.name();
+            if !entry.provider.is_available() {
+                continue;
+            }
 
-        // Try providers in order (by priority)
-        for provider in &self.providers {
-            if !provider.is_available() {
+            if entry.rate_limiter.check().is_err() {
+                debug!("Search provider {} is rate limited, skipping", name);
+                rate_limited = true;
                 continue;
             }
 
-            debug!("Trying search provider: {}", provider.name());
+            debug!("Trying search provider: {}", name);
 
-            match provider.search(query, num_results).await {
+            match entry.provider.search(query, num_results).await {
                 Ok(results) => {
                     let elapsed_ms = start.elapsed().as_millis() as u64;
 
+                    // Collapse results that point at the same page
+                    let before = results.len();
+                    let results = dedup_results(results);
+                    if results.len() < before {
+                        debug!(
+                            "Removed {} duplicate results from {}",
+                            before - results.len(),
+                            name
+                        );
+                    }
+
                     // Cache successful results
-                    self.cache.insert(query, &results, provider.name());
+                    self.cache.insert(query, &results, name);
 
                     info!(
                         "Search complete: {} results from {} in {}ms",
                         results.len(),
-                        provider.name(),
+                        name,
                         elapsed_ms
                     );
 
@@ -147,24 +209,32 @@ impl SearchService {
                         result_count: results.len(),
                         results,
                         search_time_ms: elapsed_ms,
-                        provider: provider.name().to_string(),
+                        provider: name.to_string(),
                         cached: false,
                     });
                 }
+                Err(SearchError::RateLimited { .. }) => {
+                    warn!("Search provider {} rate limited us, trying next", name);
+                    rate_limited = true;
+                }
                 Err(e) => {
-                    warn!(
-                        "Search provider {} failed: {}, trying next",
-                        provider.name(),
-                        e
-                    );
-                    continue;
+                    warn!("Search provider {} failed: {}, trying next", name, e);
+                    failures.push(format!("{}: {}", name, e));
                 }
             }
         }
 
-        Err(SearchError::ProviderUnavailable {
-            provider: "all".to_string(),
-        })
+        if rate_limited {
+            Err(SearchError::RateLimited {
+                retry_after_secs: 60,
+            })
+        } else if !failures.is_empty() {
+            Err(SearchError::AllProvidersFailed { failures })
+        } else {
+            Err(SearchError::ProviderUnavailable {
+                provider: "all".to_string(),
+            })
+        }
     }
 
     /// Perform multiple searches in parallel
@@ -236,13 +306,8 @@ impl SearchService {
                     .results
                     .into_iter()
                     .zip(contents.into_iter())
-                    .map(|(result, content)| SearchResultWithContent {
-                        title: result.title,
-                        url: result.url,
-                        snippet: result.snippet,
-                        content: content.ok().map(|c| c.text),
-                        published_date: result.published_date,
-                        source: result.source,
+                    .map(|(result, content)| {
+                        Self::with_content(result, Self::usable_content(content))
                     })
                     .collect();
 
@@ -260,31 +325,137 @@ impl SearchService {
         }
 
         // Content fetching disabled - return snippet only
-        let results_with_content: Vec<SearchResultWithContent> = search_response
+        Ok(Self::snippets_only(search_response))
+    }
+
+    /// Search, then fetch only the top result's content before returning
+    ///
+    /// Lets streaming callers start generating as soon as the best source is
+    /// available. The other pages are fetched concurrently in background tasks
+    /// and delivered on `remaining` as each completes, still bounded by the
+    /// content fetcher's total timeout.
+    pub async fn search_with_progressive_content(
+        &self,
+        query: &str,
+        num_results: Option<usize>,
+    ) -> Result<ProgressiveSearch, SearchError> {
+        let search_response = self.search(query, num_results).await?;
+        let (tx, remaining) = mpsc::unbounded_channel();
+
+        let fetcher = match self.content_fetcher {
+            Some(ref fetcher) if fetcher.is_enabled() => fetcher.clone(),
+            _ => {
+                return Ok(ProgressiveSearch {
+                    response: Self::snippets_only(search_response),
+                    remaining,
+                })
+            }
+        };
+
+        let content_start = Instant::now();
+        let total_timeout = Duration::from_secs(fetcher.config().total_timeout_secs);
+        let to_fetch: Vec<SearchResult> = search_response
             .results
-            .into_iter()
-            .map(|r| {
-                SearchResultWithContent {
-                    title: r.title,
-                    url: r.url,
-                    snippet: r.snippet.clone(),
-                    content: None, // No content fetched
-                    published_date: r.published_date,
-                    source: r.source,
+            .iter()
+            .take(fetcher.config().max_pages)
+            .cloned()
+            .collect();
+
+        for (index, result) in to_fetch.iter().enumerate().skip(1) {
+            let fetcher = fetcher.clone();
+            let tx = tx.clone();
+            let result = result.clone();
+            tokio::spawn(async move {
+                let content = match timeout(total_timeout, fetcher.fetch_content(&result.url)).await
+                {
+                    Ok(content) => Self::usable_content(content),
+                    Err(_) => None,
+                };
+                if let Some(content) = content {
+                    // The receiver may already be gone if generation finished
+                    let _ = tx.send((index, Self::with_content(result, Some(content))));
                 }
-            })
+            });
+        }
+        drop(tx);
+
+        let mut response = Self::snippets_only(search_response);
+        if let Some(top) = to_fetch.first() {
+            match timeout(total_timeout, fetcher.fetch_content(&top.url)).await {
+                Ok(content) => {
+                    response.results[0].content = Self::usable_content(content);
+                }
+                Err(_) => warn!("Top result fetch timed out: {}", top.url),
+            }
+        }
+        response.content_fetched_count = response
+            .results
+            .iter()
+            .filter(|r| r.content.is_some())
+            .count();
+        response.content_fetch_time_ms = content_start.elapsed().as_millis() as u64;
+
+        info!(
+            "Top result content {} in {}ms, {} more pages fetching in background",
+            if response.content_fetched_count > 0 {
+                "fetched"
+            } else {
+                "unavailable"
+            },
+            response.content_fetch_time_ms,
+            to_fetch.len().saturating_sub(1)
+        );
+
+        Ok(ProgressiveSearch {
+            response,
+            remaining,
+        })
+    }
+
+    /// Page text worth giving the model, or None to fall back to the snippet
+    ///
+    /// Without content the snippet is used, which beats feeding the model
+    /// boilerplate.
+    fn usable_content(content: Result<PageContent, FetchError>) -> Option<String> {
+        match content {
+            Ok(page) => Some(page.text),
+            Err(FetchError::LowQuality(score, url)) => {
+                debug!("Falling back to snippet for {} (quality {:.2})", url, score);
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    fn with_content(result: SearchResult, content: Option<String>) -> SearchResultWithContent {
+        SearchResultWithContent {
+            title: result.title,
+            url: result.url,
+            snippet: result.snippet,
+            content,
+            published_date: result.published_date,
+            source: result.source,
+        }
+    }
+
+    /// Convert a plain search response, leaving every result's content empty
+    fn snippets_only(search_response: SearchResponse) -> SearchResponseWithContent {
+        let results: Vec<SearchResultWithContent> = search_response
+            .results
+            .into_iter()
+            .map(|r| Self::with_content(r, None))
             .collect();
 
-        Ok(SearchResponseWithContent {
+        SearchResponseWithContent {
             query: search_response.query,
-            result_count: results_with_content.len(),
-            results: results_with_content,
+            result_count: results.len(),
+            results,
             search_time_ms: search_response.search_time_ms,
             content_fetch_time_ms: 0,
             provider: search_response.provider,
             cached: search_response.cached,
             content_fetched_count: 0,
-        })
+        }
     }
 
     /// Check if search is enabled
@@ -292,12 +463,12 @@ impl SearchService {
         self.config.enabled
     }
 
-    /// Get list of available provider names
+    /// Get list of available provider names, in failover order
     pub fn available_providers(&self) -> Vec<&str> {
         self.providers
             .iter()
-            .filter(|p| p.is_available())
-            .map(|p| p.name())
+            .filter(|p| p.provider.is_available())
+            .map(|p| p.kind.name())
             .collect()
     }
 
@@ -328,6 +499,187 @@ impl SearchService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    enum MockOutcome {
+        Succeed,
+        Fail,
+        RateLimited,
+    }
+
+    struct MockProvider {
+        name: &'static str,
+        outcome: MockOutcome,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl SearchProvider for MockProvider {
+        async fn search(
+            &self,
+            query: &str,
+            _num_results: usize,
+        ) -> Result<Vec<SearchResult>, SearchError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.outcome {
+                MockOutcome::Succeed => Ok(vec![SearchResult {
+                    title: format!("Result for {}", query),
+                    url: "https://example.com".to_string(),
+                    snippet: "A mock result".to_string(),
+                    published_date: None,
+                    source: self.name.to_string(),
+                }]),
+                MockOutcome::Fail => Err(SearchError::ApiError {
+                    status: 500,
+                    message: "boom".to_string(),
+                }),
+                MockOutcome::RateLimited => Err(SearchError::RateLimited {
+                    retry_after_secs: 30,
+                }),
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    fn mock(
+        kind: ProviderKind,
+        outcome: MockOutcome,
+    ) -> (ProviderKind, Box<dyn SearchProvider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = MockProvider {
+            name: kind.name(),
+            outcome,
+            calls: calls.clone(),
+        };
+        (kind, Box::new(provider), calls)
+    }
+
+    fn service_with(
+        config: SearchConfig,
+        mocks: Vec<(ProviderKind, Box<dyn SearchProvider>, Arc<AtomicUsize>)>,
+    ) -> (SearchService, Vec<Arc<AtomicUsize>>) {
+        let (providers, calls) = mocks
+            .into_iter()
+            .map(|(kind, provider, calls)| ((kind, provider), calls))
+            .unzip();
+        (SearchService::with_providers(config, providers), calls)
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_next_provider() {
+        let (service, calls) = service_with(
+            SearchConfig::default(),
+            vec![
+                mock(ProviderKind::Brave, MockOutcome::Fail),
+                mock(ProviderKind::DuckDuckGo, MockOutcome::Succeed),
+            ],
+        );
+
+        let response = service.search("failover", None).await.unwrap();
+        assert_eq!(response.provider, "duckduckgo");
+        assert_eq!(calls[0].load(Ordering::SeqCst), 1);
+        assert_eq!(calls[1].load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_provider_is_skipped() {
+        let mut config = SearchConfig::default();
+        config.rate_limit_per_minute = 1;
+        let (service, calls) = service_with(
+            config,
+            vec![
+                mock(ProviderKind::Brave, MockOutcome::Succeed),
+                mock(ProviderKind::DuckDuckGo, MockOutcome::Succeed),
+            ],
+        );
+
+        // Exhaust Brave's own limiter
+        service.providers[0].rate_limiter.check().unwrap();
+
+        let response = service.search("skip brave", None).await.unwrap();
+        assert_eq!(response.provider, "duckduckgo");
+        assert_eq!(calls[0].load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_all_providers_failed() {
+        let (service, _) = service_with(
+            SearchConfig::default(),
+            vec![
+                mock(ProviderKind::Brave, MockOutcome::Fail),
+                mock(ProviderKind::DuckDuckGo, MockOutcome::Fail),
+            ],
+        );
+
+        match service.search("nothing works", None).await {
+            Err(SearchError::AllProvidersFailed { failures }) => {
+                assert_eq!(failures.len(), 2);
+                assert!(failures[0].starts_with("brave:"));
+                assert!(failures[1].starts_with("duckduckgo:"));
+            }
+            other => panic!("expected AllProvidersFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_is_not_counted_as_failure() {
+        let (service, _) = service_with(
+            SearchConfig::default(),
+            vec![
+                mock(ProviderKind::Brave, MockOutcome::RateLimited),
+                mock(ProviderKind::DuckDuckGo, MockOutcome::Fail),
+            ],
+        );
+
+        let result = service.search("partly limited", None).await;
+        assert!(matches!(result, Err(SearchError::RateLimited { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_progressive_search_without_fetcher() {
+        let (mut service, _) = service_with(
+            SearchConfig::default(),
+            vec![mock(ProviderKind::DuckDuckGo, MockOutcome::Succeed)],
+        );
+        service.content_fetcher = None;
+
+        let mut progressive = service
+            .search_with_progressive_content("progressive", None)
+            .await
+            .unwrap();
+        assert_eq!(progressive.response.results.len(), 1);
+        assert!(progressive.response.results[0].content.is_none());
+        assert_eq!(progressive.response.content_fetched_count, 0);
+        // Nothing is left to fetch, so the channel is already closed
+        assert!(progressive.remaining.recv().await.is_none());
+    }
+
+    #[test]
+    fn test_serpapi_in_chain_when_keyed() {
+        let mut config = SearchConfig::default();
+        config.providers.serpapi_api_key = Some("serp-key".to_string());
+
+        let service = SearchService::new(config);
+        assert_eq!(service.available_providers(), vec!["serpapi", "duckduckgo"]);
+    }
+
+    #[test]
+    fn test_provider_chain_order() {
+        let mut config = SearchConfig::default();
+        config.providers.brave_api_key = Some("test-key".to_string());
+        config.provider_chain = vec![ProviderKind::DuckDuckGo, ProviderKind::Brave];
+
+        let service = SearchService::new(config);
+        assert_eq!(service.available_providers(), vec!["duckduckgo", "brave"]);
+    }
 
     #[test]
     fn test_service_creation_enabled_by_default() {
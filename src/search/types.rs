@@ -90,6 +90,13 @@ pub enum SearchError {
     /// Search is disabled on this host
     #[error("Search disabled on this host")]
     SearchDisabled,
+
+    /// Every provider in the failover chain was tried and returned an error
+    #[error("All search providers failed: {}", .failures.join("; "))]
+    AllProvidersFailed {
+        /// One `provider: error` entry per failed provider, in chain order
+        failures: Vec<String>,
+    },
 }
 
 /// A search query for batch operations
@@ -204,6 +211,15 @@ mod tests {
             message: "Internal error".to_string(),
         };
         assert!(error.to_string().contains("500"));
+
+        let error = SearchError::AllProvidersFailed {
+            failures: vec![
+                "brave: Search timeout after 10000ms".to_string(),
+                "duckduckgo: Provider unavailable: duckduckgo".to_string(),
+            ],
+        };
+        assert!(error.to_string().contains("brave: Search timeout"));
+        assert!(error.to_string().contains("; duckduckgo:"));
     }
 
     #[test]
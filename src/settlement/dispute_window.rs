@@ -0,0 +1,291 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! Settlement Dispute Window
+//!
+//! After a result is submitted its payment stays pending for a challenge
+//! period. A dispute raised during the window freezes settlement until it is
+//! resolved; funds are only released once the window has passed undisputed.
+
+use super::types::{SettlementError, SettlementStatus};
+use super::validator::SettlementValidator;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// Default challenge period before a payment becomes final
+pub const DEFAULT_DISPUTE_WINDOW: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone)]
+pub struct Dispute {
+    pub evidence: String,
+    pub raised_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingSettlement {
+    pub job_id: u64,
+    pub submitted_at: Instant,
+    pub window_ends: Instant,
+    pub status: SettlementStatus,
+    pub dispute: Option<Dispute>,
+}
+
+impl PendingSettlement {
+    pub fn remaining(&self) -> Duration {
+        self.window_ends.saturating_duration_since(Instant::now())
+    }
+}
+
+/// Tracks the challenge period of each submitted job
+pub struct DisputeWindow {
+    window: Duration,
+    entries: RwLock<HashMap<u64, PendingSettlement>>,
+    validator: Option<Arc<SettlementValidator>>,
+}
+
+impl DisputeWindow {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: RwLock::new(HashMap::new()),
+            validator: None,
+        }
+    }
+
+    /// Re-check the job's proof at release, disputing it if invalid
+    pub fn with_validator(mut self, validator: Arc<SettlementValidator>) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Start the challenge period for a job whose result was just submitted
+    pub async fn open(&self, job_id: u64) {
+        let now = Instant::now();
+        self.entries.write().await.insert(
+            job_id,
+            PendingSettlement {
+                job_id,
+                submitted_at: now,
+                window_ends: now + self.window,
+                status: SettlementStatus::Pending,
+                dispute: None,
+            },
+        );
+        info!(
+            "[DISPUTE] Window opened for job {} ({}s)",
+            job_id,
+            self.window.as_secs()
+        );
+    }
+
+    pub async fn is_tracked(&self, job_id: u64) -> bool {
+        self.entries.read().await.contains_key(&job_id)
+    }
+
+    pub async fn get(&self, job_id: u64) -> Option<PendingSettlement> {
+        self.entries.read().await.get(&job_id).cloned()
+    }
+
+    /// Freeze settlement of a job while the window is still open
+    pub async fn raise_dispute(
+        &self,
+        job_id: u64,
+        evidence: impl Into<String>,
+    ) -> Result<(), SettlementError> {
+        let mut entries = self.entries.write().await;
+        let entry = entries
+            .get_mut(&job_id)
+            .ok_or(SettlementError::SessionNotFound(job_id))?;
+
+        match entry.status {
+            SettlementStatus::Pending if entry.remaining() > Duration::ZERO => {}
+            SettlementStatus::Disputed => return Err(SettlementError::SettlementFrozen(job_id)),
+            _ => return Err(SettlementError::DisputeWindowClosed(job_id)),
+        }
+
+        let evidence = evidence.into();
+        warn!("[DISPUTE] Job {} disputed: {}", job_id, evidence);
+        entry.status = SettlementStatus::Disputed;
+        entry.dispute = Some(Dispute {
+            evidence,
+            raised_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// Settle a dispute: an upheld dispute fails the settlement, a rejected
+    /// one resumes the original window
+    pub async fn resolve_dispute(&self, job_id: u64, upheld: bool) -> Result<(), SettlementError> {
+        let mut entries = self.entries.write().await;
+        let entry = entries
+            .get_mut(&job_id)
+            .ok_or(SettlementError::SessionNotFound(job_id))?;
+
+        if entry.status != SettlementStatus::Disputed {
+            return Err(SettlementError::SettlementFailed {
+                chain: 0,
+                reason: format!("Job {} has no open dispute", job_id),
+            });
+        }
+
+        entry.status = if upheld {
+            SettlementStatus::Failed
+        } else {
+            SettlementStatus::Pending
+        };
+        info!(
+            "[DISPUTE] Dispute for job {} {}",
+            job_id,
+            if upheld { "upheld" } else { "rejected" }
+        );
+        Ok(())
+    }
+
+    /// Jobs whose window passed undisputed
+    pub async fn releasable(&self) -> Vec<u64> {
+        let mut jobs: Vec<u64> = self
+            .entries
+            .read()
+            .await
+            .values()
+            .filter(|e| e.status == SettlementStatus::Pending && e.remaining() == Duration::ZERO)
+            .map(|e| e.job_id)
+            .collect();
+        jobs.sort_unstable();
+        jobs
+    }
+
+    /// Check a job can be paid out and mark it released
+    ///
+    /// Fails while the window is open or the job is disputed. With a
+    /// validator attached, an invalid proof raises a dispute instead of
+    /// releasing.
+    pub async fn release(&self, job_id: u64) -> Result<(), SettlementError> {
+        let entry = self
+            .get(job_id)
+            .await
+            .ok_or(SettlementError::SessionNotFound(job_id))?;
+
+        match entry.status {
+            SettlementStatus::Pending => {}
+            SettlementStatus::Disputed => return Err(SettlementError::SettlementFrozen(job_id)),
+            _ => return Err(SettlementError::DisputeWindowClosed(job_id)),
+        }
+
+        let remaining = entry.remaining();
+        if remaining > Duration::ZERO {
+            return Err(SettlementError::DisputeWindowOpen {
+                job_id,
+                remaining_secs: remaining.as_secs(),
+            });
+        }
+
+        if let Some(validator) = &self.validator {
+            let valid = validator
+                .validate_before_settlement(job_id)
+                .await
+                .map_err(|e| SettlementError::SettlementFailed {
+                    chain: 0,
+                    reason: e.to_string(),
+                })?;
+            if !valid {
+                self.freeze(job_id, "Proof failed validation at release")
+                    .await;
+                return Err(SettlementError::SettlementFrozen(job_id));
+            }
+        }
+
+        let mut entries = self.entries.write().await;
+        match entries.get_mut(&job_id) {
+            // A dispute may have landed while validating
+            Some(entry) if entry.status == SettlementStatus::Pending => {
+                entry.status = SettlementStatus::Completed;
+                info!("[DISPUTE] Job {} released after undisputed window", job_id);
+                Ok(())
+            }
+            Some(_) => Err(SettlementError::SettlementFrozen(job_id)),
+            None => Err(SettlementError::SessionNotFound(job_id)),
+        }
+    }
+
+    async fn freeze(&self, job_id: u64, evidence: &str) {
+        if let Some(entry) = self.entries.write().await.get_mut(&job_id) {
+            warn!("[DISPUTE] Job {} frozen: {}", job_id, evidence);
+            entry.status = SettlementStatus::Disputed;
+            entry.dispute = Some(Dispute {
+                evidence: evidence.to_string(),
+                raised_at: Utc::now(),
+            });
+        }
+    }
+}
+
+impl Default for DisputeWindow {
+    fn default() -> Self {
+        Self::new(DEFAULT_DISPUTE_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_release_waits_for_window() {
+        let window = DisputeWindow::new(Duration::from_millis(50));
+        window.open(1).await;
+
+        assert!(matches!(
+            window.release(1).await,
+            Err(SettlementError::DisputeWindowOpen { job_id: 1, .. })
+        ));
+        assert!(window.releasable().await.is_empty());
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(window.releasable().await, vec![1]);
+        window.release(1).await.unwrap();
+        assert_eq!(
+            window.get(1).await.unwrap().status,
+            SettlementStatus::Completed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispute_freezes_settlement() {
+        let window = DisputeWindow::new(Duration::from_millis(50));
+        window.open(2).await;
+        window
+            .raise_dispute(2, "output hash mismatch")
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(window.releasable().await.is_empty());
+        assert!(matches!(
+            window.release(2).await,
+            Err(SettlementError::SettlementFrozen(2))
+        ));
+
+        // Rejected dispute resumes the elapsed window
+        window.resolve_dispute(2, false).await.unwrap();
+        window.release(2).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dispute_after_window_rejected() {
+        let window = DisputeWindow::new(Duration::ZERO);
+        window.open(3).await;
+
+        assert!(matches!(
+            window.raise_dispute(3, "too late").await,
+            Err(SettlementError::DisputeWindowClosed(3))
+        ));
+    }
+}
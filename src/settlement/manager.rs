@@ -1,5 +1,6 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
+use super::dispute_window::DisputeWindow;
 use super::gas_estimator::GasEstimator;
 use super::queue::{SettlementQueue, SettlementRequest};
 use super::types::{SettlementError, SettlementResult, SettlementStatus};
@@ -26,6 +27,7 @@ pub struct SettlementManager {
     signers: HashMap<u64, ChainSigner>,
     gas_estimator: GasEstimator,
     settlement_queue: Arc<RwLock<SettlementQueue>>,
+    dispute_window: Arc<DisputeWindow>,
     host_address: Address,
 }
 
@@ -78,10 +80,20 @@ impl SettlementManager {
             signers,
             gas_estimator: GasEstimator::new(),
             settlement_queue: Arc::new(RwLock::new(SettlementQueue::new())),
+            dispute_window: Arc::new(DisputeWindow::default()),
             host_address,
         })
     }
 
+    pub fn with_dispute_window(mut self, dispute_window: Arc<DisputeWindow>) -> Self {
+        self.dispute_window = dispute_window;
+        self
+    }
+
+    pub fn dispute_window(&self) -> Arc<DisputeWindow> {
+        self.dispute_window.clone()
+    }
+
     pub fn provider_count(&self) -> usize {
         self.providers.len()
     }
@@ -156,7 +168,12 @@ impl SettlementManager {
         }
     }
 
+    /// Queue a session for settlement. Its result has just been submitted,
+    /// so this also starts its dispute window; processing waits for it.
     pub async fn queue_settlement(&self, request: SettlementRequest) -> Result<()> {
+        if !self.dispute_window.is_tracked(request.session_id).await {
+            self.dispute_window.open(request.session_id).await;
+        }
         let mut queue = self.settlement_queue.write().await;
         queue.add(request).await;
         Ok(())
@@ -169,11 +186,41 @@ impl SettlementManager {
 
     pub async fn process_settlement_queue(&self) -> Result<Vec<SettlementResult>> {
         let mut results = Vec::new();
+        let mut deferred = Vec::new();
         let mut queue = self.settlement_queue.write().await;
 
         // Process up to 10 settlements at once
         for _ in 0..10 {
             if let Some(request) = queue.get_next().await {
+                // Sessions in a dispute window only settle once it passes
+                // undisputed; retries of an already released session go through
+                let window_status = self
+                    .dispute_window
+                    .get(request.session_id)
+                    .await
+                    .map(|entry| entry.status);
+                if window_status.is_some_and(|status| status != SettlementStatus::Completed) {
+                    match self.dispute_window.release(request.session_id).await {
+                        Ok(()) => {}
+                        Err(SettlementError::DisputeWindowOpen { .. }) => {
+                            deferred.push(request.session_id);
+                            continue;
+                        }
+                        Err(e) => {
+                            warn!(
+                                "[SETTLEMENT] Session {} not released: {}",
+                                request.session_id, e
+                            );
+                            let status = match e {
+                                SettlementError::SettlementFrozen(_) => SettlementStatus::Disputed,
+                                _ => SettlementStatus::Failed,
+                            };
+                            queue.update_status(request.session_id, status).await;
+                            continue;
+                        }
+                    }
+                }
+
                 // Update status to processing
                 queue
                     .update_status(request.session_id, SettlementStatus::Processing)
@@ -200,6 +247,13 @@ impl SettlementManager {
             }
         }
 
+        // Back in the queue for the next pass
+        for session_id in deferred {
+            queue
+                .update_status(session_id, SettlementStatus::Pending)
+                .await;
+        }
+
         Ok(results)
     }
 
@@ -213,6 +267,7 @@ impl SettlementManager {
 
     /// Settle a session on the blockchain
     /// This will be called when a WebSocket disconnects
+    #[tracing::instrument(name = "settlement", skip(self))]
     pub async fn settle_session(
         &self,
         session_id: u64,
@@ -1,6 +1,7 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 pub mod auto_settlement;
+pub mod dispute_window;
 pub mod gas_estimator;
 pub mod manager;
 pub mod payment_distribution;
@@ -12,6 +12,7 @@ pub enum SettlementStatus {
     Completed,
     Failed,
     Retrying,
+    Disputed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +68,15 @@ pub enum SettlementError {
 
     #[error("Maximum retries exceeded for session: {0}")]
     MaxRetriesExceeded(u64),
+
+    #[error("Dispute window still open for job {job_id}: {remaining_secs}s remaining")]
+    DisputeWindowOpen { job_id: u64, remaining_secs: u64 },
+
+    #[error("Dispute window closed for job: {0}")]
+    DisputeWindowClosed(u64),
+
+    #[error("Settlement frozen by dispute for job: {0}")]
+    SettlementFrozen(u64),
 }
 
 impl From<ethers::providers::ProviderError> for SettlementError {
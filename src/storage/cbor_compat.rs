@@ -43,6 +43,166 @@ pub enum CborError {
     CompressionError(String),
     #[error("Invalid data: {0}")]
     InvalidData(String),
+    #[error("Invalid manifest at {path}: {reason}")]
+    InvalidManifest { path: String, reason: String },
+}
+
+/// DirV1 versions this node can read
+pub const SUPPORTED_DIRV1_VERSIONS: &[u32] = &[1];
+
+/// Entry types written by this node and the S5 SDK
+pub const DIRV1_ENTRY_TYPES: &[&str] = &["file", "directory", "chunk"];
+
+/// Multibase prefixes for the CID encodings S5 and IPFS produce
+const CID_MULTIBASE_PREFIXES: &[char] = &['b', 'z', 'u', 'm'];
+
+fn manifest_error(path: impl Into<String>, reason: impl Into<String>) -> CborError {
+    CborError::InvalidManifest {
+        path: path.into(),
+        reason: reason.into(),
+    }
+}
+
+fn check_compression(path: String, value: &str) -> Result<(), CborError> {
+    match value.to_ascii_lowercase().as_str() {
+        "none" | "zstd" => Ok(()),
+        _ => Err(manifest_error(
+            path,
+            format!("unknown compression type '{}'", value),
+        )),
+    }
+}
+
+/// Check a decoded manifest against the structure the SDK and this node write
+///
+/// Entries are checked in name order so the same manifest always reports the
+/// same problem. Paths are `/`-separated from the manifest root, e.g.
+/// `/entries/chunk-00000001/cid`.
+pub fn validate_manifest(dir: &DirV1) -> Result<(), CborError> {
+    if !SUPPORTED_DIRV1_VERSIONS.contains(&dir.version) {
+        return Err(manifest_error(
+            "/version",
+            format!("unsupported DirV1 version {}", dir.version),
+        ));
+    }
+    if let Some(compression) = dir.metadata.get("compression") {
+        check_compression("/metadata/compression".to_string(), compression)?;
+    }
+
+    let mut names: Vec<&String> = dir.entries.keys().collect();
+    names.sort();
+
+    for name in &names {
+        let entry = &dir.entries[*name];
+        let path = format!("/entries/{}", name);
+        if name.is_empty() || name.contains('/') {
+            return Err(manifest_error(
+                path,
+                "entry name must be non-empty and contain no '/'",
+            ));
+        }
+
+        if entry.cid.is_empty() {
+            return Err(manifest_error(format!("{}/cid", path), "missing CID"));
+        }
+        if entry.cid.starts_with("s5://") {
+            return Err(manifest_error(
+                format!("{}/cid", path),
+                "CID carries an s5:// URI prefix; manifests store the bare CID",
+            ));
+        }
+        if !entry.cid.starts_with(CID_MULTIBASE_PREFIXES)
+            || !entry.cid.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(manifest_error(
+                format!("{}/cid", path),
+                format!("'{}' is not a multibase-encoded CID", entry.cid),
+            ));
+        }
+
+        if !DIRV1_ENTRY_TYPES.contains(&entry.entry_type.as_str()) {
+            return Err(manifest_error(
+                format!("{}/entry_type", path),
+                format!("unknown entry type '{}'", entry.entry_type),
+            ));
+        }
+        if let Some(compression) = entry.metadata.get("compression") {
+            check_compression(format!("{}/metadata/compression", path), compression)?;
+        }
+    }
+
+    if dir.metadata.get("type").map(String::as_str) == Some("chunked") {
+        validate_chunked(dir, &names)?;
+    }
+    Ok(())
+}
+
+/// Chunk manifests must list chunks `chunk-00000000` onwards with no gaps,
+/// and agree with their own count and size totals
+fn validate_chunked(dir: &DirV1, names: &[&String]) -> Result<(), CborError> {
+    let required = |key: &str| -> Result<u64, CborError> {
+        let path = format!("/metadata/{}", key);
+        let value = dir
+            .metadata
+            .get(key)
+            .ok_or_else(|| manifest_error(path.clone(), "missing"))?;
+        value
+            .parse()
+            .map_err(|_| manifest_error(path, format!("'{}' is not a number", value)))
+    };
+    let chunk_count = required("chunk_count")?;
+    let total_size = required("total_size")?;
+    if !dir.metadata.contains_key("content_type") {
+        return Err(manifest_error("/metadata/content_type", "missing"));
+    }
+
+    if chunk_count != names.len() as u64 {
+        return Err(manifest_error(
+            "/metadata/chunk_count",
+            format!(
+                "declares {} chunks but the manifest has {} entries",
+                chunk_count,
+                names.len()
+            ),
+        ));
+    }
+
+    for (index, name) in names.iter().enumerate() {
+        let entry = &dir.entries[*name];
+        let path = format!("/entries/{}", name);
+        // Zero-padded names sort in upload order; anything else reorders chunks
+        let expected = format!("chunk-{:08}", index);
+        if **name != expected {
+            return Err(manifest_error(
+                path,
+                format!("expected chunk entry '{}'", expected),
+            ));
+        }
+        if entry.entry_type != "chunk" {
+            return Err(manifest_error(
+                format!("{}/entry_type", path),
+                "chunked manifests may only contain chunk entries",
+            ));
+        }
+        if entry.metadata.get("index") != Some(&index.to_string()) {
+            return Err(manifest_error(
+                format!("{}/metadata/index", path),
+                format!("expected index {}", index),
+            ));
+        }
+    }
+
+    let entries_size: u64 = dir.entries.values().map(|e| e.size).sum();
+    if entries_size != total_size {
+        return Err(manifest_error(
+            "/metadata/total_size",
+            format!(
+                "declares {} bytes but chunks add up to {}",
+                total_size, entries_size
+            ),
+        ));
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
@@ -134,8 +294,11 @@ impl CborCompat {
         self.encode(dir)
     }
 
+    /// Decode a DirV1 manifest and check it with `validate_manifest`
     pub fn decode_dirv1(&self, data: &[u8]) -> Result<DirV1, CborError> {
-        self.decode(data)
+        let dir = self.decode(data)?;
+        validate_manifest(&dir)?;
+        Ok(dir)
     }
 
     pub fn encode_with_compression<T: Serialize>(
@@ -248,4 +411,72 @@ mod tests {
         assert!(compressed.len() < data.len());
         assert_eq!(decompressed, data);
     }
+
+    fn chunk(index: usize, size: u64) -> (String, DirV1Entry) {
+        (
+            format!("chunk-{:08}", index),
+            DirV1Entry {
+                cid: format!("bafkrei{}", index),
+                size,
+                entry_type: "chunk".to_string(),
+                metadata: HashMap::from([("index".to_string(), index.to_string())]),
+            },
+        )
+    }
+
+    fn chunked_manifest() -> DirV1 {
+        DirV1 {
+            version: 1,
+            entries: HashMap::from([chunk(0, 10), chunk(1, 5)]),
+            metadata: HashMap::from([
+                ("type".to_string(), "chunked".to_string()),
+                ("content_type".to_string(), "text/plain".to_string()),
+                ("total_size".to_string(), "15".to_string()),
+                ("chunk_count".to_string(), "2".to_string()),
+            ]),
+        }
+    }
+
+    fn invalid_path(dir: &DirV1) -> String {
+        match validate_manifest(dir) {
+            Err(CborError::InvalidManifest { path, .. }) => path,
+            other => panic!("expected InvalidManifest, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_manifest() {
+        let dir = chunked_manifest();
+        assert!(validate_manifest(&dir).is_ok());
+
+        let mut prefixed = dir.clone();
+        prefixed.entries.get_mut("chunk-00000001").unwrap().cid = "s5://bafkrei1".to_string();
+        assert_eq!(invalid_path(&prefixed), "/entries/chunk-00000001/cid");
+
+        let mut miscounted = dir.clone();
+        miscounted
+            .metadata
+            .insert("chunk_count".to_string(), "3".to_string());
+        assert_eq!(invalid_path(&miscounted), "/metadata/chunk_count");
+
+        // Unpadded names sort out of upload order
+        let mut unpadded = dir.clone();
+        let entry = unpadded.entries.remove("chunk-00000001").unwrap();
+        unpadded.entries.insert("chunk-1".to_string(), entry);
+        assert_eq!(invalid_path(&unpadded), "/entries/chunk-1");
+
+        let mut compressed = dir.clone();
+        compressed
+            .metadata
+            .insert("compression".to_string(), "gzip".to_string());
+        assert_eq!(invalid_path(&compressed), "/metadata/compression");
+
+        // Invalid manifests are rejected on read
+        let compat = CborCompat::new();
+        let encoded = compat.encode_dirv1(&miscounted).unwrap();
+        assert!(matches!(
+            compat.decode_dirv1(&encoded),
+            Err(CborError::InvalidManifest { .. })
+        ));
+    }
 }
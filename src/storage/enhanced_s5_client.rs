@@ -55,6 +55,15 @@
 //! export ENHANCED_S5_URL=http://localhost:5522
 //! ```
 //!
+//! For failover, list several bridges/portals in priority order. Reads go to
+//! the first one that answers; writes go to the first
+//! `ENHANCED_S5_WRITE_REPLICAS` (default 1) that accept them. A portal that
+//! keeps failing is skipped while its circuit breaker is open.
+//! ```bash
+//! export ENHANCED_S5_URL=http://localhost:5522,http://backup-bridge:5522
+//! export ENHANCED_S5_WRITE_REPLICAS=2
+//! ```
+//!
 //! ## Health Checks
 //!
 //! Before starting the node, verify the bridge is healthy:
@@ -77,9 +86,9 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
-use std::sync::Mutex;
-use std::time::Duration;
-use tracing::{error, info, warn};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 
 /// Check if a string is a valid S5 CID in multibase format
 /// S5 CIDs are raw 32-byte blake3 hashes encoded with multibase:
@@ -158,13 +167,218 @@ fn format_hash_as_cid(hash: &str) -> String {
     format!("b{}", base32_encoded)
 }
 
+/// BlobIdentifier prefix bytes: CID type (2 bytes) + BLAKE3 multihash code
+const BLOB_ID_PREFIX: [u8; 3] = [0x5b, 0x82, 0x1e];
+
+/// Compute the BlobIdentifier CID S5 assigns to `data`, without uploading it
+///
+/// Same layout the bridge builds after an upload: 'b' + base32(prefix +
+/// BLAKE3 hash + little-endian size with trailing zero bytes trimmed).
+pub fn compute_blob_cid(data: &[u8]) -> String {
+    let size = (data.len() as u64).to_le_bytes();
+    let size_len = size.iter().rposition(|&b| b != 0).map_or(1, |i| i + 1);
+
+    let mut bytes = Vec::with_capacity(BLOB_ID_PREFIX.len() + 32 + size_len);
+    bytes.extend_from_slice(&BLOB_ID_PREFIX);
+    bytes.extend_from_slice(blake3::hash(data).as_bytes());
+    bytes.extend_from_slice(&size[..size_len]);
+
+    format!(
+        "b{}",
+        data_encoding::BASE32_NOPAD.encode(&bytes).to_lowercase()
+    )
+}
+
 #[derive(Debug, Clone)]
 pub struct S5Config {
+    /// Bridge URL, or a comma-separated list of portal URLs in priority order
     pub api_url: String,
     pub api_key: Option<String>,
     pub timeout_secs: u64,
 }
 
+/// Retry and circuit-breaker settings for bridge requests
+#[derive(Debug, Clone)]
+pub struct S5RetryPolicy {
+    /// Attempts per request, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on each further retry
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Consecutive failed attempts that open the circuit
+    pub failure_threshold: u32,
+    /// How long an open circuit fast-fails before letting a trial request through
+    pub open_duration: Duration,
+}
+
+impl Default for S5RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(2),
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+impl S5RetryPolicy {
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << retry.min(16))
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// Circuit breaker state for one bridge endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerStatus {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Seconds until an open circuit lets a trial request through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_secs: Option<u64>,
+}
+
+/// Returned without contacting the bridge while its circuit is open
+#[derive(Debug, thiserror::Error)]
+#[error("S5 bridge at {endpoint} is unavailable; circuit open for another {}s", .retry_in.as_secs())]
+pub struct CircuitOpenError {
+    pub endpoint: String,
+    pub retry_in: Duration,
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// Set while a half-open trial request is in flight
+    trial_started_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Err carries how long until the circuit lets a request through
+    fn try_acquire(&mut self, policy: &S5RetryPolicy) -> std::result::Result<(), Duration> {
+        let Some(opened_at) = self.opened_at else {
+            return Ok(());
+        };
+        let elapsed = opened_at.elapsed();
+        if elapsed < policy.open_duration {
+            return Err(policy.open_duration - elapsed);
+        }
+        // One trial at a time; a trial whose caller gave up expires like the circuit did
+        if let Some(started) = self.trial_started_at {
+            if started.elapsed() < policy.open_duration {
+                return Err(Duration::ZERO);
+            }
+        }
+        self.trial_started_at = Some(Instant::now());
+        Ok(())
+    }
+
+    fn record_success(&mut self) {
+        *self = Self::default();
+    }
+
+    fn record_failure(&mut self, policy: &S5RetryPolicy) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.trial_started_at = None;
+        // A failed half-open trial reopens the circuit straight away
+        if self.opened_at.is_some() || self.consecutive_failures >= policy.failure_threshold {
+            self.opened_at = Some(Instant::now());
+        }
+    }
+
+    fn status(&self, policy: &S5RetryPolicy) -> CircuitBreakerStatus {
+        let (state, retry_in_secs) = match self.opened_at {
+            None => (CircuitState::Closed, None),
+            Some(opened_at) => match policy.open_duration.checked_sub(opened_at.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => (
+                    CircuitState::Open,
+                    Some(remaining.as_secs_f64().ceil() as u64),
+                ),
+                _ => (CircuitState::HalfOpen, None),
+            },
+        };
+        CircuitBreakerStatus {
+            state,
+            consecutive_failures: self.consecutive_failures,
+            retry_in_secs,
+        }
+    }
+}
+
+/// Breakers are shared by every client of the same bridge, so an outage seen
+/// by one component fast-fails the others too
+fn circuit_breaker_for(endpoint: &str) -> Arc<Mutex<CircuitBreaker>> {
+    static BREAKERS: OnceLock<Mutex<HashMap<String, Arc<Mutex<CircuitBreaker>>>>> = OnceLock::new();
+    BREAKERS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry(endpoint.trim_end_matches('/').to_string())
+        .or_default()
+        .clone()
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Number of portals each write goes to; defaults to 1
+pub const WRITE_REPLICAS_ENV: &str = "ENHANCED_S5_WRITE_REPLICAS";
+
+/// One configured bridge/portal and its shared circuit breaker
+#[derive(Debug, Clone)]
+struct Portal {
+    url: String,
+    breaker: Arc<Mutex<CircuitBreaker>>,
+}
+
+impl Portal {
+    fn new(url: &str) -> Self {
+        let url = url.trim().trim_end_matches('/').to_string();
+        Self {
+            breaker: circuit_breaker_for(&url),
+            url,
+        }
+    }
+
+    fn status(&self, policy: &S5RetryPolicy) -> CircuitBreakerStatus {
+        self.breaker.lock().unwrap().status(policy)
+    }
+
+    /// Known-bad portals are skipped until their circuit lets a trial through
+    fn is_available(&self, policy: &S5RetryPolicy) -> bool {
+        self.status(policy).state != CircuitState::Open
+    }
+}
+
+/// Health of one configured portal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalHealth {
+    pub url: String,
+    pub circuit_breaker: CircuitBreakerStatus,
+}
+
+/// Bridge path for a file, with or without the `/s5/fs` prefix
+fn fs_path(path: &str) -> String {
+    if path.starts_with("/s5/fs") {
+        path.to_string()
+    } else {
+        format!("/s5/fs/{}", path.trim_start_matches('/'))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct S5File {
     pub name: String,
@@ -198,8 +412,11 @@ pub struct BridgeHealthResponse {
 #[derive(Clone, Debug)]
 pub struct EnhancedS5Client {
     client: Client,
-    base_url: String,
+    /// Reads try these in order; writes go to the first `write_replicas` that accept
+    portals: Vec<Portal>,
+    write_replicas: usize,
     api_key: Option<String>,
+    retry_policy: S5RetryPolicy,
     // Mock storage for testing
     mock_storage: std::sync::Arc<Mutex<HashMap<String, (Vec<u8>, Option<JsonValue>)>>>,
 }
@@ -210,10 +427,26 @@ impl EnhancedS5Client {
             .timeout(Duration::from_secs(config.timeout_secs))
             .build()?;
 
+        let portals: Vec<Portal> = config
+            .api_url
+            .split(',')
+            .filter(|url| !url.trim().is_empty())
+            .map(Portal::new)
+            .collect();
+        if portals.is_empty() {
+            return Err(anyhow!("No S5 portal URL configured"));
+        }
+        let write_replicas = std::env::var(WRITE_REPLICAS_ENV)
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(1);
+
         Ok(Self {
             client,
-            base_url: config.api_url,
+            write_replicas: write_replicas.clamp(1, portals.len()),
+            portals,
             api_key: config.api_key,
+            retry_policy: S5RetryPolicy::default(),
             mock_storage: std::sync::Arc::new(Mutex::new(HashMap::new())),
         })
     }
@@ -227,34 +460,228 @@ impl EnhancedS5Client {
         })
     }
 
-    pub async fn health_check(&self) -> Result<HealthResponse> {
-        let url = format!("{}/health", self.base_url);
+    /// Override the default retry and circuit-breaker policy
+    pub fn with_retry_policy(mut self, policy: S5RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
 
-        let response = self.client.get(&url).send().await?;
+    /// Number of portals each write goes to, capped at the number configured
+    pub fn with_write_replicas(mut self, replicas: usize) -> Self {
+        self.write_replicas = replicas.clamp(1, self.portals.len());
+        self
+    }
 
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Health check failed with status: {}",
-                response.status()
-            ));
+    /// State of the circuit breaker guarding the primary portal
+    pub fn circuit_breaker_status(&self) -> CircuitBreakerStatus {
+        self.portals[0].status(&self.retry_policy)
+    }
+
+    /// Circuit breaker state of every configured portal, in priority order
+    pub fn portal_health(&self) -> Vec<PortalHealth> {
+        self.portals
+            .iter()
+            .map(|portal| PortalHealth {
+                url: portal.url.clone(),
+                circuit_breaker: portal.status(&self.retry_policy),
+            })
+            .collect()
+    }
+
+    /// Whether any portal's circuit would let a request through
+    pub fn is_available(&self) -> bool {
+        self.portals
+            .iter()
+            .any(|portal| portal.is_available(&self.retry_policy))
+    }
+
+    /// Whether no portal after `index` could take over from it
+    fn is_last_resort(&self, index: usize) -> bool {
+        !self.portals[index + 1..]
+            .iter()
+            .any(|portal| portal.is_available(&self.retry_policy))
+    }
+
+    /// Send a request to one portal, retrying 5xx/429 responses with
+    /// exponential backoff. Connection errors are only retried when no other
+    /// portal can take over. Fast-fails with `CircuitOpenError` while the
+    /// portal's circuit is open; any other response is returned as-is.
+    async fn send_to_portal<F>(
+        &self,
+        portal: &Portal,
+        last_resort: bool,
+        build: &F,
+    ) -> Result<reqwest::Response>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let max_attempts = self.retry_policy.max_attempts.max(1);
+        let mut attempt = 0;
+        loop {
+            let acquired = portal
+                .breaker
+                .lock()
+                .unwrap()
+                .try_acquire(&self.retry_policy);
+            if let Err(retry_in) = acquired {
+                return Err(CircuitOpenError {
+                    endpoint: portal.url.clone(),
+                    retry_in,
+                }
+                .into());
+            }
+            attempt += 1;
+
+            let result = build(&portal.url).send().await;
+            let failure = match &result {
+                Ok(response) if is_retryable_status(response.status()) => {
+                    Some(format!("status {}", response.status()))
+                }
+                Ok(_) => None,
+                Err(e) => Some(e.to_string()),
+            };
+            let Some(failure) = failure else {
+                portal.breaker.lock().unwrap().record_success();
+                return result.map_err(Into::into);
+            };
+            portal
+                .breaker
+                .lock()
+                .unwrap()
+                .record_failure(&self.retry_policy);
+
+            if attempt >= max_attempts || (result.is_err() && !last_resort) {
+                return result.map_err(Into::into);
+            }
+            let delay = self.retry_policy.backoff(attempt - 1);
+            warn!(
+                "S5 request to {} failed ({}), attempt {}/{}; retrying in {:?}",
+                portal.url, failure, attempt, max_attempts, delay
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Send a read to the first portal that answers, in priority order. A
+    /// portal that hasn't got the object passes the read on; 404 is returned
+    /// only when every portal answered 404.
+    async fn send_with_failover<F>(&self, build: F) -> Result<reqwest::Response>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let mut last = None;
+        let mut not_found = None;
+        for (index, portal) in self.portals.iter().enumerate() {
+            match self
+                .send_to_portal(portal, self.is_last_resort(index), &build)
+                .await
+            {
+                Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => {
+                    debug!("S5 portal {} has no such object", portal.url);
+                    not_found = Some(response);
+                }
+                Ok(response) if !is_retryable_status(response.status()) => return Ok(response),
+                Ok(response) => last = Some(Ok(response)),
+                Err(e) => {
+                    warn!("S5 portal {} unavailable: {}", portal.url, e);
+                    last = Some(Err(e));
+                }
+            }
+        }
+        match (last, not_found) {
+            (Some(last), _) => last,
+            (None, Some(not_found)) => Ok(not_found),
+            (None, None) => unreachable!("client has at least one portal"),
+        }
+    }
+
+    /// Send a write to the first `write_replicas` portals that accept it, in
+    /// priority order. Returns the first accepted response, or the last
+    /// rejection when no portal accepted the write.
+    async fn send_to_replicas<F>(
+        &self,
+        accepts: fn(reqwest::StatusCode) -> bool,
+        build: F,
+    ) -> Result<reqwest::Response>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let mut accepted = None;
+        let mut written = 0;
+        let mut last = None;
+        for (index, portal) in self.portals.iter().enumerate() {
+            if written == self.write_replicas {
+                break;
+            }
+            let last_resort = accepted.is_none() && self.is_last_resort(index);
+            match self.send_to_portal(portal, last_resort, &build).await {
+                Ok(response) if accepts(response.status()) => {
+                    written += 1;
+                    accepted.get_or_insert(response);
+                }
+                Ok(response) => {
+                    warn!(
+                        "S5 portal {} rejected write: {}",
+                        portal.url,
+                        response.status()
+                    );
+                    last = Some(Ok(response));
+                }
+                Err(e) => {
+                    warn!("S5 portal {} unavailable: {}", portal.url, e);
+                    last = Some(Err(e));
+                }
+            }
         }
 
+        match accepted {
+            Some(response) => {
+                if written < self.write_replicas {
+                    warn!(
+                        "S5 write reached {} of {} portals",
+                        written, self.write_replicas
+                    );
+                }
+                Ok(response)
+            }
+            None => last.expect("client has at least one portal"),
+        }
+    }
+
+    /// First portal whose `/health` answers successfully
+    async fn first_healthy(&self, what: &str) -> Result<reqwest::Response> {
+        let mut last_error = None;
+        for portal in &self.portals {
+            match self
+                .client
+                .get(format!("{}/health", portal.url))
+                .send()
+                .await
+            {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    last_error = Some(anyhow!(
+                        "{} failed with status: {}",
+                        what,
+                        response.status()
+                    ))
+                }
+                Err(e) => last_error = Some(e.into()),
+            }
+        }
+        Err(last_error.expect("client has at least one portal"))
+    }
+
+    pub async fn health_check(&self) -> Result<HealthResponse> {
+        let response = self.first_healthy("Health check").await?;
+
         let health: HealthResponse = response.json().await?;
         Ok(health)
     }
 
     /// Check Enhanced S5.js bridge service health
     pub async fn bridge_health_check(&self) -> Result<BridgeHealthResponse> {
-        let url = format!("{}/health", self.base_url);
-
-        let response = self.client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Bridge health check failed with status: {}",
-                response.status()
-            ));
-        }
+        let response = self.first_healthy("Bridge health check").await?;
 
         let health: BridgeHealthResponse = response.json().await?;
         Ok(health)
@@ -264,25 +691,26 @@ impl EnhancedS5Client {
     /// The S5 bridge returns the CID in the response body as JSON: {"cid": "bafybei..."}
     pub async fn put_file(&self, path: &str, content: Vec<u8>) -> Result<String> {
         let content_size = content.len();
-        let url = if path.starts_with("/s5/fs") {
-            format!("{}{}", self.base_url, path)
-        } else {
-            format!("{}/s5/fs/{}", self.base_url, path.trim_start_matches('/'))
-        };
+        let fs_path = fs_path(path);
 
         info!(
-            "📤 [S5-HTTP] PUT request: url='{}', path='{}', size={} bytes",
-            url, path, content_size
+            "📤 [S5-HTTP] PUT request: fs_path='{}', path='{}', size={} bytes, replicas={}",
+            fs_path, path, content_size, self.write_replicas
         );
 
         let start_time = std::time::Instant::now();
 
+        let content = bytes::Bytes::from(content);
         let response = self
-            .client
-            .put(&url)
-            .header("Content-Type", "application/octet-stream")
-            .body(content)
-            .send()
+            .send_to_replicas(
+                |status| status.is_success(),
+                |base| {
+                    self.client
+                        .put(format!("{}{}", base, fs_path))
+                        .header("Content-Type", "application/octet-stream")
+                        .body(content.clone())
+                },
+            )
             .await?;
 
         let status = response.status();
@@ -347,15 +775,13 @@ impl EnhancedS5Client {
     }
 
     pub async fn get_file(&self, path: &str) -> Result<Vec<u8>> {
-        let url = if path.starts_with("/s5/fs") {
-            format!("{}{}", self.base_url, path)
-        } else {
-            format!("{}/s5/fs/{}", self.base_url, path.trim_start_matches('/'))
-        };
+        let fs_path = fs_path(path);
 
-        info!("GET file from: {}", url);
+        info!("GET file from: {}", fs_path);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .send_with_failover(|base| self.client.get(format!("{}{}", base, fs_path)))
+            .await?;
 
         if response.status() == 404 {
             return Err(anyhow!("File not found: {}", path));
@@ -387,11 +813,11 @@ impl EnhancedS5Client {
             format!("/s5/fs/{}/", clean_path)
         };
 
-        let url = format!("{}{}", self.base_url, formatted_path);
-
-        info!("LIST directory: {}", url);
+        info!("LIST directory: {}", formatted_path);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .send_with_failover(|base| self.client.get(format!("{}{}", base, formatted_path)))
+            .await?;
 
         if response.status() == 404 {
             // Directory doesn't exist, return empty list
@@ -416,15 +842,16 @@ impl EnhancedS5Client {
     }
 
     pub async fn delete_file(&self, path: &str) -> Result<()> {
-        let url = if path.starts_with("/s5/fs") {
-            format!("{}{}", self.base_url, path)
-        } else {
-            format!("{}/s5/fs/{}", self.base_url, path.trim_start_matches('/'))
-        };
+        let fs_path = fs_path(path);
 
-        info!("DELETE file: {}", url);
+        info!("DELETE file: {}", fs_path);
 
-        let response = self.client.delete(&url).send().await?;
+        let response = self
+            .send_to_replicas(
+                |status| status.is_success() || status == reqwest::StatusCode::NOT_FOUND,
+                |base| self.client.delete(format!("{}{}", base, fs_path)),
+            )
+            .await?;
 
         // Delete should be idempotent - 404 is okay
         if response.status() == 404 {
@@ -449,17 +876,55 @@ impl EnhancedS5Client {
     }
 
     pub async fn exists(&self, path: &str) -> Result<bool> {
-        let url = if path.starts_with("/s5/fs") {
-            format!("{}{}", self.base_url, path)
-        } else {
-            format!("{}/s5/fs/{}", self.base_url, path.trim_start_matches('/'))
-        };
-
-        let response = self.client.head(&url).send().await?;
+        let fs_path = fs_path(path);
+        let response = self
+            .send_with_failover(|base| self.client.head(format!("{}{}", base, fs_path)))
+            .await?;
 
         Ok(response.status().is_success())
     }
 
+    /// Whether a blob with this CID is already stored on the network
+    pub async fn blob_exists(&self, cid: &str) -> Result<bool> {
+        let response = self
+            .send_with_failover(|base| self.client.head(format!("{}/s5/blob/{}", base, cid)))
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => Err(anyhow!("Blob existence check failed: {}", status)),
+        }
+    }
+
+    /// Upload unless identical content is already stored, returning its CID
+    ///
+    /// When the blob already exists nothing is written at `path`, so callers
+    /// must address the content by CID. Use `put` when `path` itself has to
+    /// be written or overwritten.
+    pub async fn put_dedup(
+        &self,
+        path: &str,
+        data: Vec<u8>,
+        metadata: Option<JsonValue>,
+    ) -> Result<String> {
+        let cid = compute_blob_cid(&data);
+
+        match self.blob_exists(&cid).await {
+            Ok(true) => {
+                info!(
+                    "Skipping upload to {}: content already stored as {}",
+                    path, cid
+                );
+                return Ok(cid);
+            }
+            Ok(false) => {}
+            Err(e) => warn!("Dedup check for {} failed, uploading anyway: {}", path, e),
+        }
+
+        self.put(path, data, metadata).await
+    }
+
     // New methods for E2E workflow tests
     pub async fn put(
         &self,
@@ -493,6 +958,17 @@ impl EnhancedS5Client {
             }
         }
     }
+
+    pub async fn delete(&self, path: &str) -> Result<()> {
+        let had_mock_entry = self.mock_storage.lock().unwrap().remove(path).is_some();
+
+        match self.delete_file(path).await {
+            Ok(()) => Ok(()),
+            // Entry only existed locally (bridge unavailable); local removal is enough
+            Err(_) if had_mock_entry => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -671,6 +1147,210 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compute_blob_cid() {
+        let cid = compute_blob_cid(b"inference result");
+        assert!(
+            is_valid_s5_cid(&cid),
+            "computed CID should be a BlobIdentifier"
+        );
+        assert_eq!(cid.len(), 59, "1-byte size encoding gives 36 bytes");
+        assert_eq!(cid, compute_blob_cid(b"inference result"));
+        assert_ne!(cid, compute_blob_cid(b"inference result!"));
+
+        // Size bytes follow the hash, little-endian with trailing zeros trimmed
+        let decoded = data_encoding::BASE32_NOPAD
+            .decode(cid[1..].to_uppercase().as_bytes())
+            .unwrap();
+        assert_eq!(&decoded[..3], &BLOB_ID_PREFIX);
+        assert_eq!(
+            &decoded[3..35],
+            blake3::hash(b"inference result").as_bytes()
+        );
+        assert_eq!(&decoded[35..], &[16]);
+
+        let large = compute_blob_cid(&vec![0u8; 300]);
+        assert!(is_valid_s5_cid(&large));
+        assert_eq!(large.len(), 61, "300 bytes needs a 2-byte size");
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_after_repeated_failures() {
+        // Nothing listens on port 1; the path keeps this breaker private to the test
+        let client = EnhancedS5Client::new_legacy("http://127.0.0.1:1/circuit-test".to_string())
+            .unwrap()
+            .with_retry_policy(S5RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                failure_threshold: 3,
+                open_duration: Duration::from_secs(60),
+            });
+
+        assert!(client.put_file("a.txt", b"a".to_vec()).await.is_err());
+        let status = client.circuit_breaker_status();
+        assert_eq!(status.state, CircuitState::Closed);
+        assert_eq!(status.consecutive_failures, 2);
+
+        // Third failed attempt opens the circuit mid-request; the retry fast-fails
+        let err = client.put_file("a.txt", b"a".to_vec()).await.unwrap_err();
+        assert!(err.downcast_ref::<CircuitOpenError>().is_some());
+        let status = client.circuit_breaker_status();
+        assert_eq!(status.state, CircuitState::Open);
+        assert_eq!(status.consecutive_failures, 3);
+        assert!(status.retry_in_secs.unwrap() > 0);
+
+        // Other clients of the same bridge share the open circuit
+        let other =
+            EnhancedS5Client::new_legacy("http://127.0.0.1:1/circuit-test".to_string()).unwrap();
+        let err = other.get_file("a.txt").await.unwrap_err();
+        assert!(err.downcast_ref::<CircuitOpenError>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_next_portal() {
+        use axum::routing::put;
+
+        let app = axum::Router::new().route(
+            "/s5/fs/*path",
+            put(|| async { axum::Json(serde_json::json!({ "cid": "bfailover" })) })
+                .get(|| async { "stored" }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let backup = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        // Nothing listens on port 1, so the primary portal refuses connections
+        let client =
+            EnhancedS5Client::new_legacy(format!("http://127.0.0.1:1/failover-test, {}", backup))
+                .unwrap()
+                .with_retry_policy(S5RetryPolicy {
+                    failure_threshold: 1,
+                    ..Default::default()
+                })
+                .with_write_replicas(2);
+
+        assert_eq!(
+            client.put_file("a.txt", b"a".to_vec()).await.unwrap(),
+            "bfailover"
+        );
+        assert_eq!(client.get_file("a.txt").await.unwrap(), b"stored");
+
+        // The primary is now known-bad and skipped while its circuit is open
+        let health = client.portal_health();
+        assert_eq!(health[0].circuit_breaker.state, CircuitState::Open);
+        assert_eq!(health[1].url, backup);
+        assert_eq!(health[1].circuit_breaker.state, CircuitState::Closed);
+        assert!(client.is_available());
+    }
+
+    #[tokio::test]
+    async fn test_read_falls_through_missing_object() {
+        use axum::http::StatusCode;
+        use axum::routing::get;
+
+        async fn serve(app: axum::Router) -> String {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move { axum::serve(listener, app).await });
+            url
+        }
+        let empty = serve(
+            axum::Router::new().route("/s5/fs/*path", get(|| async { StatusCode::NOT_FOUND })),
+        )
+        .await;
+        let holder =
+            serve(axum::Router::new().route("/s5/fs/*path", get(|| async { "replicated" }))).await;
+
+        // Only the second portal has the object
+        let client = EnhancedS5Client::new_legacy(format!("{}, {}", empty, holder)).unwrap();
+        assert_eq!(client.get_file("a.txt").await.unwrap(), b"replicated");
+        assert_eq!(
+            client.portal_health()[0].circuit_breaker.state,
+            CircuitState::Closed
+        );
+
+        // Missing everywhere is still a plain not-found
+        let client = EnhancedS5Client::new_legacy(format!("{}, {}", empty, empty)).unwrap();
+        let err = client.get_file("a.txt").await.unwrap_err();
+        assert!(err.to_string().contains("File not found"));
+    }
+
+    #[tokio::test]
+    async fn test_put_dedup_skips_stored_blob() {
+        use axum::extract::Path;
+        use axum::http::StatusCode;
+        use axum::routing::{get, put};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let stored = compute_blob_cid(b"stored");
+        let uploads = Arc::new(AtomicUsize::new(0));
+        let counter = uploads.clone();
+        let known = stored.clone();
+        let app = axum::Router::new()
+            .route(
+                "/s5/blob/:cid",
+                get(move |Path(cid): Path<String>| {
+                    let found = cid == known;
+                    async move {
+                        if found {
+                            StatusCode::OK
+                        } else {
+                            StatusCode::NOT_FOUND
+                        }
+                    }
+                }),
+            )
+            .route(
+                "/s5/fs/*path",
+                put(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async { axum::Json(serde_json::json!({ "cid": "bnew" })) }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let client = EnhancedS5Client::new_legacy(url).unwrap();
+
+        let cid = client
+            .put_dedup("a.txt", b"stored".to_vec(), None)
+            .await
+            .unwrap();
+        assert_eq!(cid, stored);
+        assert_eq!(uploads.load(Ordering::SeqCst), 0);
+
+        let cid = client
+            .put_dedup("b.txt", b"new".to_vec(), None)
+            .await
+            .unwrap();
+        assert_eq!(cid, "bnew");
+        assert_eq!(uploads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_half_open_trial() {
+        let policy = S5RetryPolicy {
+            failure_threshold: 1,
+            open_duration: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let mut breaker = CircuitBreaker::default();
+        breaker.record_failure(&policy);
+        assert_eq!(breaker.status(&policy).state, CircuitState::Open);
+        assert!(breaker.try_acquire(&policy).is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(breaker.status(&policy).state, CircuitState::HalfOpen);
+
+        // Only one trial at a time; its success closes the circuit
+        assert!(breaker.try_acquire(&policy).is_ok());
+        assert!(breaker.try_acquire(&policy).is_err());
+        breaker.record_success();
+        assert_eq!(breaker.status(&policy).state, CircuitState::Closed);
+        assert_eq!(breaker.status(&policy).consecutive_failures, 0);
+    }
+
     #[test]
     fn test_is_valid_s5_cid() {
         // Invalid - IPFS format CIDs (wrong structure, contain 8/9/0/1)
@@ -11,7 +11,8 @@ pub mod s5_client;
 
 // Re-export main types for convenience
 pub use cbor_compat::{
-    CborCompat, CborDecoder, CborEncoder, CborError, CompressionType, DirV1, DirV1Entry, S5Metadata,
+    validate_manifest, CborCompat, CborDecoder, CborEncoder, CborError, CompressionType, DirV1,
+    DirV1Entry, S5Metadata,
 };
 
 pub use s5_client::{
@@ -25,11 +26,15 @@ pub use model_storage::{
 };
 
 pub use result_cache::{
-    CacheConfig, CacheEntry, CacheStats, EvictionPolicy, ResultCache, StorageInfo,
+    CacheConfig, CacheEntry, CacheStats, EvictionPolicy, EvictionReason, EvictionRecord,
+    ResultCache, StorageInfo,
 };
 
 // Re-export Enhanced S5 types
-pub use enhanced_s5_client::{EnhancedS5Client, HealthResponse, S5Config, S5File};
+pub use enhanced_s5_client::{
+    compute_blob_cid, CircuitBreakerStatus, CircuitOpenError, CircuitState, EnhancedS5Client,
+    HealthResponse, PortalHealth, S5Config, S5File, S5RetryPolicy,
+};
 
 // Re-export proof and result storage types
 pub use proof_store::{ProofStore, ProofStoreStats};
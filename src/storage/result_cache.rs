@@ -4,7 +4,7 @@ use crate::storage::{CborCompat, S5Storage, StorageError};
 use chrono::{DateTime, Duration, Utc};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use zstd;
@@ -34,16 +34,46 @@ pub struct CacheEntry {
     pub size_bytes: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CacheStats {
     pub total_entries: u64,
     pub cache_hits: u64,
     pub cache_misses: u64,
     pub hit_rate: f64,
     pub total_size_bytes: u64,
+    /// All evictions; the fields below break this down by reason
     pub evictions: u64,
+    #[serde(default)]
+    pub evictions_ttl: u64,
+    #[serde(default)]
+    pub evictions_size: u64,
+    #[serde(default)]
+    pub evictions_manual: u64,
+}
+
+/// Why an entry left the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionReason {
+    /// Outlived the configured TTL
+    Ttl,
+    /// Made room for newer entries under the size limit
+    Size,
+    /// Removed through `evict`
+    Manual,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvictionRecord {
+    pub key: String,
+    pub reason: EvictionReason,
+    pub evicted_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Recent evictions kept for `eviction_record`
+const EVICTION_LOG_CAPACITY: usize = 1000;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageInfo {
     pub compressed_size: usize,
@@ -66,6 +96,7 @@ pub struct ResultCache {
     memory_cache: Arc<Mutex<LruCache<String, CacheEntry>>>,
     metadata_index: Arc<Mutex<HashMap<String, CacheMetadata>>>,
     stats: Arc<Mutex<CacheStats>>,
+    eviction_log: Arc<Mutex<VecDeque<EvictionRecord>>>,
 }
 
 impl Clone for ResultCache {
@@ -77,6 +108,7 @@ impl Clone for ResultCache {
             memory_cache: Arc::clone(&self.memory_cache),
             metadata_index: Arc::clone(&self.metadata_index),
             stats: Arc::clone(&self.stats),
+            eviction_log: Arc::clone(&self.eviction_log),
         }
     }
 }
@@ -94,14 +126,8 @@ impl ResultCache {
                 initial_capacity.try_into().unwrap(),
             ))),
             metadata_index: Arc::new(Mutex::new(HashMap::new())),
-            stats: Arc::new(Mutex::new(CacheStats {
-                total_entries: 0,
-                cache_hits: 0,
-                cache_misses: 0,
-                hit_rate: 0.0,
-                total_size_bytes: 0,
-                evictions: 0,
-            })),
+            stats: Arc::new(Mutex::new(CacheStats::default())),
+            eviction_log: Arc::new(Mutex::new(VecDeque::new())),
         }
     }
 
@@ -111,8 +137,14 @@ impl ResultCache {
         data: Vec<u8>,
         metadata: Option<HashMap<String, String>>,
     ) -> Result<(), StorageError> {
-        let config = self.config.lock().await;
-        let cache_path = format!("{}/{}", config.base_path, self.encode_key(key));
+        // Eviction below takes the config lock itself
+        let (cache_path, enable_compression) = {
+            let config = self.config.lock().await;
+            (
+                format!("{}/{}", config.base_path, self.encode_key(key)),
+                config.enable_compression,
+            )
+        };
 
         let entry = CacheEntry {
             data: data.clone(),
@@ -131,7 +163,7 @@ impl ResultCache {
             .encode(&entry)
             .map_err(|e| StorageError::SerializationError(e.to_string()))?;
 
-        let final_data = if config.enable_compression {
+        let final_data = if enable_compression {
             self.compress_data(&serialized_entry)?
         } else {
             serialized_entry
@@ -145,8 +177,8 @@ impl ResultCache {
             memory_cache.put(key.to_string(), entry.clone());
         }
 
-        // Update metadata index
-        {
+        // Update metadata index; overwriting a key replaces its old size
+        let replaced = {
             let mut metadata_index = self.metadata_index.lock().await;
             metadata_index.insert(
                 key.to_string(),
@@ -156,13 +188,18 @@ impl ResultCache {
                     created_at: entry.created_at,
                     accessed_at: entry.accessed_at,
                 },
-            );
-        }
+            )
+        };
 
         // Update stats
         {
             let mut stats = self.stats.lock().await;
-            stats.total_entries += 1;
+            match replaced {
+                Some(old) => {
+                    stats.total_size_bytes = stats.total_size_bytes.saturating_sub(old.size_bytes)
+                }
+                None => stats.total_entries += 1,
+            }
             stats.total_size_bytes += entry.size_bytes;
         }
 
@@ -175,8 +212,13 @@ impl ResultCache {
         data: Vec<u8>,
         custom_path: &str,
     ) -> Result<(), StorageError> {
-        let config = self.config.lock().await;
-        let cache_path = format!("{}/{}", config.base_path, custom_path);
+        let (cache_path, enable_compression) = {
+            let config = self.config.lock().await;
+            (
+                format!("{}/{}", config.base_path, custom_path),
+                config.enable_compression,
+            )
+        };
 
         let entry = CacheEntry {
             data: data.clone(),
@@ -191,7 +233,7 @@ impl ResultCache {
             .encode(&entry)
             .map_err(|e| StorageError::SerializationError(e.to_string()))?;
 
-        let final_data = if config.enable_compression {
+        let final_data = if enable_compression {
             self.compress_data(&serialized_entry)?
         } else {
             serialized_entry
@@ -206,6 +248,13 @@ impl ResultCache {
     }
 
     pub async fn get(&self, key: &str) -> Result<Option<CacheEntry>, StorageError> {
+        // Expired entries are evicted on access, even if still held in memory
+        if self.is_expired(key).await {
+            self.evict_with_reason(key, EvictionReason::Ttl).await?;
+            self.record_miss().await;
+            return Ok(None);
+        }
+
         // Check memory cache first
         {
             let mut memory_cache = self.memory_cache.lock().await;
@@ -369,7 +418,47 @@ impl ResultCache {
         stats_copy
     }
 
+    /// Remove one entry now; returns how many entries were evicted (0 or 1)
+    pub async fn evict(&self, key: &str) -> Result<u64, StorageError> {
+        Ok(self.evict_with_reason(key, EvictionReason::Manual).await? as u64)
+    }
+
+    /// Remove every entry past its TTL; returns how many were evicted
+    pub async fn evict_expired(&self) -> Result<u64, StorageError> {
+        let ttl = Duration::seconds(self.config.lock().await.ttl_seconds as i64);
+        let now = Utc::now();
+        let expired: Vec<String> = {
+            let metadata_index = self.metadata_index.lock().await;
+            metadata_index
+                .values()
+                .filter(|metadata| now >= metadata.created_at + ttl)
+                .map(|metadata| metadata.key.clone())
+                .collect()
+        };
+
+        let mut evicted = 0;
+        for key in expired {
+            if self.evict_with_reason(&key, EvictionReason::Ttl).await? {
+                evicted += 1;
+            }
+        }
+        Ok(evicted)
+    }
+
+    /// Why and when `key` was most recently evicted, if it still appears in
+    /// the log of recent evictions
+    pub async fn eviction_record(&self, key: &str) -> Option<EvictionRecord> {
+        let log = self.eviction_log.lock().await;
+        log.iter().rev().find(|record| record.key == key).cloned()
+    }
+
     pub async fn clear(&self) -> Result<(), StorageError> {
+        // Delete indexed entries from storage so they are not reloaded
+        let keys: Vec<String> = self.metadata_index.lock().await.keys().cloned().collect();
+        for key in keys {
+            self.remove_entry(&key).await?;
+        }
+
         // Clear memory cache
         {
             let mut memory_cache = self.memory_cache.lock().await;
@@ -385,28 +474,63 @@ impl ResultCache {
         // Reset stats
         {
             let mut stats = self.stats.lock().await;
-            *stats = CacheStats {
-                total_entries: 0,
-                cache_hits: 0,
-                cache_misses: 0,
-                hit_rate: 0.0,
-                total_size_bytes: 0,
-                evictions: 0,
-            };
+            *stats = CacheStats::default();
         }
-
-        // Note: In a real implementation, we'd need to delete all files from storage
-        // For now, this is sufficient for the tests
+        self.eviction_log.lock().await.clear();
 
         Ok(())
     }
 
     async fn delete(&self, key: &str) -> Result<(), StorageError> {
-        let config = self.config.lock().await;
-        let cache_path = format!("{}/{}", config.base_path, self.encode_key(key));
+        self.remove_entry(key).await.map(|_| ())
+    }
+
+    /// Remove `key` and count it as evicted for `reason`; false if it was not cached
+    async fn evict_with_reason(
+        &self,
+        key: &str,
+        reason: EvictionReason,
+    ) -> Result<bool, StorageError> {
+        let Some(size_bytes) = self.remove_entry(key).await? else {
+            return Ok(false);
+        };
+
+        {
+            let mut stats = self.stats.lock().await;
+            stats.evictions += 1;
+            match reason {
+                EvictionReason::Ttl => stats.evictions_ttl += 1,
+                EvictionReason::Size => stats.evictions_size += 1,
+                EvictionReason::Manual => stats.evictions_manual += 1,
+            }
+        }
+
+        let mut log = self.eviction_log.lock().await;
+        if log.len() == EVICTION_LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(EvictionRecord {
+            key: key.to_string(),
+            reason,
+            evicted_at: Utc::now(),
+            size_bytes,
+        });
+        Ok(true)
+    }
+
+    /// Remove `key` from storage, memory and the index, returning the size
+    /// of the indexed entry it removed
+    async fn remove_entry(&self, key: &str) -> Result<Option<u64>, StorageError> {
+        let cache_path = {
+            let config = self.config.lock().await;
+            format!("{}/{}", config.base_path, self.encode_key(key))
+        };
 
         // Remove from persistent storage
-        self.storage.delete(&cache_path).await?;
+        match self.storage.delete(&cache_path).await {
+            Ok(()) | Err(StorageError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
 
         // Remove from memory cache
         {
@@ -415,16 +539,22 @@ impl ResultCache {
         }
 
         // Remove from metadata index and update stats
-        {
-            let mut metadata_index = self.metadata_index.lock().await;
-            if let Some(metadata) = metadata_index.remove(key) {
-                let mut stats = self.stats.lock().await;
-                stats.total_entries = stats.total_entries.saturating_sub(1);
-                stats.total_size_bytes = stats.total_size_bytes.saturating_sub(metadata.size_bytes);
-            }
-        }
+        let mut metadata_index = self.metadata_index.lock().await;
+        let Some(metadata) = metadata_index.remove(key) else {
+            return Ok(None);
+        };
+        let mut stats = self.stats.lock().await;
+        stats.total_entries = stats.total_entries.saturating_sub(1);
+        stats.total_size_bytes = stats.total_size_bytes.saturating_sub(metadata.size_bytes);
+        Ok(Some(metadata.size_bytes))
+    }
 
-        Ok(())
+    async fn is_expired(&self, key: &str) -> bool {
+        let ttl = Duration::seconds(self.config.lock().await.ttl_seconds as i64);
+        let metadata_index = self.metadata_index.lock().await;
+        metadata_index
+            .get(key)
+            .is_some_and(|metadata| Utc::now() >= metadata.created_at + ttl)
     }
 
     async fn is_entry_valid(&self, key: &str) -> Result<bool, StorageError> {
@@ -508,17 +638,8 @@ impl ResultCache {
         drop(config); // Release lock before deletion
 
         // Evict selected entries
-        let mut eviction_count = 0;
         for key in keys_to_evict {
-            if let Ok(()) = self.delete(&key).await {
-                eviction_count += 1;
-            }
-        }
-
-        // Update eviction stats
-        {
-            let mut stats = self.stats.lock().await;
-            stats.evictions += eviction_count;
+            let _ = self.evict_with_reason(&key, EvictionReason::Size).await;
         }
 
         Ok(())
@@ -89,6 +89,14 @@ pub trait S5Storage: Send + Sync {
     async fn exists(&self, path: &str) -> Result<bool, StorageError>;
     fn clone(&self) -> Box<dyn S5Storage>;
 
+    /// Upload unless identical content is already stored, returning its CID
+    ///
+    /// Nothing may be written at `path` when the content already exists, so
+    /// only use this for content that is addressed by CID afterwards.
+    async fn put_dedup(&self, path: &str, data: Vec<u8>) -> Result<String, StorageError> {
+        self.put(path, data).await
+    }
+
     // Mock-specific methods (no-op for real backend)
     async fn inject_error(&self, _error: StorageError) {}
     async fn set_quota_limit(&self, _limit_bytes: u64) {}
@@ -478,6 +486,15 @@ impl S5Storage for EnhancedS5Backend {
         self.put(path, data).await
     }
 
+    async fn put_dedup(&self, path: &str, data: Vec<u8>) -> Result<String, StorageError> {
+        let clean_path = Self::validate_path(path)?;
+
+        self.client
+            .put_dedup(&clean_path, data, None)
+            .await
+            .map_err(|e| StorageError::ServerError(e.to_string()))
+    }
+
     async fn get(&self, path: &str) -> Result<Vec<u8>, StorageError> {
         let clean_path = Self::validate_path(path)?;
 
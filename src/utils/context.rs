@@ -1,6 +1,6 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
-use crate::inference::ChatTemplate;
+use crate::inference::{tools, ChatTemplate, ChatTemplateOverride, TemplateError, Tool};
 use crate::job_processor::Message;
 
 /// Build a prompt with conversation context
@@ -16,12 +16,67 @@ pub fn build_prompt_with_context(
     prompt: &str,
     thinking: Option<&str>,
 ) -> String {
-    // Get template from environment variable
+    format_with_template(
+        &model_template(),
+        context_messages(context, prompt),
+        thinking,
+    )
+}
+
+/// Template selected by the MODEL_CHAT_TEMPLATE environment variable
+fn model_template() -> ChatTemplate {
     let template_name =
         std::env::var("MODEL_CHAT_TEMPLATE").unwrap_or_else(|_| "harmony".to_string());
 
-    let template = ChatTemplate::from_str(&template_name).unwrap_or(ChatTemplate::Harmony); // Default to Harmony for GPT-OSS-20B
+    ChatTemplate::from_str(&template_name).unwrap_or(ChatTemplate::Harmony) // Default to Harmony for GPT-OSS-20B
+}
+
+/// Build a prompt like `build_prompt_with_context`, but with the request's
+/// template override in place of the model default when one is given, and
+/// with `tools` described to the model. Thinking directives only apply to
+/// built-in templates.
+pub fn build_prompt_with_template(
+    context: &[Message],
+    prompt: &str,
+    thinking: Option<&str>,
+    template: Option<&ChatTemplateOverride>,
+    tools: &[Tool],
+) -> Result<String, TemplateError> {
+    let mut messages = context_messages(context, prompt);
+    match template {
+        Some(ChatTemplateOverride::Custom(template)) => {
+            if thinking.is_some() {
+                tracing::debug!("Thinking mode ignored for custom chat template");
+            }
+            tools::add_to_system_message(&mut messages, tools);
+            template.format_messages(&messages)
+        }
+        Some(ChatTemplateOverride::Builtin(template)) => {
+            template.add_tool_definitions(&mut messages, tools);
+            Ok(format_with_template(template, messages, thinking))
+        }
+        None => {
+            let template = model_template();
+            template.add_tool_definitions(&mut messages, tools);
+            Ok(format_with_template(&template, messages, thinking))
+        }
+    }
+}
 
+/// Frame for late search context in a prompt built by
+/// `build_prompt_with_template` with the same `template`
+pub fn late_context_frame(template: Option<&ChatTemplateOverride>) -> (String, String) {
+    match template {
+        Some(template) => template.late_context_frame(),
+        None => {
+            let (open, close) = model_template().late_context_frame();
+            (open.to_string(), close.to_string())
+        }
+    }
+}
+
+/// Recent context plus the current prompt as (role, content) pairs
+fn context_messages(context: &[Message], prompt: &str) -> Vec<(String, String)> {
     // Take last 10 messages maximum
     let recent_context = if context.len() > 10 {
         &context[context.len() - 10..]
@@ -41,7 +96,8 @@ pub fn build_prompt_with_context(
                     msg.role, msg.content.len(), cleaned_content.len()
                 );
             }
-            (msg.role.clone(), cleaned_content)
+            let content = tools::message_content(&cleaned_content, msg.tool_calls.as_deref());
+            (msg.role.clone(), content)
         })
         .collect();
 
@@ -55,7 +111,14 @@ pub fn build_prompt_with_context(
         );
     }
     messages.push(("user".to_string(), cleaned_prompt));
+    messages
+}
 
+fn format_with_template(
+    template: &ChatTemplate,
+    mut messages: Vec<(String, String)>,
+    thinking: Option<&str>,
+) -> String {
     // Inject thinking directive if specified (v8.17.0+)
     // Returns Some(level) when Harmony post-processing is needed
     // v8.22.3: Removed GLM-4 auto-/think injection — causes degenerate meta-reasoning
@@ -96,7 +159,7 @@ pub fn build_prompt_with_context(
     tracing::debug!(
         "🎨 Formatted prompt using {} template (context: {} messages, {} chars)",
         template.as_str(),
-        recent_context.len(),
+        messages.len() - 1,
         formatted.len()
     );
 
@@ -372,6 +435,8 @@ mod tests {
             role: "user".to_string(),
             content: "test message".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         }];
         let tokens = count_context_tokens(&context);
         assert!(tokens > 0);
@@ -510,6 +575,8 @@ mod tests {
             role: "user".to_string(),
             content: "<|start|>user<|message|>Previous question<|end|>".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         }];
         let prompt = "Follow-up question";
 
@@ -592,6 +659,8 @@ mod tests {
             role: "system".to_string(),
             content: "You are helpful.\n\nReasoning: low".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         }];
         let result = build_prompt_with_context(&context, "Hello", Some("high"));
         assert!(
@@ -811,4 +880,79 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_template_override_replaces_model_default() {
+        let context = vec![Message {
+            role: "user".to_string(),
+            content: "Hi".to_string(),
+            timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
+        }];
+
+        let chatml = ChatTemplateOverride::parse("chatml").unwrap();
+        let result =
+            build_prompt_with_template(&context, "What's 2+2?", None, Some(&chatml), &[]).unwrap();
+        assert!(result.starts_with("<|im_start|>user\nHi<|im_end|>\n"));
+        assert!(!result.contains("<|start|>"));
+
+        let custom = ChatTemplateOverride::parse("[{role}] {content}\n").unwrap();
+        let result =
+            build_prompt_with_template(&context, "What's 2+2?", Some("high"), Some(&custom), &[])
+                .unwrap();
+        assert_eq!(result, "[user] Hi\n[user] What's 2+2?\n[assistant] ");
+    }
+
+    #[test]
+    fn test_tool_definitions_and_results_in_prompt() {
+        let tool: Tool = serde_json::from_value(serde_json::json!({
+            "type": "function",
+            "function": {"name": "get_weather", "parameters": {"type": "object"}}
+        }))
+        .unwrap();
+        let call =
+            crate::inference::ToolCall::new("get_weather", &serde_json::json!({"city": "Paris"}));
+        let context = vec![
+            Message {
+                role: "user".to_string(),
+                content: "Weather in Paris?".to_string(),
+                timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
+            },
+            Message {
+                role: "assistant".to_string(),
+                content: String::new(),
+                timestamp: None,
+                tool_calls: Some(vec![call.clone()]),
+                tool_call_id: None,
+            },
+            Message {
+                role: "tool".to_string(),
+                content: "18C, cloudy".to_string(),
+                timestamp: None,
+                tool_calls: None,
+                tool_call_id: Some(call.id),
+            },
+        ];
+
+        let chatml = ChatTemplateOverride::parse("chatml").unwrap();
+        let result =
+            build_prompt_with_template(&context, "Thanks!", None, Some(&chatml), &[tool.clone()])
+                .unwrap();
+        assert!(result.starts_with("<|im_start|>system\nYou can call the following functions"));
+        assert!(result.contains("\"get_weather\""));
+        assert!(result.contains("<|im_start|>tool\n18C, cloudy<|im_end|>"));
+
+        // Harmony keeps its default system prompt and lists tools separately
+        let harmony = ChatTemplateOverride::parse("harmony").unwrap();
+        let result =
+            build_prompt_with_template(&context, "Thanks!", None, Some(&harmony), &[tool]).unwrap();
+        assert!(result.starts_with("<|start|>system<|message|>You are a helpful AI assistant."));
+        assert!(
+            result.contains("<|start|>developer<|message|>You can call the following functions")
+        );
+        assert!(result.contains("<|start|>tool<|message|>18C, cloudy<|end|>"));
+    }
 }
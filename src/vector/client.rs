@@ -17,12 +17,76 @@ pub enum VectorBackend {
     Real { api_url: String },
 }
 
+/// Similarity metric used to rank search results
+///
+/// Scores are always "higher is more similar"; distances "lower is more similar".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    /// Cosine similarity; independent of vector magnitude
+    #[default]
+    Cosine,
+    /// Raw inner product; equals cosine for normalized vectors
+    DotProduct,
+    /// L2 distance
+    Euclidean,
+}
+
+impl DistanceMetric {
+    /// Computes `(score, distance)` between two vectors
+    ///
+    /// Mismatched lengths score as maximally dissimilar.
+    pub fn compare(&self, a: &[f32], b: &[f32]) -> (f32, f32) {
+        if a.len() != b.len() {
+            return match self {
+                DistanceMetric::Cosine => (0.0, 1.0),
+                DistanceMetric::DotProduct | DistanceMetric::Euclidean => {
+                    (f32::NEG_INFINITY, f32::INFINITY)
+                }
+            };
+        }
+
+        match self {
+            DistanceMetric::Cosine => {
+                let similarity = cosine_similarity(a, b);
+                (similarity, 1.0 - similarity)
+            }
+            DistanceMetric::DotProduct => {
+                let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+                (dot, -dot)
+            }
+            DistanceMetric::Euclidean => {
+                let distance = a
+                    .iter()
+                    .zip(b.iter())
+                    .map(|(x, y)| (x - y) * (x - y))
+                    .sum::<f32>()
+                    .sqrt();
+                (1.0 / (1.0 + distance), distance)
+            }
+        }
+    }
+
+    /// Returns true if a result passes `threshold` under this metric
+    ///
+    /// Cosine and dot-product thresholds are minimum scores; Euclidean
+    /// thresholds are maximum distances.
+    pub fn within_threshold(&self, score: f32, distance: f32, threshold: f32) -> bool {
+        match self {
+            DistanceMetric::Cosine | DistanceMetric::DotProduct => score >= threshold,
+            DistanceMetric::Euclidean => distance <= threshold,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct VectorDBConfig {
     pub backend: VectorBackend,
     pub api_key: Option<String>,
     pub timeout_ms: u64,
     pub max_retries: u32,
+    /// Default metric for searches that don't set `SearchOptions::metric`
+    pub metric: DistanceMetric,
 }
 
 impl Default for VectorDBConfig {
@@ -32,6 +96,7 @@ impl Default for VectorDBConfig {
             api_key: None,
             timeout_ms: 5000,
             max_retries: 3,
+            metric: DistanceMetric::Cosine,
         }
     }
 }
@@ -54,6 +119,9 @@ pub struct SearchOptions {
     pub include_metadata: bool,
     pub score_threshold: Option<f32>,
     pub filter: Option<HashMap<String, FilterValue>>,
+    /// Overrides the client's configured metric for this search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric: Option<DistanceMetric>,
 }
 
 impl Default for SearchOptions {
@@ -68,6 +136,7 @@ impl Default for SearchOptions {
             include_metadata: true,
             score_threshold: None,
             filter: None,
+            metric: None,
         }
     }
 }
@@ -200,25 +269,32 @@ impl MockBackend {
             .ok_or_else(|| VectorError::NotFound(id.to_string()))
     }
 
-    async fn search(&self, query: Vec<f32>, k: usize) -> Result<Vec<SearchResult>, VectorError> {
+    async fn search(
+        &self,
+        query: Vec<f32>,
+        k: usize,
+        metric: DistanceMetric,
+    ) -> Result<Vec<SearchResult>, VectorError> {
         let vectors = self.vectors.read().await;
         let mut results = Vec::new();
 
         for (id, entry) in vectors.iter() {
-            // Simple cosine similarity calculation
-            let similarity = cosine_similarity(&query, &entry.vector);
-            let distance = 1.0 - similarity;
+            let (score, distance) = metric.compare(&query, &entry.vector);
 
             results.push(SearchResult {
                 id: id.clone(),
                 distance,
-                score: similarity,
+                score,
                 metadata: entry.metadata.clone(),
             });
         }
 
         // Sort by distance (ascending)
-        results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+        results.sort_by(|a, b| {
+            a.distance
+                .partial_cmp(&b.distance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
         results.truncate(k);
 
         Ok(results)
@@ -446,15 +522,18 @@ impl VectorDBClient {
     pub async fn search_with_options(
         &self,
         query_vector: Vec<f32>,
-        options: SearchOptions,
+        mut options: SearchOptions,
     ) -> Result<Vec<SearchResult>, VectorError> {
+        let metric = options.metric.unwrap_or(self.config.metric);
+        options.metric = Some(metric);
+
         match &self.config.backend {
             VectorBackend::Mock => {
                 let mut results = self
                     .mock_backend
                     .as_ref()
                     .unwrap()
-                    .search(query_vector, options.k)
+                    .search(query_vector, options.k, metric)
                     .await?;
 
                 // Apply filters
@@ -495,7 +574,9 @@ impl VectorDBClient {
 
                 // Apply score threshold
                 if let Some(threshold) = options.score_threshold {
-                    results.retain(|result| result.score >= threshold);
+                    results.retain(|result| {
+                        metric.within_threshold(result.score, result.distance, threshold)
+                    });
                 }
 
                 Ok(results)
@@ -574,3 +655,120 @@ impl VectorDBClient {
         Ok(ReceiverStream::new(rx))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, vector: Vec<f32>) -> VectorEntry {
+        VectorEntry {
+            id: id.to_string(),
+            vector,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_metric_compare() {
+        let a = [1.0, 0.0];
+        let b = [2.0, 0.0];
+
+        let (score, distance) = DistanceMetric::Cosine.compare(&a, &b);
+        assert!((score - 1.0).abs() < 1e-6);
+        assert!(distance.abs() < 1e-6);
+
+        let (score, distance) = DistanceMetric::DotProduct.compare(&a, &b);
+        assert_eq!(score, 2.0);
+        assert_eq!(distance, -2.0);
+
+        let (score, distance) = DistanceMetric::Euclidean.compare(&a, &b);
+        assert_eq!(distance, 1.0);
+        assert_eq!(score, 0.5);
+    }
+
+    #[test]
+    fn test_metric_threshold_direction() {
+        assert!(DistanceMetric::Cosine.within_threshold(0.9, 0.1, 0.8));
+        assert!(!DistanceMetric::DotProduct.within_threshold(0.5, -0.5, 0.8));
+        assert!(DistanceMetric::Euclidean.within_threshold(0.8, 0.25, 0.3));
+        assert!(!DistanceMetric::Euclidean.within_threshold(0.5, 1.0, 0.3));
+    }
+
+    #[test]
+    fn test_metric_serde() {
+        assert_eq!(
+            serde_json::to_string(&DistanceMetric::DotProduct).unwrap(),
+            "\"dot_product\""
+        );
+        let parsed: SearchOptions = serde_json::from_str(
+            r#"{"k":3,"search_recent":true,"search_historical":true,"hnsw_ef":null,
+                "ivf_n_probe":null,"timeout_ms":null,"include_metadata":true,
+                "score_threshold":null,"filter":null,"metric":"euclidean"}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.metric, Some(DistanceMetric::Euclidean));
+    }
+
+    #[tokio::test]
+    async fn test_mock_search_ranks_by_configured_metric() {
+        let client = VectorDBClient::new(VectorDBConfig {
+            metric: DistanceMetric::DotProduct,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        // Same direction as the query but larger magnitude wins on dot product
+        client
+            .insert_vector(entry("unit", vec![1.0, 0.0]))
+            .await
+            .unwrap();
+        client
+            .insert_vector(entry("long", vec![3.0, 0.5]))
+            .await
+            .unwrap();
+
+        let results = client.search(vec![1.0, 0.0], 2).await.unwrap();
+        assert_eq!(results[0].id, "long");
+
+        let options = SearchOptions {
+            k: 2,
+            metric: Some(DistanceMetric::Cosine),
+            ..Default::default()
+        };
+        let results = client
+            .search_with_options(vec![1.0, 0.0], options)
+            .await
+            .unwrap();
+        assert_eq!(results[0].id, "unit");
+    }
+
+    #[tokio::test]
+    async fn test_mock_euclidean_threshold_is_max_distance() {
+        let client = VectorDBClient::new(VectorDBConfig {
+            metric: DistanceMetric::Euclidean,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        client
+            .insert_vector(entry("near", vec![1.0, 0.1]))
+            .await
+            .unwrap();
+        client
+            .insert_vector(entry("far", vec![5.0, 5.0]))
+            .await
+            .unwrap();
+
+        let options = SearchOptions {
+            k: 10,
+            score_threshold: Some(0.5),
+            ..Default::default()
+        };
+        let results = client
+            .search_with_options(vec![1.0, 0.0], options)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "near");
+    }
+}
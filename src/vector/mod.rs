@@ -10,8 +10,8 @@ pub mod vector_db_client;
 
 // Re-export commonly used types from client module
 pub use client::{
-    FilterOperator, FilterValue, SearchOptions, SearchResult, VectorBackend, VectorDBClient,
-    VectorDBConfig, VectorEntry, VectorError, VectorId, VectorStats,
+    DistanceMetric, FilterOperator, FilterValue, SearchOptions, SearchResult, VectorBackend,
+    VectorDBClient, VectorDBConfig, VectorEntry, VectorError, VectorId, VectorStats,
 };
 
 // Re-export embedding types
@@ -8,6 +8,8 @@ use std::sync::Mutex;
 use std::time::Duration;
 use uuid::Uuid;
 
+use super::client::DistanceMetric;
+
 #[derive(Debug, Clone)]
 pub struct VectorDbConfig {
     pub api_url: String,
@@ -152,6 +154,63 @@ impl VectorDbClient {
         Ok(result)
     }
 
+    /// Deletes every vector whose metadata matches `filter` on the vector DB
+    ///
+    /// `filter` uses the API's MongoDB-style syntax, where plain values match
+    /// by equality. Returns the number of vectors the API reports as deleted.
+    pub async fn delete_by_metadata(&self, filter: &Value) -> Result<u64> {
+        let url = format!("{}/api/v1/vectors/delete", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&json!({ "filter": filter }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow!("Delete by metadata failed: {}", error_text));
+        }
+
+        let result = response.json::<Value>().await.unwrap_or_default();
+        Ok(result
+            .get("deletedCount")
+            .or_else(|| result.get("deleted_count"))
+            .and_then(|count| count.as_u64())
+            .unwrap_or(0))
+    }
+
+    /// Lists vectors on the vector DB whose metadata matches `filter`
+    ///
+    /// Uses the same filter syntax as `delete_by_metadata`. Returns up to
+    /// `limit` `{ "id", "metadata" }` objects.
+    pub async fn query_by_metadata(&self, filter: &Value, limit: usize) -> Result<Vec<Value>> {
+        let url = format!("{}/api/v1/vectors/query", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&json!({ "filter": filter, "limit": limit }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow!("Query by metadata failed: {}", error_text));
+        }
+
+        let mut result = response.json::<Value>().await?;
+        match result.get_mut("results").map(Value::take) {
+            Some(Value::Array(points)) => Ok(points),
+            _ => Ok(Vec::new()),
+        }
+    }
+
     // New methods for E2E workflow tests
     pub async fn insert_vector(
         &self,
@@ -201,36 +260,8 @@ impl VectorDbClient {
         let mut results = Vec::new();
 
         for (id, (stored_vec, metadata)) in storage.iter() {
-            // Check filter if provided
-            if let Some(ref filter_obj) = filter {
-                if let Some(filter_map) = filter_obj.as_object() {
-                    let mut matches = true;
-                    for (key, value) in filter_map {
-                        if let Some(meta_value) = metadata.get(key) {
-                            // Simple equality check for arrays and values
-                            if meta_value != value {
-                                // Special handling for array contains
-                                if let (Some(meta_arr), Some(filter_arr)) =
-                                    (meta_value.as_array(), value.as_array())
-                                {
-                                    if !filter_arr.iter().all(|v| meta_arr.contains(v)) {
-                                        matches = false;
-                                        break;
-                                    }
-                                } else {
-                                    matches = false;
-                                    break;
-                                }
-                            }
-                        } else {
-                            matches = false;
-                            break;
-                        }
-                    }
-                    if !matches {
-                        continue;
-                    }
-                }
+            if !matches_filter(metadata, &filter) {
+                continue;
             }
 
             // Calculate cosine similarity (for normalized vectors, this is just the dot product)
@@ -267,6 +298,78 @@ impl VectorDbClient {
         Ok(results)
     }
 
+    /// Searches stored vectors ranking by `metric`
+    ///
+    /// Unlike `search`, scores are the raw metric value (cosine similarity,
+    /// inner product, or `1 / (1 + l2)`), and each result also carries a
+    /// `distance` so callers can apply `DistanceMetric::within_threshold`.
+    pub async fn search_with_metric(
+        &self,
+        vector: Vec<f32>,
+        k: usize,
+        filter: Option<Value>,
+        metric: DistanceMetric,
+    ) -> Result<Vec<Value>> {
+        let storage = self.mock_storage.lock().unwrap();
+        let mut results = Vec::new();
+
+        for (id, (stored_vec, metadata)) in storage.iter() {
+            if !matches_filter(metadata, &filter) {
+                continue;
+            }
+
+            let (score, distance) = metric.compare(&vector, stored_vec);
+            results.push(json!({
+                "id": id,
+                "metadata": metadata,
+                "score": score,
+                "distance": distance
+            }));
+        }
+
+        // Scores are "higher is more similar" for every metric
+        results.sort_by(|a, b| {
+            let score_a = a["score"].as_f64().unwrap_or(f64::NEG_INFINITY);
+            let score_b = b["score"].as_f64().unwrap_or(f64::NEG_INFINITY);
+            score_b
+                .partial_cmp(&score_a)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        results.truncate(k);
+        Ok(results)
+    }
+
+    /// Deletes stored vectors whose metadata passes `filter` and `predicate`
+    ///
+    /// Returns the ids of the removed vectors.
+    pub async fn delete_where<F>(&self, filter: Option<Value>, predicate: F) -> Result<Vec<String>>
+    where
+        F: Fn(&Value) -> bool,
+    {
+        let removed: Vec<String> = {
+            let mut storage = self.mock_storage.lock().unwrap();
+            let ids: Vec<String> = storage
+                .iter()
+                .filter(|(_, (_, metadata))| {
+                    matches_filter(metadata, &filter) && predicate(metadata)
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            for id in &ids {
+                storage.remove(id);
+            }
+            ids
+        };
+
+        // Also attempt to delete via API if available (ignore errors for mock)
+        for id in &removed {
+            let _ = self.delete_vector(id).await;
+        }
+
+        Ok(removed)
+    }
+
     // Legacy methods (renamed)
     pub async fn insert_vector_legacy(&self, mut vector_data: Value) -> Result<Value> {
         // If no ID is provided, generate one
@@ -332,3 +435,31 @@ impl VectorDbClient {
         Ok(result)
     }
 }
+
+/// Returns true if `metadata` satisfies every key in the optional filter object
+fn matches_filter(metadata: &Value, filter: &Option<Value>) -> bool {
+    if let Some(ref filter_obj) = filter {
+        if let Some(filter_map) = filter_obj.as_object() {
+            for (key, value) in filter_map {
+                if let Some(meta_value) = metadata.get(key) {
+                    // Simple equality check for arrays and values
+                    if meta_value != value {
+                        // Special handling for array contains
+                        if let (Some(meta_arr), Some(filter_arr)) =
+                            (meta_value.as_array(), value.as_array())
+                        {
+                            if !filter_arr.iter().all(|v| meta_arr.contains(v)) {
+                                return false;
+                            }
+                        } else {
+                            return false;
+                        }
+                    }
+                } else {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
@@ -44,6 +44,8 @@ pub struct FlorenceDecoder {
     /// Special token IDs
     bos_token_id: u32,
     eos_token_id: u32,
+    /// Token IDs of `<loc_0>` and `<loc_999>`, if the vocabulary has them
+    location_token_range: Option<(u32, u32)>,
     /// Whether model is loaded and ready
     is_ready: bool,
 }
@@ -152,9 +154,13 @@ impl FlorenceDecoder {
             .or_else(|| tokenizer.token_to_id("[SEP]"))
             .unwrap_or(2);
 
+        let location_token_range = tokenizer
+            .token_to_id("<loc_0>")
+            .zip(tokenizer.token_to_id("<loc_999>"));
+
         debug!(
-            "Special tokens - BOS: {}, EOS: {}",
-            bos_token_id, eos_token_id
+            "Special tokens - BOS: {}, EOS: {}, locations: {:?}",
+            bos_token_id, eos_token_id, location_token_range
         );
 
         info!("✅ Florence decoder loaded successfully (CPU-only)");
@@ -167,6 +173,7 @@ impl FlorenceDecoder {
             vocab_size,
             bos_token_id,
             eos_token_id,
+            location_token_range,
             is_ready: true,
         })
     }
@@ -207,6 +214,64 @@ impl FlorenceDecoder {
     /// 3. Stop at EOS token or max tokens
     /// 4. Decode tokens to text
     pub fn generate(&self, image_embeddings: &Array2<f32>, prompt: Option<&str>) -> Result<String> {
+        let tokens = self.generate_tokens(image_embeddings, prompt)?;
+
+        // Decode tokens to text
+        let output_text = self
+            .tokenizer
+            .decode(&tokens, true)
+            .map_err(|e| anyhow::anyhow!("Decoding failed: {}", e))?;
+
+        // Clean up the output - remove special tokens and task tokens
+        let cleaned = output_text
+            .trim()
+            .replace("<s>", "")
+            .replace("</s>", "")
+            .replace("<pad>", "")
+            // Remove Florence-2 task tokens
+            .replace("<cap>", "")
+            .replace("</cap>", "")
+            .replace("<dcap>", "")
+            .replace("</dcap>", "")
+            .replace("<ncap>", "")
+            .replace("</ncap>", "")
+            .trim()
+            .to_string();
+
+        debug!("Generated {} tokens: '{}'", tokens.len(), cleaned);
+
+        Ok(cleaned)
+    }
+
+    /// Generate text keeping Florence-2 location tokens (`<loc_N>`)
+    ///
+    /// Detection tasks encode boxes as location tokens, which `generate`
+    /// strips along with the other special tokens.
+    pub fn generate_with_locations(
+        &self,
+        image_embeddings: &Array2<f32>,
+        prompt: Option<&str>,
+    ) -> Result<String> {
+        let tokens = self.generate_tokens(image_embeddings, prompt)?;
+        let output_text = self
+            .tokenizer
+            .decode(&tokens, false)
+            .map_err(|e| anyhow::anyhow!("Decoding failed: {}", e))?;
+
+        Ok(output_text
+            .replace("<s>", "")
+            .replace("</s>", "")
+            .replace("<pad>", "")
+            .trim()
+            .to_string())
+    }
+
+    /// Run the autoregressive loop, returning prompt and generated token IDs
+    fn generate_tokens(
+        &self,
+        image_embeddings: &Array2<f32>,
+        prompt: Option<&str>,
+    ) -> Result<Vec<u32>> {
         // Initialize input tokens with prompt
         // NOTE: Task tokens (<cap>, <dcap>) produce "unanswerable" with this ONNX export
         // Natural language prompts like "A photo of" work correctly
@@ -339,31 +404,7 @@ impl FlorenceDecoder {
 
         debug!("Generation complete: {} total tokens", tokens.len());
 
-        // Decode tokens to text
-        let output_text = self
-            .tokenizer
-            .decode(&tokens, true)
-            .map_err(|e| anyhow::anyhow!("Decoding failed: {}", e))?;
-
-        // Clean up the output - remove special tokens and task tokens
-        let cleaned = output_text
-            .trim()
-            .replace("<s>", "")
-            .replace("</s>", "")
-            .replace("<pad>", "")
-            // Remove Florence-2 task tokens
-            .replace("<cap>", "")
-            .replace("</cap>", "")
-            .replace("<dcap>", "")
-            .replace("</dcap>", "")
-            .replace("<ncap>", "")
-            .replace("</ncap>", "")
-            .trim()
-            .to_string();
-
-        debug!("Generated {} tokens: '{}'", tokens.len(), cleaned);
-
-        Ok(cleaned)
+        Ok(tokens)
     }
 
     /// Convert token IDs to embeddings using embed_tokens model
@@ -476,15 +517,24 @@ impl FlorenceDecoder {
         Ok(logits)
     }
 
+    /// Whether `token` is a `<loc_N>` detection coordinate
+    fn is_location_token(&self, token: u32) -> bool {
+        self.location_token_range
+            .is_some_and(|(first, last)| (first..=last).contains(&token))
+    }
+
     /// Find the index of the maximum value (greedy decoding)
     /// Masks out BOS token and tokens already in sequence to prevent loops
     fn argmax(&self, logits: &[f32], existing_tokens: &[u32]) -> Result<u32> {
-        // Create a set of tokens to mask (BOS + recent tokens to prevent repetition)
+        // Create a set of tokens to mask (BOS + recent tokens to prevent repetition).
+        // Location tokens legitimately repeat (a box at <loc_0><loc_0>), so they
+        // are never masked.
         let mask_tokens: std::collections::HashSet<u32> = existing_tokens
             .iter()
             .rev()
             .take(5) // Mask last 5 tokens to prevent short loops
             .copied()
+            .filter(|&token| !self.is_location_token(token))
             .chain(std::iter::once(self.bos_token_id))
             .collect();
 
@@ -17,4 +17,6 @@ pub mod preprocessing;
 
 pub use decoder::FlorenceDecoder;
 pub use encoder::FlorenceEncoder;
-pub use model::{DescriptionResult, DetectedObject, FlorenceModel, ImageAnalysis};
+pub use model::{
+    parse_detections, DescriptionResult, DetectedObject, FlorenceModel, FlorenceTask, ImageAnalysis,
+};
@@ -5,9 +5,13 @@
 //! This module provides the complete Florence-2 pipeline combining:
 //! - Vision encoder (image feature extraction)
 //! - Language decoder (text generation)
+//!
+//! A `FlorenceTask` selects what the decoder is asked for: a free-form
+//! description, a caption, object detection or OCR.
 
 use anyhow::{Context, Result};
 use image::{DynamicImage, GenericImageView};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::time::Instant;
 use tracing::{debug, info, warn};
@@ -18,6 +22,109 @@ use super::preprocessing::preprocess_for_florence;
 
 use crate::vision::ocr::BoundingBox;
 
+/// Florence-2 task, named by its task token
+///
+/// This ONNX export answers "unanswerable" to bare task tokens, so each task
+/// is sent as the natural-language prompt the Florence-2 processor expands
+/// its token into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FlorenceTask {
+    /// Free-form description steered by `DetailLevel` or a custom prompt
+    #[default]
+    #[serde(rename = "describe")]
+    Describe,
+    #[serde(rename = "<CAPTION>", alias = "caption")]
+    Caption,
+    #[serde(rename = "<DETAILED_CAPTION>", alias = "detailed_caption")]
+    DetailedCaption,
+    #[serde(rename = "<MORE_DETAILED_CAPTION>", alias = "more_detailed_caption")]
+    MoreDetailedCaption,
+    /// Object detection: labelled boxes in `DescriptionResult::objects`
+    #[serde(rename = "<OD>", alias = "od")]
+    ObjectDetection,
+    /// Text reading: the text in `ImageAnalysis::text`
+    #[serde(rename = "<OCR>", alias = "ocr")]
+    Ocr,
+}
+
+impl FlorenceTask {
+    /// Prompt the Florence-2 processor substitutes for this task's token
+    pub fn prompt(&self) -> Option<&'static str> {
+        match self {
+            Self::Describe => None,
+            Self::Caption => Some("What does the image describe?"),
+            Self::DetailedCaption => Some("Describe in detail what is shown in the image."),
+            Self::MoreDetailedCaption => {
+                Some("Describe with a paragraph what is shown in the image.")
+            }
+            Self::ObjectDetection => Some("Locate the objects with category name in the image."),
+            Self::Ocr => Some("What is the text in the image?"),
+        }
+    }
+}
+
+/// Number of `<loc_N>` bins Florence-2 quantizes each image axis into
+const LOCATION_BINS: f32 = 1000.0;
+
+/// Parse object detection output (`label<loc_x1><loc_y1><loc_x2><loc_y2>...`)
+/// into objects with boxes in image pixels
+///
+/// A label may be followed by several boxes. Florence-2 does not score its
+/// detections, so every object has confidence 1.0.
+pub fn parse_detections(output: &str, width: u32, height: u32) -> Vec<DetectedObject> {
+    let to_pixels =
+        |bin: u32, size: u32| (((bin as f32 + 0.5) / LOCATION_BINS * size as f32) as u32).min(size);
+
+    let mut objects = Vec::new();
+    let mut label = String::new();
+    let mut label_has_boxes = false;
+    let mut coords: Vec<u32> = Vec::with_capacity(4);
+    let mut rest = output;
+
+    while let Some(c) = rest.chars().next() {
+        let location = rest.strip_prefix("<loc_").and_then(|after| {
+            let end = after.find('>')?;
+            Some((after[..end].parse::<u32>().ok()?, &after[end + 1..]))
+        });
+        if let Some((bin, after)) = location {
+            coords.push(bin);
+            if coords.len() == 4 {
+                let (x1, x2) = (coords[0].min(coords[2]), coords[0].max(coords[2]));
+                let (y1, y2) = (coords[1].min(coords[3]), coords[1].max(coords[3]));
+                let label = label.trim();
+                if !label.is_empty() {
+                    let (x, y) = (to_pixels(x1, width), to_pixels(y1, height));
+                    objects.push(DetectedObject {
+                        label: label.to_string(),
+                        confidence: 1.0,
+                        bounding_box: Some(BoundingBox {
+                            x,
+                            y,
+                            width: to_pixels(x2, width).saturating_sub(x).max(1),
+                            height: to_pixels(y2, height).saturating_sub(y).max(1),
+                        }),
+                    });
+                }
+                coords.clear();
+                label_has_boxes = true;
+            }
+            rest = after;
+            continue;
+        }
+
+        // Text after a box starts the next label; stray coordinates are dropped
+        if label_has_boxes || !coords.is_empty() {
+            label.clear();
+            coords.clear();
+            label_has_boxes = false;
+        }
+        label.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    objects
+}
+
 /// A detected object in the image
 #[derive(Debug, Clone)]
 pub struct DetectedObject {
@@ -40,6 +147,10 @@ pub struct ImageAnalysis {
     pub dominant_colors: Vec<String>,
     /// Scene type (indoor, outdoor, etc.)
     pub scene_type: Option<String>,
+    /// Task that produced this analysis
+    pub task: FlorenceTask,
+    /// Text read from the image (`FlorenceTask::Ocr` only)
+    pub text: Option<String>,
 }
 
 impl ImageAnalysis {
@@ -51,6 +162,8 @@ impl ImageAnalysis {
             height,
             dominant_colors: Vec::new(),
             scene_type: None,
+            task: FlorenceTask::Describe,
+            text: None,
         }
     }
 }
@@ -241,10 +354,23 @@ impl FlorenceModel {
 
     /// Describe an image
     ///
+    /// Equivalent to `run_task` with `FlorenceTask::Describe`.
+    pub fn describe(
+        &self,
+        image: &DynamicImage,
+        detail: &str,
+        prompt: Option<&str>,
+    ) -> Result<DescriptionResult> {
+        self.run_task(image, FlorenceTask::Describe, detail, prompt)
+    }
+
+    /// Run a Florence-2 task on an image
+    ///
     /// # Arguments
-    /// * `image` - The image to describe
-    /// * `detail` - Detail level: "brief", "detailed", or "comprehensive"
-    /// * `prompt` - Optional custom prompt (overrides detail level prompt)
+    /// * `image` - The image to analyze
+    /// * `task` - Task to run; its result shape is described on `FlorenceTask`
+    /// * `detail` - Detail level for `Describe`: "brief", "detailed", or "comprehensive"
+    /// * `prompt` - Optional custom prompt for `Describe` (overrides detail level prompt)
     ///
     /// # Returns
     /// - `Result<DescriptionResult>`: Generated description with metadata
@@ -253,17 +379,21 @@ impl FlorenceModel {
     /// 1. Preprocess image for encoder (resize, normalize)
     /// 2. Extract visual features with encoder
     /// 3. Generate text with decoder
-    /// 4. Return description with timing
-    pub fn describe(
+    /// 4. Shape the output for the task, with timing
+    pub fn run_task(
         &self,
         image: &DynamicImage,
+        task: FlorenceTask,
         detail: &str,
         prompt: Option<&str>,
     ) -> Result<DescriptionResult> {
         let start = Instant::now();
 
         let detail_level = DetailLevel::from_str(detail);
-        info!("Describing image with detail level: {:?}", detail_level);
+        info!(
+            "Running Florence task {:?} with detail level: {:?}",
+            task, detail_level
+        );
 
         // 1. Preprocess image
         info!(
@@ -287,15 +417,51 @@ impl FlorenceModel {
         );
 
         // 3. Determine prompt
-        let generation_prompt = prompt.unwrap_or_else(|| detail_level.prompt_prefix());
+        let generation_prompt = task
+            .prompt()
+            .unwrap_or_else(|| prompt.unwrap_or_else(|| detail_level.prompt_prefix()));
         info!("Step 3: Using prompt: '{}'", generation_prompt);
 
         // 4. Generate description
         info!("Step 4: Generating text from embeddings...");
-        let description = self
-            .decoder
-            .generate(&embeddings, Some(generation_prompt))
-            .context("Failed to generate description")?;
+        let generated = if task == FlorenceTask::ObjectDetection {
+            self.decoder
+                .generate_with_locations(&embeddings, Some(generation_prompt))
+        } else {
+            self.decoder.generate(&embeddings, Some(generation_prompt))
+        }
+        .context("Failed to generate description")?;
+
+        // Describe continues its prompt prefix; task answers drop the question
+        let generated = match task.prompt() {
+            Some(task_prompt) => generated
+                .strip_prefix(task_prompt)
+                .unwrap_or(&generated)
+                .trim()
+                .to_string(),
+            None => generated,
+        };
+
+        // 5. Shape the output for the task
+        let mut analysis = ImageAnalysis::from_image(image);
+        analysis.task = task;
+        let (description, objects) = match task {
+            FlorenceTask::ObjectDetection => {
+                let objects = parse_detections(&generated, analysis.width, analysis.height);
+                let mut labels: Vec<&str> = Vec::new();
+                for object in &objects {
+                    if !labels.contains(&object.label.as_str()) {
+                        labels.push(&object.label);
+                    }
+                }
+                (labels.join(", "), objects)
+            }
+            FlorenceTask::Ocr => {
+                analysis.text = Some(generated.clone());
+                (generated, Vec::new())
+            }
+            _ => (generated, Vec::new()),
+        };
         info!(
             "Generated text: '{}' ({} chars)",
             if description.len() > 50 {
@@ -308,18 +474,16 @@ impl FlorenceModel {
 
         let processing_time_ms = start.elapsed().as_millis() as u64;
 
-        // 5. Create result
-        let analysis = ImageAnalysis::from_image(image);
-
         info!(
-            "Florence complete: {} chars, {}ms",
+            "Florence complete: {} chars, {} objects, {}ms",
             description.len(),
+            objects.len(),
             processing_time_ms
         );
 
         Ok(DescriptionResult {
             description,
-            objects: Vec::new(), // Object detection not implemented yet
+            objects,
             analysis,
             processing_time_ms,
         })
@@ -381,6 +545,8 @@ mod tests {
             height: 1080,
             dominant_colors: vec!["#FF0000".to_string()],
             scene_type: Some("indoor".to_string()),
+            task: FlorenceTask::Describe,
+            text: None,
         };
         assert_eq!(analysis.width, 1920);
         assert_eq!(analysis.height, 1080);
@@ -404,6 +570,8 @@ mod tests {
                 height: 600,
                 dominant_colors: vec![],
                 scene_type: None,
+                task: FlorenceTask::Describe,
+                text: None,
             },
             processing_time_ms: 4500,
         };
@@ -420,6 +588,41 @@ mod tests {
         assert_eq!(result.processing_time_ms, 100);
     }
 
+    #[test]
+    fn test_florence_task_serde() {
+        let task: FlorenceTask = serde_json::from_str(r#""<OD>""#).unwrap();
+        assert_eq!(task, FlorenceTask::ObjectDetection);
+        let task: FlorenceTask = serde_json::from_str(r#""ocr""#).unwrap();
+        assert_eq!(task, FlorenceTask::Ocr);
+        assert_eq!(
+            serde_json::to_string(&FlorenceTask::DetailedCaption).unwrap(),
+            r#""<DETAILED_CAPTION>""#
+        );
+        assert_eq!(FlorenceTask::default(), FlorenceTask::Describe);
+        assert!(FlorenceTask::Describe.prompt().is_none());
+    }
+
+    #[test]
+    fn test_parse_detections() {
+        let output = "car<loc_0><loc_0><loc_499><loc_999>\
+                      person<loc_500><loc_100><loc_999><loc_599><loc_10><loc_10><loc_20><loc_20>";
+        let objects = parse_detections(output, 1000, 500);
+
+        assert_eq!(objects.len(), 3);
+        assert_eq!(objects[0].label, "car");
+        let car = objects[0].bounding_box.as_ref().unwrap();
+        assert_eq!((car.x, car.y, car.width, car.height), (0, 0, 499, 499));
+
+        assert_eq!(objects[1].label, "person");
+        let person = objects[1].bounding_box.as_ref().unwrap();
+        assert_eq!((person.x, person.y), (500, 50));
+        assert_eq!(objects[2].label, "person");
+
+        // Incomplete boxes and unlabelled boxes are dropped
+        assert!(parse_detections("<loc_1><loc_2><loc_3><loc_4>dog<loc_5>", 100, 100).is_empty());
+        assert!(parse_detections("", 100, 100).is_empty());
+    }
+
     #[test]
     fn test_detail_level_from_str() {
         assert_eq!(DetailLevel::from_str("brief"), DetailLevel::Brief);
@@ -3,17 +3,94 @@
 //! Image loading and utility functions for vision processing
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
-use image::{DynamicImage, ImageFormat};
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use std::io::Cursor;
+use std::sync::OnceLock;
 use thiserror::Error;
+use tracing::{debug, info};
 
 /// Maximum image size (10MB)
 const MAX_IMAGE_SIZE: usize = 10 * 1024 * 1024;
 
+pub const VISION_MAX_IMAGE_DIMENSION_ENV: &str = "VISION_MAX_IMAGE_DIMENSION";
+pub const VISION_MAX_IMAGE_PIXELS_ENV: &str = "VISION_MAX_IMAGE_PIXELS";
+pub const VISION_HARD_MAX_IMAGE_PIXELS_ENV: &str = "VISION_HARD_MAX_IMAGE_PIXELS";
+
+/// Pixel limits applied to images before vision inference
+///
+/// Images beyond `max_dimension` or `max_pixels` are downscaled to fit,
+/// preserving aspect ratio; images beyond `hard_max_pixels` are rejected
+/// from their header, before any pixels are decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageLimits {
+    /// Longest allowed side in pixels
+    pub max_dimension: u32,
+    /// Most pixels (width x height) passed to the models
+    pub max_pixels: u64,
+    /// Most pixels accepted at all
+    pub hard_max_pixels: u64,
+}
+
+impl Default for ImageLimits {
+    fn default() -> Self {
+        Self {
+            // Keeps a 300 DPI A4 scan (2480x3508) at full resolution
+            max_dimension: 4096,
+            max_pixels: 16_000_000,
+            hard_max_pixels: 100_000_000,
+        }
+    }
+}
+
+impl ImageLimits {
+    /// Defaults overridden by `VISION_MAX_IMAGE_DIMENSION`,
+    /// `VISION_MAX_IMAGE_PIXELS` and `VISION_HARD_MAX_IMAGE_PIXELS`
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|&n| n > 0)
+        };
+        let defaults = Self::default();
+        Self {
+            max_dimension: var(VISION_MAX_IMAGE_DIMENSION_ENV)
+                .map(|n| n.min(u32::MAX as u64) as u32)
+                .unwrap_or(defaults.max_dimension),
+            max_pixels: var(VISION_MAX_IMAGE_PIXELS_ENV).unwrap_or(defaults.max_pixels),
+            hard_max_pixels: var(VISION_HARD_MAX_IMAGE_PIXELS_ENV)
+                .unwrap_or(defaults.hard_max_pixels),
+        }
+    }
+
+    /// Limits read from the environment on first use
+    pub fn global() -> &'static Self {
+        static LIMITS: OnceLock<ImageLimits> = OnceLock::new();
+        LIMITS.get_or_init(Self::from_env)
+    }
+
+    /// Factor (at most 1.0) that fits `width` x `height` within the soft limits
+    pub fn scale_factor(&self, width: u32, height: u32) -> f64 {
+        let longest = width.max(height).max(1) as f64;
+        let pixels = (width as f64 * height as f64).max(1.0);
+        let by_dimension = self.max_dimension as f64 / longest;
+        let by_pixels = (self.max_pixels as f64 / pixels).sqrt();
+        by_dimension.min(by_pixels).min(1.0)
+    }
+}
+
 /// Custom error types for image processing
 #[derive(Debug, Error)]
 pub enum ImageError {
-    #[error("Image data is too large: {0} bytes (max: {1} bytes)")]
-    TooLarge(usize, usize),
+    #[error("Image is too large: {size} {unit} (max: {max} {unit})")]
+    TooLarge {
+        size: u64,
+        max: u64,
+        /// "bytes" for encoded data, "pixels" for decoded dimensions
+        unit: &'static str,
+    },
 
     #[error("Invalid base64 encoding: {0}")]
     InvalidBase64(#[from] base64::DecodeError),
@@ -34,17 +111,34 @@ pub enum ImageError {
 /// Image information extracted during loading
 #[derive(Debug, Clone)]
 pub struct ImageInfo {
-    /// Width in pixels
+    /// Width in pixels of the decoded (possibly downscaled) image
     pub width: u32,
-    /// Height in pixels
+    /// Height in pixels of the decoded (possibly downscaled) image
     pub height: u32,
     /// Detected format
     pub format: ImageFormat,
     /// Size in bytes
     pub size_bytes: usize,
+    /// Width in pixels of the submitted image, after EXIF orientation
+    pub original_width: u32,
+    /// Height in pixels of the submitted image, after EXIF orientation
+    pub original_height: u32,
+    /// Factor the image was downscaled by (1.0 if it was not)
+    pub scale_factor: f32,
+}
+
+impl ImageInfo {
+    /// Map a coordinate or length in the decoded image back to the
+    /// submitted image
+    pub fn to_original(&self, value: u32) -> u32 {
+        if self.scale_factor >= 1.0 {
+            return value;
+        }
+        (value as f32 / self.scale_factor).round() as u32
+    }
 }
 
-/// Decode a base64-encoded image
+/// Decode a base64-encoded image, applying `ImageLimits::global()`
 ///
 /// # Arguments
 /// * `base64_str` - Base64 encoded image data
@@ -59,6 +153,14 @@ pub struct ImageInfo {
 /// println!("Image size: {}x{}", info.width, info.height);
 /// ```
 pub fn decode_base64_image(base64_str: &str) -> Result<(DynamicImage, ImageInfo), ImageError> {
+    decode_base64_image_with_limits(base64_str, ImageLimits::global())
+}
+
+/// Decode a base64-encoded image, downscaling or rejecting it per `limits`
+pub fn decode_base64_image_with_limits(
+    base64_str: &str,
+    limits: &ImageLimits,
+) -> Result<(DynamicImage, ImageInfo), ImageError> {
     // Handle empty input
     if base64_str.is_empty() {
         return Err(ImageError::EmptyData);
@@ -78,33 +180,11 @@ pub fn decode_base64_image(base64_str: &str) -> Result<(DynamicImage, ImageInfo)
     // Decode base64
     let bytes = STANDARD.decode(base64_data)?;
 
-    // Validate size
-    if bytes.len() > MAX_IMAGE_SIZE {
-        return Err(ImageError::TooLarge(bytes.len(), MAX_IMAGE_SIZE));
-    }
-
-    if bytes.is_empty() {
-        return Err(ImageError::EmptyData);
-    }
-
-    // Detect format from magic bytes
-    let format = detect_format(&bytes)?;
-
-    // Load image
-    let img = image::load_from_memory_with_format(&bytes, format)
-        .map_err(|e| ImageError::DecodeFailed(e.to_string()))?;
-
-    let info = ImageInfo {
-        width: img.width(),
-        height: img.height(),
-        format,
-        size_bytes: bytes.len(),
-    };
-
-    Ok((img, info))
+    decode_image_bytes_with_limits(&bytes, limits)
 }
 
-/// Decode raw image bytes (for multipart uploads)
+/// Decode raw image bytes (for multipart uploads), applying
+/// `ImageLimits::global()`
 ///
 /// # Arguments
 /// * `bytes` - Raw image bytes
@@ -113,9 +193,24 @@ pub fn decode_base64_image(base64_str: &str) -> Result<(DynamicImage, ImageInfo)
 /// * `Ok((DynamicImage, ImageInfo))` - The decoded image and metadata
 /// * `Err(ImageError)` - If decoding fails
 pub fn decode_image_bytes(bytes: &[u8]) -> Result<(DynamicImage, ImageInfo), ImageError> {
+    decode_image_bytes_with_limits(bytes, ImageLimits::global())
+}
+
+/// Decode raw image bytes, downscaling or rejecting the image per `limits`
+///
+/// The EXIF orientation tag, if any, is applied so the returned image is
+/// upright; `ImageInfo` dimensions describe the upright image.
+pub fn decode_image_bytes_with_limits(
+    bytes: &[u8],
+    limits: &ImageLimits,
+) -> Result<(DynamicImage, ImageInfo), ImageError> {
     // Validate size
     if bytes.len() > MAX_IMAGE_SIZE {
-        return Err(ImageError::TooLarge(bytes.len(), MAX_IMAGE_SIZE));
+        return Err(ImageError::TooLarge {
+            size: bytes.len() as u64,
+            max: MAX_IMAGE_SIZE as u64,
+            unit: "bytes",
+        });
     }
 
     if bytes.is_empty() {
@@ -125,15 +220,60 @@ pub fn decode_image_bytes(bytes: &[u8]) -> Result<(DynamicImage, ImageInfo), Ima
     // Detect format from magic bytes
     let format = detect_format(bytes)?;
 
-    // Load image
-    let img = image::load_from_memory_with_format(bytes, format)
+    // Reject oversized images from the header, before allocating pixels
+    let mut decoder = ImageReader::with_format(Cursor::new(bytes), format)
+        .into_decoder()
         .map_err(|e| ImageError::DecodeFailed(e.to_string()))?;
+    let (header_width, header_height) = decoder.dimensions();
+    let pixels = header_width as u64 * header_height as u64;
+    if pixels > limits.hard_max_pixels {
+        return Err(ImageError::TooLarge {
+            size: pixels,
+            max: limits.hard_max_pixels,
+            unit: "pixels",
+        });
+    }
+
+    // Missing or unreadable EXIF means the image is already upright
+    let orientation = decoder.orientation().unwrap_or_else(|e| {
+        debug!("Ignoring invalid EXIF orientation: {}", e);
+        Orientation::NoTransforms
+    });
+
+    // Load image
+    let mut img =
+        DynamicImage::from_decoder(decoder).map_err(|e| ImageError::DecodeFailed(e.to_string()))?;
+
+    if orientation != Orientation::NoTransforms {
+        debug!("Applying EXIF orientation {:?}", orientation);
+        img.apply_orientation(orientation);
+    }
+    let (original_width, original_height) = (img.width(), img.height());
+
+    // Downscale to the soft limits
+    let scale = limits.scale_factor(img.width(), img.height());
+    if scale < 1.0 {
+        let width = ((img.width() as f64 * scale).round() as u32).max(1);
+        let height = ((img.height() as f64 * scale).round() as u32).max(1);
+        info!(
+            "Downscaling image {}x{} -> {}x{} (scale factor {:.3})",
+            img.width(),
+            img.height(),
+            width,
+            height,
+            scale
+        );
+        img = img.resize_exact(width, height, FilterType::Triangle);
+    }
 
     let info = ImageInfo {
         width: img.width(),
         height: img.height(),
         format,
         size_bytes: bytes.len(),
+        original_width,
+        original_height,
+        scale_factor: scale as f32,
     };
 
     Ok((img, info))
@@ -390,6 +530,126 @@ mod tests {
         let large_bytes = vec![0u8; MAX_IMAGE_SIZE + 1];
         let result = decode_image_bytes(&large_bytes);
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), ImageError::TooLarge(_, _)));
+        assert!(matches!(
+            result.unwrap_err(),
+            ImageError::TooLarge { unit: "bytes", .. }
+        ));
+    }
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::new_rgb8(width, height)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    /// CRC-32 as used by PNG chunks
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    /// A PNG with an eXIf chunk carrying the given orientation tag
+    fn png_with_orientation(width: u32, height: u32, orientation: u16) -> Vec<u8> {
+        let png = png_bytes(width, height);
+
+        // Little-endian TIFF header with a single-entry IFD: Orientation (0x0112), SHORT
+        let mut exif = vec![b'I', b'I', 0x2A, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00];
+        exif.extend_from_slice(&[0x12, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00]);
+        exif.extend_from_slice(&orientation.to_le_bytes());
+        exif.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        let mut chunk = (exif.len() as u32).to_be_bytes().to_vec();
+        let mut body = b"eXIf".to_vec();
+        body.extend_from_slice(&exif);
+        chunk.extend_from_slice(&body);
+        chunk.extend_from_slice(&crc32(&body).to_be_bytes());
+
+        // Insert after the signature (8 bytes) and IHDR chunk (25 bytes)
+        let mut bytes = png[..33].to_vec();
+        bytes.extend_from_slice(&chunk);
+        bytes.extend_from_slice(&png[33..]);
+        bytes
+    }
+
+    #[test]
+    fn test_exif_orientation_is_applied() {
+        let limits = ImageLimits::default();
+
+        // Orientation 6: rotate 90 degrees clockwise, swapping width and height
+        let bytes = png_with_orientation(40, 20, 6);
+        let (img, info) = decode_image_bytes_with_limits(&bytes, &limits).unwrap();
+        assert_eq!((img.width(), img.height()), (20, 40));
+        assert_eq!((info.width, info.height), (20, 40));
+        assert_eq!((info.original_width, info.original_height), (20, 40));
+
+        // Orientation 3 (rotate 180) keeps dimensions
+        let bytes = png_with_orientation(40, 20, 3);
+        let (img, _) = decode_image_bytes_with_limits(&bytes, &limits).unwrap();
+        assert_eq!((img.width(), img.height()), (40, 20));
+
+        // An out-of-range tag is ignored
+        let bytes = png_with_orientation(40, 20, 42);
+        let (img, _) = decode_image_bytes_with_limits(&bytes, &limits).unwrap();
+        assert_eq!((img.width(), img.height()), (40, 20));
+    }
+
+    #[test]
+    fn test_oversized_image_is_downscaled() {
+        let limits = ImageLimits {
+            max_dimension: 100,
+            max_pixels: 1_000_000,
+            hard_max_pixels: 1_000_000,
+        };
+        let (img, info) = decode_image_bytes_with_limits(&png_bytes(400, 200), &limits).unwrap();
+
+        assert_eq!((img.width(), img.height()), (100, 50));
+        assert_eq!((info.width, info.height), (100, 50));
+        assert_eq!((info.original_width, info.original_height), (400, 200));
+        assert!((info.scale_factor - 0.25).abs() < 1e-6);
+        assert_eq!(info.to_original(25), 100);
+
+        // The pixel budget also applies: 200x200 into 10,000 pixels is 100x100
+        let limits = ImageLimits {
+            max_dimension: 1000,
+            max_pixels: 10_000,
+            hard_max_pixels: 1_000_000,
+        };
+        let (img, _) = decode_image_bytes_with_limits(&png_bytes(200, 200), &limits).unwrap();
+        assert_eq!((img.width(), img.height()), (100, 100));
+    }
+
+    #[test]
+    fn test_image_over_hard_limit_is_rejected() {
+        let limits = ImageLimits {
+            max_dimension: 100,
+            max_pixels: 10_000,
+            hard_max_pixels: 20_000,
+        };
+        let result = decode_image_bytes_with_limits(&png_bytes(200, 101), &limits);
+        assert!(matches!(
+            result.unwrap_err(),
+            ImageError::TooLarge {
+                size: 20_200,
+                max: 20_000,
+                unit: "pixels"
+            }
+        ));
+
+        // Images within the soft limits are untouched
+        let (_, info) = decode_image_bytes_with_limits(&png_bytes(50, 50), &limits).unwrap();
+        assert_eq!(info.scale_factor, 1.0);
+        assert_eq!(info.to_original(7), 7);
     }
 }
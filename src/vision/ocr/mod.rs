@@ -9,12 +9,15 @@
 //! - `recognition` - Text recognition from detected regions
 //! - `preprocessing` - Image preprocessing for models
 //! - `model` - Combined OCR pipeline
+//! - `script` - OCR languages and script detection
 
 pub mod detection;
 pub mod model;
 pub mod preprocessing;
 pub mod recognition;
+pub mod script;
 
 pub use detection::{OcrDetectionModel, TextBox};
-pub use model::{BoundingBox, OcrResult, PaddleOcrModel, TextRegion};
+pub use model::{BoundingBox, OcrResult, PaddleOcrModel, PageOcrResult, PageOcrStream, TextRegion};
 pub use recognition::{OcrRecognitionModel, RecognizedText};
+pub use script::{detect_script, OcrLanguage};
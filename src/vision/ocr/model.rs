@@ -5,19 +5,32 @@
 //! This module provides the complete OCR pipeline combining:
 //! - Text detection (finding text regions in images)
 //! - Text recognition (reading text from detected regions)
+//!
+//! Besides the default recognizer in the model directory, language-specific
+//! recognizers are loaded from `<model_dir>/<lang>/rec_model.onnx` and
+//! `<lang>/dict.txt` for each of `en`, `zh`, `ja` and `ko` that is present.
 
 use anyhow::{Context, Result};
 use image::{DynamicImage, GenericImageView};
+use ndarray::Array4;
+use std::collections::HashMap;
 use std::error::Error;
 use std::path::Path;
 use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tracing::{debug, info, warn};
 
 use super::detection::{OcrDetectionModel, TextBox};
 use super::preprocessing::{
     preprocess_for_detection, preprocess_for_recognition, PreprocessInfo, OCR_INPUT_SIZE,
 };
-use super::recognition::OcrRecognitionModel;
+use super::recognition::{OcrRecognitionModel, RecognizedText};
+use super::script::{detect_script, OcrLanguage};
+use crate::vision::image_utils::ImageInfo;
+
+/// Pages buffered ahead of a slow consumer in `recognize_pages`
+const PAGE_CHANNEL_CAPACITY: usize = 2;
 
 /// Bounding box for detected text (in original image coordinates)
 #[derive(Debug, Clone)]
@@ -48,10 +61,13 @@ impl From<&TextBox> for BoundingBox {
 pub struct TextRegion {
     /// Extracted text content
     pub text: String,
-    /// Confidence score (0.0-1.0)
+    /// Recognition confidence score (0.0-1.0)
     pub confidence: f32,
     /// Bounding box location (in original image coordinates)
     pub bounding_box: BoundingBox,
+    /// Language of the text, detected from its script or else the
+    /// language of the recognizer that read it
+    pub language: OcrLanguage,
 }
 
 /// Result of OCR processing
@@ -77,8 +93,64 @@ impl OcrResult {
             processing_time_ms,
         }
     }
+
+    /// Map region boxes from a downscaled image back to the submitted image
+    pub fn scale_to_original(&mut self, info: &ImageInfo) {
+        for region in &mut self.regions {
+            let bbox = &mut region.bounding_box;
+            bbox.x = info.to_original(bbox.x);
+            bbox.y = info.to_original(bbox.y);
+            bbox.width = info.to_original(bbox.width);
+            bbox.height = info.to_original(bbox.height);
+        }
+    }
+
+    /// Drop regions whose recognition confidence is below `min_confidence`,
+    /// recomputing the combined text and average confidence from the rest
+    pub fn retain_min_confidence(&mut self, min_confidence: f32) {
+        let before = self.regions.len();
+        self.regions.retain(|r| r.confidence >= min_confidence);
+        if self.regions.len() == before {
+            return;
+        }
+        debug!(
+            "Filtered {} of {} regions below {:.2} confidence",
+            before - self.regions.len(),
+            before,
+            min_confidence
+        );
+
+        self.text = self
+            .regions
+            .iter()
+            .map(|r| r.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.confidence = if self.regions.is_empty() {
+            0.0
+        } else {
+            self.regions.iter().map(|r| r.confidence).sum::<f32>() / self.regions.len() as f32
+        };
+    }
+}
+
+/// OCR result for one page of a multi-page document
+#[derive(Debug, Clone)]
+pub struct PageOcrResult {
+    /// Zero-based position of the page in the submitted document
+    pub page_index: usize,
+    /// Page width in pixels, as submitted
+    pub width: u32,
+    /// Page height in pixels, as submitted
+    pub height: u32,
+    /// Text found on the page; bounding boxes are in this page's coordinates
+    /// as submitted, even if it was downscaled for inference
+    pub result: OcrResult,
 }
 
+/// Stream of per-page OCR results, in page order
+pub type PageOcrStream = ReceiverStream<Result<PageOcrResult>>;
+
 /// PaddleOCR model for text extraction
 ///
 /// Combines text detection and recognition models for end-to-end OCR.
@@ -89,6 +161,10 @@ pub struct PaddleOcrModel {
     detector: OcrDetectionModel,
     /// Text recognition model (reads text from regions)
     recognizer: OcrRecognitionModel,
+    /// Language of the default recognizer's dictionary
+    language: OcrLanguage,
+    /// Language-specific recognizers from the model's subdirectories
+    language_recognizers: HashMap<OcrLanguage, OcrRecognitionModel>,
     /// Minimum confidence threshold for detections
     confidence_threshold: f32,
     /// Model directory path
@@ -102,6 +178,7 @@ impl std::fmt::Debug for PaddleOcrModel {
         f.debug_struct("PaddleOcrModel")
             .field("model_dir", &self.model_dir)
             .field("confidence_threshold", &self.confidence_threshold)
+            .field("languages", &self.languages())
             .field("is_ready", &self.is_ready)
             .finish_non_exhaustive()
     }
@@ -141,10 +218,10 @@ impl PaddleOcrModel {
         let rec_path = model_dir.join("rec_model.onnx");
 
         // Try to find dictionary file (English or Chinese)
-        let dict_path = if model_dir.join("en_dict.txt").exists() {
-            model_dir.join("en_dict.txt")
+        let (dict_path, language) = if model_dir.join("en_dict.txt").exists() {
+            (model_dir.join("en_dict.txt"), OcrLanguage::En)
         } else {
-            model_dir.join("ppocr_keys_v1.txt")
+            (model_dir.join("ppocr_keys_v1.txt"), OcrLanguage::Zh)
         };
 
         info!("Loading PaddleOCR models from {}", model_dir.display());
@@ -160,11 +237,31 @@ impl PaddleOcrModel {
             .await
             .context("Failed to load OCR recognition model")?;
 
+        // Load optional language-specific recognizers
+        let mut language_recognizers = HashMap::new();
+        for lang in OcrLanguage::ALL {
+            let lang_dir = model_dir.join(lang.code());
+            let lang_rec_path = lang_dir.join("rec_model.onnx");
+            let lang_dict_path = lang_dir.join("dict.txt");
+            if !lang_rec_path.exists() || !lang_dict_path.exists() {
+                continue;
+            }
+            match OcrRecognitionModel::new(&lang_rec_path, &lang_dict_path).await {
+                Ok(lang_recognizer) => {
+                    info!("Loaded {} OCR recognition model", lang);
+                    language_recognizers.insert(lang, lang_recognizer);
+                }
+                Err(e) => warn!("Failed to load {} OCR recognition model: {}", lang, e),
+            }
+        }
+
         info!("✅ PaddleOCR pipeline ready (CPU-only)");
 
         Ok(Self {
             detector,
             recognizer,
+            language,
+            language_recognizers,
             confidence_threshold: 0.5,
             model_dir: model_dir.to_string_lossy().to_string(),
             is_ready: true,
@@ -187,10 +284,37 @@ impl PaddleOcrModel {
         self.is_ready
     }
 
+    /// Languages with a loaded recognizer
+    pub fn languages(&self) -> Vec<OcrLanguage> {
+        self.recognizers().map(|(lang, _)| lang).collect()
+    }
+
+    /// Every loaded recognizer with its language; a language-specific
+    /// recognizer takes the place of the default one for the same language
+    fn recognizers(&self) -> impl Iterator<Item = (OcrLanguage, &OcrRecognitionModel)> {
+        let default = (!self.language_recognizers.contains_key(&self.language))
+            .then_some((self.language, &self.recognizer));
+        default.into_iter().chain(
+            OcrLanguage::ALL
+                .into_iter()
+                .filter_map(|lang| Some((lang, self.language_recognizers.get(&lang)?))),
+        )
+    }
+
+    /// Process an image and extract text, detecting each region's script
+    ///
+    /// Equivalent to `process_with_language(image, None)`.
+    pub fn process(&self, image: &DynamicImage) -> Result<OcrResult> {
+        self.process_with_language(image, None)
+    }
+
     /// Process an image and extract text
     ///
     /// # Arguments
     /// - `image`: Image to process
+    /// - `language`: Recognize with this language's model, falling back to
+    ///   the default model if it is not loaded. With `None`, every loaded
+    ///   recognizer reads each region and the most confident reading wins.
     ///
     /// # Returns
     /// - `Result<OcrResult>`: OCR result with detected text regions
@@ -203,7 +327,11 @@ impl PaddleOcrModel {
     ///    b. Preprocess for recognition
     ///    c. Recognize text
     /// 4. Aggregate results
-    pub fn process(&self, image: &DynamicImage) -> Result<OcrResult> {
+    pub fn process_with_language(
+        &self,
+        image: &DynamicImage,
+        language: Option<OcrLanguage>,
+    ) -> Result<OcrResult> {
         let start = Instant::now();
 
         // Get preprocessing info for coordinate mapping
@@ -270,8 +398,8 @@ impl PaddleOcrModel {
 
             // Recognize text
             info!("🔤 Recognition input shape: {:?}", rec_input.shape());
-            match self.recognizer.recognize(&rec_input) {
-                Ok(recognized) => {
+            match self.recognize_region(&rec_input, language) {
+                Ok((recognized, region_language)) => {
                     // Skip empty results
                     if recognized.is_empty() {
                         debug!("Skipping empty recognition result");
@@ -293,6 +421,7 @@ impl PaddleOcrModel {
                             width: orig_width.max(1.0) as u32,
                             height: orig_height.max(1.0) as u32,
                         },
+                        language: region_language,
                     });
                 }
                 Err(e) => {
@@ -354,6 +483,120 @@ impl PaddleOcrModel {
     }
 }
 
+impl PaddleOcrModel {
+    /// Run OCR over the pages of a document, yielding each page's result as
+    /// soon as it completes
+    ///
+    /// Pages are processed in order on a blocking thread so inference does not
+    /// stall the async runtime. A page that fails yields an error and the
+    /// remaining pages are still processed. Dropping the stream stops work
+    /// after the page in progress.
+    ///
+    /// # Arguments
+    /// - `images`: Decoded pages with their metadata, as returned by
+    ///   `decode_base64_image`
+    pub fn recognize_pages(&self, images: Vec<(DynamicImage, ImageInfo)>) -> PageOcrStream {
+        self.recognize_pages_with_language(images, None)
+    }
+
+    /// `recognize_pages` with a language hint, as for `process_with_language`
+    pub fn recognize_pages_with_language(
+        &self,
+        images: Vec<(DynamicImage, ImageInfo)>,
+        language: Option<OcrLanguage>,
+    ) -> PageOcrStream {
+        let (tx, rx) = mpsc::channel(PAGE_CHANNEL_CAPACITY);
+        let model = self.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let page_count = images.len();
+            for (page_index, (image, info)) in images.into_iter().enumerate() {
+                debug!(
+                    "OCR page {}/{} ({}x{})",
+                    page_index + 1,
+                    page_count,
+                    info.width,
+                    info.height
+                );
+                let page = model
+                    .process_with_language(&image, language)
+                    .map(|mut result| {
+                        result.scale_to_original(&info);
+                        PageOcrResult {
+                            page_index,
+                            width: info.original_width,
+                            height: info.original_height,
+                            result,
+                        }
+                    })
+                    .with_context(|| format!("OCR failed on page {}", page_index));
+                if tx.blocking_send(page).is_err() {
+                    debug!("OCR page stream dropped after page {}", page_index);
+                    break;
+                }
+            }
+        });
+
+        ReceiverStream::new(rx)
+    }
+}
+
+impl PaddleOcrModel {
+    /// Read one preprocessed text region, returning its text and language
+    fn recognize_region(
+        &self,
+        input: &Array4<f32>,
+        hint: Option<OcrLanguage>,
+    ) -> Result<(RecognizedText, OcrLanguage)> {
+        if let Some(lang) = hint {
+            let recognizer = if let Some(recognizer) = self.language_recognizers.get(&lang) {
+                recognizer
+            } else {
+                if lang != self.language {
+                    debug!(
+                        "No {} recognition model loaded, using {} model",
+                        lang, self.language
+                    );
+                }
+                &self.recognizer
+            };
+            let recognized = recognizer.recognize(input)?;
+            let detected = detect_script(&recognized.text).unwrap_or(lang);
+            return Ok((recognized, detected));
+        }
+
+        // Auto-detect: keep the most confident non-empty reading
+        let mut best: Option<(RecognizedText, OcrLanguage)> = None;
+        let mut last_error = None;
+        for (lang, recognizer) in self.recognizers() {
+            match recognizer.recognize(input) {
+                Ok(recognized) => {
+                    let better = match &best {
+                        None => true,
+                        Some((b, _)) => {
+                            b.is_empty()
+                                || (!recognized.is_empty() && recognized.confidence > b.confidence)
+                        }
+                    };
+                    if better {
+                        best = Some((recognized, lang));
+                    }
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        match (best, last_error) {
+            (Some((recognized, lang)), _) => {
+                let detected = detect_script(&recognized.text).unwrap_or(lang);
+                Ok((recognized, detected))
+            }
+            (None, Some(e)) => Err(e),
+            (None, None) => anyhow::bail!("No OCR recognition model loaded"),
+        }
+    }
+}
+
 /// Crop a text box region from an image
 ///
 /// Handles edge cases where the box extends beyond image boundaries.
@@ -433,6 +676,7 @@ mod tests {
                 width: 50,
                 height: 20,
             },
+            language: OcrLanguage::En,
         };
         assert_eq!(region.text, "Hello");
         assert!(region.confidence > 0.9);
@@ -459,6 +703,43 @@ mod tests {
         assert_eq!(result.processing_time_ms, 100);
     }
 
+    #[test]
+    fn test_retain_min_confidence() {
+        let region = |text: &str, confidence: f32| TextRegion {
+            text: text.to_string(),
+            confidence,
+            bounding_box: BoundingBox {
+                x: 0,
+                y: 0,
+                width: 10,
+                height: 10,
+            },
+            language: OcrLanguage::En,
+        };
+        let mut result = OcrResult {
+            text: "Total ~#% 42.00".to_string(),
+            confidence: 0.7,
+            regions: vec![
+                region("Total", 0.9),
+                region("~#%", 0.3),
+                region("42.00", 0.9),
+            ],
+            processing_time_ms: 10,
+        };
+
+        result.retain_min_confidence(0.0);
+        assert_eq!(result.regions.len(), 3);
+        assert_eq!(result.text, "Total ~#% 42.00");
+
+        result.retain_min_confidence(0.5);
+        assert_eq!(result.text, "Total 42.00");
+        assert!((result.confidence - 0.9).abs() < 1e-6);
+
+        result.retain_min_confidence(0.95);
+        assert!(result.regions.is_empty());
+        assert_eq!(result.confidence, 0.0);
+    }
+
     #[test]
     fn test_crop_text_box_normal() {
         let img = DynamicImage::new_rgb8(100, 100);
@@ -547,6 +828,45 @@ mod tests {
         assert!(ocr_result.regions.is_empty() || ocr_result.confidence < 0.5);
     }
 
+    #[tokio::test]
+    #[ignore] // Only run if model files are downloaded
+    async fn test_recognize_pages_yields_each_page_in_order() {
+        use image::ImageFormat;
+        use tokio_stream::StreamExt;
+
+        let model = match PaddleOcrModel::new(MODEL_DIR).await {
+            Ok(m) => m,
+            Err(_) => return,
+        };
+
+        let pages = [(100, 80), (64, 64), (120, 40)]
+            .into_iter()
+            .map(|(width, height)| {
+                let info = ImageInfo {
+                    width,
+                    height,
+                    format: ImageFormat::Png,
+                    size_bytes: 0,
+                    original_width: width,
+                    original_height: height,
+                    scale_factor: 1.0,
+                };
+                (DynamicImage::new_rgb8(width, height), info)
+            })
+            .collect();
+
+        let results: Vec<_> = model.recognize_pages(pages).collect().await;
+        assert_eq!(results.len(), 3);
+        for (i, page) in results.into_iter().enumerate() {
+            let page = page.unwrap();
+            assert_eq!(page.page_index, i);
+            for region in &page.result.regions {
+                assert!(region.bounding_box.x < page.width);
+                assert!(region.bounding_box.y < page.height);
+            }
+        }
+    }
+
     #[tokio::test]
     #[ignore] // Only run if model files are downloaded
     async fn test_process_returns_timing() {
@@ -577,6 +897,7 @@ mod tests {
                     width: 50,
                     height: 20,
                 },
+                language: OcrLanguage::En,
             },
             TextRegion {
                 text: "A".to_string(),
@@ -587,6 +908,7 @@ mod tests {
                     width: 50,
                     height: 20,
                 },
+                language: OcrLanguage::En,
             },
             TextRegion {
                 text: "B".to_string(),
@@ -597,6 +919,7 @@ mod tests {
                     width: 50,
                     height: 20,
                 },
+                language: OcrLanguage::En,
             },
         ];
 
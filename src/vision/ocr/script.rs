@@ -0,0 +1,124 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+//! OCR languages and script detection
+//!
+//! Each PaddleOCR recognition model reads one script family, so the pipeline
+//! needs to know which language a text region is in: from a caller's hint,
+//! or by detecting the script of what the recognizers read.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Languages with a dedicated recognition model and dictionary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrLanguage {
+    En,
+    Zh,
+    Ja,
+    Ko,
+}
+
+impl OcrLanguage {
+    pub const ALL: [OcrLanguage; 4] = [Self::En, Self::Zh, Self::Ja, Self::Ko];
+
+    /// ISO 639-1 code, also the model subdirectory name
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Zh => "zh",
+            Self::Ja => "ja",
+            Self::Ko => "ko",
+        }
+    }
+}
+
+impl fmt::Display for OcrLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for OcrLanguage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" => Ok(Self::En),
+            "zh" | "ch" => Ok(Self::Zh),
+            "ja" => Ok(Self::Ja),
+            "ko" => Ok(Self::Ko),
+            other => anyhow::bail!("Unsupported OCR language: {}", other),
+        }
+    }
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}')
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}')
+}
+
+fn is_han(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+/// Detect the language of recognized text from its script
+///
+/// Any Hangul means Korean and any kana means Japanese (Japanese text mixes
+/// kana with Han characters); otherwise Han characters mean Chinese and
+/// Latin letters English. Returns `None` for text with no letters, such as
+/// bare numbers or punctuation.
+pub fn detect_script(text: &str) -> Option<OcrLanguage> {
+    let (mut hangul, mut kana, mut han, mut latin) = (0, 0, 0, 0);
+    for c in text.chars() {
+        if is_hangul(c) {
+            hangul += 1;
+        } else if is_kana(c) {
+            kana += 1;
+        } else if is_han(c) {
+            han += 1;
+        } else if c.is_ascii_alphabetic() {
+            latin += 1;
+        }
+    }
+
+    if hangul > 0 {
+        Some(OcrLanguage::Ko)
+    } else if kana > 0 {
+        Some(OcrLanguage::Ja)
+    } else if han > 0 {
+        Some(OcrLanguage::Zh)
+    } else if latin > 0 {
+        Some(OcrLanguage::En)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_script() {
+        assert_eq!(detect_script("Invoice total"), Some(OcrLanguage::En));
+        assert_eq!(detect_script("发票总额"), Some(OcrLanguage::Zh));
+        // Kanji with kana is Japanese, not Chinese
+        assert_eq!(detect_script("請求書の合計"), Some(OcrLanguage::Ja));
+        assert_eq!(detect_script("청구서 합계 100"), Some(OcrLanguage::Ko));
+        assert_eq!(detect_script("12.50 %"), None);
+    }
+
+    #[test]
+    fn test_language_codes() {
+        for language in OcrLanguage::ALL {
+            assert_eq!(language.code().parse::<OcrLanguage>().unwrap(), language);
+        }
+        assert_eq!("ch".parse::<OcrLanguage>().unwrap(), OcrLanguage::Zh);
+        assert!("fr".parse::<OcrLanguage>().is_err());
+    }
+}
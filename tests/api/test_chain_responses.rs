@@ -28,6 +28,9 @@ fn test_inference_response_chain() {
         search_queries_count: None,
         search_provider: None,
         usage: None,
+        logprobs: None,
+        tool_calls: None,
+        system_fingerprint: None,
     };
 
     // Serialize and check
@@ -56,6 +59,9 @@ fn test_native_token_in_response() {
         search_queries_count: None,
         search_provider: None,
         usage: None,
+        logprobs: None,
+        tool_calls: None,
+        system_fingerprint: None,
     };
 
     assert_eq!(base_response.native_token, Some("ETH".to_string()));
@@ -74,6 +80,9 @@ fn test_native_token_in_response() {
         search_queries_count: None,
         search_provider: None,
         usage: None,
+        logprobs: None,
+        tool_calls: None,
+        system_fingerprint: None,
     };
 
     assert_eq!(opbnb_response.native_token, Some("BNB".to_string()));
@@ -103,6 +112,9 @@ fn test_chain_name_included() {
         search_queries_count: None,
         search_provider: None,
         usage: None,
+        logprobs: None,
+        tool_calls: None,
+        system_fingerprint: None,
     };
 
     assert_eq!(response.chain_name, Some("Base Sepolia".to_string()));
@@ -146,6 +158,9 @@ fn test_streaming_response_with_chain() {
         chain_id: Some(84532),
         chain_name: Some("Base Sepolia".to_string()),
         native_token: Some("ETH".to_string()),
+        logprobs: None,
+        tool_calls: None,
+        system_fingerprint: None,
     };
 
     // Serialize and verify
@@ -180,6 +195,9 @@ fn test_response_formatting() {
         search_queries_count: None,
         search_provider: None,
         usage: None,
+        logprobs: None,
+        tool_calls: None,
+        system_fingerprint: None,
     };
 
     let formatted = formatter.format_inference_response(response);
@@ -15,11 +15,15 @@ mod tests {
                 role: "user".to_string(),
                 content: "What is the capital of France?".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
             Message {
                 role: "assistant".to_string(),
                 content: "The capital of France is Paris.".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         ];
 
@@ -39,11 +43,15 @@ mod tests {
                 role: "user".to_string(),
                 content: "This is a test message with some words".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
             Message {
                 role: "assistant".to_string(),
                 content: "Here is a response with more words to count".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         ];
 
@@ -63,6 +71,8 @@ mod tests {
                 role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
                 content: format!("Message {}", i),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             });
         }
 
@@ -92,6 +102,8 @@ mod tests {
             role: "user".to_string(),
             content: "Short".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         }];
 
         assert!(is_context_within_limits(&context, 100));
@@ -105,16 +117,22 @@ mod tests {
                 role: "system".to_string(),
                 content: "You are a helpful assistant.".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
             Message {
                 role: "user".to_string(),
                 content: "Hello".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
             Message {
                 role: "assistant".to_string(),
                 content: "Hi there!".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         ];
 
@@ -135,6 +153,8 @@ mod tests {
                 role: "user".to_string(),
                 content: format!("This is message number {} with some additional text", i),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             });
         }
 
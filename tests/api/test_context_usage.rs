@@ -32,6 +32,9 @@ fn test_inference_response_without_usage_omits_field() {
         search_queries_count: None,
         search_provider: None,
         usage: None,
+        logprobs: None,
+        tool_calls: None,
+        system_fingerprint: None,
     };
     let json_str = serde_json::to_string(&response).unwrap();
     assert!(!json_str.contains("\"usage\""));
@@ -57,6 +60,9 @@ fn test_inference_response_with_usage() {
             total_tokens: 510,
             context_window_size: 4096,
         }),
+        logprobs: None,
+        tool_calls: None,
+        system_fingerprint: None,
     };
     let json = serde_json::to_value(&response).unwrap();
     assert_eq!(json["usage"]["prompt_tokens"], 500);
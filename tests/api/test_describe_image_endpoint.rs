@@ -21,7 +21,7 @@ use fabstir_llm_node::{
         describe_image::{DescribeImageRequest, DescribeImageResponse},
         http_server::AppState,
     },
-    vision::{VisionModelConfig, VisionModelManager},
+    vision::{florence::FlorenceTask, VisionModelConfig, VisionModelManager},
 };
 use std::sync::Arc;
 
@@ -116,6 +116,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -138,6 +139,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -159,6 +161,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -181,6 +184,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -203,6 +207,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 5, // Below minimum
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -228,6 +233,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 1000, // Above maximum
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -253,6 +259,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 1, // Invalid chain
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -282,6 +289,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -310,6 +318,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -344,6 +353,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -379,6 +389,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -406,6 +417,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 5611,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -433,6 +445,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -462,6 +475,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -487,6 +501,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -512,6 +527,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 300,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -537,6 +553,7 @@ mod describe_image_handler_tests {
             prompt: Some("Describe the colors in this image".to_string()),
             max_tokens: 150,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -561,6 +578,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -611,6 +629,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -634,6 +653,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -661,6 +681,7 @@ mod describe_image_handler_tests {
             prompt: None,
             max_tokens: 50,
             chain_id: 84532,
+            task: FlorenceTask::Describe,
         };
 
         let result = describe_image_handler(State(state), Json(request)).await;
@@ -155,6 +155,26 @@ fn test_validate_steps_101_returns_error() {
     assert!(result.unwrap_err().contains("steps"));
 }
 
+#[test]
+fn test_validate_seed_out_of_range_returns_error() {
+    let req = GenerateImageRequest {
+        prompt: "a landscape".to_string(),
+        model: None,
+        size: None,
+        steps: None,
+        seed: Some(u32::MAX as u64 + 1),
+        negative_prompt: Some("blurry, low quality".to_string()),
+        guidance_scale: None,
+        safety_level: None,
+        chain_id: None,
+        session_id: None,
+        job_id: None,
+    };
+    let result = req.validate();
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("seed"));
+}
+
 #[test]
 fn test_validate_valid_request_passes() {
     let req = GenerateImageRequest {
@@ -218,6 +238,9 @@ fn test_response_serialization_all_fields() {
             model_multiplier: 1.0,
             megapixels: 1.0,
             steps: 4,
+            base_units: 0.0,
+            megapixel_units: 0.0,
+            step_units: 0.2,
         },
     };
     let json = serde_json::to_value(&resp).unwrap();
@@ -251,6 +274,9 @@ fn test_response_chain_context_base_sepolia() {
             model_multiplier: 1.0,
             megapixels: 1.0,
             steps: 4,
+            base_units: 0.0,
+            megapixel_units: 0.0,
+            step_units: 0.2,
         },
         84532,
     );
@@ -277,6 +303,9 @@ fn test_response_chain_context_opbnb() {
             model_multiplier: 1.0,
             megapixels: 1.0,
             steps: 4,
+            base_units: 0.0,
+            megapixel_units: 0.0,
+            step_units: 0.2,
         },
         5611,
     );
@@ -292,6 +321,9 @@ fn test_billing_info_generation_units_calculation() {
         model_multiplier: 1.0,
         megapixels: 1.0,
         steps: 4,
+        base_units: 0.0,
+        megapixel_units: 0.0,
+        step_units: 0.2,
     };
     let expected = billing.megapixels * (billing.steps as f64 / 20.0) * billing.model_multiplier;
     assert!((expected - billing.generation_units).abs() < f64::EPSILON);
@@ -375,3 +407,133 @@ async fn test_handler_unsafe_prompt_returns_400() {
     assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
     assert!(msg.to_lowercase().contains("safe") || msg.to_lowercase().contains("block"));
 }
+
+// ============================================================================
+// Generation queue and job polling
+// ============================================================================
+
+fn minimal_request(prompt: &str) -> GenerateImageRequest {
+    GenerateImageRequest {
+        prompt: prompt.to_string(),
+        model: None,
+        size: None,
+        steps: None,
+        seed: None,
+        negative_prompt: None,
+        guidance_scale: None,
+        safety_level: None,
+        chain_id: None,
+        session_id: None,
+        job_id: None,
+    }
+}
+
+#[tokio::test]
+async fn test_handler_full_queue_returns_429() {
+    use fabstir_llm_node::api::http_server::AppState;
+    use fabstir_llm_node::diffusion::{DiffusionClient, QueueConfig};
+    use std::sync::Arc;
+
+    let state = AppState::new_for_test();
+    let client = DiffusionClient::new("http://localhost:99999", "test-model")
+        .unwrap()
+        .with_queue_config(QueueConfig {
+            max_concurrent: 1,
+            max_queued: 0,
+        });
+    let client = Arc::new(client);
+    *state.diffusion_client.write().await = Some(client.clone());
+
+    // Occupy the only slot
+    let _running = client.queue().try_enqueue().unwrap().wait_turn().await;
+
+    let result = fabstir_llm_node::api::generate_image::generate_image_handler(
+        axum::extract::State(state.clone()),
+        axum::Json(minimal_request("a sunset")),
+    )
+    .await;
+    let (status, msg) = result.unwrap_err();
+    assert_eq!(status, axum::http::StatusCode::TOO_MANY_REQUESTS);
+    assert!(msg.contains("queue is full"));
+
+    let result = fabstir_llm_node::api::generate_image::submit_image_job_handler(
+        axum::extract::State(state),
+        axum::Json(minimal_request("a sunset")),
+    )
+    .await;
+    assert_eq!(
+        result.unwrap_err().0,
+        axum::http::StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[tokio::test]
+async fn test_submit_job_returns_queue_position() {
+    use fabstir_llm_node::api::generate_image::{
+        image_job_status_handler, submit_image_job_handler, ImageJobStatus,
+    };
+    use fabstir_llm_node::api::http_server::AppState;
+    use fabstir_llm_node::diffusion::{DiffusionClient, QueueConfig};
+    use std::sync::Arc;
+
+    let state = AppState::new_for_test();
+    let client = DiffusionClient::new("http://localhost:99999", "test-model")
+        .unwrap()
+        .with_queue_config(QueueConfig {
+            max_concurrent: 1,
+            max_queued: 4,
+        });
+    let client = Arc::new(client);
+    *state.diffusion_client.write().await = Some(client.clone());
+
+    let running = client.queue().try_enqueue().unwrap().wait_turn().await;
+
+    let (status, job) = submit_image_job_handler(
+        axum::extract::State(state.clone()),
+        axum::Json(minimal_request("a sunset")),
+    )
+    .await
+    .unwrap();
+    assert_eq!(status, axum::http::StatusCode::ACCEPTED);
+    assert_eq!(job.status, ImageJobStatus::Queued);
+    assert_eq!(job.queue_position, Some(1));
+    assert!(job.eta_ms.unwrap() > 0);
+
+    let polled = image_job_status_handler(
+        axum::extract::State(state.clone()),
+        axum::extract::Path(job.job_id.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(polled.job_id, job.job_id);
+    assert_eq!(polled.status, ImageJobStatus::Queued);
+
+    // Releasing the slot lets the job run; the sidecar is unreachable, so it fails
+    drop(running);
+    let mut status = ImageJobStatus::Queued;
+    for _ in 0..100 {
+        status = state
+            .api_server
+            .image_gen_jobs()
+            .get(&job.job_id)
+            .unwrap()
+            .status;
+        if status == ImageJobStatus::Failed {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(status, ImageJobStatus::Failed);
+}
+
+#[tokio::test]
+async fn test_unknown_job_returns_404() {
+    use fabstir_llm_node::api::http_server::AppState;
+
+    let result = fabstir_llm_node::api::generate_image::image_job_status_handler(
+        axum::extract::State(AppState::new_for_test()),
+        axum::extract::Path("missing".to_string()),
+    )
+    .await;
+    assert_eq!(result.unwrap_err().0, axum::http::StatusCode::NOT_FOUND);
+}
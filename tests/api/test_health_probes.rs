@@ -0,0 +1,28 @@
+// Copyright (c) 2025 Fabstir
+// SPDX-License-Identifier: BUSL-1.1
+
+//! Liveness and readiness probes (/health/live, /health/ready)
+
+use fabstir_llm_node::api::ApiServer;
+use fabstir_llm_node::monitoring::HealthStatus;
+
+#[tokio::test]
+async fn test_live_but_not_ready_before_model_loads() {
+    let server = ApiServer::new_for_test();
+
+    let live = server.liveness_check();
+    assert!(live.is_alive);
+    assert_eq!(live.status, HealthStatus::Healthy);
+
+    let ready = server.readiness_check().await;
+    assert!(!ready.is_ready);
+    assert_eq!(ready.components_ready.get("model"), Some(&false));
+
+    let model = &ready.dependencies["model"];
+    assert_eq!(model.status, HealthStatus::Unhealthy);
+    assert!(model.message.is_some());
+
+    // Dependencies that were never attached are not probed
+    assert!(!ready.dependencies.contains_key("chain_rpc"));
+    assert!(!ready.dependencies.contains_key("s5"));
+}
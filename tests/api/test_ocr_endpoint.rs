@@ -95,6 +95,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -115,6 +116,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -134,6 +136,7 @@ mod ocr_handler_tests {
             format: "bmp".to_string(), // Not supported
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -154,6 +157,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "fr".to_string(), // Not supported
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -177,6 +181,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 1, // Invalid chain
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -204,6 +209,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -230,6 +236,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -258,6 +265,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -288,6 +296,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -311,6 +320,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 5611,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -334,6 +344,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -359,6 +370,7 @@ mod ocr_handler_tests {
             format: "gif".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -406,6 +418,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -431,6 +444,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -451,6 +465,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
@@ -473,6 +488,7 @@ mod ocr_handler_tests {
             format: "png".to_string(),
             language: "en".to_string(),
             chain_id: 84532,
+            min_confidence: None,
         };
 
         let result = ocr_handler(State(state), Json(request)).await;
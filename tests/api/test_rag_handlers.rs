@@ -246,6 +246,8 @@ fn test_search_handler_success() {
         k: 2,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, search_request).unwrap();
@@ -268,6 +270,8 @@ fn test_search_handler_empty_store() {
         k: 10,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, request).unwrap();
@@ -314,6 +318,8 @@ fn test_search_handler_with_threshold() {
         k: 10,
         threshold: Some(0.99), // High threshold (only exact/near matches)
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, search_request).unwrap();
@@ -357,6 +363,8 @@ fn test_search_handler_with_filter() {
         k: 10,
         threshold: None,
         metadata_filter: Some(json!({"category": {"$eq": "science"}})),
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, search_request).unwrap();
@@ -378,6 +386,8 @@ fn test_search_handler_rag_disabled_error() {
         k: 10,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let result = handle_search_vectors(&session, request);
@@ -417,6 +427,8 @@ fn test_search_handler_timing_accurate() {
         k: 10,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, search_request).unwrap();
@@ -28,6 +28,8 @@ async fn setup_test_server() -> Result<(), Box<dyn std::error::Error>> {
         model_eviction_policy: "lru".to_string(),
         kv_cache_type_k: None,
         kv_cache_type_v: None,
+        default_timeout_ms: None,
+        draft_model: None,
     };
 
     let mut engine = LlmEngine::new(engine_config).await?;
@@ -84,6 +86,8 @@ async fn test_load_real_model_on_startup() {
         model_eviction_policy: "lru".to_string(),
         kv_cache_type_k: None,
         kv_cache_type_v: None,
+        default_timeout_ms: None,
+        draft_model: None,
     };
 
     let mut engine = LlmEngine::new(engine_config).await
@@ -14,6 +14,8 @@ fn test_search_request_serialization() {
         k: 10,
         threshold: Some(0.7),
         metadata_filter: Some(json!({"category": {"$eq": "science"}})),
+        query_text: None,
+        rerank_top_k: None,
     };
 
     // Serialize to JSON
@@ -73,6 +75,8 @@ fn test_search_validates_k_limit() {
         k: 150, // Too large
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let validation_result = request.validate();
@@ -88,6 +92,8 @@ fn test_search_validates_query_dimensions() {
         k: 10,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let validation_result = request.validate();
@@ -104,6 +110,8 @@ fn test_search_with_threshold() {
         k: 5,
         threshold: Some(0.8),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let json_str = serde_json::to_string(&request_with_threshold).unwrap();
@@ -117,6 +125,8 @@ fn test_search_with_threshold() {
         k: 5,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let json_str = serde_json::to_string(&request_no_threshold).unwrap();
@@ -138,6 +148,8 @@ fn test_search_with_metadata_filter() {
         k: 10,
         threshold: None,
         metadata_filter: Some(filter.clone()),
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let json_str = serde_json::to_string(&request).unwrap();
@@ -153,6 +165,8 @@ fn test_search_with_metadata_filter() {
         k: 10,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let json_str = serde_json::to_string(&request_no_filter).unwrap();
@@ -169,6 +183,7 @@ fn test_search_timing_included() {
             id: "doc1".to_string(),
             score: 0.95,
             metadata: json!({"title": "Test"}),
+            rerank_score: None,
         }],
         total_vectors: 500,
         search_time_ms: 8.3,
@@ -219,6 +234,7 @@ fn test_search_result_structure() {
             "page": 15,
             "tags": ["ml", "ai"]
         }),
+        rerank_score: None,
     };
 
     // Serialize and verify structure
@@ -98,6 +98,8 @@ fn test_search_s5_loaded_index_basic() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, request).expect("Search should succeed");
@@ -131,6 +133,8 @@ fn test_search_s5_loaded_index_k_parameter() {
         k: 1,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response_k1 = handle_search_vectors(&session, request_k1).unwrap();
@@ -143,6 +147,8 @@ fn test_search_s5_loaded_index_k_parameter() {
         k: 5,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response_k5 = handle_search_vectors(&session, request_k5).unwrap();
@@ -155,6 +161,8 @@ fn test_search_s5_loaded_index_k_parameter() {
         k: 100,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response_k100 = handle_search_vectors(&session, request_k100).unwrap();
@@ -178,6 +186,8 @@ fn test_search_s5_loaded_index_threshold() {
         k: 50,
         threshold: Some(0.95),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response_high = handle_search_vectors(&session, request_high).unwrap();
@@ -189,6 +199,8 @@ fn test_search_s5_loaded_index_threshold() {
         k: 50,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response_low = handle_search_vectors(&session, request_low).unwrap();
@@ -220,6 +232,8 @@ fn test_search_s5_loaded_index_metadata_preserved() {
         k: 5,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, request).unwrap();
@@ -253,6 +267,8 @@ fn test_search_s5_loaded_index_performance() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, request).unwrap();
@@ -304,6 +320,8 @@ fn test_search_uploaded_vectors_still_works() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, search_request).expect("Search should succeed");
@@ -347,6 +365,8 @@ fn test_search_uploaded_with_metadata_filter() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: Some(json!({"category": {"$eq": "tech"}})),
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, search_request).unwrap();
@@ -384,6 +404,8 @@ fn test_search_while_loading_returns_error() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let result = handle_search_vectors(&session_arc, request);
@@ -418,6 +440,8 @@ fn test_search_not_started_returns_error() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let result = handle_search_vectors(&session_arc, request);
@@ -448,6 +472,8 @@ fn test_search_error_state_returns_error() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let result = handle_search_vectors(&session_arc, request);
@@ -486,6 +512,8 @@ fn test_search_loaded_but_no_index_returns_error() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let result = handle_search_vectors(&session_arc, request);
@@ -517,6 +545,8 @@ fn test_search_s5_empty_index() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, request).expect("Should handle empty index");
@@ -541,6 +571,8 @@ fn test_search_no_rag_enabled_error() {
         k: 10,
         threshold: Some(0.0),
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let result = handle_search_vectors(&session_arc, request);
@@ -569,6 +601,8 @@ fn test_search_concurrent_on_s5_index() {
                     k: 5,
                     threshold: Some(0.0),
                     metadata_filter: None,
+                    query_text: None,
+                    rerank_top_k: None,
                 };
 
                 handle_search_vectors(&session_clone, request)
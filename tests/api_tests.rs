@@ -15,6 +15,7 @@ mod api {
     mod test_embed_request;
     mod test_embed_response;
     mod test_generate_image;
+    mod test_health_probes;
     mod test_loading_progress_messages;
     mod test_models_endpoint;
     mod test_ocr_endpoint;
@@ -269,6 +269,65 @@ async fn test_dht_republish_records() {
     .expect("Republish failed");
 }
 
+#[tokio::test]
+async fn test_dht_republish_provider_records() {
+    let mut bootstrap = create_node().await;
+    let _bootstrap_events = bootstrap.start().await;
+
+    let config = NodeConfig {
+        dht_republish_interval: Duration::from_secs(1),
+        capabilities: vec!["llama-7b".to_string()],
+        bootstrap_peers: vec![(bootstrap.peer_id(), bootstrap.listeners()[0].clone())],
+        ..Default::default()
+    };
+
+    let mut node = Node::new(config).await.expect("Failed to create node");
+    let mut event_receiver = node.start().await;
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.announce_capabilities().await.expect("Failed to announce capabilities");
+
+    // The capability should be re-announced on the next republish round
+    timeout(Duration::from_secs(3), async {
+        loop {
+            match event_receiver.recv().await {
+                Some(NodeEvent::DhtEvent(DhtEvent::Republished { providers, .. }))
+                    if providers >= 1 =>
+                {
+                    return Ok(());
+                }
+                Some(_) => continue,
+                None => return Err("Channel closed"),
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for republish")
+    .expect("Republish failed");
+
+    let health = node.dht_routing_table_health();
+    let outcome = health.last_republish.expect("No republish outcome");
+    assert_eq!(outcome.providers, 1);
+    assert!(health.time_to_next_republish.unwrap() <= Duration::from_secs(1));
+}
+
+#[test]
+fn test_republish_interval_capped_by_record_ttl() {
+    let config = NodeConfig {
+        dht_republish_interval: Duration::from_secs(3600),
+        dht_record_ttl: Duration::from_secs(2 * 3600),
+        ..Default::default()
+    };
+    assert_eq!(
+        config.effective_dht_republish_interval(),
+        Duration::from_secs(1800)
+    );
+    assert_eq!(
+        NodeConfig::default().effective_dht_republish_interval(),
+        Duration::from_secs(3600)
+    );
+}
+
 #[tokio::test]
 async fn test_dht_closest_peers() {
     // Create a network of nodes
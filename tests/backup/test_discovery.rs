@@ -1,5 +1,6 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
+use fabstir_llm_node::p2p::discovery::{agent_version, parse_advertised_capabilities};
 use fabstir_llm_node::p2p::{Node, NodeConfig, NodeEvent, DiscoveryEvent};
 use libp2p::PeerId;
 use std::time::Duration;
@@ -393,6 +394,39 @@ async fn test_discovery_priority() {
     assert_eq!(peers[2].1, 1);
 }
 
+#[tokio::test]
+async fn test_peers_filtered_by_capability() {
+    let bootstrap = create_bootstrap_node().await;
+    let llama_node = create_node_with_capabilities(&bootstrap, vec!["llama-7b", "inference"]).await;
+    let mistral_node = create_node_with_capability(&bootstrap, "mistral-7b").await;
+
+    // Identify runs once the nodes connect to the bootstrap
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    assert_eq!(
+        bootstrap.peers_with_capability("llama-7b").await,
+        vec![llama_node.peer_id()]
+    );
+    assert_eq!(
+        bootstrap.peers_with_capability("mistral-7b").await,
+        vec![mistral_node.peer_id()]
+    );
+    assert!(bootstrap.peers_with_capability("gpt-4").await.is_empty());
+}
+
+#[test]
+fn test_advertised_capabilities_round_trip() {
+    let capabilities = vec!["llama-7b".to_string(), "inference".to_string()];
+    let parsed = parse_advertised_capabilities(&agent_version("1.0.0", &capabilities)).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert!(parsed.contains("llama-7b"));
+    assert!(parsed.contains("inference"));
+
+    assert!(parse_advertised_capabilities(&agent_version("1.0.0", &[])).unwrap().is_empty());
+    // Peers that are not our nodes advertise nothing we can use
+    assert!(parse_advertised_capabilities("rust-libp2p/0.54.0").is_none());
+}
+
 // Helper functions
 
 async fn create_bootstrap_node() -> Node {
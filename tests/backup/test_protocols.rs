@@ -32,6 +32,7 @@ async fn test_inference_request_protocol() {
         max_tokens: 100,
         temperature: 0.7,
         stream: false,
+        route: vec![],
     };
     
     client
@@ -87,6 +88,7 @@ async fn test_inference_response_protocol() {
         max_tokens: 50,
         temperature: 0.8,
         stream: false,
+        route: vec![],
     };
     
     client.send_inference_request(provider_peer_id, request.clone()).await.unwrap();
@@ -106,6 +108,7 @@ async fn test_inference_response_protocol() {
                         tokens_used: 6,
                         model_used: request.model,
                         finish_reason: "stop".to_string(),
+                        served_by: None,
                     };
                     
                     provider
@@ -173,6 +176,7 @@ async fn test_streaming_inference_protocol() {
                                 tokens_used: 1,
                                 model_used: request.model.clone(),
                                 finish_reason: if i == chunks.len() - 1 { "stop" } else { "length" }.to_string(),
+                                served_by: None,
                             };
                             
                             provider
@@ -197,6 +201,7 @@ async fn test_streaming_inference_protocol() {
         max_tokens: 100,
         temperature: 0.7,
         stream: true,
+        route: vec![],
     };
     
     let mut stream = client
@@ -426,6 +431,7 @@ async fn test_request_timeout() {
         max_tokens: 50,
         temperature: 0.7,
         stream: false,
+        route: vec![],
     };
     
     client
@@ -480,6 +486,7 @@ async fn test_protocol_rate_limiting() {
             max_tokens: 10,
             temperature: 0.7,
             stream: false,
+            route: vec![],
         };
         
         let _ = client.send_inference_request(provider_peer_id, request).await;
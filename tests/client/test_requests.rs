@@ -216,6 +216,44 @@ async fn test_rate_limiting() {
     }
 }
 
+#[tokio::test]
+async fn test_rejected_api_keys_are_rate_limited() {
+    let mut config = ApiConfig::default();
+    config.require_api_key = true;
+    config.api_keys = vec!["test-key-123".to_string()];
+    config.rate_limit_per_minute = 3;
+
+    let server = ApiServer::new(config)
+        .await
+        .expect("Failed to create server");
+    let addr = server.local_addr();
+
+    let client = Client::new();
+    let url = format!("http://{}/v1/inference", addr);
+    let request = json!({
+        "model": "llama-7b",
+        "prompt": "test",
+        "max_tokens": 10
+    });
+
+    // Guessed keys are refused, then the client IP runs out of requests
+    for i in 0..4 {
+        let resp = client
+            .post(&url)
+            .header("Authorization", format!("Bearer wrong-key-{}", i))
+            .json(&request)
+            .send()
+            .await
+            .expect("Failed to send request");
+
+        if i < 3 {
+            assert_eq!(resp.status(), 401, "Request {} should be unauthorized", i);
+        } else {
+            assert_eq!(resp.status(), 429, "Request {} should be rate limited", i);
+        }
+    }
+}
+
 #[tokio::test]
 async fn test_cors_headers() {
     let mut config = ApiConfig::default();
@@ -245,6 +283,63 @@ async fn test_cors_headers() {
     );
 }
 
+#[tokio::test]
+async fn test_cors_credentials_only_for_allowed_origins() {
+    let mut config = ApiConfig::default();
+    config.listen_addr = "127.0.0.1:0".to_string();
+    config.cors_allowed_origins = vec!["https://app.example.com".to_string()];
+    config.cors_allow_credentials = true;
+
+    let server = ApiServer::new(config)
+        .await
+        .expect("Failed to create server");
+    let url = format!("http://{}/v1/inference", server.local_addr());
+    let client = Client::new();
+
+    let preflight = |origin: &'static str| {
+        client
+            .request(reqwest::Method::OPTIONS, &url)
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", "POST")
+            .header(
+                "Access-Control-Request-Headers",
+                "authorization, content-type",
+            )
+            .send()
+    };
+
+    let resp = preflight("https://app.example.com")
+        .await
+        .expect("Failed to send request");
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("Access-Control-Allow-Origin").unwrap(),
+        "https://app.example.com"
+    );
+    assert_eq!(
+        resp.headers()
+            .get("Access-Control-Allow-Credentials")
+            .unwrap(),
+        "true"
+    );
+
+    // Other origins are not echoed back
+    let resp = preflight("https://evil.example.com")
+        .await
+        .expect("Failed to send request");
+    assert!(resp.headers().get("Access-Control-Allow-Origin").is_none());
+}
+
+#[tokio::test]
+async fn test_cors_credentials_require_explicit_origins() {
+    let mut config = ApiConfig::default();
+    config.listen_addr = "127.0.0.1:0".to_string();
+    config.cors_allow_credentials = true;
+
+    // The default origin list is `*`
+    assert!(ApiServer::new(config).await.is_err());
+}
+
 #[tokio::test]
 async fn test_request_timeout() {
     let mut config = ApiConfig::default();
@@ -1,7 +1,9 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use ethers::prelude::*;
-use fabstir_llm_node::contracts::{PaymentConfig, PaymentStatus, PaymentVerifier, TokenInfo};
+use fabstir_llm_node::contracts::{
+    is_deferred, PaymentConfig, PaymentStatus, PaymentVerifier, TokenInfo,
+};
 use fabstir_llm_node::{Web3Client, Web3Config};
 use std::sync::Arc;
 use std::time::Duration;
@@ -326,6 +328,36 @@ async fn test_batch_payment_processing() {
     }
 }
 
+#[tokio::test]
+async fn test_deferred_payments_are_queued_once_per_job() {
+    let config = PaymentConfig::default();
+    // A zero ceiling defers every claim
+    let web3_client = Arc::new(
+        Web3Client::new(Web3Config {
+            max_gas_price_gwei: Some(0.0),
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to create Web3 client"),
+    );
+    let verifier = PaymentVerifier::new(config, web3_client)
+        .await
+        .expect("Failed to create payment verifier");
+
+    let job_ids = vec![U256::from(1), U256::from(2)];
+    for _ in 0..2 {
+        let results = verifier
+            .process_batch_payments(&job_ids)
+            .await
+            .expect("Failed to process batch payments");
+        for (_, result) in results {
+            assert!(is_deferred(&result.unwrap_err()));
+        }
+    }
+
+    assert_eq!(verifier.deferred_count(), 2);
+}
+
 #[tokio::test]
 async fn test_payment_history_tracking() {
     let config = PaymentConfig::default();
@@ -1,7 +1,10 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use ethers::prelude::*;
-use fabstir_llm_node::contracts::{ProofConfig, ProofData, ProofStatus, ProofSubmitter};
+use fabstir_llm_node::contracts::proofs::{is_nonce_error, is_retryable_proof_error};
+use fabstir_llm_node::contracts::{
+    is_deferred, ProofConfig, ProofData, ProofStatus, ProofSubmitter,
+};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -312,6 +315,101 @@ async fn test_proof_resubmission_on_failure() {
     assert!(metrics.successful_submissions > 0);
 }
 
+#[test]
+fn test_resubmission_backoff_grows_and_caps() {
+    let config = ProofConfig {
+        proof_system_address: Address::zero(),
+        ezkl_verifier_address: Address::zero(),
+        proof_generation_timeout: Duration::from_secs(300),
+        max_proof_size: 10 * 1024,
+        challenge_period: Duration::from_secs(86400),
+        enable_batch_submission: false,
+        batch_size: 5,
+        use_proof_compression: false,
+        store_proofs_on_ipfs: false,
+        max_proof_delay: Duration::from_secs(3600),
+        max_resubmission_attempts: 5,
+        resubmission_delay: Duration::from_millis(100),
+        max_resubmission_delay: Duration::from_millis(500),
+        resubmission_backoff_multiplier: 2.0,
+        resubmission_jitter: 0.2,
+    };
+
+    assert_eq!(config.resubmission_backoff(1), Duration::from_millis(100));
+    assert_eq!(config.resubmission_backoff(2), Duration::from_millis(200));
+    assert_eq!(config.resubmission_backoff(3), Duration::from_millis(400));
+    // Capped at max_resubmission_delay
+    assert_eq!(config.resubmission_backoff(4), Duration::from_millis(500));
+    assert_eq!(config.resubmission_backoff(50), Duration::from_millis(500));
+}
+
+#[test]
+fn test_retryable_proof_errors() {
+    assert!(is_retryable_proof_error(&anyhow::anyhow!(
+        "request timed out"
+    )));
+    assert!(is_retryable_proof_error(&anyhow::anyhow!(
+        "(code: -32000, message: nonce too low, data: None)"
+    )));
+    assert!(!is_retryable_proof_error(&anyhow::anyhow!(
+        "execution reverted: job already has proof"
+    )));
+    assert!(!is_retryable_proof_error(&anyhow::anyhow!(
+        "insufficient funds for gas * price + value"
+    )));
+}
+
+#[test]
+fn test_nonce_errors() {
+    assert!(is_nonce_error(&anyhow::anyhow!(
+        "(code: -32000, message: nonce too low, data: None)"
+    )));
+    assert!(is_nonce_error(&anyhow::anyhow!(
+        "replacement transaction underpriced"
+    )));
+    assert!(!is_nonce_error(&anyhow::anyhow!("request timed out")));
+    assert!(!is_nonce_error(&anyhow::anyhow!(
+        "execution reverted: job already has proof"
+    )));
+}
+
+#[tokio::test]
+async fn test_deferred_proof_is_queued_once_without_retries() {
+    let config = ProofConfig {
+        max_resubmission_attempts: 3,
+        resubmission_delay: Duration::from_millis(10),
+        ..Default::default()
+    };
+    // A zero ceiling defers every submission
+    let web3_client = create_gas_capped_web3_client(0.0).await;
+    let mut submitter = ProofSubmitter::new(config, web3_client)
+        .await
+        .expect("Failed to create proof submitter");
+    let mut events = submitter.start_monitoring().await;
+
+    let proof = generate_test_proof(U256::from(42));
+    let first = submitter.submit_proof_with_retry(proof.clone()).await;
+    assert!(is_deferred(&first.unwrap_err()));
+    let second = submitter.submit_proof_with_retry(proof).await;
+    assert!(is_deferred(&second.unwrap_err()));
+
+    // One queue entry and one event, however often the job is submitted
+    assert_eq!(submitter.deferred_count(), 1);
+    assert_eq!(submitter.get_metrics().retry_count, 0);
+    let mut deferred_events = 0;
+    while let Ok(event) = events.try_recv() {
+        match event {
+            ProofEvent::Deferred { job_id, .. } => {
+                assert_eq!(job_id, U256::from(42));
+                deferred_events += 1;
+            }
+            ProofEvent::RetryScheduled { .. } => panic!("deferral must not be retried"),
+            _ => {}
+        }
+    }
+    assert_eq!(deferred_events, 1);
+}
+
 #[tokio::test]
 async fn test_proof_validation_before_submission() {
     let config = ProofConfig::default();
@@ -456,6 +554,18 @@ async fn get_job_timestamp(client: &Web3Client, job_id: U256) -> u64 {
     1234567890
 }
 
+async fn create_gas_capped_web3_client(max_gas_price_gwei: f64) -> Arc<Web3Client> {
+    let config = Web3Config {
+        max_gas_price_gwei: Some(max_gas_price_gwei),
+        ..Default::default()
+    };
+    Arc::new(
+        Web3Client::new(config)
+            .await
+            .expect("Failed to create Web3 client"),
+    )
+}
+
 fn generate_test_proof(job_id: U256) -> ProofData {
     ProofData {
         job_id,
@@ -15,6 +15,7 @@ async fn test_web3_client_connection() {
         private_key: None,
         max_reconnection_attempts: 3,
         reconnection_delay: Duration::from_millis(100),
+        ..Default::default()
     };
 
     let client = Web3Client::new(config)
@@ -40,6 +41,7 @@ async fn test_base_network_connection() {
         private_key: None,
         max_reconnection_attempts: 3,
         reconnection_delay: Duration::from_millis(100),
+        ..Default::default()
     };
 
     let client = Web3Client::new(config)
@@ -66,6 +68,7 @@ async fn test_wallet_management() {
         private_key: Some(private_key.to_string()),
         max_reconnection_attempts: 3,
         reconnection_delay: Duration::from_millis(100),
+        ..Default::default()
     };
 
     let client = Web3Client::new(config)
@@ -268,6 +271,7 @@ async fn test_reconnection_on_failure() {
         private_key: None,
         max_reconnection_attempts: 3,
         reconnection_delay: Duration::from_millis(100),
+        ..Default::default()
     };
 
     let client = Web3Client::new(config).await;
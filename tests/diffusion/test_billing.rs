@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: BUSL-1.1
 //! Tests for image generation billing (Phase 5.1)
 
-use fabstir_llm_node::diffusion::billing::{calculate_generation_units, ImageGenerationTracker};
+use fabstir_llm_node::diffusion::billing::{
+    calculate_generation_units, units_to_tokens, ImageGenerationTracker, ImagePriceTable,
+};
+use fabstir_llm_node::diffusion::ImageSize;
 
 #[test]
 fn test_calculate_units_1024x1024_20steps_1x() {
@@ -85,3 +88,59 @@ fn test_image_generation_units_to_tokens_conversion() {
     let tokens_256 = (units_256 * 1000.0).ceil() as u64;
     assert_eq!(tokens_256, 13);
 }
+
+#[test]
+fn test_default_price_table_matches_generation_units() {
+    let table = ImagePriceTable::default();
+    for (size, steps) in [("1024x1024", 4), ("512x512", 20), ("1024x768", 50)] {
+        let size = ImageSize::parse(size).unwrap();
+        let charge = table.charge(&size, steps, "flux2-klein-4b");
+        let expected = calculate_generation_units(size.width, size.height, steps, 1.0);
+        assert!((charge.total_units - expected).abs() < 0.001);
+    }
+}
+
+#[test]
+fn test_price_table_itemizes_charge() {
+    let table = ImagePriceTable {
+        base_units: 0.1,
+        units_per_megapixel: 0.2,
+        units_per_megapixel_step: 0.05,
+        ..Default::default()
+    };
+    let charge = table.charge(&ImageSize::parse("1024x1024").unwrap(), 4, "any-model");
+
+    assert!((charge.base_units - 0.1).abs() < 0.001);
+    assert!((charge.megapixel_units - 0.2).abs() < 0.001);
+    assert!((charge.step_units - 0.2).abs() < 0.001);
+    assert!((charge.total_units - 0.5).abs() < 0.001);
+    assert_eq!(charge.steps, 4);
+    assert_eq!(charge.model_multiplier, 1.0);
+}
+
+#[test]
+fn test_price_table_applies_model_multiplier() {
+    let mut table = ImagePriceTable {
+        base_units: 0.1,
+        ..Default::default()
+    };
+    table
+        .model_multipliers
+        .insert("premium-model".to_string(), 2.0);
+    let size = ImageSize::parse("1024x1024").unwrap();
+
+    let premium = table.charge(&size, 20, "premium-model");
+    assert_eq!(premium.model_multiplier, 2.0);
+    assert!((premium.base_units - 0.2).abs() < 0.001);
+    assert!((premium.total_units - 2.2).abs() < 0.001);
+
+    let standard = table.charge(&size, 20, "other-model");
+    assert!((standard.total_units - 1.1).abs() < 0.001);
+}
+
+#[test]
+fn test_units_to_tokens_rounds_up() {
+    assert_eq!(units_to_tokens(0.2), 200);
+    assert_eq!(units_to_tokens(0.0131), 14);
+    assert_eq!(units_to_tokens(0.0), 0);
+}
@@ -4,7 +4,7 @@
 
 use fabstir_llm_node::diffusion::client::{
     DiffusionClient, DiffusionResult, ImageGenerationRequest, ImageSize, OpenAIImageResponse,
-    ALLOWED_SIZES,
+    ALLOWED_SIZES, MAX_SEED,
 };
 
 // ===== Sub-phase 1.1: Core Types =====
@@ -134,6 +134,37 @@ fn test_image_generation_request_validate_steps_over_100() {
     assert!(result.unwrap_err().contains("steps"));
 }
 
+#[test]
+fn test_image_generation_request_validate_seed_out_of_range() {
+    let mut request = ImageGenerationRequest {
+        prompt: "A cat".to_string(),
+        model: None,
+        size: "1024x1024".to_string(),
+        steps: 4,
+        seed: Some(MAX_SEED + 1),
+        negative_prompt: Some("blurry".to_string()),
+        guidance_scale: 3.5,
+        response_format: "b64_json".to_string(),
+        n: 1,
+    };
+    let result = request.validate();
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("seed"));
+
+    request.seed = Some(MAX_SEED);
+    assert!(request.validate().is_ok());
+}
+
+#[test]
+fn test_image_generation_request_effective_seed() {
+    let mut request: ImageGenerationRequest =
+        serde_json::from_value(serde_json::json!({ "prompt": "A cat" })).unwrap();
+    assert!(request.effective_seed() <= MAX_SEED);
+
+    request.seed = Some(42);
+    assert_eq!(request.effective_seed(), 42);
+}
+
 #[test]
 fn test_image_generation_request_validate_valid() {
     let request = ImageGenerationRequest {
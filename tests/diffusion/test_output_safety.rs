@@ -66,3 +66,27 @@ async fn test_classify_image_returns_unsafe_when_vlm_unavailable() {
     );
     assert!(result.reason.is_some());
 }
+
+#[test]
+fn test_classify_applies_category_thresholds() {
+    let classifier = OutputSafetyClassifier::new(
+        SafetyConfig::default().with_category_threshold(SafetyCategory::Violence, 0.7),
+    );
+
+    let result = classifier.classify(
+        r#"{"is_safe": false, "category": "violence", "reason": "cartoon fight", "confidence": 0.4}"#,
+    );
+    assert!(
+        result.is_safe,
+        "Violence below its threshold should be allowed"
+    );
+
+    let result = classifier.classify(
+        r#"{"is_safe": false, "category": "violence", "reason": "blood", "confidence": 0.9}"#,
+    );
+    assert!(!result.is_safe);
+    assert_eq!(result.category, Some(SafetyCategory::Violence));
+
+    // Malformed output has no category, so no threshold can allow it
+    assert!(!classifier.classify("not json").is_safe);
+}
@@ -2,8 +2,12 @@
 // SPDX-License-Identifier: BUSL-1.1
 //! Tests for prompt safety classifier (Sub-phase 2.2)
 
-use fabstir_llm_node::diffusion::prompt_safety::PromptSafetyClassifier;
+use fabstir_llm_node::diffusion::prompt_safety::{PromptSafetyCache, PromptSafetyClassifier};
 use fabstir_llm_node::diffusion::safety::{SafetyCategory, SafetyConfig, SafetyLevel};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[test]
 fn test_keyword_blocklist_detects_unsafe_terms() {
@@ -74,6 +78,7 @@ fn test_strict_blocks_more_than_moderate() {
             SafetyCategory::Deceptive,
         ],
         custom_blocked_terms: Vec::new(),
+        category_thresholds: HashMap::new(),
     });
     let moderate = PromptSafetyClassifier::new(SafetyConfig {
         level: SafetyLevel::Moderate,
@@ -84,6 +89,7 @@ fn test_strict_blocks_more_than_moderate() {
             SafetyCategory::SelfHarm,
         ],
         custom_blocked_terms: Vec::new(),
+        category_thresholds: HashMap::new(),
     });
 
     // Strict should block a category that moderate does not
@@ -106,6 +112,7 @@ fn test_permissive_only_blocks_illegal_and_self_harm() {
         level: SafetyLevel::Permissive,
         blocked_categories: vec![SafetyCategory::Illegal, SafetyCategory::SelfHarm],
         custom_blocked_terms: Vec::new(),
+        category_thresholds: HashMap::new(),
     });
 
     // Violence should be allowed in permissive mode
@@ -123,3 +130,133 @@ fn test_permissive_only_blocks_illegal_and_self_harm() {
     let result = permissive.classify(illegal_response);
     assert!(!result.is_safe, "Permissive should still block illegal");
 }
+
+#[test]
+fn test_category_thresholds_apply_per_category() {
+    // Strict on sexual content, lenient on violence
+    let classifier = PromptSafetyClassifier::new(
+        SafetyConfig::default()
+            .with_category_threshold(SafetyCategory::Sexual, 0.1)
+            .with_category_threshold(SafetyCategory::Violence, 0.9),
+    );
+
+    let result = classifier.classify(
+        r#"{"is_safe": false, "category": "sexual", "reason": "suggestive", "confidence": 0.3}"#,
+    );
+    assert!(!result.is_safe);
+    assert_eq!(result.category, Some(SafetyCategory::Sexual));
+
+    let result = classifier.classify(
+        r#"{"is_safe": false, "category": "violence", "reason": "fight", "confidence": 0.6}"#,
+    );
+    assert!(
+        result.is_safe,
+        "Violence below its threshold should be allowed"
+    );
+
+    let result = classifier.classify(
+        r#"{"is_safe": false, "category": "violence", "reason": "gore", "confidence": 0.95}"#,
+    );
+    assert!(!result.is_safe);
+    assert_eq!(result.category, Some(SafetyCategory::Violence));
+
+    // Keyword matches are fully confident
+    let result = classifier.check_keywords("a scene of graphic violence");
+    assert!(!result.is_safe);
+    assert_eq!(result.category, Some(SafetyCategory::Violence));
+}
+
+#[test]
+fn test_threshold_above_one_allows_keywords() {
+    let classifier = PromptSafetyClassifier::new(
+        SafetyConfig::default().with_category_threshold(SafetyCategory::Violence, 1.1),
+    );
+    assert!(
+        classifier
+            .check_keywords("a scene of graphic violence")
+            .is_safe
+    );
+}
+
+#[test]
+fn test_attest_records_checked_categories() {
+    let config = SafetyConfig::default().with_category_threshold(SafetyCategory::Hate, 0.5);
+    let classifier = PromptSafetyClassifier::new(config);
+    let result = classifier.check_keywords("a mountain lake");
+    let attestation = classifier.attest("a mountain lake", &result);
+
+    assert!(attestation.prompt_safe);
+    assert_eq!(
+        attestation.checked_categories,
+        vec![
+            SafetyCategory::Violence,
+            SafetyCategory::Sexual,
+            SafetyCategory::Hate,
+            SafetyCategory::SelfHarm,
+            SafetyCategory::Illegal,
+        ]
+    );
+    assert!(attestation.output_hash.is_none());
+}
+
+fn cache(capacity: usize, ttl: Duration) -> Arc<PromptSafetyCache> {
+    Arc::new(PromptSafetyCache::new(
+        NonZeroUsize::new(capacity).unwrap(),
+        ttl,
+    ))
+}
+
+#[test]
+fn test_cached_result_returns_earlier_decision() {
+    let cache = cache(8, Duration::from_secs(60));
+    let classifier = PromptSafetyClassifier::new(SafetyConfig::default()).with_cache(cache.clone());
+
+    assert!(classifier.cached_result("A knight in battle").is_none());
+    let result = classifier.classify_prompt(
+        "A knight in battle",
+        r#"{"is_safe": false, "category": "violence", "reason": "battle", "confidence": 0.9}"#,
+    );
+    assert!(!result.is_safe);
+
+    // Normalized prompt text hits the same entry
+    let cached = classifier.cached_result("  a KNIGHT in   battle ").unwrap();
+    assert!(!cached.is_safe);
+    assert_eq!(cached.category, Some(SafetyCategory::Violence));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_cache_is_keyed_on_thresholds() {
+    let cache = cache(8, Duration::from_secs(60));
+    let strict = PromptSafetyClassifier::new(SafetyConfig::default()).with_cache(cache.clone());
+    strict.classify_prompt(
+        "A knight in battle",
+        r#"{"is_safe": false, "category": "violence", "reason": "battle", "confidence": 0.6}"#,
+    );
+    assert!(!strict.cached_result("A knight in battle").unwrap().is_safe);
+
+    // Changed thresholds must not reuse the strict decision
+    let lenient = PromptSafetyClassifier::new(
+        SafetyConfig::default().with_category_threshold(SafetyCategory::Violence, 0.8),
+    )
+    .with_cache(cache);
+    assert!(lenient.cached_result("A knight in battle").is_none());
+}
+
+#[test]
+fn test_cache_entries_expire_and_skip_malformed() {
+    let cache = cache(8, Duration::from_millis(20));
+    let classifier = PromptSafetyClassifier::new(SafetyConfig::default()).with_cache(cache.clone());
+
+    let result = classifier.classify_prompt("a lake", "not json");
+    assert!(result.category.is_some());
+    assert!(cache.is_empty(), "Malformed responses should not be cached");
+
+    classifier.classify_prompt(
+        "a lake",
+        r#"{"is_safe": true, "category": null, "reason": null}"#,
+    );
+    assert!(classifier.cached_result("a lake").is_some());
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(classifier.cached_result("a lake").is_none());
+}
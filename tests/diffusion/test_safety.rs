@@ -65,6 +65,7 @@ fn test_safety_config_with_blocked_categories() {
         level: SafetyLevel::Moderate,
         blocked_categories: vec![SafetyCategory::Violence, SafetyCategory::Hate],
         custom_blocked_terms: vec!["badword".to_string()],
+        category_thresholds: Default::default(),
     };
     assert_eq!(config.level, SafetyLevel::Moderate);
     assert_eq!(config.blocked_categories.len(), 2);
@@ -124,6 +125,7 @@ fn test_safety_attestation_compute_hash_returns_32_bytes() {
         output_safe: None,
         safety_level: SafetyLevel::Strict,
         timestamp: 1700000000,
+        checked_categories: vec![],
     };
     let hash = attestation.compute_hash();
     assert_eq!(hash.len(), 32);
@@ -140,6 +142,7 @@ fn test_safety_attestation_compute_hash_is_deterministic() {
         output_safe: Some(true),
         safety_level: SafetyLevel::Moderate,
         timestamp: 1700000000,
+        checked_categories: vec![],
     };
     let hash1 = attestation.compute_hash();
     let hash2 = attestation.compute_hash();
@@ -155,6 +158,7 @@ fn test_safety_attestation_to_bytes() {
         output_safe: None,
         safety_level: SafetyLevel::Permissive,
         timestamp: 1700000000,
+        checked_categories: vec![],
     };
     let bytes = attestation.to_bytes();
     assert!(!bytes.is_empty());
@@ -164,3 +168,52 @@ fn test_safety_attestation_to_bytes() {
     assert_eq!(deserialized.timestamp, 1700000000);
     assert_eq!(deserialized.safety_level, SafetyLevel::Permissive);
 }
+
+#[test]
+fn test_safety_config_threshold_for() {
+    let config = SafetyConfig::default()
+        .with_category_threshold(SafetyCategory::Violence, 0.8)
+        .with_category_threshold(SafetyCategory::Hate, 0.4);
+
+    // Explicit thresholds win; other blocked categories block at any confidence
+    assert_eq!(config.threshold_for(SafetyCategory::Violence), Some(0.8));
+    assert_eq!(config.threshold_for(SafetyCategory::Hate), Some(0.4));
+    assert_eq!(config.threshold_for(SafetyCategory::Sexual), Some(0.0));
+    assert_eq!(config.threshold_for(SafetyCategory::Deceptive), None);
+
+    assert!(!config.blocks(SafetyCategory::Violence, 0.5));
+    assert!(config.blocks(SafetyCategory::Violence, 0.8));
+    assert!(!config.blocks(SafetyCategory::Deceptive, 1.0));
+}
+
+#[test]
+fn test_safety_config_evaluate_keeps_uncategorized_unsafe() {
+    let config = SafetyConfig::default();
+    let result = config.evaluate(SafetyResult {
+        is_safe: false,
+        category: None,
+        reason: Some("classifier unavailable".to_string()),
+        confidence: 0.0,
+    });
+    assert!(!result.is_safe);
+}
+
+#[test]
+fn test_safety_attestation_hash_covers_checked_categories() {
+    let result = SafetyResult {
+        is_safe: true,
+        category: None,
+        reason: None,
+        confidence: 1.0,
+    };
+    let strict = SafetyAttestation::for_prompt("a cat", &result, &SafetyConfig::default());
+    let mut lenient = strict.clone();
+    lenient.checked_categories = vec![SafetyCategory::Illegal];
+
+    assert_eq!(strict.checked_categories.len(), 4);
+    assert_ne!(strict.compute_hash(), lenient.compute_hash());
+
+    let with_output = strict.with_output(b"image bytes", &result);
+    assert_eq!(with_output.output_safe, Some(true));
+    assert!(with_output.output_hash.is_some());
+}
@@ -99,6 +99,9 @@ fn test_image_generation_result_contains_safety_info() {
             model_multiplier: 1.0,
             megapixels: 1.0,
             steps: 4,
+            base_units: 0.0,
+            megapixel_units: 0.0,
+            step_units: 0.2,
         },
     };
 
@@ -27,11 +27,15 @@ async fn test_ezkl_end_to_end_proof_flow() -> Result<()> {
                 role: "user".to_string(),
                 content: "What is machine learning?".to_string(),
                 timestamp: Some(chrono::Utc::now().timestamp()),
+                tool_calls: None,
+                tool_call_id: None,
             },
             Message {
                 role: "assistant".to_string(),
                 content: "Machine learning is a subset of AI...".to_string(),
                 timestamp: Some(chrono::Utc::now().timestamp()),
+                tool_calls: None,
+                tool_call_id: None,
             },
         ],
     };
@@ -273,4 +273,81 @@ mod tests {
             .any(|period| matches!(period.status, AvailabilityStatus::Maintenance));
         assert!(has_maintenance);
     }
+
+    #[tokio::test]
+    async fn test_is_available_at_respects_maintenance() {
+        let mut manager = AvailabilityManager::new();
+        let start = Utc::now() + Duration::hours(1);
+        manager
+            .schedule_maintenance(MaintenanceWindow {
+                id: "maint-003".to_string(),
+                start_time: start,
+                end_time: start + Duration::hours(1),
+                description: "GPU swap".to_string(),
+                affects_models: vec!["llama-3.2-1b".to_string()],
+            })
+            .await
+            .unwrap();
+
+        assert!(manager.is_available_at(Utc::now()).await);
+        assert!(!manager.is_available_at(start + Duration::minutes(30)).await);
+        assert!(manager.is_available_at(start + Duration::hours(2)).await);
+
+        // Only the listed model is affected
+        let end = start + Duration::minutes(10);
+        assert!(manager
+            .maintenance_conflict("llama-3.2-1b", Utc::now(), end)
+            .await
+            .is_some());
+        assert!(manager
+            .maintenance_conflict("mistral-7b", Utc::now(), end)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_admit_job_before_maintenance() {
+        let mut manager = AvailabilityManager::new();
+        let mut changes = manager.subscribe_to_changes().await;
+        manager
+            .schedule_maintenance(MaintenanceWindow {
+                id: "maint-004".to_string(),
+                start_time: Utc::now() + Duration::minutes(5),
+                end_time: Utc::now() + Duration::minutes(35),
+                description: "Restart".to_string(),
+                affects_models: vec![],
+            })
+            .await
+            .unwrap();
+
+        // Finishes before the window
+        assert!(manager
+            .admit_job("llama-3.2-1b", Duration::minutes(2), Duration::zero())
+            .await
+            .is_ok());
+
+        // Runs into the window
+        let result = manager
+            .admit_job("llama-3.2-1b", Duration::minutes(10), Duration::minutes(1))
+            .await;
+        assert!(matches!(result, Err(ScheduleError::MaintenanceConflict(id)) if id == "maint-004"));
+        assert_eq!(
+            manager.get_current_status().await,
+            AvailabilityStatus::MaintenancePending
+        );
+        assert_eq!(
+            changes.recv().await.unwrap().new_status,
+            AvailabilityStatus::MaintenancePending
+        );
+
+        // Short enough for the grace period
+        assert!(manager
+            .admit_job("llama-3.2-1b", Duration::minutes(10), Duration::minutes(15))
+            .await
+            .is_ok());
+        assert_eq!(
+            manager.get_current_status().await,
+            AvailabilityStatus::Available
+        );
+    }
 }
@@ -1,8 +1,10 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use fabstir_llm_node::host::{
-    HostingError, ModelConfig, ModelHostingManager, ModelMetadata, ModelParameters, ModelStatus,
+    HostingError, ModelConfig, ModelConfigField, ModelHostingManager, ModelMetadata,
+    ModelParameters, ModelStatus, RopeScaling,
 };
+use fabstir_llm_node::inference::ChatTemplate;
 use std::collections::HashMap;
 
 #[cfg(test)]
@@ -30,6 +32,9 @@ mod tests {
                 version: "3.2".to_string(),
             },
             status: ModelStatus::Enabled,
+            price_per_token: Some(0.0001),
+            chat_template: None,
+            rope_scaling: None,
         }
     }
 
@@ -204,4 +209,95 @@ mod tests {
         assert_eq!(models.len(), 1);
         assert_eq!(models[0].model_id, "llama-3.2-1b-instruct");
     }
+
+    #[tokio::test]
+    async fn test_reload_applies_config_in_place() {
+        let mut manager = ModelHostingManager::new();
+        manager.add_model(create_test_model_config()).await.unwrap();
+
+        let mut config = create_test_model_config();
+        config.price_per_token = Some(0.0002);
+        config.chat_template = Some(ChatTemplate::Llama2);
+        config.rope_scaling = Some(RopeScaling {
+            freq_base: 10000.0,
+            freq_scale: 0.5,
+        });
+        config.parameters.context_size = 8192;
+
+        let reload = manager
+            .reload_model_config("llama-3.2-1b-instruct", config)
+            .await
+            .unwrap();
+        assert!(!reload.needs_reload());
+        assert_eq!(
+            reload.applied,
+            vec![
+                ModelConfigField::ContextSize,
+                ModelConfigField::Pricing,
+                ModelConfigField::ChatTemplate,
+                ModelConfigField::RopeScaling,
+            ]
+        );
+
+        let model = manager.get_model("llama-3.2-1b-instruct").await.unwrap();
+        assert_eq!(model.price_per_token, Some(0.0002));
+        assert_eq!(model.status, ModelStatus::Enabled);
+
+        // Same config again changes nothing
+        let reload = manager
+            .reload_model_config("llama-3.2-1b-instruct", model)
+            .await
+            .unwrap();
+        assert!(reload.is_unchanged());
+    }
+
+    #[tokio::test]
+    async fn test_reload_with_new_weights() {
+        let mut manager = ModelHostingManager::new();
+        manager.add_model(create_test_model_config()).await.unwrap();
+
+        let mut config = create_test_model_config();
+        config.model_path = "/models/llama-3.2-1b-instruct-q8.gguf".to_string();
+        config.price_per_token = Some(0.0003);
+
+        let reload = manager
+            .reload_model_config("llama-3.2-1b-instruct", config)
+            .await
+            .unwrap();
+        assert!(reload.needs_reload());
+        assert_eq!(reload.requires_reload, vec![ModelConfigField::ModelPath]);
+        assert_eq!(reload.applied, vec![ModelConfigField::Pricing]);
+
+        let model = manager.get_model("llama-3.2-1b-instruct").await.unwrap();
+        assert_eq!(model.status, ModelStatus::Loading);
+    }
+
+    #[tokio::test]
+    async fn test_reload_rejects_invalid_config() {
+        let mut manager = ModelHostingManager::new();
+        manager.add_model(create_test_model_config()).await.unwrap();
+
+        let mut config = create_test_model_config();
+        config.model_path = "/invalid/path.gguf".to_string();
+        let result = manager
+            .reload_model_config("llama-3.2-1b-instruct", config)
+            .await;
+        assert!(matches!(result, Err(HostingError::ModelNotFound(_))));
+
+        let mut config = create_test_model_config();
+        config.price_per_token = Some(-1.0);
+        let result = manager
+            .reload_model_config("llama-3.2-1b-instruct", config)
+            .await;
+        assert!(matches!(result, Err(HostingError::InvalidConfiguration(_))));
+
+        let result = manager
+            .reload_model_config("mistral-7b-instruct", create_test_model_config())
+            .await;
+        assert!(matches!(result, Err(HostingError::InvalidConfiguration(_))));
+
+        // Nothing was applied
+        let model = manager.get_model("llama-3.2-1b-instruct").await.unwrap();
+        assert_eq!(model, create_test_model_config());
+    }
 }
@@ -3,7 +3,7 @@
 use chrono::{DateTime, Duration, Utc};
 use fabstir_llm_node::host::{
     Currency, DynamicPricingConfig, PriceUpdate, PricingError, PricingManager, PricingModel,
-    PricingTier,
+    PricingTier, ResourceSummary, SurgePricingConfig, SurgeThreshold,
 };
 use std::collections::HashMap;
 
@@ -242,4 +242,97 @@ mod tests {
         let result = manager.set_pricing(pricing).await;
         assert!(matches!(result, Err(PricingError::BelowMinimum(_))));
     }
+
+    #[tokio::test]
+    async fn test_surge_pricing_follows_utilization() {
+        let mut manager = PricingManager::new();
+        manager.set_pricing(create_test_pricing()).await.unwrap();
+        manager
+            .set_surge_pricing(SurgePricingConfig::default())
+            .await
+            .unwrap();
+        let mut updates = manager.subscribe_to_price_updates().await;
+
+        // Below every threshold: base price, no update
+        assert!(manager.update_utilization(0.5).await.is_none());
+        assert_eq!(manager.get_surge_multiplier().await, 1.0);
+
+        // Surges apply immediately
+        let update = manager.update_utilization(0.9).await.unwrap();
+        assert_eq!(update.multiplier, 1.5);
+        assert_eq!(update.affected_models, vec!["llama-3.2-1b-instruct"]);
+        assert_eq!(updates.recv().await.unwrap().multiplier, 1.5);
+
+        let price = manager
+            .calculate_token_price("llama-3.2-1b-instruct", 1_000)
+            .await
+            .unwrap();
+        assert!((price - 0.0015).abs() < 1e-12);
+
+        // Load drops: decay halfway back each update, then snap to base
+        let update = manager.update_utilization(0.1).await.unwrap();
+        assert!((update.multiplier - 1.25).abs() < 1e-9);
+        for _ in 0..5 {
+            manager.update_utilization(0.1).await.unwrap();
+        }
+        assert_eq!(manager.get_surge_multiplier().await, 1.0);
+        assert!(manager.update_utilization(0.1).await.is_none());
+
+        let history = manager
+            .get_pricing_history("llama-3.2-1b-instruct", 100)
+            .await;
+        assert!(history
+            .iter()
+            .any(|entry| entry.reason.starts_with("Surge pricing")));
+    }
+
+    #[tokio::test]
+    async fn test_surge_multiplier_is_clamped() {
+        let mut manager = PricingManager::new();
+        manager.set_pricing(create_test_pricing()).await.unwrap();
+        manager
+            .set_surge_pricing(SurgePricingConfig {
+                thresholds: vec![SurgeThreshold {
+                    utilization: 0.5,
+                    multiplier: 10.0,
+                }],
+                max_multiplier: 3.0,
+                decay_factor: 1.0,
+            })
+            .await
+            .unwrap();
+
+        let summary = ResourceSummary {
+            timestamp: 0,
+            cpu_usage: 20.0,
+            memory_usage: 40.0,
+            gpu_usage: vec![99.0],
+            network_bandwidth: 0.0,
+            health_score: 0.0,
+            active_alerts: 0,
+        };
+        assert!((summary.utilization() - 0.99).abs() < 1e-9);
+
+        let update = manager.update_from_resources(&summary).await.unwrap();
+        assert_eq!(update.multiplier, 3.0);
+
+        // decay_factor 1.0 drops straight back to base
+        let update = manager.update_utilization(0.0).await.unwrap();
+        assert_eq!(update.multiplier, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_surge_config_rejected() {
+        let mut manager = PricingManager::new();
+        let result = manager
+            .set_surge_pricing(SurgePricingConfig {
+                max_multiplier: 0.5,
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(result, Err(PricingError::InvalidConfiguration(_))));
+
+        // Without surge pricing, utilization does not move prices
+        assert!(manager.update_utilization(1.0).await.is_none());
+    }
 }
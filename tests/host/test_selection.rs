@@ -5,6 +5,7 @@ use fabstir_llm_node::host::registry::HostInfo;
 use fabstir_llm_node::host::selection::{
     HostSelector, JobRequirements, PerformanceMetrics, ScoringWeights,
 };
+use fabstir_llm_node::p2p::ModelAnnouncement;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -377,3 +378,116 @@ async fn test_concurrent_metric_updates() {
     let count = selector.get_metrics_count().await;
     assert!(count >= 10);
 }
+
+#[tokio::test]
+async fn test_selection_from_model_announcements() {
+    let announcement = |host_address: Option<&str>, models: &[&str], load: u32| ModelAnnouncement {
+        peer_id: "12D3KooWtest".to_string(),
+        host_address: host_address.map(String::from),
+        models: models.iter().map(|m| m.to_string()).collect(),
+        price_per_token: 0.0002,
+        current_load: load,
+        timestamp: 0,
+    };
+    let busy = "0x1111111111111111111111111111111111111111";
+    let idle = "0x2222222222222222222222222222222222222222";
+
+    let selector = HostSelector::new();
+    let hosts = selector
+        .apply_model_announcements(&[
+            announcement(Some(busy), &["llama-7b"], 8),
+            announcement(Some(idle), &["llama-7b", "mistral-7b"], 0),
+            announcement(None, &["llama-7b"], 0),
+        ])
+        .await;
+
+    // The announcement without a host address cannot be selected
+    assert_eq!(hosts.len(), 2);
+    assert_eq!(selector.get_metrics_count().await, 2);
+
+    let requirements = JobRequirements {
+        model_id: "mistral-7b".to_string(),
+        min_ram_gb: 0,
+        max_cost_per_token: None,
+        min_reliability: None,
+    };
+    let selected = selector
+        .select_best_host(hosts.clone(), &requirements)
+        .await;
+    assert_eq!(selected, Some(idle.parse().unwrap()));
+
+    let least_loaded = selector.select_with_load_balancing(hosts).await;
+    assert_eq!(least_loaded, Some(idle.parse().unwrap()));
+}
+
+#[tokio::test]
+async fn test_select_with_explanation() {
+    let (hosts, metrics) = create_mock_hosts_with_metrics();
+    let mut selector = HostSelector::new();
+    for (addr, metric) in metrics {
+        selector.update_performance_metrics(addr, metric).await;
+    }
+
+    let requirements = JobRequirements {
+        model_id: "llama-7b".to_string(),
+        min_ram_gb: 32,
+        max_cost_per_token: None,
+        min_reliability: None,
+    };
+    let explanation = selector
+        .select_with_explanation(hosts.clone(), &requirements)
+        .await;
+
+    // Same winner as select_best_host, ranked first among the candidates
+    let best = selector.select_best_host(hosts, &requirements).await;
+    assert_eq!(explanation.selected, best);
+    assert_eq!(explanation.candidates.len(), 3);
+    assert_eq!(Some(explanation.candidates[0].address), best);
+    assert!(explanation
+        .candidates
+        .windows(2)
+        .all(|pair| pair[0].breakdown.total >= pair[1].breakdown.total));
+
+    // Components carry the configured weights and add up to the total
+    let weights = ScoringWeights::default();
+    for candidate in &explanation.candidates {
+        let b = &candidate.breakdown;
+        assert_eq!(b.latency.weight, weights.performance);
+        assert_eq!(b.price.weight, weights.cost);
+        assert_eq!(b.reputation.weight, weights.reliability);
+        assert_eq!(b.load.weight, weights.load);
+        let sum =
+            b.latency.weighted() + b.price.weighted() + b.reputation.weighted() + b.load.weighted();
+        assert!((sum - b.total).abs() < 1e-9);
+    }
+
+    // The overloaded host loses on load
+    let overloaded = "0x4444444444444444444444444444444444444444"
+        .parse::<Address>()
+        .unwrap();
+    let overloaded = explanation
+        .candidates
+        .iter()
+        .find(|c| c.address == overloaded)
+        .unwrap();
+    assert!(explanation
+        .candidates
+        .iter()
+        .all(|c| c.breakdown.load.score >= overloaded.breakdown.load.score));
+}
+
+#[tokio::test]
+async fn test_explanation_without_candidates() {
+    let selector = HostSelector::new();
+    let requirements = JobRequirements {
+        model_id: "llama-7b".to_string(),
+        min_ram_gb: 0,
+        max_cost_per_token: None,
+        min_reliability: None,
+    };
+    let explanation = selector
+        .select_with_explanation(Vec::new(), &requirements)
+        .await;
+    assert_eq!(explanation.selected, None);
+    assert!(explanation.candidates.is_empty());
+}
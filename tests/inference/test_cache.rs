@@ -1,7 +1,8 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use fabstir_llm_node::inference::{
-    CacheConfig, CacheEntry, CacheKey, EvictionPolicy, InferenceCache,
+    CacheConfig, CacheEntry, CacheKey, EvictionPolicy, InferenceCache, PrefixCache,
+    PrefixCacheConfig,
 };
 use std::time::Duration;
 use tokio::time::sleep;
@@ -520,3 +521,73 @@ async fn test_cache_with_different_params() {
         );
     }
 }
+
+fn prefix_cache(max_entries: usize, max_memory_bytes: usize) -> PrefixCache {
+    PrefixCache::new(PrefixCacheConfig {
+        max_entries,
+        max_memory_bytes,
+        min_prefix_tokens: 4,
+    })
+    .expect("Failed to create prefix cache")
+}
+
+#[test]
+fn test_prefix_cache_reuses_system_prefix() {
+    let mut cache = prefix_cache(8, 1024);
+    let system: Vec<i32> = (1..=10).collect();
+
+    assert!(cache.should_store("llama-7b", &system));
+    assert!(cache.insert("llama-7b", system.clone(), vec![1; 64]));
+    assert!(!cache.should_store("llama-7b", &system));
+
+    let hit = cache.lookup("llama-7b", &system).expect("prefix hit");
+    assert_eq!(hit.reused_tokens, system.len());
+    assert_eq!(hit.state.len(), 64);
+
+    // Only the exact prefix matches; other models and prompts miss
+    assert!(cache.lookup("mistral-7b", &system).is_none());
+    assert!(cache.lookup("llama-7b", &system[..8]).is_none());
+    let mut edited = system.clone();
+    edited[9] = 99;
+    assert!(cache.lookup("llama-7b", &edited).is_none());
+
+    // Prefixes below the minimum are neither looked up nor stored
+    assert!(cache.lookup("llama-7b", &[1, 2, 3]).is_none());
+    assert!(!cache.should_store("llama-7b", &[1, 2, 3]));
+
+    let stats = cache.stats();
+    assert_eq!(stats.lookups, 4);
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 3);
+    assert_eq!(stats.tokens_reused, system.len());
+    assert_eq!(stats.hit_rate(), 0.25);
+}
+
+#[test]
+fn test_prefix_cache_eviction() {
+    let mut cache = prefix_cache(2, 100);
+    let prompt = |start: i32| -> Vec<i32> { (start..start + 8).collect() };
+
+    cache.insert("llama-7b", prompt(0), vec![0; 40]);
+    cache.insert("llama-7b", prompt(100), vec![0; 40]);
+    // Touch the first entry so the second is least recently used
+    assert!(cache.lookup("llama-7b", &prompt(0)).is_some());
+
+    // Entry limit evicts the LRU entry
+    cache.insert("llama-7b", prompt(200), vec![0; 40]);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.lookup("llama-7b", &prompt(100)).is_none());
+    assert!(cache.lookup("llama-7b", &prompt(0)).is_some());
+
+    // Memory budget evicts until the new state fits; oversized states are refused
+    cache.insert("llama-7b", prompt(300), vec![0; 90]);
+    assert_eq!(cache.len(), 1);
+    assert!(!cache.insert("llama-7b", prompt(400), vec![0; 101]));
+
+    let stats = cache.stats();
+    assert_eq!(stats.evictions, 3);
+    assert_eq!(stats.memory_bytes, 90);
+
+    assert_eq!(cache.invalidate_model("llama-7b"), 1);
+    assert!(cache.is_empty());
+}
@@ -22,6 +22,8 @@ async fn test_engine_initialization() {
         model_eviction_policy: "lru".to_string(),
         kv_cache_type_k: None,
         kv_cache_type_v: None,
+        default_timeout_ms: None,
+        draft_model: None,
     };
 
     let engine = LlmEngine::new(config)
@@ -382,10 +384,14 @@ async fn test_prompt_template_handling() {
         ChatMessage {
             role: "system".to_string(),
             content: "You are a helpful assistant.".to_string(),
+            tool_calls: None,
+            tool_call_id: None,
         },
         ChatMessage {
             role: "user".to_string(),
             content: "What is 2+2?".to_string(),
+            tool_calls: None,
+            tool_call_id: None,
         },
     ];
 
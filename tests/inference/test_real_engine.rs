@@ -23,6 +23,8 @@ async fn test_load_gguf_model() {
         model_eviction_policy: "lru".to_string(),
         kv_cache_type_k: None,
         kv_cache_type_v: None,
+        default_timeout_ms: None,
+        draft_model: None,
     };
 
     let mut engine = LlmEngine::new(config).await
@@ -318,6 +320,8 @@ async fn test_model_unloading() {
         model_eviction_policy: "lru".to_string(),
         kv_cache_type_k: None,
         kv_cache_type_v: None,
+        default_timeout_ms: None,
+        draft_model: None,
     };
 
     let mut engine = LlmEngine::new(config).await
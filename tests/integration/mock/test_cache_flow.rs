@@ -19,7 +19,7 @@ use fabstir_llm_node::{
     cache::{CacheConfig, CacheMetrics, PromptCache},
     embeddings::{EmbeddingConfig, EmbeddingGenerator},
     storage::{EnhancedS5Client, S5Config},
-    vector::{VectorDbClient, VectorDbConfig},
+    vector::{DistanceMetric, VectorDbClient, VectorDbConfig},
 };
 
 fn hash_prompt(prompt: &str) -> String {
@@ -72,7 +72,7 @@ async fn test_search_vector_db_for_similar_prompts() -> Result<()> {
         batch_size: 32,
         normalize: true,
     };
-    let generator = EmbeddingGenerator::new(embedding_config).await?;
+    let generator = EmbeddingGenerator::mock(embedding_config);
 
     // Store various cached prompts with embeddings
     let cached_prompts = vec![
@@ -236,6 +236,7 @@ async fn test_measure_cache_hit_rates() -> Result<()> {
         similarity_threshold: 0.8,
         ttl_seconds: 3600,
         max_cache_size_mb: 10,
+        metric: DistanceMetric::Cosine,
     };
     let cache = PromptCache::new(cache_config).await?;
 
@@ -302,6 +303,7 @@ async fn test_complete_cache_flow() -> Result<()> {
         similarity_threshold: 0.75,
         ttl_seconds: 3600,
         max_cache_size_mb: 10,
+        metric: DistanceMetric::Cosine,
     };
     let cache = PromptCache::new(cache_config).await?;
 
@@ -355,6 +357,7 @@ async fn test_cache_expiration_and_cleanup() -> Result<()> {
         similarity_threshold: 0.8,
         ttl_seconds: 2, // 2 second TTL
         max_cache_size_mb: 10,
+        metric: DistanceMetric::Cosine,
     };
     let cache = PromptCache::new(cache_config).await?;
 
@@ -388,6 +391,7 @@ async fn test_cache_expiration_and_cleanup() -> Result<()> {
         similarity_threshold: 0.8,
         ttl_seconds: 3600,
         max_cache_size_mb: 1, // Very small cache (1 MB)
+        metric: DistanceMetric::Cosine,
     };
     let large_cache = PromptCache::new(large_cache_config).await?;
 
@@ -418,6 +422,7 @@ async fn test_cache_performance_metrics() -> Result<()> {
         similarity_threshold: 0.8,
         ttl_seconds: 3600,
         max_cache_size_mb: 10,
+        metric: DistanceMetric::Cosine,
     };
     let cache = PromptCache::new(cache_config).await?;
 
@@ -68,7 +68,7 @@ async fn test_generate_embeddings_for_model() -> Result<()> {
         batch_size: 32,
         normalize: true,
     };
-    let generator = EmbeddingGenerator::new(embedding_config).await?;
+    let generator = EmbeddingGenerator::mock(embedding_config);
 
     // Model description for embedding
     let model_description = "Llama 3.2 1B Instruct is a lightweight language model \
@@ -113,7 +113,7 @@ async fn test_store_embeddings_in_vector_db() -> Result<()> {
         batch_size: 32,
         normalize: true,
     };
-    let generator = EmbeddingGenerator::new(embedding_config).await?;
+    let generator = EmbeddingGenerator::mock(embedding_config);
 
     // Generate embeddings for model descriptions
     let model_descriptions = vec![
@@ -163,7 +163,7 @@ async fn test_semantic_search_for_similar_models() -> Result<()> {
         batch_size: 32,
         normalize: true,
     };
-    let generator = EmbeddingGenerator::new(embedding_config).await?;
+    let generator = EmbeddingGenerator::mock(embedding_config);
 
     // Store various model embeddings
     let models = vec![
@@ -252,7 +252,7 @@ async fn test_complete_workflow_integration() -> Result<()> {
         batch_size: 32,
         normalize: true,
     };
-    let generator = EmbeddingGenerator::new(embedding_config).await?;
+    let generator = EmbeddingGenerator::mock(embedding_config);
 
     // Complete workflow for a model
     let model_id = "phi-3-mini";
@@ -332,7 +332,7 @@ async fn test_model_discovery_by_capability() -> Result<()> {
         batch_size: 32,
         normalize: true,
     };
-    let generator = EmbeddingGenerator::new(embedding_config).await?;
+    let generator = EmbeddingGenerator::mock(embedding_config);
 
     // Store models with different capabilities
     let models_with_capabilities = vec![
@@ -439,7 +439,7 @@ async fn test_model_versioning_workflow() -> Result<()> {
         batch_size: 32,
         normalize: true,
     };
-    let generator = EmbeddingGenerator::new(embedding_config).await?;
+    let generator = EmbeddingGenerator::mock(embedding_config);
 
     let base_model_id = "gemma-2b";
     let versions = vec![
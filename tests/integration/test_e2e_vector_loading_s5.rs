@@ -203,6 +203,9 @@ async fn test_complete_vector_loading_flow() {
                 total_chunks = total;
                 println!("   ✓ Chunk {}/{} downloaded", chunk_id + 1, total);
             }
+            LoadProgress::VectorsLoaded { loaded, total } => {
+                println!("   ✓ {}/{} vectors loaded", loaded, total);
+            }
             LoadProgress::IndexBuilding => {
                 println!("   ⚙️  Building HNSW index...");
             }
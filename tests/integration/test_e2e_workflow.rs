@@ -12,7 +12,7 @@ use anyhow::Result;
 use fabstir_llm_node::{
     cache::{PromptCache, CacheConfig, CacheMetrics},
     storage::{EnhancedS5Client, S5Config},
-    vector::{VectorDbClient, VectorDbConfig},
+    vector::{DistanceMetric, VectorDbClient, VectorDbConfig},
     embeddings::{EmbeddingGenerator, EmbeddingConfig},
 };
 use serde_json::json;
@@ -180,6 +180,7 @@ async fn test_measure_cache_hit_rates() -> Result<()> {
         similarity_threshold: 0.85, // High threshold for exact matches
         ttl_seconds: 3600,
         max_cache_size_mb: 100,
+        metric: DistanceMetric::Cosine,
     };
     
     let cache = PromptCache::new(cache_config).await?;
@@ -388,6 +389,7 @@ async fn test_cache_expiration_and_cleanup() -> Result<()> {
         similarity_threshold: 0.85,
         ttl_seconds: 2, // Very short TTL for testing
         max_cache_size_mb: 1, // Small size to trigger cleanup
+        metric: DistanceMetric::Cosine,
     };
     
     let cache = PromptCache::new(cache_config).await?;
@@ -441,6 +443,7 @@ async fn test_cache_performance_metrics() -> Result<()> {
         similarity_threshold: 0.85,
         ttl_seconds: 3600,
         max_cache_size_mb: 100,
+        metric: DistanceMetric::Cosine,
     };
     
     let cache = PromptCache::new(cache_config).await?;
@@ -11,11 +11,17 @@
 
 use anyhow::Result;
 use chrono::Utc;
+use fabstir_llm_node::config::chains::ChainRegistry;
 use fabstir_llm_node::results::packager::{InferenceResult, ResultMetadata};
 use fabstir_llm_node::results::proofs::{ProofGenerationConfig, ProofGenerator, ProofType};
+use fabstir_llm_node::settlement::dispute_window::DisputeWindow;
+use fabstir_llm_node::settlement::manager::SettlementManager;
+use fabstir_llm_node::settlement::queue::SettlementRequest;
+use fabstir_llm_node::settlement::types::{SettlementError, SettlementStatus};
 use fabstir_llm_node::settlement::validator::SettlementValidator;
 use fabstir_llm_node::storage::{ProofStore, ResultStore};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 fn create_test_result(job_id: u64, response: &str, tokens: u32) -> InferenceResult {
@@ -398,3 +404,139 @@ async fn test_legitimate_job_passes_dispute_checks() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_dispute_window_blocks_tampered_settlement() -> Result<()> {
+    // Scenario: Tampered output is only caught at release, after the window
+    let proof_gen = create_proof_generator();
+    let proof_store = Arc::new(RwLock::new(ProofStore::new()));
+    let result_store = Arc::new(RwLock::new(ResultStore::new()));
+
+    let validator = Arc::new(SettlementValidator::new(
+        proof_gen.clone(),
+        proof_store.clone(),
+        result_store.clone(),
+    ));
+    let window = DisputeWindow::new(Duration::from_millis(50)).with_validator(validator);
+
+    // Honest job and tampered job both submitted
+    let honest = create_test_result(9000, "Legitimate response", 100);
+    let proof = proof_gen.generate_proof(&honest).await?;
+    proof_store.write().await.store_proof(9000, proof).await?;
+    result_store
+        .write()
+        .await
+        .store_result(9000, honest)
+        .await?;
+
+    let original = create_test_result(9001, "Original output", 50);
+    let proof = proof_gen.generate_proof(&original).await?;
+    proof_store.write().await.store_proof(9001, proof).await?;
+    let tampered = create_test_result(9001, "Tampered output claiming more", 500);
+    result_store
+        .write()
+        .await
+        .store_result(9001, tampered)
+        .await?;
+
+    window.open(9000).await;
+    window.open(9001).await;
+
+    // Nothing is released while the challenge period runs
+    assert!(matches!(
+        window.release(9000).await,
+        Err(SettlementError::DisputeWindowOpen { .. })
+    ));
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+
+    window.release(9000).await?;
+    assert_eq!(
+        window.get(9000).await.unwrap().status,
+        SettlementStatus::Completed
+    );
+
+    // Invalid proof freezes the settlement instead of paying out
+    assert!(matches!(
+        window.release(9001).await,
+        Err(SettlementError::SettlementFrozen(9001))
+    ));
+    let frozen = window.get(9001).await.unwrap();
+    assert_eq!(frozen.status, SettlementStatus::Disputed);
+    assert!(frozen.dispute.is_some());
+
+    println!("✅ DISPUTE RESOLVED: Tampered job frozen, honest job released after window");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dispute_raised_during_window_freezes_payment() -> Result<()> {
+    // Scenario: Client challenges the result before the window closes
+    let window = DisputeWindow::new(Duration::from_millis(50));
+    window.open(9100).await;
+
+    window
+        .raise_dispute(9100, "Output does not match requested model")
+        .await?;
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(window.releasable().await.is_empty());
+    assert!(matches!(
+        window.release(9100).await,
+        Err(SettlementError::SettlementFrozen(9100))
+    ));
+
+    // Upheld dispute means the payment never releases
+    window.resolve_dispute(9100, true).await?;
+    assert_eq!(
+        window.get(9100).await.unwrap().status,
+        SettlementStatus::Failed
+    );
+
+    println!("✅ DISPUTE RESOLVED: Challenged payment frozen and failed");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_queued_settlement_waits_for_dispute_window() -> Result<()> {
+    // Scenario: Settlement is queued when the result is submitted and only
+    // pays out once the challenge period has passed
+    let window = Arc::new(DisputeWindow::new(Duration::from_millis(50)));
+    let manager = SettlementManager::new(
+        Arc::new(ChainRegistry::new()),
+        "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+    )
+    .await?
+    .with_dispute_window(window.clone());
+
+    manager
+        .queue_settlement(SettlementRequest {
+            session_id: 9200,
+            chain_id: 84532,
+            priority: 1,
+            retry_count: 0,
+            status: SettlementStatus::Pending,
+        })
+        .await?;
+    assert!(window.is_tracked(9200).await);
+
+    // Still inside the window: nothing settles and the request stays queued
+    assert!(manager.process_settlement_queue().await?.is_empty());
+    assert_eq!(manager.get_pending_count().await, 1);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+
+    let results = manager.process_settlement_queue().await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].session_id, 9200);
+    assert_eq!(
+        window.get(9200).await.unwrap().status,
+        SettlementStatus::Completed
+    );
+
+    println!("✅ Queued settlement released only after its dispute window");
+
+    Ok(())
+}
@@ -98,6 +98,8 @@ fn test_full_rag_workflow() {
         k: 3,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let search_response = handle_search_vectors(&session, search_request).unwrap();
@@ -142,6 +144,8 @@ fn test_upload_search_inference_pipeline() {
         k: 5,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let search_response = handle_search_vectors(&session, search_request).unwrap();
@@ -204,6 +208,8 @@ fn test_multiple_searches_same_session() {
             k: 2,
             threshold: None,
             metadata_filter: None,
+            query_text: None,
+            rerank_top_k: None,
         };
 
         let response = handle_search_vectors(&session, search_request).unwrap();
@@ -236,6 +242,8 @@ fn test_replace_vectors_mid_session() {
         k: 100,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
     let response1 = handle_search_vectors(&session, search1).unwrap();
     assert_eq!(response1.total_vectors, 50);
@@ -257,6 +265,8 @@ fn test_replace_vectors_mid_session() {
         k: 100,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
     let response2 = handle_search_vectors(&session, search2).unwrap();
     assert_eq!(response2.total_vectors, 30);
@@ -295,6 +305,8 @@ fn test_search_with_filters() {
         k: 10,
         threshold: None,
         metadata_filter: Some(json!({"category": {"$eq": "ml"}})),
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let response = handle_search_vectors(&session, search_request).unwrap();
@@ -344,6 +356,8 @@ fn test_session_cleanup_removes_vectors() {
         k: 100,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
     let response1 = handle_search_vectors(&session1, search1).unwrap();
     assert_eq!(response1.total_vectors, 20);
@@ -355,6 +369,8 @@ fn test_session_cleanup_removes_vectors() {
         k: 100,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
     let response2 = handle_search_vectors(&session2, search2).unwrap();
     assert_eq!(response2.total_vectors, 30);
@@ -375,6 +391,8 @@ fn test_session_cleanup_removes_vectors() {
         k: 100,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
     let response3 = handle_search_vectors(&session1, search3).unwrap();
     assert_eq!(response3.total_vectors, 0);
@@ -386,6 +404,8 @@ fn test_session_cleanup_removes_vectors() {
         k: 100,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
     let response4 = handle_search_vectors(&session2, search4).unwrap();
     assert_eq!(response4.total_vectors, 30);
@@ -422,6 +442,8 @@ fn test_rag_10k_vectors_performance() {
         k: 10,
         threshold: None,
         metadata_filter: None,
+        query_text: None,
+        rerank_top_k: None,
     };
 
     let search_response = handle_search_vectors(&session, search_request).unwrap();
@@ -474,6 +496,8 @@ fn test_concurrent_sessions_rag() {
                     k: 10,
                     threshold: None,
                     metadata_filter: None,
+                    query_text: None,
+                    rerank_top_k: None,
                 };
 
                 let search_response = handle_search_vectors(&session, search_request).unwrap();
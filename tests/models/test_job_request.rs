@@ -63,6 +63,8 @@ mod tests {
             role: "user".to_string(),
             content: "Test message".to_string(),
             timestamp: Some(1234567890),
+            tool_calls: None,
+            tool_call_id: None,
         };
 
         let serialized = serde_json::to_string(&msg).unwrap();
@@ -79,6 +81,8 @@ mod tests {
             role: "assistant".to_string(),
             content: "Response".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         };
 
         let serialized = serde_json::to_string(&msg).unwrap();
@@ -422,3 +422,92 @@ async fn test_alert_recovery_actions() {
     let history = manager.get_action_history().await;
     assert!(history.iter().any(|a| a.action_type == "recovery"));
 }
+
+#[tokio::test]
+async fn test_alert_deduplication_and_group_notifications() {
+    let manager = create_test_alert_manager().await.unwrap();
+
+    for i in 0..3 {
+        let rule = ThresholdAlert::new(
+            &format!("worker_{}_queue_full", i),
+            &format!("worker_{}_queue_depth", i),
+            100.0,
+            AlertLevel::Warning,
+        )
+        .with_labels(vec![("service_group", "workers")]);
+
+        manager.add_threshold_alert(rule).await.unwrap();
+        manager
+            .update_metric(&format!("worker_{}_queue_depth", i), 150.0)
+            .await;
+    }
+
+    // Repeated evaluations update the same alerts instead of refiring
+    for _ in 0..3 {
+        manager.evaluate_alerts().await.unwrap();
+    }
+
+    let active = manager.get_active_alerts().await;
+    assert_eq!(active.len(), 3);
+    assert!(active.iter().all(|a| a.occurrences == 3));
+
+    // The whole group goes out as one notification per channel
+    let notifications = manager.get_notification_history().await;
+    let firing: Vec<_> = notifications
+        .iter()
+        .filter(|n| n.action_type == "alert")
+        .collect();
+    assert_eq!(firing.len(), 2);
+    assert!(firing.iter().all(|n| n.alert_id == firing[0].alert_id));
+
+    // Clearing the condition sends a resolve notification for the group
+    for i in 0..3 {
+        manager
+            .update_metric(&format!("worker_{}_queue_depth", i), 10.0)
+            .await;
+    }
+    manager.evaluate_alerts().await.unwrap();
+
+    assert!(manager.get_active_alerts().await.is_empty());
+    let notifications = manager.get_notification_history().await;
+    let resolved: Vec<_> = notifications
+        .iter()
+        .filter(|n| n.action_type == "resolved")
+        .collect();
+    assert_eq!(resolved.len(), 2);
+    assert_eq!(resolved[0].alert_id, firing[0].alert_id);
+}
+
+#[tokio::test]
+async fn test_silence_suppresses_refiring() {
+    let manager = create_test_alert_manager().await.unwrap();
+
+    let rule = ThresholdAlert::new("gpu_hot", "gpu_temp", 85.0, AlertLevel::Critical)
+        .with_labels(vec![("device", "0")]);
+    let other = ThresholdAlert::new("gpu_fan", "gpu_fan_rpm", 5000.0, AlertLevel::Warning);
+    manager.add_threshold_alert(rule).await.unwrap();
+    manager.add_threshold_alert(other).await.unwrap();
+
+    let silence_id = manager
+        .create_silence(
+            HashMap::from([("device".to_string(), "0".to_string())]),
+            Duration::from_secs(300),
+            "Maintenance",
+        )
+        .await
+        .unwrap();
+
+    manager.update_metric("gpu_temp", 95.0).await;
+    manager.update_metric("gpu_fan_rpm", 6000.0).await;
+    manager.evaluate_alerts().await.unwrap();
+
+    // Only the alert outside the silence's labels fires
+    let active = manager.get_active_alerts().await;
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].rule_name, "gpu_fan");
+
+    // Once the silence ends the alert fires again
+    manager.expire_silence(&silence_id).await.unwrap();
+    manager.evaluate_alerts().await.unwrap();
+    assert_eq!(manager.get_active_alerts().await.len(), 2);
+}
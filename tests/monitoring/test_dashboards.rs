@@ -3,6 +3,7 @@
 // tests/monitoring/test_dashboards.rs
 
 use anyhow::Result;
+use fabstir_llm_node::monitoring::dashboards::Threshold;
 use fabstir_llm_node::monitoring::{
     Dashboard, DashboardConfig, DashboardExport, DashboardManager, DataSource, GridPosition,
     Layout, Panel, Query, RefreshInterval, TimeRange, Visualization, Widget, WidgetType,
@@ -362,6 +363,106 @@ async fn test_dashboard_export_import() {
     assert_eq!(panels.len(), 1);
 }
 
+#[tokio::test]
+async fn test_grafana_export_round_trip() {
+    let mut dashboard = Dashboard::new("node_overview", "Node Overview", "Versioned dashboard")
+        .with_refresh_interval(RefreshInterval::Seconds(30));
+    dashboard.add_tag("inference");
+    dashboard.add_variable("model", vec!["llama-7b", "llama-13b"], "llama-7b");
+    dashboard.time_range = TimeRange::Custom {
+        from_hours: 6,
+        to_hours: 1,
+    };
+
+    let position = GridPosition {
+        x: 0,
+        y: 0,
+        width: 12,
+        height: 8,
+    };
+    for (name, widget_type) in [
+        ("throughput", WidgetType::Graph),
+        ("gpu_memory", WidgetType::Gauge),
+        ("jobs", WidgetType::Table),
+        ("latency", WidgetType::Heatmap),
+        ("logs", WidgetType::LogViewer),
+        ("tokens", WidgetType::Stat),
+        ("models", WidgetType::PieChart),
+        ("chains", WidgetType::BarChart),
+    ] {
+        let mut panel = Panel::new(name, name, widget_type, position);
+        panel.add_query(
+            Query::new("prometheus", "rate(fabstir_tokens_generated_total[5m])")
+                .with_legend("{{model}}"),
+        );
+        dashboard.panels.push(panel);
+    }
+    if let Widget::Gauge(gauge) = &mut dashboard.panels[1].widget {
+        gauge.unit = "bytes".to_string();
+        gauge.max_value = 80.0;
+        gauge.thresholds = vec![
+            Threshold {
+                value: f64::NEG_INFINITY,
+                color: "green".to_string(),
+            },
+            Threshold {
+                value: 70.0,
+                color: "red".to_string(),
+            },
+        ];
+    }
+
+    let parse =
+        |e: &DashboardExport| serde_json::from_str::<serde_json::Value>(&e.content).unwrap();
+
+    let first = dashboard.to_export().unwrap();
+    assert_eq!(first.format, "grafana");
+
+    let imported = Dashboard::from_export(&first).unwrap();
+    assert_eq!(imported.name, "node_overview");
+    assert_eq!(imported.panels.len(), 8);
+    assert_eq!(imported.refresh_interval_seconds, 30);
+    match &imported.panels[1].widget {
+        Widget::Gauge(gauge) => {
+            assert_eq!(gauge.unit, "bytes");
+            assert_eq!(gauge.thresholds.len(), 2);
+        }
+        other => panic!("expected gauge, got {:?}", other),
+    }
+
+    let second = imported.to_export().unwrap();
+    assert_eq!(parse(&first), parse(&second));
+
+    // A panel type this node does not model survives as passthrough
+    let mut model = parse(&second);
+    model["panels"]
+        .as_array_mut()
+        .unwrap()
+        .push(serde_json::json!({
+            "type": "nodeGraph",
+            "title": "Peers",
+            "gridPos": { "x": 0, "y": 8, "w": 24, "h": 10 },
+            "options": { "nodes": { "mainStatUnit": "ms" } },
+        }));
+    let edited = DashboardExport {
+        content: model.to_string(),
+        ..second
+    };
+
+    let imported = Dashboard::from_export(&edited).unwrap();
+    assert!(
+        matches!(&imported.panels[8].widget, Widget::Passthrough(w) if w.panel_type == "nodeGraph")
+    );
+
+    let third = imported.to_export().unwrap();
+    let fourth = Dashboard::from_export(&third).unwrap().to_export().unwrap();
+    assert_eq!(parse(&third), parse(&fourth));
+    assert_eq!(
+        parse(&third)["panels"][8]["options"]["nodes"]["mainStatUnit"],
+        "ms"
+    );
+}
+
 #[tokio::test]
 async fn test_dashboard_annotations() {
     let manager = create_test_dashboard_manager().await.unwrap();
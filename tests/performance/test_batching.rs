@@ -4,7 +4,8 @@ use anyhow::Result;
 use fabstir_llm_node::performance::{
     BatchProcessor, BatchConfig, BatchRequest, BatchResult,
     BatchStatus, BatchError, BatchingStrategy, QueueConfig,
-    BatchMetrics, PaddingStrategy, BatchPriority
+    BatchMetrics, PaddingStrategy, BatchPriority, BatchMemoryModel,
+    GpuConfig, GpuManager
 };
 use std::sync::Arc;
 use std::time::Duration;
@@ -388,4 +389,53 @@ async fn test_adaptive_batching() {
     
     // Adaptive strategy should adjust batch size based on load
     assert!(batch2.requests.len() > batch1.requests.len());
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_batch_capped_by_gpu_memory() {
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    let gpu_manager = Arc::new(GpuManager::new(GpuConfig::default()).await.unwrap());
+    // 24GB device with 20GB taken by loaded weights leaves 4GB
+    gpu_manager.allocate_gpu("llama-7b", 20 * GIB).await.unwrap();
+
+    // 1MB per token and no headroom: each request below costs 256MB
+    let memory_model = BatchMemoryModel {
+        kv_cache_bytes_per_token: 1024 * 1024,
+        activation_bytes_per_token: 0,
+        chars_per_token: 4,
+        headroom_fraction: 0.0,
+    };
+    let processor = create_test_batch_processor()
+        .await
+        .unwrap()
+        .with_gpu_manager(gpu_manager.clone(), 0, memory_model);
+
+    for i in 0..20 {
+        processor.submit_request(BatchRequest {
+            id: format!("mem_{}", i),
+            model_id: "llama-7b".to_string(),
+            prompt: "abcd".to_string(),
+            max_tokens: 255,
+            priority: BatchPriority::Normal,
+        }).await.unwrap();
+    }
+
+    let batch = processor.get_next_batch().await.unwrap();
+    assert_eq!(batch.requests.len(), 16, "4GB fits sixteen 256MB requests");
+    assert_eq!(batch.estimated_memory_bytes, 4 * GIB);
+    assert_eq!(batch.requests[0].id, "mem_0");
+
+    // Overflow stays queued in order
+    assert_eq!(processor.get_pending_requests().await, 4);
+
+    let metrics = processor.get_metrics().await;
+    assert_eq!(metrics.last_batch_size, 16);
+    assert_eq!(metrics.last_batch_memory_estimate, 4 * GIB);
+    assert_eq!(metrics.available_gpu_memory, Some(4 * GIB));
+    assert_eq!(metrics.memory_limited_batches, 1);
+
+    let next = processor.get_next_batch().await.unwrap();
+    assert_eq!(next.requests[0].id, "mem_16");
+    assert_eq!(next.requests.len(), 4);
+}
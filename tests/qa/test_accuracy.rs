@@ -1,9 +1,9 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use fabstir_llm_node::qa::{
-    AccuracyAlert, AccuracyError, AccuracyMetrics, AccuracyVerifier, ConsistencyCheck,
-    QualityScore, SamplingStrategy, ValidationRule, VerificationConfig, VerificationMethod,
-    VerificationResult,
+    exact_match_score, rouge_l_score, AccuracyAlert, AccuracyError, AccuracyMetrics,
+    AccuracyVerifier, ConsistencyCheck, QualityScore, SamplingStrategy, ValidationRule,
+    VerificationConfig, VerificationMethod, VerificationResult,
 };
 use std::collections::HashMap;
 
@@ -340,4 +340,65 @@ mod tests {
         assert!(export.is_ok());
         assert!(export.unwrap().contains("job_id,accuracy,score"));
     }
+
+    #[test]
+    fn test_exact_match_normalization() {
+        assert_eq!(exact_match_score("The Paris!", "paris"), 1.0);
+        assert_eq!(exact_match_score("  New   York. ", "new york"), 1.0);
+        assert_eq!(exact_match_score("Paris, France", "Paris"), 0.0);
+    }
+
+    #[test]
+    fn test_rouge_l_score() {
+        assert_eq!(rouge_l_score("the cat sat", "the cat sat"), 1.0);
+        assert_eq!(rouge_l_score("dog", "the cat sat"), 0.0);
+        assert_eq!(rouge_l_score("", ""), 1.0);
+        assert_eq!(rouge_l_score("", "paris"), 0.0);
+
+        // LCS "the cat on mat" = 4 of 6 candidate / 5 reference tokens
+        let score = rouge_l_score("the cat was on the mat", "the cat sat on mat");
+        let (p, r) = (4.0 / 6.0, 4.0 / 5.0);
+        assert!((score - 2.0 * p * r / (p + r)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_verify_against_reference() {
+        let mut config = create_test_config();
+        config.verification_methods =
+            vec![VerificationMethod::RougeL, VerificationMethod::ExactMatch];
+        config.accuracy_threshold = 0.5;
+        let verifier = AccuracyVerifier::new(config);
+
+        let result = verifier
+            .verify_against_reference(
+                "The capital of France is Paris.",
+                "Paris is the capital of France",
+            )
+            .await;
+        assert!(matches!(result.method_used, VerificationMethod::RougeL));
+        assert!(result.is_accurate);
+        assert!(result.accuracy_score > 0.5 && result.accuracy_score < 1.0);
+        let details = result.details.clone().unwrap();
+        assert!(details.contains("RougeL=") && details.contains("ExactMatch=0.0000"));
+
+        let quality = QualityScore::from_reference_result(&result);
+        assert_eq!(quality.overall_score, result.accuracy_score);
+        assert_eq!(quality.accuracy_component, result.accuracy_score);
+
+        let result = verifier
+            .verify_against_reference("Berlin", "Paris is the capital of France")
+            .await;
+        assert!(!result.is_accurate);
+        assert_eq!(verifier.get_verification_history(10).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_verify_against_reference_defaults_to_rouge_l() {
+        let verifier = AccuracyVerifier::new(create_test_config());
+        let result = verifier.verify_against_reference("paris", "Paris").await;
+
+        assert!(matches!(result.method_used, VerificationMethod::RougeL));
+        assert_eq!(result.accuracy_score, 1.0);
+        assert!(result.is_accurate);
+    }
 }
@@ -27,6 +27,7 @@ mod tests {
             allow_anonymous: false,
             require_verification: true,
             decay_period_days: 90,
+            decay_half_life_days: None,
         }
     }
 
@@ -371,4 +372,84 @@ mod tests {
 
         assert!(moderation_result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_decayed_aggregation_favours_recent_ratings() {
+        let mut config = create_test_config();
+        config.decay_half_life_days = Some(7.0);
+        let manager = RatingsManager::new(config);
+
+        // Many old bad ratings followed by a few recent good ones
+        for i in 0..6 {
+            let mut rating = create_test_rating();
+            rating.job_id = format!("old-{}", i);
+            rating.overall_rating = 1;
+            rating.timestamp = Utc::now() - Duration::days(35);
+            manager.submit_rating(rating).await.unwrap();
+        }
+        for i in 0..2 {
+            let mut rating = create_test_rating();
+            rating.job_id = format!("new-{}", i);
+            rating.overall_rating = 5;
+            manager.submit_rating(rating).await.unwrap();
+        }
+
+        let aggregation = manager.get_rating_aggregation("llama-3.2-1b", 60).await;
+        assert_eq!(aggregation.total_ratings, 8);
+        // Five half-lives: each old rating weighs 1/32
+        assert!((aggregation.effective_count - (2.0 + 6.0 / 32.0)).abs() < 0.01);
+        assert!(aggregation.weighted_average > 4.0);
+        assert!(aggregation.average_by_category[&RatingCategory::Overall] > 4.0);
+        assert!(aggregation.recent_trend > 0.0);
+
+        // The undecayed summary is still dominated by the old ratings
+        let summary = manager.get_ratings_summary("llama-3.2-1b").await;
+        assert!(summary.average_overall < 3.0);
+
+        let trend = manager.get_rating_trend("llama-3.2-1b", 60).await;
+        assert!(trend.is_improving);
+    }
+
+    #[tokio::test]
+    async fn test_decay_weight_halves_per_half_life() {
+        let mut config = create_test_config();
+        config.decay_half_life_days = Some(10.0);
+        let manager = RatingsManager::new(config);
+
+        let now = Utc::now();
+        assert_eq!(manager.decay_weight(now, now), 1.0);
+        assert!((manager.decay_weight(now - Duration::days(10), now) - 0.5).abs() < 1e-9);
+        assert!((manager.decay_weight(now - Duration::days(20), now) - 0.25).abs() < 1e-9);
+        // Future timestamps are not boosted
+        assert_eq!(manager.decay_weight(now + Duration::days(1), now), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_decayed_reputation_impact_is_monotonic() {
+        let mut config = create_test_config();
+        config.decay_half_life_days = Some(30.0);
+
+        let mut changes = Vec::new();
+        for value in 1..=5 {
+            let manager = RatingsManager::new(config.clone());
+            for i in 0..5 {
+                let mut rating = create_test_rating();
+                rating.job_id = format!("job-{}", i);
+                rating.overall_rating = value;
+                rating.timestamp = Utc::now() - Duration::days(i * 15);
+                manager
+                    .submit_rating_for_host("host-123", rating)
+                    .await
+                    .unwrap();
+            }
+            let impact = manager
+                .calculate_reputation_impact("host-123")
+                .await
+                .unwrap();
+            changes.push(impact.reputation_change);
+        }
+
+        assert!(changes.windows(2).all(|w| w[0] < w[1]));
+        assert!(changes[2].abs() < 1e-9);
+    }
 }
@@ -318,4 +318,65 @@ mod tests {
         assert!(comp_data.p50_improvement > 0.0);
         assert!(comp_data.p99_improvement > 0.0);
     }
+
+    #[tokio::test]
+    async fn test_model_percentiles() {
+        let config = create_test_config();
+        let tracker = ResponseTimeTracker::new(config);
+
+        for time_ms in 1..=100 {
+            tracker
+                .record_response_time("llama-3.2-1b", "inference", time_ms)
+                .await
+                .unwrap();
+        }
+        tracker
+            .record_response_time("other-model", "inference", 5000)
+            .await
+            .unwrap();
+
+        let percentiles = tracker
+            .percentiles("llama-3.2-1b", &[50.0, 95.0, 99.0])
+            .await;
+        assert_eq!(percentiles.len(), 3);
+        assert_eq!(percentiles[0], (50.0, 50.0));
+        assert_eq!(percentiles[1], (95.0, 95.0));
+        assert_eq!(percentiles[2], (99.0, 99.0));
+
+        // Unknown models report zeros rather than an empty result
+        let unknown = tracker.percentiles("missing", &[95.0]).await;
+        assert_eq!(unknown, vec![(95.0, 0.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_model_prometheus_export() {
+        let config = create_test_config();
+        let tracker = ResponseTimeTracker::new(config);
+
+        tracker
+            .record_response_time("model-a", "inference", 100)
+            .await
+            .unwrap();
+        tracker
+            .record_response_time("model-b", "inference", 200)
+            .await
+            .unwrap();
+
+        let output = tracker.export_model_prometheus().await.unwrap();
+        assert_eq!(
+            output
+                .matches("# TYPE response_time_model_milliseconds summary")
+                .count(),
+            1
+        );
+        assert!(output
+            .contains("response_time_model_milliseconds{model=\"model-a\",quantile=\"0.95\"} 100"));
+        assert!(output
+            .contains("response_time_model_milliseconds{model=\"model-b\",quantile=\"0.99\"} 200"));
+        assert!(output.contains("response_time_model_milliseconds_count{model=\"model-a\"} 1"));
+
+        // The combined scrape output carries the per-model series too
+        let prometheus = tracker.export_prometheus_format().await;
+        assert!(prometheus.contains("model=\"model-b\""));
+    }
 }
@@ -1,9 +1,10 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use chrono::{DateTime, Duration, Utc};
+use fabstir_llm_node::qa::uptime::RESTART_DOWNTIME_REASON;
 use fabstir_llm_node::qa::{
     DowntimeEvent, HistoricalUptime, ServiceStatus, UptimeAlert, UptimeConfig, UptimeError,
-    UptimeMetrics, UptimeTracker,
+    UptimeMetrics, UptimeSnapshot, UptimeTracker,
 };
 use std::collections::HashMap;
 
@@ -23,6 +24,7 @@ mod tests {
             rolling_window_hours: 24,
             persist_metrics: true,
             persistence_path: "/tmp/uptime_metrics".to_string(),
+            persist_path: None,
         }
     }
 
@@ -274,4 +276,94 @@ mod tests {
         assert_eq!(report.services["p2p"].status, ServiceStatus::Online);
         assert_eq!(report.services["contracts"].status, ServiceStatus::Offline);
     }
+
+    #[tokio::test]
+    async fn test_snapshot_persists_on_heartbeat() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = create_test_config();
+        config.persist_path = Some(dir.path().join("uptime.json"));
+
+        let tracker = UptimeTracker::new(config.clone());
+        tracker.start_tracking().await.unwrap();
+        tracker
+            .record_downtime_event(DowntimeEvent {
+                start_time: Utc::now() - Duration::minutes(10),
+                end_time: Some(Utc::now() - Duration::minutes(5)),
+                duration_ms: 300_000,
+                reason: "Maintenance".to_string(),
+            })
+            .await
+            .unwrap();
+        tracker.record_heartbeat().await.unwrap();
+
+        // No explicit save: the heartbeat wrote the snapshot
+        let restored = UptimeTracker::load(config).await.unwrap();
+        assert_eq!(restored.get_current_status().await, ServiceStatus::Online);
+        let events = restored.get_downtime_events(Duration::hours(1)).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].reason, "Maintenance");
+    }
+
+    #[tokio::test]
+    async fn test_restore_infers_downtime_across_restart() {
+        let tracker = UptimeTracker::new(create_test_config());
+        let now = Utc::now();
+        let last_seen = now - Duration::minutes(30);
+
+        tracker
+            .restore_snapshot(
+                UptimeSnapshot {
+                    saved_at: last_seen,
+                    tracking_start: Some(now - Duration::hours(2)),
+                    last_heartbeat: Some(last_seen),
+                    status: ServiceStatus::Online,
+                    downtime_events: Vec::new(),
+                    recovery_events: Vec::new(),
+                    rollups: Vec::new(),
+                },
+                now,
+            )
+            .await;
+
+        assert_eq!(tracker.get_current_status().await, ServiceStatus::Offline);
+        let events = tracker.get_downtime_events(Duration::hours(1)).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].reason, RESTART_DOWNTIME_REASON);
+        assert_eq!(events[0].start_time, last_seen);
+        assert_eq!(events[0].end_time, Some(now));
+        assert_eq!(events[0].duration_ms, 30 * 60 * 1000);
+    }
+
+    #[tokio::test]
+    async fn test_restore_closes_open_downtime_event() {
+        let tracker = UptimeTracker::new(create_test_config());
+        let now = Utc::now();
+        let started = now - Duration::minutes(3);
+
+        tracker
+            .restore_snapshot(
+                UptimeSnapshot {
+                    saved_at: started,
+                    tracking_start: Some(now - Duration::hours(1)),
+                    last_heartbeat: Some(started),
+                    status: ServiceStatus::Offline,
+                    downtime_events: vec![DowntimeEvent {
+                        start_time: started,
+                        end_time: None,
+                        duration_ms: 0,
+                        reason: "Network outage".to_string(),
+                    }],
+                    recovery_events: Vec::new(),
+                    rollups: Vec::new(),
+                },
+                now,
+            )
+            .await;
+
+        // The open event is closed rather than a second gap being inferred
+        let events = tracker.get_downtime_events(Duration::hours(1)).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].end_time, Some(now));
+        assert_eq!(events[0].duration_ms, 3 * 60 * 1000);
+    }
 }
@@ -533,6 +533,60 @@ mod vector_loader_tests {
         assert!(has_complete, "Should report completion");
     }
 
+    /// Test 10b: Vector counts are reported after every chunk
+    #[tokio::test]
+    async fn test_vectors_loaded_progress() {
+        use fabstir_llm_node::rag::vector_loader::{LoadProgress, VectorLoader};
+
+        let owner = "0xPROGRESS2";
+        let db_name = "progress-vectors";
+        let session_key = [7u8; 32];
+
+        let storage = setup_mock_storage(owner, db_name, 3, &session_key).await;
+        let loader = VectorLoader::new(Box::new(storage), 2);
+        let (progress_tx, mut progress_rx) = mpsc::channel(20);
+
+        let manifest_path = format!("home/vector-databases/{}/{}/manifest.json", owner, db_name);
+        loader
+            .load_vectors_from_s5(&manifest_path, owner, &session_key, Some(progress_tx))
+            .await
+            .unwrap();
+
+        let mut counts = vec![];
+        while let Ok(msg) = progress_rx.try_recv() {
+            if let LoadProgress::VectorsLoaded { loaded, total } = msg {
+                counts.push((loaded, total));
+            }
+        }
+        assert_eq!(counts, vec![(10, 30), (20, 30), (30, 30)]);
+    }
+
+    /// Test 10c: Cancellation frees loaded chunks and reports Cancelled
+    #[tokio::test]
+    async fn test_cancelled_load() {
+        use fabstir_llm_node::rag::errors::VectorLoadError;
+        use fabstir_llm_node::rag::vector_loader::VectorLoader;
+        use tokio_util::sync::CancellationToken;
+
+        let owner = "0xCANCEL";
+        let db_name = "cancel-test";
+        let session_key = [8u8; 32];
+
+        let storage = setup_mock_storage(owner, db_name, 3, &session_key).await;
+        let token = CancellationToken::new();
+        let loader = VectorLoader::new(Box::new(storage), 2).with_cancellation(token.clone());
+        token.cancel();
+
+        let manifest_path = format!("home/vector-databases/{}/{}/manifest.json", owner, db_name);
+        let result = loader
+            .load_vectors_from_s5(&manifest_path, owner, &session_key, None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(VectorLoadError::Cancelled { loaded_vectors: 0 })
+        ));
+    }
+
     /// Test 11: Empty manifest (no chunks)
     #[tokio::test]
     async fn test_empty_manifest() {
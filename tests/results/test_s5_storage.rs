@@ -224,4 +224,61 @@ mod tests {
             assert!(result.is_ok());
         }
     }
+
+    #[tokio::test]
+    async fn test_large_result_uploaded_in_chunks() {
+        let client = S5StorageClient::new(create_test_config()).with_chunking(64, 256);
+        let mut original_result = create_test_packaged_result();
+        original_result.result.response = "long generation ".repeat(100);
+
+        let storage_result = client.store_result(&original_result).await.unwrap();
+        assert!(storage_result.metadata.size_bytes > 256);
+        assert_eq!(storage_result.metadata.content_type, "application/cbor");
+
+        // Both lookups reassemble the chunks behind the manifest
+        let by_cid = client.retrieve_result(&storage_result.cid).await.unwrap();
+        assert_eq!(by_cid.result.response, original_result.result.response);
+        let by_path = client.retrieve_by_path("job_12345").await.unwrap();
+        assert_eq!(by_path.result.response, original_result.result.response);
+    }
+
+    #[tokio::test]
+    async fn test_stream_below_threshold_is_single_shot() {
+        let client = S5StorageClient::new(create_test_config()).with_chunking(4, 1024);
+        let data = b"small image bytes".to_vec();
+
+        let storage_result = client
+            .store_stream("job_img", "node_abc123", "image/png", &data[..])
+            .await
+            .unwrap();
+
+        assert_eq!(storage_result.metadata.size_bytes, data.len());
+        assert_eq!(
+            client.retrieve_bytes(&storage_result.cid).await.unwrap(),
+            data
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_chunk_boundary() {
+        // Exactly four full chunks, no trailing partial chunk
+        let client = S5StorageClient::new(create_test_config()).with_chunking(8, 16);
+        let data: Vec<u8> = (0..32u8).collect();
+
+        let storage_result = client
+            .store_stream(
+                "job_bin",
+                "node_abc123",
+                "application/octet-stream",
+                &data[..],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(storage_result.metadata.size_bytes, 32);
+        assert_eq!(
+            client.retrieve_bytes(&storage_result.cid).await.unwrap(),
+            data
+        );
+    }
 }
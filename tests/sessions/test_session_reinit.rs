@@ -93,6 +93,8 @@ async fn test_ensure_session_preserves_conversation_history() {
                 role: "user".to_string(),
                 content: "hello".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         )
         .await
@@ -104,6 +106,8 @@ async fn test_ensure_session_preserves_conversation_history() {
                 role: "assistant".to_string(),
                 content: "hi".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         )
         .await
@@ -173,6 +177,8 @@ async fn test_create_session_still_replaces() {
                 role: "user".to_string(),
                 content: "hello".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         )
         .await
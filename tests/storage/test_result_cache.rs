@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: BUSL-1.1
 use chrono::{Duration, Utc};
 use fabstir_llm_node::storage::{
-    CacheConfig, CacheEntry, CacheStats, EvictionPolicy, ResultCache, S5Backend, S5Client,
-    S5Storage, S5StorageConfig, StorageError,
+    CacheConfig, CacheEntry, CacheStats, EvictionPolicy, EvictionReason, ResultCache, S5Backend,
+    S5Client, S5Storage, S5StorageConfig, StorageError,
 };
 use std::collections::HashMap;
 
@@ -304,6 +304,38 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_manual_and_expired_eviction() {
+        let cache = create_test_cache().await.unwrap();
+
+        cache.put("keep", b"a".to_vec(), None).await.unwrap();
+        cache.put("drop", b"b".to_vec(), None).await.unwrap();
+
+        assert_eq!(cache.evict("drop").await.unwrap(), 1);
+        assert_eq!(cache.evict("drop").await.unwrap(), 0);
+        assert!(cache.get("drop").await.unwrap().is_none());
+        assert_eq!(
+            cache.eviction_record("drop").await.unwrap().reason,
+            EvictionReason::Manual
+        );
+
+        cache.set_ttl(1).await;
+        cache.put("stale", b"c".to_vec(), None).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+        assert_eq!(cache.evict_expired().await.unwrap(), 2);
+        assert_eq!(
+            cache.eviction_record("stale").await.unwrap().reason,
+            EvictionReason::Ttl
+        );
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.total_entries, 0);
+        assert_eq!(stats.evictions, 3);
+        assert_eq!(stats.evictions_manual, 1);
+        assert_eq!(stats.evictions_ttl, 2);
+        assert_eq!(stats.evictions_size, 0);
+    }
+
     #[tokio::test]
     async fn test_concurrent_cache_access() {
         let cache = create_test_cache().await.unwrap();
@@ -1,8 +1,8 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use fabstir_llm_node::vector::{
-    FilterOperator, FilterValue, SearchOptions, SearchResult, VectorBackend, VectorDBClient,
-    VectorDBConfig, VectorEntry, VectorError, VectorId, VectorStats,
+    DistanceMetric, FilterOperator, FilterValue, SearchOptions, SearchResult, VectorBackend,
+    VectorDBClient, VectorDBConfig, VectorEntry, VectorError, VectorId, VectorStats,
 };
 use futures::StreamExt;
 use std::collections::HashMap;
@@ -26,6 +26,7 @@ mod tests {
                     api_key,
                     timeout_ms: 5000,
                     max_retries: 3,
+                    metric: DistanceMetric::Cosine,
                 }
             }
             _ => VectorDBConfig {
@@ -33,6 +34,7 @@ mod tests {
                 api_key: None,
                 timeout_ms: 5000,
                 max_retries: 3,
+                metric: DistanceMetric::Cosine,
             },
         };
 
@@ -187,6 +189,7 @@ mod tests {
                 "category".to_string(),
                 FilterValue::String("A".to_string()),
             )])),
+            metric: None,
         };
 
         let results = client
@@ -366,6 +369,7 @@ mod tests {
                 api_key: None,
                 timeout_ms: 5000,
                 max_retries: 3,
+                metric: DistanceMetric::Cosine,
             };
 
             let client = VectorDBClient::new(config).await.unwrap();
@@ -1,9 +1,9 @@
 // Copyright (c) 2025 Fabstir
 // SPDX-License-Identifier: BUSL-1.1
 use fabstir_llm_node::vector::{
-    CacheEntry, CacheError, CacheEvictionPolicy, CacheHit, CacheStats, EmbeddingConfig,
-    EmbeddingGenerator, EmbeddingModel, SemanticCache, SemanticCacheConfig, SimilarityThreshold,
-    VectorBackend, VectorDBClient, VectorDBConfig,
+    CacheEntry, CacheError, CacheEvictionPolicy, CacheHit, CacheStats, DistanceMetric,
+    EmbeddingConfig, EmbeddingGenerator, EmbeddingModel, SemanticCache, SemanticCacheConfig,
+    SimilarityThreshold, VectorBackend, VectorDBClient, VectorDBConfig,
 };
 use std::collections::HashMap;
 use std::time::Duration;
@@ -34,12 +34,14 @@ mod tests {
                 api_key: std::env::var("VECTOR_DB_API_KEY").ok(),
                 timeout_ms: 5000,
                 max_retries: 3,
+                metric: DistanceMetric::Cosine,
             },
             _ => VectorDBConfig {
                 backend: VectorBackend::Mock,
                 api_key: None,
                 timeout_ms: 5000,
                 max_retries: 3,
+                metric: DistanceMetric::Cosine,
             },
         };
         let vector_client = VectorDBClient::new(vector_config).await?;
@@ -322,6 +324,7 @@ mod tests {
             api_key: None,
             timeout_ms: 5000,
             max_retries: 3,
+            metric: DistanceMetric::Cosine,
         };
         let vector_client1 = VectorDBClient::new(vector_config.clone()).await.unwrap();
         let vector_client2 = VectorDBClient::new(vector_config).await.unwrap();
@@ -68,11 +68,15 @@ async fn test_token_counting() {
             role: "user".to_string(),
             content: "Hello world".to_string(), // ~2-3 tokens
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         },
         Message {
             role: "assistant".to_string(),
             content: "Hi there! How can I help you today?".to_string(), // ~8-10 tokens
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         },
     ];
 
@@ -107,6 +111,8 @@ async fn test_context_validation() {
         role: "user".to_string(),
         content: "Normal message".to_string(),
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     }];
 
     assert!(manager.validate_context(&valid_messages).await.is_ok());
@@ -116,6 +122,8 @@ async fn test_context_validation() {
         role: "invalid_role".to_string(),
         content: "Message".to_string(),
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     }];
 
     let result = manager.validate_context(&invalid_messages).await;
@@ -132,11 +140,15 @@ async fn test_context_sanitization() {
             role: "user".to_string(),
             content: "Hello\x00\x01\x02world".to_string(), // Contains control characters
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         },
         Message {
             role: "assistant".to_string(),
             content: "Response with\nnewlines\tand\ttabs".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         },
     ];
 
@@ -162,16 +174,22 @@ async fn test_format_context_for_llm() {
             role: "system".to_string(),
             content: "You are a helpful assistant.".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         },
         Message {
             role: "user".to_string(),
             content: "What is 2+2?".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         },
         Message {
             role: "assistant".to_string(),
             content: "2+2 equals 4.".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         },
     ];
 
@@ -290,6 +308,8 @@ fn create_test_session_with_messages() -> WebSocketSession {
             role: "user".to_string(),
             content: "What is the capital of France?".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -298,6 +318,8 @@ fn create_test_session_with_messages() -> WebSocketSession {
             role: "assistant".to_string(),
             content: "The capital of France is Paris.".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -313,6 +335,8 @@ fn create_large_session() -> WebSocketSession {
                 role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
                 content: format!("Message {}", i),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             })
             .unwrap();
     }
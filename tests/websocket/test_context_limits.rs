@@ -105,6 +105,8 @@ async fn test_memory_pressure_handling() {
         role: "user".to_string(),
         content: "x".repeat(2000), // 2KB message
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     let result = manager.validate_memory_usage(&[large_message]).await;
@@ -155,6 +157,8 @@ async fn test_priority_message_preservation() {
             role: "system".to_string(),
             content: "Important system prompt".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -165,6 +169,8 @@ async fn test_priority_message_preservation() {
                 role: "user".to_string(),
                 content: format!("Message {}", i),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             })
             .unwrap();
     }
@@ -239,6 +245,8 @@ async fn test_multi_turn_context_coherence() {
             role: "user".to_string(),
             content: "Let's talk about Python".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -247,6 +255,8 @@ async fn test_multi_turn_context_coherence() {
             role: "assistant".to_string(),
             content: "Sure! Python is a versatile programming language.".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -255,6 +265,8 @@ async fn test_multi_turn_context_coherence() {
             role: "user".to_string(),
             content: "What about its data types?".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -283,6 +295,8 @@ fn create_session_with_many_messages(count: usize) -> WebSocketSession {
                 role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
                 content: format!("Message {} with some content to make it longer", i),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             })
             .unwrap();
     }
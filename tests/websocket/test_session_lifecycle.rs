@@ -61,6 +61,8 @@ async fn test_update_session() {
         role: "user".to_string(),
         content: "Test message".to_string(),
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     let result = store.update_session(&session_id, message).await;
@@ -80,6 +82,8 @@ async fn test_update_nonexistent_session() {
         role: "user".to_string(),
         content: "Test message".to_string(),
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     let result = store.update_session(&fake_id, message).await;
@@ -216,6 +220,8 @@ async fn test_session_metrics_tracking() {
         role: "user".to_string(),
         content: "Test".to_string(),
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     store.update_session(&id1, message.clone()).await.unwrap();
@@ -253,6 +259,8 @@ async fn test_concurrent_session_access() {
                 role: "user".to_string(),
                 content: format!("Message {}", i),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             };
 
             store_clone
@@ -302,3 +310,59 @@ async fn test_session_exists() {
     let fake_id = Uuid::new_v4().to_string();
     assert!(!store.session_exists(&fake_id).await);
 }
+
+#[tokio::test]
+async fn test_cancel_message_stops_running_generation() {
+    use fabstir_llm_node::api::websocket::messages::{
+        is_cancel_message, message_index_of, WebSocketMessage,
+    };
+    use std::sync::atomic::Ordering;
+
+    let mut store = SessionStore::new(SessionStoreConfig::default());
+    let session_id = store.create_session(SessionConfig::default()).await;
+    let cancel_flag = store.begin_inference(&session_id, Some(1)).await.unwrap();
+    let observed_flag = cancel_flag.clone();
+
+    // Stand-in for the decode loop, which checks the flag between tokens
+    let (token_tx, mut token_rx) = tokio::sync::mpsc::unbounded_channel();
+    let generation = tokio::task::spawn_blocking(move || {
+        let mut tokens = 0;
+        while !cancel_flag.load(Ordering::Acquire) {
+            tokens += 1;
+            let _ = token_tx.send(tokens);
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        tokens
+    });
+    token_rx.recv().await.unwrap();
+
+    let cancel = serde_json::to_value(WebSocketMessage::Cancel {
+        session_id: session_id.clone(),
+        message_index: 1,
+    })
+    .unwrap();
+    assert!(is_cancel_message(&cancel));
+    assert_eq!(message_index_of(&cancel), Some(1));
+
+    // A late cancel for an earlier message is ignored
+    assert!(store.cancel_inference(&session_id, Some(0)).await.is_none());
+    assert!(!observed_flag.load(Ordering::Acquire));
+    assert!(store
+        .cancel_inference(&session_id, message_index_of(&cancel))
+        .await
+        .is_some());
+
+    let tokens = tokio::time::timeout(Duration::from_secs(5), generation)
+        .await
+        .expect("generation should stop once cancelled")
+        .unwrap();
+    assert!(tokens >= 1);
+
+    // The next generation starts uncancelled
+    let next = store.begin_inference(&session_id, Some(2)).await.unwrap();
+    assert!(!next.load(Ordering::Acquire));
+    assert!(store
+        .cancel_inference("unknown-session", None)
+        .await
+        .is_none());
+}
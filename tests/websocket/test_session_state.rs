@@ -28,6 +28,8 @@ fn test_add_message_to_session() {
         role: "user".to_string(),
         content: "Hello, how are you?".to_string(),
         timestamp: Some(1234567890),
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     session.add_message(message.clone());
@@ -51,6 +53,8 @@ fn test_session_memory_limit() {
         role: "user".to_string(),
         content: "a".repeat(400), // 400 bytes content + overhead
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     assert!(session.add_message(large_message.clone()).is_ok());
@@ -77,6 +81,8 @@ fn test_session_context_window() {
             role: "user".to_string(),
             content: format!("Message {}", i),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         };
         session.add_message(message).unwrap();
     }
@@ -104,6 +110,8 @@ fn test_session_clear() {
             role: "user".to_string(),
             content: format!("Message {}", i),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         };
         session.add_message(message).unwrap();
     }
@@ -133,6 +141,8 @@ fn test_session_last_activity() {
         role: "user".to_string(),
         content: "Test message".to_string(),
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
     session.add_message(message).unwrap();
 
@@ -152,6 +162,8 @@ fn test_session_metrics() {
             role: "user".to_string(),
             content: "Hello".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -160,6 +172,8 @@ fn test_session_metrics() {
             role: "assistant".to_string(),
             content: "Hi there! How can I help you today?".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -168,6 +182,8 @@ fn test_session_metrics() {
             role: "user".to_string(),
             content: "What's the weather?".to_string(),
             timestamp: None,
+            tool_calls: None,
+            tool_call_id: None,
         })
         .unwrap();
 
@@ -209,6 +225,8 @@ fn test_session_token_counting() {
         role: "user".to_string(),
         content: "This is a test message with several words.".to_string(),
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     session.add_message(message).unwrap();
@@ -231,6 +249,8 @@ fn test_session_memory_calculation() {
         role: "user".to_string(),
         content: "x".repeat(1000), // 1000 bytes of content
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     session.add_message(message).unwrap();
@@ -273,6 +293,8 @@ fn test_session_with_system_message() {
         role: "system".to_string(),
         content: "You are a helpful assistant.".to_string(),
         timestamp: None,
+        tool_calls: None,
+        tool_call_id: None,
     };
 
     session.add_message(system_message).unwrap();
@@ -89,6 +89,8 @@ async fn test_automatic_context_building() {
                 role: "assistant".to_string(),
                 content: "The capital of France is Paris.".to_string(),
                 timestamp: None,
+                tool_calls: None,
+                tool_call_id: None,
             },
         )
         .await